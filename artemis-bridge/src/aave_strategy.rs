@@ -6,12 +6,21 @@ use artemis_core::collectors::block_collector::NewBlock;
 
 
 use ethers::prelude::*;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::allocation::{
+    allocate, intent_hash, split_proceeds, AllocationCandidate, AllocationLine, AllocationPolicy, AllocationRecord,
+    DistributionScheme,
+};
 use crate::types::*;
+use crate::wallets::ExecutionWallet;
+
+/// `basisPointsRate()`, the transfer-fee setting on USDT-style tokens
+const BASIS_POINTS_RATE_SELECTOR: [u8; 4] = [0xdd, 0x64, 0x4f, 0x72];
 
 // Events that our strategy processes
 #[derive(Debug, Clone)]
@@ -39,8 +48,44 @@ pub struct AaveLiquidationStrategy<M> {
     min_profit_usd: U256,
     /// Optional channel to broadcast discovered opportunities to the WS loop
     broadcast_sender: Option<UnboundedSender<AaveLiquidationOpportunity>>,
+    /// Unix time of the last event processed, shared across clones for the engine watchdog
+    last_event_at: Arc<AtomicU64>,
+    /// Optional channel reporting watchlist changes to the WS loop
+    position_sender: Option<UnboundedSender<PositionNotice>>,
+    /// Most positions kept on the watchlist, and how many of them are re-checked per block
+    watchlist_capacity: usize,
+    revalidate_per_block: usize,
+    /// Where the next re-check batch starts in the address-ordered watchlist
+    revalidation_cursor: usize,
+    /// Watched victims the node leaves to peers, shared across clones like the last-event marker
+    delegation: Arc<Mutex<MonitoringDelegation>>,
+}
+
+/// Victims a peer polls and relays for us. The batch rotation passes over them except for a
+/// random share of spot checks, which the node compares against the peer's relays.
+#[derive(Debug, Default)]
+pub struct MonitoringDelegation {
+    pub delegated: HashSet<Address>,
+    pub spot_check_bps: u16,
+    /// Re-checks left out of a batch, and delegated victims re-checked anyway
+    pub skipped_checks: u64,
+    pub spot_checks: u64,
+}
+
+impl MonitoringDelegation {
+    pub fn report(&self) -> serde_json::Value {
+        serde_json::json!({
+            "delegated": self.delegated.len(),
+            "spot_check_bps": self.spot_check_bps,
+            "skipped_checks": self.skipped_checks,
+            "spot_checks": self.spot_checks,
+        })
+    }
 }
 
+pub const DEFAULT_WATCHLIST_CAPACITY: usize = 500;
+pub const DEFAULT_REVALIDATE_PER_BLOCK: usize = 25;
+
 // Aave contract ABIs
 abigen!(
     AavePool,
@@ -48,11 +93,56 @@ abigen!(
         function getUserAccountData(address user) external view returns (uint256 totalCollateralETH, uint256 totalDebtETH, uint256 availableBorrowsETH, uint256 currentLiquidationThreshold, uint256 ltv, uint256 healthFactor)
         function getReserveData(address asset) external view returns (uint256, uint128, uint128, uint128, uint128, uint128, uint40, address, address, address, address, uint8)
         function liquidationCall(address collateralAsset, address debtAsset, address user, uint256 debtToCover, bool receiveAToken) external
+        function getReservesList() external view returns (address[])
+        function ADDRESSES_PROVIDER() external view returns (address)
         event Liquidation(address indexed collateralAsset, address indexed debtAsset, address indexed user, uint256 debtToCover, uint256 liquidatedCollateralAmount, address liquidator, bool receiveAToken)
     ]"#
 );
 
+abigen!(
+    AaveAddressesProvider,
+    r#"[
+        function getPriceOracle() external view returns (address)
+        function getPoolDataProvider() external view returns (address)
+    ]"#
+);
+
+abigen!(
+    AaveDataProvider,
+    r#"[
+        function getUserReserveData(address asset, address user) external view returns (uint256 currentATokenBalance, uint256 currentStableDebt, uint256 currentVariableDebt, uint256 principalStableDebt, uint256 scaledVariableDebt, uint256 stableBorrowRate, uint256 liquidityRate, uint40 stableRateLastUpdated, bool usageAsCollateralEnabled)
+        function getReserveConfigurationData(address asset) external view returns (uint256 decimals, uint256 ltv, uint256 liquidationThreshold, uint256 liquidationBonus, uint256 reserveFactor, bool usageAsCollateralEnabled, bool borrowingEnabled, bool stableBorrowRateEnabled, bool isActive, bool isFrozen)
+    ]"#
+);
+
+abigen!(
+    AaveOracle,
+    r#"[
+        function getAssetPrice(address asset) external view returns (uint256)
+    ]"#
+);
+
+/// Below this health factor Aave v3 lets the whole debt be repaid instead of half (0.95)
+const FULL_CLOSE_HEALTH_FACTOR: u64 = 950_000_000_000_000_000;
 
+/// A victim's collateral reserve, priced by the Aave oracle in its base currency
+#[derive(Debug, Clone)]
+pub struct CollateralReserve {
+    pub asset: Address,
+    pub balance: U256,
+    pub decimals: u32,
+    pub price: U256,
+    pub bonus_bps: u16,
+}
+
+/// A planned seize leg: the debt (in base currency) it covers and the collateral that brings in
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedLeg {
+    pub asset: Address,
+    pub debt_covered: U256,
+    pub collateral: U256,
+    pub bonus_bps: u16,
+}
 
 impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
     pub fn new(
@@ -69,15 +159,85 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
             liquidation_threshold: U256::from_dec_str("1000000000000000000").unwrap(), // 1.0
             min_profit_usd,
             broadcast_sender: None,
+            last_event_at: Arc::new(AtomicU64::new(0)),
+            position_sender: None,
+            watchlist_capacity: DEFAULT_WATCHLIST_CAPACITY,
+            revalidate_per_block: DEFAULT_REVALIDATE_PER_BLOCK,
+            revalidation_cursor: 0,
+            delegation: Arc::new(Mutex::new(MonitoringDelegation::default())),
         }
     }
 
+    /// Shared marker of the last processed event; every clone of the strategy updates it
+    pub fn last_event_marker(&self) -> Arc<AtomicU64> {
+        self.last_event_at.clone()
+    }
+
+    /// Shared delegation state; every clone of the strategy reads it
+    pub fn delegation_handle(&self) -> Arc<Mutex<MonitoringDelegation>> {
+        self.delegation.clone()
+    }
+
     pub fn set_broadcast_sender(
         &mut self,
         sender: UnboundedSender<AaveLiquidationOpportunity>,
     ) {
         self.broadcast_sender = Some(sender);
     }
+
+    pub fn set_position_sender(&mut self, sender: UnboundedSender<PositionNotice>) {
+        self.position_sender = Some(sender);
+    }
+
+    pub fn set_watchlist_limits(&mut self, capacity: usize, revalidate_per_block: usize) {
+        self.watchlist_capacity = capacity.max(1);
+        self.revalidate_per_block = revalidate_per_block.max(1);
+    }
+
+    fn notify_position(&self, notice: PositionNotice) {
+        if let Some(tx) = &self.position_sender {
+            let _ = tx.send(notice);
+        }
+    }
+
+    /// Adds or refreshes a watched position, evicting the least interesting ones past capacity
+    fn watch_position(&mut self, position: UserPosition) {
+        self.notify_position(PositionNotice::PositionWatched {
+            borrower: format!("{:?}", position.user),
+            collateral_asset: format!("{:?}", position.collateral_asset),
+            debt_asset: format!("{:?}", position.debt_asset),
+            health_factor: position.health_factor.to_string(),
+            debt_amount: position.debt_amount.to_string(),
+            block: position.last_updated_block,
+        });
+        self.monitored_positions.insert(position.user, position);
+        
+        while self.monitored_positions.len() > self.watchlist_capacity {
+            // Furthest from liquidation first, then the smallest debt
+            let Some(evicted) = self.monitored_positions.values()
+                .max_by_key(|p| {
+                    let hf = match p.health_factor {
+                        HealthFactor::NoDebt => U256::MAX,
+                        HealthFactor::Value(value) => value,
+                    };
+                    (hf, std::cmp::Reverse(p.debt_amount), p.user)
+                })
+                .map(|p| p.user) else {
+                break;
+            };
+            self.close_position(evicted, PositionClosedReason::Evicted);
+        }
+    }
+
+    fn close_position(&mut self, user: Address, reason: PositionClosedReason) {
+        if self.monitored_positions.remove(&user).is_some() {
+            tracing::info!("No longer watching {:?}: {:?}", user, reason);
+            self.notify_position(PositionNotice::PositionClosed {
+                borrower: format!("{:?}", user),
+                reason,
+            });
+        }
+    }
     
     /// Scan blockchain for users with unhealthy positions
     async fn sync_unhealthy_positions(&mut self) -> Result<(), anyhow::Error> {
@@ -104,9 +264,15 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
         // Check current health factor for each user
         for user in users {
             if let Ok(account_data) = self.aave_pool.get_user_account_data(user).await {
-                let health_factor = account_data.5;
+                let health_factor = HealthFactor::from_account_data(account_data.5, account_data.1);
+                
+                // Debt-free accounts can't be liquidated; stop spending RPC calls on them
+                if health_factor == HealthFactor::NoDebt {
+                    self.close_position(user, PositionClosedReason::DebtRepaid);
+                    continue;
+                }
                 
-                if health_factor > U256::zero() && health_factor < self.liquidation_threshold * 2 {
+                if health_factor.is_below(self.liquidation_threshold * 2) {
                     // This user might become liquidatable soon
                     let position = UserPosition {
                         user,
@@ -118,7 +284,7 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
                         last_updated_block: current_block.as_u64(),
                     };
                     
-                    self.monitored_positions.insert(user, position);
+                    self.watch_position(position);
                 }
             }
         }
@@ -127,26 +293,68 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
         Ok(())
     }
     
-    /// Check if a specific user is liquidatable and create opportunity
-    async fn check_liquidation_opportunity(&self, user: Address) -> Result<Option<AaveLiquidationOpportunity>, anyhow::Error> {
+    /// Check if a specific user is liquidatable and create opportunity; also refreshes (or closes)
+    /// the user's watchlist entry
+    async fn check_liquidation_opportunity(
+        &mut self,
+        user: Address,
+        block_number: u64,
+        liquidated_this_block: bool,
+    ) -> Result<Option<AaveLiquidationOpportunity>, anyhow::Error> {
         let account_data = self.aave_pool.get_user_account_data(user).await?;
-        let health_factor = account_data.5;
+        let health_factor = HealthFactor::from_account_data(account_data.5, account_data.1);
+        
+        // The debt is gone since it was picked up; evict it from monitoring
+        if health_factor == HealthFactor::NoDebt {
+            let reason = if liquidated_this_block {
+                PositionClosedReason::LiquidatedExternally
+            } else {
+                PositionClosedReason::DebtRepaid
+            };
+            self.close_position(user, reason);
+            return Ok(None);
+        }
+        
+        if let Some(mut position) = self.monitored_positions.get(&user).cloned() {
+            position.collateral_amount = account_data.0;
+            position.debt_amount = account_data.1;
+            position.health_factor = health_factor;
+            position.last_updated_block = block_number;
+            self.watch_position(position);
+        }
         
         // Only liquidatable if health factor < 1.0
-        if health_factor >= self.liquidation_threshold {
+        if !health_factor.is_below(self.liquidation_threshold) {
             return Ok(None);
         }
         
+        // Half the debt can be repaid, or all of it once the health factor is below 0.95
         let total_debt = account_data.1;
-        let max_liquidation_amount = total_debt / 2; // Can liquidate up to 50% of debt
-        
-        // Simulate liquidation to calculate profit
-        // This is simplified - in practice we'd need to:
-        // 1. Determine which collateral and debt assets to use
-        // 2. Calculate exact liquidation bonus
-        // 3. Account for price impact and gas costs
+        let max_liquidation_amount = if health_factor.is_below(U256::from(FULL_CLOSE_HEALTH_FACTOR)) {
+            total_debt
+        } else {
+            total_debt / 2
+        };
         
-        let estimated_profit = max_liquidation_amount / 20; // Rough 5% profit estimate
+        // Spread the repay over the victim's collateral, best bonus first. Price impact and gas
+        // are not accounted for yet.
+        let legs = match self.collateral_reserves(user).await {
+            Ok(reserves) => plan_seize_legs(&reserves, max_liquidation_amount),
+            Err(e) => {
+                tracing::warn!("Cannot read collateral reserves of {:?}: {}; assuming a single WETH leg", user, e);
+                Vec::new()
+            }
+        };
+        let (max_repay, estimated_profit, seize_asset, min_bonus_bps) = if legs.is_empty() {
+            // Rough 5% profit estimate on the WETH placeholder
+            (max_liquidation_amount, max_liquidation_amount / 20, "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(), 500)
+        } else {
+            let repay = legs.iter().fold(U256::zero(), |acc, leg| acc + leg.debt_covered);
+            let profit = legs.iter()
+                .fold(U256::zero(), |acc, leg| acc + leg.debt_covered * U256::from(leg.bonus_bps) / U256::from(10_000u64));
+            let lowest_bonus = legs.iter().map(|leg| leg.bonus_bps).min().unwrap_or(0);
+            (repay, profit, format!("{:?}", legs[0].asset), lowest_bonus)
+        };
         
         if estimated_profit < self.min_profit_usd {
             return Ok(None);
@@ -156,12 +364,20 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
             opp_id: uuid::Uuid::new_v4().to_string(),
             victim_address: format!("{:?}", user),
             repay_asset: "0xA0b86a33E6D66c9e4A2a1B7d4c5a5e1d4b7b14e7".to_string(), // USDC placeholder
-            seize_asset: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(), // WETH placeholder
-            max_repay_amount: max_liquidation_amount.to_string(),
-            min_bonus_bps: 500, // 5%
+            seize_asset,
+            max_repay_amount: max_repay.to_string(),
+            min_bonus_bps,
             health_factor: health_factor.to_string(),
             deadline_block: self.provider.get_block_number().await?.as_u64() + 5,
             estimated_profit_usd: estimated_profit.to_string(),
+            seize_assets: legs.iter()
+                .map(|leg| SeizeLeg {
+                    asset: format!("{:?}", leg.asset),
+                    expected_amount: leg.collateral.to_string(),
+                    bonus_bps: leg.bonus_bps,
+                })
+                .collect(),
+            distribution: Default::default(),
         };
         
         // Also send via broadcast channel if configured
@@ -172,48 +388,207 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
         Ok(Some(opportunity))
     }
     
-    /// Execute liquidation with provided intents from P2P network
+    /// The reserves `user` has enabled as collateral, with the oracle price and liquidation bonus
+    /// of each; contract addresses are resolved through the pool's addresses provider
+    async fn collateral_reserves(&self, user: Address) -> Result<Vec<CollateralReserve>, anyhow::Error> {
+        let addresses = AaveAddressesProvider::new(self.aave_pool.addresses_provider().await?, self.provider.clone());
+        let data_provider = AaveDataProvider::new(addresses.get_pool_data_provider().await?, self.provider.clone());
+        let oracle = AaveOracle::new(addresses.get_price_oracle().await?, self.provider.clone());
+        
+        let mut reserves = Vec::new();
+        for asset in self.aave_pool.get_reserves_list().await? {
+            let (balance, _, _, _, _, _, _, _, usage_as_collateral) = data_provider.get_user_reserve_data(asset, user).await?;
+            if balance.is_zero() || !usage_as_collateral {
+                continue;
+            }
+            let (decimals, _, _, liquidation_bonus, _, _, _, _, _, _) = data_provider.get_reserve_configuration_data(asset).await?;
+            // Configured as 10000 plus the bonus, e.g. 10500 for 5%
+            let bonus_bps = liquidation_bonus.saturating_sub(U256::from(10_000u64)).min(U256::from(u16::MAX)).as_u32() as u16;
+            reserves.push(CollateralReserve {
+                asset,
+                balance,
+                decimals: decimals.as_u32(),
+                price: oracle.get_asset_price(asset).await?,
+                bonus_bps,
+            });
+        }
+        Ok(reserves)
+    }
+    
+    /// Current health factor of `user` and whether it is liquidatable, for Hyperware's
+    /// `ValidateOpportunity`; read-only, so the watchlist is left as it is
+    pub async fn validate_victim(&self, user: Address) -> Result<(HealthFactor, bool), anyhow::Error> {
+        let account_data = self.aave_pool.get_user_account_data(user).await?;
+        let health_factor = HealthFactor::from_account_data(account_data.5, account_data.1);
+        Ok((health_factor, health_factor.is_below(self.liquidation_threshold)))
+    }
+    
+    /// Structural lookups behind Hyperware's risk flags, for `ProbeRisk`: whether `victim` has
+    /// contract code, and which of `assets` answer USDT's `basisPointsRate()` fee switch
+    pub async fn probe_risk(&self, victim: Address, assets: &[Address]) -> Result<(bool, Vec<Address>), anyhow::Error> {
+        let is_contract = !self.provider.get_code(victim, None).await?.is_empty();
+        let mut fee_on_transfer = Vec::new();
+        for asset in assets {
+            let call: TypedTransaction = TransactionRequest::new()
+                .to(*asset)
+                .data(Bytes::from(BASIS_POINTS_RATE_SELECTOR.to_vec()))
+                .into();
+            // Tokens without the function revert; only a well-formed answer counts
+            if matches!(self.provider.call(&call, None).await, Ok(output) if output.len() == 32) {
+                fee_on_transfer.push(*asset);
+            }
+        }
+        Ok((is_contract, fee_on_transfer))
+    }
+    
+    /// Execute liquidation with provided intents from P2P network. When Hyperware declares an
+    /// allocation it is replayed here first and the execution funded exactly as allocated;
+    /// otherwise intents are pooled in arrival order up to `max_repay_amount`. Each of
+    /// `seize_legs` is realized in proportion to the share of the repay that got funded.
     pub async fn execute_with_intents(
         &self, 
         opp_id: String, 
-        intents: Vec<IntentData>
+        repay_asset: Option<String>,
+        max_repay_amount: Option<String>,
+        seize_legs: Vec<SeizeLeg>,
+        distribution: DistributionScheme,
+        intents: Vec<IntentData>,
+        allocation: Option<AllocationRecord>,
+        wallet: &mut ExecutionWallet,
     ) -> Result<Option<AaveLiquidationReceipt>, anyhow::Error> {
+        if let Some(record) = allocation {
+            return self.execute_allocation(opp_id, intents, record, &seize_legs, &distribution, wallet).await;
+        }
+        
+        // Only capital in the repay asset (or explicitly flagged for a swap) can fund the liquidation
+        let intents: Vec<IntentData> = intents.into_iter()
+            .filter(|data| {
+                let Ok(intent) = serde_json::from_str::<AaveLiquidationIntent>(&data.intent) else {
+                    tracing::warn!("Dropping unreadable intent from {} for {}", data.submitter_node, opp_id);
+                    return false;
+                };
+                let usable = match &repay_asset {
+                    Some(asset) => intent.requires_swap || intent.asset.eq_ignore_ascii_case(asset),
+                    None => true,
+                };
+                if !usable {
+                    tracing::warn!("Dropping {} intent from {} for {}: repay asset is {:?}",
+                        intent.asset, data.submitter_node, opp_id, repay_asset);
+                }
+                usable
+            })
+            .collect();
+        
         if intents.is_empty() {
             tracing::info!("No intents available for opportunity {}", opp_id);
             return Ok(None);
         }
         
-        // Select the best intent (highest amount)
-        let best_intent = intents.iter()
-            .max_by_key(|intent| intent.max_amount.parse::<U256>().unwrap_or(U256::zero()))
-            .ok_or_else(|| anyhow::anyhow!("No valid intent found"))?;
-        
-        // Parse intent to get actual liquidation details
-        let intent: AaveLiquidationIntent = serde_json::from_str(&best_intent.intent)?;
+        let (record, available) = pool_intents(&intents, max_repay_amount)
+            .map_err(|e| anyhow::anyhow!("Cannot allocate intents for {}: {}", opp_id, e))?;
+        let target = U256::from_dec_str(&record.target_amount).unwrap_or_default();
+        if available < target {
+            tracing::warn!("Intents for {} cover {} of {}; executing the partial amount", opp_id, available, target);
+        }
+        self.execute_allocation(opp_id, intents, record, &seize_legs, &distribution, wallet).await
+    }
+    
+    async fn execute_allocation(
+        &self,
+        opp_id: String,
+        intents: Vec<IntentData>,
+        record: AllocationRecord,
+        seize_legs: &[SeizeLeg],
+        distribution: &DistributionScheme,
+        wallet: &mut ExecutionWallet,
+    ) -> Result<Option<AaveLiquidationReceipt>, anyhow::Error> {
+        let replayed = allocate(record.policy, &record.target_amount, &record.candidates)
+            .map_err(|e| anyhow::anyhow!("Unreplayable allocation for {}: {}", opp_id, e))?;
+        if replayed != record.allocation {
+            tracing::warn!("Refusing {}: declared allocation does not match the {:?} policy", opp_id, record.policy);
+            return Ok(None);
+        }
+        if record.allocation.is_empty() {
+            tracing::info!("No capital allocated for opportunity {}", opp_id);
+            return Ok(None);
+        }
         
-        let _user: Address = intent.opp_id.parse().map_err(|_| anyhow::anyhow!("Invalid user address"))?;
-        let _debt_asset: Address = intent.asset.parse()?;
-        let _debt_to_cover: U256 = intent.max_amount.parse()?;
+        // Lines are matched back to intents by hash (over the re-serialized intent, as Hyperware hashes it)
+        let parsed: Vec<(String, AaveLiquidationIntent)> = intents.iter()
+            .filter_map(|data| serde_json::from_str::<AaveLiquidationIntent>(&data.intent).ok())
+            .filter_map(|intent| serde_json::to_string(&intent).ok().map(|json| (intent_hash(&json), intent)))
+            .collect();
+        let mut used_amounts = Vec::with_capacity(record.allocation.len());
+        for line in &record.allocation {
+            let intent = parsed.iter()
+                .find(|(hash, _)| *hash == line.intent_hash)
+                .map(|(_, intent)| intent.clone())
+                .ok_or_else(|| anyhow::anyhow!("Allocated intent {} from {} is missing", line.intent_hash, line.provider))?;
+            used_amounts.push(CapitalUsage {
+                node_id: line.provider.clone(),
+                asset: intent.asset,
+                amount_used: line.amount.clone(),
+                profit_share: "0".to_string(),
+                fee_bps: line.fee_bps,
+            });
+        }
+        let total_allocated = record.allocation.iter()
+            .fold(U256::zero(), |acc, line| acc + U256::from_dec_str(&line.amount).unwrap_or_default());
+        if let Some(refused) = self.check_wallet(wallet, &opp_id, total_allocated).await? {
+            return Ok(Some(refused));
+        }
         
         // For now, simulate execution and return a receipt
+        let total_proceeds = U256::from_dec_str("2000000000000000000")?; // 2 ETH total proceeds
+        assign_profit_shares(&mut used_amounts, total_proceeds, distribution);
+        let target = U256::from_dec_str(&record.target_amount).unwrap_or_default();
+        let seized = realized_legs(seize_legs, total_allocated, target);
         let receipt = AaveLiquidationReceipt {
             opp_id: opp_id.clone(),
             status: ExecutionStatus::Success,
             block_number: self.provider.get_block_number().await?.as_u64(),
             tx_hash: format!("0x{:064x}", 0x1234567890abcdef_u64), // Simulate tx hash
-            used_amounts: vec![CapitalUsage {
-                node_id: best_intent.submitter_node.clone(),
-                asset: intent.asset,
-                amount_used: intent.max_amount,
-                profit_share: "1000000000000000000".to_string(), // 1 ETH profit example
-            }],
-            total_proceeds: "2000000000000000000".to_string(), // 2 ETH total proceeds
+            used_amounts,
+            total_proceeds: total_proceeds.to_string(),
             gas_paid_usdc: "50000000".to_string(), // 50 USDC gas cost
+            provider_rule: None,
+            allocation: Some(record),
+            execution_wallet: Some(format!("{:?}", wallet.address())),
+            seized,
         };
+        wallet.record_execution(&opp_id, total_allocated, "Success", chrono::Utc::now().timestamp());
         
-        tracing::info!("Executed liquidation for opportunity {}", opp_id);
+        tracing::info!("Executed liquidation for opportunity {} across {} providers", opp_id, receipt.used_amounts.len());
         Ok(Some(receipt))
     }
+    
+    /// A failed receipt when the wallet's spend limit or gas reserve rules out this execution
+    async fn check_wallet(
+        &self,
+        wallet: &mut ExecutionWallet,
+        opp_id: &str,
+        amount: U256,
+    ) -> Result<Option<AaveLiquidationReceipt>, anyhow::Error> {
+        let now = chrono::Utc::now().timestamp();
+        let Err(refusal) = wallet.check_execution(&*self.provider, amount, now).await else {
+            return Ok(None);
+        };
+        tracing::warn!("Not executing {}: {}", opp_id, refusal);
+        wallet.record_execution(opp_id, amount, "Refused", now);
+        Ok(Some(AaveLiquidationReceipt {
+            opp_id: opp_id.to_string(),
+            status: ExecutionStatus::Failed(refusal.to_string()),
+            block_number: self.provider.get_block_number().await?.as_u64(),
+            tx_hash: String::new(),
+            used_amounts: Vec::new(),
+            total_proceeds: "0".to_string(),
+            gas_paid_usdc: "0".to_string(),
+            provider_rule: None,
+            allocation: None,
+            execution_wallet: Some(format!("{:?}", wallet.address())),
+            seized: Vec::new(),
+        }))
+    }
 }
 
 #[async_trait]
@@ -225,26 +600,79 @@ impl<M: Middleware + 'static> Strategy<AaveEvent, AaveAction> for AaveLiquidatio
     
     async fn process_event(&mut self, event: AaveEvent) -> Vec<AaveAction> {
         // Process the AaveEvent directly
-        if let Ok(actions) = self.process_new_block_internal(event).await {
-            actions
-        } else {
-            Vec::new()
-        }
+        let actions = self.process_new_block_internal(event).await.unwrap_or_default();
+        self.last_event_at.store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
+        actions
     }
 }
 
 impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
     async fn process_new_block_internal(&mut self, event: AaveEvent) -> Result<Vec<AaveAction>, anyhow::Error> {
         match event {
-            AaveEvent::NewBlock(_block) => self.process_new_block().await,
+            AaveEvent::NewBlock(block) => self.process_new_block(block).await,
+        }
+    }
+    
+    /// Watched borrowers that someone liquidated in the given block
+    async fn liquidated_in_block(&self, block_number: U64) -> Result<HashSet<Address>, anyhow::Error> {
+        let filter = Filter::new()
+            .address(self.aave_pool.address())
+            .from_block(block_number)
+            .to_block(block_number)
+            .event("Liquidation(address,address,address,uint256,uint256,address,bool)");
+        
+        let logs = self.provider.get_logs(&filter).await?;
+        Ok(logs.into_iter()
+            .filter_map(|log| self.aave_pool.decode_event::<LiquidationFilter>("Liquidation", log.topics, log.data).ok())
+            .map(|event| event.user)
+            .filter(|user| self.monitored_positions.contains_key(user))
+            .collect())
+    }
+    
+    /// The next slice of the watchlist to re-check, rotating through it in address order
+    fn next_revalidation_batch(&mut self) -> Vec<Address> {
+        let mut users: Vec<Address> = self.monitored_positions.keys().copied().collect();
+        if users.is_empty() {
+            return users;
+        }
+        users.sort();
+        let start = self.revalidation_cursor % users.len();
+        let count = self.revalidate_per_block.min(users.len());
+        self.revalidation_cursor = start + count;
+        let batch = (0..count).map(|i| users[(start + i) % users.len()]);
+        
+        // Delegated victims whose turn came up are skipped unless drawn for a spot check
+        let Ok(mut delegation) = self.delegation.lock() else {
+            return batch.collect();
+        };
+        let mut kept = Vec::with_capacity(count);
+        for user in batch {
+            if !delegation.delegated.contains(&user) {
+                kept.push(user);
+            } else if uuid::Uuid::new_v4().as_u128() % 10_000 < delegation.spot_check_bps as u128 {
+                delegation.spot_checks += 1;
+                kept.push(user);
+            } else {
+                delegation.skipped_checks += 1;
+            }
         }
+        kept
     }
     
-    async fn process_new_block(&mut self) -> Result<Vec<AaveAction>, anyhow::Error> {
-        // Check all monitored positions for liquidation opportunities
-        let positions = self.monitored_positions.clone();
-        for (user, _position) in positions {
-            if let Some(opportunity) = self.check_liquidation_opportunity(user).await? {
+    async fn process_new_block(&mut self, block: NewBlock) -> Result<Vec<AaveAction>, anyhow::Error> {
+        // Borrowers liquidated by someone else are re-checked at once; the rest of the
+        // watchlist is re-checked a bounded batch per block
+        let liquidated = self.liquidated_in_block(block.number).await.unwrap_or_default();
+        let mut batch = self.next_revalidation_batch();
+        for user in &liquidated {
+            if !batch.contains(user) {
+                batch.push(*user);
+            }
+        }
+        
+        for user in batch {
+            let liquidated_this_block = liquidated.contains(&user);
+            if let Some(opportunity) = self.check_liquidation_opportunity(user, block.number.as_u64(), liquidated_this_block).await? {
                 // Send opportunity to Hyperware via WebSocket channel
                 if let Some(sender) = &self.broadcast_sender {
                     let _ = sender.send(opportunity);
@@ -256,4 +684,294 @@ impl<M: Middleware + 'static> AaveLiquidationStrategy<M> {
     }
     
 
-}
\ No newline at end of file
+}
+
+/// Without a declared allocation, pools every readable intent, cheapest fee bid first, until
+/// `max_repay_amount` (or, without one, everything offered) is covered. Returns the allocation to
+/// declare on the receipt so it can be replayed, and the total the intents offered.
+fn pool_intents(intents: &[IntentData], max_repay_amount: Option<String>) -> Result<(AllocationRecord, U256), String> {
+    let candidates: Vec<AllocationCandidate> = intents.iter()
+        .filter_map(|data| {
+            let intent = serde_json::from_str::<AaveLiquidationIntent>(&data.intent).ok()?;
+            let json = serde_json::to_string(&intent).ok()?;
+            Some(AllocationCandidate {
+                intent_hash: intent_hash(&json),
+                provider: data.submitter_node.clone(),
+                max_amount: data.max_amount.clone(),
+                fee_bps: intent.fee_bps,
+                received_at: data.received_at.parse::<u64>().unwrap_or(0),
+            })
+        })
+        .collect();
+    let available = candidates.iter()
+        .fold(U256::zero(), |acc, candidate| acc + U256::from_dec_str(&candidate.max_amount).unwrap_or_default());
+    let target_amount = max_repay_amount.unwrap_or_else(|| available.to_string());
+    let policy = AllocationPolicy::FeeAscending;
+    let allocation = allocate(policy, &target_amount, &candidates)?;
+    Ok((AllocationRecord { policy, target_amount, candidates, allocation }, available))
+}
+
+/// Splits the proceeds over the capital used, pro rata to each line's amount less what its fee bid
+/// leaves unclaimed, through the shared split so shares round (and dust lands) exactly as every
+/// node replaying the receipt computes
+fn assign_profit_shares(used_amounts: &mut [CapitalUsage], total_proceeds: U256, distribution: &DistributionScheme) {
+    let lines: Vec<AllocationLine> = used_amounts.iter()
+        .map(|usage| AllocationLine {
+            intent_hash: String::new(),
+            provider: usage.node_id.clone(),
+            amount: usage.amount_used.clone(),
+            fee_bps: usage.fee_bps,
+        })
+        .collect();
+    match split_proceeds(&total_proceeds.to_string(), "0", 0, 0, &lines, distribution) {
+        Ok(split) => {
+            for (usage, line) in used_amounts.iter_mut().zip(split.shares) {
+                usage.profit_share = line.share;
+            }
+        }
+        Err(e) => tracing::warn!("Cannot split proceeds: {}", e),
+    }
+}
+
+/// Spreads up to `max_repay` of debt (in base currency) over the collateral reserves. Every unit
+/// of debt covered earns its reserve's bonus and a reserve can absorb at most its value over
+/// (1 + bonus), so filling the best bonus first maximizes the total bonus within the close factor.
+pub fn plan_seize_legs(reserves: &[CollateralReserve], max_repay: U256) -> Vec<PlannedLeg> {
+    let mut ranked: Vec<&CollateralReserve> = reserves.iter()
+        .filter(|reserve| !reserve.balance.is_zero() && !reserve.price.is_zero())
+        .collect();
+    ranked.sort_by(|a, b| b.bonus_bps.cmp(&a.bonus_bps).then(a.asset.cmp(&b.asset)));
+    
+    let mut remaining = max_repay;
+    let mut legs = Vec::new();
+    for reserve in ranked {
+        if remaining.is_zero() {
+            break;
+        }
+        let unit = U256::exp10(reserve.decimals as usize);
+        let premium = U256::from(10_000u64 + reserve.bonus_bps as u64);
+        let value = reserve.balance * reserve.price / unit;
+        let debt_covered = remaining.min(value * U256::from(10_000u64) / premium);
+        if debt_covered.is_zero() {
+            continue;
+        }
+        let collateral = (debt_covered * premium * unit / (U256::from(10_000u64) * reserve.price)).min(reserve.balance);
+        remaining -= debt_covered;
+        legs.push(PlannedLeg {
+            asset: reserve.asset,
+            debt_covered,
+            collateral,
+            bonus_bps: reserve.bonus_bps,
+        });
+    }
+    legs
+}
+
+/// Each leg's expected collateral scaled by how much of the target repay was funded
+fn realized_legs(legs: &[SeizeLeg], funded: U256, target: U256) -> Vec<SeizedLeg> {
+    legs.iter()
+        .map(|leg| {
+            let expected = U256::from_dec_str(&leg.expected_amount).unwrap_or_default();
+            let amount = if target.is_zero() { expected } else { expected * funded.min(target) / target };
+            SeizedLeg {
+                asset: leg.asset.clone(),
+                amount: amount.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(provider: &str, max_amount: u64, received_at: u64) -> IntentData {
+        let intent = serde_json::json!({
+            "opp_id": "opp",
+            "asset": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            "max_amount": max_amount.to_string(),
+            "min_bonus_bps": 500,
+            "expires_block": 100,
+        });
+        IntentData {
+            intent: intent.to_string(),
+            submitter_node: provider.to_string(),
+            max_amount: max_amount.to_string(),
+            expires_block: 100,
+            received_at: received_at.to_string(),
+        }
+    }
+
+    fn mocked_strategy() -> (AaveLiquidationStrategy<Provider<MockProvider>>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        (AaveLiquidationStrategy::new(Arc::new(provider), Address::repeat_byte(0xaa), U256::zero()), mock)
+    }
+
+    /// `getUserAccountData` as the pool returns it: collateral, debt, available borrows,
+    /// liquidation threshold, LTV and health factor
+    fn account_data(collateral: u64, debt: u64, health_factor: U256) -> Bytes {
+        let words = [U256::from(collateral), U256::from(debt), U256::zero(), U256::from(8_000u64), U256::from(7_500u64), health_factor];
+        Bytes::from(ethers::abi::encode(&words.map(ethers::abi::Token::Uint)))
+    }
+
+    fn watched(user: Address) -> UserPosition {
+        UserPosition {
+            user,
+            collateral_asset: Address::zero(),
+            debt_asset: Address::zero(),
+            collateral_amount: U256::from(10_000u64),
+            debt_amount: U256::from(8_000u64),
+            health_factor: HealthFactor::Value(U256::exp10(18) * 11 / 10),
+            last_updated_block: 1,
+        }
+    }
+
+    /// Oracle prices carry 8 decimals, like Aave's USD base currency
+    const BASE: u64 = 100_000_000;
+
+    fn reserve(byte: u8, balance: &str, decimals: u32, price: u64, bonus_bps: u16) -> CollateralReserve {
+        CollateralReserve {
+            asset: Address::repeat_byte(byte),
+            balance: U256::from_dec_str(balance).unwrap(),
+            decimals,
+            price: U256::from(price * BASE),
+            bonus_bps,
+        }
+    }
+
+    /// 1 WETH at 2000 with a 5% bonus, 0.01 WBTC at 60000 with 10%, 50 LINK at 10 with 7.5%
+    fn weth() -> CollateralReserve { reserve(0x01, "1000000000000000000", 18, 2_000, 500) }
+    fn wbtc() -> CollateralReserve { reserve(0x02, "1000000", 8, 60_000, 1_000) }
+    fn link() -> CollateralReserve { reserve(0x03, "50000000000000000000", 18, 10, 750) }
+
+    fn seized(legs: &[PlannedLeg]) -> Vec<(Address, String, String)> {
+        legs.iter().map(|leg| (leg.asset, leg.debt_covered.to_string(), leg.collateral.to_string())).collect()
+    }
+
+    fn used(record: &AllocationRecord) -> Vec<(&str, &str)> {
+        record.allocation.iter().map(|line| (line.provider.as_str(), line.amount.as_str())).collect()
+    }
+
+    #[test]
+    fn one_intent_covering_everything_funds_it_alone() {
+        let intents = [intent("alice.os", 5000, 10), intent("bob.os", 3000, 20)];
+        let (record, available) = pool_intents(&intents, Some("4000".to_string())).unwrap();
+        assert_eq!(used(&record), vec![("alice.os", "4000")]);
+        assert_eq!(available, U256::from(8000u64));
+    }
+
+    #[test]
+    fn three_intents_are_pooled_with_the_last_partly_used() {
+        let intents = [intent("carol.os", 3000, 30), intent("alice.os", 3000, 10), intent("bob.os", 3000, 20)];
+        let (record, _) = pool_intents(&intents, Some("7000".to_string())).unwrap();
+        assert_eq!(used(&record), vec![("alice.os", "3000"), ("bob.os", "3000"), ("carol.os", "1000")]);
+        // Everything declared replays to the same allocation
+        assert_eq!(crate::allocation::verify_allocation(&record, &record.candidates), Ok(()));
+    }
+
+    #[test]
+    fn intents_falling_short_are_all_used() {
+        let intents = [intent("alice.os", 1000, 10), intent("bob.os", 2000, 20)];
+        let (record, available) = pool_intents(&intents, Some("10000".to_string())).unwrap();
+        assert_eq!(used(&record), vec![("alice.os", "1000"), ("bob.os", "2000")]);
+        assert!(available < U256::from(10_000u64));
+        // Without a repay amount, everything offered is the target
+        let (record, _) = pool_intents(&intents, None).unwrap();
+        assert_eq!(record.target_amount, "3000");
+    }
+
+    #[test]
+    fn profit_shares_follow_the_capital_used() {
+        let mut used_amounts: Vec<CapitalUsage> = [("alice.os", "3000"), ("bob.os", "3000"), ("carol.os", "1000")]
+            .into_iter()
+            .map(|(node_id, amount_used)| CapitalUsage {
+                node_id: node_id.to_string(),
+                asset: "usdc".to_string(),
+                amount_used: amount_used.to_string(),
+                profit_share: "0".to_string(),
+                fee_bps: 0,
+            })
+            .collect();
+        assign_profit_shares(&mut used_amounts, U256::from(700u64), &DistributionScheme::default());
+        let shares: Vec<&str> = used_amounts.iter().map(|usage| usage.profit_share.as_str()).collect();
+        assert_eq!(shares, vec!["300", "300", "100"]);
+        // A remainder from rounding lands on the first line, so the shares still sum to the proceeds
+        assign_profit_shares(&mut used_amounts, U256::from(701u64), &DistributionScheme::default());
+        let shares: Vec<&str> = used_amounts.iter().map(|usage| usage.profit_share.as_str()).collect();
+        assert_eq!(shares, vec!["301", "300", "100"]);
+    }
+
+    #[tokio::test]
+    async fn repaid_debt_takes_the_victim_off_the_watchlist() {
+        let (mut strategy, mock) = mocked_strategy();
+        let (tx, mut notices) = tokio::sync::mpsc::unbounded_channel();
+        strategy.set_position_sender(tx);
+        let (victim, other) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        strategy.watch_position(watched(victim));
+        strategy.watch_position(watched(other));
+        
+        mock.push::<Bytes, _>(account_data(10_000, 0, U256::MAX)).unwrap();
+        let opportunity = strategy.check_liquidation_opportunity(victim, 2, false).await.unwrap();
+        assert!(opportunity.is_none());
+        assert!(!strategy.monitored_positions.contains_key(&victim));
+        assert!(strategy.monitored_positions.contains_key(&other));
+        
+        let mut closed = Vec::new();
+        while let Ok(notice) = notices.try_recv() {
+            if let PositionNotice::PositionClosed { borrower, reason } = notice {
+                closed.push((borrower, reason));
+            }
+        }
+        assert_eq!(closed, vec![(format!("{:?}", victim), PositionClosedReason::DebtRepaid)]);
+    }
+
+    #[tokio::test]
+    async fn debt_gone_in_a_liquidation_block_counts_as_liquidated_elsewhere() {
+        let (mut strategy, mock) = mocked_strategy();
+        let (tx, mut notices) = tokio::sync::mpsc::unbounded_channel();
+        strategy.set_position_sender(tx);
+        let victim = Address::repeat_byte(0x01);
+        strategy.watch_position(watched(victim));
+        
+        mock.push::<Bytes, _>(account_data(0, 0, U256::MAX)).unwrap();
+        assert!(strategy.check_liquidation_opportunity(victim, 2, true).await.unwrap().is_none());
+        assert!(strategy.monitored_positions.is_empty());
+        let last = std::iter::from_fn(|| notices.try_recv().ok()).last();
+        assert!(matches!(last, Some(PositionNotice::PositionClosed { reason: PositionClosedReason::LiquidatedExternally, .. })));
+    }
+
+    #[test]
+    fn two_collaterals_fill_the_higher_bonus_first() {
+        // WBTC's 10% wins over WETH though WETH is listed first, and covers a 300 repay alone
+        let legs = plan_seize_legs(&[weth(), wbtc()], U256::from(300 * BASE));
+        assert_eq!(seized(&legs), vec![(wbtc().asset, "30000000000".to_string(), "550000".to_string())]);
+        
+        // A 1000 repay drains the WBTC (worth 600, so 545.45 of debt at 110%) and WETH takes the rest
+        let legs = plan_seize_legs(&[weth(), wbtc()], U256::from(1_000 * BASE));
+        assert_eq!(seized(&legs), vec![
+            (wbtc().asset, "54545454545".to_string(), "999999".to_string()),
+            (weth().asset, "45454545455".to_string(), "238636363638750000".to_string()),
+        ]);
+        assert_eq!(legs.iter().map(|leg| leg.bonus_bps).collect::<Vec<_>>(), vec![1_000, 500]);
+    }
+
+    #[test]
+    fn three_collaterals_stop_once_the_repay_is_covered() {
+        // WBTC then LINK cover 1000 between them; WETH, the lowest bonus, is left alone
+        let legs = plan_seize_legs(&[weth(), link(), wbtc()], U256::from(1_000 * BASE));
+        assert_eq!(seized(&legs), vec![
+            (wbtc().asset, "54545454545".to_string(), "999999".to_string()),
+            (link().asset, "45454545455".to_string(), "48863636364125000000".to_string()),
+        ]);
+        
+        // A 2000 repay exhausts WBTC and LINK and reaches WETH
+        let legs = plan_seize_legs(&[weth(), link(), wbtc()], U256::from(2_000 * BASE));
+        assert_eq!(seized(&legs), vec![
+            (wbtc().asset, "54545454545".to_string(), "999999".to_string()),
+            (link().asset, "46511627906".to_string(), "49999999998950000000".to_string()),
+            (weth().asset, "98942917549".to_string(), "519450317132250000".to_string()),
+        ]);
+        let covered = legs.iter().fold(U256::zero(), |acc, leg| acc + leg.debt_covered);
+        assert_eq!(covered, U256::from(2_000 * BASE));
+    }
+}
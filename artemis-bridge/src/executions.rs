@@ -0,0 +1,110 @@
+// Execution log: every intent collection the bridge accepted and, once known, its receipt. Kept on
+// disk so that after a restart on either side the Hyperware node can ask what became of an
+// execution instead of re-dispatching it or waiting forever.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::types::AaveLiquidationReceipt;
+
+/// Completed entries are kept this long for late status queries
+const COMPLETED_RETENTION_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExecutionStage {
+    /// Accepted by this run of the bridge; the receipt is still being produced
+    InProgress,
+    /// Accepted before the bridge last restarted and never finished
+    Interrupted,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionEntry {
+    pub accepted_at: i64,
+    pub stage: ExecutionStage,
+    #[serde(default)]
+    pub receipt: Option<AaveLiquidationReceipt>,
+}
+
+pub struct ExecutionLog {
+    path: PathBuf,
+    entries: BTreeMap<String, ExecutionEntry>,
+}
+
+impl ExecutionLog {
+    /// Loads `EXECUTION_LOG_PATH` (default `artemis-executions.json`). Anything still in progress
+    /// belonged to a previous run and is marked interrupted.
+    pub fn from_env() -> Self {
+        let path = PathBuf::from(std::env::var("EXECUTION_LOG_PATH")
+            .unwrap_or_else(|_| "artemis-executions.json".to_string()));
+        let mut entries: BTreeMap<String, ExecutionEntry> = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        for entry in entries.values_mut() {
+            if entry.stage == ExecutionStage::InProgress {
+                entry.stage = ExecutionStage::Interrupted;
+            }
+        }
+        let interrupted = entries.values().filter(|e| e.stage == ExecutionStage::Interrupted).count();
+        if interrupted > 0 {
+            eprintln!("⚠️  {} executions were interrupted by the last shutdown", interrupted);
+        }
+        Self { path, entries }
+    }
+
+    pub fn get(&self, opp_id: &str) -> Option<&ExecutionEntry> {
+        self.entries.get(opp_id)
+    }
+
+    pub fn accept(&mut self, opp_id: &str, now: i64) -> anyhow::Result<()> {
+        self.entries.insert(opp_id.to_string(), ExecutionEntry {
+            accepted_at: now,
+            stage: ExecutionStage::InProgress,
+            receipt: None,
+        });
+        self.persist(now)
+    }
+
+    pub fn complete(&mut self, receipt: &AaveLiquidationReceipt, now: i64) -> anyhow::Result<()> {
+        let entry = self.entries.entry(receipt.opp_id.clone()).or_insert(ExecutionEntry {
+            accepted_at: now,
+            stage: ExecutionStage::InProgress,
+            receipt: None,
+        });
+        entry.stage = ExecutionStage::Completed;
+        entry.receipt = Some(receipt.clone());
+        self.persist(now)
+    }
+
+    /// Answer to a `QueryExecutionStatus` from Hyperware
+    pub fn status_report(&self, opp_id: &str) -> serde_json::Value {
+        let (status, receipt) = match self.entries.get(opp_id) {
+            None => ("Unknown", None),
+            Some(entry) => match entry.stage {
+                ExecutionStage::InProgress => ("InProgress", None),
+                ExecutionStage::Interrupted => ("Interrupted", None),
+                ExecutionStage::Completed => ("Completed", entry.receipt.as_ref()),
+            },
+        };
+        serde_json::json!({
+            "type": "ExecutionStatusReport",
+            "opp_id": opp_id,
+            "status": status,
+            "receipt": receipt,
+        })
+    }
+
+    /// Writes the log through a temporary file so a crash mid-write leaves the previous copy intact
+    fn persist(&mut self, now: i64) -> anyhow::Result<()> {
+        self.entries.retain(|_, entry| {
+            entry.stage != ExecutionStage::Completed || now - entry.accepted_at <= COMPLETED_RETENTION_SECS
+        });
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&self.entries)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
@@ -5,9 +5,11 @@ use artemis_core::types::CollectorMap;
 use artemis_core::engine::Engine;
 use artemis_core::collectors::block_collector::BlockCollector;
 
-use ethers::providers::{Provider, Ws};
-use ethers::types::{Address, U256};
+use ethers::providers::{Middleware, Provider, Ws};
+use ethers::types::{Address, U256, U64};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use futures_util::{SinkExt, StreamExt};
@@ -17,31 +19,174 @@ use tokio::task::LocalSet;
 use tokio::sync::mpsc;
 
 mod aave_strategy;
+// Same source as the Hyperware process, so both sides allocate identically
+#[path = "../../hyper-mev/src/allocation.rs"]
+#[allow(dead_code)]
+mod allocation;
+mod executions;
+mod outage;
 mod types;
+mod wallets;
 
-use aave_strategy::{AaveLiquidationStrategy, AaveEvent, AaveAction};
+use aave_strategy::{AaveLiquidationStrategy, AaveEvent, AaveAction, DEFAULT_REVALIDATE_PER_BLOCK, DEFAULT_WATCHLIST_CAPACITY};
+use executions::{ExecutionLog, ExecutionStage};
+use outage::OutageBuffer;
 use types::*;
+use wallets::WalletRegistry;
 
 // We'll use JSON messages directly
 
+/// Exit code used when the engine keeps wedging, so a supervisor can tell it apart from a crash
+const EXIT_ENGINE_WEDGED: i32 = 75;
+
+/// Longest wait between attempts to reach Hyperware
+const MAX_RECONNECT_DELAY_SECS: u64 = 60;
+
+/// Most executions answered in one sync response, matching the node's request cap
+const MAX_SYNC_ENTRIES: usize = 500;
+
+type HyperwareStream = futures_util::stream::SplitStream<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>>;
+type HyperwareSink = futures_util::stream::SplitSink<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>, Message>;
+
+fn env_u64(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Detects an engine that stopped processing events while the chain keeps producing blocks
+struct EngineWatchdog {
+    /// Seconds without a processed event before the engine is considered wedged
+    stall_after_secs: u64,
+    /// Restarts tolerated within `rapid_window_secs` before escalating to a process exit
+    max_rapid_restarts: usize,
+    rapid_window_secs: u64,
+    last_block_seen: Option<U64>,
+    restarts: u64,
+    recent_restarts: VecDeque<u64>,
+}
+
+impl EngineWatchdog {
+    fn from_env() -> Self {
+        let block_time_secs = env_u64("WATCHDOG_BLOCK_TIME_SECS", 12);
+        let stall_multiple = env_u64("WATCHDOG_STALL_MULTIPLE", 5);
+        Self {
+            stall_after_secs: block_time_secs * stall_multiple,
+            max_rapid_restarts: env_u64("WATCHDOG_MAX_RAPID_RESTARTS", 3) as usize,
+            rapid_window_secs: env_u64("WATCHDOG_RAPID_WINDOW_SECS", 600),
+            last_block_seen: None,
+            restarts: 0,
+            recent_restarts: VecDeque::new(),
+        }
+    }
+
+    /// True when the provider reports new blocks but the strategy has been idle too long
+    fn is_wedged(&mut self, latest_block: U64, last_event_at: u64, now: u64) -> bool {
+        let chain_advanced = self.last_block_seen.map_or(false, |seen| latest_block > seen);
+        self.last_block_seen = Some(latest_block);
+        chain_advanced && now.saturating_sub(last_event_at) > self.stall_after_secs
+    }
+
+    /// Records a restart; returns false once restarts come too fast to keep recovering in place
+    fn record_restart(&mut self, now: u64) -> bool {
+        self.restarts += 1;
+        self.recent_restarts.push_back(now);
+        while self.recent_restarts.front().map_or(false, |t| now.saturating_sub(*t) > self.rapid_window_secs) {
+            self.recent_restarts.pop_front();
+        }
+        self.recent_restarts.len() <= self.max_rapid_restarts
+    }
+}
+
+/// Folds repeat threshold crossings by the same victim into its existing opportunity
+struct OriginationThrottle {
+    cooldown_secs: i64,
+    /// (strategy, victim) -> (opp_id, last activity)
+    by_victim: HashMap<(String, String), (String, i64)>,
+}
+
+impl OriginationThrottle {
+    /// Opportunities we originated that are still within their cooldown, for the reconnection sync
+    fn live_opportunities(&self, now: i64) -> Vec<String> {
+        self.by_victim.values()
+            .filter(|(_, last_seen)| now - *last_seen <= self.cooldown_secs)
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect()
+    }
+
+    fn from_env() -> Self {
+        Self {
+            cooldown_secs: env_u64("ORIGINATION_COOLDOWN_SECS", 300) as i64,
+            by_victim: HashMap::new(),
+        }
+    }
+
+    /// Message to send for a discovered opportunity: a broadcast for a new victim, or an update
+    /// reusing the victim's opp_id while it is still within the cooldown
+    fn originate(&mut self, mut opportunity: AaveLiquidationOpportunity, now: i64) -> serde_json::Value {
+        let cooldown_secs = self.cooldown_secs;
+        self.by_victim.retain(|_, (_, last_seen)| now - *last_seen <= cooldown_secs);
+        
+        let key = ("aave-liquidation".to_string(), opportunity.victim_address.to_lowercase());
+        if let Some((opp_id, last_seen)) = self.by_victim.get_mut(&key) {
+            opportunity.opp_id = opp_id.clone();
+            *last_seen = now;
+            return serde_json::json!({
+                "type": "OpportunityUpdate",
+                "opportunity": opportunity,
+            });
+        }
+        
+        self.by_victim.insert(key, (opportunity.opp_id.clone(), now));
+        serde_json::json!({
+            "type": "OpportunityBroadcast",
+            "opportunity": opportunity,
+        })
+    }
+}
+
+/// Builds a fresh engine: new collectors (and their subscriptions) and a copy of the strategy
+fn build_engine(
+    provider: Arc<Provider<Ws>>,
+    strategy: &AaveLiquidationStrategy<Provider<Ws>>,
+) -> Engine<AaveEvent, AaveAction> {
+    let mut engine: Engine<AaveEvent, AaveAction> = Engine::default();
+    
+    // Set up block collector
+    let block_collector = Box::new(BlockCollector::new(provider));
+    let block_collector_map = CollectorMap::new(block_collector, AaveEvent::NewBlock);
+    engine.add_collector(Box::new(block_collector_map));
+    
+    engine.add_strategy(Box::new(strategy.clone()));
+    
+    // No executor needed since we're not submitting transactions in the MVP
+    // Transactions will be submitted by Hyperware nodes after coordination
+    engine
+}
+
+/// Runs the engine to completion; must be spawned on the LocalSet since its error type isn't Send
+async fn run_engine(engine: Engine<AaveEvent, AaveAction>) {
+    match engine.run().await {
+        Ok(mut join_set) => {
+            while let Some(task_result) = join_set.join_next().await {
+                if let Err(e) = task_result {
+                    eprintln!("Artemis engine task failed: {:?}", e);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Artemis engine error: {:?}", e);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
     
-    // Connect to Hyperware WebSocket endpoint
+    // The Hyperware connection is (re)established inside the main loop below
     let hyperware_ws_url = std::env::var("HYPERWARE_WS_URL")
         .unwrap_or_else(|_| "ws://localhost:8080/hyper-mev:hyper-mev:template.os".to_string());
-    println!("Connecting to Hyperware at: {}", hyperware_ws_url);
-    
-    let url = Url::parse(&hyperware_ws_url)?;
-    let (ws_stream, _) = connect_async(url).await?;
-    let (mut write, mut read) = ws_stream.split();
-    
-    println!("Connected to Hyperware P2P Pool via WebSocket!");
-    
-    // Send initial handshake message
-    let handshake = "artemis-bridge-connected";
-    write.send(Message::Text(handshake.to_string())).await?;
+    let hyperware_url = Url::parse(&hyperware_ws_url)?;
+    let bridge_id = std::env::var("BRIDGE_ID").unwrap_or_else(|_| "artemis-bridge".to_string());
     
     // Connect to Ethereum
     let eth_ws_url = std::env::var("ETH_WS_URL")
@@ -50,6 +195,11 @@ async fn main() -> anyhow::Result<()> {
     
     let ws = Ws::connect(&eth_ws_url).await?;
     let provider = Arc::new(Provider::new(ws));
+    let chain_id = provider.get_chainid().await?.as_u64();
+    
+    // Execution identities per (strategy, chain)
+    let mut wallets = WalletRegistry::from_env()?;
+    let mut executions = ExecutionLog::from_env();
     
     // Create Aave liquidation strategy
     let mut strategy = AaveLiquidationStrategy::new(
@@ -62,128 +212,274 @@ async fn main() -> anyhow::Result<()> {
     let (opp_tx, mut opp_rx) = mpsc::unbounded_channel::<AaveLiquidationOpportunity>();
     strategy.set_broadcast_sender(opp_tx);
     
-    // Set up engine with our custom event and action types
-    let mut engine: Engine<AaveEvent, AaveAction> = Engine::default();
-    
-    // Set up block collector
-    let block_collector = Box::new(BlockCollector::new(provider.clone()));
-    let block_collector_map = CollectorMap::new(block_collector, AaveEvent::NewBlock);
-    engine.add_collector(Box::new(block_collector_map));
-    
-    // Clone strategy for engine (since we need it later for WebSocket handling)
-    engine.add_strategy(Box::new(strategy.clone()));
+    // Watchlist additions, refreshes and removals are mirrored to Hyperware
+    let (position_tx, mut position_rx) = mpsc::unbounded_channel::<PositionNotice>();
+    strategy.set_position_sender(position_tx);
+    strategy.set_watchlist_limits(
+        env_u64("WATCHLIST_CAPACITY", DEFAULT_WATCHLIST_CAPACITY as u64) as usize,
+        env_u64("WATCHLIST_REVALIDATE_PER_BLOCK", DEFAULT_REVALIDATE_PER_BLOCK as u64) as usize,
+    );
     
-    // No executor needed since we're not submitting transactions in the MVP
-    // Transactions will be submitted by Hyperware nodes after coordination
+    // The engine gets its own copy of the strategy (we need ours later for WebSocket handling);
+    // both copies share the last-event marker the watchdog reads
+    let last_event_at: Arc<AtomicU64> = strategy.last_event_marker();
+    last_event_at.store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
+    let engine = build_engine(provider.clone(), &strategy);
     
     // Start Artemis engine using a LocalSet so we don't require Send
     let local_set = LocalSet::new();
 
     // Spawn the engine locally; it may return a non-Send error type
-    local_set.spawn_local(async move {
-        match engine.run().await {
-            Ok(mut join_set) => {
-                while let Some(task_result) = join_set.join_next().await {
-                    if let Err(e) = task_result {
-                        eprintln!("Artemis engine task failed: {:?}", e);
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Artemis engine error: {:?}", e);
-            }
-        }
-    });
+    let mut engine_task = local_set.spawn_local(run_engine(engine));
+    
+    let mut watchdog = EngineWatchdog::from_env();
+    let mut throttle = OriginationThrottle::from_env();
+    let mut outage = OutageBuffer::from_env();
+    let mut watchdog_tick = tokio::time::interval(tokio::time::Duration::from_secs(
+        env_u64("WATCHDOG_BLOCK_TIME_SECS", 12).max(1),
+    ));
+    let mut wallet_report_tick = tokio::time::interval(tokio::time::Duration::from_secs(
+        env_u64("WALLET_REPORT_INTERVAL_SECS", 300).max(1),
+    ));
+    // The node has no chain access of its own; this is how it learns the head
+    let mut block_update_tick = tokio::time::interval(tokio::time::Duration::from_secs(
+        env_u64("BLOCK_UPDATE_INTERVAL_SECS", 12).max(1),
+    ));
+    let mut last_block_sent: Option<U64> = None;
 
     // Run the main WebSocket loop within the same LocalSet
     local_set
         .run_until(async move {
-            // Main loop - handle WebSocket messages and Artemis events
+            let mut reconnect_delay_secs = 1;
             loop {
-                tokio::select! {
-                    // Forward opportunities discovered by the Artemis strategy to Hyperware
-                    Some(opportunity) = opp_rx.recv() => {
-                        let message = serde_json::json!({
-                            "type": "OpportunityBroadcast",
-                            "opportunity": opportunity
-                        });
-                        write.send(Message::Text(message.to_string())).await?;
+                // (Re)connect to Hyperware; finds made in the meantime wait in the outage buffer
+                println!("Connecting to Hyperware at: {}", hyperware_ws_url);
+                let (mut write, mut read) = loop {
+                    tokio::select! {
+                        Some(opportunity) = opp_rx.recv() => {
+                            let now = chrono::Utc::now().timestamp();
+                            outage.push(throttle.originate(opportunity, now), now);
+                        }
+                        connected = connect_hyperware(&hyperware_url, &bridge_id) => match connected {
+                            Ok(halves) => break halves,
+                            Err(e) => {
+                                eprintln!("Hyperware connection failed: {}; retrying in {}s", e, reconnect_delay_secs);
+                                tokio::time::sleep(tokio::time::Duration::from_secs(reconnect_delay_secs)).await;
+                                reconnect_delay_secs = (reconnect_delay_secs * 2).min(MAX_RECONNECT_DELAY_SECS);
+                            }
+                        },
                     }
-                    // Handle incoming WebSocket messages from Hyperware
-                    Some(message) = read.next() => {
-                        match message {
-                            Ok(Message::Text(text)) => {
-                                handle_hyperware_message_json(&text, &mut strategy, &mut write).await?;
+                };
+                reconnect_delay_secs = 1;
+                last_block_sent = None;
+                println!("Connected to Hyperware P2P Pool via WebSocket!");
+                
+                // Main loop - handle WebSocket messages and Artemis events
+                loop {
+                    tokio::select! {
+                        // Forward opportunities discovered by the Artemis strategy to Hyperware
+                        Some(opportunity) = opp_rx.recv() => {
+                            let message = throttle.originate(opportunity, chrono::Utc::now().timestamp());
+                            if !deliver(&mut write, &mut outage, message).await {
+                                break;
                             }
-                            Ok(Message::Binary(data)) => {
-                                if let Ok(text) = String::from_utf8(data) {
-                                    handle_hyperware_message_json(&text, &mut strategy, &mut write).await?;
-                                }
+                        }
+                        Some(notice) = position_rx.recv() => {
+                            let Ok(notice) = serde_json::to_string(&notice) else {
+                                continue;
+                            };
+                            if write.send(Message::Text(notice)).await.is_err() {
+                                break;
+                            }
+                        }
+                        // Rebuild the engine in place if it stopped processing while blocks keep coming
+                        _ = watchdog_tick.tick() => {
+                            let Ok(latest_block) = provider.get_block_number().await else {
+                                continue;
+                            };
+                            let now = chrono::Utc::now().timestamp() as u64;
+                            let last_event = last_event_at.load(Ordering::Relaxed);
+                            if !watchdog.is_wedged(latest_block, last_event, now) {
+                                continue;
+                            }
+                            
+                            let keep_recovering = watchdog.record_restart(now);
+                            eprintln!("⚠️  Artemis engine idle for {}s at block {}; restart #{}",
+                                now.saturating_sub(last_event), latest_block, watchdog.restarts);
+                            let health = serde_json::json!({
+                                "type": "BridgeHealth",
+                                "event": if keep_recovering { "EngineRestarted" } else { "EngineWedged" },
+                                "engine_restarts": watchdog.restarts,
+                                "last_event_at": last_event,
+                                "latest_block": latest_block.as_u64(),
+                                "wallets": wallets.report(&*provider).await,
+                            });
+                            let sent = write.send(Message::Text(health.to_string())).await.is_ok();
+                            
+                            if !keep_recovering {
+                                eprintln!("Artemis engine restarted {} times within {}s; exiting for the supervisor",
+                                    watchdog.recent_restarts.len(), watchdog.rapid_window_secs);
+                                std::process::exit(EXIT_ENGINE_WEDGED);
                             }
-                            Ok(Message::Close(_)) => {
-                                println!("Hyperware disconnected");
+                            
+                            engine_task.abort();
+                            last_event_at.store(now, Ordering::Relaxed);
+                            engine_task = tokio::task::spawn_local(run_engine(build_engine(provider.clone(), &strategy)));
+                            if !sent {
                                 break;
                             }
-                            Err(e) => {
-                                eprintln!("WebSocket error: {}", e);
+                        }
+                        // Current head for the node's deadline and expiry checks, once per new block
+                        _ = block_update_tick.tick() => {
+                            let Ok(Some(block)) = provider.get_block(ethers::types::BlockNumber::Latest).await else {
+                                continue;
+                            };
+                            let Some(number) = block.number.filter(|number| Some(*number) != last_block_sent) else {
+                                continue;
+                            };
+                            let update = serde_json::json!({
+                                "type": "BlockUpdate",
+                                "number": number.as_u64(),
+                                "timestamp": block.timestamp.as_u64(),
+                                "base_fee_per_gas": block.base_fee_per_gas.map(|fee| fee.to_string()),
+                            });
+                            if write.send(Message::Text(update.to_string())).await.is_err() {
                                 break;
                             }
-                            _ => {}
+                            last_block_sent = Some(number);
                         }
-                    }
+                        // Periodic wallet summary for the node's health view
+                        _ = wallet_report_tick.tick() => {
+                            let health = serde_json::json!({
+                                "type": "BridgeHealth",
+                                "event": "Report",
+                                "engine_restarts": watchdog.restarts,
+                                "last_event_at": last_event_at.load(Ordering::Relaxed),
+                                "wallets": wallets.report(&*provider).await,
+                                "monitoring": strategy.delegation_handle().lock().map(|delegation| delegation.report()).unwrap_or_default(),
+                            });
+                            if write.send(Message::Text(health.to_string())).await.is_err() {
+                                break;
+                            }
+                        }
+                        // Handle incoming WebSocket messages from Hyperware
+                        message = read.next() => {
+                            let text = match message {
+                                Some(Ok(Message::Text(text))) => text,
+                                Some(Ok(Message::Binary(data))) => match String::from_utf8(data) {
+                                    Ok(text) => text,
+                                    Err(_) => continue,
+                                },
+                                Some(Ok(Message::Close(_))) | None => {
+                                    println!("Hyperware disconnected");
+                                    break;
+                                }
+                                Some(Err(e)) => {
+                                    eprintln!("WebSocket error: {}", e);
+                                    break;
+                                }
+                                _ => continue,
+                            };
+                            if let Err(e) = handle_hyperware_message_json(&text, &mut strategy, &mut wallets, &mut executions, &mut outage, &throttle, chain_id, &mut write).await {
+                                eprintln!("Failed to handle Hyperware message: {}", e);
+                                // Only a broken connection ends the session; a bad message doesn't
+                                if e.downcast_ref::<tokio_tungstenite::tungstenite::Error>().is_some() {
+                                    break;
+                                }
+                            }
+                        }
+                        
+                                    // Handle periodic opportunity generation (simulate MEV findings)
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {
+                    // Simulate finding different liquidation scenarios
+                    let scenarios = vec![
+                        ("0x742d35Cc6634C0532925a3b844D0C4E7F2a21eBc", "950000000000000000", "1500000000", "75000000", "525000000000000000"), // 0.95 HF, $1500 repay, $75 profit, 0.525 WETH
+                        ("0x8B3a350Cf5c34C9194CA55829DB2dB4A37e5E6A3", "850000000000000000", "3000000000", "180000000", "1050000000000000000"), // 0.85 HF, $3000 repay, $180 profit, 1.05 WETH
+                        ("0x1234567890123456789012345678901234567890", "920000000000000000", "800000000", "32000000", "280000000000000000"), // 0.92 HF, $800 repay, $32 profit, 0.28 WETH
+                    ];
                     
-                                // Handle periodic opportunity generation (simulate MEV findings)
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {
-                // Simulate finding different liquidation scenarios
-                let scenarios = vec![
-                    ("0x742d35Cc6634C0532925a3b844D0C4E7F2a21eBc", "950000000000000000", "1500000000", "75000000"), // 0.95 HF, $1500 repay, $75 profit
-                    ("0x8B3a350Cf5c34C9194CA55829DB2dB4A37e5E6A3", "850000000000000000", "3000000000", "180000000"), // 0.85 HF, $3000 repay, $180 profit
-                    ("0x1234567890123456789012345678901234567890", "920000000000000000", "800000000", "32000000"), // 0.92 HF, $800 repay, $32 profit
-                ];
-                
-                let scenario_idx = (chrono::Utc::now().timestamp() % 3) as usize;
-                let (victim, hf, repay, profit) = scenarios[scenario_idx];
-                
-                let opportunity = AaveLiquidationOpportunity {
-                    opp_id: uuid::Uuid::new_v4().to_string(),
-                    victim_address: victim.to_string(),
-                    repay_asset: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(), // USDC mainnet
-                    seize_asset: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(), // WETH mainnet
-                    max_repay_amount: repay.to_string(),
-                    min_bonus_bps: 500, // 5% liquidation bonus
-                    health_factor: hf.to_string(),
-                    deadline_block: 100000000 + 10, // Current block + 10
-                    estimated_profit_usd: profit.to_string(),
-                };
-                
-                let message = serde_json::json!({
-                    "type": "OpportunityBroadcast",
-                    "opportunity": opportunity,
-                });
-                write.send(Message::Text(message.to_string())).await?;
-                
-                println!("\n🎯 Found Liquidation Opportunity:");
-                println!("   Victim: {}", victim);
-                println!("   Health Factor: {}", hf);
-                println!("   Max Repay: ${} USDC", repay);
-                println!("   Est. Profit: ${}", profit);
-                println!("   Opp ID: {}", opportunity.opp_id);
-            }
+                    let scenario_idx = (chrono::Utc::now().timestamp() % 3) as usize;
+                    let (victim, hf, repay, profit, seized) = scenarios[scenario_idx];
                     
-                    else => break,
+                    let opportunity = AaveLiquidationOpportunity {
+                        opp_id: uuid::Uuid::new_v4().to_string(),
+                        victim_address: victim.to_string(),
+                        repay_asset: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(), // USDC mainnet
+                        seize_asset: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(), // WETH mainnet
+                        max_repay_amount: repay.to_string(),
+                        min_bonus_bps: 500, // 5% liquidation bonus
+                        health_factor: hf.to_string(),
+                        deadline_block: 100000000 + 10, // Current block + 10
+                        estimated_profit_usd: profit.to_string(),
+                        seize_assets: vec![SeizeLeg {
+                            asset: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(),
+                            expected_amount: seized.to_string(),
+                            bonus_bps: 500,
+                        }],
+                        distribution: Default::default(),
+                    };
+                    
+                    let message = throttle.originate(opportunity, chrono::Utc::now().timestamp());
+                    
+                    println!("\n🎯 Found Liquidation Opportunity:");
+                    println!("   Victim: {}", victim);
+                    println!("   Health Factor: {}", hf);
+                    println!("   Max Repay: ${} USDC", repay);
+                    println!("   Est. Profit: ${}", profit);
+                    println!("   Opp ID: {} ({})", message["opportunity"]["opp_id"], message["type"]);
+                    if !deliver(&mut write, &mut outage, message).await {
+                        break;
+                    }
+                }
+                        
+                        else => break,
+                    }
                 }
+                
+                println!("Hyperware connection lost; reconnecting ({} messages buffered)", outage.len());
             }
-            Ok::<(), anyhow::Error>(())
         })
-        .await?;
+        .await;
     
     Ok(())
 }
 
+/// Connects to Hyperware and sends the handshake; the bridge id lets the node match in-flight
+/// executions to us across reconnects
+async fn connect_hyperware(url: &Url, bridge_id: &str) -> anyhow::Result<(HyperwareSink, HyperwareStream)> {
+    let (ws_stream, _) = connect_async(url.clone()).await?;
+    let (mut write, read) = ws_stream.split();
+    let handshake = serde_json::json!({
+        "type": "BridgeHello",
+        "bridge_id": bridge_id,
+        "bridge_version": env!("CARGO_PKG_VERSION"),
+    });
+    write.send(Message::Text(handshake.to_string())).await?;
+    Ok((write, read))
+}
+
+/// Sends a message to Hyperware. One that can't be sent waits in the outage buffer for the next
+/// sync, and false tells the caller the connection is gone.
+async fn deliver(write: &mut HyperwareSink, outage: &mut OutageBuffer, message: serde_json::Value) -> bool {
+    match write.send(Message::Text(message.to_string())).await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Hyperware send failed: {}; buffering {}", e, message["type"]);
+            outage.push(message, chrono::Utc::now().timestamp());
+            false
+        }
+    }
+}
+
 async fn handle_hyperware_message_json(
     json_str: &str,
     strategy: &mut AaveLiquidationStrategy<Provider<Ws>>,
-    write: &mut futures_util::stream::SplitSink<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>, Message>,
+    wallets: &mut WalletRegistry,
+    executions: &mut ExecutionLog,
+    outage: &mut OutageBuffer,
+    throttle: &OriginationThrottle,
+    chain_id: u64,
+    write: &mut HyperwareSink,
 ) -> anyhow::Result<()> {
     let message: serde_json::Value = serde_json::from_str(json_str)?;
     
@@ -198,13 +494,59 @@ async fn handle_hyperware_message_json(
         }
         Some("IntentCollection") => {
             let opp_id = message["opp_id"].as_str().unwrap_or("").to_string();
+            let repay_asset = message["repay_asset"].as_str().map(|s| s.to_string());
+            let max_repay_amount = message["max_repay_amount"].as_str().map(|s| s.to_string());
+            let seize_legs: Vec<SeizeLeg> = serde_json::from_value(message["seize_assets"].clone()).unwrap_or_default();
+            let distribution: allocation::DistributionScheme = serde_json::from_value(message["distribution"].clone()).unwrap_or_default();
             let intents: Vec<IntentData> = serde_json::from_value(message["intents"].clone())?;
+            let allocation: Option<allocation::AllocationRecord> = serde_json::from_value(message["allocation"].clone()).unwrap_or(None);
             println!("\n📥 Received {} intents for opportunity {}", intents.len(), opp_id);
             
+            // A collection we already took is never executed twice; a finished one gets its receipt again
+            if let Some(entry) = executions.get(&opp_id) {
+                println!("   ↩️  Already {:?} for {}; not executing again", entry.stage, opp_id);
+                if entry.stage == ExecutionStage::Completed {
+                    write.send(Message::Text(executions.status_report(&opp_id).to_string())).await?;
+                }
+                return Ok(());
+            }
+            let now = chrono::Utc::now().timestamp();
+            executions.accept(&opp_id, now)?;
+            
+            // Each strategy executes from its own wallet on this chain
+            let wallet = match wallets.select("aave-liquidation", chain_id) {
+                Ok(wallet) => wallet,
+                Err(e) => {
+                    eprintln!("   ❌ {}", e);
+                    let receipt = AaveLiquidationReceipt {
+                        opp_id,
+                        status: ExecutionStatus::Failed(e.to_string()),
+                        block_number: 0,
+                        tx_hash: String::new(),
+                        used_amounts: Vec::new(),
+                        total_proceeds: "0".to_string(),
+                        gas_paid_usdc: "0".to_string(),
+                        provider_rule: None,
+                        allocation: None,
+                        execution_wallet: None,
+                        seized: Vec::new(),
+                    };
+                    executions.complete(&receipt, now)?;
+                    let response = serde_json::json!({
+                        "type": "ExecutionReceipt",
+                        "receipt": receipt,
+                    });
+                    write.send(Message::Text(response.to_string())).await?;
+                    return Ok(());
+                }
+            };
+            
             // Execute liquidation with available capital from P2P network
-            if let Some(receipt) = strategy.execute_with_intents(opp_id, intents).await? {
+            if let Some(mut receipt) = strategy.execute_with_intents(opp_id, repay_asset, max_repay_amount, seize_legs, distribution, intents, allocation, wallet).await? {
+                receipt.provider_rule = serde_json::from_value(message["provider_rule"].clone()).unwrap_or(None);
                 println!("   ✅ Simulated execution complete!");
                 println!("   Total proceeds: ${}", receipt.total_proceeds);
+                executions.complete(&receipt, chrono::Utc::now().timestamp())?;
                 
                 let response = serde_json::json!({
                     "type": "ExecutionReceipt",
@@ -213,6 +555,111 @@ async fn handle_hyperware_message_json(
                 write.send(Message::Text(response.to_string())).await?;
             }
         }
+        Some("SyncRequest") => {
+            // Hyperware's view after a (re)connect: our record of each execution it lists, the finds
+            // we still track, and what we couldn't deliver while disconnected
+            let sync_id = message["sync_id"].as_u64().unwrap_or(0);
+            let reports: Vec<serde_json::Value> = message["live"].as_array().into_iter().flatten()
+                .take(MAX_SYNC_ENTRIES)
+                .filter_map(|entry| entry["opp_id"].as_str())
+                .map(|opp_id| executions.status_report(opp_id))
+                .collect();
+            let now = chrono::Utc::now().timestamp();
+            let undelivered = outage.take(now);
+            let response = serde_json::json!({
+                "type": "SyncResponse",
+                "sync_id": sync_id,
+                "executions": reports,
+                "known_opportunities": throttle.live_opportunities(now),
+                "undelivered": undelivered.iter().map(|(_, message)| message).collect::<Vec<_>>(),
+                "outage_dropped": outage.dropped,
+            });
+            println!("\n🔄 Sync {}: {} executions checked, {} buffered messages handed over",
+                sync_id, reports.len(), undelivered.len());
+            // A failed hand-over keeps the buffer for the next sync
+            if let Err(e) = write.send(Message::Text(response.to_string())).await {
+                outage.restore(undelivered);
+                return Err(e.into());
+            }
+        }
+        Some("QueryExecutionStatus") => {
+            let opp_id = message["opp_id"].as_str().unwrap_or("");
+            let report = executions.status_report(opp_id);
+            println!("\n🔎 Execution status for {}: {}", opp_id, report["status"]);
+            write.send(Message::Text(report.to_string())).await?;
+        }
+        Some("ValidateOpportunity") => {
+            let victim = message["victim"].as_str().unwrap_or("").to_string();
+            let block = message["block"].as_u64().unwrap_or(0);
+            let checked = match victim.parse::<Address>() {
+                Ok(user) => strategy.validate_victim(user).await,
+                Err(e) => Err(anyhow::anyhow!("Invalid victim address: {}", e)),
+            };
+            // Echo the requested block so the node can match the answer to its request
+            let result = match checked {
+                Ok((health_factor, liquidatable)) => serde_json::json!({
+                    "type": "ValidationResult",
+                    "victim": victim,
+                    "block": block,
+                    "health_factor": health_factor.to_string(),
+                    "liquidatable": liquidatable,
+                }),
+                Err(e) => serde_json::json!({
+                    "type": "ValidationResult",
+                    "victim": victim,
+                    "block": block,
+                    "health_factor": "",
+                    "liquidatable": false,
+                    "error": e.to_string(),
+                }),
+            };
+            write.send(Message::Text(result.to_string())).await?;
+        }
+        Some("ProbeRisk") => {
+            let victim = message["victim"].as_str().unwrap_or("").to_string();
+            let assets: Result<Vec<Address>, _> = message["assets"].as_array().cloned().unwrap_or_default().iter()
+                .map(|asset| asset.as_str().unwrap_or("").parse::<Address>())
+                .collect();
+            let probed = match (victim.parse::<Address>(), assets) {
+                (Ok(user), Ok(assets)) => strategy.probe_risk(user, &assets).await,
+                (Err(e), _) | (_, Err(e)) => Err(anyhow::anyhow!("Invalid address: {}", e)),
+            };
+            let result = match probed {
+                Ok((is_contract, fee_on_transfer)) => serde_json::json!({
+                    "type": "RiskProbeResult",
+                    "victim": victim,
+                    "is_contract": is_contract,
+                    "fee_on_transfer_assets": fee_on_transfer.iter().map(|asset| format!("{:?}", asset)).collect::<Vec<_>>(),
+                }),
+                Err(e) => serde_json::json!({
+                    "type": "RiskProbeResult",
+                    "victim": victim,
+                    "error": e.to_string(),
+                }),
+            };
+            write.send(Message::Text(result.to_string())).await?;
+        }
+        Some("MonitoringAssignment") => {
+            let delegated: HashSet<Address> = message["delegated"].as_array().cloned().unwrap_or_default().iter()
+                .filter_map(|victim| victim.as_str()?.parse::<Address>().ok())
+                .collect();
+            let handle = strategy.delegation_handle();
+            let Ok(mut delegation) = handle.lock() else {
+                return Ok(());
+            };
+            println!("\n📡 Leaving {} watched victims to peers (spot checks {} bps)",
+                delegated.len(), message["spot_check_bps"]);
+            delegation.delegated = delegated;
+            delegation.spot_check_bps = message["spot_check_bps"].as_u64().unwrap_or(0).min(10_000) as u16;
+        }
+        Some("RotateWallet") => {
+            let strategy_id = message["strategy"].as_str().unwrap_or("aave-liquidation");
+            let wallet_chain = message["chain_id"].as_u64().unwrap_or(chain_id);
+            match wallets.rotate_key(strategy_id, wallet_chain, message["key_env"].as_str()) {
+                Ok(address) => println!("\n🔑 Rotated execution wallet for {} on chain {} to {:?}", strategy_id, wallet_chain, address),
+                Err(e) => eprintln!("\n⚠️  Wallet rotation failed: {}", e),
+            }
+        }
         _ => {
             println!("Unknown message type");
         }
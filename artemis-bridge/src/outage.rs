@@ -0,0 +1,58 @@
+// Outage buffer: messages for Hyperware that couldn't be sent while the connection was down,
+// handed over in the reconnection sync. Bounded by count and by age, since an opportunity found
+// minutes ago is rarely still worth anything.
+
+use std::collections::VecDeque;
+
+pub struct OutageBuffer {
+    capacity: usize,
+    max_age_secs: i64,
+    /// (buffered at, message), oldest first
+    entries: VecDeque<(i64, serde_json::Value)>,
+    /// Messages discarded for capacity or age since the bridge started
+    pub dropped: u64,
+}
+
+impl OutageBuffer {
+    /// Sized by `OUTAGE_BUFFER_CAPACITY` (default 200) and `OUTAGE_BUFFER_MAX_AGE_SECS` (default 300)
+    pub fn from_env() -> Self {
+        Self {
+            capacity: crate::env_u64("OUTAGE_BUFFER_CAPACITY", 200).max(1) as usize,
+            max_age_secs: crate::env_u64("OUTAGE_BUFFER_MAX_AGE_SECS", 300) as i64,
+            entries: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn push(&mut self, message: serde_json::Value, now: i64) {
+        self.entries.push_back((now, message));
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// Everything still fresh enough to deliver, oldest first; the buffer is left empty
+    pub fn take(&mut self, now: i64) -> Vec<(i64, serde_json::Value)> {
+        let before = self.entries.len();
+        let max_age_secs = self.max_age_secs;
+        self.entries.retain(|(buffered_at, _)| now - *buffered_at <= max_age_secs);
+        self.dropped += (before - self.entries.len()) as u64;
+        self.entries.drain(..).collect()
+    }
+
+    /// Puts back messages whose hand-over failed, ahead of anything buffered since
+    pub fn restore(&mut self, taken: Vec<(i64, serde_json::Value)>) {
+        for entry in taken.into_iter().rev() {
+            self.entries.push_front(entry);
+        }
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
@@ -13,6 +13,29 @@ pub struct AaveLiquidationOpportunity {
     pub health_factor: String,
     pub deadline_block: u64,
     pub estimated_profit_usd: String,
+    /// Every collateral the liquidation seizes, best bonus first; `seize_asset` and
+    /// `min_bonus_bps` mirror the first leg and the lowest bonus for single-asset readers
+    #[serde(default)]
+    pub seize_assets: Vec<SeizeLeg>,
+    /// Rounding rules the finder declared for splitting the proceeds
+    #[serde(default)]
+    pub distribution: crate::allocation::DistributionScheme,
+}
+
+/// One collateral leg: the asset, how much of it the full repay is expected to seize (in its own
+/// units), and the reserve's liquidation bonus
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SeizeLeg {
+    pub asset: String,
+    pub expected_amount: String,
+    pub bonus_bps: u16,
+}
+
+/// Collateral a leg actually brought in
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SeizedLeg {
+    pub asset: String,
+    pub amount: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -22,6 +45,12 @@ pub struct AaveLiquidationIntent {
     pub max_amount: String,
     pub min_bonus_bps: u16,
     pub expires_block: u64,
+    #[serde(default)]
+    pub requires_swap: bool,
+    #[serde(default)]
+    pub simulated: bool,
+    #[serde(default)]
+    pub fee_bps: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -33,6 +62,25 @@ pub struct AaveLiquidationReceipt {
     pub used_amounts: Vec<CapitalUsage>,
     pub total_proceeds: String,
     pub gas_paid_usdc: String,
+    #[serde(default)]
+    pub provider_rule: Option<ProviderRuleOutcome>,
+    #[serde(default)]
+    pub allocation: Option<crate::allocation::AllocationRecord>,
+    /// Address of the execution wallet that ran (or refused) the liquidation
+    #[serde(default)]
+    pub execution_wallet: Option<String>,
+    /// Collateral realized per seize leg
+    #[serde(default)]
+    pub seized: Vec<SeizedLeg>,
+}
+
+// Distinct-provider rule the Hyperware executor applied, echoed on the receipt
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProviderRuleOutcome {
+    pub threshold_usd: String,
+    pub min_providers: u32,
+    pub distinct_providers: u32,
+    pub fallback_used: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -48,6 +96,8 @@ pub struct CapitalUsage {
     pub asset: String,
     pub amount_used: String,
     pub profit_share: String,
+    #[serde(default)]
+    pub fee_bps: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -62,7 +112,69 @@ pub struct NodeConfig {
     pub max_gas_price_gwei: String,
 }
 
+/// Watchlist changes reported to Hyperware so its copy of the monitored positions stays in step
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum PositionNotice {
+    PositionWatched {
+        borrower: String,
+        collateral_asset: String,
+        debt_asset: String,
+        health_factor: String,
+        debt_amount: String,
+        block: u64,
+    },
+    PositionClosed {
+        borrower: String,
+        reason: PositionClosedReason,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionClosedReason {
+    DebtRepaid,
+    LiquidatedExternally,
+    /// Dropped to keep the watchlist within capacity
+    Evicted,
+}
+
 // Internal types for Artemis bridge
+
+/// Aave health factor (1e18 = 1.0). Aave reports `type(uint256).max` for accounts with no debt,
+/// which is kept distinct so it never takes part in threshold math.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthFactor {
+    NoDebt,
+    Value(U256),
+}
+
+impl HealthFactor {
+    pub fn from_account_data(raw_health_factor: U256, total_debt: U256) -> Self {
+        if raw_health_factor == U256::MAX || total_debt.is_zero() {
+            HealthFactor::NoDebt
+        } else {
+            HealthFactor::Value(raw_health_factor)
+        }
+    }
+
+    /// Strictly between zero and `threshold`; debt-free accounts never qualify
+    pub fn is_below(&self, threshold: U256) -> bool {
+        match self {
+            HealthFactor::NoDebt => false,
+            HealthFactor::Value(value) => !value.is_zero() && *value < threshold,
+        }
+    }
+}
+
+impl std::fmt::Display for HealthFactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthFactor::NoDebt => write!(f, "NoDebt"),
+            HealthFactor::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UserPosition {
     pub user: Address,
@@ -70,7 +182,7 @@ pub struct UserPosition {
     pub debt_asset: Address,
     pub collateral_amount: U256,
     pub debt_amount: U256,
-    pub health_factor: U256,
+    pub health_factor: HealthFactor,
     pub last_updated_block: u64,
 }
 
@@ -82,4 +194,52 @@ pub struct IntentData {
     pub max_amount: String,
     pub expires_block: u64,
     pub received_at: String,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u64 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn sentinel_and_zero_debt_read_as_no_debt() {
+        let debt = U256::from(500u64);
+        assert_eq!(HealthFactor::from_account_data(U256::MAX, debt), HealthFactor::NoDebt);
+        assert_eq!(HealthFactor::from_account_data(U256::from(ONE), U256::zero()), HealthFactor::NoDebt);
+        assert_eq!(HealthFactor::from_account_data(U256::from(ONE), debt), HealthFactor::Value(U256::from(ONE)));
+    }
+
+    #[test]
+    fn value_round_trips_through_its_wire_string() {
+        let health_factor = HealthFactor::from_account_data(U256::from(ONE - 1), U256::from(1u64));
+        let wire = health_factor.to_string();
+        assert_eq!(wire, "999999999999999999");
+        assert_eq!(HealthFactor::Value(U256::from_dec_str(&wire).unwrap()), health_factor);
+        // Never a number, so the sentinel can't be read back as a health factor
+        assert_eq!(HealthFactor::NoDebt.to_string(), "NoDebt");
+        assert!(U256::from_dec_str(&HealthFactor::NoDebt.to_string()).is_err());
+    }
+
+    #[test]
+    fn only_values_strictly_between_zero_and_the_threshold_are_below_it() {
+        let threshold = U256::from(ONE);
+        assert!(HealthFactor::Value(threshold - 1).is_below(threshold));
+        assert!(!HealthFactor::Value(threshold).is_below(threshold));
+        assert!(!HealthFactor::Value(threshold + 1).is_below(threshold));
+        assert!(!HealthFactor::Value(U256::zero()).is_below(threshold));
+        assert!(!HealthFactor::NoDebt.is_below(threshold));
+        assert!(!HealthFactor::NoDebt.is_below(U256::MAX));
+    }
+
+    #[test]
+    fn account_repaying_its_debt_stops_qualifying() {
+        let threshold = U256::from(ONE);
+        let indebted = HealthFactor::from_account_data(U256::from(ONE / 2), U256::from(500u64));
+        assert!(indebted.is_below(threshold));
+        // Next block the debt is gone and Aave reports the sentinel
+        let repaid = HealthFactor::from_account_data(U256::MAX, U256::zero());
+        assert_eq!(repaid, HealthFactor::NoDebt);
+        assert!(!repaid.is_below(threshold));
+    }
+}
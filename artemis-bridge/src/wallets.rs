@@ -0,0 +1,259 @@
+// Execution wallets: one signing identity per (strategy, chain), each with its own gas reserve,
+// nonce tracking and spend limit, so one strategy's activity never touches another's wallet
+
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Activity entries kept per wallet for the health report
+const RECENT_ACTIVITY: usize = 20;
+const SPEND_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WalletKey {
+    pub strategy: String,
+    pub chain_id: u64,
+}
+
+impl std::fmt::Display for WalletKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} on chain {}", self.strategy, self.chain_id)
+    }
+}
+
+/// One entry of `EXECUTION_WALLETS`. The key itself is read from the named environment variable
+/// so it never sits in the wallet list.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WalletConfig {
+    pub strategy: String,
+    pub chain_id: u64,
+    pub label: String,
+    pub key_env: String,
+    /// Native balance (wei) held back for gas; execution stops when the wallet would dip below it
+    #[serde(default)]
+    pub gas_reserve_wei: Option<String>,
+    /// Most the wallet may commit to executions per rolling day, in repay-asset units
+    #[serde(default)]
+    pub spend_limit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletActivity {
+    pub at: i64,
+    pub opp_id: String,
+    pub amount: String,
+    pub outcome: String,
+}
+
+#[derive(Debug)]
+pub enum WalletError {
+    NotConfigured(WalletKey),
+    MissingKey { label: String, key_env: String },
+    InvalidConfig(String),
+    SpendLimitExceeded { label: String, limit: U256, spent: U256, requested: U256 },
+    BelowGasReserve { label: String, balance: U256, reserve: U256 },
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletError::NotConfigured(key) => write!(f, "No execution wallet configured for {}", key),
+            WalletError::MissingKey { label, key_env } => write!(f, "Wallet {}: {} is not set or not a valid key", label, key_env),
+            WalletError::InvalidConfig(reason) => write!(f, "Invalid wallet configuration: {}", reason),
+            WalletError::SpendLimitExceeded { label, limit, spent, requested } =>
+                write!(f, "Wallet {} spend limit {} reached ({} spent, {} requested)", label, limit, spent, requested),
+            WalletError::BelowGasReserve { label, balance, reserve } =>
+                write!(f, "Wallet {} balance {} is below its gas reserve {}", label, balance, reserve),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+pub struct ExecutionWallet {
+    pub label: String,
+    signer: LocalWallet,
+    key_env: String,
+    gas_reserve: U256,
+    spend_limit: Option<U256>,
+    /// Next nonce to use; fetched from the chain on first use and after a key rotation
+    next_nonce: Option<U256>,
+    spends: VecDeque<(i64, U256)>,
+    recent: VecDeque<WalletActivity>,
+}
+
+impl ExecutionWallet {
+    fn from_config(config: &WalletConfig) -> Result<Self, WalletError> {
+        let parse = |value: &Option<String>, field: &str| -> Result<Option<U256>, WalletError> {
+            value.as_deref()
+                .map(|v| U256::from_dec_str(v)
+                    .map_err(|_| WalletError::InvalidConfig(format!("{} {} for {}", field, v, config.label))))
+                .transpose()
+        };
+        Ok(Self {
+            label: config.label.clone(),
+            signer: load_signer(&config.label, &config.key_env, config.chain_id)?,
+            key_env: config.key_env.clone(),
+            gas_reserve: parse(&config.gas_reserve_wei, "gas_reserve_wei")?.unwrap_or_default(),
+            spend_limit: parse(&config.spend_limit, "spend_limit")?,
+            next_nonce: None,
+            spends: VecDeque::new(),
+            recent: VecDeque::new(),
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    // Signing and nonce allocation are for the on-chain execution path; executions are simulated for now
+    #[allow(dead_code)]
+    pub fn signer(&self) -> &LocalWallet {
+        &self.signer
+    }
+
+    fn spent_within_window(&mut self, now: i64) -> U256 {
+        while self.spends.front().map_or(false, |(at, _)| now - *at > SPEND_WINDOW_SECS) {
+            self.spends.pop_front();
+        }
+        self.spends.iter().fold(U256::zero(), |acc, (_, amount)| acc + *amount)
+    }
+
+    /// Checks the spend limit and gas reserve before committing `amount` to an execution
+    pub async fn check_execution<M: Middleware>(&mut self, provider: &M, amount: U256, now: i64) -> Result<(), WalletError> {
+        if let Some(limit) = self.spend_limit {
+            let spent = self.spent_within_window(now);
+            if spent + amount > limit {
+                return Err(WalletError::SpendLimitExceeded { label: self.label.clone(), limit, spent, requested: amount });
+            }
+        }
+        if !self.gas_reserve.is_zero() {
+            let balance = provider.get_balance(self.address(), None).await.unwrap_or_default();
+            if balance < self.gas_reserve {
+                return Err(WalletError::BelowGasReserve { label: self.label.clone(), balance, reserve: self.gas_reserve });
+            }
+        }
+        Ok(())
+    }
+
+    /// Hands out the next nonce, reading the pending count from the chain the first time
+    #[allow(dead_code)]
+    pub async fn next_nonce<M: Middleware>(&mut self, provider: &M) -> Result<U256, anyhow::Error> {
+        let nonce = match self.next_nonce {
+            Some(nonce) => nonce,
+            None => provider.get_transaction_count(self.address(), Some(BlockNumber::Pending.into())).await
+                .map_err(|e| anyhow::anyhow!("Nonce lookup for {} failed: {}", self.label, e))?,
+        };
+        self.next_nonce = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    pub fn record_execution(&mut self, opp_id: &str, amount: U256, outcome: &str, now: i64) {
+        if outcome == "Success" {
+            self.spends.push_back((now, amount));
+        }
+        self.recent.push_back(WalletActivity {
+            at: now,
+            opp_id: opp_id.to_string(),
+            amount: amount.to_string(),
+            outcome: outcome.to_string(),
+        });
+        while self.recent.len() > RECENT_ACTIVITY {
+            self.recent.pop_front();
+        }
+    }
+}
+
+fn load_signer(label: &str, key_env: &str, chain_id: u64) -> Result<LocalWallet, WalletError> {
+    let missing = || WalletError::MissingKey { label: label.to_string(), key_env: key_env.to_string() };
+    let key = std::env::var(key_env).map_err(|_| missing())?;
+    key.trim().parse::<LocalWallet>()
+        .map(|wallet| wallet.with_chain_id(chain_id))
+        .map_err(|_| missing())
+}
+
+/// Coarse balance buckets for the health report, in ETH
+fn balance_bucket(balance: U256) -> &'static str {
+    let milli_eth = balance / U256::exp10(15);
+    if milli_eth < U256::from(10) {
+        "<0.01"
+    } else if milli_eth < U256::from(100) {
+        "0.01-0.1"
+    } else if milli_eth < U256::from(1_000) {
+        "0.1-1"
+    } else if milli_eth < U256::from(10_000) {
+        "1-10"
+    } else {
+        ">=10"
+    }
+}
+
+#[derive(Default)]
+pub struct WalletRegistry {
+    wallets: HashMap<WalletKey, ExecutionWallet>,
+}
+
+impl WalletRegistry {
+    /// Reads `EXECUTION_WALLETS`, a JSON list of `WalletConfig`. A wallet whose key can't be
+    /// loaded is skipped with an error so the others still come up.
+    pub fn from_env() -> Result<Self, WalletError> {
+        let Ok(raw) = std::env::var("EXECUTION_WALLETS") else {
+            return Ok(Self::default());
+        };
+        let configs: Vec<WalletConfig> = serde_json::from_str(&raw)
+            .map_err(|e| WalletError::InvalidConfig(format!("EXECUTION_WALLETS: {}", e)))?;
+
+        let mut registry = Self::default();
+        for config in configs {
+            let key = WalletKey { strategy: config.strategy.clone(), chain_id: config.chain_id };
+            if registry.wallets.contains_key(&key) {
+                return Err(WalletError::InvalidConfig(format!("more than one wallet for {}", key)));
+            }
+            match ExecutionWallet::from_config(&config) {
+                Ok(wallet) => {
+                    println!("🔑 Execution wallet {} ({:?}) for {}", wallet.label, wallet.address(), key);
+                    registry.wallets.insert(key, wallet);
+                }
+                Err(e) => eprintln!("⚠️  {}", e),
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn select(&mut self, strategy: &str, chain_id: u64) -> Result<&mut ExecutionWallet, WalletError> {
+        let key = WalletKey { strategy: strategy.to_string(), chain_id };
+        self.wallets.get_mut(&key).ok_or(WalletError::NotConfigured(key))
+    }
+
+    /// Swaps one wallet's key; only that wallet's nonce tracking restarts
+    pub fn rotate_key(&mut self, strategy: &str, chain_id: u64, key_env: Option<&str>) -> Result<Address, WalletError> {
+        let wallet = self.select(strategy, chain_id)?;
+        let key_env = key_env.map(str::to_string).unwrap_or_else(|| wallet.key_env.clone());
+        wallet.signer = load_signer(&wallet.label, &key_env, chain_id)?;
+        wallet.key_env = key_env;
+        wallet.next_nonce = None;
+        Ok(wallet.address())
+    }
+
+    /// Wallet list for the health report: address, bucketed balance, limits and recent activity
+    pub async fn report<M: Middleware>(&self, provider: &M) -> serde_json::Value {
+        let mut keys: Vec<&WalletKey> = self.wallets.keys().collect();
+        keys.sort();
+        let mut wallets = Vec::with_capacity(keys.len());
+        for key in keys {
+            let wallet = &self.wallets[key];
+            let balance = provider.get_balance(wallet.address(), None).await.ok();
+            wallets.push(serde_json::json!({
+                "strategy": key.strategy,
+                "chain_id": key.chain_id,
+                "label": wallet.label,
+                "address": format!("{:?}", wallet.address()),
+                "balance_bucket": balance.map(balance_bucket),
+                "below_gas_reserve": balance.map(|b| b < wallet.gas_reserve),
+                "spend_limit": wallet.spend_limit.map(|limit| limit.to_string()),
+                "recent_activity": wallet.recent,
+            }));
+        }
+        serde_json::Value::Array(wallets)
+    }
+}
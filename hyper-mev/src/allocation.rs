@@ -0,0 +1,441 @@
+// DETERMINISTIC CAPITAL ALLOCATION
+// Shared verbatim by the Hyperware process and the Artemis bridge (included by path), so an
+// executor's allocation can be replayed bit-for-bit by any verifier. Integer math and a total
+// ordering only: no floats, no hash-map iteration, no dependence on input order beyond the policy.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum AllocationPolicy {
+    // Earliest intent first
+    ReceivedOrder,
+    // Cheapest capital first (lowest fee asked)
+    #[default]
+    FeeAscending,
+    // Largest intent first, so fewer providers share the execution
+    AmountDescending,
+}
+
+// One intent as the executor considered it
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AllocationCandidate {
+    pub intent_hash: String,
+    pub provider: String,
+    pub max_amount: String,
+    pub fee_bps: u16,
+    pub received_at: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AllocationLine {
+    pub intent_hash: String,
+    pub provider: String,
+    pub amount: String,
+    // The candidate's fee bid; executors from before fee bidding declare none
+    #[serde(default)]
+    pub fee_bps: u16,
+}
+
+// What an executor declares on its receipt: the policy, the full input, and its result
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AllocationRecord {
+    pub policy: AllocationPolicy,
+    pub target_amount: String,
+    pub candidates: Vec<AllocationCandidate>,
+    pub allocation: Vec<AllocationLine>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum AllocationMismatch {
+    // The declared input can't be replayed (unparseable amounts, duplicate intents)
+    InvalidInput(String),
+    // Replaying the declared input gives a different allocation
+    Diverged { expected: Vec<AllocationLine>, reported: Vec<AllocationLine> },
+    // An intent the verifier knows was submitted is missing from the declared input
+    OmittedIntent { intent_hash: String, provider: String },
+}
+
+// FNV-1a over the intent's JSON; identifies an intent without pulling in a hashing crate
+pub fn intent_hash(intent_json: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in intent_json.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn parse_amount(amount: &str) -> Result<u128, String> {
+    amount.parse::<u128>().map_err(|_| format!("Invalid amount: {}", amount))
+}
+
+// Candidates in the order the policy funds them; ties always fall back to (intent_hash, provider)
+pub fn order_candidates(policy: AllocationPolicy, candidates: &[AllocationCandidate]) -> Result<Vec<AllocationCandidate>, String> {
+    let mut keyed = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        keyed.push((parse_amount(&candidate.max_amount)?, candidate.clone()));
+    }
+    keyed.sort_by(|(a_amount, a), (b_amount, b)| {
+        let primary = match policy {
+            AllocationPolicy::ReceivedOrder => a.received_at.cmp(&b.received_at),
+            AllocationPolicy::FeeAscending => a.fee_bps.cmp(&b.fee_bps).then(a.received_at.cmp(&b.received_at)),
+            AllocationPolicy::AmountDescending => b_amount.cmp(a_amount).then(a.received_at.cmp(&b.received_at)),
+        };
+        primary.then_with(|| a.intent_hash.cmp(&b.intent_hash)).then_with(|| a.provider.cmp(&b.provider))
+    });
+    Ok(keyed.into_iter().map(|(_, candidate)| candidate).collect())
+}
+
+// Funds `target_amount` from the candidates in policy order; each takes what is still uncovered,
+// up to its max_amount
+pub fn allocate(policy: AllocationPolicy, target_amount: &str, candidates: &[AllocationCandidate]) -> Result<Vec<AllocationLine>, String> {
+    let mut seen = std::collections::BTreeSet::new();
+    for candidate in candidates {
+        if !seen.insert(candidate.intent_hash.as_str()) {
+            return Err(format!("Duplicate intent {}", candidate.intent_hash));
+        }
+    }
+
+    let mut remaining = parse_amount(target_amount)?;
+    let mut allocation = Vec::new();
+    for candidate in order_candidates(policy, candidates)? {
+        if remaining == 0 {
+            break;
+        }
+        let amount = parse_amount(&candidate.max_amount)?.min(remaining);
+        if amount == 0 {
+            continue;
+        }
+        remaining -= amount;
+        allocation.push(AllocationLine {
+            intent_hash: candidate.intent_hash,
+            provider: candidate.provider,
+            amount: amount.to_string(),
+            fee_bps: candidate.fee_bps,
+        });
+    }
+    Ok(allocation)
+}
+
+// Replays a declared allocation; `known` are intents the verifier itself saw submitted
+pub fn verify_allocation(record: &AllocationRecord, known: &[AllocationCandidate]) -> Result<(), AllocationMismatch> {
+    for intent in known {
+        if !record.candidates.iter().any(|c| c.intent_hash == intent.intent_hash && c.provider == intent.provider) {
+            return Err(AllocationMismatch::OmittedIntent {
+                intent_hash: intent.intent_hash.clone(),
+                provider: intent.provider.clone(),
+            });
+        }
+    }
+    let mut expected = allocate(record.policy, &record.target_amount, &record.candidates)
+        .map_err(AllocationMismatch::InvalidInput)?;
+    // Lines without fees come from executors that predate fee bidding
+    if record.allocation.iter().all(|line| line.fee_bps == 0) {
+        expected.iter_mut().for_each(|line| line.fee_bps = 0);
+    }
+    if expected != record.allocation {
+        return Err(AllocationMismatch::Diverged { expected, reported: record.allocation.clone() });
+    }
+    Ok(())
+}
+
+// Who receives what rounding leaves over once provider shares are floored
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum DustSink {
+    // The first allocation line, as splits always did
+    #[default]
+    FirstProvider,
+    Executor,
+    Finder,
+    // A node collecting the pool's dust
+    Treasury(String),
+}
+
+// Rounding rules declared on an opportunity by its finder, so every verifier derives the same
+// split from a receipt: provider shares round down, the remainder goes to `dust_sink`, and a
+// share under `min_payable_share` is deferred to the next settlement rather than paid out alone
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct DistributionScheme {
+    pub dust_sink: DustSink,
+    pub min_payable_share: String,
+}
+
+impl Default for DistributionScheme {
+    fn default() -> Self {
+        Self { dust_sink: DustSink::FirstProvider, min_payable_share: "0".to_string() }
+    }
+}
+
+// One provider's expected cut of an execution's proceeds
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ShareLine {
+    pub provider: String,
+    pub amount_used: String,
+    pub share: String,
+    // Below the scheme's minimum payable share: owed, but carried into the next settlement
+    #[serde(default)]
+    pub deferred: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ProceedsSplit {
+    pub gross: String,
+    pub gas_cost: String,
+    pub finder_fee: String,
+    pub executor_fee: String,
+    pub provider_pool: String,
+    pub shares: Vec<ShareLine>,
+    // Left over from flooring the shares, owed to `dust_sink`; zero when the first line keeps it
+    #[serde(default)]
+    pub dust: String,
+    #[serde(default)]
+    pub dust_sink: DustSink,
+    // Pool the providers' fee bids left unclaimed, owed to the executor
+    #[serde(default)]
+    pub unclaimed: String,
+}
+
+// Floors each line's pro-rata part of `pool`, then keeps fee_bps of it for lines bidding a fee
+// (0 claims the whole part). Returns the shares, the dust, which the FirstProvider sink folds
+// into the first share (leaving no dust), and what the fee bids left unclaimed.
+pub fn distribute_pool(pool: u128, allocation: &[AllocationLine], scheme: &DistributionScheme) -> Result<(Vec<u128>, u128, u128), String> {
+    let mut amounts = Vec::with_capacity(allocation.len());
+    for line in allocation {
+        amounts.push(parse_amount(&line.amount)?);
+    }
+    let total_used = amounts.iter().try_fold(0u128, |acc, amount| acc.checked_add(*amount))
+        .ok_or("Allocation total overflows")?;
+    let mut shares: Vec<u128> = amounts.iter()
+        .map(|amount| if total_used == 0 { 0 } else { mul_div(pool, *amount, total_used) })
+        .collect();
+    let mut dust = pool - shares.iter().sum::<u128>();
+    let mut unclaimed = 0u128;
+    for (share, line) in shares.iter_mut().zip(allocation) {
+        if line.fee_bps > 0 && line.fee_bps < 10_000 {
+            let claimed = mul_div(*share, line.fee_bps as u128, 10_000);
+            unclaimed += *share - claimed;
+            *share = claimed;
+        }
+    }
+    if scheme.dust_sink == DustSink::FirstProvider {
+        if let Some(first) = shares.first_mut() {
+            *first += dust;
+            dust = 0;
+        }
+    }
+    Ok((shares, dust, unclaimed))
+}
+
+// Splits proceeds over an allocation: gas comes off the top, the finder and executor fees are bps
+// of what remains (rounded down), and providers share the rest pro rata to the capital each
+// supplied less what their fee bids leave to the executor, under the declared scheme. Fees,
+// shares, dust and the unclaimed part always sum to the proceeds after gas.
+pub fn split_proceeds(
+    gross: &str,
+    gas_cost: &str,
+    finder_fee_bps: u16,
+    executor_fee_bps: u16,
+    allocation: &[AllocationLine],
+    scheme: &DistributionScheme,
+) -> Result<ProceedsSplit, String> {
+    if finder_fee_bps as u32 + executor_fee_bps as u32 > 10_000 {
+        return Err("Fees exceed the proceeds".to_string());
+    }
+    let min_payable = parse_amount(&scheme.min_payable_share)?;
+    let gas = parse_amount(gas_cost)?;
+    let after_gas = parse_amount(gross)?.saturating_sub(gas);
+    let finder_fee = mul_div(after_gas, finder_fee_bps as u128, 10_000);
+    let executor_fee = mul_div(after_gas, executor_fee_bps as u128, 10_000);
+    let pool = after_gas - finder_fee - executor_fee;
+    let (shares, dust, unclaimed) = distribute_pool(pool, allocation, scheme)?;
+
+    Ok(ProceedsSplit {
+        gross: gross.to_string(),
+        gas_cost: gas.to_string(),
+        finder_fee: finder_fee.to_string(),
+        executor_fee: executor_fee.to_string(),
+        provider_pool: pool.to_string(),
+        shares: allocation.iter().zip(shares)
+            .map(|(line, share)| ShareLine {
+                provider: line.provider.clone(),
+                amount_used: line.amount.clone(),
+                share: share.to_string(),
+                deferred: share > 0 && share < min_payable,
+            })
+            .collect(),
+        dust: dust.to_string(),
+        dust_sink: scheme.dust_sink.clone(),
+        unclaimed: unclaimed.to_string(),
+    })
+}
+
+// Exact floor(a * b / c) through a 256-bit intermediate; the quotient must fit in u128
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    const LOW: u128 = u64::MAX as u128;
+    let (a1, a0, b1, b0) = (a >> 64, a & LOW, b >> 64, b & LOW);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & LOW) + (p10 & LOW);
+    let lo = (p00 & LOW) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+
+    // Binary long division of (hi, lo) by c
+    let (mut quotient, mut remainder) = (0u128, 0u128);
+    for bit in (0..256).rev() {
+        let next = if bit >= 128 { (hi >> (bit - 128)) & 1 } else { (lo >> bit) & 1 };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | next;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            if bit < 128 {
+                quotient |= 1 << bit;
+            }
+        }
+    }
+    quotient
+}
+
+// Compiled into both crates through the shared path, so the app and the bridge each check the
+// same recorded fixtures
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Recorded from an executor: three providers, two bidding the same fee
+    const RECORDED: &str = r#"{
+        "policy": "FeeAscending",
+        "target_amount": "1000",
+        "candidates": [
+            {"intent_hash": "c3", "provider": "carol.os", "max_amount": "600", "fee_bps": 9000, "received_at": 30},
+            {"intent_hash": "a1", "provider": "alice.os", "max_amount": "500", "fee_bps": 9500, "received_at": 10},
+            {"intent_hash": "b2", "provider": "bob.os", "max_amount": "700", "fee_bps": 9000, "received_at": 20}
+        ],
+        "allocation": [
+            {"intent_hash": "b2", "provider": "bob.os", "amount": "700", "fee_bps": 9000},
+            {"intent_hash": "c3", "provider": "carol.os", "amount": "300", "fee_bps": 9000}
+        ]
+    }"#;
+
+    fn recorded() -> AllocationRecord {
+        serde_json::from_str(RECORDED).unwrap()
+    }
+
+    #[test]
+    fn recorded_allocation_replays_exactly() {
+        let record = recorded();
+        assert_eq!(allocate(record.policy, &record.target_amount, &record.candidates).unwrap(), record.allocation);
+        assert_eq!(verify_allocation(&record, &record.candidates), Ok(()));
+    }
+
+    #[test]
+    fn allocation_does_not_depend_on_candidate_order() {
+        let record = recorded();
+        for policy in [AllocationPolicy::ReceivedOrder, AllocationPolicy::FeeAscending, AllocationPolicy::AmountDescending] {
+            let forward = allocate(policy, "1000", &record.candidates).unwrap();
+            let mut reversed = record.candidates.clone();
+            reversed.reverse();
+            assert_eq!(allocate(policy, "1000", &reversed).unwrap(), forward);
+        }
+        let by_amount = allocate(AllocationPolicy::AmountDescending, "1000", &record.candidates).unwrap();
+        assert_eq!(by_amount.iter().map(|line| line.provider.as_str()).collect::<Vec<_>>(), vec!["bob.os", "carol.os"]);
+        let by_arrival = allocate(AllocationPolicy::ReceivedOrder, "1000", &record.candidates).unwrap();
+        assert_eq!(by_arrival.iter().map(|line| line.amount.as_str()).collect::<Vec<_>>(), vec!["500", "500"]);
+    }
+
+    #[test]
+    fn favouring_a_costlier_provider_is_flagged() {
+        let mut record = recorded();
+        record.allocation = allocate(AllocationPolicy::ReceivedOrder, "1000", &record.candidates).unwrap();
+        assert!(matches!(verify_allocation(&record, &[]), Err(AllocationMismatch::Diverged { .. })));
+    }
+
+    #[test]
+    fn dropping_an_intent_we_sent_is_flagged() {
+        let mut record = recorded();
+        let ours = record.candidates.remove(1);
+        assert_eq!(
+            verify_allocation(&record, &[ours]),
+            Err(AllocationMismatch::OmittedIntent { intent_hash: "a1".to_string(), provider: "alice.os".to_string() })
+        );
+    }
+
+    #[test]
+    fn duplicate_or_unreadable_input_is_refused() {
+        let mut record = recorded();
+        record.candidates.push(record.candidates[0].clone());
+        assert!(matches!(verify_allocation(&record, &[]), Err(AllocationMismatch::InvalidInput(_))));
+        let mut record = recorded();
+        record.candidates[0].max_amount = "lots".to_string();
+        assert!(matches!(verify_allocation(&record, &[]), Err(AllocationMismatch::InvalidInput(_))));
+    }
+
+    fn lines(amounts: &[(&str, &str, u16)]) -> Vec<AllocationLine> {
+        amounts.iter()
+            .map(|(provider, amount, fee_bps)| AllocationLine {
+                intent_hash: format!("{}-intent", provider),
+                provider: provider.to_string(),
+                amount: amount.to_string(),
+                fee_bps: *fee_bps,
+            })
+            .collect()
+    }
+
+    fn scheme(dust_sink: DustSink, min_payable_share: &str) -> DistributionScheme {
+        DistributionScheme { dust_sink, min_payable_share: min_payable_share.to_string() }
+    }
+
+    #[test]
+    fn split_serializes_identically_wherever_it_is_computed() {
+        let split = split_proceeds("1000001", "1", 100, 200, &lines(&[("a", "1", 0), ("b", "1", 0), ("c", "1", 0)]),
+            &scheme(DustSink::Executor, "0")).unwrap();
+        // Recorded: any node replaying this receipt must write exactly these bytes to its ledger
+        assert_eq!(serde_json::to_string(&split).unwrap(), concat!(
+            r#"{"gross":"1000001","gas_cost":"1","finder_fee":"10000","executor_fee":"20000","provider_pool":"970000","#,
+            r#""shares":[{"provider":"a","amount_used":"1","share":"323333","deferred":false},"#,
+            r#"{"provider":"b","amount_used":"1","share":"323333","deferred":false},"#,
+            r#"{"provider":"c","amount_used":"1","share":"323333","deferred":false}],"#,
+            r#""dust":"1","dust_sink":"Executor","unclaimed":"0"}"#,
+        ));
+    }
+
+    #[test]
+    fn shares_floor_and_the_dust_goes_to_the_sink() {
+        let allocation = lines(&[("a", "1", 0), ("b", "1", 0), ("c", "1", 0)]);
+        let first = split_proceeds("100", "0", 0, 0, &allocation, &scheme(DustSink::FirstProvider, "0")).unwrap();
+        assert_eq!(first.shares.iter().map(|line| line.share.as_str()).collect::<Vec<_>>(), vec!["34", "33", "33"]);
+        assert_eq!(first.dust, "0");
+        let treasury = split_proceeds("100", "0", 0, 0, &allocation, &scheme(DustSink::Treasury("pool.os".to_string()), "0")).unwrap();
+        assert_eq!(treasury.shares.iter().map(|line| line.share.as_str()).collect::<Vec<_>>(), vec!["33", "33", "33"]);
+        assert_eq!(treasury.dust, "1");
+    }
+
+    #[test]
+    fn fees_shares_dust_and_unclaimed_sum_to_the_proceeds_after_gas() {
+        let allocation = lines(&[("a", "700", 5000), ("b", "300", 0), ("c", "3", 9999)]);
+        for gross in ["0", "7", "999999", "123456789012345678901234567"] {
+            let split = split_proceeds(gross, "5", 150, 275, &allocation, &scheme(DustSink::Finder, "0")).unwrap();
+            let parts = [&split.finder_fee, &split.executor_fee, &split.dust, &split.unclaimed].into_iter()
+                .chain(split.shares.iter().map(|line| &line.share))
+                .map(|amount| amount.parse::<u128>().unwrap())
+                .sum::<u128>();
+            assert_eq!(parts, gross.parse::<u128>().unwrap().saturating_sub(5), "gross {}", gross);
+        }
+    }
+
+    #[test]
+    fn shares_under_the_minimum_are_deferred() {
+        let allocation = lines(&[("a", "1", 0), ("b", "1", 0), ("c", "1", 0)]);
+        let split = split_proceeds("100", "0", 0, 0, &allocation, &scheme(DustSink::FirstProvider, "34")).unwrap();
+        assert_eq!(split.shares.iter().map(|line| line.deferred).collect::<Vec<_>>(), vec![false, true, true]);
+        // Nothing owed is nothing to defer
+        let split = split_proceeds("0", "0", 0, 0, &allocation, &scheme(DustSink::FirstProvider, "34")).unwrap();
+        assert!(split.shares.iter().all(|line| !line.deferred));
+    }
+
+    #[test]
+    fn mul_div_is_exact_past_u128() {
+        assert_eq!(mul_div(10, 3, 4), 7);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(mul_div(u128::MAX, 2, 4), u128::MAX / 2);
+    }
+}
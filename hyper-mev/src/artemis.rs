@@ -0,0 +1,625 @@
+// BRIDGE VALIDATION BROKER
+// Every feature that wants the bridge to re-check a victim goes through here, so the single
+// Artemis channel sees at most one ValidateOpportunity per (victim, block). Duplicate requests
+// wait on the one in flight, answers are cached until the next block, and the number outstanding
+// is capped with the most urgent requests sent first. Pure bookkeeping: sending is up to the caller.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+
+// A request the bridge hasn't answered this many blocks on is given up
+const IN_FLIGHT_TIMEOUT_BLOCKS: u64 = 2;
+
+// Declared in urgency order; the derived Ord sends PreDispatch first
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationPriority {
+    // An executor about to hand intents to the bridge
+    PreDispatch,
+    ReceiptCheck,
+    Hypothetical,
+    // Background re-check of opportunities we hold intents on
+    Repricing,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ValidationKey {
+    pub victim: String,
+    pub block: u64,
+}
+
+impl ValidationKey {
+    pub fn new(victim: &str, block: u64) -> Self {
+        Self { victim: victim.to_lowercase(), block }
+    }
+}
+
+// The bridge's answer to ValidateOpportunity
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ValidationResult {
+    pub victim: String,
+    pub block: u64,
+    pub health_factor: String,
+    pub liquidatable: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// Someone waiting on a validation, resumed when the answer arrives
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ValidationWaiter {
+    pub opp_id: String,
+    pub priority: ValidationPriority,
+}
+
+#[derive(Debug)]
+pub enum Admission {
+    // Answered for this block already; nothing to wait for
+    Cached(ValidationResult),
+    // First request for the key: send it to the bridge now
+    Send(ValidationKey),
+    // Joined a request already in flight or queued
+    Coalesced,
+    // Over the outstanding cap; sent once capacity frees up
+    Queued,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct BrokerStats {
+    pub requests: u64,
+    pub cache_hits: u64,
+    pub coalesced: u64,
+    pub sent: u64,
+    pub abandoned: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ValidationBroker {
+    in_flight: BTreeMap<ValidationKey, Vec<ValidationWaiter>>,
+    queued: BTreeMap<ValidationKey, Vec<ValidationWaiter>>,
+    cache: BTreeMap<ValidationKey, ValidationResult>,
+    pub stats: BrokerStats,
+}
+
+impl ValidationBroker {
+    // Registers `waiter`'s interest in `key`; at most `max_outstanding` keys are with the bridge at once
+    pub fn request(&mut self, key: ValidationKey, waiter: ValidationWaiter, max_outstanding: usize) -> Admission {
+        self.stats.requests += 1;
+        if let Some(result) = self.cache.get(&key) {
+            self.stats.cache_hits += 1;
+            return Admission::Cached(result.clone());
+        }
+        for pending in [&mut self.in_flight, &mut self.queued] {
+            if let Some(waiters) = pending.get_mut(&key) {
+                if !waiters.contains(&waiter) {
+                    waiters.push(waiter);
+                }
+                self.stats.coalesced += 1;
+                return Admission::Coalesced;
+            }
+        }
+        if self.in_flight.len() < max_outstanding.max(1) {
+            self.in_flight.insert(key.clone(), vec![waiter]);
+            self.stats.sent += 1;
+            Admission::Send(key)
+        } else {
+            self.queued.insert(key, vec![waiter]);
+            Admission::Queued
+        }
+    }
+
+    // Records the bridge's answer. Returns who was waiting on it and the queued keys that now fit
+    // under the cap, which the caller must send.
+    pub fn complete(&mut self, result: ValidationResult, max_outstanding: usize) -> (Vec<ValidationWaiter>, Vec<ValidationKey>) {
+        let key = ValidationKey::new(&result.victim, result.block);
+        let waiters = self.in_flight.remove(&key).unwrap_or_default();
+        // Failures are cached too, so a struggling bridge isn't asked again within the block
+        self.cache.insert(key, result);
+        (waiters, self.promote(max_outstanding))
+    }
+
+    // Moves the most urgent queued keys in flight while there is room
+    fn promote(&mut self, max_outstanding: usize) -> Vec<ValidationKey> {
+        let mut promoted = Vec::new();
+        while self.in_flight.len() < max_outstanding.max(1) {
+            let Some(key) = self.queued.iter()
+                .min_by_key(|(key, waiters)| (waiters.iter().map(|w| w.priority).min(), key.block, key.victim.clone()))
+                .map(|(key, _)| key.clone()) else {
+                break;
+            };
+            let waiters = self.queued.remove(&key).unwrap_or_default();
+            self.in_flight.insert(key.clone(), waiters);
+            self.stats.sent += 1;
+            promoted.push(key);
+        }
+        promoted
+    }
+
+    // A new block invalidates cached answers and queued requests for older blocks, and gives up on
+    // requests the bridge has sat on too long. Returns the keys to send now and the waiters whose
+    // requests were dropped, so they can ask again at the new block.
+    pub fn on_block(&mut self, block: u64, max_outstanding: usize) -> (Vec<ValidationKey>, Vec<ValidationWaiter>) {
+        self.cache.retain(|key, _| key.block >= block);
+        let mut dropped = Vec::new();
+        let stale: Vec<ValidationKey> = self.queued.keys()
+            .filter(|key| key.block < block)
+            .chain(self.in_flight.keys().filter(|key| key.block + IN_FLIGHT_TIMEOUT_BLOCKS < block))
+            .cloned()
+            .collect();
+        for key in stale {
+            let waiters = self.queued.remove(&key).or_else(|| self.in_flight.remove(&key)).unwrap_or_default();
+            dropped.extend(waiters);
+            self.stats.abandoned += 1;
+        }
+        (self.promote(max_outstanding), dropped)
+    }
+
+    // The bridge went away: nothing in flight will be answered
+    pub fn abandon_in_flight(&mut self) -> Vec<ValidationWaiter> {
+        self.stats.abandoned += self.in_flight.len() as u64;
+        std::mem::take(&mut self.in_flight).into_values().flatten().collect()
+    }
+
+    pub fn metrics(&self) -> serde_json::Value {
+        let hit_rate_bps = if self.stats.requests == 0 { 0 } else { self.stats.cache_hits * 10_000 / self.stats.requests };
+        serde_json::json!({
+            "in_flight": self.in_flight.len(),
+            "queue_depth": self.queued.len(),
+            "cached": self.cache.len(),
+            "cache_hit_rate_bps": hit_rate_bps,
+            "stats": self.stats,
+        })
+    }
+}
+
+// BRIDGE CONNECTIONS
+// Every WebSocket channel a bridge has opened, so a reconnect that beats the old channel's Close
+// frame, or a second bridge, doesn't leave us pushing to a dead channel. Executions and
+// validations go to one primary channel (the newest handshake) so nothing is executed twice;
+// config pushes go to all of them.
+
+// A channel silent this long is dropped once another channel is live. send_ws_push reports no
+// delivery failure, so silence is our only sign of a dead channel; bridges report health every
+// five minutes by default.
+const CHANNEL_STALE_SECS: u64 = 900;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ArtemisConnection {
+    pub connected_at: u64,
+    pub last_message_at: u64,
+    pub bridge_id: Option<String>,
+    pub bridge_version: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ArtemisChannels {
+    channels: BTreeMap<u32, ArtemisConnection>,
+    primary: Option<u32>,
+}
+
+impl ArtemisChannels {
+    pub fn is_connected(&self) -> bool {
+        self.primary.is_some()
+    }
+
+    pub fn contains(&self, channel_id: u32) -> bool {
+        self.channels.contains_key(&channel_id)
+    }
+
+    pub fn primary(&self) -> Option<u32> {
+        self.primary
+    }
+
+    pub fn channel_ids(&self) -> Vec<u32> {
+        self.channels.keys().copied().collect()
+    }
+
+    pub fn bridge_id(&self) -> Option<String> {
+        self.primary_connection().and_then(|connection| connection.bridge_id.clone())
+    }
+
+    pub fn bridge_version(&self) -> Option<String> {
+        self.primary_connection().and_then(|connection| connection.bridge_version.clone())
+    }
+
+    pub fn connection(&self, channel_id: u32) -> Option<&ArtemisConnection> {
+        self.channels.get(&channel_id)
+    }
+
+    fn primary_connection(&self) -> Option<&ArtemisConnection> {
+        self.primary.and_then(|channel_id| self.connection(channel_id))
+    }
+
+    // Registers a handshake and makes the channel primary. Earlier channels from the same bridge
+    // are its previous connection and are dropped; their ids are returned.
+    pub fn open(&mut self, channel_id: u32, connection: ArtemisConnection) -> Vec<u32> {
+        let superseded: Vec<u32> = match &connection.bridge_id {
+            Some(bridge_id) => self.channels.iter()
+                .filter(|(_, existing)| existing.bridge_id.as_ref() == Some(bridge_id))
+                .map(|(id, _)| *id)
+                .collect(),
+            None => Vec::new(),
+        };
+        for id in &superseded {
+            self.channels.remove(id);
+        }
+        self.channels.insert(channel_id, connection);
+        self.primary = Some(channel_id);
+        superseded
+    }
+
+    pub fn touch(&mut self, channel_id: u32, now: u64) {
+        if let Some(connection) = self.channels.get_mut(&channel_id) {
+            connection.last_message_at = now;
+        }
+    }
+
+    // Forgets a closed channel. Returns true when it was the primary, in which case the most
+    // recently active remaining channel, if any, takes over.
+    pub fn close(&mut self, channel_id: u32) -> bool {
+        if self.channels.remove(&channel_id).is_none() {
+            return false;
+        }
+        if self.primary != Some(channel_id) {
+            return false;
+        }
+        self.primary = self.channels.iter()
+            .max_by_key(|(id, connection)| (connection.last_message_at, **id))
+            .map(|(id, _)| *id);
+        true
+    }
+
+    // Drops channels silent past CHANNEL_STALE_SECS while a fresher one exists. Returns the
+    // dropped ids and whether the primary was among them.
+    pub fn prune_stale(&mut self, now: u64) -> (Vec<u32>, bool) {
+        let freshest = self.channels.values().map(|connection| connection.last_message_at).max().unwrap_or(0);
+        let stale: Vec<u32> = self.channels.iter()
+            .filter(|(_, connection)| now.saturating_sub(connection.last_message_at) > CHANNEL_STALE_SECS
+                && connection.last_message_at < freshest)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut primary_dropped = false;
+        for id in &stale {
+            primary_dropped |= self.close(*id);
+        }
+        (stale, primary_dropped)
+    }
+
+    pub fn metrics(&self) -> serde_json::Value {
+        let channels: Vec<serde_json::Value> = self.channels.iter()
+            .map(|(id, connection)| serde_json::json!({
+                "channel_id": id,
+                "primary": self.primary == Some(*id),
+                "connection": connection,
+            }))
+            .collect();
+        serde_json::json!(channels)
+    }
+}
+
+// RECONNECTION SYNC
+// When a bridge (re)connects we send what we consider live and it answers with its own view: the
+// executions it holds for those opportunities, the finds it still tracks, and whatever it couldn't
+// deliver while the connection was down. Only the answer to the sync in flight counts, so a
+// connection that flaps mid-sync just starts over. Pure bookkeeping: reconciling is up to the caller.
+
+// Most opportunities listed in one sync request
+pub const MAX_SYNC_ENTRIES: usize = 500;
+
+// A sync the bridge hasn't answered by then is dropped; older bridges don't answer at all
+pub const SYNC_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum LiveState {
+    // Our own find, still gathering intents
+    Collecting,
+    // Sent to the bridge, no word back yet
+    Dispatched,
+    // The bridge has acknowledged it and owes us a receipt
+    PendingReceipt,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SyncInFlight {
+    pub sync_id: u64,
+    pub channel_id: u32,
+    pub started_at: u64,
+    pub live: Vec<(String, LiveState)>,
+    pub truncated: usize,
+}
+
+// What one sync reconciled, kept for get_health and journaled
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SyncSummary {
+    pub sync_id: u64,
+    pub completed_at: u64,
+    pub listed: usize,
+    // Live opportunities left off the request past MAX_SYNC_ENTRIES
+    pub truncated: usize,
+    pub redispatched: Vec<String>,
+    pub receipts_delivered: Vec<String>,
+    pub opportunities_delivered: usize,
+    pub retired: Vec<String>,
+    // Executions the bridge holds but hasn't finished, including ones it lost in a restart
+    pub unresolved: Vec<String>,
+    // Messages the bridge had to drop from its outage buffer
+    pub bridge_dropped: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct BridgeSync {
+    next_id: u64,
+    in_flight: Option<SyncInFlight>,
+    pub last: Option<SyncSummary>,
+}
+
+impl BridgeSync {
+    // Starts a sync on `channel_id`, replacing any still in flight; returns its id
+    pub fn begin(&mut self, channel_id: u32, live: Vec<(String, LiveState)>, truncated: usize, now: u64) -> u64 {
+        self.next_id += 1;
+        self.in_flight = Some(SyncInFlight { sync_id: self.next_id, channel_id, started_at: now, live, truncated });
+        self.next_id
+    }
+
+    // Takes the sync a response answers; None for a stale or unknown one
+    pub fn finish(&mut self, sync_id: u64, channel_id: u32) -> Option<SyncInFlight> {
+        let matches = self.in_flight.as_ref()
+            .is_some_and(|sync| sync.sync_id == sync_id && sync.channel_id == channel_id);
+        if matches { self.in_flight.take() } else { None }
+    }
+
+    // Drops the sync in flight once it is past SYNC_TIMEOUT_SECS, returning it
+    pub fn expire(&mut self, now: u64) -> Option<SyncInFlight> {
+        let expired = self.in_flight.as_ref()
+            .is_some_and(|sync| now.saturating_sub(sync.started_at) > SYNC_TIMEOUT_SECS);
+        if expired { self.in_flight.take() } else { None }
+    }
+
+    pub fn metrics(&self) -> serde_json::Value {
+        serde_json::json!({
+            "in_flight": self.in_flight.as_ref().map(|sync| serde_json::json!({
+                "sync_id": sync.sync_id,
+                "channel_id": sync.channel_id,
+                "started_at": sync.started_at,
+                "listed": sync.live.len(),
+            })),
+            "last": self.last,
+        })
+    }
+}
+
+// Frames from the bridge wait here and are handled most urgent first, so a receipt that frees
+// capital or a closed position that withdraws intents isn't stuck behind a burst of opportunity
+// frames. Each turn handles frames until its time budget is spent and leaves the rest for the
+// next. Order within a class is kept, and a frame waiting past INBOUND_AGING_MS is handled ahead
+// of every class so opportunities are never starved. Pure bookkeeping: handling is up to the caller.
+
+// Time one turn may spend handling frames; at least one frame is always handled
+pub const INBOUND_TURN_BUDGET_MS: u128 = 25;
+// A frame waiting this long goes ahead of fresher, more urgent ones
+pub const INBOUND_AGING_MS: u128 = 2_000;
+// Frames held at most; past this the oldest of the lowest class are dropped
+pub const INBOUND_QUEUE_CAPACITY: usize = 1_000;
+
+// Declared in urgency order; the derived Ord handles Settlement first
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InboundClass {
+    // Receipts, recovered executions and closed positions: they release capital or intents
+    Settlement,
+    // Chain head, validation answers, sync, health
+    Chain,
+    Opportunity,
+}
+
+impl InboundClass {
+    pub fn for_type(message_type: &str) -> Self {
+        match message_type {
+            "ExecutionReceipt" | "ExecutionStatusReport" | "PositionClosed" => InboundClass::Settlement,
+            "OpportunityBroadcast" | "OpportunityUpdate" | "PositionWatched" => InboundClass::Opportunity,
+            _ => InboundClass::Chain,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InboundFrame {
+    pub channel_id: u32,
+    pub text: String,
+    pub class: InboundClass,
+    pub seq: u64,
+    pub enqueued: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct InboundQueue {
+    frames: BTreeMap<InboundClass, VecDeque<InboundFrame>>,
+    next_seq: u64,
+    pub handled: u64,
+    pub aged: u64,
+    pub dropped: u64,
+    // Turns that ended with frames left over
+    pub carried_over: u64,
+    // A continuation turn has been requested and not yet run
+    pub continuation_pending: bool,
+}
+
+impl InboundQueue {
+    pub fn push(&mut self, channel_id: u32, text: String) {
+        let message_type = serde_json::from_str::<serde_json::Value>(&text).ok()
+            .and_then(|message| message["type"].as_str().map(str::to_string))
+            .unwrap_or_default();
+        let class = InboundClass::for_type(&message_type);
+        self.next_seq += 1;
+        let frame = InboundFrame { channel_id, text, class, seq: self.next_seq, enqueued: Instant::now() };
+        self.frames.entry(class).or_default().push_back(frame);
+        if self.len() > INBOUND_QUEUE_CAPACITY {
+            if let Some(lowest) = self.frames.values_mut().rev().find(|frames| !frames.is_empty()) {
+                lowest.pop_front();
+                self.dropped += 1;
+            }
+        }
+    }
+
+    // The next frame to handle: the oldest frame if it has aged out, otherwise the head of the
+    // most urgent non-empty class
+    pub fn pop(&mut self) -> Option<InboundFrame> {
+        self.pop_at(Instant::now())
+    }
+
+    fn pop_at(&mut self, now: Instant) -> Option<InboundFrame> {
+        let oldest = self.frames.iter()
+            .filter_map(|(class, frames)| frames.front().map(|frame| (*class, frame.seq, frame.enqueued)))
+            .min_by_key(|(_, seq, _)| *seq)?;
+        let class = if now.saturating_duration_since(oldest.2).as_millis() >= INBOUND_AGING_MS {
+            let urgent = self.frames.iter().find(|(_, frames)| !frames.is_empty()).map(|(class, _)| *class);
+            if urgent != Some(oldest.0) {
+                self.aged += 1;
+            }
+            oldest.0
+        } else {
+            *self.frames.iter().find(|(_, frames)| !frames.is_empty())?.0
+        };
+        let frame = self.frames.get_mut(&class)?.pop_front();
+        if frame.is_some() {
+            self.handled += 1;
+        }
+        frame
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.values().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> serde_json::Value {
+        let depths: BTreeMap<String, usize> = self.frames.iter()
+            .map(|(class, frames)| (format!("{:?}", class), frames.len()))
+            .collect();
+        serde_json::json!({
+            "depths": depths,
+            "handled": self.handled,
+            "aged": self.aged,
+            "dropped": self.dropped,
+            "carried_over": self.carried_over,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waiter(opp_id: &str, priority: ValidationPriority) -> ValidationWaiter {
+        ValidationWaiter { opp_id: opp_id.to_string(), priority }
+    }
+
+    fn answer(victim: &str, block: u64) -> ValidationResult {
+        ValidationResult { victim: victim.to_string(), block, health_factor: "1".to_string(), liquidatable: true, error: None }
+    }
+
+    #[test]
+    fn ten_consumers_of_one_victim_make_one_round_trip() {
+        let mut broker = ValidationBroker::default();
+        let admissions: Vec<Admission> = (0..10)
+            .map(|i| broker.request(ValidationKey::new("0xVictim", 7), waiter(&format!("opp-{}", i), ValidationPriority::Repricing), 4))
+            .collect();
+        assert_eq!(admissions.iter().filter(|admission| matches!(admission, Admission::Send(_))).count(), 1);
+        assert_eq!(admissions.iter().filter(|admission| matches!(admission, Admission::Coalesced)).count(), 9);
+        assert_eq!(broker.stats.sent, 1);
+
+        // Every consumer hears the one answer, and asking again this block hits the cache
+        let (waiters, promoted) = broker.complete(answer("0xvictim", 7), 4);
+        assert_eq!(waiters.len(), 10);
+        assert!(promoted.is_empty());
+        assert!(matches!(broker.request(ValidationKey::new("0xVICTIM", 7), waiter("opp-0", ValidationPriority::Repricing), 4), Admission::Cached(_)));
+        assert_eq!(broker.stats.sent, 1);
+    }
+
+    #[test]
+    fn cached_answers_last_until_the_next_block() {
+        let mut broker = ValidationBroker::default();
+        broker.request(ValidationKey::new("0xa", 7), waiter("opp", ValidationPriority::Repricing), 4);
+        broker.complete(answer("0xa", 7), 4);
+        broker.on_block(8, 4);
+        assert!(matches!(broker.request(ValidationKey::new("0xa", 8), waiter("opp", ValidationPriority::Repricing), 4), Admission::Send(_)));
+    }
+
+    #[test]
+    fn queued_requests_go_out_most_urgent_first() {
+        let mut broker = ValidationBroker::default();
+        assert!(matches!(broker.request(ValidationKey::new("0xa", 7), waiter("a", ValidationPriority::Repricing), 1), Admission::Send(_)));
+        assert!(matches!(broker.request(ValidationKey::new("0xb", 7), waiter("b", ValidationPriority::Repricing), 1), Admission::Queued));
+        assert!(matches!(broker.request(ValidationKey::new("0xc", 7), waiter("c", ValidationPriority::PreDispatch), 1), Admission::Queued));
+        let (_, promoted) = broker.complete(answer("0xa", 7), 1);
+        assert_eq!(promoted, vec![ValidationKey::new("0xc", 7)]);
+        let (_, promoted) = broker.complete(answer("0xc", 7), 1);
+        assert_eq!(promoted, vec![ValidationKey::new("0xb", 7)]);
+    }
+
+    #[test]
+    fn new_block_drops_stale_queued_and_overdue_requests() {
+        let mut broker = ValidationBroker::default();
+        broker.request(ValidationKey::new("0xa", 7), waiter("a", ValidationPriority::Repricing), 1);
+        broker.request(ValidationKey::new("0xb", 7), waiter("b", ValidationPriority::Repricing), 1);
+        // The queued request was for block 7 and is dropped; the one in flight gets two blocks
+        let (promoted, dropped) = broker.on_block(8, 1);
+        assert!(promoted.is_empty());
+        assert_eq!(dropped, vec![waiter("b", ValidationPriority::Repricing)]);
+        let (_, dropped) = broker.on_block(10, 1);
+        assert_eq!(dropped, vec![waiter("a", ValidationPriority::Repricing)]);
+        assert_eq!(broker.stats.abandoned, 2);
+    }
+
+    fn frame(message_type: &str, n: usize) -> String {
+        serde_json::json!({ "type": message_type, "n": n }).to_string()
+    }
+
+    fn popped(queue: &mut InboundQueue, now: Instant) -> Vec<String> {
+        std::iter::from_fn(|| queue.pop_at(now)).map(|frame| frame.text).collect()
+    }
+
+    #[test]
+    fn receipt_after_ten_opportunities_is_handled_first() {
+        let mut queue = InboundQueue::default();
+        for n in 0..10 {
+            queue.push(1, frame("OpportunityBroadcast", n));
+        }
+        queue.push(1, frame("ExecutionReceipt", 10));
+        queue.push(1, frame("BlockUpdate", 11));
+        let handled = popped(&mut queue, Instant::now());
+        assert_eq!(handled[0], frame("ExecutionReceipt", 10));
+        assert_eq!(handled[1], frame("BlockUpdate", 11));
+        // Opportunities keep their arrival order
+        let expected: Vec<String> = (0..10).map(|n| frame("OpportunityBroadcast", n)).collect();
+        assert_eq!(handled[2..], expected[..]);
+        assert_eq!(queue.handled, 12);
+    }
+
+    #[test]
+    fn frame_waiting_past_the_aging_limit_goes_first() {
+        let mut queue = InboundQueue::default();
+        queue.push(1, frame("OpportunityBroadcast", 0));
+        queue.push(1, frame("ExecutionReceipt", 1));
+        let later = Instant::now() + std::time::Duration::from_millis(INBOUND_AGING_MS as u64);
+        assert_eq!(queue.pop_at(later).unwrap().text, frame("OpportunityBroadcast", 0));
+        assert_eq!(queue.aged, 1);
+        assert_eq!(queue.pop_at(later).unwrap().text, frame("ExecutionReceipt", 1));
+    }
+
+    #[test]
+    fn full_queue_drops_the_oldest_of_the_lowest_class() {
+        let mut queue = InboundQueue::default();
+        queue.push(1, frame("ExecutionReceipt", 0));
+        for n in 1..=INBOUND_QUEUE_CAPACITY {
+            queue.push(1, frame("OpportunityBroadcast", n));
+        }
+        assert_eq!(queue.len(), INBOUND_QUEUE_CAPACITY);
+        assert_eq!(queue.dropped, 1);
+        let handled = popped(&mut queue, Instant::now());
+        assert_eq!(handled[0], frame("ExecutionReceipt", 0));
+        assert_eq!(handled[1], frame("OpportunityBroadcast", 2));
+    }
+}
@@ -0,0 +1,124 @@
+// PROTOCOL EVENT LOG
+// A bounded, always-on log of the messages this node exchanges: every peer message in, every
+// send out, every Artemis frame both ways. Unlike a traffic recording it needs no start, keeps
+// only the newest `capacity` entries and cuts payloads to `payload_max_bytes`, so it can stay in
+// state for the life of a demo. Inbound peer messages are judged at the node's gates: access and
+// rate limits reject, an unparseable body is an error, and handlers may still reject what passed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::recording::Boundary;
+
+// Ceilings on what the config may ask for
+pub const MAX_EVENT_LOG_CAPACITY: usize = 20_000;
+pub const MAX_EVENT_PAYLOAD_BYTES: usize = 16 * 1024;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct EventLogConfig {
+    // Entries kept; the oldest go first
+    pub capacity: usize,
+    // Longest payload kept per entry, in bytes (0 = payloads not kept)
+    pub payload_max_bytes: usize,
+}
+
+impl Default for EventLogConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1000,
+            payload_max_bytes: 512,
+        }
+    }
+}
+
+impl EventLogConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.capacity == 0 || self.capacity > MAX_EVENT_LOG_CAPACITY {
+            return Err(format!("Event log capacity must be between 1 and {}", MAX_EVENT_LOG_CAPACITY));
+        }
+        if self.payload_max_bytes > MAX_EVENT_PAYLOAD_BYTES {
+            return Err(format!("Event payloads are kept to at most {} bytes", MAX_EVENT_PAYLOAD_BYTES));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum EventOutcome {
+    Accepted,
+    Rejected,
+    Error,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct EventLogEntry {
+    pub seq: u64,
+    pub at: u64,
+    pub direction: Boundary,
+    // Peer node or Artemis channel on the other side
+    pub counterparty: Option<String>,
+    // Remote handler or Artemis message type
+    pub message_type: String,
+    pub opp_id: Option<String>,
+    pub outcome: EventOutcome,
+    pub reason: Option<String>,
+    pub payload: String,
+    // Full payload size when it was cut to payload_max_bytes
+    #[serde(default)]
+    pub payload_bytes: Option<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct EventLog {
+    pub entries: VecDeque<EventLogEntry>,
+    pub next_seq: u64,
+}
+
+impl EventLog {
+    // Appends an entry (its seq is assigned here) and drops the oldest past capacity
+    pub fn push(&mut self, mut entry: EventLogEntry, capacity: usize) -> u64 {
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+        self.entries.push_back(entry);
+        while self.entries.len() > capacity.max(1) {
+            self.entries.pop_front();
+        }
+        self.next_seq - 1
+    }
+
+    // Later verdict on an entry still held, e.g. a handler refusing what passed the gates
+    pub fn set_outcome(&mut self, seq: u64, outcome: EventOutcome, reason: Option<String>) {
+        if let Some(entry) = self.entries.iter_mut().rev().find(|entry| entry.seq == seq) {
+            entry.outcome = outcome;
+            entry.reason = reason;
+        }
+    }
+}
+
+// The payload cut to at most `max` bytes on a char boundary, and its full size when cut
+pub fn truncate_payload(text: &str, max: usize) -> (String, Option<usize>) {
+    if text.len() <= max {
+        return (text.to_string(), None);
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), Some(text.len()))
+}
+
+// The opportunity a message is about: a top-level opp_id, else one on an embedded object or
+// on JSON carried as a string (opportunities, intents and receipts travel that way)
+pub fn message_opp_id(message: &serde_json::Value) -> Option<String> {
+    if let Some(opp_id) = message["opp_id"].as_str() {
+        return Some(opp_id.to_string());
+    }
+    message.as_object()?.values().find_map(|value| match value {
+        serde_json::Value::Object(_) => value["opp_id"].as_str().map(str::to_string),
+        serde_json::Value::String(text) => serde_json::from_str::<serde_json::Value>(text).ok()
+            .and_then(|embedded| embedded["opp_id"].as_str().map(str::to_string)),
+        _ => None,
+    })
+}
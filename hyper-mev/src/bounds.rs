@@ -0,0 +1,127 @@
+// COLLECTION CAPS
+// Opportunities, the intents submitted on them and execution receipts all live in state, which is
+// saved whole whenever it changes, so each is held to a cap. Past a cap, maintenance evicts the
+// least recently touched entries, finished ones (settled, failed or expired opportunities, receipts
+// with nothing left owed) before live ones. Nothing tied to capital we committed is evicted, so a
+// cap can stay exceeded while that capital is out. Peers are already held to max_peers.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Ceiling on what the config may ask for, per collection
+pub const MAX_COLLECTION_CAP: usize = 100_000;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct CollectionCaps {
+    pub opportunities: usize,
+    // Counted one by one, evicted by opportunity
+    pub intents: usize,
+    pub receipts: usize,
+}
+
+impl Default for CollectionCaps {
+    fn default() -> Self {
+        Self {
+            opportunities: 500,
+            intents: 2000,
+            receipts: 1000,
+        }
+    }
+}
+
+impl CollectionCaps {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, cap) in [("opportunities", self.opportunities), ("intents", self.intents), ("receipts", self.receipts)] {
+            if cap == 0 || cap > MAX_COLLECTION_CAP {
+                return Err(format!("The {} cap must be between 1 and {}", name, MAX_COLLECTION_CAP));
+            }
+        }
+        Ok(())
+    }
+}
+
+// When each opportunity, or the intents or receipt keyed by it, was last touched. A counter
+// rather than a timestamp, so touches within the same second still order.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct RecencyIndex {
+    touched: HashMap<String, u64>,
+    next: u64,
+}
+
+impl RecencyIndex {
+    pub fn touch(&mut self, opp_id: &str) {
+        self.next += 1;
+        self.touched.insert(opp_id.to_string(), self.next);
+    }
+
+    // 0 for anything never touched, e.g. kept from before the index existed
+    pub fn last_touched(&self, opp_id: &str) -> u64 {
+        self.touched.get(opp_id).copied().unwrap_or(0)
+    }
+
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.touched.retain(|opp_id, _| keep(opp_id));
+    }
+}
+
+// Candidates `(opp_id, finished)` in the order to evict them: finished before live, each least
+// recently touched first
+pub fn eviction_order(mut candidates: Vec<(String, bool)>, recency: &RecencyIndex) -> Vec<String> {
+    candidates.sort_by_cached_key(|(opp_id, finished)| (!*finished, recency.last_touched(opp_id), opp_id.clone()));
+    candidates.into_iter().map(|(opp_id, _)| opp_id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(entries: &[(&str, bool)]) -> Vec<(String, bool)> {
+        entries.iter().map(|(opp_id, finished)| (opp_id.to_string(), *finished)).collect()
+    }
+
+    #[test]
+    fn finished_entries_go_before_live_ones_least_recently_touched_first() {
+        let mut recency = RecencyIndex::default();
+        for opp_id in ["live-old", "done-new", "live-new", "done-old"] {
+            recency.touch(opp_id);
+        }
+        // Touching again makes it the most recent
+        recency.touch("done-new");
+        recency.touch("done-old");
+        recency.touch("done-new");
+        let order = eviction_order(
+            candidates(&[("live-new", false), ("done-new", true), ("live-old", false), ("done-old", true)]),
+            &recency,
+        );
+        assert_eq!(order, vec!["done-old", "done-new", "live-old", "live-new"]);
+    }
+
+    #[test]
+    fn never_touched_entries_go_first_in_id_order() {
+        let mut recency = RecencyIndex::default();
+        recency.touch("touched");
+        let order = eviction_order(candidates(&[("touched", true), ("b", true), ("a", true)]), &recency);
+        assert_eq!(order, vec!["a", "b", "touched"]);
+        assert_eq!(recency.last_touched("a"), 0);
+    }
+
+    #[test]
+    fn retain_forgets_evicted_entries() {
+        let mut recency = RecencyIndex::default();
+        recency.touch("kept");
+        recency.touch("evicted");
+        recency.retain(|opp_id| opp_id == "kept");
+        assert_eq!(recency.last_touched("evicted"), 0);
+        assert_eq!(recency.last_touched("kept"), 1);
+    }
+
+    #[test]
+    fn caps_must_be_positive_and_within_the_ceiling() {
+        assert!(CollectionCaps::default().validate().is_ok());
+        assert!(CollectionCaps { intents: 0, ..CollectionCaps::default() }.validate().is_err());
+        assert!(CollectionCaps { receipts: MAX_COLLECTION_CAP + 1, ..CollectionCaps::default() }.validate().is_err());
+        assert!(CollectionCaps { opportunities: MAX_COLLECTION_CAP, ..CollectionCaps::default() }.validate().is_ok());
+    }
+}
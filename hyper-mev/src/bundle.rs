@@ -0,0 +1,156 @@
+// SIGNED EXPORT BUNDLES
+// Evidence about a set of opportunities for someone outside the pool (an accountant, an
+// arbitrator, another pool). A bundle holds the canonical form of each record with any signature
+// it originally carried, the networking keys of everyone who signed something in it, and the
+// exporting node's signature over the whole body. Any node can check one without asking the pool.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::allocation::intent_hash;
+use crate::{
+    AaveLiquidationIntent, AaveLiquidationOpportunity, AaveLiquidationReceipt, ArchivedReceipt, ExpiryRecord,
+    FinderClaim, StatementLine,
+};
+
+pub const BUNDLE_FORMAT: &str = "hyper-mev/bundle";
+// 1: compact serde_json of the parsed record, fields in struct order
+pub const CANONICALIZATION_VERSION: u32 = 1;
+pub const MAX_BUNDLE_OPPORTUNITIES: usize = 100;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BundleItemKind {
+    Opportunity,
+    FinderClaim,
+    Intent,
+    Receipt,
+    ArchivedReceipt,
+    Expiry,
+    StatementLine,
+}
+
+// A signature the record carried when we received it, kept as it was
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ItemSignature {
+    // Full address (`node@process`) of the signer
+    pub signer: String,
+    pub signature: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BundleItem {
+    pub kind: BundleItemKind,
+    pub opp_id: String,
+    pub canonical: String,
+    // FNV-1a over `canonical`; an index for cross-references, the body signature is the guarantee
+    pub hash: String,
+    #[serde(default)]
+    pub signatures: Vec<ItemSignature>,
+}
+
+impl BundleItem {
+    pub fn new(kind: BundleItemKind, opp_id: &str, canonical: String) -> Self {
+        Self {
+            kind,
+            opp_id: opp_id.to_string(),
+            hash: intent_hash(&canonical),
+            canonical,
+            signatures: Vec::new(),
+        }
+    }
+
+    // The opp_id the record itself names, where it names one
+    fn named_opp_id(&self) -> Result<Option<String>, String> {
+        let text = self.canonical.as_str();
+        let parsed = match self.kind {
+            BundleItemKind::Opportunity => serde_json::from_str::<AaveLiquidationOpportunity>(text).map(|r| Some(r.opp_id)),
+            BundleItemKind::Intent => serde_json::from_str::<AaveLiquidationIntent>(text).map(|r| Some(r.opp_id)),
+            BundleItemKind::Receipt => serde_json::from_str::<AaveLiquidationReceipt>(text).map(|r| Some(r.opp_id)),
+            BundleItemKind::ArchivedReceipt => serde_json::from_str::<ArchivedReceipt>(text).map(|r| Some(r.opp_id)),
+            BundleItemKind::StatementLine => serde_json::from_str::<StatementLine>(text).map(|r| Some(r.opp_id)),
+            // Claims may name a linked duplicate's id, expiries name none
+            BundleItemKind::FinderClaim => serde_json::from_str::<FinderClaim>(text).map(|_| None),
+            BundleItemKind::Expiry => serde_json::from_str::<ExpiryRecord>(text).map(|_| None),
+        };
+        parsed.map_err(|e| format!("{:?} for {} does not parse: {}", self.kind, self.opp_id, e))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BundleKey {
+    pub node: String,
+    // Hex networking key as the PKI listed it at export; None if the exporter couldn't look it up
+    pub networking_key: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BundleBody {
+    pub format: String,
+    pub canonicalization_version: u32,
+    // Full address of the exporting process, which signs the body
+    pub exported_by: String,
+    pub exported_at: u64,
+    pub opp_ids: Vec<String>,
+    pub keys: Vec<BundleKey>,
+    pub items: Vec<BundleItem>,
+}
+
+impl BundleBody {
+    pub fn canonical_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    // Every signer in the bundle: the exporter and each item signature's signer
+    pub fn signers(&self) -> BTreeSet<String> {
+        std::iter::once(self.exported_by.as_str())
+            .chain(self.items.iter().flat_map(|item| item.signatures.iter().map(|s| s.signer.as_str())))
+            .map(|address| address.split('@').next().unwrap_or(address).to_string())
+            .collect()
+    }
+
+    // Problems that need no signature check: format and version, item hashes, each record
+    // parsing as its kind and naming the opp_id it is filed under, and a key for every signer
+    pub fn check_consistency(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.format != BUNDLE_FORMAT {
+            problems.push(format!("Unknown bundle format {}", self.format));
+        }
+        if self.canonicalization_version != CANONICALIZATION_VERSION {
+            problems.push(format!("Unsupported canonicalization version {}", self.canonicalization_version));
+        }
+        let listed: BTreeSet<&String> = self.opp_ids.iter().collect();
+        for item in &self.items {
+            if intent_hash(&item.canonical) != item.hash {
+                problems.push(format!("{:?} for {} does not match its hash", item.kind, item.opp_id));
+            }
+            if !listed.contains(&item.opp_id) {
+                problems.push(format!("{:?} filed under unlisted opportunity {}", item.kind, item.opp_id));
+            }
+            match item.named_opp_id() {
+                Ok(Some(named)) if named != item.opp_id => {
+                    problems.push(format!("{:?} filed under {} names {}", item.kind, item.opp_id, named));
+                }
+                Ok(_) => {}
+                Err(e) => problems.push(e),
+            }
+        }
+        let keyed: BTreeSet<&String> = self.keys.iter()
+            .filter(|key| key.networking_key.is_some())
+            .map(|key| &key.node)
+            .collect();
+        for signer in self.signers() {
+            if !keyed.contains(&signer) {
+                problems.push(format!("No key included for signer {}", signer));
+            }
+        }
+        problems
+    }
+}
+
+// As exported: the canonical body text, its hash, and the exporter's hex signature over the text
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SignedBundle {
+    pub body: String,
+    pub manifest_hash: String,
+    pub signature: String,
+}
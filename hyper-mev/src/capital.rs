@@ -0,0 +1,89 @@
+// CAPITAL OPERATIONS
+// Operator changes to available_balances. Every read-modify-write of a balance string goes through
+// credit/debit here, so no handler keeps its own copy of a balance across a change. A request may
+// carry a client-supplied operation id; the outcome is kept in a bounded log and a retry with the
+// same id gets that outcome back instead of being applied twice.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+// Operations remembered for replay and history, oldest dropped first
+pub const CAPITAL_LOG_CAPACITY: usize = 500;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum CapitalOperationKind {
+    Add,
+    Remove,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CapitalOperation {
+    #[serde(default)]
+    pub operation_id: Option<String>,
+    pub kind: CapitalOperationKind,
+    pub asset: String,
+    // As requested, so a replay can be checked against it ("all" for a full withdrawal)
+    pub amount: String,
+    // Balance after the operation; unchanged when it was refused
+    pub balance_after: String,
+    pub applied_at: String,
+    pub outcome: Result<String, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct CapitalLedger {
+    operations: VecDeque<CapitalOperation>,
+}
+
+impl CapitalLedger {
+    // The recorded outcome of `operation_id`, or an error if the id was used for a different
+    // operation; None when the id is new
+    pub fn replay(&self, operation_id: &str, kind: CapitalOperationKind, asset: &str, amount: &str) -> Option<Result<String, String>> {
+        let earlier = self.operations.iter().rev()
+            .find(|operation| operation.operation_id.as_deref() == Some(operation_id))?;
+        if earlier.kind != kind || !earlier.asset.eq_ignore_ascii_case(asset) || earlier.amount != amount {
+            return Some(Err(format!("Operation {} was already used for {:?} {} of asset {}",
+                operation_id, earlier.kind, earlier.amount, earlier.asset)));
+        }
+        Some(earlier.outcome.clone())
+    }
+
+    pub fn record(&mut self, operation: CapitalOperation) {
+        self.operations.push_back(operation);
+        while self.operations.len() > CAPITAL_LOG_CAPACITY {
+            self.operations.pop_front();
+        }
+    }
+
+    // Newest first, optionally for one asset
+    pub fn history(&self, asset: Option<&str>) -> Vec<&CapitalOperation> {
+        self.operations.iter().rev()
+            .filter(|operation| asset.map_or(true, |asset| operation.asset.eq_ignore_ascii_case(asset)))
+            .collect()
+    }
+}
+
+pub fn balance_of(balances: &HashMap<String, String>, asset: &str) -> U256 {
+    balances.get(asset)
+        .and_then(|amount| amount.parse::<U256>().ok())
+        .unwrap_or(U256::ZERO)
+}
+
+// Adds to a balance; returns the new balance
+pub fn credit(balances: &mut HashMap<String, String>, asset: &str, amount: U256) -> U256 {
+    let balance = balance_of(balances, asset) + amount;
+    balances.insert(asset.to_string(), balance.to_string());
+    balance
+}
+
+// Takes from a balance, dropping the asset once it reaches zero; returns what remains
+pub fn debit(balances: &mut HashMap<String, String>, asset: &str, amount: U256) -> U256 {
+    let remaining = balance_of(balances, asset).saturating_sub(amount);
+    if remaining.is_zero() {
+        balances.remove(asset);
+    } else {
+        balances.insert(asset.to_string(), remaining.to_string());
+    }
+    remaining
+}
@@ -0,0 +1,201 @@
+// COOPERATIVE MONITORING
+// Members with a bridge advertise the victims their bridge re-checks. A victim we also watch
+// that a peer with a good enough record covers can be left to that peer: our bridge stops
+// polling it and the peer relays each health factor it reads as a PositionUpdate. The victim
+// goes back to local polling when the relays stop or a spot check disagrees with them. The
+// bridge keeps re-checking a random share of delegated victims, so a peer that only claims
+// coverage is caught out.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+// Fallbacks kept for get_monitoring_coverage
+pub const MAX_COVERAGE_FALLBACKS: usize = 100;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MonitoringPolicy {
+    pub enabled: bool,
+    // Least reputation tier a peer needs before its coverage replaces our polling
+    pub min_peer_tier: u8,
+    // Blocks without a relayed update before a delegated victim is polled locally again
+    pub stale_after_blocks: u64,
+    // Share of delegated victims the bridge still re-checks each block
+    pub spot_check_bps: u16,
+    // How far a relayed health factor may be from our own read of a nearby block
+    pub health_factor_tolerance_bps: u16,
+    // How long a peer caught relaying an inaccurate update is passed over
+    pub distrust_secs: u64,
+    // Most victims we advertise, or take from any one peer's advertisement
+    pub max_advertised: usize,
+}
+
+impl Default for MonitoringPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_peer_tier: 1,
+            stale_after_blocks: 5,
+            spot_check_bps: 500,
+            health_factor_tolerance_bps: 200,
+            distrust_secs: 3_600,
+            max_advertised: 500,
+        }
+    }
+}
+
+// What a peer told us it monitors, and which of those victims it leaves to us
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct PeerCoverage {
+    pub monitoring: BTreeSet<String>,
+    pub subscribed: BTreeSet<String>,
+    pub advertised_at: u64,
+}
+
+// A victim we leave to a peer's relays instead of polling ourselves
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CoverageAssignment {
+    pub peer: String,
+    pub assigned_at: u64,
+    pub assigned_block: u64,
+    // Latest relayed read: its block and health factor
+    pub last_update_block: Option<u64>,
+    pub last_health_factor: Option<String>,
+    pub updates: u64,
+    pub spot_checks: u64,
+}
+
+impl CoverageAssignment {
+    pub fn new(peer: &str, now: u64, block: u64) -> Self {
+        Self {
+            peer: peer.to_string(),
+            assigned_at: now,
+            assigned_block: block,
+            last_update_block: None,
+            last_health_factor: None,
+            updates: 0,
+            spot_checks: 0,
+        }
+    }
+
+    // Blocks since the last relayed update, or since the assignment before any arrived
+    pub fn silent_blocks(&self, head: u64) -> u64 {
+        head.saturating_sub(self.last_update_block.unwrap_or(self.assigned_block))
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum FallbackReason {
+    // No relayed update for stale_after_blocks
+    Stale,
+    // A spot check disagreed with the peer's relayed health factor
+    Inaccurate,
+    // The peer stopped advertising the victim
+    PeerWithdrew,
+    // The peer fell below the policy, was blocked or went unreachable
+    PeerUnavailable,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CoverageFallback {
+    pub victim: String,
+    pub peer: String,
+    pub reason: FallbackReason,
+    pub block: u64,
+    pub at: u64,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct MonitoringCoverage {
+    // Latest advertisement per peer
+    pub peers: BTreeMap<String, PeerCoverage>,
+    // Victims left to a peer, by victim
+    pub assignments: BTreeMap<String, CoverageAssignment>,
+    pub fallbacks: VecDeque<CoverageFallback>,
+    // Peers passed over until the given time after an inaccurate update
+    pub distrusted: BTreeMap<String, u64>,
+    // Delegated set last sent to the bridge, so it's only resent on change
+    pub sent_to_bridge: BTreeSet<String>,
+    pub updates_relayed: u64,
+    pub updates_received: u64,
+    pub spot_checks: u64,
+    pub spot_check_failures: u64,
+    // Sum over blocks of the victims left to peers: the polls our bridge didn't make
+    pub delegated_victim_blocks: u64,
+    pub last_counted_block: Option<u64>,
+}
+
+impl MonitoringCoverage {
+    pub fn fall_back(&mut self, victim: &str, reason: FallbackReason, block: u64, now: u64, detail: Option<String>) -> Option<CoverageFallback> {
+        let assignment = self.assignments.remove(victim)?;
+        let fallback = CoverageFallback {
+            victim: victim.to_string(),
+            peer: assignment.peer,
+            reason,
+            block,
+            at: now,
+            detail,
+        };
+        self.fallbacks.push_back(fallback.clone());
+        while self.fallbacks.len() > MAX_COVERAGE_FALLBACKS {
+            self.fallbacks.pop_front();
+        }
+        Some(fallback)
+    }
+
+    pub fn distrusts(&self, peer: &str, now: u64) -> bool {
+        self.distrusted.get(peer).is_some_and(|until| *until > now)
+    }
+
+    // Peers advertising `victim` that we'd rely on, best record first
+    pub fn candidates(&self, victim: &str, now: u64, tier_of: impl Fn(&str) -> Option<u8>, min_tier: u8) -> Vec<(u8, String)> {
+        let mut candidates: Vec<(u8, String)> = self.peers.iter()
+            .filter(|(peer, coverage)| coverage.monitoring.contains(victim) && !self.distrusts(peer, now))
+            .filter_map(|(peer, _)| tier_of(peer).filter(|tier| *tier >= min_tier).map(|tier| (tier, peer.clone())))
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        candidates
+    }
+
+    // Peers that asked for our relays of `victim`
+    pub fn subscribers(&self, victim: &str) -> Vec<String> {
+        self.peers.iter()
+            .filter(|(_, coverage)| coverage.subscribed.contains(victim))
+            .map(|(peer, _)| peer.clone())
+            .collect()
+    }
+
+    // Victims we leave to `peer`, which our advertisement to it lists as subscribed
+    pub fn delegated_to(&self, peer: &str) -> BTreeSet<String> {
+        self.assignments.iter()
+            .filter(|(_, assignment)| assignment.peer == peer)
+            .map(|(victim, _)| victim.clone())
+            .collect()
+    }
+
+    pub fn count_delegated_blocks(&mut self, head: u64) {
+        if let Some(last) = self.last_counted_block {
+            let blocks = head.saturating_sub(last);
+            self.delegated_victim_blocks += blocks.saturating_mul(self.assignments.len() as u64);
+        }
+        if self.last_counted_block.map_or(true, |last| head > last) {
+            self.last_counted_block = Some(head);
+        }
+    }
+}
+
+// Whether two health factor reads agree within `tolerance_bps` of the larger. Debt-free reads
+// ("NoDebt") only agree with each other.
+pub fn health_factors_agree(ours: &str, theirs: &str, tolerance_bps: u16) -> bool {
+    match (ours.parse::<U256>(), theirs.parse::<U256>()) {
+        (Ok(ours), Ok(theirs)) => {
+            let larger = ours.max(theirs);
+            let difference = larger - ours.min(theirs);
+            difference.saturating_mul(U256::from(10_000u64)) <= larger.saturating_mul(U256::from(tolerance_bps))
+        }
+        _ => ours == theirs,
+    }
+}
@@ -0,0 +1,64 @@
+// RECEIPT DISPUTES
+// A provider that finds a receipt drawing a different amount of its capital than it committed,
+// or at a fee other than the one it bid, raises a dispute. The dispute goes on its own copy of
+// the receipt and is sent to the executor, which attaches it to its copy. Both nodes leave the
+// receipt's proceeds out of their cumulative PnL until the operator resolves the dispute.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum DisputeReason {
+    // The receipt's CapitalUsage for the provider diverges from what it committed
+    CapitalUsageMismatch,
+    // The receipt's CapitalUsage carries a fee other than the provider's bid
+    FeeMismatch,
+}
+
+// The message: what the provider expected and what the receipt says, as decimal strings
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReceiptDispute {
+    pub opp_id: String,
+    pub reason: DisputeReason,
+    pub expected: String,
+    pub received: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DisputeResolution {
+    pub resolved_at: u64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DisputeRecord {
+    pub dispute: ReceiptDispute,
+    pub raised_by: String,
+    pub raised_at: u64,
+    #[serde(default)]
+    pub resolution: Option<DisputeResolution>,
+}
+
+impl DisputeRecord {
+    pub fn is_open(&self) -> bool {
+        self.resolution.is_none()
+    }
+}
+
+// Our share of a disputed receipt, taken out of earned proceeds and metrics until it's resolved
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct WithheldProceeds {
+    pub asset: String,
+    pub amount: String,
+    pub strategy_id: String,
+    // Whether the share also went into the strategy's metrics, which only count executions we
+    // ran or lent capital to
+    pub from_metrics: bool,
+}
+
+// Whether `received` is further from `expected` than `tolerance_bps` of it
+pub fn usage_diverges(expected: U256, received: U256, tolerance_bps: u16) -> bool {
+    let difference = if expected > received { expected - received } else { received - expected };
+    difference.saturating_mul(U256::from(10_000u64)) > expected.saturating_mul(U256::from(tolerance_bps))
+}
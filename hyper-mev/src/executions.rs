@@ -0,0 +1,264 @@
+// IN-FLIGHT EXECUTIONS
+// An opportunity we dispatched stays pending until the bridge sends its receipt. Pending
+// executions are persisted with the rest of our state, so after a restart or a bridge reconnection
+// each one is picked up where it stood: the bridge is synced with, or asked about each execution
+// in turn, and resume_execution decides from its answer. What stays unresolved past the timeout
+// is given up as unknown.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::allocation::AllocationRecord;
+use crate::artemis::{LiveState, SyncSummary, MAX_SYNC_ENTRIES};
+use crate::expiry::RetirementOutcome;
+use crate::{AaveLiquidationReceipt, HyperMevApp, OpportunityStatus};
+
+// An intent collection handed to the bridge whose receipt hasn't come back yet. Persisted so a
+// restart neither re-dispatches it nor forgets to collect its receipt.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PendingExecution {
+    pub dispatched_at: String,
+    pub allocation: AllocationRecord,
+    // Identity the bridge gave when it connected, if any
+    pub bridge_id: Option<String>,
+    #[serde(default)]
+    pub last_status: Option<String>,
+    #[serde(default)]
+    pub last_queried_at: Option<String>,
+}
+
+// After this long without a terminal answer from the bridge, an execution is given up as unknown
+pub const PENDING_EXECUTION_TIMEOUT_SECS: u64 = 900;
+
+impl PendingExecution {
+    pub fn timed_out(&self, now: u64) -> bool {
+        now.saturating_sub(self.dispatched_at.parse::<u64>().unwrap_or(now)) > PENDING_EXECUTION_TIMEOUT_SECS
+    }
+}
+
+// Where an opportunity's execution picks up after a restart or a bridge reconnection
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExecutionResume {
+    // The receipt is stored; nothing is in flight
+    Settled,
+    // Never handed to the bridge; dispatch decides as for any opportunity
+    Dispatch,
+    // With the bridge, which hasn't said where it stands yet
+    Query,
+    // The bridge is on it, or was interrupted; wait on its word
+    Await(String),
+    // The bridge finished it and sent the receipt along
+    RecoverReceipt,
+    // The bridge never got the dispatch
+    Redispatch,
+    // Unresolved past PENDING_EXECUTION_TIMEOUT_SECS; expired as ExecutionUnknown
+    GiveUp,
+}
+
+// Decides an execution's next step from what we persisted and the bridge's answer to the sync or
+// QueryExecutionStatus, if it gave one. A receipt is taken whenever it comes, even past the timeout.
+pub fn resume_execution(pending: Option<&PendingExecution>, receipt_held: bool, bridge_status: Option<&str>, now: u64) -> ExecutionResume {
+    match (pending, bridge_status) {
+        _ if receipt_held => ExecutionResume::Settled,
+        (_, Some("Completed")) => ExecutionResume::RecoverReceipt,
+        (None, _) => ExecutionResume::Dispatch,
+        (Some(pending), _) if pending.timed_out(now) => ExecutionResume::GiveUp,
+        (Some(_), None) => ExecutionResume::Query,
+        (Some(_), Some("Unknown")) => ExecutionResume::Redispatch,
+        (Some(_), Some(status)) => ExecutionResume::Await(status.to_string()),
+    }
+}
+
+impl HyperMevApp {
+    // Asks the (re)connected bridge where each unanswered execution stands
+    fn query_pending_executions(&mut self) {
+        let mut opp_ids: Vec<String> = self.pending_executions.keys().cloned().collect();
+        opp_ids.sort();
+        let now = Self::current_timestamp();
+        for opp_id in opp_ids {
+            if let Some(pending) = self.pending_executions.get_mut(&opp_id) {
+                if pending.bridge_id.is_some() && pending.bridge_id != self.artemis_channels.bridge_id() {
+                    println!("   Execution {} was dispatched to bridge {:?}, now connected to {:?}",
+                        opp_id, pending.bridge_id, self.artemis_channels.bridge_id());
+                }
+                pending.last_queried_at = Some(now.clone());
+            }
+            let _ = self.send_to_artemis_json_sync(&serde_json::json!({
+                "type": "QueryExecutionStatus",
+                "opp_id": opp_id,
+            }).to_string());
+        }
+    }
+
+    // Opens the reconnection sync on a bridge channel: our live opportunities as the bridge may
+    // know them, executions first, then our own finds, newest first
+    pub(crate) fn begin_bridge_sync(&mut self, channel_id: u32) {
+        let mut live: Vec<(String, LiveState)> = self.pending_executions.iter()
+            .map(|(opp_id, pending)| {
+                let state = if pending.last_status.is_some() { LiveState::PendingReceipt } else { LiveState::Dispatched };
+                (opp_id.clone(), state)
+            })
+            .collect();
+        live.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut collecting: Vec<(&String, u64)> = self.active_opportunities.iter()
+            .filter(|(opp_id, data)| data.finder_node == "artemis-bot" && !self.pending_executions.contains_key(*opp_id))
+            .map(|(opp_id, data)| (opp_id, data.received_at))
+            .collect();
+        collecting.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        live.extend(collecting.into_iter().map(|(opp_id, _)| (opp_id.clone(), LiveState::Collecting)));
+        let truncated = live.len().saturating_sub(MAX_SYNC_ENTRIES);
+        live.truncate(MAX_SYNC_ENTRIES);
+
+        let listed: Vec<serde_json::Value> = live.iter()
+            .map(|(opp_id, state)| serde_json::json!({ "opp_id": opp_id, "state": state }))
+            .collect();
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let request = serde_json::json!({
+            "type": "SyncRequest",
+            "sync_id": self.bridge_sync.begin(channel_id, live, truncated, now),
+            "live": listed,
+            "truncated": truncated,
+        });
+        println!("   Syncing with the bridge on channel {}", channel_id);
+        self.push_to_artemis_channel(channel_id, &request.to_string());
+    }
+
+    // Reconciles the bridge's answer to our sync: receipts we never got are stored, dispatches it
+    // never got are sent again, and our own finds that it no longer tracks and nobody funded are
+    // retired. Answers to any sync but the one in flight are ignored, and everything here is a
+    // no-op the second time, so a repeated answer changes nothing.
+    pub(crate) fn reconcile_bridge_sync(&mut self, channel_id: u32, response: &serde_json::Value) {
+        let sync_id = response["sync_id"].as_u64().unwrap_or(0);
+        let Some(sync) = self.bridge_sync.finish(sync_id, channel_id) else {
+            println!("   Ignoring stale sync response {} from channel {}", sync_id, channel_id);
+            return;
+        };
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut summary = SyncSummary {
+            sync_id,
+            completed_at: now,
+            listed: sync.live.len(),
+            truncated: sync.truncated,
+            bridge_dropped: response["outage_dropped"].as_u64().unwrap_or(0),
+            ..SyncSummary::default()
+        };
+
+        // What the bridge couldn't deliver while we were apart, in the order it happened
+        for message in response["undelivered"].as_array().into_iter().flatten() {
+            let kind = message["type"].as_str().unwrap_or("");
+            if !matches!(kind, "OpportunityBroadcast" | "OpportunityUpdate" | "ExecutionReceipt") {
+                continue;
+            }
+            if kind == "ExecutionReceipt" {
+                let opp_id = message["receipt"]["opp_id"].as_str().unwrap_or("").to_string();
+                if self.execution_receipts.contains_key(&opp_id) {
+                    continue;
+                }
+                summary.receipts_delivered.push(opp_id);
+            } else {
+                summary.opportunities_delivered += 1;
+            }
+            if let Err(e) = self.handle_artemis_message_json(channel_id, &message.to_string()) {
+                println!("   Undelivered {} from the bridge unreadable: {}", kind, e);
+            }
+        }
+
+        for report in response["executions"].as_array().into_iter().flatten() {
+            let opp_id = report["opp_id"].as_str().unwrap_or("").to_string();
+            let status = report["status"].as_str().unwrap_or("Unknown");
+            let held = self.execution_receipts.contains_key(&opp_id);
+            match resume_execution(self.pending_executions.get(&opp_id), held, Some(status), now) {
+                ExecutionResume::RecoverReceipt => {
+                    match serde_json::from_value::<AaveLiquidationReceipt>(report["receipt"].clone()) {
+                        Ok(receipt) => {
+                            self.store_artemis_receipt(receipt);
+                            summary.receipts_delivered.push(opp_id);
+                        }
+                        Err(e) => println!("   Bridge's receipt for {} unreadable: {}", opp_id, e),
+                    }
+                }
+                ExecutionResume::Redispatch => {
+                    self.pending_executions.remove(&opp_id);
+                    self.set_opportunity_status(&opp_id, OpportunityStatus::CollectingIntents);
+                    self.dispatch_opportunity(&opp_id);
+                    if self.pending_executions.contains_key(&opp_id) {
+                        summary.redispatched.push(opp_id);
+                    } else {
+                        summary.unresolved.push(opp_id);
+                    }
+                }
+                ExecutionResume::Await(status) => {
+                    if let Some(pending) = self.pending_executions.get_mut(&opp_id) {
+                        pending.last_status = Some(status);
+                        pending.last_queried_at = Some(now.to_string());
+                    }
+                    summary.unresolved.push(opp_id);
+                }
+                // Expired by the maintenance tick
+                ExecutionResume::GiveUp => summary.unresolved.push(opp_id),
+                ExecutionResume::Settled | ExecutionResume::Dispatch | ExecutionResume::Query => {}
+            }
+        }
+
+        let tracked: HashSet<&str> = response["known_opportunities"].as_array().into_iter().flatten()
+            .filter_map(|opp_id| opp_id.as_str())
+            .collect();
+        let unsubstantiated: Vec<String> = sync.live.iter()
+            .filter(|(opp_id, state)| *state == LiveState::Collecting
+                && !tracked.contains(opp_id.as_str())
+                && !self.pending_executions.contains_key(opp_id)
+                && self.submitted_intents.get(opp_id).map_or(true, |intents| intents.is_empty()))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        for opp_id in unsubstantiated {
+            if self.retire_opportunity(&opp_id, RetirementOutcome::expired("UnsubstantiatedAfterSync", None, true)) {
+                summary.retired.push(opp_id);
+            }
+        }
+
+        println!("\n🔄 Bridge sync {}: {} re-dispatched, {} receipts and {} opportunities delivered, {} retired, {} unresolved",
+            sync_id, summary.redispatched.len(), summary.receipts_delivered.len(),
+            summary.opportunities_delivered, summary.retired.len(), summary.unresolved.len());
+        self.record_journal("BridgeSync", serde_json::to_value(&summary).unwrap_or_default());
+        self.bridge_sync.last = Some(summary);
+    }
+
+    // A bridge that never answers the sync (one predating it, or a dropped channel) gets the
+    // per-execution status queries instead
+    pub(crate) fn expire_bridge_sync(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let Some(sync) = self.bridge_sync.expire(now) else {
+            return;
+        };
+        println!("\n⌛ Bridge sync {} unanswered after {}s; querying executions one by one", sync.sync_id, now.saturating_sub(sync.started_at));
+        self.record_journal("BridgeSyncTimedOut", serde_json::json!({
+            "sync_id": sync.sync_id,
+            "channel_id": sync.channel_id,
+            "listed": sync.live.len(),
+        }));
+        if self.artemis_channels.is_connected() {
+            self.query_pending_executions();
+        }
+    }
+
+    // Gives up on executions the bridge never resolved: the opportunity is expired as unknown, and
+    // a receipt that still turns up later amends that record
+    pub(crate) fn resolve_stale_pending_executions(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut stale: Vec<(String, PendingExecution)> = self.pending_executions.iter()
+            .filter(|(_, pending)| pending.timed_out(now))
+            .map(|(opp_id, pending)| (opp_id.clone(), pending.clone()))
+            .collect();
+        stale.sort_by(|a, b| a.0.cmp(&b.0));
+        for (opp_id, pending) in stale {
+            self.pending_executions.remove(&opp_id);
+            println!("\n❓ Execution {} unresolved after {}s (last bridge status: {:?})",
+                opp_id, PENDING_EXECUTION_TIMEOUT_SECS, pending.last_status);
+            self.record_journal("ExecutionUnresolved", serde_json::json!({
+                "opp_id": opp_id,
+                "pending": pending,
+            }));
+            self.retire_opportunity(&opp_id, RetirementOutcome::expired("ExecutionUnknown", None, true));
+        }
+    }
+}
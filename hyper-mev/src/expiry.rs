@@ -0,0 +1,290 @@
+// OPPORTUNITY EXPIRY
+// An opportunity leaves the active set only through retire_opportunity: executed, once its receipt
+// is stored, or expired, once its deadline has passed unexecuted or a peer's notice says so. An
+// expiry is kept as a record, and amended if a receipt turns up after all. Intents expire on their
+// own expires_block, releasing the capital we locked behind ours.
+
+use hyperware_process_lib::{eth::U256, our};
+use serde::{Deserialize, Serialize};
+
+use crate::proceeds::SETTLED_INTENT_RETENTION_SECS;
+use crate::{
+    intent_coverage, AaveLiquidationOpportunity, HyperMevApp, IntentData, MevMessage, OpportunityData, OpportunityStatus,
+    OriginClass, OutstandingIntent, SECONDS_PER_BLOCK,
+};
+
+// How an opportunity leaves the active set
+#[derive(Clone, Debug)]
+pub enum RetirementOutcome {
+    // Won't execute: recorded in expired_opportunities and counted against the finder, and
+    // announced to peers when we speak for the opportunity
+    Expired { reason: String, reported_by: Option<String>, announce: bool },
+    // Executed; its stored receipt is the record from here on
+    Executed,
+}
+
+impl RetirementOutcome {
+    pub fn expired(reason: &str, reported_by: Option<String>, announce: bool) -> Self {
+        RetirementOutcome::Expired { reason: reason.to_string(), reported_by, announce }
+    }
+}
+
+// Final state of an opportunity that passed its deadline without executing
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ExpiryRecord {
+    pub finder_node: String,
+    pub reason: String,
+    pub best_coverage_reached: String,
+    pub expired_at: String,
+    // Node whose notice closed it (our own node when our tick did)
+    pub reported_by: String,
+    // A receipt arrived after the expiry, so it executed after all
+    #[serde(default)]
+    pub receipt_arrived: bool,
+    #[serde(default)]
+    pub origin: Option<OriginClass>,
+}
+
+const EXPIRY_HISTORY_CAPACITY: usize = 1000;
+// Blocks past the deadline we wait for the originator's notice before expiring on our own
+pub const EXPIRY_GRACE_BLOCKS: u64 = 5;
+
+impl HyperMevApp {
+    // Expires opportunities past their deadline (or past opportunity_ttl_secs when no chain head
+    // is known) and drops executed ones whose receipt already records them. The originator or
+    // claiming executor announces an expiry at the deadline; everyone else waits out a grace
+    // period for that notice first. Executions still with the bridge are left to their own timeout.
+    // Returns how many opportunities were removed.
+    pub(crate) fn expire_stale_opportunities(&mut self) -> usize {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let head = self.chain_head.map(|head| head.estimate(now));
+        let our_node = our().node;
+        let ttl = self.node_config.opportunity_ttl_secs;
+
+        let mut stale: Vec<(String, bool)> = Vec::new();
+        let mut executed: Vec<String> = Vec::new();
+        let mut lapsed: Vec<String> = Vec::new();
+        for (opp_id, data) in &self.active_opportunities {
+            if self.pending_executions.contains_key(opp_id) {
+                continue;
+            }
+            let has_receipt = self.execution_receipts.contains_key(opp_id);
+            let announces = data.finder_node == "artemis-bot" || data.claimed_by.as_deref() == Some(our_node.as_str());
+            let grace = if announces || has_receipt { 0 } else { EXPIRY_GRACE_BLOCKS };
+            let past = |grace: u64| match head {
+                Some(head) => serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                    .map_or(false, |opportunity| head > opportunity.deadline_block + grace),
+                None => now.saturating_sub(data.received_at)
+                    > ttl + grace * SECONDS_PER_BLOCK,
+            };
+            if !past(grace) {
+                // Within the grace period it stays, but shows as expired
+                if data.status.dispatchable() && past(0) {
+                    lapsed.push(opp_id.clone());
+                }
+                continue;
+            }
+            if has_receipt {
+                executed.push(opp_id.clone());
+            } else {
+                stale.push((opp_id.clone(), announces));
+            }
+        }
+        stale.sort();
+        for opp_id in lapsed {
+            self.set_opportunity_status(&opp_id, OpportunityStatus::Expired);
+        }
+
+        let mut pruned = 0;
+        for (opp_id, announce) in stale {
+            println!("\n⌛ Opportunity {} passed its deadline unexecuted", opp_id);
+            if self.retire_opportunity(&opp_id, RetirementOutcome::expired("DeadlinePassed", None, announce)) {
+                pruned += 1;
+            }
+        }
+        for opp_id in executed {
+            if self.retire_opportunity(&opp_id, RetirementOutcome::Executed) {
+                pruned += 1;
+            }
+        }
+        pruned
+    }
+
+    // The one way an opportunity leaves the active set. Drops everything keyed by it (intents,
+    // our outstanding intent and its capital lock, duplicate links, sends awaiting repair), then
+    // records the outcome. Returns false when there was nothing to retire: an expiry for an
+    // opportunity already expired or executed, or an executed one already gone.
+    pub(crate) fn retire_opportunity(&mut self, opp_id: &str, outcome: RetirementOutcome) -> bool {
+        let (reason, reported_by, announce) = match outcome {
+            RetirementOutcome::Executed => {
+                let retired = self.active_opportunities.contains_key(opp_id);
+                self.drop_opportunity_state(opp_id);
+                return retired;
+            }
+            RetirementOutcome::Expired { reason, reported_by, announce } => (reason, reported_by, announce),
+        };
+        if self.execution_receipts.contains_key(opp_id) || self.expired_opportunities.contains_key(opp_id) {
+            return false;
+        }
+        let reason = reason.as_str();
+        let (data, intents, ours) = self.drop_opportunity_state(opp_id);
+        let Some(data) = data else {
+            // Nothing of ours hangs on an opportunity we never stored or committed to
+            if ours.is_none() {
+                return false;
+            }
+            self.expired_opportunities.insert(opp_id.to_string(), ExpiryRecord {
+                finder_node: String::new(),
+                reason: reason.to_string(),
+                best_coverage_reached: "0".to_string(),
+                expired_at: Self::current_timestamp(),
+                reported_by: reported_by.unwrap_or_else(|| our().node),
+                receipt_arrived: false,
+                origin: None,
+            });
+            return true;
+        };
+
+        let best_coverage = serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+            .map(|opportunity| intent_coverage(&opportunity, &intents))
+            .unwrap_or(U256::ZERO);
+        if let Some(stats) = self.finder_sequences.get_mut(&data.finder_node) {
+            stats.expired_unexecuted += 1;
+        }
+        if let Some(reputation) = self.peer_reputation.get_mut(&data.finder_node) {
+            reputation.opportunities_expired += 1;
+        }
+        self.origin_stats.entry(data.origin.class()).or_default().expired_unexecuted += 1;
+        let record = ExpiryRecord {
+            finder_node: data.finder_node.clone(),
+            reason: reason.to_string(),
+            best_coverage_reached: best_coverage.to_string(),
+            expired_at: Self::current_timestamp(),
+            reported_by: reported_by.unwrap_or_else(|| our().node),
+            receipt_arrived: false,
+            origin: Some(data.origin.class()),
+        };
+        self.record_journal("OpportunityExpired", serde_json::json!({
+            "opp_id": opp_id,
+            "record": record,
+            "released_intent": ours.map(|outstanding| outstanding.intent.max_amount),
+        }));
+        if data.window_extension.as_ref().map_or(false, |extension| extension.extended_by == our().node) {
+            self.record_journal("IntentWindowOutcome", serde_json::json!({
+                "opp_id": opp_id,
+                "outcome": "Expired",
+                "reason": reason,
+                "coverage": best_coverage.to_string(),
+            }));
+        }
+        self.expired_opportunities.insert(opp_id.to_string(), record);
+        while self.expired_opportunities.len() > EXPIRY_HISTORY_CAPACITY {
+            let Some(oldest) = self.expired_opportunities.iter()
+                .min_by_key(|(id, record)| (record.expired_at.parse::<u64>().unwrap_or(0), (*id).clone()))
+                .map(|(id, _)| id.clone()) else {
+                break;
+            };
+            self.expired_opportunities.remove(&oldest);
+        }
+
+        if announce {
+            let notice = MevMessage::OpportunityExpired {
+                opp_id: opp_id.to_string(),
+                reason: Some(reason.to_string()),
+                best_coverage_reached: Some(best_coverage.to_string()),
+            };
+            self.send_to_peers(notice.handler(), &Self::message_json(&notice));
+        }
+        true
+    }
+
+    // Removes an opportunity and every record keyed by it, returning what retirement still needs
+    pub(crate) fn drop_opportunity_state(&mut self, opp_id: &str) -> (Option<OpportunityData>, Vec<IntentData>, Option<OutstandingIntent>) {
+        let data = self.active_opportunities.remove(opp_id);
+        let intents = self.submitted_intents.remove(opp_id).unwrap_or_default();
+        let ours = self.our_intents.remove(opp_id);
+        self.revoked_intents.remove(opp_id);
+        self.release_commitment(opp_id);
+        self.reservation_rounds.remove(opp_id);
+        self.capital_reservations.remove(opp_id);
+        self.duplicate_index.retain(|_, canonical| canonical != opp_id);
+        self.opp_aliases.retain(|alias, canonical| canonical != opp_id && alias != opp_id);
+        self.outbound_budget.borrow_mut().skipped.retain(|send| send.opp_id != opp_id);
+        (data, intents, ours)
+    }
+
+    // A receipt after an expiry wins: the record is kept but marked, and the finder uncounted
+    pub(crate) fn amend_expiry_for_receipt(&mut self, opp_id: &str) {
+        let Some(record) = self.expired_opportunities.get_mut(opp_id) else {
+            return;
+        };
+        if record.receipt_arrived {
+            return;
+        }
+        record.receipt_arrived = true;
+        let finder = record.finder_node.clone();
+        let origin = record.origin;
+        if let Some(stats) = self.finder_sequences.get_mut(&finder) {
+            stats.expired_unexecuted = stats.expired_unexecuted.saturating_sub(1);
+        }
+        if let Some(reputation) = self.peer_reputation.get_mut(&finder) {
+            reputation.opportunities_expired = reputation.opportunities_expired.saturating_sub(1);
+        }
+        if let Some(stats) = origin.and_then(|class| self.origin_stats.get_mut(&class)) {
+            stats.expired_unexecuted = stats.expired_unexecuted.saturating_sub(1);
+            stats.executed += 1;
+        }
+        println!("   Receipt for {} arrived after it expired; expiry amended", opp_id);
+        self.record_journal("ExpiryAmended", serde_json::json!({ "opp_id": opp_id }));
+    }
+
+    // Drops intents whose expires_block the chain head has passed: those we received, and our own,
+    // whose capital lock is released. Executions already with the bridge keep theirs until the receipt.
+    // Settled intents go by age instead, whether or not the chain head is known.
+    pub(crate) fn prune_expired_intents(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.settled_intents.retain(|_, outstanding| {
+            now.saturating_sub(outstanding.submitted_at.parse::<u64>().unwrap_or(0)) < SETTLED_INTENT_RETENTION_SECS
+        });
+        let Some(head) = self.chain_head.map(|head| head.estimate(now)) else {
+            return;
+        };
+        let mut dropped = 0;
+        for (opp_id, intents) in self.submitted_intents.iter_mut() {
+            if self.pending_executions.contains_key(opp_id) {
+                continue;
+            }
+            let before = intents.len();
+            intents.retain(|data| data.expires_block >= head);
+            dropped += before - intents.len();
+        }
+        self.submitted_intents.retain(|_, intents| !intents.is_empty());
+        if dropped > 0 {
+            println!("\n⌛ Pruned {} expired intent(s) at block {}", dropped, head);
+        }
+
+        let mut ours: Vec<String> = self.our_intents.iter()
+            .filter(|(opp_id, outstanding)| outstanding.intent.expires_block < head && !self.pending_executions.contains_key(*opp_id))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        ours.sort();
+        for opp_id in ours {
+            let Some(outstanding) = self.our_intents.remove(&opp_id) else {
+                continue;
+            };
+            self.release_commitment(&opp_id);
+            self.capital_reservations.remove(&opp_id);
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                data.participating = false;
+            }
+            self.record_journal("IntentExpired", serde_json::json!({
+                "opp_id": opp_id,
+                "expires_block": outstanding.intent.expires_block,
+                "head": head,
+                "released": outstanding.intent.max_amount,
+            }));
+        }
+        // A revoked intent can't be held to anything past its expiry
+        self.revoked_intents.retain(|_, outstanding| outstanding.intent.expires_block >= head);
+    }
+}
@@ -0,0 +1,60 @@
+// RECEIPT FINALITY
+// A successful receipt counts as soon as it is verified, but its transaction can still be reorged
+// out. While eth_checks.check_receipts is on, a counted receipt stays Confirming until the chain
+// head is required_confirmations past the block the transaction was mined in. Then the transaction
+// is looked up once more. If it is still there, the receipt is Final. If the reorg dropped it, the
+// receipt becomes Failed and everything it moved is put back. What it moved is recorded when it
+// counts, so the reversal undoes exactly that.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::SignedAmount;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum ReceiptFinality {
+    // Past its confirmations, or never tracked (checks off, not successful, kept from before)
+    #[default]
+    Final,
+    // Mined at `block_number`, which moves if a reorg re-includes the transaction elsewhere
+    Confirming { block_number: u64 },
+    // Dropped by a reorg at the head we saw then; the receipt now reads Failed
+    Reorged { head: u64 },
+}
+
+impl ReceiptFinality {
+    // Blocks the head still has to advance before the final check; None once not confirming
+    pub fn confirmations_remaining(&self, required: u64, head: Option<u64>) -> Option<u64> {
+        let ReceiptFinality::Confirming { block_number } = self else {
+            return None;
+        };
+        let confirmed = head.map_or(0, |head| head.saturating_sub(*block_number));
+        Some(required.saturating_sub(confirmed))
+    }
+}
+
+// One posting a counted receipt made to the settlement ledger
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LedgerPosting {
+    pub peer: String,
+    pub asset: String,
+    pub delta: SignedAmount,
+}
+
+// Everything a counted receipt changed in our books, for a reorg to reverse
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct AppliedEffects {
+    // Credited to earned_proceeds
+    pub proceeds_asset: String,
+    pub proceeds: String,
+    // Drawn from our available balance into used_capital
+    #[serde(default)]
+    pub capital_asset: Option<String>,
+    #[serde(default)]
+    pub drawn: String,
+    // Capital drawn, as posted to the settlement ledger; distributions are reversed from their record
+    #[serde(default)]
+    pub postings: Vec<LedgerPosting>,
+    // Counted as a success in this strategy's metrics, with the share in its proceeds
+    #[serde(default)]
+    pub metrics_strategy: Option<String>,
+}
@@ -0,0 +1,193 @@
+// SETTLEMENT LEDGER
+// Our running net position with each peer, per asset: positive when the peer owes us, negative
+// when we owe it. Executions post to it as they settle (capital an executor drew, returned to its
+// provider, and proceeds as the executor distributed them), and a payment made outside the pool
+// and recorded with mark_settled posts the opposite of a position, closing it.
+//
+// Every other amount in the node is an unsigned U256 string, and a position has to go below zero
+// without wrapping or stopping at zero. A SignedAmount is a sign and a U256 magnitude. It is
+// written as a decimal string with a leading '-' when negative, and zero is never negative, so
+// equal positions always serialize the same.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::proceeds::ProceedsDistribution;
+
+// Recorded payments kept for history, oldest dropped first
+pub const SETTLEMENT_PAYMENT_CAPACITY: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SignedAmount {
+    negative: bool,
+    magnitude: U256,
+}
+
+impl SignedAmount {
+    pub const ZERO: Self = Self { negative: false, magnitude: U256::ZERO };
+
+    // `amount` the peer owes us
+    pub fn owed_to_us(amount: U256) -> Self {
+        Self { negative: false, magnitude: amount }
+    }
+
+    // `amount` we owe the peer
+    pub fn owed_by_us(amount: U256) -> Self {
+        Self::owed_to_us(amount).negated()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> U256 {
+        self.magnitude
+    }
+
+    pub fn negated(self) -> Self {
+        Self { negative: !self.negative && !self.magnitude.is_zero(), magnitude: self.magnitude }
+    }
+
+    // None only when the magnitude would leave U256
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        if self.negative == other.negative {
+            return self.magnitude.checked_add(other.magnitude)
+                .map(|magnitude| Self { negative: self.negative && !magnitude.is_zero(), magnitude });
+        }
+        Some(if self.magnitude >= other.magnitude {
+            let magnitude = self.magnitude - other.magnitude;
+            Self { negative: self.negative && !magnitude.is_zero(), magnitude }
+        } else {
+            Self { negative: other.negative, magnitude: other.magnitude - self.magnitude }
+        })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.negated())
+    }
+}
+
+impl fmt::Display for SignedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-{}", self.magnitude)
+        } else {
+            write!(f, "{}", self.magnitude)
+        }
+    }
+}
+
+impl FromStr for SignedAmount {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(format!("Invalid signed amount: {}", value));
+        }
+        let magnitude = digits.parse::<U256>().map_err(|e| format!("Invalid signed amount {}: {}", value, e))?;
+        Ok(Self { negative: negative && !magnitude.is_zero(), magnitude })
+    }
+}
+
+impl Serialize for SignedAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SignedAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Net position per peer, then per asset
+pub type SettlementPositions = HashMap<String, HashMap<String, SignedAmount>>;
+
+pub fn position_of(positions: &SettlementPositions, peer: &str, asset: &str) -> SignedAmount {
+    positions.get(peer)
+        .and_then(|assets| assets.get(asset))
+        .copied()
+        .unwrap_or(SignedAmount::ZERO)
+}
+
+// Moves a position by `delta`, dropping it (and the peer, once it has none) when it comes to zero;
+// returns the new position. Refused, leaving the position alone, only if it would overflow.
+pub fn post(positions: &mut SettlementPositions, peer: &str, asset: &str, delta: SignedAmount) -> Result<SignedAmount, String> {
+    let position = position_of(positions, peer, asset).checked_add(delta)
+        .ok_or_else(|| format!("Position with {} in {} overflows", peer, asset))?;
+    let assets = positions.entry(peer.to_string()).or_default();
+    if position.is_zero() {
+        assets.remove(asset);
+    } else {
+        assets.insert(asset.to_string(), position);
+    }
+    if assets.is_empty() {
+        positions.remove(peer);
+    }
+    Ok(position)
+}
+
+// What a distribution moves between us and each other node, by (peer, asset). As its executor we
+// owe every other entry; otherwise its executor owes us ours.
+pub fn distribution_postings(distribution: &ProceedsDistribution, us: &str) -> BTreeMap<(String, String), SignedAmount> {
+    let mut postings: BTreeMap<(String, String), SignedAmount> = BTreeMap::new();
+    for entry in &distribution.entries {
+        let amount = entry.amount.parse::<U256>().unwrap_or(U256::ZERO);
+        let (peer, delta) = if distribution.executor == us && entry.node_id != us {
+            (entry.node_id.clone(), SignedAmount::owed_by_us(amount))
+        } else if distribution.executor != us && entry.node_id == us {
+            (distribution.executor.clone(), SignedAmount::owed_to_us(amount))
+        } else {
+            continue;
+        };
+        let posting = postings.entry((peer, entry.asset.clone())).or_default();
+        *posting = posting.checked_add(delta).unwrap_or(*posting);
+    }
+    postings
+}
+
+// An off-band payment closing a position, as the operator reported it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SettlementPayment {
+    pub peer: String,
+    pub asset: String,
+    // The position the payment closed: positive when the peer paid us, negative when we paid it
+    pub settled: SignedAmount,
+    pub tx_hash: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub recorded_at: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SettlementPayments {
+    payments: VecDeque<SettlementPayment>,
+}
+
+impl SettlementPayments {
+    pub fn record(&mut self, payment: SettlementPayment) {
+        self.payments.push_back(payment);
+        while self.payments.len() > SETTLEMENT_PAYMENT_CAPACITY {
+            self.payments.pop_front();
+        }
+    }
+
+    // Newest first, optionally with one peer
+    pub fn history(&self, peer: Option<&str>) -> Vec<&SettlementPayment> {
+        self.payments.iter().rev()
+            .filter(|payment| peer.map_or(true, |peer| payment.peer == peer))
+            .collect()
+    }
+}
@@ -3,16 +3,132 @@
 
 use hyperprocess_macro::*;
 use hyperware_process_lib::{
-    our, Request, Address, ProcessId,
+    our, Request, Address,
     homepage::add_to_homepage,
-    eth::U256,
+    eth::{
+        Address as EthAddress, Bytes as EthBytes, Provider as EthProvider, TransactionInput,
+        TransactionRequest, TxHash, U256,
+    },
     http::server::{send_ws_push, HttpServer, WsMessageType},
     LazyLoadBlob,
 };
 use hyperware_app_common::{source, SaveOptions};
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+mod artemis;
+use artemis::{Admission, ArtemisChannels, ArtemisConnection, BridgeSync, InboundQueue, INBOUND_TURN_BUDGET_MS, ValidationBroker, ValidationKey, ValidationPriority, ValidationResult, ValidationWaiter};
+
+mod recording;
+use recording::{Boundary, RecordedFrame, Recording, MAX_RECORDING_BYTES};
+
+mod signing;
+use signing::MessageSignature;
+
+mod nonce;
+
+mod persistence;
+use persistence::{numeric_timestamp, peer_map, JournalEntry};
+
+mod executions;
+use executions::{resume_execution, ExecutionResume, PendingExecution};
+
+mod expiry;
+use expiry::{ExpiryRecord, RetirementOutcome, EXPIRY_GRACE_BLOCKS};
+
+mod rotation;
+use rotation::{judge_rotation, rotation_bytes, RetiredKey, RotationClaim, SigningKeyPair};
+
+mod audit;
+use audit::{message_opp_id, truncate_payload, EventLog, EventLogConfig, EventLogEntry, EventOutcome};
+
+mod bounds;
+use bounds::{eviction_order, CollectionCaps, RecencyIndex};
+
+#[cfg(test)]
+mod tests;
+
+mod settlement;
+use settlement::{CounterpartyRegistry, CounterpartyState};
+
+mod risk;
+use risk::{RiskAction, RiskContext, RiskFlag, RiskProbe, PROBED_FLAGS};
+
+mod capital;
+use capital::{CapitalLedger, CapitalOperation, CapitalOperationKind};
+
+mod reservation;
+use reservation::{reservation_refusal, CapitalReservation, ReservationRound};
+
+mod selftest;
+use selftest::is_self_test_id;
+
+mod outbound;
+use outbound::{OutboundBudget, OutboundQueues};
+
+mod local_api;
+use local_api::{
+    AssetAmount, LocalCapital, LocalCommitment, LocalOpportunity, LocalOpportunityFilter, LocalScope,
+    LocalSettlementBalance, LocalSubscribers, PoolEvent, PoolEventKind, LOCAL_PROBE_TIMEOUT_SECS,
+};
+
+mod bundle;
+use bundle::{
+    BundleBody, BundleItem, BundleItemKind, BundleKey, SignedBundle, BUNDLE_FORMAT, CANONICALIZATION_VERSION,
+    MAX_BUNDLE_OPPORTUNITIES,
+};
+
+mod reputation;
+use reputation::{PeerReputation, ReputationPolicy};
+
+mod mirror;
+use mirror::{
+    HeldMirror, MirrorAction, MirrorAgreement, MirrorEntry, MirrorLog, MirrorRecordKind, MirrorRestore, MirrorState,
+    RestoredRecord, Reverification, MAX_MIRROR_BATCH,
+};
+
+mod ratelimit;
+use ratelimit::{InboundClass, InboundRateLimits, PeerRateLimiter};
+
+mod records;
+use records::{
+    diff_records, CounterpartyView, ReceiptView, RecordSection, RecordSet, RecordSharingPolicy, SharedRecord,
+    ALL_SECTIONS, MAX_COUNTERPARTY_VIEWS,
+};
+
+mod coverage;
+use coverage::{health_factors_agree, CoverageAssignment, FallbackReason, MonitoringCoverage, MonitoringPolicy};
+
+mod updates;
+use updates::{UiChannels, UiEvent, UiEventKind};
+
+mod metrics;
+use metrics::PerformanceMetrics;
+
+mod allocation;
+use allocation::{
+    allocate, distribute_pool, intent_hash, split_proceeds, verify_allocation, AllocationCandidate, AllocationLine,
+    AllocationMismatch, AllocationPolicy, AllocationRecord, DistributionScheme, DustSink,
+};
+
+mod proceeds;
+use proceeds::{
+    DistributionEntry, DistributionLedger, DistributionRecord, DistributionRole, ProceedsDistribution,
+};
+
+mod ledger;
+use ledger::{distribution_postings, SettlementPayment, SettlementPayments, SettlementPositions, SignedAmount};
+
+mod netting;
+use netting::{EntryNetting, EpochLength, NettingStatement, SettlementEpochs, StatementItem, StatementStatus};
+
+mod dispute;
+use dispute::{usage_diverges, DisputeReason, DisputeRecord, DisputeResolution, ReceiptDispute, WithheldProceeds};
+
+mod finality;
+use finality::{AppliedEffects, LedgerPosting, ReceiptFinality};
 
 
 // WebSocket messages for Artemis MEV bot communication
@@ -21,11 +137,40 @@ use std::collections::{HashMap, HashSet};
 // MEV STRATEGY TRAIT SYSTEM
 pub type StrategyId = String;
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+// Per-strategy settings, kept in strategy_configs. Unset thresholds fall back to the node-wide
+// ones; `params` holds whatever the strategy itself reads.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct StrategyConfig {
-    pub min_profit_usd: U256,
-    pub max_gas_price_gwei: U256,
+    #[serde(default)]
+    pub min_profit_usd: Option<String>,
+    #[serde(default)]
+    pub max_gas_price_gwei: Option<String>,
+    // Blocks after a find our own broadcasts give executors; 0 keeps the bridge's deadline
+    #[serde(default)]
     pub execution_deadline_blocks: u64,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+impl StrategyConfig {
+    pub fn aave_default() -> Self {
+        Self {
+            params: serde_json::json!({ "min_health_factor": "100000000000000000" }), // 0.1
+            ..Self::default()
+        }
+    }
+    
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("min_profit_usd", &self.min_profit_usd), ("max_gas_price_gwei", &self.max_gas_price_gwei)] {
+            if let Some(value) = value {
+                value.parse::<U256>().map_err(|_| format!("Invalid {}: {}", name, value))?;
+            }
+        }
+        if !self.params.is_null() && !self.params.is_object() {
+            return Err("params must be a JSON object".to_string());
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -43,7 +188,354 @@ pub trait MevStrategy {
     type Receipt: Clone + Serialize + for<'de> Deserialize<'de>;
 }
 
-// P2P MESSAGE TYPES - We'll use JSON strings internally
+// P2P MESSAGE TYPES
+// Core peer messages. The "type" tag keeps the wire format the JSON peers already exchange;
+// embedded strategy payloads (opportunity, intent, receipt) travel as JSON strings.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum MevMessage {
+    NodeAnnouncement {
+        node_info: NodeInfo,
+        capabilities: Vec<StrategyId>,
+        timestamp: String,
+        // Peer exchange: a sample of the sender's reachable peers
+        #[serde(default)]
+        known_peers: Vec<String>,
+        // Answer to an announcement; never answered itself, so two nodes don't loop
+        #[serde(default)]
+        reply: bool,
+        // Planned downtime of the sender; None clears one announced earlier
+        #[serde(default)]
+        maintenance: Option<MaintenanceWindow>,
+        // Sender's signature; absent from peers that predate signing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    OpportunityBroadcast {
+        opp_id: String,
+        strategy_id: StrategyId,
+        #[serde(deserialize_with = "embedded_json_string")]
+        opportunity: String,
+        finder_fee_bps: u16,
+        deadline_block: u64,
+        #[serde(default)]
+        executor_preference: Vec<String>,
+        #[serde(default)]
+        finder_seq: Option<u64>,
+        // Resent in answer to a backfill request rather than freshly found
+        #[serde(default)]
+        backfill: bool,
+        #[serde(default)]
+        origin: OpportunityOrigin,
+        // Times the broadcast has been forwarded; absent from peers that predate forwarding
+        #[serde(default)]
+        hop_count: Option<u8>,
+        // Node whose bot found the opportunity, set by the originator and kept by relays.
+        // Absent from older peers, whose broadcasts are always their own finds.
+        #[serde(default)]
+        finder_node: Option<String>,
+    },
+    IntentSubmission {
+        opp_id: String,
+        strategy_id: StrategyId,
+        #[serde(deserialize_with = "embedded_json_string")]
+        intent: String,
+        max_amount: String,
+        min_bonus_bps: u16,
+        expires_block: u64,
+        #[serde(default)]
+        fee_bps: u16,
+        // Sender's signature; absent from peers that predate signing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    ExecutionReceipt {
+        opp_id: String,
+        strategy_id: StrategyId,
+        #[serde(deserialize_with = "embedded_json_string")]
+        receipt: String,
+        block_number: u64,
+        tx_hash: String,
+        gas_used: String,
+        proceeds: ProceedsBreakdown,
+        // Sender's signature; absent from peers that predate signing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    // The sender's app signing key replaced; old_signature and new_signature are each key's
+    // signature over rotation_bytes
+    KeyRotation {
+        node: String,
+        old_key: String,
+        new_key: String,
+        rotated_at: u64,
+        old_signature: String,
+        new_signature: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    // What the sender owes the receiver in `asset` over its closed `epoch`, per opportunity
+    NettingStatement {
+        statement_id: String,
+        epoch: u64,
+        asset: String,
+        items: Vec<StatementItem>,
+        net: SignedAmount,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    // The receiver's answer: items it agrees with, netted, and those it disputes
+    NettingAcknowledgment {
+        statement_id: String,
+        accepted: Vec<String>,
+        disputed: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<MessageSignature>,
+    },
+    // Execution rights on `opp_id` passed to `holder`, at `holder_index` in the sender's ranking
+    ExecutorSuccession {
+        opp_id: String,
+        holder: String,
+        holder_index: usize,
+    },
+    // An executor asking a provider to hold the capital behind its intent until `expires_at`
+    CapitalReserveRequest {
+        opp_id: String,
+        intent_hash: String,
+        amount: String,
+        expires_at: u64,
+    },
+    CapitalReserveResponse {
+        opp_id: String,
+        intent_hash: String,
+        amount: String,
+        confirmed: bool,
+        // Granted expiry, or the requested one when refused
+        expires_at: u64,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    IntentRevocation {
+        opp_id: String,
+    },
+    // An executor refusing a revocation because the opportunity is already executing
+    IntentRevocationRejection {
+        opp_id: String,
+        reason: String,
+    },
+    SyncRequest {
+        #[serde(default)]
+        max_opportunities: usize,
+    },
+    // Broadcasts and signed intents kept as sent, so each still verifies and validates as one
+    SyncResponse {
+        opportunities: Vec<serde_json::Value>,
+        intents: Vec<serde_json::Value>,
+        #[serde(default)]
+        truncated: bool,
+    },
+    // The finder's cancellation, as the exact text it signed, and its hex signature over it
+    #[serde(alias = "OpportunityCancelled")]
+    OpportunityCancellation {
+        cancellation: String,
+        #[serde(default)]
+        signature: Option<String>,
+    },
+    // from_seq and to_seq go with ResendRequest only
+    MirrorControl {
+        action: MirrorAction,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from_seq: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_seq: Option<u64>,
+    },
+    MirrorEntries {
+        entries: Vec<MirrorEntry>,
+        // Our own records coming back, rather than a buddy's to keep
+        #[serde(default)]
+        restore: bool,
+        #[serde(default)]
+        last: bool,
+    },
+    // Heartbeat; a ping that isn't itself a reply gets one
+    Ping {
+        sent_at: String,
+        #[serde(default)]
+        reply: bool,
+    },
+    OpportunityUpdate {
+        opp_id: String,
+        #[serde(default)]
+        strategy_id: StrategyId,
+        #[serde(deserialize_with = "embedded_json_string")]
+        opportunity: String,
+    },
+    // reason defaults to DeadlinePassed; best_coverage_reached is absent from older peers
+    OpportunityExpired {
+        opp_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+        #[serde(default)]
+        best_coverage_reached: Option<String>,
+    },
+    ProviderShortfall {
+        opp_id: String,
+        distinct_providers: u32,
+        min_providers: u32,
+    },
+    IntentWindowExtended {
+        opp_id: String,
+        new_deadline: u64,
+    },
+    ExecutionClaim {
+        opp_id: String,
+    },
+    // A provider shrinking its intent; "0" withdraws it
+    IntentAmendment {
+        opp_id: String,
+        new_max_amount: String,
+    },
+    // committed_amount is what the executor allocated, which the provider stays held to
+    IntentAmendmentRejection {
+        opp_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+        #[serde(default)]
+        committed_amount: Option<String>,
+    },
+    // Sent to the finder for the sequence numbers we missed from it
+    BackfillRequest {
+        finder: String,
+        from_seq: u64,
+        to_seq: u64,
+    },
+    // The victims the sender's bridge polls, and which of them it leaves to the receiver
+    MonitoringCoverage {
+        monitoring: Vec<String>,
+        #[serde(default)]
+        subscribed: Vec<String>,
+        #[serde(default)]
+        block: u64,
+    },
+    PositionUpdate {
+        borrower: String,
+        #[serde(default)]
+        collateral_asset: String,
+        #[serde(default)]
+        debt_asset: String,
+        health_factor: String,
+        #[serde(default)]
+        debt_amount: String,
+        block: u64,
+    },
+    RecordRequest {
+        request_id: String,
+        opp_id: String,
+        sections: Vec<RecordSection>,
+    },
+    // body is the signed RecordSet text; signature is empty when the responder couldn't sign
+    RecordResponse {
+        request_id: String,
+        responder: String,
+        body: String,
+        signature: String,
+    },
+}
+
+impl MevMessage {
+    // Remote handler on the receiving node
+    pub fn handler(&self) -> &'static str {
+        match self {
+            MevMessage::NodeAnnouncement { .. } => "ReceiveNodeAnnouncement",
+            MevMessage::OpportunityBroadcast { .. } => "ReceiveOpportunityBroadcast",
+            MevMessage::IntentSubmission { .. } => "ReceiveIntentSubmission",
+            MevMessage::ExecutionReceipt { .. } => "ReceiveExecutionReceipt",
+            MevMessage::KeyRotation { .. } => "ReceiveKeyRotation",
+            MevMessage::NettingStatement { .. } => "ReceiveNettingStatement",
+            MevMessage::NettingAcknowledgment { .. } => "ReceiveNettingAcknowledgment",
+            MevMessage::ExecutorSuccession { .. } => "ReceiveExecutorSuccession",
+            MevMessage::CapitalReserveRequest { .. } => "ReceiveCapitalReserveRequest",
+            MevMessage::CapitalReserveResponse { .. } => "ReceiveCapitalReserveResponse",
+            MevMessage::IntentRevocation { .. } => "ReceiveIntentRevocation",
+            MevMessage::IntentRevocationRejection { .. } => "ReceiveIntentRevocationRejection",
+            MevMessage::SyncRequest { .. } => "ReceiveSyncRequest",
+            MevMessage::SyncResponse { .. } => "ReceiveSyncResponse",
+            MevMessage::OpportunityCancellation { .. } => "ReceiveOpportunityCancellation",
+            MevMessage::MirrorControl { .. } => "ReceiveMirrorControl",
+            MevMessage::MirrorEntries { .. } => "ReceiveMirrorEntries",
+            MevMessage::Ping { .. } => "ReceivePing",
+            MevMessage::OpportunityUpdate { .. } => "ReceiveOpportunityUpdate",
+            MevMessage::OpportunityExpired { .. } => "ReceiveOpportunityExpiry",
+            MevMessage::ProviderShortfall { .. } => "ReceiveProviderShortfall",
+            MevMessage::IntentWindowExtended { .. } => "ReceiveIntentWindowExtended",
+            MevMessage::ExecutionClaim { .. } => "ReceiveExecutionClaim",
+            MevMessage::IntentAmendment { .. } => "ReceiveIntentAmendment",
+            MevMessage::IntentAmendmentRejection { .. } => "ReceiveIntentAmendmentRejection",
+            MevMessage::BackfillRequest { .. } => "ReceiveBackfillRequest",
+            MevMessage::MonitoringCoverage { .. } => "ReceiveMonitoringCoverage",
+            MevMessage::PositionUpdate { .. } => "ReceivePositionUpdate",
+            MevMessage::RecordRequest { .. } => "ReceiveRecordRequest",
+            MevMessage::RecordResponse { .. } => "ReceiveRecordResponse",
+        }
+    }
+    
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MevMessage::NodeAnnouncement { .. } => "NodeAnnouncement",
+            MevMessage::OpportunityBroadcast { .. } => "OpportunityBroadcast",
+            MevMessage::IntentSubmission { .. } => "IntentSubmission",
+            MevMessage::ExecutionReceipt { .. } => "ExecutionReceipt",
+            MevMessage::KeyRotation { .. } => "KeyRotation",
+            MevMessage::NettingStatement { .. } => "NettingStatement",
+            MevMessage::NettingAcknowledgment { .. } => "NettingAcknowledgment",
+            MevMessage::ExecutorSuccession { .. } => "ExecutorSuccession",
+            MevMessage::CapitalReserveRequest { .. } => "CapitalReserveRequest",
+            MevMessage::CapitalReserveResponse { .. } => "CapitalReserveResponse",
+            MevMessage::IntentRevocation { .. } => "IntentRevocation",
+            MevMessage::IntentRevocationRejection { .. } => "IntentRevocationRejection",
+            MevMessage::SyncRequest { .. } => "SyncRequest",
+            MevMessage::SyncResponse { .. } => "SyncResponse",
+            MevMessage::OpportunityCancellation { .. } => "OpportunityCancellation",
+            MevMessage::MirrorControl { .. } => "MirrorControl",
+            MevMessage::MirrorEntries { .. } => "MirrorEntries",
+            MevMessage::Ping { .. } => "Ping",
+            MevMessage::OpportunityUpdate { .. } => "OpportunityUpdate",
+            MevMessage::OpportunityExpired { .. } => "OpportunityExpired",
+            MevMessage::ProviderShortfall { .. } => "ProviderShortfall",
+            MevMessage::IntentWindowExtended { .. } => "IntentWindowExtended",
+            MevMessage::ExecutionClaim { .. } => "ExecutionClaim",
+            MevMessage::IntentAmendment { .. } => "IntentAmendment",
+            MevMessage::IntentAmendmentRejection { .. } => "IntentAmendmentRejection",
+            MevMessage::BackfillRequest { .. } => "BackfillRequest",
+            MevMessage::MonitoringCoverage { .. } => "MonitoringCoverage",
+            MevMessage::PositionUpdate { .. } => "PositionUpdate",
+            MevMessage::RecordRequest { .. } => "RecordRequest",
+            MevMessage::RecordResponse { .. } => "RecordResponse",
+        }
+    }
+    
+    // Parses a message for the handler expecting `expected`, with an error the sender can act on
+    pub fn parse(message_json: &str, expected: &str) -> Result<MevMessage, String> {
+        let malformed = |reason: String| serde_json::json!({
+            "error": "MalformedMessage",
+            "expected": expected,
+            "reason": reason,
+        }).to_string();
+        let message: MevMessage = serde_json::from_str(message_json).map_err(|e| malformed(e.to_string()))?;
+        if message.kind() != expected {
+            return Err(malformed(format!("{} sent to the {} handler", message.kind(), expected)));
+        }
+        Ok(message)
+    }
+}
+
+// Accepts an embedded payload either as a JSON string or inline, as older peers sent both
+fn embedded_json_string<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(json) => json,
+        other => other.to_string(),
+    })
+}
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct NodeInfo {
@@ -53,13 +545,64 @@ pub struct NodeInfo {
     pub capital_assets: Vec<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum NodeRole {
     Finder,
     CapitalProvider,
     Executor,
 }
 
+// Where an opportunity came from. Set by the submission path on the originating node and carried
+// unchanged by every node that forwards it.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum OpportunityOrigin {
+    Bot { bridge_version: String },
+    Manual { operator_note: String },
+    // Provenance unknown: forwarded without it, or from a peer that predates the field
+    #[default]
+    Relay,
+}
+
+impl OpportunityOrigin {
+    pub fn class(&self) -> OriginClass {
+        match self {
+            OpportunityOrigin::Bot { .. } => OriginClass::Bot,
+            OpportunityOrigin::Manual { .. } => OriginClass::Manual,
+            OpportunityOrigin::Relay => OriginClass::Relay,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OriginClass {
+    Bot,
+    Manual,
+    Relay,
+}
+
+// Extra requirements a capital provider applies to finds of one origin class
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct OriginPolicy {
+    // Replaces min_profit_threshold_usd for this class
+    #[serde(default)]
+    pub min_profit_threshold_usd: Option<String>,
+    // Commit only once our own on-chain read confirms the victim is liquidatable
+    #[serde(default)]
+    pub require_independent_validation: bool,
+}
+
+// How finds of one origin class fared on this node
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct OriginStats {
+    pub received: u64,
+    pub committed: u64,
+    pub executed: u64,
+    pub expired_unexecuted: u64,
+    // Why we declined to commit, by failed rule
+    pub skipped: BTreeMap<String, u64>,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ProceedsBreakdown {
     pub asset: String,
@@ -68,6 +611,154 @@ pub struct ProceedsBreakdown {
     pub finder_fee: String,
     pub executor_fee: String,
     pub net_profit: String,
+    // How the finder fee is divided when several finders claimed the same opportunity
+    #[serde(default)]
+    pub finder_fee_policy: Option<DuplicateFinderPolicy>,
+    #[serde(default)]
+    pub finder_fee_recipients: Vec<FinderFeeShare>,
+    // The same fee split applied to each seized collateral in its own units, so providers can
+    // be paid (or swap) leg by leg; gas is charged once, on the aggregate above
+    #[serde(default)]
+    pub legs: Vec<LegProceeds>,
+    // Left over from flooring provider shares, owed to the opportunity's dust sink
+    #[serde(default)]
+    pub dust: String,
+    #[serde(default)]
+    pub dust_sink: DustSink,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LegProceeds {
+    pub asset: String,
+    pub seized: String,
+    pub finder_fee: String,
+    pub executor_fee: String,
+    pub provider_pool: String,
+    #[serde(default)]
+    pub dust: String,
+}
+
+// Pool-level rule for who earns the finder fee when several finders report the same opportunity
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub enum DuplicateFinderPolicy {
+    #[default]
+    FirstSeen,
+    BestTerms,
+    Split,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FinderFeeShare {
+    pub finder: String,
+    pub share_bps: u16,
+}
+
+// One finder's report of an opportunity, linked under the canonical record
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FinderClaim {
+    pub finder: String,
+    pub opp_id: String,
+    pub seen_at: String,
+    pub finder_fee_bps: u16,
+    pub opportunity: String,
+}
+
+impl FinderClaim {
+    // Better terms for capital: lower finder fee, then higher estimated profit, then earlier
+    fn terms_key(&self) -> (u16, std::cmp::Reverse<U256>, u64) {
+        let profit = serde_json::from_str::<AaveLiquidationOpportunity>(&self.opportunity)
+            .ok()
+            .and_then(|opp| opp.estimated_profit_usd.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        (self.finder_fee_bps, std::cmp::Reverse(profit), self.seen_at.parse::<u64>().unwrap_or(u64::MAX))
+    }
+}
+
+// Line-level differences between our statement and a peer's for the same parties and period
+pub fn diff_statements(ours: &SettlementStatement, theirs: &SettlementStatement) -> Vec<StatementDiscrepancy> {
+    let key = |line: &StatementLine| (line.opp_id.clone(), line.provider.clone());
+    let our_lines: BTreeMap<_, _> = ours.lines.iter().map(|line| (key(line), line)).collect();
+    let their_lines: BTreeMap<_, _> = theirs.lines.iter().map(|line| (key(line), line)).collect();
+    
+    let keys: BTreeSet<_> = our_lines.keys().chain(their_lines.keys()).cloned().collect();
+    keys.into_iter()
+        .filter_map(|(opp_id, provider)| {
+            let ours = our_lines.get(&(opp_id.clone(), provider.clone())).map(|line| (*line).clone());
+            let theirs = their_lines.get(&(opp_id.clone(), provider.clone())).map(|line| (*line).clone());
+            let kind = match (&ours, &theirs) {
+                (Some(a), Some(b)) if a == b => return None,
+                (Some(_), Some(_)) => DiscrepancyKind::Mismatch,
+                (None, _) => DiscrepancyKind::MissingFromOurs,
+                (_, None) => DiscrepancyKind::MissingFromTheirs,
+            };
+            Some(StatementDiscrepancy { opp_id, provider, kind, ours, theirs })
+        })
+        .collect()
+}
+
+pub fn best_claim(claims: &[FinderClaim]) -> Option<&FinderClaim> {
+    claims.iter().min_by_key(|claim| claim.terms_key())
+}
+
+pub fn duplicate_key(strategy_id: &str, victim_address: &str, repay_asset: &str) -> String {
+    format!("{}:{}:{}", strategy_id, victim_address.to_lowercase(), repay_asset.to_lowercase())
+}
+
+// Folds a finder's update into a stored opportunity payload; the deadline only ever extends
+fn merge_opportunity_update(stored: &str, update: &AaveLiquidationOpportunity) -> Option<String> {
+    let mut stored: AaveLiquidationOpportunity = serde_json::from_str(stored).ok()?;
+    if !stored.victim_address.eq_ignore_ascii_case(&update.victim_address)
+        || !stored.repay_asset.eq_ignore_ascii_case(&update.repay_asset)
+        || !stored.seize_asset.eq_ignore_ascii_case(&update.seize_asset) {
+        return None;
+    }
+    // Legs may be re-sized or dropped as prices move, but the update can't seize new collateral
+    let stored_legs = stored.seize_legs();
+    if !update.seize_legs().iter().all(|leg| stored_legs.iter().any(|stored| stored.asset.eq_ignore_ascii_case(&leg.asset))) {
+        return None;
+    }
+    stored.seize_assets = update.seize_assets.clone();
+    stored.estimated_profit_usd = update.estimated_profit_usd.clone();
+    stored.health_factor = update.health_factor.clone();
+    stored.max_repay_amount = update.max_repay_amount.clone();
+    stored.min_bonus_bps = update.min_bonus_bps;
+    stored.deadline_block = stored.deadline_block.max(update.deadline_block);
+    serde_json::to_string(&stored).ok()
+}
+
+// Finder-fee split across the claims of one opportunity under the given policy
+pub fn finder_fee_recipients(policy: DuplicateFinderPolicy, claims: &[FinderClaim]) -> Vec<FinderFeeShare> {
+    let first_seen = || claims.iter().min_by_key(|claim| claim.seen_at.parse::<u64>().unwrap_or(u64::MAX));
+    match policy {
+        DuplicateFinderPolicy::FirstSeen => first_seen()
+            .map(|claim| vec![FinderFeeShare { finder: claim.finder.clone(), share_bps: 10_000 }])
+            .unwrap_or_default(),
+        DuplicateFinderPolicy::BestTerms => best_claim(claims)
+            .map(|claim| vec![FinderFeeShare { finder: claim.finder.clone(), share_bps: 10_000 }])
+            .unwrap_or_default(),
+        DuplicateFinderPolicy::Split => {
+            let mut finders: Vec<&String> = Vec::new();
+            let mut ordered: Vec<&FinderClaim> = claims.iter().collect();
+            ordered.sort_by_key(|claim| claim.seen_at.parse::<u64>().unwrap_or(u64::MAX));
+            for claim in ordered {
+                if !finders.contains(&&claim.finder) {
+                    finders.push(&claim.finder);
+                }
+            }
+            if finders.is_empty() {
+                return Vec::new();
+            }
+            // Equal shares; the rounding remainder goes to the first finder seen
+            let share = 10_000 / finders.len() as u16;
+            let remainder = 10_000 - share * finders.len() as u16;
+            finders.into_iter().enumerate()
+                .map(|(i, finder)| FinderFeeShare {
+                    finder: finder.clone(),
+                    share_bps: if i == 0 { share + remainder } else { share },
+                })
+                .collect()
+        }
+    }
 }
 
 // AAVE LIQUIDATION STRATEGY TYPES
@@ -82,6 +773,113 @@ pub struct AaveLiquidationOpportunity {
     pub health_factor: String,
     pub deadline_block: u64,
     pub estimated_profit_usd: String,
+    // Every collateral the liquidation seizes, best bonus first; `seize_asset` and `min_bonus_bps`
+    // stay the first leg and the lowest leg bonus so single-asset peers still read it. Empty on
+    // opportunities from before multi-collateral support.
+    #[serde(default)]
+    pub seize_assets: Vec<SeizeLeg>,
+    // Rounding rules for splitting the proceeds, set by the finder
+    #[serde(default)]
+    pub distribution: DistributionScheme,
+}
+
+// One collateral leg: the asset seized, how much of it (in its own units) the full repay is
+// expected to bring in, and the reserve's liquidation bonus
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SeizeLeg {
+    pub asset: String,
+    pub expected_amount: String,
+    pub bonus_bps: u16,
+}
+
+// Collateral a leg actually brought in on execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SeizedLeg {
+    pub asset: String,
+    pub amount: String,
+}
+
+impl AaveLiquidationOpportunity {
+    // The seize legs, with the single-asset form read as a one-element list; stored and wire
+    // opportunities without `seize_assets` go through here rather than being rewritten
+    pub fn seize_legs(&self) -> Vec<SeizeLeg> {
+        if !self.seize_assets.is_empty() {
+            return self.seize_assets.clone();
+        }
+        vec![SeizeLeg {
+            asset: self.seize_asset.clone(),
+            expected_amount: "0".to_string(),
+            bonus_bps: self.min_bonus_bps,
+        }]
+    }
+    
+    // Everything checked before an opportunity is stored: field formats, the seize legs, the health
+    // factor, and a deadline still ahead of `head` when we know it
+    pub fn validate(&self, head: Option<u64>) -> Result<(), String> {
+        self.check_fields()?;
+        self.check_seize_legs()?;
+        self.check_health_factor()?;
+        if let Some(head) = head.filter(|head| self.deadline_block <= *head) {
+            return Err(format!("deadline_block {} is not after the chain head {}", self.deadline_block, head));
+        }
+        Ok(())
+    }
+    
+    // Addresses parse as addresses, amounts as U256, and bonuses stay within 10000 bps
+    pub fn check_fields(&self) -> Result<(), String> {
+        if self.opp_id.is_empty() {
+            return Err("opp_id is empty".to_string());
+        }
+        let addresses = [
+            ("victim_address", &self.victim_address),
+            ("repay_asset", &self.repay_asset),
+            ("seize_asset", &self.seize_asset),
+        ];
+        let legs = self.seize_assets.iter().map(|leg| ("seize_assets.asset", &leg.asset));
+        for (field, value) in addresses.into_iter().chain(legs) {
+            value.parse::<EthAddress>().map_err(|_| format!("{} is not an address: {:?}", field, value))?;
+        }
+        for (field, value) in [("max_repay_amount", &self.max_repay_amount), ("estimated_profit_usd", &self.estimated_profit_usd)] {
+            value.parse::<U256>().map_err(|_| format!("{} is not a number: {:?}", field, value))?;
+        }
+        let mut bonuses = std::iter::once(self.min_bonus_bps).chain(self.seize_assets.iter().map(|leg| leg.bonus_bps));
+        if let Some(bonus) = bonuses.find(|bonus| *bonus > 10_000) {
+            return Err(format!("Bonus of {} bps exceeds 10000", bonus));
+        }
+        Ok(())
+    }
+    
+    // Declared legs must agree with the single-asset fields older peers read
+    pub fn check_seize_legs(&self) -> Result<(), String> {
+        let Some(first) = self.seize_assets.first() else {
+            return Ok(());
+        };
+        if !first.asset.eq_ignore_ascii_case(&self.seize_asset) {
+            return Err(format!("First seize leg {} is not the seize asset {}", first.asset, self.seize_asset));
+        }
+        for leg in &self.seize_assets {
+            leg.expected_amount.parse::<U256>()
+                .map_err(|_| format!("Unreadable expected amount for {}: {}", leg.asset, leg.expected_amount))?;
+        }
+        let lowest_bonus = self.seize_assets.iter().map(|leg| leg.bonus_bps).min().unwrap_or(0);
+        if lowest_bonus != self.min_bonus_bps {
+            return Err(format!("min_bonus_bps {} is not the lowest leg bonus {}", self.min_bonus_bps, lowest_bonus));
+        }
+        Ok(())
+    }
+    
+    // Aave reports u256 max for debt-free accounts; neither that sentinel nor zero is liquidatable
+    pub fn check_health_factor(&self) -> Result<U256, String> {
+        let health_factor = self.health_factor.parse::<U256>()
+            .map_err(|_| format!("Unreadable health factor: {}", self.health_factor))?;
+        if health_factor == U256::MAX {
+            return Err("Health factor is the no-debt sentinel".to_string());
+        }
+        if health_factor.is_zero() {
+            return Err("Health factor is zero".to_string());
+        }
+        Ok(health_factor)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -91,6 +889,32 @@ pub struct AaveLiquidationIntent {
     pub max_amount: String,
     pub min_bonus_bps: u16,
     pub expires_block: u64,
+    // Opt-in for intents priced in another asset that the executor must swap into the repay asset
+    #[serde(default)]
+    pub requires_swap: bool,
+    // Sized from demo capital; only peers in paper-trading mode accept these
+    #[serde(default)]
+    pub simulated: bool,
+    // Share of its pro-rata slice of the provider pool this capital claims (0 = all of it);
+    // the rest goes to the executor, which funds the cheapest bids first
+    #[serde(default)]
+    pub fee_bps: u16,
+}
+
+impl AaveLiquidationIntent {
+    pub fn matches_repay_asset(&self, opportunity: &AaveLiquidationOpportunity) -> bool {
+        self.requires_swap || self.asset.eq_ignore_ascii_case(&opportunity.repay_asset)
+    }
+    
+    // The intent's capital only goes in at a bonus at least as high as it asks
+    pub fn accepts_bonus(&self, opportunity: &AaveLiquidationOpportunity) -> bool {
+        self.min_bonus_bps <= opportunity.min_bonus_bps
+    }
+    
+    // Whether the intent can fund the opportunity as it now stands
+    pub fn can_fund(&self, opportunity: &AaveLiquidationOpportunity) -> bool {
+        self.matches_repay_asset(opportunity) && self.accepts_bonus(opportunity)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -102,6 +926,17 @@ pub struct AaveLiquidationReceipt {
     pub used_amounts: Vec<CapitalUsage>,
     pub total_proceeds: String,
     pub gas_paid_usdc: String,
+    #[serde(default)]
+    pub provider_rule: Option<ProviderRuleOutcome>,
+    // Declared allocation policy, input and result, so providers can replay it
+    #[serde(default)]
+    pub allocation: Option<AllocationRecord>,
+    // Bridge wallet that ran (or refused) the execution
+    #[serde(default)]
+    pub execution_wallet: Option<String>,
+    // Collateral realized per seize leg; empty from bridges that predate multi-collateral support
+    #[serde(default)]
+    pub seized: Vec<SeizedLeg>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -111,711 +946,11708 @@ pub enum ExecutionStatus {
     Pending,
 }
 
+impl ExecutionStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExecutionStatus::Success => "Success",
+            ExecutionStatus::Failed(_) => "Failed",
+            ExecutionStatus::Pending => "Pending",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CapitalUsage {
     pub node_id: String,
     pub asset: String,
     pub amount_used: String,
     pub profit_share: String,
+    // Fee bid of the intent this capital came from
+    #[serde(default)]
+    pub fee_bps: u16,
 }
 
-// NODE STATE MANAGEMENT
-#[derive(Default, Serialize, Deserialize, Debug)]
-pub struct HyperMevApp {
-    // Node configuration
-    pub node_config: NodeConfig,
-    pub active_strategy: Option<StrategyId>,
-    
-    // P2P state
-    pub known_peers: HashSet<String>,
-    
-    // MEV coordination state (in-memory, keyed by opp_id) 
-    pub active_opportunities: HashMap<String, OpportunityData>,
-    pub submitted_intents: HashMap<String, Vec<IntentData>>,
-    pub execution_receipts: HashMap<String, ReceiptData>,
-    
-    // Capital management - using String for WIT compatibility
-    pub available_balances: HashMap<String, String>,
-    pub committed_amounts: HashMap<String, String>,
-    
-    // Strategy state
-    pub aave_strategy_config: AaveStrategyConfig,
-    
-    // WebSocket connection to Artemis MEV bot
-    #[serde(skip)]
-    pub artemis_channel_id: Option<u32>,
-    
-    // HTTP server for WebSocket connections
-    #[serde(skip)]
-    pub http_server: Option<HttpServer>,
+// Executor-side requirement: opportunities of at least threshold_usd need min_providers distinct providers
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ProviderRequirement {
+    pub threshold_usd: String,
+    pub min_providers: u32,
 }
 
+// The provider requirement that governed an execution, echoed back on the receipt
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct NodeConfig {
-    pub node_id: String,
-    pub publisher: String,
-    pub enabled_strategies: Vec<StrategyId>,
-    pub finder_enabled: bool,
-    pub capital_provider_enabled: bool,
-    pub executor_enabled: bool,
-    pub finder_fee_bps: u16,
-    pub executor_fee_bps: u16,
-    pub min_profit_threshold_usd: String,
-    pub max_gas_price_gwei: String,
-    pub aave_pool_address: String,
+pub struct ProviderRuleOutcome {
+    pub threshold_usd: String,
+    pub min_providers: u32,
+    pub distinct_providers: u32,
+    pub fallback_used: bool,
 }
 
-impl Default for NodeConfig {
-    fn default() -> Self {
-        Self {
-            node_id: String::new(),
-            publisher: "skeleton.os".to_string(),
-            enabled_strategies: vec!["aave-liquidation".to_string()],
-            finder_enabled: true,
-            capital_provider_enabled: true,
-            executor_enabled: true,
-            finder_fee_bps: 100,
-            executor_fee_bps: 50,
-            min_profit_threshold_usd: "10000000000000000000".to_string(),
-            max_gas_price_gwei: "50".to_string(),
-            aave_pool_address: "0x87870bE17b9C61bE44b13bc108ad8E2C16684e78".to_string(),
-        }
-    }
+// Broadcast while an execution waits on more participants so providers can rally others
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ProviderShortfall {
+    pub distinct_providers: u32,
+    pub min_providers: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct OpportunityData {
-    pub opportunity: String,
-    pub strategy_id: StrategyId,
-    pub finder_node: String,
-    pub received_at: String,
+// The executor's single extension of an opportunity's intent-collection window, granted when
+// coverage is close enough that a little more time is likely to fill it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct IntentWindowExtension {
+    pub new_deadline: u64,
+    pub coverage: String,
+    pub max_repay_amount: String,
+    pub extended_by: String,
+    pub extended_at: String,
+}
+
+const INTENT_WINDOW_EXTENSION_BLOCKS: u64 = 2;
+// Blocks that must remain before the execution deadline when an opportunity is dispatched
+const DISPATCH_SAFETY_MARGIN_BLOCKS: u64 = 2;
+
+// Optional checks a fast-path rule may waive
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum SkippableCheck {
+    // Waiting for min_distinct_providers_above_usd to be met
+    ProviderWait,
 }
 
+// Streamlined handling for small, high-confidence opportunities; all set criteria must hold
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct IntentData {
-    pub intent: String,
-    pub submitter_node: String,
-    pub max_amount: String,
-    pub expires_block: u64,
-    pub received_at: String,
+pub struct FastPathRule {
+    pub max_size_usd: String,
+    // Criteria we can't yet evaluate (no confidence data) never match when set
+    #[serde(default)]
+    pub min_finder_reputation_tier: Option<u8>,
+    #[serde(default)]
+    pub min_confidence_bps: Option<u16>,
+    pub skip_checks: Vec<SkippableCheck>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ReceiptData {
-    pub receipt: String,
-    pub executor_node: String,
-    pub our_proceeds: String,
-    pub verified_at: String,
+// Fast-path sizes can never exceed this, whatever the config says (10,000 USDC)
+const FAST_PATH_SIZE_CEILING_USD: &str = "10000000000";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum EvaluationPath {
+    Full,
+    Fast { rule_index: usize, skipped_checks: Vec<SkippableCheck> },
 }
 
-#[derive(Default, Serialize, Deserialize, Debug)]
-pub struct AaveStrategyConfig {
-    pub monitored_positions: HashMap<String, PositionData>,
-    pub subscription_ids: Vec<u64>,
-    pub min_health_factor: String,
+// Pool manifest published by a pool coordinator, listing members and recommended settings
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PoolManifest {
+    pub pool_id: String,
+    pub version: u64,
+    pub members: Vec<PoolMember>,
+    #[serde(default)]
+    pub fee_bands: Vec<FeeBand>,
+    #[serde(default)]
+    pub bootstrap_peers: Vec<String>,
+    // Pool-wide finder-fee policy for duplicate finds; members keep their own setting when absent
+    #[serde(default)]
+    pub duplicate_finder_policy: Option<DuplicateFinderPolicy>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PositionData {
-    pub borrower: String,
-    pub collateral_asset: String,
-    pub debt_asset: String,
-    pub last_health_factor: String,
-    pub last_updated: String,
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PoolMember {
+    pub node: String,
+    pub publisher: String,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub payout_address: Option<String>,
 }
 
-// HYPERPROCESS APPLICATION IMPLEMENTATION
-#[hyperprocess(
-    name = "Hyper-MEV P2P Pool",
-    ui = Some(HttpBindingConfig::default()),
-    endpoints = vec![
-        Binding::Http { 
-            path: "/api", 
+// Recommended fee range for a role ("finder" or "executor")
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FeeBand {
+    pub role: String,
+    pub min_bps: u16,
+    pub max_bps: u16,
+}
+
+// Manifest as distributed: the exact manifest text plus the coordinator's hex signature over it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SignedPoolManifest {
+    pub manifest: String,
+    pub signature: String,
+}
+
+// Key and payout details we hold for a peer, and how far we trust them
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PeerRegistration {
+    pub public_key: Option<String>,
+    pub payout_address: Option<String>,
+    pub status: RegistrationStatus,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum RegistrationStatus {
+    // Asserted by a pool manifest, pending live verification with the peer
+    ManifestAsserted { pool_id: String, version: u64 },
+    Verified,
+}
+
+// Page size of the listing endpoints when none is asked for, and the most they return at once
+const DEFAULT_PAGE_LIMIT: usize = 50;
+const MAX_PAGE_LIMIT: usize = 200;
+
+// A peer message body that was not JSON, kept for the operator to inspect
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DeadLetter {
+    pub from: String,
+    pub handler: String,
+    pub received_at: String,
+    pub body_len: usize,
+    // The first DEAD_LETTER_SAMPLE_BYTES bytes, non-printable ones as \xNN
+    pub sample: String,
+    pub error: String,
+}
+
+const DEAD_LETTER_CAPACITY: usize = 200;
+const DEAD_LETTER_SAMPLE_BYTES: usize = 64;
+
+fn escaped_sample(body: &str) -> String {
+    body.bytes().take(DEAD_LETTER_SAMPLE_BYTES)
+        .map(|byte| match byte {
+            b' '..=b'~' if byte != b'\\' => (byte as char).to_string(),
+            _ => format!("\\x{:02x}", byte),
+        })
+        .collect()
+}
+
+// Most sequence numbers kept per finder as outstanding gaps, and most replayed per backfill request
+const MAX_TRACKED_GAPS: u64 = 1000;
+const MAX_BACKFILL_BATCH: usize = 100;
+
+// Most live opportunities sent in answer to a state sync request, newest first, and how long we
+// wait for the answer to one before asking again
+const MAX_SYNC_OPPORTUNITIES: usize = 100;
+const SYNC_RESPONSE_TIMEOUT_SECS: u64 = 60;
+
+// Last opportunity originated for a (strategy, victim) pair; repeat crossings update it instead
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct OriginationRecord {
+    pub opp_id: String,
+    pub last_seen: u64,
+}
+
+pub fn origination_key(strategy_id: &str, victim_address: &str) -> String {
+    format!("{}:{}", strategy_id, victim_address.to_lowercase())
+}
+
+// Most opportunity broadcasts remembered for deduplication
+const MAX_SEEN_BROADCASTS: usize = 5000;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SeenBroadcast {
+    // Hash of the opportunity payload, so a re-priced broadcast isn't mistaken for a repeat
+    pub digest: String,
+    pub seen_at: u64,
+}
+
+// Opportunity broadcasts already handled, so copies arriving again over another path or as
+// backfill are acknowledged and otherwise ignored
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SeenBroadcasts {
+    pub entries: HashMap<String, SeenBroadcast>,
+    pub duplicates_dropped: u64,
+}
+
+impl SeenBroadcasts {
+    pub fn is_duplicate(&self, opp_id: &str, digest: &str) -> bool {
+        self.entries.get(opp_id).is_some_and(|seen| seen.digest == digest)
+    }
+    
+    pub fn record(&mut self, opp_id: &str, digest: String, now: u64) {
+        if !self.entries.contains_key(opp_id) && self.entries.len() >= MAX_SEEN_BROADCASTS {
+            let oldest = self.entries.iter()
+                .min_by_key(|(_, seen)| seen.seen_at)
+                .map(|(opp_id, _)| opp_id.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(opp_id.to_string(), SeenBroadcast { digest, seen_at: now });
+    }
+    
+    pub fn prune(&mut self, now: u64, ttl_secs: u64) {
+        self.entries.retain(|_, seen| now.saturating_sub(seen.seen_at) < ttl_secs);
+    }
+}
+
+// Hop count to forward a broadcast with, or None once it has travelled `max_hops`; absent counts
+// come from peers that predate the field and are taken as the finder's own send
+pub fn forward_hop(hop_count: Option<u8>, max_hops: u8) -> Option<u8> {
+    let hops = hop_count.unwrap_or(0);
+    (hops < max_hops).then(|| hops + 1)
+}
+
+// Delivery tracking for one finder's opportunity sequence
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct FinderSequenceStats {
+    pub highest_seq: u64,
+    pub received: u64,
+    // Sequence numbers skipped over and not yet backfilled
+    pub missing: BTreeSet<u64>,
+    // Total broadcasts we apparently never received, backfilled or not
+    pub gaps_detected: u64,
+    pub backfilled: u64,
+    // Opportunities from this finder that expired without a receipt
+    #[serde(default)]
+    pub expired_unexecuted: u64,
+    // Broadcasts from this peer by the origin they declared
+    #[serde(default)]
+    pub by_origin: BTreeMap<OriginClass, u64>,
+    // New opportunities accepted from this finder within the last hour, and those refused over quota
+    #[serde(default)]
+    pub recent_accepts: VecDeque<u64>,
+    #[serde(default)]
+    pub quota_rejected: u64,
+    // Broadcasts from this peer refused as invalid opportunities
+    #[serde(default)]
+    pub invalid_opportunities: u64,
+}
+
+impl FinderSequenceStats {
+    pub fn accepted_within_hour(&mut self, now: u64) -> usize {
+        while self.recent_accepts.front().map_or(false, |at| now.saturating_sub(*at) >= 3600) {
+            self.recent_accepts.pop_front();
+        }
+        self.recent_accepts.len()
+    }
+    
+    // Records a sequence number; returns the newly missing range when it skipped ahead
+    pub fn observe(&mut self, seq: u64) -> Option<(u64, u64)> {
+        if self.missing.remove(&seq) {
+            self.received += 1;
+            self.backfilled += 1;
+            return None;
+        }
+        if seq <= self.highest_seq {
+            return None; // Repeat broadcast
+        }
+        
+        // The first sequence we see from a finder is the baseline; earlier ones predate us
+        let first = self.received == 0;
+        let previous = self.highest_seq;
+        self.received += 1;
+        self.highest_seq = seq;
+        if first || seq == previous + 1 {
+            return None;
+        }
+        
+        let (from, to) = (previous + 1, seq - 1);
+        self.gaps_detected += to - from + 1;
+        let tracked_from = from.max(to.saturating_sub(MAX_TRACKED_GAPS - 1));
+        self.missing.extend(tracked_from..=to);
+        while self.missing.len() as u64 > MAX_TRACKED_GAPS {
+            self.missing.pop_first();
+        }
+        Some((tracked_from, to))
+    }
+}
+
+// How many opportunities we take from one finder: concurrently stored, and new ones per hour
+// (0 = unlimited). Both are multiplied by the finder's reputation tier plus one.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct FinderQuota {
+    pub max_live: usize,
+    pub max_per_hour: usize,
+}
+
+impl Default for FinderQuota {
+    fn default() -> Self {
+        Self {
+            max_live: 20,
+            max_per_hour: 200,
+        }
+    }
+}
+
+impl FinderQuota {
+    pub fn scaled(&self, tier: u8) -> (usize, usize) {
+        let factor = tier as usize + 1;
+        (self.max_live.saturating_mul(factor), self.max_per_hour.saturating_mul(factor))
+    }
+}
+
+// How we bid as capital provider. With auto_bid off, finds that pass evaluation wait as pending
+// review until the operator bids on them through submit_intent_manual; the caps size automatic
+// bids only (0 = no limit on concurrent commitments).
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct BidPolicy {
+    pub auto_bid: bool,
+    // Share of our free balance in the repay asset one opportunity may take
+    pub max_fraction_of_balance_per_opp_bps: u16,
+    // Largest bid on any one opportunity, by repay asset
+    pub max_absolute_per_opp: BTreeMap<String, String>,
+    pub max_concurrent_commitments: usize,
+}
+
+impl Default for BidPolicy {
+    fn default() -> Self {
+        Self {
+            auto_bid: true,
+            max_fraction_of_balance_per_opp_bps: 10_000,
+            max_absolute_per_opp: BTreeMap::new(),
+            max_concurrent_commitments: 0,
+        }
+    }
+}
+
+impl BidPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_fraction_of_balance_per_opp_bps == 0 || self.max_fraction_of_balance_per_opp_bps > 10_000 {
+            return Err("max_fraction_of_balance_per_opp_bps must be between 1 and 10000".to_string());
+        }
+        for (asset, cap) in &self.max_absolute_per_opp {
+            cap.parse::<U256>().map_err(|_| format!("Invalid per-opportunity cap for {}: {}", asset, cap))?;
+        }
+        Ok(())
+    }
+    
+    // Most an automatic bid in `asset` may be, given our free balance in it
+    pub fn bid_cap(&self, asset: &str, free_balance: U256) -> U256 {
+        let fraction = free_balance * U256::from(self.max_fraction_of_balance_per_opp_bps) / U256::from(10_000u64);
+        let absolute = self.max_absolute_per_opp.iter()
+            .find(|(cap_asset, _)| cap_asset.eq_ignore_ascii_case(asset))
+            .and_then(|(_, cap)| cap.parse::<U256>().ok())
+            .unwrap_or(U256::MAX);
+        fraction.min(absolute)
+    }
+}
+
+// What executing an opportunity costs at a given gas price: gas units at that price, valued at
+// native_price_usd per ETH, which lands in the 18-decimal USD units of estimated_profit_usd
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GasCostModel {
+    pub execution_gas_units: u64,
+    // Whole USD per ETH
+    pub native_price_usd: String,
+}
+
+impl Default for GasCostModel {
+    fn default() -> Self {
+        Self {
+            execution_gas_units: 600_000,
+            native_price_usd: "3000".to_string(),
+        }
+    }
+}
+
+impl GasCostModel {
+    pub fn validate(&self) -> Result<(), String> {
+        self.native_price_usd.parse::<U256>()
+            .map_err(|_| format!("Invalid native_price_usd: {}", self.native_price_usd))?;
+        Ok(())
+    }
+    
+    pub fn execution_cost_usd(&self, gas_price_wei: U256) -> U256 {
+        let native_price = self.native_price_usd.parse::<U256>().unwrap_or(U256::ZERO);
+        gas_price_wei * U256::from(self.execution_gas_units) * native_price
+    }
+}
+
+// On-chain spot checks made from this node through the platform's eth provider, so a node
+// without a bridge can still check what peers tell it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct EthCheckConfig {
+    pub chain_id: u64,
+    pub check_health_factor: bool,
+    pub check_receipts: bool,
+    pub check_balances: bool,
+    // Address holding the funds behind our recorded available_balances
+    pub wallet_address: Option<String>,
+    pub calls_per_minute: u32,
+    // Contracts besides aave_pool_address a verified receipt's transaction may be sent to
+    pub liquidation_contracts: Vec<String>,
+    // Blocks past its own the head must reach before a counted receipt is final
+    pub required_confirmations: u64,
+}
+
+impl Default for EthCheckConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: 1,
+            check_health_factor: false,
+            check_receipts: false,
+            check_balances: false,
+            wallet_address: None,
+            calls_per_minute: 30,
+            liquidation_contracts: Vec::new(),
+            required_confirmations: 3,
+        }
+    }
+}
+
+// What our own eth query said about a receipt's transaction
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum ChainVerification {
+    Confirmed { block_number: u64 },
+    Reverted,
+    NotFound,
+    // Mined, but not in the block the receipt claims
+    BlockMismatch { claimed: u64, mined: u64 },
+    // Sent to neither the Aave pool nor a configured liquidation contract
+    UnexpectedTarget { to: Option<String> },
+    // The check couldn't run; the bridge's word stands
+    Unchecked { reason: String },
+}
+
+// Whether a peer's receipt may move our balances and metrics. Successful receipts wait on the
+// chain check while check_receipts is on; receipts that need none, and those kept before
+// verification existed, are Verified.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub enum ReceiptVerification {
+    #[default]
+    Verified,
+    // The check couldn't run yet; retried each maintenance tick
+    Pending,
+    Rejected { verification: ChainVerification },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BalanceReconciliation {
+    pub onchain: String,
+    pub recorded: String,
+    pub checked_at: String,
+    // Recorded operator capital exceeds what the wallet holds
+    pub shortfall: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct EthDegradation {
+    pub error: String,
+    pub since: String,
+}
+
+#[derive(Default, Debug)]
+pub struct EthCallBudget {
+    pub window_start: u64,
+    pub used: u32,
+}
+
+const ETH_REQUEST_TIMEOUT_SECS: u64 = 10;
+const BALANCE_RECONCILE_INTERVAL_SECS: u64 = 600;
+// Peer receipts awaiting their chain check that are retried per maintenance tick
+const MAX_RECEIPT_VERIFICATIONS_PER_TICK: usize = 10;
+// getUserAccountData(address) and balanceOf(address)
+const AAVE_GET_USER_ACCOUNT_DATA: [u8; 4] = [0xbf, 0x92, 0x85, 0x7c];
+const ERC20_BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+// Calldata for a single-address-argument call
+fn address_calldata(selector: [u8; 4], address: &str) -> Option<Vec<u8>> {
+    let address = address.parse::<EthAddress>().ok()?;
+    let mut data = selector.to_vec();
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(address.as_slice());
+    Some(data)
+}
+
+// Peers shared per announcement
+const PEER_EXCHANGE_LIMIT: usize = 20;
+// Least time between asking the same unannounced sender for its announcement
+const ANNOUNCEMENT_REQUEST_INTERVAL_SECS: u64 = 60;
+
+// A peer we know of; node_info stays empty until it announces itself
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct PeerInfo {
+    #[serde(default)]
+    pub node_info: Option<NodeInfo>,
+    #[serde(default)]
+    pub capabilities: Vec<StrategyId>,
+    #[serde(default)]
+    pub added_at: String,
+    #[serde(default)]
+    pub last_seen: Option<String>,
+    // Heartbeat rounds in a row without hearing from the peer
+    #[serde(default)]
+    pub missed_heartbeats: u32,
+    // Set once it missed heartbeat_miss_limit rounds; broadcasts skip it until it answers again
+    #[serde(default)]
+    pub unreachable_since: Option<String>,
+    // Downtime the peer announced; it stays a peer, but isn't trusted to execute inside the window
+    #[serde(default)]
+    pub maintenance: Option<MaintenanceWindow>,
+    // Networking key from its signed announcement, checked against the PKI. Once set, the
+    // peer's unsigned announcements, intents and receipts are refused.
+    #[serde(default)]
+    pub public_key: Option<String>,
+    // App signing key its signatures are countersigned with, from its first announcement to carry
+    // one; replaced only by a KeyRotation signed with it
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    // Key its last rotation replaced, honoured until the grace window ends
+    #[serde(default)]
+    pub retired_signing_key: Option<RetiredKey>,
+}
+
+impl PeerInfo {
+    fn pending(added_at: String) -> Self {
+        Self { added_at, ..Self::default() }
+    }
+    
+    pub fn status(&self) -> &'static str {
+        if self.unreachable_since.is_some() {
+            "unreachable"
+        } else if self.node_info.is_some() {
+            "active"
+        } else {
+            "pending"
+        }
+    }
+    
+    pub fn reachable(&self) -> bool {
+        self.unreachable_since.is_none()
+    }
+    
+    fn note_seen(&mut self, now: String) {
+        self.last_seen = Some(now);
+        self.missed_heartbeats = 0;
+        self.unreachable_since = None;
+    }
+    
+    pub fn roles(&self) -> &[NodeRole] {
+        self.node_info.as_ref().map_or(&[], |info| info.roles.as_slice())
+    }
+}
+
+// Peers listed in an announcement to `recipient`: our reachable peers other than it, in a stable
+// order, up to PEER_EXCHANGE_LIMIT
+pub fn peers_to_share(known_peers: &HashMap<String, PeerInfo>, recipient: &str) -> Vec<String> {
+    let mut shared: Vec<String> = known_peers.iter()
+        .filter(|(peer, info)| info.reachable() && *peer != recipient)
+        .map(|(peer, _)| peer.clone())
+        .collect();
+    shared.sort();
+    shared.truncate(PEER_EXCHANGE_LIMIT);
+    shared
+}
+
+// Peers from an announcement's list to introduce ourselves to: ones that aren't us, aren't known
+// or blocked, and still fit under max_peers
+pub fn peers_to_introduce(
+    offered: Vec<String>,
+    our_node: &str,
+    known_peers: &HashMap<String, PeerInfo>,
+    config: &NodeConfig,
+) -> Vec<String> {
+    let room = config.max_peers.saturating_sub(known_peers.len());
+    let mut introduced: Vec<String> = Vec::new();
+    for peer in offered.into_iter().take(PEER_EXCHANGE_LIMIT) {
+        if introduced.len() >= room {
+            break;
+        }
+        if peer != our_node && !known_peers.contains_key(&peer) && config.peer_blocked(&peer).is_none()
+            && !introduced.contains(&peer) {
+            introduced.push(peer);
+        }
+    }
+    introduced
+}
+
+// Planned downtime, in unix seconds. Opportunities whose deadline falls inside a peer's window are
+// routed around it: no execution rights, claims or intents go its way.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MaintenanceWindow {
+    pub start: u64,
+    pub duration_secs: u64,
+}
+
+impl MaintenanceWindow {
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.duration_secs)
+    }
+    
+    pub fn covers(&self, at: u64) -> bool {
+        self.start <= at && at < self.end()
+    }
+    
+    pub fn overlaps(&self, from: u64, to: u64) -> bool {
+        self.start <= to && from < self.end()
+    }
+}
+
+// Longest maintenance window a node may announce
+const MAX_MAINTENANCE_SECS: u64 = 7 * 24 * 3600;
+
+// Latest block number the bridge reported, extrapolated forward by wall-clock time
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct ChainHeadEstimate {
+    pub block: u64,
+    pub observed_at: u64,
+    // The block's own timestamp, when it came from a BlockUpdate
+    #[serde(default)]
+    pub block_timestamp: Option<u64>,
+    // Base fee of the last BlockUpdate that carried one, in wei
+    #[serde(default)]
+    pub base_fee_wei: Option<u128>,
+}
+
+// A head older than this is reported stale: the bridge has stopped sending blocks
+const CHAIN_HEAD_STALE_SECS: u64 = 60;
+
+impl ChainHeadEstimate {
+    pub fn estimate(&self, now: u64) -> u64 {
+        self.block + now.saturating_sub(self.observed_at) / SECONDS_PER_BLOCK
+    }
+    
+    // Gas price to judge execution cost by; none once the head has gone stale
+    pub fn gas_price_wei(&self, now: u64) -> Option<U256> {
+        if now.saturating_sub(self.observed_at) > CHAIN_HEAD_STALE_SECS {
+            return None;
+        }
+        self.base_fee_wei.map(U256::from)
+    }
+    
+    pub fn status(&self, now: u64) -> serde_json::Value {
+        let age_secs = now.saturating_sub(self.observed_at);
+        serde_json::json!({
+            "block": self.estimate(now),
+            "reported_block": self.block,
+            "reported_at": self.observed_at,
+            "block_timestamp": self.block_timestamp,
+            "base_fee_wei": self.base_fee_wei.map(|fee| fee.to_string()),
+            "age_secs": age_secs,
+            "stale": age_secs > CHAIN_HEAD_STALE_SECS,
+        })
+    }
+}
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// NODE STATE MANAGEMENT
+#[derive(Default, Serialize, Deserialize, Debug)]
+pub struct HyperMevApp {
+    // Node configuration
+    pub node_config: NodeConfig,
+    pub active_strategy: Option<StrategyId>,
+    
+    // P2P state
+    #[serde(deserialize_with = "peer_map")]
+    pub known_peers: HashMap<String, PeerInfo>,
+    // When we last pinged every peer; a peer not heard from since has missed that round
+    #[serde(skip)]
+    pub last_heartbeat_at: Option<u64>,
+    // Malformed or invalid messages received, per sending peer
+    #[serde(default)]
+    pub malformed_message_strikes: HashMap<String, u32>,
+    // Message validity, find outcomes and realized profit, per peer
+    #[serde(default)]
+    pub peer_reputation: BTreeMap<String, PeerReputation>,
+    // Messages refused by the blacklist or allowlist, per sending node
+    #[serde(default)]
+    pub blocked_messages: BTreeMap<String, u64>,
+    // Inbound token buckets per sending node; refusals are kept in peer_reputation
+    #[serde(skip)]
+    pub inbound_rate_limiters: BTreeMap<String, PeerRateLimiter>,
+    // State sync requests awaiting an answer, by peer, with when they were sent
+    #[serde(skip)]
+    pub pending_syncs: BTreeMap<String, u64>,
+    // Senders we asked to announce themselves because their message came first, with when
+    #[serde(skip)]
+    pub announcement_requests: HashMap<String, u64>,
+    // Peer message bodies that failed to parse as JSON, oldest dropped past DEAD_LETTER_CAPACITY
+    #[serde(default)]
+    pub dead_letters: Vec<DeadLetter>,
+    // Our own announced downtime; cleared once it ends
+    #[serde(default)]
+    pub maintenance_window: Option<MaintenanceWindow>,
+    // Interior mutability so every send path, including &self ones, draws from the same budget
+    #[serde(skip)]
+    pub outbound_budget: RefCell<OutboundBudget>,
+    // One bounded queue per peer, drained against outbound_budget
+    #[serde(skip)]
+    pub outbound_queues: RefCell<OutboundQueues>,
+    // Traffic capture started by start_recording; kept after it stops until the next start
+    #[serde(skip)]
+    pub recording: RefCell<Option<Recording>>,
+    // Newest protocol events in and out, sized by node_config.event_log
+    #[serde(default)]
+    pub event_log: RefCell<EventLog>,
+    // Event log entry of the peer message being handled, for verdicts after the gates
+    #[serde(skip)]
+    pub inbound_event: Cell<Option<u64>>,
+    // Our networking key as the PKI lists it, looked up at startup for our announcements
+    #[serde(skip)]
+    pub our_networking_key: Option<String>,
+    // Our app signing key, generated on first start and replaced by rotate_signing_key
+    #[serde(default)]
+    pub signing_key: Option<SigningKeyPair>,
+    // The key our last rotation replaced, while peers still honour it
+    #[serde(default)]
+    pub retired_signing_key: Option<RetiredKey>,
+    // Last nonce we put on an outgoing announcement, intent or receipt
+    #[serde(default)]
+    pub outbound_nonce: Cell<u64>,
+    // Highest nonce seen per sender and message kind; anything at or below it is a replay
+    #[serde(default)]
+    pub peer_nonces: HashMap<String, BTreeMap<String, u64>>,
+    #[serde(skip)]
+    pub validation_broker: ValidationBroker,
+    
+    // MEV coordination state (in-memory, keyed by opp_id) 
+    pub active_opportunities: HashMap<String, OpportunityData>,
+    pub submitted_intents: HashMap<String, Vec<IntentData>>,
+    pub execution_receipts: HashMap<String, ReceiptData>,
+    #[serde(default)]
+    pub receipt_history: HashMap<String, ArchivedReceipt>,
+    // Last touch per opportunity, to evict the least recently touched past collection_caps
+    #[serde(default)]
+    pub collection_recency: RecencyIndex,
+    
+    // Per-finder sequence numbers: ours as a finder (seq -> opp_id), and what we've seen from others
+    #[serde(default)]
+    pub next_finder_seq: u64,
+    #[serde(default)]
+    pub originated_by_seq: BTreeMap<u64, String>,
+    #[serde(default)]
+    pub finder_sequences: HashMap<String, FinderSequenceStats>,
+    #[serde(default)]
+    pub origin_stats: BTreeMap<OriginClass, OriginStats>,
+    #[serde(default)]
+    pub seen_broadcasts: SeenBroadcasts,
+    // Broadcasts acknowledged but dropped because their strategy is disabled here
+    #[serde(default)]
+    pub ignored_broadcasts: BTreeMap<StrategyId, u64>,
+    #[serde(default)]
+    pub origination_index: HashMap<String, OriginationRecord>,
+    // Economic duplicates: duplicate_key -> canonical opp_id, and each linked opp_id -> canonical
+    #[serde(default)]
+    pub duplicate_index: HashMap<String, String>,
+    #[serde(default)]
+    pub opp_aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub expired_opportunities: HashMap<String, ExpiryRecord>,
+    #[serde(default)]
+    pub chain_head: Option<ChainHeadEstimate>,
+    
+    // Pool membership
+    #[serde(default)]
+    pub pool_manifest: Option<PoolManifest>,
+    #[serde(default)]
+    pub peer_registry: HashMap<String, PeerRegistration>,
+    
+    // Append-only record of notable events, oldest dropped past JOURNAL_CAPACITY
+    #[serde(default)]
+    pub journal: Vec<JournalEntry>,
+    // Latest statement exchange per counterparty
+    #[serde(default)]
+    pub statement_comparisons: BTreeMap<String, StatementComparison>,
+    // Buddies keeping a copy of our public records, and the log we stream to them
+    #[serde(default)]
+    pub mirror_buddies: BTreeMap<String, MirrorAgreement>,
+    #[serde(default)]
+    pub mirror_log: MirrorLog,
+    // Copies we keep for buddies, by the node they mirror
+    #[serde(default)]
+    pub held_mirrors: BTreeMap<String, HeldMirror>,
+    // Our last restore from a buddy, and the records it rebuilt, by kind and key
+    #[serde(default)]
+    pub mirror_restore: Option<MirrorRestore>,
+    #[serde(default)]
+    pub restored_records: BTreeMap<String, RestoredRecord>,
+    // Peers' advertised monitoring, victims we leave to them, and how that has gone
+    #[serde(default)]
+    pub monitoring_coverage: MonitoringCoverage,
+    // Record views we asked counterparties for, by request id
+    #[serde(default)]
+    pub counterparty_views: BTreeMap<String, CounterpartyView>,
+    // Config update awaiting commit_config; persisted so a restart doesn't skip the revert
+    #[serde(default)]
+    pub config_trial: Option<ConfigTrial>,
+    // Revision the current config and strategy settings make up, and the snapshots open
+    // opportunities still refer to
+    #[serde(default)]
+    pub config_revision: u64,
+    #[serde(default)]
+    pub config_history: BTreeMap<u64, ConfigRevision>,
+    // Named tuning presets, by name
+    #[serde(default)]
+    pub config_presets: BTreeMap<String, ConfigPreset>,
+    // Onboarding state of executors that have drawn on our capital
+    #[serde(default)]
+    pub counterparties: CounterpartyRegistry,
+    
+    // Capital management - using String for WIT compatibility
+    pub available_balances: HashMap<String, String>,
+    pub committed_amounts: HashMap<String, String>,
+    // Per-opportunity breakdown of committed_amounts
+    #[serde(default)]
+    pub capital_commitments: HashMap<String, CapitalCommitment>,
+    // Capital that executions actually drew, per asset, over the node's lifetime
+    #[serde(default)]
+    pub used_capital: HashMap<String, String>,
+    // Our cut of successful executions, per proceeds asset, over the node's lifetime
+    #[serde(default)]
+    pub earned_proceeds: HashMap<String, String>,
+    // Running per-strategy counts and totals behind get_metrics
+    #[serde(default)]
+    pub performance_metrics: PerformanceMetrics,
+    // Intents we submitted as a capital provider, keyed by opp_id
+    #[serde(default)]
+    pub our_intents: HashMap<String, OutstandingIntent>,
+    // Our intents whose execution has a terminal receipt, kept to check the executor's
+    // distribution against until it arrives or they age out
+    #[serde(default)]
+    pub settled_intents: HashMap<String, OutstandingIntent>,
+    // Executors' proceeds distributions, ours included, by opp_id
+    #[serde(default)]
+    pub proceeds_distributions: DistributionLedger,
+    // Net position with each peer per asset, from capital drawn and proceeds distributed;
+    // positive when the peer owes us
+    #[serde(default)]
+    pub settlement_ledger: SettlementPositions,
+    // Off-band payments recorded with mark_settled
+    #[serde(default)]
+    pub settlement_payments: SettlementPayments,
+    // Ledger postings by epoch, the netting statements made of them and the net payables agreed
+    #[serde(default)]
+    pub settlement_epochs: SettlementEpochs,
+    // Our intents revoked with revoke_intent, kept until expiry in case an executor refuses
+    #[serde(default)]
+    pub revoked_intents: HashMap<String, OutstandingIntent>,
+    // Portion of each available balance that is demo capital rather than operator funds
+    #[serde(default)]
+    pub demo_balances: HashMap<String, String>,
+    // Phantom seed capital stripped from older state, held until the operator says whether it's real
+    #[serde(default)]
+    pub seed_capital_migrated: bool,
+    #[serde(default)]
+    pub stripped_seed_capital: Option<String>,
+    
+    // Strategy settings by strategy id; only strategies registered here are evaluated
+    #[serde(default)]
+    pub strategy_configs: HashMap<StrategyId, StrategyConfig>,
+    // Borrowers the bridge watches for the aave-liquidation strategy
+    #[serde(default)]
+    pub watched_positions: HashMap<String, PositionData>,
+    // Older state kept both in one aave_strategy_config; read once by migrate_strategy_configs
+    #[serde(default, rename = "aave_strategy_config", skip_serializing)]
+    pub legacy_aave_strategy_config: Option<AaveStrategyConfig>,
+    
+    // WebSocket connections from Artemis bridges; executions go to the primary
+    #[serde(skip)]
+    pub artemis_channels: ArtemisChannels,
+    // Browser tabs subscribed on /updates for pushed pool events
+    #[serde(skip)]
+    pub ui_channels: UiChannels,
+    // Latest health report from the Artemis bridge
+    #[serde(skip)]
+    pub artemis_health: Option<serde_json::Value>,
+    // Reconnection sync with the bridge: the one in flight and what the last one reconciled
+    #[serde(skip)]
+    pub bridge_sync: BridgeSync,
+    // Bridge frames waiting to be handled, most urgent first
+    #[serde(skip)]
+    pub artemis_inbound: InboundQueue,
+    // Bridge lookups behind the probed risk flags, by lowercased victim, and those still in flight
+    #[serde(skip)]
+    pub risk_probes: HashMap<String, RiskProbe>,
+    #[serde(skip)]
+    pub risk_probes_pending: HashSet<String>,
+    // Executions sent to the bridge and not yet answered with a receipt
+    #[serde(default)]
+    pub pending_executions: HashMap<String, PendingExecution>,
+    // Reservation rounds we run as executor, and reservations we granted as provider, by opp_id
+    #[serde(default)]
+    pub reservation_rounds: HashMap<String, ReservationRound>,
+    #[serde(default)]
+    pub capital_reservations: HashMap<String, CapitalReservation>,
+    // Recent add_capital/remove_capital outcomes, for replaying retried operation ids
+    #[serde(default)]
+    pub capital_ledger: CapitalLedger,
+    // Local processes registered for PoolEvents through local_subscribe
+    #[serde(default)]
+    pub local_subscribers: LocalSubscribers,
+    
+    // On-chain spot checks: last reconciliation per asset, and which checks are currently degraded
+    #[serde(default)]
+    pub balance_reconciliation: HashMap<String, BalanceReconciliation>,
+    #[serde(skip)]
+    pub eth_degraded: HashMap<String, EthDegradation>,
+    #[serde(skip)]
+    pub eth_budget: EthCallBudget,
+    #[serde(skip)]
+    pub last_balance_check: u64,
+    
+    // HTTP server for WebSocket connections
+    #[serde(skip)]
+    pub http_server: Option<HttpServer>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct NodeConfig {
+    pub node_id: String,
+    pub publisher: String,
+    pub enabled_strategies: Vec<StrategyId>,
+    pub finder_enabled: bool,
+    pub capital_provider_enabled: bool,
+    pub executor_enabled: bool,
+    pub finder_fee_bps: u16,
+    pub executor_fee_bps: u16,
+    pub min_profit_threshold_usd: String,
+    pub max_gas_price_gwei: String,
+    pub aave_pool_address: String,
+    // Ordered executor failover list carried on opportunities we originate (empty = no designation)
+    #[serde(default)]
+    pub executor_preference: Vec<String>,
+    // Blocks without a claim or receipt before execution rights pass to the next executor
+    #[serde(default = "default_executor_failover_blocks")]
+    pub executor_failover_blocks: u64,
+    #[serde(default)]
+    pub min_distinct_providers_above_usd: Vec<ProviderRequirement>,
+    // Once the wait lapses: execute with fewer providers if set, otherwise expire the opportunity
+    #[serde(default)]
+    pub allow_under_provisioned_execution: bool,
+    #[serde(default = "default_provider_wait_blocks")]
+    pub provider_wait_blocks: u64,
+    // Coverage (bps of max repay) at which a lapsing intent window is extended once instead of expiring
+    #[serde(default = "default_window_extension_coverage_bps")]
+    pub window_extension_coverage_bps: u16,
+    // Settled receipts older than this are reduced to summaries, at most receipt_archive_budget per tick
+    #[serde(default = "default_receipt_archive_after_secs")]
+    pub receipt_archive_after_secs: u64,
+    #[serde(default = "default_receipt_archive_budget")]
+    pub receipt_archive_budget: usize,
+    // Hysteresis margins around min_profit_threshold_usd for entering and leaving an opportunity
+    #[serde(default = "default_profit_entry_margin_bps")]
+    pub profit_entry_margin_bps: u16,
+    #[serde(default = "default_profit_exit_margin_bps")]
+    pub profit_exit_margin_bps: u16,
+    // How long after its last activity a victim's opportunity still absorbs new originations
+    #[serde(default = "default_origination_cooldown_secs")]
+    pub origination_cooldown_secs: u64,
+    #[serde(default)]
+    pub fast_path_rules: Vec<FastPathRule>,
+    // Seed demo capital (tagged as such) and whether we accept simulated intents from peers
+    #[serde(default)]
+    pub seed_demo_capital: bool,
+    #[serde(default)]
+    pub paper_trading: bool,
+    // Pool membership; the coordinator node is learned out-of-band, the rest comes from its manifest
+    #[serde(default)]
+    pub pool_id: Option<String>,
+    #[serde(default)]
+    pub pool_coordinator: Option<String>,
+    #[serde(default)]
+    pub bootstrap_peers: Vec<String>,
+    // Publisher of each peer's hyper-mev package, where it differs from ours
+    #[serde(default)]
+    pub peer_publishers: HashMap<String, String>,
+    // Who earns the finder fee when the same opportunity arrives from several finders
+    #[serde(default)]
+    pub duplicate_finder_policy: DuplicateFinderPolicy,
+    // Order in which we fund an execution from submitted intents, declared on our receipts
+    #[serde(default)]
+    pub allocation_policy: AllocationPolicy,
+    #[serde(default)]
+    pub eth_checks: EthCheckConfig,
+    // Global cap on outbound P2P requests per second (0 = unlimited)
+    #[serde(default = "default_outbound_requests_per_sec")]
+    pub outbound_requests_per_sec: u32,
+    // Peers an opportunity broadcast still reaches when the budget can't cover every peer
+    #[serde(default = "default_pressure_fanout_peers")]
+    pub pressure_fanout_peers: usize,
+    // Without any block observation, opportunities older than this are treated as expired
+    #[serde(default = "default_opportunity_ttl_secs")]
+    pub opportunity_ttl_secs: u64,
+    // Per-origin overrides for capital commitment; classes without an entry use the defaults
+    #[serde(default)]
+    pub origin_policies: BTreeMap<OriginClass, OriginPolicy>,
+    // Ask the bridge to re-check the victim before dispatch and, each block, for opportunities
+    // we hold intents on
+    #[serde(default)]
+    pub bridge_validation: bool,
+    #[serde(default = "default_max_outstanding_validations")]
+    pub max_outstanding_validations: usize,
+    // Missed heartbeat rounds before a peer is marked unreachable, and how long it is then kept
+    #[serde(default = "default_heartbeat_miss_limit")]
+    pub heartbeat_miss_limit: u32,
+    #[serde(default = "default_unreachable_peer_grace_secs")]
+    pub unreachable_peer_grace_secs: u64,
+    // Most peers learned through announcements and peer exchange; connect_to_peer is not limited
+    #[serde(default = "default_max_peers")]
+    pub max_peers: usize,
+    // Times a received opportunity broadcast is passed on to our peers (0 = never forwarded)
+    #[serde(default)]
+    pub max_broadcast_hops: u8,
+    // How long a handled broadcast is remembered; repeats within it are ignored
+    #[serde(default = "default_seen_broadcast_ttl_secs")]
+    pub seen_broadcast_ttl_secs: u64,
+    // What a raised risk flag does to our commitment; flags without an entry are ignored
+    #[serde(default)]
+    pub risk_flag_actions: BTreeMap<RiskFlag, RiskAction>,
+    // As executor, have every allocated provider reserve its capital before dispatch, and hold
+    // reservations (asked of us or granted by us) for reservation_ttl_secs
+    #[serde(default)]
+    pub capital_reservation: bool,
+    #[serde(default = "default_reservation_ttl_secs")]
+    pub reservation_ttl_secs: u64,
+    // Declared on the opportunities we find: where rounding dust goes and the smallest share paid
+    #[serde(default)]
+    pub distribution_scheme: DistributionScheme,
+    // Local processes allowed to use the typed local API, by process id or `package:publisher`
+    #[serde(default)]
+    pub local_api_grants: BTreeMap<String, Vec<LocalScope>>,
+    // Limits on the opportunities any one finder can have us hold
+    #[serde(default)]
+    pub finder_quota: FinderQuota,
+    // When a peer's record gets its broadcasts ignored or its finds left unevaluated
+    #[serde(default)]
+    pub reputation_policy: ReputationPolicy,
+    // Nodes whose messages are refused and that are never taken on as peers
+    #[serde(default)]
+    pub blacklisted_peers: BTreeSet<String>,
+    // When on, only allowed_peers get through; the blacklist still applies to them
+    #[serde(default)]
+    pub allowlist_mode: bool,
+    #[serde(default)]
+    pub allowed_peers: BTreeSet<String>,
+    // Whether and how we share victim monitoring with peers
+    #[serde(default)]
+    pub monitoring_policy: MonitoringPolicy,
+    // Messages per minute any one peer may send us, per message class
+    #[serde(default)]
+    pub inbound_rate_limits: InboundRateLimits,
+    // Who may see each section of our records through a counterparty view
+    #[serde(default)]
+    pub record_sharing: RecordSharingPolicy,
+    // Size of the protocol event log and of the payloads it keeps
+    #[serde(default)]
+    pub event_log: EventLogConfig,
+    // Refuse unsigned announcements, intents and receipts from every peer, not only from those
+    // that have signed before
+    #[serde(default)]
+    pub require_signed_messages: bool,
+    // How long after a peer rotates its signing key we still take messages countersigned by the old one
+    #[serde(default = "default_key_rotation_grace_secs")]
+    pub key_rotation_grace_secs: u64,
+    // Whether we bid on our own and how large automatic bids may be
+    #[serde(default)]
+    pub bid_policy: BidPolicy,
+    // Prices execution at the bridge's gas price for the profit check
+    #[serde(default)]
+    pub gas_cost_model: GasCostModel,
+    // Fee bid on the intents we submit: the share of our pro-rata slice of the provider pool we
+    // claim (0 = all of it)
+    #[serde(default)]
+    pub capital_provider_fee_bps: u16,
+    // How far, in bps of what we committed, a receipt's use of our capital may stray before we
+    // dispute it
+    #[serde(default = "default_receipt_dispute_tolerance_bps")]
+    pub receipt_dispute_tolerance_bps: u16,
+    // How long settlements accrue before we propose netting statements for them
+    #[serde(default)]
+    pub settlement_epoch: EpochLength,
+    // Most opportunities, intents and receipts kept in state before maintenance evicts
+    #[serde(default)]
+    pub collection_caps: CollectionCaps,
+}
+
+fn default_executor_failover_blocks() -> u64 {
+    5
+}
+
+fn default_provider_wait_blocks() -> u64 {
+    3
+}
+
+fn default_window_extension_coverage_bps() -> u16 {
+    7_000
+}
+
+fn default_outbound_requests_per_sec() -> u32 {
+    50
+}
+
+fn default_pressure_fanout_peers() -> usize {
+    8
+}
+
+fn default_opportunity_ttl_secs() -> u64 {
+    600
+}
+
+fn default_max_outstanding_validations() -> usize {
+    4
+}
+
+fn default_heartbeat_miss_limit() -> u32 {
+    3
+}
+
+fn default_unreachable_peer_grace_secs() -> u64 {
+    60 * 60
+}
+
+fn default_max_peers() -> usize {
+    50
+}
+
+fn default_key_rotation_grace_secs() -> u64 {
+    10 * 60
+}
+
+fn default_seen_broadcast_ttl_secs() -> u64 {
+    60 * 60
+}
+
+fn default_reservation_ttl_secs() -> u64 {
+    60
+}
+
+fn default_receipt_archive_after_secs() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+fn default_receipt_archive_budget() -> usize {
+    25
+}
+
+fn default_receipt_dispute_tolerance_bps() -> u16 {
+    100
+}
+
+fn default_profit_entry_margin_bps() -> u16 {
+    200
+}
+
+fn default_profit_exit_margin_bps() -> u16 {
+    200
+}
+
+fn default_origination_cooldown_secs() -> u64 {
+    300
+}
+
+impl NodeConfig {
+    // Why messages from `peer` are refused, if they are
+    pub fn peer_blocked(&self, peer: &str) -> Option<&'static str> {
+        if self.blacklisted_peers.contains(peer) {
+            Some("Blacklisted")
+        } else if self.allowlist_mode && !self.allowed_peers.contains(peer) {
+            Some("NotAllowlisted")
+        } else {
+            None
+        }
+    }
+    
+    // First fast-path rule the opportunity satisfies, if any
+    pub fn fast_path_rule_for(&self, size: U256, finder_tier: u8) -> Option<(usize, &FastPathRule)> {
+        let ceiling = FAST_PATH_SIZE_CEILING_USD.parse::<U256>().unwrap_or(U256::ZERO);
+        self.fast_path_rules.iter().enumerate().find(|(_, rule)| {
+            let max_size = rule.max_size_usd.parse::<U256>().unwrap_or(U256::ZERO).min(ceiling);
+            size <= max_size
+                && rule.min_finder_reputation_tier.map_or(true, |min| finder_tier >= min)
+                && rule.min_confidence_bps.is_none()
+        })
+    }
+    
+    // The strictest rule whose threshold the opportunity size reaches
+    pub fn provider_requirement_for(&self, size: U256) -> Option<&ProviderRequirement> {
+        self.min_distinct_providers_above_usd.iter()
+            .filter_map(|rule| rule.threshold_usd.parse::<U256>().ok().map(|threshold| (threshold, rule)))
+            .filter(|(threshold, _)| size >= *threshold)
+            .max_by_key(|(threshold, _)| *threshold)
+            .map(|(_, rule)| rule)
+    }
+    
+    pub fn origin_policy(&self, origin: OriginClass) -> OriginPolicy {
+        self.origin_policies.get(&origin).cloned().unwrap_or_default()
+    }
+    
+    pub fn strategy_enabled(&self, strategy_id: &str) -> bool {
+        self.enabled_strategies.iter().any(|enabled| enabled == strategy_id)
+    }
+    
+    // Gas ceiling in wei: the strategy's max_gas_price_gwei, else the node's
+    pub fn max_gas_price_wei(&self, strategy: Option<&StrategyConfig>) -> U256 {
+        strategy.and_then(|strategy| strategy.max_gas_price_gwei.clone())
+            .unwrap_or_else(|| self.max_gas_price_gwei.clone())
+            .parse::<U256>()
+            .unwrap_or(U256::MAX)
+            .saturating_mul(U256::from(1_000_000_000u64))
+    }
+    
+    // Profit threshold for finds of this origin under this strategy: the origin policy's, then
+    // the strategy's, then min_profit_threshold_usd
+    pub fn min_profit_for(&self, origin: OriginClass, strategy: Option<&StrategyConfig>) -> U256 {
+        self.origin_policy(origin).min_profit_threshold_usd
+            .or_else(|| strategy.and_then(|strategy| strategy.min_profit_usd.clone()))
+            .unwrap_or_else(|| self.min_profit_threshold_usd.clone())
+            .parse::<U256>()
+            .unwrap_or(U256::ZERO)
+    }
+    
+    // Rules spanning several fields, checked on the complete config before it is applied
+    pub fn validate(&self) -> Result<(), String> {
+        if self.finder_fee_bps as u32 + self.executor_fee_bps as u32 > 10_000 {
+            return Err(format!("Finder and executor fees together exceed 10000 bps ({} + {})",
+                self.finder_fee_bps, self.executor_fee_bps));
+        }
+        let policy = &self.reputation_policy;
+        if let Some(bps) = policy.ignore_above_invalid_bps.into_iter().chain(policy.min_realization_bps).find(|bps| *bps > 10_000) {
+            return Err(format!("Reputation policy bps {} exceeds 10000", bps));
+        }
+        let monitoring = &self.monitoring_policy;
+        if monitoring.spot_check_bps > 10_000 || monitoring.health_factor_tolerance_bps > 10_000 {
+            return Err("Monitoring policy bps exceed 10000".to_string());
+        }
+        if self.allowlist_mode && self.allowed_peers.is_empty() {
+            return Err("Allowlist mode with no allowed peers would refuse every peer".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Config in force before a trial update, restored at revert_at unless the trial is committed
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ConfigTrial {
+    pub previous: NodeConfig,
+    pub started_at: u64,
+    pub revert_at: u64,
+}
+
+// Top-level config fields that differ between two configs
+pub fn changed_config_fields(before: &NodeConfig, after: &NodeConfig) -> Vec<String> {
+    let (serde_json::Value::Object(before), serde_json::Value::Object(after)) =
+        (serde_json::to_value(before).unwrap_or_default(), serde_json::to_value(after).unwrap_or_default()) else {
+        return Vec::new();
+    };
+    after.iter()
+        .filter(|(field, value)| before.get(*field) != Some(*value))
+        .map(|(field, _)| field.clone())
+        .collect()
+}
+
+// Fields left out keep their current value
+#[derive(Deserialize)]
+struct ConfigUpdate {
+    finder_enabled: Option<bool>,
+    capital_provider_enabled: Option<bool>,
+    executor_enabled: Option<bool>,
+    finder_fee_bps: Option<u16>,
+    executor_fee_bps: Option<u16>,
+    min_profit_threshold_usd: Option<String>,
+    max_gas_price_gwei: Option<String>,
+    executor_preference: Option<Vec<String>>,
+    executor_failover_blocks: Option<u64>,
+    min_distinct_providers_above_usd: Option<Vec<ProviderRequirement>>,
+    allow_under_provisioned_execution: Option<bool>,
+    provider_wait_blocks: Option<u64>,
+    window_extension_coverage_bps: Option<u16>,
+    profit_entry_margin_bps: Option<u16>,
+    profit_exit_margin_bps: Option<u16>,
+    origination_cooldown_secs: Option<u64>,
+    fast_path_rules: Option<Vec<FastPathRule>>,
+    seed_demo_capital: Option<bool>,
+    paper_trading: Option<bool>,
+    pool_coordinator: Option<String>,
+    bootstrap_peers: Option<Vec<String>>,
+    duplicate_finder_policy: Option<DuplicateFinderPolicy>,
+    allocation_policy: Option<AllocationPolicy>,
+    eth_checks: Option<EthCheckConfig>,
+    outbound_requests_per_sec: Option<u32>,
+    pressure_fanout_peers: Option<usize>,
+    opportunity_ttl_secs: Option<u64>,
+    origin_policies: Option<BTreeMap<OriginClass, OriginPolicy>>,
+    bridge_validation: Option<bool>,
+    max_outstanding_validations: Option<usize>,
+    heartbeat_miss_limit: Option<u32>,
+    unreachable_peer_grace_secs: Option<u64>,
+    max_peers: Option<usize>,
+    max_broadcast_hops: Option<u8>,
+    seen_broadcast_ttl_secs: Option<u64>,
+    risk_flag_actions: Option<BTreeMap<RiskFlag, RiskAction>>,
+    capital_reservation: Option<bool>,
+    reservation_ttl_secs: Option<u64>,
+    distribution_scheme: Option<DistributionScheme>,
+    local_api_grants: Option<BTreeMap<String, Vec<LocalScope>>>,
+    finder_quota: Option<FinderQuota>,
+    reputation_policy: Option<ReputationPolicy>,
+    blacklisted_peers: Option<BTreeSet<String>>,
+    allowlist_mode: Option<bool>,
+    allowed_peers: Option<BTreeSet<String>>,
+    monitoring_policy: Option<MonitoringPolicy>,
+    inbound_rate_limits: Option<InboundRateLimits>,
+    record_sharing: Option<RecordSharingPolicy>,
+    event_log: Option<EventLogConfig>,
+    require_signed_messages: Option<bool>,
+    key_rotation_grace_secs: Option<u64>,
+    bid_policy: Option<BidPolicy>,
+    gas_cost_model: Option<GasCostModel>,
+    capital_provider_fee_bps: Option<u16>,
+    receipt_dispute_tolerance_bps: Option<u16>,
+    settlement_epoch: Option<EpochLength>,
+    collection_caps: Option<CollectionCaps>,
+    // Reverts the update after this many seconds unless commit_config confirms it
+    trial_secs: Option<u64>,
+}
+
+// Revisions kept besides those an open opportunity was created under
+const CONFIG_HISTORY_CAPACITY: usize = 20;
+
+// Config and strategy settings as of one applied change. Opportunities are stamped with the
+// revision current when they arrived and keep its fees, margins and allocation policy until
+// they settle, whatever is applied in between.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ConfigRevision {
+    pub revision: u64,
+    pub applied_at: u64,
+    pub reason: String,
+    pub config: NodeConfig,
+    pub strategies: BTreeMap<StrategyId, StrategyConfig>,
+}
+
+const PRESET_CAPACITY: usize = 32;
+
+// NodeConfig fields a preset captures: thresholds, fees, margins and policies, not roles,
+// identity, grants or networking
+const PRESET_FIELDS: &[&str] = &[
+    "finder_fee_bps",
+    "executor_fee_bps",
+    "min_profit_threshold_usd",
+    "max_gas_price_gwei",
+    "executor_failover_blocks",
+    "min_distinct_providers_above_usd",
+    "allow_under_provisioned_execution",
+    "provider_wait_blocks",
+    "window_extension_coverage_bps",
+    "profit_entry_margin_bps",
+    "profit_exit_margin_bps",
+    "origination_cooldown_secs",
+    "fast_path_rules",
+    "allocation_policy",
+    "origin_policies",
+    "risk_flag_actions",
+    "capital_reservation",
+    "reservation_ttl_secs",
+    "distribution_scheme",
+    "finder_quota",
+    "reputation_policy",
+    "monitoring_policy",
+    "inbound_rate_limits",
+    "bid_policy",
+    "gas_cost_model",
+    "capital_provider_fee_bps",
+    "receipt_dispute_tolerance_bps",
+];
+
+// A named set of tuning, applied in one step by apply_preset
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ConfigPreset {
+    pub name: String,
+    pub saved_at: u64,
+    // PRESET_FIELDS as they stood, in the shape update_node_config takes
+    pub config: serde_json::Map<String, serde_json::Value>,
+    pub strategies: BTreeMap<StrategyId, StrategyConfig>,
+}
+
+impl ConfigPreset {
+    pub fn capture(name: String, config: &NodeConfig, strategies: &HashMap<StrategyId, StrategyConfig>, now: u64) -> Self {
+        let all = match serde_json::to_value(config) {
+            Ok(serde_json::Value::Object(all)) => all,
+            _ => serde_json::Map::new(),
+        };
+        Self {
+            name,
+            saved_at: now,
+            config: all.into_iter().filter(|(field, _)| PRESET_FIELDS.contains(&field.as_str())).collect(),
+            strategies: strategies.iter().map(|(id, strategy)| (id.clone(), strategy.clone())).collect(),
+        }
+    }
+}
+
+// Demo capital seeded when seed_demo_capital is on; earlier versions seeded it unconditionally
+const DEMO_CAPITAL_ASSET: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"; // USDC mainnet
+const DEMO_CAPITAL_AMOUNT: &str = "10000000000"; // 10,000 USDC (6 decimals)
+
+// Block windows are counted against the tracked chain head; this converts them to wall-clock time
+// only before the bridge has reported a head, and for deadlines that need a time
+const SECONDS_PER_BLOCK: u64 = 12;
+
+const MAINTENANCE_INTERVAL_MS: u64 = 30_000;
+// Most pause before each startup re-announcement, so a long peer list isn't announced in a burst
+const STARTUP_ANNOUNCE_JITTER_MS: u64 = 500;
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            node_id: String::new(),
+            publisher: "skeleton.os".to_string(),
+            enabled_strategies: vec!["aave-liquidation".to_string()],
+            finder_enabled: true,
+            capital_provider_enabled: true,
+            executor_enabled: true,
+            finder_fee_bps: 100,
+            executor_fee_bps: 50,
+            min_profit_threshold_usd: "10000000000000000000".to_string(),
+            max_gas_price_gwei: "50".to_string(),
+            aave_pool_address: "0x87870bE17b9C61bE44b13bc108ad8E2C16684e78".to_string(),
+            executor_preference: Vec::new(),
+            executor_failover_blocks: default_executor_failover_blocks(),
+            min_distinct_providers_above_usd: Vec::new(),
+            allow_under_provisioned_execution: false,
+            provider_wait_blocks: default_provider_wait_blocks(),
+            window_extension_coverage_bps: default_window_extension_coverage_bps(),
+            receipt_archive_after_secs: default_receipt_archive_after_secs(),
+            receipt_archive_budget: default_receipt_archive_budget(),
+            profit_entry_margin_bps: default_profit_entry_margin_bps(),
+            profit_exit_margin_bps: default_profit_exit_margin_bps(),
+            origination_cooldown_secs: default_origination_cooldown_secs(),
+            fast_path_rules: Vec::new(),
+            seed_demo_capital: false,
+            paper_trading: false,
+            pool_id: None,
+            pool_coordinator: None,
+            bootstrap_peers: Vec::new(),
+            peer_publishers: HashMap::new(),
+            duplicate_finder_policy: DuplicateFinderPolicy::default(),
+            allocation_policy: AllocationPolicy::default(),
+            eth_checks: EthCheckConfig::default(),
+            outbound_requests_per_sec: default_outbound_requests_per_sec(),
+            pressure_fanout_peers: default_pressure_fanout_peers(),
+            opportunity_ttl_secs: default_opportunity_ttl_secs(),
+            origin_policies: BTreeMap::new(),
+            bridge_validation: false,
+            max_outstanding_validations: default_max_outstanding_validations(),
+            heartbeat_miss_limit: default_heartbeat_miss_limit(),
+            unreachable_peer_grace_secs: default_unreachable_peer_grace_secs(),
+            max_peers: default_max_peers(),
+            max_broadcast_hops: 0,
+            seen_broadcast_ttl_secs: default_seen_broadcast_ttl_secs(),
+            risk_flag_actions: BTreeMap::new(),
+            capital_reservation: false,
+            reservation_ttl_secs: default_reservation_ttl_secs(),
+            distribution_scheme: DistributionScheme::default(),
+            local_api_grants: BTreeMap::new(),
+            finder_quota: FinderQuota::default(),
+            reputation_policy: ReputationPolicy::default(),
+            blacklisted_peers: BTreeSet::new(),
+            allowlist_mode: false,
+            allowed_peers: BTreeSet::new(),
+            monitoring_policy: MonitoringPolicy::default(),
+            inbound_rate_limits: InboundRateLimits::default(),
+            record_sharing: RecordSharingPolicy::default(),
+            event_log: EventLogConfig::default(),
+            require_signed_messages: false,
+            key_rotation_grace_secs: default_key_rotation_grace_secs(),
+            bid_policy: BidPolicy::default(),
+            gas_cost_model: GasCostModel::default(),
+            capital_provider_fee_bps: 0,
+            receipt_dispute_tolerance_bps: default_receipt_dispute_tolerance_bps(),
+            settlement_epoch: EpochLength::default(),
+            collection_caps: CollectionCaps::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpportunityData {
+    pub opportunity: String,
+    pub strategy_id: StrategyId,
+    pub finder_node: String,
+    #[serde(deserialize_with = "numeric_timestamp")]
+    pub received_at: u64,
+    // Failover designation from the finder; index points at the current holder of execution rights
+    #[serde(default)]
+    pub executor_preference: Vec<String>,
+    #[serde(default)]
+    pub execution_holder_index: usize,
+    #[serde(default)]
+    pub holder_since: String,
+    // Chain head when it arrived and when the current holder got execution rights; None when no
+    // head was known then, and block windows fall back to the wall-clock times beside them
+    #[serde(default)]
+    pub received_block: Option<u64>,
+    #[serde(default)]
+    pub holder_since_block: Option<u64>,
+    #[serde(default)]
+    pub claimed_by: Option<String>,
+    #[serde(default)]
+    pub provider_shortfall: Option<ProviderShortfall>,
+    // Hysteresis state: whether we hold an intent on it / consider it dispatchable as executor
+    #[serde(default)]
+    pub participating: bool,
+    #[serde(default)]
+    pub dispatch_armed: bool,
+    // Set once the executor has sent its intent collection; amendments are refused from then on
+    #[serde(default)]
+    pub allocation_locked: bool,
+    // Decided on first evaluation as executor, then fixed
+    #[serde(default)]
+    pub evaluation_path: Option<EvaluationPath>,
+    #[serde(default)]
+    pub audit_trail: Vec<JournalEntry>,
+    // Every finder that reported this opportunity (the first is the one that created the record)
+    #[serde(default)]
+    pub claiming_finders: Vec<FinderClaim>,
+    #[serde(default)]
+    pub window_extension: Option<IntentWindowExtension>,
+    #[serde(default)]
+    pub origin: OpportunityOrigin,
+    // Peer the broadcast reached us through, when that isn't the finder; None for our own finds
+    #[serde(default)]
+    pub received_from: Option<String>,
+    // Computed at ingestion, and again when the bridge's lookups come back
+    #[serde(default)]
+    pub risk_flags: BTreeSet<RiskFlag>,
+    // Operator cleared the flags calling for manual approval
+    #[serde(default)]
+    pub risk_approved: bool,
+    // The preference list is our election among executor-capable peers, not the finder's
+    #[serde(default)]
+    pub executor_elected: bool,
+    #[serde(default)]
+    pub status: OpportunityStatus,
+    // Config revision in force when it arrived; its terms hold until it settles
+    #[serde(default)]
+    pub config_revision: u64,
+    // Peers our own broadcast of it went to, by fan-out, repair or backfill
+    #[serde(default)]
+    pub broadcast_to: BTreeSet<String>,
+    // Where our own bid on it stands, once evaluated as capital provider
+    #[serde(default)]
+    pub bid_status: Option<BidStatus>,
+    // Set while dispatch to our bridge waits for gas to come back under the cap
+    #[serde(default)]
+    pub gas_deferral: Option<GasDeferral>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct GasDeferral {
+    pub since: u64,
+    pub gas_price_wei: String,
+    pub max_gas_price_gwei: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum BidStatus {
+    // Passed evaluation with auto_bid off; waits for submit_intent_manual
+    PendingReview,
+    // The rule that kept us out, as counted in origin_stats.skipped
+    Declined { reason: String },
+    Submitted { amount: String, manual: bool },
+}
+
+// Where an opportunity is in its life, as far as this node can see
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum OpportunityStatus {
+    #[default]
+    Open,
+    // At least one intent received
+    CollectingIntents,
+    // Sent to our bridge, or reported pending by the executor's receipt
+    Executing,
+    Settled,
+    Failed,
+    // Past its deadline unexecuted; retired once any grace period runs out
+    Expired,
+}
+
+impl OpportunityStatus {
+    // Whether planning may still send it to the bridge
+    pub fn dispatchable(&self) -> bool {
+        matches!(self, OpportunityStatus::Open | OpportunityStatus::CollectingIntents)
+    }
+    
+    pub fn from_receipt(status: &ExecutionStatus) -> Self {
+        match status {
+            ExecutionStatus::Success => OpportunityStatus::Settled,
+            ExecutionStatus::Failed(_) => OpportunityStatus::Failed,
+            ExecutionStatus::Pending => OpportunityStatus::Executing,
+        }
+    }
+}
+
+// Deterministic executor ranking for an opportunity: each candidate's hash of opp_id ++ node,
+// lowest first, so every node with the same view of the executors elects the same one
+pub fn election_order(opp_id: &str, candidates: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut ranked: Vec<(String, String)> = candidates.into_iter()
+        .map(|node| (intent_hash(&format!("{}{}", opp_id, node)), node))
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.1 == b.1);
+    ranked.into_iter().map(|(_, node)| node).collect()
+}
+
+// Profit hysteresis around a threshold: getting in requires threshold × (1 + entry margin),
+// while staying in only requires threshold × (1 − exit margin)
+pub fn clears_profit_bar(
+    profit: U256,
+    threshold: U256,
+    entry_margin_bps: u16,
+    exit_margin_bps: u16,
+    already_in: bool,
+) -> bool {
+    let bps = U256::from(10_000u64);
+    if already_in {
+        profit * bps >= threshold * (bps - U256::from(exit_margin_bps.min(10_000)))
+    } else {
+        profit * bps >= threshold * (bps + U256::from(entry_margin_bps))
+    }
+}
+
+// One decision rule as evaluated, with the figures it compared
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RuleCheck {
+    pub rule: String,
+    pub passed: bool,
+    pub detail: serde_json::Value,
+}
+
+impl RuleCheck {
+    fn new(rule: &str, passed: bool, detail: serde_json::Value) -> Self {
+        Self { rule: rule.to_string(), passed, detail }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CommitmentDecision {
+    pub commit: bool,
+    pub amount: String,
+    pub simulated: bool,
+    pub checks: Vec<RuleCheck>,
+}
+
+// Capital-provider decision for entering an opportunity. Pure: the live path and
+// evaluate_hypothetical both call it with balances, open commitments, the chain head and gas
+// price read from state. A manual amount replaces the bid policy's sizing and concurrency cap.
+#[allow(clippy::too_many_arguments)]
+pub fn decide_commitment(
+    config: &NodeConfig,
+    opportunity: &AaveLiquidationOpportunity,
+    origin: OriginClass,
+    strategy: Option<&StrategyConfig>,
+    free_balance: U256,
+    operator_balance: U256,
+    open_commitments: usize,
+    head: Option<u64>,
+    gas_price_wei: Option<U256>,
+    manual_amount: Option<U256>,
+) -> CommitmentDecision {
+    let mut checks = Vec::new();
+    
+    let health_factor = opportunity.check_health_factor();
+    checks.push(RuleCheck::new("health_factor", health_factor.is_ok(), serde_json::json!({
+        "health_factor": opportunity.health_factor,
+        "error": health_factor.err(),
+    })));
+    
+    let strategy_enabled = config.strategy_enabled("aave-liquidation");
+    checks.push(RuleCheck::new("strategy_enabled", strategy_enabled, serde_json::json!({
+        "strategy_id": "aave-liquidation",
+        "enabled_strategies": config.enabled_strategies,
+    })));
+    
+    // Without a known head the deadline can't be judged, so it doesn't block
+    let deadline_ahead = head.map_or(true, |head| opportunity.deadline_block > head);
+    checks.push(RuleCheck::new("deadline_ahead", deadline_ahead, serde_json::json!({
+        "deadline_block": opportunity.deadline_block,
+        "head": head,
+    })));
+    
+    let max_repay = opportunity.max_repay_amount.parse::<U256>().unwrap_or(U256::ZERO);
+    let required = manual_amount.unwrap_or(max_repay);
+    checks.push(RuleCheck::new("capital_available", free_balance >= required, serde_json::json!({
+        "asset": opportunity.repay_asset,
+        "free_balance": free_balance.to_string(),
+        "required": required.to_string(),
+    })));
+    if let Some(amount) = manual_amount {
+        checks.push(RuleCheck::new("manual_amount", !amount.is_zero() && amount <= max_repay, serde_json::json!({
+            "amount": amount.to_string(),
+            "max_repay_amount": max_repay.to_string(),
+        })));
+    } else {
+        let limit = config.bid_policy.max_concurrent_commitments;
+        checks.push(RuleCheck::new("concurrent_commitments", limit == 0 || open_commitments < limit, serde_json::json!({
+            "open_commitments": open_commitments,
+            "max_concurrent_commitments": limit,
+        })));
+    }
+    
+    // Like the deadline, gas only counts once the bridge has reported a price
+    let max_gas_price = config.max_gas_price_wei(strategy);
+    checks.push(RuleCheck::new("gas_price_ceiling", gas_price_wei.map_or(true, |price| price <= max_gas_price), serde_json::json!({
+        "gas_price_wei": gas_price_wei.map(|price| price.to_string()),
+        "max_gas_price_wei": max_gas_price.to_string(),
+    })));
+    
+    let profit = opportunity.estimated_profit_usd.parse::<U256>().unwrap_or(U256::ZERO);
+    let gas_cost = gas_price_wei.map_or(U256::ZERO, |price| config.gas_cost_model.execution_cost_usd(price));
+    let net_profit = profit.saturating_sub(gas_cost);
+    let min_profit = config.min_profit_for(origin, strategy);
+    let clears_bar = clears_profit_bar(net_profit, min_profit, config.profit_entry_margin_bps, config.profit_exit_margin_bps, false);
+    checks.push(RuleCheck::new("profit_entry_bar", clears_bar, serde_json::json!({
+        "estimated_profit_usd": profit.to_string(),
+        "gas_cost_usd": gas_cost.to_string(),
+        "net_profit_usd": net_profit.to_string(),
+        "min_profit_threshold_usd": min_profit.to_string(),
+        "origin": origin,
+        "entry_margin_bps": config.profit_entry_margin_bps,
+    })));
+    
+    // Anything beyond our operator-funded balance would be backed by demo capital
+    let cap = match manual_amount {
+        Some(amount) => amount,
+        None => config.bid_policy.bid_cap(&opportunity.repay_asset, free_balance),
+    };
+    let commit = checks.iter().all(|check| check.passed) && !cap.is_zero();
+    let amount = if commit { free_balance.min(max_repay).min(cap) } else { U256::ZERO };
+    let simulated = commit && amount > operator_balance;
+    checks.push(RuleCheck::new("commitment_sizing", commit, serde_json::json!({
+        "amount": amount.to_string(),
+        "bid_cap": cap.to_string(),
+        "manual": manual_amount.is_some(),
+        "operator_balance": operator_balance.to_string(),
+        "simulated": simulated,
+    })));
+    
+    CommitmentDecision { commit, amount: amount.to_string(), simulated, checks }
+}
+
+// Executor-side checks for dispatching a stored opportunity with the given intents. Pure and
+// read-only; mirrors the gates dispatch_opportunity applies.
+#[allow(clippy::too_many_arguments)]
+pub fn executor_checks(
+    config: &NodeConfig,
+    strategy: Option<&StrategyConfig>,
+    data: &OpportunityData,
+    opportunity: &AaveLiquidationOpportunity,
+    intents: &[IntentData],
+    our_node: &str,
+    now: u64,
+    head: Option<u64>,
+    gas_price_wei: Option<U256>,
+) -> Vec<RuleCheck> {
+    let mut checks = Vec::new();
+    
+    checks.push(RuleCheck::new("executor_enabled", config.executor_enabled, serde_json::json!({})));
+    checks.push(RuleCheck::new("execution_rights", data.may_execute(our_node), serde_json::json!({
+        "holder": data.execution_holder(),
+        "claimed_by": data.claimed_by,
+    })));
+    
+    let max_repay = opportunity.max_repay_amount.parse::<U256>().unwrap_or(U256::ZERO);
+    let coverage = intent_coverage(opportunity, intents);
+    checks.push(RuleCheck::new("coverage", !coverage.is_zero(), serde_json::json!({
+        "coverage": coverage.to_string(),
+        "max_repay_amount": max_repay.to_string(),
+        "intents": intents.len(),
+    })));
+    
+    let max_gas_price = config.max_gas_price_wei(strategy);
+    checks.push(RuleCheck::new("gas_price_ceiling", gas_price_wei.map_or(true, |price| price <= max_gas_price), serde_json::json!({
+        "gas_price_wei": gas_price_wei.map(|price| price.to_string()),
+        "max_gas_price_wei": max_gas_price.to_string(),
+        "deferred_since": data.gas_deferral.as_ref().map(|deferral| deferral.since),
+    })));
+    
+    let profit = opportunity.estimated_profit_usd.parse::<U256>().unwrap_or(U256::ZERO);
+    let min_profit = config.min_profit_threshold_usd.parse::<U256>().unwrap_or(U256::ZERO);
+    checks.push(RuleCheck::new("profit_dispatch_bar", clears_profit_bar(
+        profit, min_profit, config.profit_entry_margin_bps, config.profit_exit_margin_bps, data.dispatch_armed,
+    ), serde_json::json!({
+        "estimated_profit_usd": profit.to_string(),
+        "min_profit_threshold_usd": min_profit.to_string(),
+        "armed": data.dispatch_armed,
+    })));
+    
+    if let Some(rule) = config.provider_requirement_for(max_repay) {
+        let distinct_providers = intents.iter()
+            .filter(|intent| intent.parsed_intent().map_or(false, |i| i.can_fund(opportunity)))
+            .map(|intent| intent.submitter_node.as_str())
+            .collect::<HashSet<_>>()
+            .len() as u32;
+        let waited_blocks = blocks_since(head, data.received_block, now, data.received_at);
+        let window_blocks = data.intent_window_blocks(config.provider_wait_blocks);
+        let passed = distinct_providers >= rule.min_providers
+            || (provider_wait_over(waited_blocks, window_blocks, head, opportunity.deadline_block)
+                && config.allow_under_provisioned_execution);
+        checks.push(RuleCheck::new("distinct_providers", passed, serde_json::json!({
+            "distinct_providers": distinct_providers,
+            "min_providers": rule.min_providers,
+            "threshold_usd": rule.threshold_usd,
+            "waited_blocks": waited_blocks,
+            "provider_wait_blocks": window_blocks,
+            "window_extension": data.window_extension,
+        })));
+    }
+    
+    checks
+}
+
+impl OpportunityData {
+    pub fn new(
+        opportunity: String,
+        strategy_id: StrategyId,
+        finder_node: String,
+        executor_preference: Vec<String>,
+    ) -> Self {
+        let now = HyperMevApp::current_timestamp();
+        Self {
+            opportunity,
+            strategy_id,
+            finder_node,
+            received_at: now.parse().unwrap_or(0),
+            executor_preference,
+            execution_holder_index: 0,
+            holder_since: now,
+            received_block: None,
+            holder_since_block: None,
+            claimed_by: None,
+            provider_shortfall: None,
+            participating: false,
+            dispatch_armed: false,
+            allocation_locked: false,
+            evaluation_path: None,
+            audit_trail: Vec::new(),
+            claiming_finders: Vec::new(),
+            window_extension: None,
+            origin: OpportunityOrigin::default(),
+            received_from: None,
+            risk_flags: BTreeSet::new(),
+            risk_approved: false,
+            executor_elected: false,
+            status: OpportunityStatus::Open,
+            config_revision: 0,
+            broadcast_to: BTreeSet::new(),
+            bid_status: None,
+            gas_deferral: None,
+        }
+    }
+    
+    // Blocks after receipt during which we wait for providers, including any extension
+    pub fn intent_window_blocks(&self, provider_wait_blocks: u64) -> u64 {
+        match self.window_extension {
+            Some(_) => provider_wait_blocks + INTENT_WINDOW_EXTENSION_BLOCKS,
+            None => provider_wait_blocks,
+        }
+    }
+    
+    // Prices the record at the best terms any claiming finder offers, until allocation locks it
+    fn adopt_best_terms(&mut self, canonical_opp_id: &str) {
+        if self.allocation_locked {
+            return;
+        }
+        let Some(best) = best_claim(&self.claiming_finders) else {
+            return;
+        };
+        if let Ok(mut opportunity) = serde_json::from_str::<AaveLiquidationOpportunity>(&best.opportunity) {
+            opportunity.opp_id = canonical_opp_id.to_string();
+            if let Ok(json) = serde_json::to_string(&opportunity) {
+                self.opportunity = json;
+            }
+        }
+    }
+    
+    pub fn execution_holder(&self) -> Option<&String> {
+        self.executor_preference.get(self.execution_holder_index)
+    }
+
+    // Without a preference list every executor may act, matching the original behavior
+    pub fn may_execute(&self, node: &str) -> bool {
+        if self.executor_preference.is_empty() {
+            return true;
+        }
+        self.execution_holder().map(|h| h == node).unwrap_or(false)
+            && self.claimed_by.as_ref().map(|c| c == node).unwrap_or(true)
+    }
+    
+    // A receipt counts only from the executor that claimed the opportunity, or one may_execute allows
+    pub fn may_report_receipt(&self, node: &str) -> bool {
+        self.claimed_by.as_deref() == Some(node) || self.may_execute(node)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct IntentData {
+    pub intent: String,
+    pub submitter_node: String,
+    pub max_amount: String,
+    pub expires_block: u64,
+    #[serde(deserialize_with = "numeric_timestamp")]
+    pub received_at: u64,
+    #[serde(default)]
+    pub fee_bps: u16,
+}
+
+impl IntentData {
+    pub fn parsed_intent(&self) -> Option<AaveLiquidationIntent> {
+        serde_json::from_str(&self.intent).ok()
+    }
+    
+    // Hashed over the re-serialized intent so submitter and executor agree regardless of formatting
+    pub fn intent_hash(&self) -> String {
+        match self.parsed_intent().and_then(|intent| serde_json::to_string(&intent).ok()) {
+            Some(canonical) => intent_hash(&canonical),
+            None => intent_hash(&self.intent),
+        }
+    }
+    
+    pub fn allocation_candidate(&self) -> AllocationCandidate {
+        AllocationCandidate {
+            intent_hash: self.intent_hash(),
+            provider: self.submitter_node.clone(),
+            max_amount: self.max_amount.clone(),
+            fee_bps: self.parsed_intent().map_or(self.fee_bps, |intent| intent.fee_bps),
+            received_at: self.received_at,
+        }
+    }
+}
+
+// An intent this node submitted, remembered so it can be amended if our balance drops
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct OutstandingIntent {
+    pub intent: AaveLiquidationIntent,
+    pub sent_to: Vec<String>,
+    pub submitted_at: String,
+    // Why an executor refused to shrink it; we remain on the hook for the amount it holds
+    #[serde(default)]
+    pub amendment_rejected: Option<String>,
+    // Config revision we committed under; its exit bar applies until we withdraw
+    #[serde(default)]
+    pub config_revision: u64,
+    // Why an executor refused our revocation; the intent stands and its capital is locked again
+    #[serde(default)]
+    pub revocation_rejected: Option<String>,
+}
+
+// Capital locked behind one of our intents; committed_amounts holds the per-asset totals
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CapitalCommitment {
+    pub asset: String,
+    pub amount: String,
+    pub committed_at: String,
+}
+
+// Blocks since `since_block` by the chain head, or estimated from `since_secs` when either is unknown
+pub fn blocks_since(head: Option<u64>, since_block: Option<u64>, now: u64, since_secs: u64) -> u64 {
+    match (head, since_block) {
+        (Some(head), Some(since_block)) => head.saturating_sub(since_block),
+        _ => now.saturating_sub(since_secs) / SECONDS_PER_BLOCK,
+    }
+}
+
+// Whether execution rights may move `steps` holders along the preference list after the current
+// holder has held them `held_for_blocks`: each holder passed over gets a full failover window, and
+// only a holder away for maintenance is passed over without waiting
+pub fn succession_due(held_for_blocks: u64, window_blocks: u64, steps: usize, holder_away: bool) -> bool {
+    if holder_away {
+        return held_for_blocks >= window_blocks.saturating_mul(steps.saturating_sub(1) as u64);
+    }
+    held_for_blocks >= window_blocks.saturating_mul(steps as u64)
+}
+
+// Whether the wait for more capital providers is over: its window ran out, or the head reached
+// the last block dispatch is still allowed at, so the deadline forces the decision before the
+// safety margin holds the opportunity for good
+pub fn provider_wait_over(waited_blocks: u64, window_blocks: u64, head: Option<u64>, deadline_block: u64) -> bool {
+    waited_blocks >= window_blocks
+        || head.is_some_and(|head| head + DISPATCH_SAFETY_MARGIN_BLOCKS >= deadline_block)
+}
+
+// How an intent was filed among those held for its opportunity
+#[derive(Debug, PartialEq)]
+pub enum IntentMerge {
+    Added,
+    // The submitter's earlier intent was replaced; its amount
+    Replaced { previous_amount: String },
+    // The intent held from the submitter was received later and stands
+    Stale,
+}
+
+// Files `entry` as its submitter's one intent on an opportunity, keeping the newest received_at:
+// it replaces the intent held from the same node unless that one was received after it
+pub fn merge_intent(intents: &mut Vec<IntentData>, entry: IntentData) -> IntentMerge {
+    match intents.iter_mut().find(|data| data.submitter_node == entry.submitter_node) {
+        Some(previous) if previous.received_at > entry.received_at => IntentMerge::Stale,
+        Some(previous) => {
+            let previous_amount = std::mem::replace(previous, entry).max_amount;
+            IntentMerge::Replaced { previous_amount }
+        }
+        None => {
+            intents.push(entry);
+            IntentMerge::Added
+        }
+    }
+}
+
+// Capital offered toward an opportunity, counting only intents usable for its repay asset and bonus
+pub fn intent_coverage(opportunity: &AaveLiquidationOpportunity, intents: &[IntentData]) -> U256 {
+    intents.iter()
+        .filter(|data| data.parsed_intent().map(|i| i.can_fund(opportunity)).unwrap_or(false))
+        .map(|data| data.max_amount.parse::<U256>().unwrap_or(U256::ZERO))
+        .fold(U256::ZERO, |acc, amount| acc + amount)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReceiptData {
+    pub receipt: String,
+    pub executor_node: String,
+    pub our_proceeds: String,
+    #[serde(deserialize_with = "numeric_timestamp")]
+    pub verified_at: u64,
+    #[serde(default)]
+    pub retention: ReceiptRetention,
+    // Headline figures, parsed once when the receipt is stored
+    #[serde(default)]
+    pub summary: Option<ReceiptSummary>,
+    // Set when replaying the executor's declared allocation didn't reproduce it
+    #[serde(default)]
+    pub allocation_dispute: Option<AllocationMismatch>,
+    #[serde(default)]
+    pub chain_verification: Option<ChainVerification>,
+    // Disputes a provider raised over the receipt's capital usage or fees, ours or the peer's
+    #[serde(default)]
+    pub disputes: Vec<DisputeRecord>,
+    // Our share, held out of PnL while a dispute is open
+    #[serde(default)]
+    pub withheld: Option<WithheldProceeds>,
+    // Asset our share is paid in, as the executor's breakdown declared it
+    #[serde(default)]
+    pub proceeds_asset: Option<String>,
+    #[serde(default)]
+    pub verification: ReceiptVerification,
+    #[serde(default)]
+    pub finality: ReceiptFinality,
+    // What counting the receipt changed, kept until it is final in case a reorg drops it
+    #[serde(default)]
+    pub applied: Option<AppliedEffects>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ReceiptSummary {
+    pub strategy_id: StrategyId,
+    pub status: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub total_proceeds: String,
+    pub gas_paid_usdc: String,
+}
+
+impl ReceiptSummary {
+    pub fn from_receipt(receipt: &str) -> Self {
+        let Ok(parsed) = serde_json::from_str::<AaveLiquidationReceipt>(receipt) else {
+            return Self { strategy_id: "aave-liquidation".to_string(), status: "Unreadable".to_string(), ..Default::default() };
+        };
+        Self {
+            strategy_id: "aave-liquidation".to_string(),
+            status: parsed.status.name().to_string(),
+            tx_hash: parsed.tx_hash,
+            block_number: parsed.block_number,
+            total_proceeds: parsed.total_proceeds,
+            gas_paid_usdc: parsed.gas_paid_usdc,
+        }
+    }
+}
+
+// Receipts only become archivable once nothing is owed on them; age alone never qualifies
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub enum ReceiptRetention {
+    #[default]
+    Verified,
+    Settled,
+    Archivable,
+}
+
+impl ReceiptData {
+    pub fn new(receipt: String, executor_node: String, our_proceeds: String) -> Self {
+        // Failed executions and receipts paying us nothing have no settlement outstanding
+        let settled = match serde_json::from_str::<AaveLiquidationReceipt>(&receipt).map(|r| r.status) {
+            Ok(ExecutionStatus::Failed(_)) => true,
+            Ok(ExecutionStatus::Success) => our_proceeds.parse::<U256>().map(|p| p.is_zero()).unwrap_or(false),
+            _ => false,
+        };
+        let summary = ReceiptSummary::from_receipt(&receipt);
+        Self {
+            receipt,
+            executor_node,
+            our_proceeds,
+            verified_at: HyperMevApp::current_timestamp().parse().unwrap_or(0),
+            retention: if settled { ReceiptRetention::Settled } else { ReceiptRetention::Verified },
+            summary: Some(summary),
+            allocation_dispute: None,
+            chain_verification: None,
+            disputes: Vec::new(),
+            withheld: None,
+            proceeds_asset: None,
+            verification: ReceiptVerification::Verified,
+            finality: ReceiptFinality::Final,
+            applied: None,
+        }
+    }
+    
+    pub fn is_disputed(&self) -> bool {
+        self.disputes.iter().any(DisputeRecord::is_open)
+    }
+    
+    // Stored summary, or one parsed on the spot for receipts kept before summaries existed
+    pub fn summary(&self) -> ReceiptSummary {
+        self.summary.clone().unwrap_or_else(|| ReceiptSummary::from_receipt(&self.receipt))
+    }
+
+    pub fn summarize(&self, opp_id: &str, archived_at: String) -> ArchivedReceipt {
+        let parsed = serde_json::from_str::<AaveLiquidationReceipt>(&self.receipt).ok();
+        let summary = self.summary();
+        ArchivedReceipt {
+            opp_id: opp_id.to_string(),
+            executor_node: self.executor_node.clone(),
+            status: summary.status,
+            tx_hash: summary.tx_hash,
+            block_number: summary.block_number,
+            total_proceeds: summary.total_proceeds,
+            gas_paid_usdc: summary.gas_paid_usdc,
+            our_proceeds: self.our_proceeds.clone(),
+            participants: parsed.as_ref().map(|r| r.used_amounts.iter().map(|u| u.node_id.clone()).collect()).unwrap_or_default(),
+            capital_usage: parsed.map(|r| r.used_amounts).unwrap_or_default(),
+            verified_at: self.verified_at,
+            archived_at,
+        }
+    }
+}
+
+// Compact record kept after an archivable receipt's full payload is dropped
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ArchivedReceipt {
+    pub opp_id: String,
+    pub executor_node: String,
+    pub status: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub total_proceeds: String,
+    pub gas_paid_usdc: String,
+    pub our_proceeds: String,
+    pub participants: Vec<String>,
+    // Kept so settlement statements still cover archived executions
+    #[serde(default)]
+    pub capital_usage: Vec<CapitalUsage>,
+    #[serde(deserialize_with = "numeric_timestamp")]
+    pub verified_at: u64,
+    pub archived_at: String,
+}
+
+// One provider's part in an execution between the two statement parties, stated neutrally so
+// both sides derive the same line from their own records
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatementLine {
+    pub opp_id: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub status: String,
+    pub executor: String,
+    pub provider: String,
+    pub asset: String,
+    pub amount_used: String,
+    // Owed by the executor to the provider
+    pub share_owed: String,
+}
+
+// Settlement statement between two nodes over a period. Contains nothing local to the
+// generating node (no generation time, parties sorted), so both sides produce identical bytes
+// when their records agree.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SettlementStatement {
+    pub parties: Vec<String>,
+    pub from: u64,
+    pub to: u64,
+    pub lines: Vec<StatementLine>,
+    // Total owed to each party by the other
+    pub owed_to: BTreeMap<String, String>,
+    // Party owed on balance, and how much; None when square
+    pub net_creditor: Option<String>,
+    pub net_amount: String,
+}
+
+impl SettlementStatement {
+    pub fn canonical_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+    
+    // FNV-1a over the canonical JSON, the same hash intents are identified by
+    pub fn hash(&self) -> String {
+        intent_hash(&self.canonical_json())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum DiscrepancyKind {
+    MissingFromOurs,
+    MissingFromTheirs,
+    Mismatch,
+}
+
+// A line on which a peer's statement and ours disagree; raised before any dispute
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StatementDiscrepancy {
+    pub opp_id: String,
+    pub provider: String,
+    pub kind: DiscrepancyKind,
+    pub ours: Option<StatementLine>,
+    pub theirs: Option<StatementLine>,
+}
+
+// Outcome of the last statement exchange with a peer
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StatementComparison {
+    pub from: u64,
+    pub to: u64,
+    pub our_hash: String,
+    pub their_hash: String,
+    pub compared_at: String,
+    pub discrepancies: Vec<StatementDiscrepancy>,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug)]
+pub struct AaveStrategyConfig {
+    #[serde(default)]
+    pub monitored_positions: HashMap<String, PositionData>,
+    #[serde(default)]
+    pub subscription_ids: Vec<u64>,
+    #[serde(default)]
+    pub min_health_factor: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PositionData {
+    pub borrower: String,
+    pub collateral_asset: String,
+    pub debt_asset: String,
+    pub last_health_factor: String,
+    pub last_updated: String,
+    #[serde(default)]
+    pub debt_amount: String,
+    // Block at which the bridge last re-checked the position
+    #[serde(default)]
+    pub last_checked_block: u64,
+    // Peer whose PositionUpdate relay this last came from; None for our bridge's own read
+    #[serde(default)]
+    pub relayed_by: Option<String>,
+}
+
+// HYPERPROCESS APPLICATION IMPLEMENTATION
+#[hyperprocess(
+    name = "Hyper-MEV P2P Pool",
+    ui = Some(HttpBindingConfig::default()),
+    endpoints = vec![
+        Binding::Http { 
+            path: "/api", 
             config: HttpBindingConfig::new(false, false, false, None) 
         }
-    ],
-    save_config = SaveOptions::OnDiff,
-    wit_world = "hyper-mev-dot-os-v0"
-)]
-impl HyperMevApp {
-    #[init]
-    async fn initialize(&mut self) {
-        // Add to homepage
-        add_to_homepage("Hyper-MEV P2P Pool", Some("⚡"), Some("/"), None);
+    ],
+    save_config = SaveOptions::OnDiff,
+    wit_world = "hyper-mev-dot-os-v0"
+)]
+impl HyperMevApp {
+    #[init]
+    async fn initialize(&mut self) {
+        // Add to homepage
+        add_to_homepage("Hyper-MEV P2P Pool", Some("⚡"), Some("/"), None);
+        
+        // Initialize node configuration
+        self.node_config.node_id = our().node.clone();
+        self.active_strategy = Some("aave-liquidation".to_string());
+        
+        // Initialize strategy config
+        self.migrate_strategy_configs();
+        if self.config_history.is_empty() {
+            self.record_config_revision("Initial");
+        }
+        
+        if self.performance_metrics.since == 0 {
+            self.performance_metrics.since = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        }
+        
+        self.collapse_duplicate_intents();
+        
+        // Demo capital is opt-in and tracked apart from operator funds
+        self.migrate_seed_capital();
+        self.apply_demo_capital_setting();
+        
+        // Set up WebSocket server for Artemis MEV bot
+        let mut http_server = HttpServer::new(5);
+        let ws_config = WsBindingConfig::new(false, false, false);
+        http_server.bind_ws_path("/artemis", ws_config).unwrap();
+        // And for the UI's live feed
+        http_server.bind_ws_path("/updates", WsBindingConfig::new(false, false, false)).unwrap();
+        self.http_server = Some(http_server);
+        
+        println!("Hyper-MEV P2P Pool initialized on node: {}", our().node);
+        println!("Strategy: {}", self.active_strategy.as_ref().unwrap());
+        println!("WebSocket endpoint available at /artemis for Artemis MEV bot");
+        println!("WebSocket endpoint available at /updates for the UI's live feed");
+        if !self.pending_executions.is_empty() {
+            // Reconciled with the bridge as soon as it connects
+            println!("{} executions were in flight at shutdown; awaiting the bridge", self.pending_executions.len());
+        }
+        
+        self.our_networking_key = Self::networking_key(&our().node).await;
+        if self.our_networking_key.is_none() {
+            println!("Could not look up our networking key; announcements go out without it");
+        }
+        if self.signing_key.is_none() {
+            let key = SigningKeyPair::generate();
+            println!("Generated signing key {}", key.public_key);
+            self.signing_key = Some(key);
+        }
+        self.reannounce_on_startup().await;
+        
+        // Maintenance runs for the life of the process
+        loop {
+            let _ = hyperware_app_common::sleep(MAINTENANCE_INTERVAL_MS).await;
+            self.run_maintenance();
+        }
+    }
+    
+    // HTTP ENDPOINTS FOR FRONTEND
+    
+    #[http]
+    async fn get_node_status(&self, _request_body: String) -> Result<String, String> {
+        let status = serde_json::json!({
+            "node_id": self.node_config.node_id,
+            // Shown whenever a recording exists, so capture is never on unnoticed
+            "recording": self.recording.borrow().as_ref().map(|recording| recording.status()),
+            "active_strategy": self.active_strategy,
+            "peer_count": self.known_peers.len(),
+            "reachable_peer_count": self.reachable_peers().count(),
+            "opportunity_count": self.active_opportunities.len(),
+            "intent_count": self.submitted_intents.len(),
+            "available_capital": self.available_balances,
+            "capital": self.capital_summary(),
+            "earned_proceeds": self.earned_proceeds,
+            "capital_provenance": self.capital_provenance(),
+            "unconfirmed_seed_capital": self.stripped_seed_capital,
+            "artemis_health": self.artemis_health,
+            "chain_head": self.chain_head.map(|head| head.status(Self::current_timestamp().parse::<u64>().unwrap_or(0))),
+            "local_subscribers": self.local_subscribers.subscribers,
+            "pending_executions": self.pending_executions,
+            "enabled_strategies": self.node_config.enabled_strategies,
+            "ignored_broadcasts": self.ignored_broadcasts,
+            "roles": {
+                "finder_enabled": self.node_config.finder_enabled,
+                "capital_provider_enabled": self.node_config.capital_provider_enabled,
+                "executor_enabled": self.node_config.executor_enabled
+            },
+            "config": {
+                "finder_fee_bps": self.node_config.finder_fee_bps,
+                "executor_fee_bps": self.node_config.executor_fee_bps,
+                "min_profit_threshold_usd": self.node_config.min_profit_threshold_usd.to_string(),
+                "max_gas_price_gwei": self.node_config.max_gas_price_gwei.to_string()
+            }
+        });
+        
+        Ok(status.to_string())
+    }
+    
+    #[http] 
+    async fn update_node_config(&mut self, request_body: String) -> Result<String, String> {
+        let update: ConfigUpdate = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid config update: {}", e))?;
+        let trial_secs = update.trial_secs;
+        let staged = self.stage_config_update(update)?;
+        
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let trial_note = match trial_secs {
+            Some(0) => return Err("Trial window must be positive".to_string()),
+            Some(secs) => {
+                // Updates during a trial join it: reverting still returns to the pre-trial config
+                let previous = self.config_trial.take()
+                    .map(|trial| trial.previous)
+                    .unwrap_or_else(|| self.node_config.clone());
+                self.config_trial = Some(ConfigTrial { previous, started_at: now, revert_at: now + secs });
+                format!(" (trial: reverts in {}s unless committed with commit_config)", secs)
+            }
+            None => match &self.config_trial {
+                Some(trial) => format!(" (part of the trial reverting in {}s)", trial.revert_at.saturating_sub(now)),
+                None => String::new(),
+            },
+        };
+        self.apply_node_config(staged, if trial_secs.is_some() { "Trial" } else { "Update" });
+        
+        Ok(format!("Configuration updated successfully{}", trial_note))
+    }
+    
+    // Keeps the configuration under trial; without this it reverts when the window closes
+    #[http]
+    async fn commit_config(&mut self, _request_body: String) -> Result<String, String> {
+        let trial = self.config_trial.take().ok_or("No configuration trial in progress")?;
+        self.record_journal("ConfigCommitted", serde_json::json!({
+            "trial_started_at": trial.started_at,
+            "changed": changed_config_fields(&trial.previous, &self.node_config),
+        }));
+        Ok("Configuration committed".to_string())
+    }
+    
+    // Credits operator capital. A retried request carrying the same operation_id gets the original
+    // result back instead of being credited twice.
+    #[http]
+    async fn add_capital(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct CapitalAddition {
+            asset: String,
+            amount: String,
+            #[serde(default)]
+            operation_id: Option<String>,
+        }
+        
+        let addition: CapitalAddition = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid capital addition: {}", e))?;
+        if let Some(outcome) = addition.operation_id.as_deref()
+            .and_then(|id| self.capital_ledger.replay(id, CapitalOperationKind::Add, &addition.asset, &addition.amount)) {
+            return outcome;
+        }
+        
+        let outcome = self.credit_capital(&addition.asset, &addition.amount);
+        self.finish_capital_operation(addition.operation_id, CapitalOperationKind::Add, &addition.asset, &addition.amount, outcome)
+    }
+    
+    // Takes operator capital out of the pool; like add_capital, a retried operation_id is answered
+    // from the log rather than applied again
+    #[http]
+    async fn remove_capital(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct CapitalRemoval {
+            asset: String,
+            amount: String,
+            #[serde(default)]
+            operation_id: Option<String>,
+        }
+        
+        let removal: CapitalRemoval = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid capital removal: {}", e))?;
+        if let Some(outcome) = removal.operation_id.as_deref()
+            .and_then(|id| self.capital_ledger.replay(id, CapitalOperationKind::Remove, &removal.asset, &removal.amount)) {
+            return outcome;
+        }
+        
+        let outcome = self.debit_capital(&removal.asset, &removal.amount);
+        self.finish_capital_operation(removal.operation_id, CapitalOperationKind::Remove, &removal.asset, &removal.amount, outcome)
+    }
+    
+    // Logged capital operations, newest first; `asset` narrows to one asset
+    #[http]
+    async fn get_capital_history(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        struct HistoryFilter {
+            #[serde(default)]
+            asset: Option<String>,
+        }
+        
+        let filter: HistoryFilter = if request_body.trim().is_empty() {
+            HistoryFilter::default()
+        } else {
+            serde_json::from_str(&request_body).map_err(|e| format!("Invalid history filter: {}", e))?
+        };
+        let operations = self.capital_ledger.history(filter.asset.as_deref());
+        Ok(serde_json::json!({
+            "operations": operations,
+            "count": operations.len(),
+        }).to_string())
+    }
+    
+    // Resolves seed capital stripped during migration: restore it as operator funds if it is real
+    #[http]
+    async fn confirm_seed_capital(&mut self, request_body: String) -> Result<String, String> {
+        let is_real: bool = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid confirmation: {}", e))?;
+        let Some(amount) = self.stripped_seed_capital.take() else {
+            return Err("No stripped seed capital awaiting confirmation".to_string());
+        };
+        
+        if is_real {
+            capital::credit(&mut self.available_balances, DEMO_CAPITAL_ASSET, amount.parse::<U256>().unwrap_or(U256::ZERO));
+        }
+        self.record_journal("SeedCapitalResolved", serde_json::json!({
+            "asset": DEMO_CAPITAL_ASSET,
+            "amount": amount,
+            "restored_as_real": is_real,
+        }));
+        
+        Ok(if is_real { "Seed capital restored as operator funds" } else { "Seed capital discarded" }.to_string())
+    }
+    
+    #[http]
+    async fn connect_to_peer(&mut self, request_body: String) -> Result<String, String> {
+        let peer_node: String = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid peer node: {}", e))?;
+        
+        if peer_node == our().node {
+            return Err("Cannot connect to self".to_string());
+        }
+        if let Some(reason) = self.node_config.peer_blocked(&peer_node) {
+            return Err(format!("Cannot connect to {}: {}", peer_node, reason));
+        }
+        
+        // Add to known peers; it stays pending until it announces itself back
+        self.known_peers.entry(peer_node.clone())
+            .or_insert_with(|| PeerInfo::pending(Self::current_timestamp()));
+        
+        // Send node announcement to new peer, then ask for what it holds; the announcement is
+        // queued ahead, so it knows us by the time the request lands
+        self.announce_to_peer(peer_node.clone(), false).await?;
+        self.request_state_sync(&peer_node);
+        
+        Ok(format!("Connected to peer: {}", peer_node))
+    }
+    
+    // Known peers with what they announced and their liveness: pending until they first answer,
+    // unreachable after missing heartbeat_miss_limit heartbeats
+    #[http]
+    async fn get_peers(&self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let now_ms = Self::current_timestamp_ms();
+        let limits = &self.node_config.inbound_rate_limits;
+        let queues = self.outbound_queues.borrow();
+        let mut peers: Vec<(&String, &PeerInfo)> = self.known_peers.iter().collect();
+        peers.sort_by(|a, b| a.0.cmp(b.0));
+        let peers: Vec<serde_json::Value> = peers.into_iter()
+            .map(|(node, info)| serde_json::json!({
+                "node": node,
+                "status": info.status(),
+                "roles": info.roles(),
+                "app_version": info.node_info.as_ref().map(|n| n.app_version.clone()),
+                "capital_assets": info.node_info.as_ref().map_or(Vec::new(), |n| n.capital_assets.clone()),
+                "capabilities": info.capabilities,
+                "added_at": info.added_at,
+                "last_seen": info.last_seen,
+                "missed_heartbeats": info.missed_heartbeats,
+                "unreachable_since": info.unreachable_since,
+                "maintenance": info.maintenance,
+                "in_maintenance": info.maintenance.is_some_and(|window| window.covers(now)),
+                "outbound_queue": queues.peers.get(node.as_str()).map(|queue| queue.summary()),
+                "reputation": self.peer_reputation.get(node).map(|reputation| reputation.summary()),
+                "rate_limits": self.inbound_rate_limiters.get(node)
+                    .map_or_else(|| PeerRateLimiter::default().summary(limits, now_ms), |limiter| limiter.summary(limits, now_ms)),
+            }))
+            .collect();
+        Ok(serde_json::json!({
+            "peers": peers,
+            "inbound_rate_limits": limits,
+            "our_maintenance": self.maintenance_window,
+        }).to_string())
+    }
+    
+    // Announces planned downtime (`{start?, duration_secs}`, start defaulting to now) so peers
+    // stop routing executions to us for deadlines inside it. Replaces any window already set.
+    #[http]
+    async fn schedule_maintenance(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct MaintenanceRequest {
+            #[serde(default)]
+            start: Option<u64>,
+            duration_secs: u64,
+        }
+        
+        let request: MaintenanceRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid maintenance request: {}", e))?;
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if request.duration_secs == 0 || request.duration_secs > MAX_MAINTENANCE_SECS {
+            return Err(format!("duration_secs must be between 1 and {}", MAX_MAINTENANCE_SECS));
+        }
+        let window = MaintenanceWindow {
+            start: request.start.unwrap_or(now).max(now),
+            duration_secs: request.duration_secs,
+        };
+        self.maintenance_window = Some(window);
+        self.record_journal("MaintenanceScheduled", serde_json::json!({
+            "start": window.start,
+            "duration_secs": window.duration_secs,
+        }));
+        println!("\n🛠️  Maintenance scheduled from {} for {}s", window.start, window.duration_secs);
+        self.announce_maintenance();
+        Ok(serde_json::json!({ "maintenance": window }).to_string())
+    }
+    
+    #[http]
+    async fn cancel_maintenance(&mut self, _request_body: String) -> Result<String, String> {
+        let Some(window) = self.maintenance_window.take() else {
+            return Err("No maintenance window is scheduled".to_string());
+        };
+        self.record_journal("MaintenanceCanceled", serde_json::json!({
+            "start": window.start,
+            "duration_secs": window.duration_secs,
+        }));
+        println!("\n🛠️  Maintenance canceled");
+        self.announce_maintenance();
+        Ok(serde_json::json!({ "canceled": window }).to_string())
+    }
+    
+    // Manual find from an operator without a bridge; broadcast like a bot find but marked Manual
+    #[http]
+    async fn submit_manual_opportunity(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ManualSubmission {
+            opportunity: AaveLiquidationOpportunity,
+            #[serde(default)]
+            operator_note: String,
+        }
+        
+        let submission: ManualSubmission = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid manual opportunity: {}", e))?;
+        submission.opportunity.validate(self.current_block())?;
+        let opp_id = submission.opportunity.opp_id.clone();
+        println!("\n✍️  Manual opportunity submitted:");
+        self.originate_opportunity(submission.opportunity, OpportunityOrigin::Manual {
+            operator_note: submission.operator_note,
+        });
+        
+        Ok(serde_json::json!({
+            "opp_id": opp_id,
+            "active": self.active_opportunities.contains_key(&self.canonical_opp_id(&opp_id)),
+        }).to_string())
+    }
+    
+    #[http]
+    async fn get_opportunities(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct OpportunityQuery {
+            limit: Option<usize>,
+            offset: usize,
+            strategy_id: Option<String>,
+            status: Option<OpportunityStatus>,
+            // Only those received at or after this unix time
+            since_timestamp: Option<u64>,
+            // Only those this node found or also claimed
+            finder_node: Option<String>,
+            // Only those waiting for a manual bid
+            pending_review: bool,
+        }
+        
+        // The UI sends an empty string when it has no parameters
+        let query: OpportunityQuery = match serde_json::from_str::<serde_json::Value>(&request_body) {
+            Ok(serde_json::Value::Object(params)) => serde_json::from_value(serde_json::Value::Object(params))
+                .map_err(|e| format!("Invalid opportunity query: {}", e))?,
+            _ => OpportunityQuery::default(),
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        let mut matching: Vec<(&String, &OpportunityData)> = self.active_opportunities.iter()
+            .filter(|(_, data)| query.status.map_or(true, |status| data.status == status)
+                && query.strategy_id.as_ref().map_or(true, |strategy| data.strategy_id == *strategy)
+                && query.since_timestamp.map_or(true, |since| data.received_at >= since)
+                && (!query.pending_review || data.bid_status == Some(BidStatus::PendingReview))
+                && query.finder_node.as_ref().map_or(true, |node| data.finder_node == *node
+                    || data.claiming_finders.iter().any(|claim| claim.finder == *node)))
+            .collect();
+        matching.sort_by(|a, b| b.1.received_at.cmp(&a.1.received_at).then_with(|| a.0.cmp(b.0)));
+        let total = matching.len();
+        let has_more = total > query.offset.saturating_add(limit);
+        
+        let items: Vec<_> = matching.into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .map(|(opp_id, data)| serde_json::json!({
+                "opp_id": opp_id,
+                "status": data.status,
+                "strategy_id": data.strategy_id,
+                "finder_node": data.finder_node,
+                "received_from": data.received_from.as_ref().unwrap_or(&data.finder_node),
+                "received_at": data.received_at,
+                "execution_holder": data.execution_holder(),
+                "elected_executor": data.executor_elected.then(|| data.execution_holder()).flatten(),
+                "executor_ranking": data.executor_preference,
+                "claimed_by": data.claimed_by,
+                "provider_shortfall": data.provider_shortfall,
+                "evaluation_path": data.evaluation_path,
+                "risk_flags": data.risk_flags,
+                "risk_approved": data.risk_approved,
+                "risk_action": self.risk_gate(opp_id).0,
+                "reservations": self.reservation_rounds.get(opp_id),
+                "our_reservation": self.capital_reservations.get(opp_id),
+                "audit_trail": data.audit_trail,
+                "claiming_finders": data.claiming_finders,
+                "window_extension": data.window_extension,
+                "bid_status": data.bid_status,
+                "gas_deferral": data.gas_deferral,
+                "finder_fee_policy": self.node_config.duplicate_finder_policy,
+                "opportunity": data.opportunity
+            }))
+            .collect();
+        
+        Ok(serde_json::json!({
+            "items": items,
+            "total": total,
+            "has_more": has_more,
+        }).to_string())
+    }
+    
+    // Intents received per opportunity, optionally for one `opp_id`, with the bid total and how
+    // much of it counts toward the repay asset
+    #[http]
+    async fn get_intents(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        struct IntentFilter {
+            #[serde(default)]
+            opp_id: Option<String>,
+        }
+        
+        let filter: IntentFilter = if request_body.trim().is_empty() {
+            IntentFilter::default()
+        } else {
+            serde_json::from_str::<Option<IntentFilter>>(&request_body)
+                .map_err(|e| format!("Invalid intent filter: {}", e))?
+                .unwrap_or_default()
+        };
+        let wanted = filter.opp_id.map(|opp_id| self.canonical_opp_id(&opp_id));
+        
+        let our_node = our().node;
+        let mut opp_ids: Vec<&String> = self.submitted_intents.keys()
+            .filter(|opp_id| wanted.as_ref().map_or(true, |wanted| *opp_id == wanted))
+            .collect();
+        opp_ids.sort();
+        let listing: Vec<_> = opp_ids.into_iter()
+            .map(|opp_id| {
+                let intents = &self.submitted_intents[opp_id];
+                let total_bid = intents.iter()
+                    .map(|data| data.max_amount.parse::<U256>().unwrap_or(U256::ZERO))
+                    .fold(U256::ZERO, |acc, amount| acc + amount);
+                let opportunity = self.active_opportunities.get(opp_id)
+                    .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok());
+                serde_json::json!({
+                    "opp_id": opp_id,
+                    "intents": intents.iter().map(|data| serde_json::json!({
+                        "submitter_node": data.submitter_node,
+                        "max_amount": data.max_amount,
+                        "expires_block": data.expires_block,
+                        "received_at": data.received_at,
+                        "fee_bps": data.allocation_candidate().fee_bps,
+                    })).collect::<Vec<_>>(),
+                    "includes_ours": intents.iter().any(|data| data.submitter_node == our_node),
+                    "total_bid": total_bid.to_string(),
+                    "coverage": opportunity.as_ref().map(|opportunity| intent_coverage(opportunity, intents).to_string()),
+                    "max_repay_amount": opportunity.map(|opportunity| opportunity.max_repay_amount),
+                })
+            })
+            .collect();
+        
+        Ok(serde_json::to_string(&listing).unwrap_or_else(|_| "[]".to_string()))
+    }
+    
+    // Executors' proceeds distributions, newest first: each with what it owes us and any flags we
+    // raised against it, optionally for one `opp_id`, those naming `node_id`, or only `flagged`
+    // ones, plus what the whole ledger owes us per asset. Alongside, our net position with each
+    // peer per asset (with only `node_id` when given), signed, and the payments recorded against them,
+    // then the settlement epoch: its gross entries, netting statements, and the net payables agreed.
+    #[http]
+    async fn get_settlements(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct SettlementQuery {
+            opp_id: Option<String>,
+            node_id: Option<String>,
+            flagged: bool,
+            limit: Option<usize>,
+            offset: usize,
+        }
+        
+        let query: SettlementQuery = match serde_json::from_str::<serde_json::Value>(&request_body) {
+            Ok(serde_json::Value::Object(params)) => serde_json::from_value(serde_json::Value::Object(params))
+                .map_err(|e| format!("Invalid settlement query: {}", e))?,
+            _ => SettlementQuery::default(),
+        };
+        let wanted = query.opp_id.map(|opp_id| self.canonical_opp_id(&opp_id));
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        let mut matching: Vec<&DistributionRecord> = self.proceeds_distributions.records.values()
+            .filter(|record| wanted.as_ref().map_or(true, |opp_id| record.distribution.opp_id == *opp_id)
+                && query.node_id.as_ref().map_or(true, |node| record.distribution.executor == *node
+                    || record.distribution.entries.iter().any(|entry| entry.node_id == *node))
+                && (!query.flagged || !record.flags.is_empty()))
+            .collect();
+        matching.sort_by(|a, b| b.received_at.cmp(&a.received_at).then_with(|| a.distribution.opp_id.cmp(&b.distribution.opp_id)));
+        let total = matching.len();
+        let has_more = total > query.offset.saturating_add(limit);
+        
+        let mut owed_to_us: BTreeMap<String, U256> = BTreeMap::new();
+        for record in self.proceeds_distributions.records.values() {
+            let Some(asset) = record.distribution.entries.first().map(|entry| entry.asset.clone()) else {
+                continue;
+            };
+            *owed_to_us.entry(asset).or_insert(U256::ZERO) += record.owed_to_us.parse::<U256>().unwrap_or(U256::ZERO);
+        }
+        let items: Vec<_> = matching.into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .collect();
+        
+        let positions: BTreeMap<&String, BTreeMap<&String, &SignedAmount>> = self.settlement_ledger.iter()
+            .filter(|(peer, _)| query.node_id.as_ref().map_or(true, |node| *peer == node))
+            .map(|(peer, assets)| (peer, assets.iter().collect()))
+            .collect();
+        let with_node = |peer: &str| query.node_id.as_ref().map_or(true, |node| node == peer);
+        let epochs = &self.settlement_epochs;
+        let netted: Vec<serde_json::Value> = epochs.outstanding().into_iter()
+            .filter(|((payer, payee, _), _)| with_node(payer) || with_node(payee))
+            .map(|((payer, payee, asset), amount)| serde_json::json!({
+                "payer": payer,
+                "payee": payee,
+                "asset": asset,
+                "amount": amount.to_string(),
+            }))
+            .collect();
+        
+        Ok(serde_json::json!({
+            "items": items,
+            "total": total,
+            "has_more": has_more,
+            "owed_to_us": owed_to_us.into_iter()
+                .map(|(asset, amount)| (asset, amount.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            "positions": positions,
+            "payments": self.settlement_payments.history(query.node_id.as_deref()).into_iter()
+                .take(limit)
+                .collect::<Vec<_>>(),
+            "epoch": {
+                "number": epochs.epoch,
+                "started_at": epochs.started_at,
+                "started_block": epochs.started_block,
+                "length": self.node_config.settlement_epoch,
+            },
+            "gross_entries": epochs.entries.iter().rev()
+                .filter(|entry| with_node(&entry.peer))
+                .take(limit)
+                .collect::<Vec<_>>(),
+            "netted_positions": netted,
+            "net_payables": epochs.net_payables.iter().rev()
+                .filter(|payable| with_node(&payable.payer) || with_node(&payable.payee))
+                .take(limit)
+                .collect::<Vec<_>>(),
+            "netting_statements": epochs.statements.values()
+                .filter(|statement| with_node(&statement.debtor) || with_node(&statement.creditor))
+                .collect::<Vec<_>>(),
+        }).to_string())
+    }
+    
+    // Closes every open dispute on a receipt, once the operator has settled it with the other
+    // side, and puts any share held back from PnL back in
+    #[http]
+    async fn resolve_dispute(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ResolveRequest {
+            opp_id: String,
+            #[serde(default)]
+            note: Option<String>,
+        }
+        
+        let request: ResolveRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid dispute resolution: {}", e))?;
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let data = self.execution_receipts.get_mut(&opp_id).ok_or("Receipt not found")?;
+        if !data.is_disputed() {
+            return Err(serde_json::json!({
+                "error": "NoOpenDispute",
+                "opp_id": opp_id,
+            }).to_string());
+        }
+        let mut resolved = 0;
+        for record in data.disputes.iter_mut().filter(|record| record.is_open()) {
+            record.resolution = Some(DisputeResolution { resolved_at: now, note: request.note.clone() });
+            resolved += 1;
+        }
+        let restored = data.withheld.take();
+        if let Some(withheld) = &restored {
+            let amount = withheld.amount.parse::<U256>().unwrap_or(U256::ZERO);
+            capital::credit(&mut self.earned_proceeds, &withheld.asset, amount);
+            if withheld.from_metrics {
+                self.performance_metrics.strategy(&withheld.strategy_id).note_proceeds(&withheld.asset, amount);
+            }
+        }
+        self.record_journal("ReceiptDisputeResolved", serde_json::json!({
+            "opp_id": opp_id,
+            "resolved": resolved,
+            "note": request.note,
+            "restored": restored,
+        }));
+        Ok(serde_json::json!({
+            "opp_id": opp_id,
+            "resolved": resolved,
+            "restored": restored,
+        }).to_string())
+    }
+    
+    // Records a payment made outside the pool that settles our position with `peer` in `asset`:
+    // the position is zeroed, and `tx_hash` kept as the reference. Settles whichever way the
+    // position runs; nothing to settle is an error.
+    #[http]
+    async fn mark_settled(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct SettledRequest {
+            peer: String,
+            asset: String,
+            tx_hash: String,
+            #[serde(default)]
+            note: Option<String>,
+        }
+        
+        let request: SettledRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid settlement: {}", e))?;
+        if request.tx_hash.trim().is_empty() {
+            return Err("A settlement needs the tx_hash of the payment".to_string());
+        }
+        let settled = ledger::position_of(&self.settlement_ledger, &request.peer, &request.asset);
+        if settled.is_zero() {
+            return Err(serde_json::json!({
+                "error": "NothingToSettle",
+                "peer": request.peer,
+                "asset": request.asset,
+            }).to_string());
+        }
+        ledger::post(&mut self.settlement_ledger, &request.peer, &request.asset, settled.negated())?;
+        // The payment closes what the peer's receipts in this asset left owed
+        let mut receipts_settled = Vec::new();
+        for (opp_id, data) in self.execution_receipts.iter_mut() {
+            // Breakdowns declared their asset before it was kept on the receipt, always as ETH
+            let asset = data.proceeds_asset.as_deref().unwrap_or("ETH");
+            if data.retention == ReceiptRetention::Verified && asset.eq_ignore_ascii_case(&request.asset)
+                && Self::receipt_counterparties(data).contains(&request.peer) {
+                data.retention = ReceiptRetention::Settled;
+                receipts_settled.push(opp_id.clone());
+            }
+        }
+        receipts_settled.sort();
+        let net_payables_paid = self.settlement_epochs.mark_paid(&request.peer, &request.asset, &request.tx_hash);
+        self.settlement_payments.record(SettlementPayment {
+            peer: request.peer.clone(),
+            asset: request.asset.clone(),
+            settled,
+            tx_hash: request.tx_hash.clone(),
+            note: request.note,
+            recorded_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+        });
+        println!("\n💸 Settled {} {} with {} ({})", settled, request.asset, request.peer, request.tx_hash);
+        self.record_journal("SettlementRecorded", serde_json::json!({
+            "peer": request.peer,
+            "asset": request.asset,
+            "settled": settled,
+            "tx_hash": request.tx_hash,
+            "receipts_settled": receipts_settled,
+            "net_payables_paid": net_payables_paid,
+        }));
+        Ok(serde_json::json!({
+            "peer": request.peer,
+            "asset": request.asset,
+            "settled": settled,
+            "tx_hash": request.tx_hash,
+        }).to_string())
+    }
+    
+    // Runs the expiry pass now instead of waiting for the next maintenance tick
+    #[http]
+    async fn cleanup_expired(&mut self, _request_body: String) -> Result<String, String> {
+        let pruned = self.expire_stale_opportunities();
+        Ok(serde_json::json!({
+            "pruned": pruned,
+            "active_opportunities": self.active_opportunities.len(),
+        }).to_string())
+    }
+    
+    // Operator removal of a live opportunity. Retired like any other expiry, and announced to
+    // peers when we are its finder or claiming executor.
+    #[http]
+    async fn remove_opportunity(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RemovalRequest {
+            opp_id: String,
+        }
+        
+        let request: RemovalRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid removal request: {}", e))?;
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        if self.pending_executions.contains_key(&opp_id) {
+            return Err("Opportunity is with the bridge; wait for its receipt".to_string());
+        }
+        let data = self.active_opportunities.get(&opp_id).ok_or("Opportunity not found")?;
+        let announce = data.finder_node == "artemis-bot" || data.claimed_by.as_deref() == Some(our().node.as_str());
+        if !self.retire_opportunity(&opp_id, RetirementOutcome::expired("RemovedByOperator", None, announce)) {
+            return Err("Opportunity already has a receipt".to_string());
+        }
+        Ok(serde_json::json!({ "removed": opp_id, "announced": announce }).to_string())
+    }
+    
+    // Pulls back one of our own finds (`{opp_id, reason}`); see cancel_own_opportunity
+    #[http]
+    async fn cancel_my_opportunity(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct CancelRequest {
+            opp_id: String,
+            reason: String,
+        }
+        
+        let request: CancelRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid cancellation: {}", e))?;
+        self.cancel_own_opportunity(&request.opp_id, &request.reason)
+            .map(|outcome| outcome.to_string())
+    }
+    
+    // Pulls back our intent on `{opp_id}`: its capital is released at once and every peer is
+    // told to drop it. Refused here if the opportunity is already executing; an executor that
+    // already is sends a rejection, which reinstates the intent and its lock.
+    #[http]
+    async fn revoke_intent(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RevokeRequest {
+            opp_id: String,
+        }
+        
+        let request: RevokeRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid revocation: {}", e))?;
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        if !self.our_intents.contains_key(&opp_id) {
+            return Err(format!("We have no intent on {}", opp_id));
+        }
+        if self.active_opportunities.get(&opp_id).is_some_and(|data| data.status == OpportunityStatus::Executing) {
+            return Err(serde_json::json!({
+                "error": "RevocationRejected",
+                "opp_id": opp_id,
+                "reason": "Opportunity is already executing; the intent stands",
+            }).to_string());
+        }
+        let Some(outstanding) = self.our_intents.remove(&opp_id) else {
+            return Err(format!("We have no intent on {}", opp_id));
+        };
+        let released = self.release_commitment(&opp_id);
+        self.capital_reservations.remove(&opp_id);
+        if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+            data.participating = false;
+        }
+        self.revoked_intents.insert(opp_id.clone(), outstanding);
+        let revocation = MevMessage::IntentRevocation { opp_id: opp_id.clone() };
+        self.send_to_peers(revocation.handler(), &Self::message_json(&revocation));
+        
+        println!("\n↩️  Revoked our intent for {}", opp_id);
+        let outcome = serde_json::json!({
+            "opp_id": opp_id,
+            "released": released,
+        });
+        self.record_journal("IntentRevoked", outcome.clone());
+        Ok(outcome.to_string())
+    }
+    
+    // Clears the risk flags that hold an opportunity for manual approval, then evaluates it again.
+    // Flags set to reject it still do.
+    #[http]
+    async fn approve_opportunity_risk(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RiskApproval {
+            opp_id: String,
+        }
+        
+        let request: RiskApproval = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid risk approval: {}", e))?;
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        let data = self.active_opportunities.get_mut(&opp_id).ok_or("Opportunity not found")?;
+        if data.risk_approved {
+            return Err("Opportunity's risk flags are already approved".to_string());
+        }
+        data.risk_approved = true;
+        let flags = data.risk_flags.clone();
+        data.audit_trail.push(JournalEntry {
+            timestamp: Self::current_timestamp(),
+            kind: "RiskApproved".to_string(),
+            detail: serde_json::json!({ "flags": flags }),
+        });
+        self.record_journal("RiskApproved", serde_json::json!({
+            "opp_id": opp_id,
+            "flags": flags,
+        }));
+        
+        if self.node_config.capital_provider_enabled {
+            self.evaluate_and_submit_intent(opp_id.clone()).await?;
+        }
+        let (action, flag) = self.risk_gate(&opp_id);
+        Ok(serde_json::json!({
+            "opp_id": opp_id,
+            "risk_action": action,
+            "blocking_flag": flag,
+        }).to_string())
+    }
+    
+    // Bids a chosen amount on one opportunity, for operators running with auto_bid off. The
+    // evaluation rules still apply; the bid policy's caps don't.
+    #[http]
+    async fn submit_intent_manual(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ManualIntent {
+            opp_id: String,
+            amount: String,
+        }
+        
+        let request: ManualIntent = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid manual intent: {}", e))?;
+        let amount = request.amount.parse::<U256>()
+            .map_err(|_| format!("Invalid amount: {}", request.amount))?;
+        if !self.node_config.capital_provider_enabled {
+            return Err("Capital provider role is disabled".to_string());
+        }
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        let data = self.active_opportunities.get(&opp_id).ok_or("Opportunity not found")?;
+        if data.participating || self.our_intents.contains_key(&opp_id) {
+            return Err(format!("We already hold an intent on {}", opp_id));
+        }
+        if data.strategy_id != "aave-liquidation" {
+            return Err(format!("Manual bids are not supported for {}", data.strategy_id));
+        }
+        
+        let Some(intent) = self.place_bid(opp_id.clone(), Some(amount)).await? else {
+            let reason = match self.active_opportunities.get(&opp_id).and_then(|data| data.bid_status.clone()) {
+                Some(BidStatus::Declined { reason }) => reason,
+                _ => "not_evaluated".to_string(),
+            };
+            return Err(serde_json::json!({
+                "error": "BidDeclined",
+                "opp_id": opp_id,
+                "reason": reason,
+            }).to_string());
+        };
+        self.record_journal("ManualIntentSubmitted", serde_json::json!({
+            "opp_id": opp_id,
+            "amount": intent.max_amount,
+            "simulated": intent.simulated,
+        }));
+        Ok(serde_json::json!({
+            "opp_id": opp_id,
+            "asset": intent.asset,
+            "amount": intent.max_amount,
+            "simulated": intent.simulated,
+            "sent_to": self.our_intents.get(&opp_id).map(|outstanding| &outstanding.sent_to),
+        }).to_string())
+    }
+    
+    // Opportunities that expired unexecuted, newest first; receipt_arrived marks late executions
+    #[http]
+    async fn get_expired_opportunities(&self, _request_body: String) -> Result<String, String> {
+        let mut expired: Vec<(&String, &ExpiryRecord)> = self.expired_opportunities.iter().collect();
+        expired.sort_by(|a, b| b.1.expired_at.parse::<u64>().unwrap_or(0).cmp(&a.1.expired_at.parse::<u64>().unwrap_or(0))
+            .then(a.0.cmp(b.0)));
+        let expired: Vec<serde_json::Value> = expired.into_iter()
+            .map(|(opp_id, record)| serde_json::json!({
+                "opp_id": opp_id,
+                "record": record,
+            }))
+            .collect();
+        Ok(serde_json::json!({ "expired": expired }).to_string())
+    }
+    
+    #[http]
+    async fn get_execution_receipts(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct ReceiptQuery {
+            // Opaque cursor from the previous page ("verified_at:opp_id"); offset counts from it
+            cursor: Option<String>,
+            limit: Option<usize>,
+            offset: usize,
+            status: Option<String>,
+            executor_node: Option<String>,
+            // Node that found the opportunity, when we still know it
+            finder_node: Option<String>,
+            strategy_id: Option<String>,
+            #[serde(alias = "since_timestamp")]
+            from_time: Option<u64>,
+            to_time: Option<u64>,
+            retention: Option<ReceiptRetention>,
+            // Only receipts with (true) or without (false) an open dispute
+            disputed: Option<bool>,
+            // "summary" (default) or "full"
+            detail: Option<String>,
+        }
+        
+        // The UI sends an empty string when it has no parameters
+        let query: ReceiptQuery = match serde_json::from_str::<serde_json::Value>(&request_body) {
+            Ok(serde_json::Value::Object(params)) => serde_json::from_value(serde_json::Value::Object(params))
+                .map_err(|e| format!("Invalid receipt query: {}", e))?,
+            _ => ReceiptQuery::default(),
+        };
+        let full = query.detail.as_deref() == Some("full");
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        let cursor = query.cursor.as_deref().and_then(|cursor| {
+            let (verified_at, opp_id) = cursor.split_once(':')?;
+            Some((verified_at.parse::<u64>().ok()?, opp_id.to_string()))
+        });
+        
+        // (verified_at, opp_id, summary, retention, executor, our_proceeds, live record)
+        let mut rows: Vec<(u64, &String, ReceiptSummary, ReceiptRetention, &String, &String, Option<&ReceiptData>)> =
+            self.execution_receipts.iter()
+                .map(|(opp_id, data)| (
+                    data.verified_at,
+                    opp_id,
+                    data.summary(),
+                    data.retention,
+                    &data.executor_node,
+                    &data.our_proceeds,
+                    Some(data),
+                ))
+                .collect();
+        // Archived receipts are served from their summaries; the full payload is gone
+        rows.extend(self.receipt_history.iter().map(|(opp_id, archived)| (
+            archived.verified_at,
+            opp_id,
+            ReceiptSummary {
+                strategy_id: "aave-liquidation".to_string(),
+                status: archived.status.clone(),
+                tx_hash: archived.tx_hash.clone(),
+                block_number: archived.block_number,
+                total_proceeds: archived.total_proceeds.clone(),
+                gas_paid_usdc: archived.gas_paid_usdc.clone(),
+            },
+            ReceiptRetention::Archivable,
+            &archived.executor_node,
+            &archived.our_proceeds,
+            None,
+        )));
+        
+        rows.retain(|(verified_at, opp_id, summary, retention, executor, _, live)| {
+            // Older archives stored the Debug form of the status, e.g. Failed("reverted")
+            query.status.as_ref().map_or(true, |status| summary.status == *status || summary.status.starts_with(&format!("{}(", status)))
+                && query.executor_node.as_ref().map_or(true, |node| *executor == node)
+                && query.strategy_id.as_ref().map_or(true, |strategy| summary.strategy_id == *strategy)
+                && query.from_time.map_or(true, |from| *verified_at >= from)
+                && query.to_time.map_or(true, |to| *verified_at <= to)
+                && query.retention.map_or(true, |wanted| *retention == wanted)
+                && query.finder_node.as_ref().map_or(true, |node| self.opportunity_finder(opp_id) == Some(node.as_str()))
+                && query.disputed.map_or(true, |wanted| live.map_or(false, ReceiptData::is_disputed) == wanted)
+        });
+        let total = rows.len();
+        rows.retain(|(verified_at, opp_id, ..)| {
+            cursor.as_ref().map_or(true, |(cursor_at, cursor_id)| (*verified_at, opp_id.as_str()) < (*cursor_at, cursor_id.as_str()))
+        });
+        rows.sort_by(|a, b| (b.0, b.1).cmp(&(a.0, a.1)));
+        
+        let has_more = rows.len() > query.offset.saturating_add(limit);
+        rows.drain(..query.offset.min(rows.len()));
+        rows.truncate(limit);
+        let next_cursor = if has_more {
+            rows.last().map(|(verified_at, opp_id, ..)| format!("{}:{}", verified_at, opp_id))
+        } else {
+            None
+        };
+        
+        let required_confirmations = self.node_config.eth_checks.required_confirmations;
+        let head = self.current_block();
+        let items: Vec<_> = rows.into_iter()
+            .map(|(verified_at, opp_id, summary, retention, executor, our_proceeds, live)| {
+                let mut row = serde_json::json!({
+                    "opp_id": opp_id,
+                    "executor_node": executor,
+                    "our_proceeds": our_proceeds,
+                    "verified_at": verified_at,
+                    "retention": retention,
+                    "archived": live.is_none(),
+                    "summary": summary,
+                    "verification": live.map(|data| &data.verification),
+                    "disputed": live.map_or(false, ReceiptData::is_disputed),
+                    "disputes": live.map(|data| &data.disputes),
+                    "withheld": live.and_then(|data| data.withheld.as_ref()),
+                    "finality": live.map(|data| data.finality),
+                    "confirmations_remaining": live.and_then(|data| data.finality.confirmations_remaining(required_confirmations, head)),
+                });
+                if full {
+                    row["receipt"] = match live {
+                        Some(data) => serde_json::json!(data.receipt),
+                        None => serde_json::json!(self.receipt_history.get(opp_id)),
+                    };
+                }
+                row
+            })
+            .collect();
+        
+        Ok(serde_json::json!({
+            "items": items,
+            "total": total,
+            "has_more": has_more,
+            "next_cursor": next_cursor,
+        }).to_string())
+    }
+    
+    // Starts capturing boundary traffic and decisions for up to `duration_secs` or `max_bytes`
+    // (both capped), replacing any earlier recording
+    #[http]
+    async fn start_recording(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RecordingRequest {
+            duration_secs: u64,
+            #[serde(default)]
+            max_bytes: Option<usize>,
+        }
+        
+        let request: RecordingRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid recording request: {}", e))?;
+        if self.recording_active() {
+            return Err("A recording is already running; stop it first".to_string());
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let recording = Recording::start(now, request.duration_secs, request.max_bytes.unwrap_or(MAX_RECORDING_BYTES));
+        let status = recording.status();
+        *self.recording.borrow_mut() = Some(recording);
+        println!("\n⏺️  Traffic recording started until {}", status["until"]);
+        self.record_journal("RecordingStarted", status.clone());
+        Ok(status.to_string())
+    }
+    
+    #[http]
+    async fn stop_recording(&mut self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let status = {
+            let mut recording = self.recording.borrow_mut();
+            let recording = recording.as_mut().filter(|recording| recording.is_active())
+                .ok_or("No recording is running")?;
+            recording.stop("Stopped", now);
+            recording.status()
+        };
+        println!("\n⏹️  Traffic recording stopped");
+        self.record_journal("RecordingStopped", status.clone());
+        Ok(status.to_string())
+    }
+    
+    // The latest recording as a redacted fixture; stops it first if it is still running
+    #[http]
+    async fn export_recording(&mut self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut recording = self.recording.borrow_mut();
+        let recording = recording.as_mut().ok_or("Nothing has been recorded")?;
+        recording.stop("Stopped", now);
+        Ok(recording.export(&our().node).to_string())
+    }
+    
+    // Settlement statement with one counterparty over [from, to) (unix seconds, by when we
+    // recorded each receipt). With `send`, the statement also goes to the peer to be diffed.
+    #[http]
+    async fn generate_statement(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct StatementRequest {
+            peer: String,
+            from: u64,
+            to: u64,
+            #[serde(default)]
+            send: bool,
+        }
+        
+        let request: StatementRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid statement request: {}", e))?;
+        if request.from >= request.to {
+            return Err("Statement period is empty".to_string());
+        }
+        let statement = self.build_statement(&request.peer, request.from, request.to);
+        let hash = statement.hash();
+        if request.send {
+            self.send_to_peer(&request.peer, "ReceiveStatement", &serde_json::json!({
+                "type": "SettlementStatement",
+                "statement": statement,
+                "hash": hash,
+            }));
+        }
+        // The statement accrues regardless; only an approved counterparty is netted and paid
+        Ok(serde_json::json!({
+            "statement": statement.canonical_json(),
+            "hash": hash,
+            "counterparty_state": self.counterparties.state(&request.peer),
+            "settles": self.counterparties.settles_with(&request.peer),
+        }).to_string())
+    }
+    
+    #[http]
+    async fn get_statement_discrepancies(&self, _request_body: String) -> Result<String, String> {
+        Ok(serde_json::json!({ "comparisons": self.statement_comparisons }).to_string())
+    }
+    
+    // Counterparties left out of payouts and netting: awaiting approval or suspended
+    #[http]
+    async fn get_pending_counterparties(&self, _request_body: String) -> Result<String, String> {
+        let held: Vec<_> = self.counterparties.records.iter()
+            .filter(|(_, record)| matches!(record.state, CounterpartyState::PendingApproval | CounterpartyState::Suspended))
+            .map(|(peer, record)| serde_json::json!({
+                "peer": peer,
+                "state": record.state,
+                "since": record.changed_at,
+                "reason": record.reason,
+                "first_exposure": record.first_exposure,
+                "executions": record.executions,
+            }))
+            .collect();
+        Ok(serde_json::json!({
+            "counterparties": held,
+            "pending_approval": self.counterparties.pending().count(),
+        }).to_string())
+    }
+    
+    #[http]
+    async fn approve_counterparty(&mut self, request_body: String) -> Result<String, String> {
+        self.move_counterparty(&request_body, CounterpartyState::Approved)
+    }
+    
+    // Also stops new intents on opportunities the counterparty is to execute
+    #[http]
+    async fn suspend_counterparty(&mut self, request_body: String) -> Result<String, String> {
+        self.move_counterparty(&request_body, CounterpartyState::Suspended)
+    }
+    
+    #[http]
+    async fn get_receipt_detail(&self, request_body: String) -> Result<String, String> {
+        let opp_id: String = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid opp_id: {}", e))?;
+        
+        if let Some(data) = self.execution_receipts.get(&opp_id) {
+            return Ok(serde_json::json!({
+                "opp_id": opp_id,
+                "executor_node": data.executor_node,
+                "our_proceeds": data.our_proceeds,
+                "verified_at": data.verified_at,
+                "retention": data.retention,
+                "archived": false,
+                "summary": data.summary(),
+                "allocation_dispute": data.allocation_dispute,
+                "chain_verification": data.chain_verification,
+                "receipt": data.receipt,
+            }).to_string());
+        }
+        if let Some(archived) = self.receipt_history.get(&opp_id) {
+            return Ok(serde_json::json!({
+                "opp_id": opp_id,
+                "executor_node": archived.executor_node,
+                "our_proceeds": archived.our_proceeds,
+                "verified_at": archived.verified_at,
+                "retention": ReceiptRetention::Archivable,
+                "archived": true,
+                "summary": archived,
+            }).to_string());
+        }
+        
+        Err(format!("No receipt for opportunity {}", opp_id))
+    }
+    
+    
+    #[http]
+    async fn get_node_config(&self, _request_body: String) -> Result<String, String> {
+        let config = serde_json::json!({
+            "node_id": self.node_config.node_id,
+            "finder_enabled": self.node_config.finder_enabled,
+            "capital_provider_enabled": self.node_config.capital_provider_enabled,
+            "executor_enabled": self.node_config.executor_enabled,
+            "finder_fee_bps": self.node_config.finder_fee_bps,
+            "executor_fee_bps": self.node_config.executor_fee_bps,
+            "min_profit_threshold_usd": self.node_config.min_profit_threshold_usd,
+            "max_gas_price_gwei": self.node_config.max_gas_price_gwei,
+            "executor_preference": self.node_config.executor_preference,
+            "executor_failover_blocks": self.node_config.executor_failover_blocks,
+            "min_distinct_providers_above_usd": self.node_config.min_distinct_providers_above_usd,
+            "allow_under_provisioned_execution": self.node_config.allow_under_provisioned_execution,
+            "provider_wait_blocks": self.node_config.provider_wait_blocks,
+            "window_extension_coverage_bps": self.node_config.window_extension_coverage_bps,
+            "profit_entry_margin_bps": self.node_config.profit_entry_margin_bps,
+            "profit_exit_margin_bps": self.node_config.profit_exit_margin_bps,
+            "origination_cooldown_secs": self.node_config.origination_cooldown_secs,
+            "fast_path_rules": self.node_config.fast_path_rules,
+            "seed_demo_capital": self.node_config.seed_demo_capital,
+            "paper_trading": self.node_config.paper_trading,
+            "fast_path_size_ceiling_usd": FAST_PATH_SIZE_CEILING_USD,
+            "pool_id": self.node_config.pool_id,
+            "pool_coordinator": self.node_config.pool_coordinator,
+            "bootstrap_peers": self.node_config.bootstrap_peers,
+            "peer_publishers": self.node_config.peer_publishers,
+            "duplicate_finder_policy": self.node_config.duplicate_finder_policy,
+            "allocation_policy": self.node_config.allocation_policy,
+            "eth_checks": self.node_config.eth_checks,
+            "outbound_requests_per_sec": self.node_config.outbound_requests_per_sec,
+            "pressure_fanout_peers": self.node_config.pressure_fanout_peers,
+            "opportunity_ttl_secs": self.node_config.opportunity_ttl_secs,
+            "origin_policies": self.node_config.origin_policies,
+            "bridge_validation": self.node_config.bridge_validation,
+            "max_outstanding_validations": self.node_config.max_outstanding_validations,
+            "heartbeat_miss_limit": self.node_config.heartbeat_miss_limit,
+            "unreachable_peer_grace_secs": self.node_config.unreachable_peer_grace_secs,
+            "max_peers": self.node_config.max_peers,
+            "max_broadcast_hops": self.node_config.max_broadcast_hops,
+            "seen_broadcast_ttl_secs": self.node_config.seen_broadcast_ttl_secs,
+            "risk_flag_actions": self.node_config.risk_flag_actions,
+            "capital_reservation": self.node_config.capital_reservation,
+            "reservation_ttl_secs": self.node_config.reservation_ttl_secs,
+            "distribution_scheme": self.node_config.distribution_scheme,
+            "local_api_grants": self.node_config.local_api_grants,
+            "finder_quota": self.node_config.finder_quota,
+            "reputation_policy": self.node_config.reputation_policy,
+            "blacklisted_peers": self.node_config.blacklisted_peers,
+            "allowlist_mode": self.node_config.allowlist_mode,
+            "allowed_peers": self.node_config.allowed_peers,
+            "monitoring_policy": self.node_config.monitoring_policy,
+            "inbound_rate_limits": self.node_config.inbound_rate_limits,
+            "record_sharing": self.node_config.record_sharing,
+            "event_log": self.node_config.event_log,
+            "require_signed_messages": self.node_config.require_signed_messages,
+            "key_rotation_grace_secs": self.node_config.key_rotation_grace_secs,
+            "bid_policy": self.node_config.bid_policy,
+            "gas_cost_model": self.node_config.gas_cost_model,
+            "capital_provider_fee_bps": self.node_config.capital_provider_fee_bps,
+            "receipt_dispute_tolerance_bps": self.node_config.receipt_dispute_tolerance_bps,
+            "settlement_epoch": self.node_config.settlement_epoch,
+            "collection_caps": self.node_config.collection_caps
+        });
+        
+        Ok(config.to_string())
+    }
+    
+    #[http]
+    async fn import_pool_manifest(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ManifestImport {
+            // Either a pasted signed manifest or a URL to fetch one from
+            document: Option<SignedPoolManifest>,
+            url: Option<String>,
+            #[serde(default)]
+            confirm_removals: bool,
+        }
+        
+        let import: ManifestImport = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid manifest import: {}", e))?;
+        
+        let document = match (import.document, import.url) {
+            (Some(document), _) => document,
+            (None, Some(url)) => {
+                let response = hyperware_process_lib::http::client::send_request_await_response(
+                    hyperware_process_lib::http::Method::GET,
+                    url.parse().map_err(|_| format!("Invalid manifest URL: {}", url))?,
+                    None,
+                    30,
+                    Vec::new(),
+                ).map_err(|e| format!("Failed to fetch manifest: {:?}", e))?;
+                serde_json::from_slice::<SignedPoolManifest>(response.body())
+                    .map_err(|e| format!("Invalid manifest document at {}: {}", url, e))?
+            }
+            (None, None) => return Err("Provide a manifest document or a URL".to_string()),
+        };
+        
+        let coordinator = self.node_config.pool_coordinator.clone()
+            .ok_or("Set pool_coordinator before importing a manifest")?;
+        let signature = hex_decode(&document.signature)
+            .ok_or("Manifest signature is not valid hex")?;
+        let signer = Address::new(coordinator.clone(), self.peer_process_id(&coordinator)?);
+        if !Self::verify_signature(signer, document.manifest.as_bytes(), signature).await? {
+            self.record_journal("PoolManifestRejected", serde_json::json!({
+                "coordinator": coordinator,
+                "reason": "Bad coordinator signature",
+            }));
+            return Err(format!("Manifest is not signed by pool coordinator {}", coordinator));
+        }
+        
+        let manifest: PoolManifest = serde_json::from_str(&document.manifest)
+            .map_err(|e| format!("Invalid manifest: {}", e))?;
+        if let Some(current) = &self.pool_manifest {
+            if current.pool_id == manifest.pool_id && manifest.version <= current.version {
+                return Err(format!("Manifest version {} is not newer than imported version {}",
+                    manifest.version, current.version));
+            }
+        }
+        
+        let diff = self.diff_pool_manifest(&manifest);
+        if !diff["removed"].as_array().map_or(true, |removed| removed.is_empty()) && !import.confirm_removals {
+            self.record_journal("PoolManifestAwaitingConfirmation", serde_json::json!({
+                "pool_id": manifest.pool_id,
+                "version": manifest.version,
+                "diff": diff,
+            }));
+            return Ok(serde_json::json!({
+                "status": "ConfirmationRequired",
+                "diff": diff,
+            }).to_string());
+        }
+        
+        self.apply_pool_manifest(manifest.clone());
+        self.record_journal("PoolManifestImported", serde_json::json!({
+            "coordinator": coordinator,
+            "manifest": manifest,
+            "signature": document.signature,
+            "diff": diff,
+        }));
+        
+        Ok(serde_json::json!({
+            "status": "Imported",
+            "pool_id": manifest.pool_id,
+            "version": manifest.version,
+            "diff": diff,
+        }).to_string())
+    }
+    
+    // Coordinator side: sign a manifest with this node's key for distribution to members
+    #[http]
+    async fn sign_pool_manifest(&self, request_body: String) -> Result<String, String> {
+        let manifest: PoolManifest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid manifest: {}", e))?;
+        let manifest_text = serde_json::to_string(&manifest)
+            .map_err(|e| format!("Serialization error: {}", e))?;
+        let signature = Self::sign_bytes(manifest_text.as_bytes()).await?;
+        
+        serde_json::to_string(&SignedPoolManifest {
+            manifest: manifest_text,
+            signature: hex_encode(&signature),
+        }).map_err(|e| format!("Serialization error: {}", e))
+    }
+    
+    // Evidence bundle for `{opp_ids}`: every record we hold on them in canonical form, statement
+    // lines included, with the keys needed to check it and our signature over the body
+    #[http]
+    async fn export_signed_bundle(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ExportRequest {
+            opp_ids: Vec<String>,
+        }
+        
+        let request: ExportRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid export request: {}", e))?;
+        if request.opp_ids.is_empty() {
+            return Err("Name at least one opportunity to export".to_string());
+        }
+        if request.opp_ids.len() > MAX_BUNDLE_OPPORTUNITIES {
+            return Err(format!("At most {} opportunities per bundle", MAX_BUNDLE_OPPORTUNITIES));
+        }
+        let mut opp_ids: Vec<String> = request.opp_ids.iter().map(|opp_id| self.canonical_opp_id(opp_id)).collect();
+        opp_ids.sort();
+        opp_ids.dedup();
+        
+        let lines = self.execution_lines(0, u64::MAX);
+        let mut items = Vec::new();
+        for opp_id in &opp_ids {
+            let records = self.bundle_items(opp_id, &lines)?;
+            if records.is_empty() {
+                return Err(format!("Nothing recorded for {}", opp_id));
+            }
+            items.extend(records);
+        }
+        let mut body = BundleBody {
+            format: BUNDLE_FORMAT.to_string(),
+            canonicalization_version: CANONICALIZATION_VERSION,
+            exported_by: our().to_string(),
+            exported_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            opp_ids,
+            keys: Vec::new(),
+            items,
+        };
+        for node in body.signers() {
+            let networking_key = Self::networking_key(&node).await;
+            body.keys.push(BundleKey { node, networking_key });
+        }
+        
+        let text = body.canonical_json();
+        let signature = Self::sign_bytes(text.as_bytes()).await?;
+        serde_json::to_string(&SignedBundle {
+            manifest_hash: intent_hash(&text),
+            body: text,
+            signature: hex_encode(&signature),
+        }).map_err(|e| format!("Serialization error: {}", e))
+    }
+    
+    // Checks a bundle from any node's export_signed_bundle: its structure, the exporter's
+    // signature over the body and every record signature in it. Signatures are checked by the
+    // networking module against the key the PKI lists for the signer, and the key the bundle
+    // carries is compared with that one. Needs no pool membership or state.
+    #[http]
+    async fn verify_bundle(&self, request_body: String) -> Result<String, String> {
+        let bundle: SignedBundle = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid bundle: {}", e))?;
+        let body: BundleBody = serde_json::from_str(&bundle.body)
+            .map_err(|e| format!("Bundle body does not parse: {}", e))?;
+        
+        let mut problems = body.check_consistency();
+        if intent_hash(&bundle.body) != bundle.manifest_hash {
+            problems.push("Manifest hash does not match the body".to_string());
+        }
+        if body.canonical_json() != bundle.body {
+            problems.push("Body is not in canonical form".to_string());
+        }
+        
+        let mut signatures = vec![(body.exported_by.clone(), "body".to_string(),
+            Self::check_bundle_signature(&body.exported_by, bundle.body.as_bytes(), &bundle.signature).await)];
+        for item in &body.items {
+            for signature in &item.signatures {
+                let outcome = Self::check_bundle_signature(&signature.signer, item.canonical.as_bytes(), &signature.signature).await;
+                signatures.push((signature.signer.clone(), format!("{:?} {}", item.kind, item.opp_id), outcome));
+            }
+        }
+        let all_signed = signatures.iter().all(|(_, _, outcome)| matches!(outcome, Ok(true)));
+        
+        let mut keys = Vec::new();
+        for key in &body.keys {
+            let status = match (&key.networking_key, Self::networking_key(&key.node).await) {
+                (Some(included), Some(listed)) if *included == listed => "Matches",
+                (Some(_), Some(_)) => {
+                    problems.push(format!("Key included for {} differs from the one the PKI lists", key.node));
+                    "Mismatch"
+                }
+                _ => "Unchecked",
+            };
+            keys.push(serde_json::json!({ "node": key.node, "status": status }));
+        }
+        
+        Ok(serde_json::json!({
+            "valid": problems.is_empty() && all_signed,
+            "manifest_hash": bundle.manifest_hash,
+            "exported_by": body.exported_by,
+            "exported_at": body.exported_at,
+            "opp_ids": body.opp_ids,
+            "item_count": body.items.len(),
+            "problems": problems,
+            "signatures": signatures.into_iter().map(|(signer, covers, outcome)| serde_json::json!({
+                "signer": signer,
+                "covers": covers,
+                "valid": matches!(outcome, Ok(true)),
+                "error": outcome.err(),
+            })).collect::<Vec<_>>(),
+            "keys": keys,
+        }).to_string())
+    }
+    
+    // Asks `{peer}` for its records of `{opp_id}` (`sections` defaulting to all of them). The
+    // diff against ours lands in get_counterparty_views once the peer answers.
+    #[http]
+    async fn request_counterparty_view(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct ViewRequest {
+            peer: String,
+            opp_id: String,
+            #[serde(default)]
+            sections: Vec<RecordSection>,
+        }
+        
+        let request: ViewRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid view request: {}", e))?;
+        if !self.known_peers.contains_key(&request.peer) {
+            return Err(format!("{} is not a known peer", request.peer));
+        }
+        if let Some(reason) = self.node_config.peer_blocked(&request.peer) {
+            return Err(format!("{} is blocked ({})", request.peer, reason));
+        }
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        let mut sections = if request.sections.is_empty() { ALL_SECTIONS.to_vec() } else { request.sections };
+        sections.sort();
+        sections.dedup();
+        
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let request_id = format!("view-{}", intent_hash(&format!("{}:{}:{}", request.peer, opp_id, Self::current_timestamp_ms())));
+        self.counterparty_views.insert(request_id.clone(), CounterpartyView {
+            request_id: request_id.clone(),
+            peer: request.peer.clone(),
+            opp_id: opp_id.clone(),
+            sections: sections.clone(),
+            requested_at: now,
+            received_at: None,
+            response_signature_valid: None,
+            refused: BTreeMap::new(),
+            diffs: Vec::new(),
+        });
+        while self.counterparty_views.len() > MAX_COUNTERPARTY_VIEWS {
+            let Some(oldest) = self.counterparty_views.values().min_by_key(|view| view.requested_at).map(|view| view.request_id.clone()) else {
+                break;
+            };
+            self.counterparty_views.remove(&oldest);
+        }
+        
+        let message = MevMessage::RecordRequest {
+            request_id: request_id.clone(),
+            opp_id: opp_id.clone(),
+            sections: sections.clone(),
+        };
+        self.send_to_peer(&request.peer, message.handler(), &Self::message_json(&message));
+        self.record_journal("CounterpartyViewRequested", serde_json::json!({
+            "request_id": request_id,
+            "peer": request.peer,
+            "opp_id": opp_id,
+            "sections": sections,
+        }));
+        
+        Ok(serde_json::json!({ "request_id": request_id, "status": "Requested" }).to_string())
+    }
+    
+    // `{request_id}` for one view with its full diff; otherwise a summary of each view kept
+    #[http]
+    async fn get_counterparty_views(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        struct ViewQuery {
+            request_id: Option<String>,
+        }
+        
+        let query: ViewQuery = serde_json::from_str(&request_body).unwrap_or_default();
+        if let Some(request_id) = query.request_id {
+            let view = self.counterparty_views.get(&request_id)
+                .ok_or_else(|| format!("No counterparty view {}", request_id))?;
+            return serde_json::to_string(view).map_err(|e| format!("Serialization error: {}", e));
+        }
+        let mut views: Vec<&CounterpartyView> = self.counterparty_views.values().collect();
+        views.sort_by(|a, b| b.requested_at.cmp(&a.requested_at));
+        Ok(serde_json::json!({
+            "views": views.into_iter().map(|view| view.summary()).collect::<Vec<_>>(),
+            "sharing_policy": self.node_config.record_sharing,
+        }).to_string())
+    }
+    
+    // Asks `{buddy}` to keep a mirror of our public records; it starts once the buddy accepts
+    #[http]
+    async fn request_mirror(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct MirrorRequest {
+            buddy: String,
+        }
+        
+        let request: MirrorRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid mirror request: {}", e))?;
+        if request.buddy == our().node {
+            return Err("A node can't mirror itself".to_string());
+        }
+        if self.mirror_buddies.get(&request.buddy).is_some_and(|agreement| agreement.active()) {
+            return Err(format!("{} already mirrors us", request.buddy));
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.mirror_buddies.insert(request.buddy.clone(), MirrorAgreement::offered(now));
+        self.send_mirror_control(&request.buddy, MirrorAction::Offer);
+        self.record_journal("MirrorOffered", serde_json::json!({ "buddy": request.buddy }));
+        Ok(format!("Asked {} to mirror our records", request.buddy))
+    }
+    
+    // Agrees to keep the mirror `{node}` asked us for
+    #[http]
+    async fn accept_mirror(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct AcceptRequest {
+            node: String,
+        }
+        
+        let request: AcceptRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid accept request: {}", e))?;
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let held = self.held_mirrors.get_mut(&request.node)
+            .ok_or_else(|| format!("{} hasn't asked us to mirror it", request.node))?;
+        if held.agreement.active() {
+            return Err(format!("Already mirroring {}", request.node));
+        }
+        held.agreement = MirrorAgreement { state: MirrorState::Active, since: now };
+        self.send_mirror_control(&request.node, MirrorAction::Accept);
+        self.record_journal("MirrorAccepted", serde_json::json!({ "node": request.node }));
+        Ok(format!("Mirroring {}", request.node))
+    }
+    
+    // Ends a mirror in either direction with `{peer}`. A copy we held for them is dropped.
+    #[http]
+    async fn revoke_mirror(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RevokeRequest {
+            peer: String,
+        }
+        
+        let request: RevokeRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid revoke request: {}", e))?;
+        let theirs = self.mirror_buddies.remove(&request.peer).is_some();
+        let ours = self.held_mirrors.remove(&request.peer).is_some();
+        if !theirs && !ours {
+            return Err(format!("No mirror with {}", request.peer));
+        }
+        self.send_mirror_control(&request.peer, MirrorAction::Revoke);
+        self.record_journal("MirrorRevoked", serde_json::json!({
+            "peer": request.peer,
+            "by": our().node,
+        }));
+        Ok(format!("Mirror with {} revoked", request.peer))
+    }
+    
+    #[http]
+    async fn get_mirrors(&self, _request_body: String) -> Result<String, String> {
+        let mut restored: BTreeMap<String, u64> = BTreeMap::new();
+        for record in self.restored_records.values() {
+            *restored.entry(format!("{:?}", record.reverification)).or_insert(0) += 1;
+        }
+        Ok(serde_json::json!({
+            "buddies": self.mirror_buddies,
+            "log": {
+                "next_seq": self.mirror_log.next_seq,
+                "held": self.mirror_log.entries.len(),
+            },
+            "held_for": self.held_mirrors.iter()
+                .map(|(node, held)| (node.clone(), serde_json::json!({
+                    "agreement": held.agreement,
+                    "copy": held.copy.summary(),
+                })))
+                .collect::<BTreeMap<_, _>>(),
+            "restore": self.mirror_restore,
+            "restored_by_status": restored,
+            "restored": self.restored_records.values().collect::<Vec<_>>(),
+        }).to_string())
+    }
+    
+    // Asks `{buddy}` for the copy of our records it holds, after we've lost state. What comes
+    // back fills in only what we no longer have, marked pending live reverification.
+    #[http]
+    async fn restore_from_mirror(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct RestoreRequest {
+            buddy: String,
+        }
+        
+        let request: RestoreRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid restore request: {}", e))?;
+        if self.mirror_restore.as_ref().is_some_and(|restore| restore.completed_at.is_none()) {
+            return Err("A restore is already in progress".to_string());
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.mirror_restore = Some(MirrorRestore {
+            buddy: request.buddy.clone(),
+            requested_at: now,
+            restored: 0,
+            kept_local: 0,
+            bad_signatures: 0,
+            completed_at: None,
+        });
+        self.send_mirror_control(&request.buddy, MirrorAction::RestoreRequest);
+        self.record_journal("MirrorRestoreRequested", serde_json::json!({ "buddy": request.buddy }));
+        Ok(format!("Requested our records from {}", request.buddy))
+    }
+    
+    #[http]
+    async fn get_pool(&self, _request_body: String) -> Result<String, String> {
+        let pool = serde_json::json!({
+            "pool_id": self.node_config.pool_id,
+            "pool_coordinator": self.node_config.pool_coordinator,
+            "manifest": self.pool_manifest,
+            "peer_registry": self.peer_registry,
+        });
+        
+        Ok(pool.to_string())
+    }
+    
+    #[http]
+    async fn get_health(&self, _request_body: String) -> Result<String, String> {
+        let eth_checks = &self.node_config.eth_checks;
+        let enabled = eth_checks.check_health_factor || eth_checks.check_receipts || eth_checks.check_balances;
+        Ok(serde_json::json!({
+            "artemis_connected": self.artemis_channels.is_connected(),
+            "artemis_channels": self.artemis_channels.metrics(),
+            "artemis_health": self.artemis_health,
+            "ui_channels": self.ui_channels.channels,
+            "eth_checks": {
+                "enabled": enabled,
+                "config": eth_checks,
+                // Checks currently falling back to trusting the bridge, and why
+                "degraded": self.eth_degraded,
+                "calls_this_minute": self.eth_budget.used,
+                "balance_reconciliation": self.balance_reconciliation,
+            },
+            "outbound": self.outbound_metrics(),
+            "validation": self.validation_broker.metrics(),
+            "bridge_sync": self.bridge_sync.metrics(),
+            "artemis_inbound": self.artemis_inbound.stats(),
+            "config_trial": self.config_trial.as_ref().map(|trial| serde_json::json!({
+                "started_at": trial.started_at,
+                "revert_at": trial.revert_at,
+                "changed": changed_config_fields(&trial.previous, &self.node_config),
+            })),
+            "seen_broadcasts": {
+                "tracked": self.seen_broadcasts.entries.len(),
+                "duplicates_dropped": self.seen_broadcasts.duplicates_dropped,
+            },
+        }).to_string())
+    }
+    
+    // Asks the bridge to reload one execution wallet's key; the other wallets are untouched
+    #[http]
+    async fn rotate_execution_wallet(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct Rotation {
+            strategy: String,
+            chain_id: Option<u64>,
+            key_env: Option<String>,
+        }
+        
+        let rotation: Rotation = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid wallet rotation: {}", e))?;
+        if !self.artemis_channels.is_connected() {
+            return Err("No Artemis bridge connected".to_string());
+        }
+        let message = serde_json::json!({
+            "type": "RotateWallet",
+            "strategy": rotation.strategy,
+            "chain_id": rotation.chain_id,
+            "key_env": rotation.key_env,
+        });
+        self.send_to_artemis_json_sync(&message.to_string())?;
+        self.record_journal("ExecutionWalletRotationRequested", message);
+        Ok("Rotation sent to Artemis".to_string())
+    }
+    
+    // Replaces our app signing key and tells every reachable peer, signed by both keys. Peers keep
+    // honouring the old key for key_rotation_grace_secs, and no second rotation starts before then.
+    #[http]
+    async fn rotate_signing_key(&mut self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if let Some(retired) = self.retired_signing_key.as_ref().filter(|retired| retired.pending(now)) {
+            return Err(serde_json::json!({
+                "error": "RotationPending",
+                "retired_key": retired.public_key,
+                "valid_until": retired.valid_until,
+            }).to_string());
+        }
+        let old = self.signing_key.clone().ok_or("No signing key to rotate")?;
+        let new = SigningKeyPair::generate();
+        let node = our().node;
+        let bytes = rotation_bytes(&node, &old.public_key, &new.public_key, now);
+        let (Some(old_signature), Some(new_signature)) = (old.sign(&bytes), new.sign(&bytes)) else {
+            return Err("Failed to sign the rotation".to_string());
+        };
+        let valid_until = now.saturating_add(self.node_config.key_rotation_grace_secs);
+        self.retired_signing_key = Some(RetiredKey { public_key: old.public_key.clone(), valid_until });
+        self.signing_key = Some(new.clone());
+        let rotation = MevMessage::KeyRotation {
+            node,
+            old_key: old.public_key.clone(),
+            new_key: new.public_key.clone(),
+            rotated_at: now,
+            old_signature,
+            new_signature,
+            signature: None,
+        };
+        self.broadcast_message(&rotation);
+        let detail = serde_json::json!({
+            "old_key": old.public_key,
+            "new_key": new.public_key,
+            "rotated_at": now,
+            "old_key_valid_until": valid_until,
+            "peers_notified": self.reachable_peers().count(),
+        });
+        println!("🔑 Rotated our signing key to {}", new.public_key);
+        self.record_journal("SigningKeyRotated", detail.clone());
+        Ok(detail.to_string())
+    }
+    
+    #[http]
+    async fn get_journal(&self, _request_body: String) -> Result<String, String> {
+        serde_json::to_string(&self.journal).map_err(|e| format!("Serialization error: {}", e))
+    }
+    
+    // Peer message bodies that failed to parse, newest first, with each sender's malformed count
+    #[http]
+    async fn get_dead_letters(&self, _request_body: String) -> Result<String, String> {
+        let letters: Vec<&DeadLetter> = self.dead_letters.iter().rev().collect();
+        Ok(serde_json::json!({
+            "dead_letters": letters,
+            "malformed_by_peer": self.malformed_message_strikes,
+        }).to_string())
+    }
+    
+    // The protocol event log, newest first. Takes an optional `{opp_id, message_type, direction,
+    // peer, outcome, limit, offset}`; filters combine.
+    #[http]
+    async fn get_event_log(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct EventLogQuery {
+            opp_id: Option<String>,
+            message_type: Option<String>,
+            direction: Option<Boundary>,
+            // Peer node or Artemis channel
+            peer: Option<String>,
+            outcome: Option<EventOutcome>,
+            limit: Option<usize>,
+            offset: usize,
+        }
+        
+        let query: EventLogQuery = match serde_json::from_str::<serde_json::Value>(&request_body) {
+            Ok(serde_json::Value::Object(params)) => serde_json::from_value(serde_json::Value::Object(params))
+                .map_err(|e| format!("Invalid event log query: {}", e))?,
+            _ => EventLogQuery::default(),
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        let log = self.event_log.borrow();
+        let matching: Vec<&EventLogEntry> = log.entries.iter().rev()
+            .filter(|entry| query.opp_id.as_ref().map_or(true, |opp_id| entry.opp_id.as_ref() == Some(opp_id))
+                && query.message_type.as_ref().map_or(true, |kind| entry.message_type == *kind)
+                && query.direction.map_or(true, |direction| entry.direction == direction)
+                && query.peer.as_ref().map_or(true, |peer| entry.counterparty.as_ref() == Some(peer))
+                && query.outcome.map_or(true, |outcome| entry.outcome == outcome))
+            .collect();
+        let total = matching.len();
+        let items: Vec<&EventLogEntry> = matching.into_iter().skip(query.offset).take(limit).collect();
+        Ok(serde_json::json!({
+            "has_more": query.offset + items.len() < total,
+            "items": items,
+            "total": total,
+            "held": log.entries.len(),
+            "capacity": self.node_config.event_log.capacity,
+        }).to_string())
+    }
+    
+    // Runs a synthetic opportunity through the whole local pipeline and reports each stage with its
+    // timing. Nothing is stored or sent; the check afterwards confirms the state is untouched.
+    #[http]
+    async fn run_self_test(&mut self, _request_body: String) -> Result<String, String> {
+        let fingerprint = |app: &Self| (
+            app.active_opportunities.len(),
+            app.submitted_intents.len(),
+            app.our_intents.len(),
+            app.pending_executions.len(),
+            app.execution_receipts.len(),
+            serde_json::to_string(&app.available_balances).unwrap_or_default(),
+            serde_json::to_string(&app.committed_amounts).unwrap_or_default(),
+        );
+        let before = fingerprint(self);
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut report = selftest::run_pipeline(&self.node_config, self.strategy_configs.get("aave-liquidation"), &our().node, now);
+        
+        let after = fingerprint(self);
+        let leaked = self.active_opportunities.keys().chain(self.submitted_intents.keys())
+            .chain(self.execution_receipts.keys())
+            .any(|opp_id| is_self_test_id(opp_id));
+        let bridge = self.artemis_channels.bridge_id();
+        report.run_stage("teardown", || {
+            if before != after || leaked {
+                return Err("Self-test left state behind".to_string());
+            }
+            Ok(((), serde_json::json!({ "state_unchanged": true, "connected_bridge": bridge })))
+        });
+        let report = report.finish();
+        
+        println!("\n🧪 Self-test {}: {}", report.opp_id, if report.passed { "passed" } else { "failed" });
+        self.record_journal("SelfTest", serde_json::to_value(&report).unwrap_or_default());
+        serde_json::to_string(&report).map_err(|e| format!("Serialization error: {}", e))
+    }
+    
+    // Settings of one registered strategy (`{strategy_id}`), or of all of them
+    #[http]
+    async fn get_strategy_config(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize, Default)]
+        struct StrategyQuery {
+            #[serde(default)]
+            strategy_id: Option<String>,
+        }
+        
+        let query: StrategyQuery = if request_body.trim().is_empty() {
+            StrategyQuery::default()
+        } else {
+            serde_json::from_str(&request_body).map_err(|e| format!("Invalid strategy query: {}", e))?
+        };
+        match query.strategy_id {
+            Some(strategy_id) => {
+                let config = self.strategy_config(&strategy_id)?;
+                Ok(serde_json::json!({ "strategy_id": strategy_id, "config": config }).to_string())
+            }
+            None => {
+                let configs: BTreeMap<&String, &StrategyConfig> = self.strategy_configs.iter().collect();
+                Ok(serde_json::json!({ "strategies": configs }).to_string())
+            }
+        }
+    }
+    
+    // Changes a registered strategy's settings; fields left out keep their value, and
+    // `clear_min_profit_usd` / `clear_max_gas_price_gwei` fall back to the node-wide thresholds
+    #[http]
+    async fn update_strategy_config(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct StrategyUpdate {
+            strategy_id: String,
+            min_profit_usd: Option<String>,
+            #[serde(default)]
+            clear_min_profit_usd: bool,
+            max_gas_price_gwei: Option<String>,
+            #[serde(default)]
+            clear_max_gas_price_gwei: bool,
+            execution_deadline_blocks: Option<u64>,
+            params: Option<serde_json::Value>,
+        }
+        
+        let update: StrategyUpdate = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid strategy update: {}", e))?;
+        let mut staged = self.strategy_config(&update.strategy_id)?.clone();
+        if update.clear_min_profit_usd {
+            staged.min_profit_usd = None;
+        }
+        if let Some(min_profit) = update.min_profit_usd {
+            staged.min_profit_usd = Some(min_profit);
+        }
+        if update.clear_max_gas_price_gwei {
+            staged.max_gas_price_gwei = None;
+        }
+        if let Some(max_gas) = update.max_gas_price_gwei {
+            staged.max_gas_price_gwei = Some(max_gas);
+        }
+        if let Some(blocks) = update.execution_deadline_blocks {
+            staged.execution_deadline_blocks = blocks;
+        }
+        if let Some(params) = update.params {
+            staged.params = params;
+        }
+        staged.validate()?;
+        
+        self.record_journal("StrategyConfigUpdated", serde_json::json!({
+            "strategy_id": update.strategy_id,
+            "config": staged,
+        }));
+        self.strategy_configs.insert(update.strategy_id.clone(), staged.clone());
+        self.record_config_revision("StrategyUpdate");
+        Ok(serde_json::json!({ "strategy_id": update.strategy_id, "config": staged }).to_string())
+    }
+    
+    // Starts participating in a registered strategy (`{strategy_id}`); peers are re-announced to
+    // with the new capability list
+    #[http]
+    async fn enable_strategy(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct StrategyToggle {
+            strategy_id: String,
+        }
+        
+        let request: StrategyToggle = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid strategy request: {}", e))?;
+        self.strategy_config(&request.strategy_id)?;
+        if self.node_config.strategy_enabled(&request.strategy_id) {
+            return Ok(format!("Strategy {} is already enabled", request.strategy_id));
+        }
+        let mut staged = self.node_config.clone();
+        staged.enabled_strategies.push(request.strategy_id.clone());
+        staged.validate()?;
+        self.apply_node_config(staged, "StrategyEnabled");
+        Ok(format!("Strategy {} enabled", request.strategy_id))
+    }
+    
+    // Stops storing and evaluating opportunities of a strategy (`{strategy_id}`). Broadcasts for
+    // it are still acknowledged and counted under ignored_broadcasts; intents already committed
+    // run their course.
+    #[http]
+    async fn disable_strategy(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct StrategyToggle {
+            strategy_id: String,
+        }
+        
+        let request: StrategyToggle = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid strategy request: {}", e))?;
+        if !self.node_config.strategy_enabled(&request.strategy_id) {
+            return Ok(format!("Strategy {} is not enabled", request.strategy_id));
+        }
+        let mut staged = self.node_config.clone();
+        staged.enabled_strategies.retain(|enabled| enabled != &request.strategy_id);
+        staged.validate()?;
+        self.apply_node_config(staged, "StrategyDisabled");
+        Ok(format!("Strategy {} disabled", request.strategy_id))
+    }
+    
+    // Refuses every message from `{peer, reason?}` from now on. A known peer is dropped, with
+    // its queued sends and the intents it submitted.
+    #[http]
+    async fn blacklist_peer(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct BlacklistRequest {
+            peer: String,
+            #[serde(default)]
+            reason: Option<String>,
+        }
+        
+        let request: BlacklistRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid blacklist request: {}", e))?;
+        if request.peer == our().node {
+            return Err("Cannot blacklist ourselves".to_string());
+        }
+        if self.node_config.blacklisted_peers.contains(&request.peer) {
+            return Ok(format!("{} is already blacklisted", request.peer));
+        }
+        let mut staged = self.node_config.clone();
+        staged.blacklisted_peers.insert(request.peer.clone());
+        staged.validate()?;
+        self.apply_node_config(staged, "PeerBlacklisted");
+        self.record_journal("PeerBlacklisted", serde_json::json!({
+            "peer": request.peer,
+            "reason": request.reason,
+        }));
+        Ok(format!("{} blacklisted", request.peer))
+    }
+    
+    // Lifts a blacklisting (`{peer}`); the node has to connect or be connected to again
+    #[http]
+    async fn unblacklist_peer(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PeerRequest {
+            peer: String,
+        }
+        
+        let request: PeerRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid blacklist request: {}", e))?;
+        if !self.node_config.blacklisted_peers.contains(&request.peer) {
+            return Ok(format!("{} is not blacklisted", request.peer));
+        }
+        let mut staged = self.node_config.clone();
+        staged.blacklisted_peers.remove(&request.peer);
+        staged.validate()?;
+        self.apply_node_config(staged, "PeerUnblacklisted");
+        Ok(format!("{} removed from the blacklist", request.peer))
+    }
+    
+    // Adds `{peer}` to the allowlist, which only takes effect with allowlist_mode on
+    #[http]
+    async fn allow_peer(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PeerRequest {
+            peer: String,
+        }
+        
+        let request: PeerRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid allowlist request: {}", e))?;
+        if self.node_config.allowed_peers.contains(&request.peer) {
+            return Ok(format!("{} is already allowed", request.peer));
+        }
+        let mut staged = self.node_config.clone();
+        staged.allowed_peers.insert(request.peer.clone());
+        staged.validate()?;
+        self.apply_node_config(staged, "PeerAllowed");
+        Ok(format!("{} allowed", request.peer))
+    }
+    
+    // Takes `{peer}` off the allowlist; with allowlist_mode on it is dropped like a blacklisted peer
+    #[http]
+    async fn disallow_peer(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PeerRequest {
+            peer: String,
+        }
+        
+        let request: PeerRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid allowlist request: {}", e))?;
+        if !self.node_config.allowed_peers.contains(&request.peer) {
+            return Ok(format!("{} is not on the allowlist", request.peer));
+        }
+        let mut staged = self.node_config.clone();
+        staged.allowed_peers.remove(&request.peer);
+        staged.validate()?;
+        self.apply_node_config(staged, "PeerDisallowed");
+        Ok(format!("{} removed from the allowlist", request.peer))
+    }
+    
+    // Turns allowlist mode on or off (`{enabled}`). Turning it on drops every known peer not on
+    // the allowlist, and is refused while the allowlist is empty.
+    #[http]
+    async fn set_allowlist_mode(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct AllowlistModeRequest {
+            enabled: bool,
+        }
+        
+        let request: AllowlistModeRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid allowlist mode request: {}", e))?;
+        let mut staged = self.node_config.clone();
+        staged.allowlist_mode = request.enabled;
+        staged.validate()?;
+        self.apply_node_config(staged, "AllowlistMode");
+        Ok(format!("Allowlist mode {}", if request.enabled { "on" } else { "off" }))
+    }
+    
+    #[http]
+    async fn get_access_lists(&self, _request_body: String) -> Result<String, String> {
+        Ok(serde_json::json!({
+            "blacklisted_peers": self.node_config.blacklisted_peers,
+            "allowlist_mode": self.node_config.allowlist_mode,
+            "allowed_peers": self.node_config.allowed_peers,
+            "blocked_messages": self.blocked_messages,
+        }).to_string())
+    }
+    
+    // Saves the current tuning (PRESET_FIELDS and every strategy's settings) as `{name}`,
+    // replacing a preset of that name
+    #[http]
+    async fn save_preset(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PresetRequest {
+            name: String,
+        }
+        
+        let request: PresetRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid preset request: {}", e))?;
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Preset name cannot be empty".to_string());
+        }
+        if !self.config_presets.contains_key(&name) && self.config_presets.len() >= PRESET_CAPACITY {
+            return Err(format!("At most {} presets; delete one first", PRESET_CAPACITY));
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let preset = ConfigPreset::capture(name.clone(), &self.node_config, &self.strategy_configs, now);
+        
+        self.record_journal("PresetSaved", serde_json::json!({
+            "name": name,
+            "revision": self.config_revision,
+        }));
+        self.config_presets.insert(name, preset.clone());
+        serde_json::to_string(&preset).map_err(|e| format!("Serialization error: {}", e))
+    }
+    
+    // Switches to preset `{name}` in one step. The preset goes through the same staging and
+    // validation as update_node_config and nothing changes if any of it is refused; opportunities
+    // and intents already in flight keep the revision they were created under.
+    #[http]
+    async fn apply_preset(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PresetRequest {
+            name: String,
+        }
+        
+        let request: PresetRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid preset request: {}", e))?;
+        let preset = self.config_presets.get(&request.name).cloned()
+            .ok_or_else(|| format!("No preset named {}", request.name))?;
+        // A trial revert would restore the node config but not the preset's strategy settings
+        if self.config_trial.is_some() {
+            return Err("A configuration trial is in progress; commit it or let it revert first".to_string());
+        }
+        for (strategy_id, strategy) in &preset.strategies {
+            self.strategy_config(strategy_id)?;
+            strategy.validate().map_err(|e| format!("Preset {} has invalid {} settings: {}", preset.name, strategy_id, e))?;
+        }
+        let update: ConfigUpdate = serde_json::from_value(serde_json::Value::Object(preset.config.clone()))
+            .map_err(|e| format!("Preset {} no longer fits the config: {}", preset.name, e))?;
+        let staged = self.stage_config_update(update)?;
+        
+        let strategies_changed = preset.strategies.iter().any(|(strategy_id, strategy)| {
+            serde_json::to_value(self.strategy_configs.get(strategy_id)).ok() != serde_json::to_value(Some(strategy)).ok()
+        });
+        for (strategy_id, strategy) in &preset.strategies {
+            self.strategy_configs.insert(strategy_id.clone(), strategy.clone());
+        }
+        let config_changed = self.apply_node_config(staged, "Preset");
+        if strategies_changed && !config_changed {
+            self.record_config_revision("Preset");
+        }
+        
+        let revision = self.config_revision;
+        let in_flight = self.active_opportunities.values()
+            .filter(|data| data.config_revision != revision)
+            .count();
+        println!("\n🎛️  Applied preset {} (revision {}, {} opportunities keep earlier terms)", preset.name, revision, in_flight);
+        self.record_journal("PresetApplied", serde_json::json!({
+            "name": preset.name,
+            "revision": revision,
+            "config_changed": config_changed,
+            "strategies_changed": strategies_changed,
+            "in_flight_on_earlier_revisions": in_flight,
+        }));
+        Ok(serde_json::json!({
+            "name": preset.name,
+            "revision": revision,
+            "changed": config_changed || strategies_changed,
+            "in_flight_on_earlier_revisions": in_flight,
+        }).to_string())
+    }
+    
+    #[http]
+    async fn list_presets(&self, _request_body: String) -> Result<String, String> {
+        let presets: Vec<&ConfigPreset> = self.config_presets.values().collect();
+        Ok(serde_json::json!({
+            "presets": presets,
+            "config_revision": self.config_revision,
+        }).to_string())
+    }
+    
+    #[http]
+    async fn delete_preset(&mut self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PresetRequest {
+            name: String,
+        }
+        
+        let request: PresetRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid preset request: {}", e))?;
+        self.config_presets.remove(&request.name)
+            .ok_or_else(|| format!("No preset named {}", request.name))?;
+        self.record_journal("PresetDeleted", serde_json::json!({ "name": request.name }));
+        Ok(format!("Preset {} deleted", request.name))
+    }
+    
+    // Watched borrowers with how long since the bridge last re-checked each, stalest first
+    #[http]
+    async fn get_watched_positions(&self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut positions: Vec<(u64, serde_json::Value)> = self.watched_positions.values()
+            .map(|position| {
+                let age_secs = now.saturating_sub(position.last_updated.parse::<u64>().unwrap_or(0));
+                (age_secs, serde_json::json!({
+                    "borrower": position.borrower,
+                    "collateral_asset": position.collateral_asset,
+                    "debt_asset": position.debt_asset,
+                    "health_factor": position.last_health_factor,
+                    "debt_amount": position.debt_amount,
+                    "last_updated": position.last_updated,
+                    "last_checked_block": position.last_checked_block,
+                    "age_secs": age_secs,
+                    "relayed_by": position.relayed_by,
+                    "delegated_to": self.monitoring_coverage.assignments.get(&position.borrower).map(|assignment| &assignment.peer),
+                }))
+            })
+            .collect();
+        positions.sort_by(|a, b| b.0.cmp(&a.0));
+        let positions: Vec<serde_json::Value> = positions.into_iter().map(|(_, position)| position).collect();
+        
+        Ok(serde_json::json!({
+            "positions": positions,
+            "count": positions.len(),
+        }).to_string())
+    }
+    
+    // Victims left to peers and to whom, peers' advertised coverage, fallbacks back to local
+    // polling, and the polls that saved
+    #[http]
+    async fn get_monitoring_coverage(&self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let coverage = &self.monitoring_coverage;
+        let head = self.current_block().unwrap_or(0);
+        let peers: BTreeMap<String, serde_json::Value> = coverage.peers.iter()
+            .map(|(peer, advert)| (peer.clone(), serde_json::json!({
+                "monitoring": advert.monitoring.len(),
+                "subscribed_to_us": advert.subscribed.len(),
+                "advertised_at": advert.advertised_at,
+                "tier": self.coverage_tier(peer),
+                "distrusted_until": coverage.distrusted.get(peer).filter(|until| **until > now),
+                "covering_for_us": coverage.delegated_to(peer).len(),
+            })))
+            .collect();
+        let assignments: BTreeMap<String, serde_json::Value> = coverage.assignments.iter()
+            .map(|(victim, assignment)| (victim.clone(), serde_json::json!({
+                "assignment": assignment,
+                "silent_blocks": assignment.silent_blocks(head),
+            })))
+            .collect();
+        let mut fallbacks_by_reason: BTreeMap<String, u64> = BTreeMap::new();
+        for fallback in &coverage.fallbacks {
+            *fallbacks_by_reason.entry(format!("{:?}", fallback.reason)).or_insert(0) += 1;
+        }
+        let relaying: BTreeMap<String, usize> = coverage.peers.iter()
+            .filter(|(_, advert)| !advert.subscribed.is_empty())
+            .map(|(peer, advert)| (peer.clone(), advert.subscribed.iter().filter(|victim| self.watched_positions.contains_key(*victim)).count()))
+            .collect();
+        
+        Ok(serde_json::json!({
+            "policy": self.node_config.monitoring_policy,
+            "watched": self.watched_positions.len(),
+            "delegated": coverage.assignments.len(),
+            "assignments": assignments,
+            "peers": peers,
+            "relaying_to": relaying,
+            "fallbacks": coverage.fallbacks,
+            "fallbacks_by_reason": fallbacks_by_reason,
+            "updates_received": coverage.updates_received,
+            "updates_relayed": coverage.updates_relayed,
+            "spot_checks": coverage.spot_checks,
+            "spot_check_failures": coverage.spot_check_failures,
+            "savings": {
+                // Upper bound: every delegated victim would otherwise have been polled every block
+                "delegated_victim_blocks": coverage.delegated_victim_blocks,
+                // What the bridge counted as actually skipped, from its last health report
+                "bridge": self.artemis_health.as_ref().map(|health| health["monitoring"].clone()),
+            },
+        }).to_string())
+    }
+    
+    // What-if: runs the decision pipeline on a supplied opportunity without mutating or sending anything
+    #[http]
+    async fn evaluate_hypothetical(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct Hypothetical {
+            opportunity: Option<AaveLiquidationOpportunity>,
+            #[serde(default)]
+            as_executor: bool,
+            // Executor mode: which stored opportunity's intents to evaluate against
+            opp_id: Option<String>,
+            #[serde(default)]
+            origin: OpportunityOrigin,
+        }
+        
+        let request: Hypothetical = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid hypothetical: {}", e))?;
+        
+        if request.as_executor {
+            let opp_id = request.opp_id
+                .or_else(|| request.opportunity.as_ref().map(|o| o.opp_id.clone()))
+                .ok_or("Executor mode needs an opp_id")?;
+            let data = self.active_opportunities.get(&opp_id)
+                .ok_or_else(|| format!("Unknown opportunity: {}", opp_id))?;
+            let opportunity = match request.opportunity {
+                Some(opportunity) => opportunity,
+                None => serde_json::from_str(&data.opportunity)
+                    .map_err(|e| format!("Stored opportunity is unreadable: {}", e))?,
+            };
+            let intents = self.submitted_intents.get(&opp_id).cloned().unwrap_or_default();
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            let checks = executor_checks(
+                &self.node_config,
+                self.strategy_configs.get(&data.strategy_id),
+                data,
+                &opportunity,
+                &intents,
+                &our().node,
+                now,
+                self.current_block(),
+                self.current_gas_price(),
+            );
+            let dispatch = checks.iter().all(|check| check.passed);
+            
+            return Ok(serde_json::json!({
+                "role": "executor",
+                "opp_id": opp_id,
+                "decision": if dispatch { "Dispatch" } else { "Hold" },
+                "checks": checks,
+            }).to_string());
+        }
+        
+        let opportunity = request.opportunity.ok_or("Provide an opportunity payload")?;
+        let decision = decide_commitment(
+            &self.node_config,
+            &opportunity,
+            request.origin.class(),
+            self.strategy_configs.get("aave-liquidation"),
+            self.free_balance(&opportunity.repay_asset),
+            self.operator_balance(&opportunity.repay_asset),
+            self.capital_commitments.len(),
+            self.current_block(),
+            self.current_gas_price(),
+            None,
+        );
+        
+        Ok(serde_json::json!({
+            "role": "capital_provider",
+            "opp_id": opportunity.opp_id,
+            "decision": if decision.commit { "Commit" } else { "Skip" },
+            "amount": decision.amount,
+            "simulated": decision.simulated,
+            "capital_provider_enabled": self.node_config.capital_provider_enabled,
+            "auto_bid": self.node_config.bid_policy.auto_bid,
+            "checks": decision.checks,
+        }).to_string())
+    }
+    
+    // Estimated split of an opportunity's proceeds: the shared allocation over the intents we know
+    // of, plus ours (submitted or hypothetical), run through the same split receipts are checked by
+    #[http]
+    async fn preview_distribution(&self, request_body: String) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct PreviewRequest {
+            opp_id: String,
+            // Hypothetical intent size; defaults to what we would commit
+            amount: Option<String>,
+            policy: Option<AllocationPolicy>,
+            executor_fee_bps: Option<u16>,
+        }
+        
+        let request: PreviewRequest = serde_json::from_str(&request_body)
+            .map_err(|e| format!("Invalid preview request: {}", e))?;
+        let opp_id = self.canonical_opp_id(&request.opp_id);
+        let data = self.active_opportunities.get(&opp_id)
+            .ok_or_else(|| format!("Unknown opportunity: {}", opp_id))?;
+        let opportunity: AaveLiquidationOpportunity = serde_json::from_str(&data.opportunity)
+            .map_err(|e| format!("Stored opportunity is unreadable: {}", e))?;
+        
+        let me = our().node;
+        let mut intents: Vec<IntentData> = self.submitted_intents.get(&opp_id).cloned().unwrap_or_default();
+        intents.retain(|intent| intent.submitter_node != me);
+        let (our_intent, hypothetical) = match self.our_intents.get(&opp_id) {
+            Some(outstanding) if request.amount.is_none() => (outstanding.intent.clone(), false),
+            _ => {
+                let amount = match request.amount {
+                    Some(amount) => amount,
+                    None => decide_commitment(
+                        &self.node_config,
+                        &opportunity,
+                        data.origin.class(),
+                        self.strategy_configs.get(&data.strategy_id),
+                        self.free_balance(&opportunity.repay_asset),
+                        self.operator_balance(&opportunity.repay_asset),
+                        self.capital_commitments.len(),
+                        self.current_block(),
+                        self.current_gas_price(),
+                        None,
+                    ).amount,
+                };
+                (AaveLiquidationIntent {
+                    opp_id: opp_id.clone(),
+                    asset: opportunity.repay_asset.clone(),
+                    max_amount: amount,
+                    min_bonus_bps: opportunity.min_bonus_bps,
+                    expires_block: opportunity.deadline_block,
+                    requires_swap: false,
+                    simulated: false,
+                    fee_bps: self.node_config.capital_provider_fee_bps,
+                }, true)
+            }
+        };
+        intents.push(IntentData {
+            intent: serde_json::to_string(&our_intent).map_err(|e| e.to_string())?,
+            submitter_node: me.clone(),
+            max_amount: our_intent.max_amount.clone(),
+            expires_block: our_intent.expires_block,
+            received_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            fee_bps: our_intent.fee_bps,
+        });
+        
+        let policy = request.policy.unwrap_or(self.node_config.allocation_policy);
+        let candidates: Vec<AllocationCandidate> = intents.iter().map(|data| data.allocation_candidate()).collect();
+        let allocation = allocate(policy, &opportunity.max_repay_amount, &candidates)?;
+        let finder_fee_bps = best_claim(&data.claiming_finders).map_or(0, |claim| claim.finder_fee_bps);
+        let executor_fee_bps = request.executor_fee_bps.unwrap_or(self.node_config.executor_fee_bps);
+        // The advertised profit is already net of the finder's gas estimate
+        let split = split_proceeds(&opportunity.estimated_profit_usd, "0", finder_fee_bps, executor_fee_bps, &allocation, &opportunity.distribution)?;
+        
+        let shares: Vec<serde_json::Value> = split.shares.iter()
+            .map(|line| serde_json::json!({
+                "provider": line.provider,
+                "amount_used": line.amount_used,
+                "share": line.share,
+                "ours": line.provider == me,
+            }))
+            .collect();
+        let our_share = split.shares.iter()
+            .find(|line| line.provider == me)
+            .map(|line| line.share.clone());
+        
+        Ok(serde_json::json!({
+            "opp_id": opp_id,
+            "estimate": true,
+            "basis": "Current intents and the advertised profit; the executor's final allocation and actual gas decide the real split",
+            "policy": policy,
+            "hypothetical_intent": hypothetical,
+            "our_amount": our_intent.max_amount,
+            "our_share": our_share,
+            "gross": split.gross,
+            "gas_cost": split.gas_cost,
+            "finder_fee_bps": finder_fee_bps,
+            "finder_fee": split.finder_fee,
+            "executor_fee_bps": executor_fee_bps,
+            "executor_fee": split.executor_fee,
+            "provider_pool": split.provider_pool,
+            "shares": shares,
+        }).to_string())
+    }
+    
+    // What this node has made: running per-strategy counts of opportunities, intents and
+    // executions, our proceeds and gas, and realized against estimated profit, with the totals
+    // and the capital accounting alongside
+    #[http]
+    async fn get_metrics(&self, _request_body: String) -> Result<String, String> {
+        let by_strategy: BTreeMap<&String, serde_json::Value> = self.performance_metrics.by_strategy.iter()
+            .map(|(strategy_id, metrics)| (strategy_id, metrics.summary()))
+            .collect();
+        let caps = &self.node_config.collection_caps;
+        Ok(serde_json::json!({
+            "since": self.performance_metrics.since,
+            "totals": self.performance_metrics.totals().summary(),
+            "by_strategy": by_strategy,
+            "capital": {
+                "earned_proceeds": self.earned_proceeds,
+                "used_capital": self.used_capital,
+                "committed": self.committed_amounts,
+                "balances": self.available_balances,
+            },
+            "live": {
+                "opportunities": self.active_opportunities.len(),
+                "our_intents": self.our_intents.len(),
+                "pending_executions": self.pending_executions.len(),
+            },
+            // Sizes against the caps maintenance holds them to
+            "collections": {
+                "opportunities": { "size": self.active_opportunities.len(), "cap": caps.opportunities },
+                "intents": { "size": self.submitted_intents.values().map(Vec::len).sum::<usize>(), "cap": caps.intents },
+                "receipts": { "size": self.execution_receipts.len(), "cap": caps.receipts },
+                "peers": { "size": self.known_peers.len(), "cap": self.node_config.max_peers },
+            },
+        }).to_string())
+    }
+    
+    #[http]
+    async fn get_peer_stats(&self, _request_body: String) -> Result<String, String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut peers: Vec<&String> = self.known_peers.keys()
+            .chain(self.finder_sequences.keys())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        peers.sort();
+        
+        let stats: Vec<_> = peers.into_iter()
+            .map(|peer| {
+                let sequence = self.finder_sequences.get(peer);
+                serde_json::json!({
+                    "peer": peer,
+                    "connected": self.known_peers.get(peer).map_or(false, |info| info.reachable()),
+                    "highest_finder_seq": sequence.map(|s| s.highest_seq),
+                    "broadcasts_received": sequence.map_or(0, |s| s.received),
+                    "broadcasts_missed": sequence.map_or(0, |s| s.gaps_detected),
+                    "broadcasts_backfilled": sequence.map_or(0, |s| s.backfilled),
+                    "broadcasts_outstanding": sequence.map_or(0, |s| s.missing.len()),
+                    "expired_unexecuted": sequence.map_or(0, |s| s.expired_unexecuted),
+                    "broadcasts_by_origin": sequence.map(|s| &s.by_origin),
+                    "reputation_tier": self.finder_tier(peer),
+                    "live_opportunities": self.active_opportunities.values().filter(|data| &data.finder_node == peer).count(),
+                    "accepted_last_hour": sequence.map_or(0, |s| {
+                        s.recent_accepts.iter().filter(|at| now.saturating_sub(**at) < 3600).count()
+                    }),
+                    "quota_rejected": sequence.map_or(0, |s| s.quota_rejected),
+                    "invalid_opportunities": sequence.map_or(0, |s| s.invalid_opportunities),
+                })
+            })
+            .collect();
+        
+        Ok(serde_json::json!({ "peers": stats, "by_origin": self.origin_stats }).to_string())
+    }
+    
+    // WEBSOCKET HANDLER FOR ARTEMIS MEV BOT
+    
+    #[ws]
+    fn handle_artemis_websocket(&mut self, channel_id: u32, message_type: WsMessageType, payload: LazyLoadBlob) {
+        // UI tabs share this handler; they only listen, so all they can send us is a Close
+        if self.ui_channels.contains(channel_id) {
+            if matches!(message_type, WsMessageType::Close) && self.ui_channels.close(channel_id) {
+                println!("UI disconnected from live updates (channel {})", channel_id);
+            }
+            return;
+        }
+        if matches!(message_type, WsMessageType::Text) && !self.artemis_channels.contains(channel_id) {
+            let hello = serde_json::from_slice::<serde_json::Value>(&payload.bytes).unwrap_or_default();
+            if hello["type"] == "Subscribe" {
+                self.open_ui_channel(channel_id);
+                return;
+            }
+        }
+        if matches!(message_type, WsMessageType::Text | WsMessageType::Binary) {
+            self.record_message(Boundary::ArtemisInbound, Some(channel_id.to_string()), "",
+                &String::from_utf8_lossy(&payload.bytes));
+        }
+        match message_type {
+            WsMessageType::Text => {
+                let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+                // A channel's first message is its handshake: send it our config
+                if !self.artemis_channels.contains(channel_id) {
+                    // The handshake may carry the bridge's identity
+                    let hello = serde_json::from_slice::<serde_json::Value>(&payload.bytes).unwrap_or_default();
+                    let superseded = self.artemis_channels.open(channel_id, ArtemisConnection {
+                        connected_at: now,
+                        last_message_at: now,
+                        bridge_id: hello["bridge_id"].as_str().map(|id| id.to_string()),
+                        bridge_version: hello["bridge_version"].as_str().map(|v| v.to_string()),
+                    });
+                    println!("Artemis MEV bot connected via WebSocket (channel {})", channel_id);
+                    if !superseded.is_empty() {
+                        // The bridge reconnected before its old channel's Close arrived
+                        println!("   Replacing earlier channels {:?} from the same bridge", superseded);
+                        self.validation_broker.abandon_in_flight();
+                    }
+                    
+                    // Send initial node config to Artemis bot
+                    let config_json = serde_json::json!({
+                        "type": "NodeConfig",
+                        "config": self.node_config
+                    });
+                    self.push_to_artemis_channel(channel_id, &config_json.to_string());
+                    self.begin_bridge_sync(channel_id);
+                    self.push_bridge_delegation(true);
+                    return;
+                }
+                self.artemis_channels.touch(channel_id, now);
+                
+                // Handle incoming text messages from Artemis
+                if let Ok(text) = String::from_utf8(payload.bytes.clone()) {
+                    self.artemis_inbound.push(channel_id, text);
+                }
+                self.drain_artemis_inbound();
+                
+                // Bridge traffic doubles as our clock for executor failover
+                for opp_id in self.check_executor_succession() {
+                    self.dispatch_opportunity(&opp_id);
+                }
+            }
+            WsMessageType::Binary => {
+                self.artemis_channels.touch(channel_id, Self::current_timestamp().parse::<u64>().unwrap_or(0));
+                // Handle binary messages from Artemis
+                if let Ok(text) = String::from_utf8(payload.bytes.clone()) {
+                    self.artemis_inbound.push(channel_id, text);
+                }
+                self.drain_artemis_inbound();
+            }
+            WsMessageType::Close => {
+                println!("Artemis MEV bot disconnected (channel {})", channel_id);
+                if self.artemis_channels.close(channel_id) {
+                    self.on_primary_channel_lost();
+                }
+            }
+            _ => {
+                // Handle other message types if needed
+            }
+        }
+    }
+    
+    // P2P REMOTE HANDLERS
+    
+    #[remote]
+    async fn receive_node_announcement(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveNodeAnnouncement", &message_json)?;
+        if let MevMessage::NodeAnnouncement { node_info, capabilities, known_peers, reply, maintenance, signature, .. } =
+            MevMessage::parse(&message_json, "NodeAnnouncement")? {
+            let sender = source();
+            if node_info.node_id != sender.node {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "NodeIdMismatch",
+                    "node_id": node_info.node_id,
+                    "sender": sender.node,
+                }).to_string()));
+            }
+            self.check_message_signature(&sender, &message_json).await
+                .map_err(|error| self.reject_inbound(error))?;
+            let nonce = self.check_message_nonce(&sender.node, "NodeAnnouncement", &message_json)
+                .map_err(|error| self.reject_inbound(error))?;
+            let signing_key = signature.as_ref().and_then(|signature| signature.signing_key.clone());
+            // The key it announces must be the one the PKI lists for it
+            let public_key = match signature.and_then(|signature| signature.public_key) {
+                Some(key) => match Self::networking_key(&sender.node).await {
+                    Some(listed) if listed != key => return Err(self.reject_inbound(serde_json::json!({
+                        "error": "KeyMismatch",
+                        "announced": key,
+                        "listed": listed,
+                    }).to_string())),
+                    Some(_) => Some(key),
+                    None => None,
+                },
+                None => None,
+            };
+            if !self.known_peers.contains_key(&node_info.node_id) && self.known_peers.len() >= self.node_config.max_peers {
+                println!("Ignoring announcement from {}: already at max_peers ({})", node_info.node_id, self.node_config.max_peers);
+                return Ok("ACK".to_string());
+            }
+            // Add to known peers
+            let now = Self::current_timestamp();
+            let peer = self.known_peers.entry(node_info.node_id.clone())
+                .or_insert_with(|| PeerInfo::pending(now.clone()));
+            // First contact, or back after being unreachable: it has missed broadcasts either way
+            let joined = peer.node_info.is_none() || peer.unreachable_since.is_some();
+            peer.node_info = Some(node_info.clone());
+            peer.capabilities = capabilities.clone();
+            let now_secs = now.parse::<u64>().unwrap_or(0);
+            let maintenance = maintenance.filter(|window| window.end() > now_secs);
+            if peer.maintenance != maintenance {
+                match &maintenance {
+                    Some(window) => println!("   🛠️  {} plans maintenance from {} for {}s", node_info.node_id, window.start, window.duration_secs),
+                    None => println!("   🛠️  {} has no maintenance planned", node_info.node_id),
+                }
+            }
+            peer.maintenance = maintenance;
+            if let Some(key) = public_key {
+                if peer.public_key.as_ref() != Some(&key) {
+                    println!("   🔑 {} signs with networking key {}", node_info.node_id, key);
+                }
+                peer.public_key = Some(key);
+            }
+            // Taken from the first announcement to carry one; after that only a KeyRotation signed
+            // with it replaces it
+            if let Some(key) = signing_key {
+                match &peer.signing_key {
+                    None => {
+                        println!("   🔑 {} countersigns with signing key {}", node_info.node_id, key);
+                        peer.signing_key = Some(key);
+                    }
+                    Some(held) if *held != key => {
+                        println!("   ⚠️  {} announced signing key {} without rotating from {}; keeping {}", node_info.node_id, key, held, held);
+                    }
+                    Some(_) => {}
+                }
+            }
+            peer.note_seen(now);
+            self.record_message_nonce(&sender.node, "NodeAnnouncement", nonce);
+            
+            println!("Received announcement from node: {} with capabilities: {:?}", 
+                node_info.node_id, capabilities);
+            
+            // Respond with our own announcement, unless this already was the response
+            if !reply {
+                self.announce_to_peer(node_info.node_id.clone(), true).await?;
+            }
+            if joined {
+                self.request_state_sync(&node_info.node_id);
+                self.notify_ui(UiEventKind::PeerConnected, serde_json::json!({
+                    "node": node_info.node_id,
+                    "roles": node_info.roles,
+                    "capabilities": capabilities,
+                    "app_version": node_info.app_version,
+                }));
+            }
+            
+            // Peer exchange: introduce ourselves to peers we hadn't heard of. They answer with a
+            // reply, which stops there.
+            let introduced = peers_to_introduce(known_peers, &our().node, &self.known_peers, &self.node_config);
+            for peer in introduced {
+                println!("   Learned of peer {} from {}", peer, node_info.node_id);
+                self.known_peers.insert(peer.clone(), PeerInfo::pending(Self::current_timestamp()));
+                self.announce_to_peer(peer, false).await?;
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A peer replacing its app signing key. Taken only when signed by the key we hold for it and by
+    // the new one; the old key is then honoured for key_rotation_grace_secs and everything else we
+    // hold on the peer stays as it was. Anything else is refused and counted against the sender.
+    #[remote]
+    async fn receive_key_rotation(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveKeyRotation", &message_json)?;
+        if let MevMessage::KeyRotation { node, old_key, new_key, rotated_at, old_signature, new_signature, .. } =
+            MevMessage::parse(&message_json, "KeyRotation")? {
+            let sender = source();
+            self.check_message_signature(&sender, &message_json).await
+                .map_err(|error| self.reject_inbound(error))?;
+            let nonce = self.check_message_nonce(&sender.node, "KeyRotation", &message_json)
+                .map_err(|error| self.reject_inbound(error))?;
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            let peer = self.known_peers.get(&sender.node);
+            let claim = RotationClaim {
+                node: &node,
+                old_key: &old_key,
+                new_key: &new_key,
+                rotated_at,
+                old_signature: &old_signature,
+                new_signature: &new_signature,
+            };
+            let held = peer.and_then(|peer| peer.signing_key.clone());
+            let verdict = judge_rotation(
+                &sender.node,
+                held.as_deref(),
+                peer.and_then(|peer| peer.retired_signing_key.as_ref()),
+                &claim,
+                now,
+            );
+            if let Err(error) = verdict {
+                println!("\n🔑 P2P: Ignored signing-key rotation from {}: {}", sender.node, error);
+                self.peer_reputation.entry(sender.node.clone()).or_default().note_refused();
+                self.record_journal("KeyRotationRefused", serde_json::json!({
+                    "node": sender.node,
+                    "error": error,
+                    "held_key": held,
+                    "old_key": old_key,
+                    "new_key": new_key,
+                }));
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": error,
+                    "old_key": old_key,
+                }).to_string()));
+            }
+            let valid_until = now.saturating_add(self.node_config.key_rotation_grace_secs);
+            if let Some(peer) = self.known_peers.get_mut(&sender.node) {
+                peer.retired_signing_key = Some(RetiredKey { public_key: old_key.clone(), valid_until });
+                peer.signing_key = Some(new_key.clone());
+            }
+            self.record_message_nonce(&sender.node, "KeyRotation", nonce);
+            println!("\n🔑 P2P: {} rotated its signing key to {}; the old one is honoured until {}", sender.node, new_key, valid_until);
+            self.record_journal("PeerKeyRotated", serde_json::json!({
+                "node": sender.node,
+                "old_key": old_key,
+                "new_key": new_key,
+                "rotated_at": rotated_at,
+                "old_key_valid_until": valid_until,
+            }));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // Heartbeat: any ping shows the sender is alive; a ping that isn't itself a reply gets one
+    #[remote]
+    async fn receive_ping(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceivePing", &message_json)?;
+        let MevMessage::Ping { reply, .. } = MevMessage::parse(&message_json, "Ping")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        let Some(peer) = self.known_peers.get_mut(&sender) else {
+            return Ok("ACK".to_string());
+        };
+        if peer.unreachable_since.is_some() {
+            println!("\n💓 Peer {} is reachable again", sender);
+        }
+        peer.note_seen(Self::current_timestamp());
+        if !reply {
+            let pong = MevMessage::Ping { sent_at: Self::current_timestamp(), reply: true };
+            self.send_to_peer(&sender, pong.handler(), &Self::message_json(&pong));
+        }
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_opportunity_broadcast(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveOpportunityBroadcast", &message_json)?;
+        self.accept_opportunity_broadcast(source().node, &message_json, true).await
+            .map_err(|error| self.reject_inbound(error))?;
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_opportunity_update(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveOpportunityUpdate", &message_json)?;
+        if let MevMessage::OpportunityUpdate { opp_id, opportunity, .. } =
+            MevMessage::parse(&message_json, "OpportunityUpdate")? {
+            let canonical = self.canonical_opp_id(&opp_id);
+            let Some(data) = self.active_opportunities.get(&canonical) else {
+                return Ok("ACK".to_string()); // Never saw the original, or it's gone
+            };
+            // Each claiming finder may update only its own find
+            let finder = data.claiming_finders.iter()
+                .find(|claim| claim.opp_id == opp_id)
+                .map_or(&data.finder_node, |claim| &claim.finder);
+            if *finder != source().node {
+                return Err("Only the finder may update an opportunity".to_string());
+            }
+            let opportunity: AaveLiquidationOpportunity = serde_json::from_str(&opportunity)
+                .map_err(|e| format!("Invalid opportunity update: {}", e))?;
+            if self.apply_opportunity_update(&opp_id, &opportunity).is_none() {
+                return Err("Update does not match the stored opportunity".to_string());
+            }
+            
+            println!("\n🔄 P2P: Opportunity {} updated by {}", opp_id, source().node);
+            if self.node_config.capital_provider_enabled {
+                self.evaluate_and_submit_intent(canonical).await?;
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_opportunity_expiry(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveOpportunityExpiry", &message_json)?;
+        if let MevMessage::OpportunityExpired { opp_id, reason, best_coverage_reached } =
+            MevMessage::parse(&message_json, "OpportunityExpired")? {
+            let opp_id = self.canonical_opp_id(&opp_id);
+            let sender = source().node;
+            // Only the originator or the claiming executor speaks for the opportunity
+            if let Some(data) = self.active_opportunities.get(&opp_id) {
+                let originated = data.finder_node == sender
+                    || data.claiming_finders.iter().any(|claim| claim.finder == sender);
+                if !originated && data.claimed_by.as_deref() != Some(sender.as_str()) {
+                    return Err("Only the finder or claiming executor may expire an opportunity".to_string());
+                }
+            }
+            let reason = reason.as_deref().unwrap_or("DeadlinePassed");
+            if self.retire_opportunity(&opp_id, RetirementOutcome::expired(reason, Some(sender.clone()), false)) {
+                if let (Some(coverage), Some(record)) = (best_coverage_reached, self.expired_opportunities.get_mut(&opp_id)) {
+                    record.best_coverage_reached = coverage;
+                }
+                println!("\n⌛ P2P: {} reports opportunity {} expired ({})", sender, opp_id, reason);
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_intent_submission(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentSubmission", &message_json)?;
+        self.check_message_signature(&source(), &message_json).await
+            .map_err(|error| self.reject_inbound(error))?;
+        let dispatch = self.accept_intent_submission(source().node, &message_json, true)
+            .map_err(|error| self.reject_inbound(error))?;
+        for opp_id in dispatch {
+            self.dispatch_opportunity(&opp_id);
+        }
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_execution_receipt(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveExecutionReceipt", &message_json)?;
+        if let MevMessage::ExecutionReceipt { opp_id, receipt, proceeds, .. } =
+            MevMessage::parse(&message_json, "ExecutionReceipt").map_err(|error| self.reject_inbound(error))? {
+            let executor = source().node;
+            if self.active_opportunities.get(&opp_id).is_some_and(|data| !data.may_report_receipt(&executor)) {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "NotExecutor",
+                    "opp_id": opp_id,
+                    "sender": executor,
+                }).to_string()));
+            }
+            self.check_message_signature(&source(), &message_json).await
+                .map_err(|error| self.reject_inbound(error))?;
+            let nonce = self.check_message_nonce(&executor, "ExecutionReceipt", &message_json)
+                .map_err(|error| self.reject_inbound(error))?;
+            let our_share_str = self.calculate_our_proceeds_share(&opp_id, &executor, &receipt, &proceeds);
+            let mut receipt_data = ReceiptData::new(receipt.clone(), executor.clone(), our_share_str.clone());
+            receipt_data.proceeds_asset = Some(proceeds.asset.clone());
+            if let Some(mismatch) = self.check_declared_allocation(&opp_id, &executor, &receipt) {
+                println!("\n⚖️  Allocation for {} by {} does not replay: {:?}", opp_id, executor, mismatch);
+                self.record_journal("AllocationDisputed", serde_json::json!({
+                    "opp_id": opp_id,
+                    "executor": executor,
+                    "mismatch": mismatch,
+                }));
+                receipt_data.retention = ReceiptRetention::Verified;
+                receipt_data.allocation_dispute = Some(mismatch);
+            }
+            self.check_receipt_against_chain(&opp_id, &mut receipt_data);
+            self.record_message_nonce(&executor, "ExecutionReceipt", nonce);
+            
+            if receipt_data.verification != ReceiptVerification::Verified {
+                // A copy we can't trust never displaces one that already counted
+                if self.execution_receipts.get(&opp_id).is_some_and(|stored| stored.verification == ReceiptVerification::Verified
+                    && stored.summary().status != "Pending") {
+                    println!("   Kept the verified receipt for {}; this copy is {:?}", opp_id, receipt_data.verification);
+                    return Ok("ACK".to_string());
+                }
+                if receipt_data.verification == ReceiptVerification::Pending {
+                    println!("   Receipt for {} awaits its chain check before it counts", opp_id);
+                    self.record_journal("ReceiptAwaitingVerification", serde_json::json!({
+                        "opp_id": opp_id,
+                        "executor": executor,
+                    }));
+                }
+                self.execution_receipts.insert(opp_id.clone(), receipt_data);
+                self.mirror_receipt(&opp_id);
+                return Ok("ACK".to_string());
+            }
+            self.apply_peer_receipt(&opp_id, receipt_data);
+            
+            println!("Received execution receipt for opportunity {} with our proceeds: {}", 
+                opp_id, our_share_str);
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // The executor's distribution for an execution whose receipt we hold: kept as the ledger for
+    // it, with our total as owed_to_us, and checked against the intent we submitted and the share
+    // we derived from the receipt. Mismatches are flagged and journaled, not rejected.
+    #[remote]
+    async fn receive_proceeds_distribution(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveProceedsDistribution", &message_json)?;
+        #[derive(Deserialize)]
+        struct DistributionMessage {
+            distribution: ProceedsDistribution,
+        }
+        
+        let message: DistributionMessage = serde_json::from_str(&message_json)
+            .map_err(|e| self.reject_inbound(format!("Invalid proceeds distribution: {}", e)))?;
+        let mut distribution = message.distribution;
+        distribution.opp_id = self.canonical_opp_id(&distribution.opp_id);
+        let sender = source().node;
+        let Some(receipt) = self.execution_receipts.get(&distribution.opp_id) else {
+            return Err(self.reject_inbound(serde_json::json!({
+                "error": "UnknownReceipt",
+                "opp_id": distribution.opp_id,
+            }).to_string()));
+        };
+        if receipt.executor_node != sender || distribution.executor != sender {
+            return Err(self.reject_inbound(serde_json::json!({
+                "error": "NotExecutor",
+                "opp_id": distribution.opp_id,
+                "executor": receipt.executor_node,
+            }).to_string()));
+        }
+        let computed_share = receipt.our_proceeds.parse::<U256>().unwrap_or(U256::ZERO);
+        let awaiting_verification = receipt.verification != ReceiptVerification::Verified;
+        
+        let me = our().node;
+        // An unverified receipt hasn't settled our intent yet
+        let intent = self.settled_intents.remove(&distribution.opp_id)
+            .or_else(|| self.our_intents.get(&distribution.opp_id).cloned());
+        let flags = distribution.check_for(&me, intent.as_ref(), computed_share);
+        let owed_to_us = distribution.owed_to(&me).to_string();
+        self.record_journal("ProceedsDistributionReceived", serde_json::json!({
+            "opp_id": distribution.opp_id,
+            "executor": sender,
+            "entries": distribution.entries.len(),
+            "owed_to_us": owed_to_us,
+        }));
+        if !flags.is_empty() {
+            println!("\n⚖️  Distribution for {} by {} does not match our records: {:?}", distribution.opp_id, sender, flags);
+            self.record_journal("ProceedsDistributionDisputed", serde_json::json!({
+                "opp_id": distribution.opp_id,
+                "executor": sender,
+                "flags": flags,
+            }));
+        }
+        if !awaiting_verification {
+            self.post_distribution(&distribution);
+        }
+        self.proceeds_distributions.insert(DistributionRecord {
+            distribution,
+            received_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            owed_to_us,
+            flags,
+            awaiting_verification,
+        });
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A provider disputing a receipt we hold, usually one we executed: attached to the receipt,
+    // whose proceeds leave our PnL until resolve_dispute
+    #[remote]
+    async fn receive_receipt_dispute(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveReceiptDispute", &message_json)?;
+        #[derive(Deserialize)]
+        struct DisputeMessage {
+            dispute: ReceiptDispute,
+        }
+        
+        let message: DisputeMessage = serde_json::from_str(&message_json)
+            .map_err(|e| self.reject_inbound(format!("Invalid receipt dispute: {}", e)))?;
+        let mut dispute = message.dispute;
+        dispute.opp_id = self.canonical_opp_id(&dispute.opp_id);
+        let sender = source().node;
+        let Some(data) = self.execution_receipts.get(&dispute.opp_id) else {
+            return Err(self.reject_inbound(serde_json::json!({
+                "error": "UnknownReceipt",
+                "opp_id": dispute.opp_id,
+            }).to_string()));
+        };
+        let provider = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt)
+            .map_or(false, |receipt| receipt.used_amounts.iter().any(|usage| usage.node_id == sender))
+            || self.submitted_intents.get(&dispute.opp_id)
+                .map_or(false, |intents| intents.iter().any(|intent| intent.submitter_node == sender));
+        if !provider {
+            return Err(self.reject_inbound(serde_json::json!({
+                "error": "NotAProvider",
+                "opp_id": dispute.opp_id,
+            }).to_string()));
+        }
+        
+        println!("\n⚖️  P2P: {} disputes the receipt for {}: {:?} (expected {}, received {})",
+            sender, dispute.opp_id, dispute.reason, dispute.expected, dispute.received);
+        self.attach_dispute(DisputeRecord {
+            dispute,
+            raised_by: sender,
+            raised_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            resolution: None,
+        });
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A debtor's netting statement for its closed epoch. Each item our own entries with it agree
+    // with is netted; the rest are disputed and settle entry by entry. Either way it gets an
+    // acknowledgment naming both.
+    #[remote]
+    async fn receive_netting_statement(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveNettingStatement", &message_json)?;
+        if let MevMessage::NettingStatement { statement_id, epoch, asset, items, net, .. } =
+            MevMessage::parse(&message_json, "NettingStatement")? {
+            let sender = source();
+            self.check_message_signature(&sender, &message_json).await
+                .map_err(|error| self.reject_inbound(error))?;
+            let nonce = self.check_message_nonce(&sender.node, "NettingStatement", &message_json)
+                .map_err(|error| self.reject_inbound(error))?;
+            let me = our().node;
+            let expected_id = NettingStatement::id(epoch, &sender.node, &me, &asset);
+            if statement_id != expected_id || netting::net_of(&items) != net {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "InvalidStatement",
+                    "statement_id": statement_id,
+                    "expected_id": expected_id,
+                }).to_string()));
+            }
+            if self.settlement_epochs.statements.contains_key(&statement_id) {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "DuplicateStatement",
+                    "statement_id": statement_id,
+                }).to_string()));
+            }
+            let (accepted, disputed) = self.settlement_epochs.judge_items(&sender.node, &asset, &items);
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            let statement = NettingStatement {
+                statement_id: statement_id.clone(),
+                epoch,
+                recorded_in: self.settlement_epochs.epoch,
+                debtor: sender.node.clone(),
+                creditor: me,
+                asset: asset.clone(),
+                items,
+                net,
+                proposed_at: now,
+                status: StatementStatus::Acknowledged { accepted: accepted.clone(), disputed: disputed.clone() },
+            };
+            let payable = statement.collapse(&accepted, now);
+            self.settlement_epochs.mark(&sender.node, &asset, &accepted, EntryNetting::Netted { statement_id: statement_id.clone() });
+            self.settlement_epochs.mark(&sender.node, &asset, &disputed, EntryNetting::Disputed { statement_id: statement_id.clone() });
+            self.settlement_epochs.statements.insert(statement_id.clone(), statement);
+            self.settlement_epochs.net_payables.extend(payable.clone());
+            self.record_message_nonce(&sender.node, "NettingStatement", nonce);
+            println!("\n🧾 P2P: Netting statement {} from {}: {} item(s) netted, {} disputed",
+                statement_id, sender.node, accepted.len(), disputed.len());
+            self.record_journal("NettingStatementAcknowledged", serde_json::json!({
+                "statement_id": statement_id,
+                "debtor": sender.node,
+                "asset": asset,
+                "accepted": accepted,
+                "disputed": disputed,
+                "net_payable": payable,
+            }));
+            let acknowledgment = MevMessage::NettingAcknowledgment {
+                statement_id,
+                accepted,
+                disputed,
+                signature: None,
+            };
+            self.send_to_peer(&sender.node, acknowledgment.handler(), &self.sign_message(&acknowledgment));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A creditor's answer to one of our netting statements: the accepted items become one net
+    // payable, the disputed ones stay gross
+    #[remote]
+    async fn receive_netting_acknowledgment(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveNettingAcknowledgment", &message_json)?;
+        if let MevMessage::NettingAcknowledgment { statement_id, accepted, disputed, .. } =
+            MevMessage::parse(&message_json, "NettingAcknowledgment")? {
+            let sender = source();
+            self.check_message_signature(&sender, &message_json).await
+                .map_err(|error| self.reject_inbound(error))?;
+            let nonce = self.check_message_nonce(&sender.node, "NettingAcknowledgment", &message_json)
+                .map_err(|error| self.reject_inbound(error))?;
+            let Some(statement) = self.settlement_epochs.statements.get(&statement_id)
+                .filter(|statement| statement.creditor == sender.node && statement.status == StatementStatus::Proposed) else {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "UnknownStatement",
+                    "statement_id": statement_id,
+                }).to_string()));
+            };
+            // Every item answered once, and nothing that wasn't on the statement
+            let mut answered: Vec<&String> = accepted.iter().chain(disputed.iter()).collect();
+            answered.sort();
+            let mut listed: Vec<&String> = statement.items.iter().map(|item| &item.opp_id).collect();
+            listed.sort();
+            if answered != listed {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "InvalidAcknowledgment",
+                    "statement_id": statement_id,
+                }).to_string()));
+            }
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            let payable = statement.collapse(&accepted, now);
+            let asset = statement.asset.clone();
+            if let Some(statement) = self.settlement_epochs.statements.get_mut(&statement_id) {
+                statement.status = StatementStatus::Acknowledged { accepted: accepted.clone(), disputed: disputed.clone() };
+            }
+            // Disputed items go back to being settled on their own
+            self.settlement_epochs.mark(&sender.node, &asset, &accepted, EntryNetting::Netted { statement_id: statement_id.clone() });
+            self.settlement_epochs.mark(&sender.node, &asset, &disputed, EntryNetting::Disputed { statement_id: statement_id.clone() });
+            self.settlement_epochs.net_payables.extend(payable.clone());
+            self.record_message_nonce(&sender.node, "NettingAcknowledgment", nonce);
+            println!("\n🧾 P2P: {} acknowledged netting statement {}: {} item(s) netted, {} disputed",
+                sender.node, statement_id, accepted.len(), disputed.len());
+            self.record_journal("NettingAcknowledged", serde_json::json!({
+                "statement_id": statement_id,
+                "creditor": sender.node,
+                "asset": asset,
+                "accepted": accepted,
+                "disputed": disputed,
+                "net_payable": payable,
+            }));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_executor_succession(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveExecutorSuccession", &message_json)?;
+        if let MevMessage::ExecutorSuccession { opp_id, holder, mut holder_index } =
+            MevMessage::parse(&message_json, "ExecutorSuccession")? {
+            let mut we_hold_rights = false;
+            let mut succeeded = None;
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            let head = self.current_block();
+            let window = self.node_config.executor_failover_blocks;
+            let holder_away = self.active_opportunities.get(&opp_id)
+                .and_then(|data| data.execution_holder().map(|holder| self.in_maintenance_at(holder, self.deadline_at(data))))
+                .unwrap_or(false);
+            
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                // Our election ranking may order executors differently from the sender's; go by name
+                if data.executor_elected {
+                    holder_index = data.executor_preference.iter().position(|node| *node == holder).unwrap_or(0);
+                }
+                // Rights only move forward along the finder's list, so stale or duplicate notices are no-ops
+                // and each holder passed over must have had its failover window
+                let held_for = blocks_since(head, data.holder_since_block, now, data.holder_since.parse::<u64>().unwrap_or(now));
+                let steps = holder_index.saturating_sub(data.execution_holder_index);
+                if holder_index > data.execution_holder_index
+                    && holder_index < data.executor_preference.len()
+                    && data.claimed_by.is_none()
+                    && !succession_due(held_for, window, steps, holder_away)
+                {
+                    println!("\n⚠️  P2P: {} reports execution rights for {} passed {} holder(s) on after {} blocks; too early",
+                        source().node, opp_id, steps, held_for);
+                } else if holder_index > data.execution_holder_index
+                    && holder_index < data.executor_preference.len()
+                    && data.claimed_by.is_none()
+                {
+                    let previous = data.execution_holder().cloned();
+                    data.execution_holder_index = holder_index;
+                    data.holder_since = now.to_string();
+                    data.holder_since_block = head;
+                    we_hold_rights = data.execution_holder() == Some(&our().node);
+                    succeeded = Some((previous, data.execution_holder().cloned()));
+                    println!("\n🔁 P2P: {} reports execution rights for {} passed to {:?}",
+                        source().node, opp_id, data.execution_holder());
+                }
+            }
+            if let Some((previous, holder)) = succeeded {
+                self.record_journal("ExecutorSucceeded", serde_json::json!({
+                    "opp_id": opp_id,
+                    "from": previous,
+                    "to": holder,
+                    "source": source().node,
+                }));
+            }
+            
+            if we_hold_rights && self.node_config.executor_enabled {
+                self.dispatch_opportunity(&opp_id);
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_provider_shortfall(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveProviderShortfall", &message_json)?;
+        if let MevMessage::ProviderShortfall { opp_id, distinct_providers, min_providers } =
+            MevMessage::parse(&message_json, "ProviderShortfall")? {
+            let shortfall = ProviderShortfall { distinct_providers, min_providers };
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                println!("\n🙋 P2P: {} needs more capital providers for {}: {} of {} committed",
+                    source().node, opp_id, shortfall.distinct_providers, shortfall.min_providers);
+                data.provider_shortfall = Some(shortfall);
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A counterparty's statement: rebuilt from our own records for the same period and diffed
+    #[remote]
+    async fn receive_statement(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveStatement", &message_json)?;
+        #[derive(Deserialize)]
+        struct StatementMessage {
+            statement: SettlementStatement,
+            hash: String,
+        }
+        
+        let message: StatementMessage = serde_json::from_str(&message_json)
+            .map_err(|e| format!("Invalid statement: {}", e))?;
+        let sender = source().node;
+        let theirs = message.statement;
+        if !theirs.parties.contains(&sender) || !theirs.parties.contains(&our().node) {
+            return Err("Statement is not between us and the sender".to_string());
+        }
+        if theirs.hash() != message.hash {
+            return Err("Statement hash does not match its content".to_string());
+        }
+        
+        let ours = self.build_statement(&sender, theirs.from, theirs.to);
+        let discrepancies = diff_statements(&ours, &theirs);
+        if discrepancies.is_empty() {
+            println!("\n📒 Statement from {} matches ours ({})", sender, message.hash);
+        } else {
+            println!("\n📒 Statement from {} differs from ours on {} lines", sender, discrepancies.len());
+            self.record_journal("StatementDiscrepancies", serde_json::json!({
+                "peer": sender,
+                "from": theirs.from,
+                "to": theirs.to,
+                "lines": discrepancies.len(),
+            }));
+        }
+        let our_hash = ours.hash();
+        self.statement_comparisons.insert(sender, StatementComparison {
+            from: theirs.from,
+            to: theirs.to,
+            our_hash: our_hash.clone(),
+            their_hash: message.hash,
+            compared_at: Self::current_timestamp(),
+            discrepancies,
+        });
+        Ok(serde_json::json!({ "hash": our_hash }).to_string())
+    }
+    
+    #[remote]
+    async fn receive_intent_window_extended(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentWindowExtended", &message_json)?;
+        if let MevMessage::IntentWindowExtended { opp_id, new_deadline } =
+            MevMessage::parse(&message_json, "IntentWindowExtended")? {
+            let opp_id = self.canonical_opp_id(&opp_id);
+            let sender = source().node;
+            
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                if !data.may_execute(&sender) {
+                    return Err(format!("{} does not hold execution rights for {}", sender, opp_id));
+                }
+                // Extensions happen once; a repeat notice changes nothing
+                if data.window_extension.is_none() {
+                    println!("\n⏳ P2P: {} extended the intent window for {} to block {}", sender, opp_id, new_deadline);
+                    data.window_extension = Some(IntentWindowExtension {
+                        new_deadline,
+                        coverage: String::new(),
+                        max_repay_amount: String::new(),
+                        extended_by: sender,
+                        extended_at: Self::current_timestamp(),
+                    });
+                }
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_execution_claim(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveExecutionClaim", &message_json)?;
+        if let MevMessage::ExecutionClaim { opp_id } = MevMessage::parse(&message_json, "ExecutionClaim")? {
+            let claimant = source().node;
+            
+            let away = self.active_opportunities.get(&opp_id)
+                .is_some_and(|data| self.in_maintenance_at(&claimant, self.deadline_at(data)));
+            if away {
+                return Err(format!("{} announced maintenance over the deadline of {}", claimant, opp_id));
+            }
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                if data.executor_preference.is_empty() || data.execution_holder() == Some(&claimant) {
+                    data.claimed_by.get_or_insert(claimant.clone());
+                    println!("\n🔒 P2P: {} claimed execution of {}", claimant, opp_id);
+                } else {
+                    return Err(format!("{} does not hold execution rights for {}", claimant, opp_id));
+                }
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_capital_reserve_request(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveCapitalReserveRequest", &message_json)?;
+        if let MevMessage::CapitalReserveRequest { opp_id, intent_hash, amount, expires_at } =
+            MevMessage::parse(&message_json, "CapitalReserveRequest")? {
+            let executor = source().node;
+            
+            let granted = self.reserve_capital(&executor, &opp_id, &intent_hash, &amount, expires_at);
+            match &granted {
+                Ok(until) => println!("\n🔐 P2P: Reserved {} for {} on {} until {}", amount, executor, opp_id, until),
+                Err(reason) => println!("\n🔐 P2P: Refused to reserve {} for {} on {}: {}", amount, executor, opp_id, reason),
+            }
+            let response = MevMessage::CapitalReserveResponse {
+                opp_id,
+                intent_hash,
+                amount,
+                confirmed: granted.is_ok(),
+                expires_at: granted.as_ref().map_or(expires_at, |until| *until),
+                reason: granted.err(),
+            };
+            self.send_to_peer(&executor, response.handler(), &Self::message_json(&response));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_capital_reserve_response(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveCapitalReserveResponse", &message_json)?;
+        if let MevMessage::CapitalReserveResponse { opp_id, intent_hash, confirmed, expires_at, reason, .. } =
+            MevMessage::parse(&message_json, "CapitalReserveResponse")? {
+            let reason = reason.as_deref().unwrap_or("Refused");
+            let provider = source().node;
+            
+            let Some(excluded) = self.reservation_rounds.get_mut(&opp_id)
+                .and_then(|round| round.record_response(&intent_hash, &provider, confirmed, expires_at, reason)) else {
+                return Ok("ACK".to_string());
+            };
+            if excluded {
+                println!("\n🔐 P2P: {} refused to reserve for {} ({}); re-planning", provider, opp_id, reason);
+                self.record_journal("ReservationRejected", serde_json::json!({
+                    "opp_id": opp_id,
+                    "provider": provider,
+                    "intent_hash": intent_hash,
+                    "reason": reason,
+                }));
+            } else {
+                println!("\n🔐 P2P: {} reserved its capital for {}", provider, opp_id);
+            }
+            self.dispatch_opportunity(&opp_id);
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_intent_amendment(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentAmendment", &message_json)?;
+        let MevMessage::IntentAmendment { opp_id, new_max_amount } = MevMessage::parse(&message_json, "IntentAmendment")? else {
+            return Ok("ACK".to_string());
+        };
+        let new_max_amount = new_max_amount.parse::<U256>().map_err(|_| "Invalid new_max_amount")?;
+        let submitter = source().node;
+        
+        // Once the allocation went to Artemis the provider is committed to the original amount
+        if self.active_opportunities.get(&opp_id).map_or(false, |data| data.allocation_locked) {
+            let rejection = serde_json::json!({
+                "error": "AllocationLocked",
+                "opp_id": opp_id,
+                "reason": "Allocation already sent for execution; the original intent amount stands",
+            });
+            let committed_amount = self.submitted_intents.get(&opp_id)
+                .and_then(|intents| intents.iter().find(|data| data.submitter_node == submitter))
+                .map(|data| data.max_amount.clone());
+            println!("\n🔒 P2P: Refused amendment from {} for {}: allocation locked", submitter, opp_id);
+            let refusal = MevMessage::IntentAmendmentRejection {
+                opp_id: opp_id.clone(),
+                reason: rejection["reason"].as_str().map(str::to_string),
+                committed_amount,
+            };
+            self.send_to_peer(&submitter, refusal.handler(), &Self::message_json(&refusal));
+            return Err(rejection.to_string());
+        }
+        
+        let Some(data) = self.submitted_intents.get_mut(&opp_id)
+            .and_then(|intents| intents.iter_mut().find(|data| data.submitter_node == submitter)) else {
+            return Err(serde_json::json!({
+                "error": "UnknownIntent",
+                "opp_id": opp_id,
+            }).to_string());
+        };
+        let current = data.max_amount.parse::<U256>().unwrap_or(U256::ZERO);
+        if new_max_amount > current {
+            return Err(serde_json::json!({
+                "error": "AmendmentGrowsIntent",
+                "opp_id": opp_id,
+                "current_max_amount": current.to_string(),
+            }).to_string());
+        }
+        
+        // Update in place, keeping the embedded intent consistent with max_amount
+        data.max_amount = new_max_amount.to_string();
+        if let Some(mut intent) = data.parsed_intent() {
+            intent.max_amount = new_max_amount.to_string();
+            if let Ok(intent_json) = serde_json::to_string(&intent) {
+                data.intent = intent_json;
+            }
+        }
+        
+        println!("\n✂️  P2P: {} shrank its intent for {} from {} to {}", submitter, opp_id, current, new_max_amount);
+        if let (Some(opportunity), Some(intents)) = (
+            self.active_opportunities.get(&opp_id)
+                .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok()),
+            self.submitted_intents.get(&opp_id),
+        ) {
+            println!("   Coverage now: {} / {}", intent_coverage(&opportunity, intents), opportunity.max_repay_amount);
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_intent_amendment_rejection(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentAmendmentRejection", &message_json)?;
+        if let MevMessage::IntentAmendmentRejection { opp_id, reason, committed_amount } =
+            MevMessage::parse(&message_json, "IntentAmendmentRejection")? {
+            let reason = reason.unwrap_or_else(|| "Rejected".to_string());
+            let Some(outstanding) = self.our_intents.get_mut(&opp_id) else {
+                return Ok("ACK".to_string());
+            };
+            outstanding.amendment_rejected = Some(reason.clone());
+            // The executor holds us to what it allocated, not to the amount we tried to shrink to
+            if let Some(committed) = committed_amount {
+                outstanding.intent.max_amount = committed;
+            }
+            let committed_amount = outstanding.intent.max_amount.clone();
+            let asset = outstanding.intent.asset.clone();
+            self.set_commitment(&opp_id, &asset, &committed_amount);
+            println!("\n⚠️  P2P: {} refused to shrink our intent for {}: {}", source().node, opp_id, reason);
+            self.record_journal("IntentAmendmentRejected", serde_json::json!({
+                "opp_id": opp_id,
+                "executor": source().node,
+                "reason": reason,
+                "committed_amount": committed_amount,
+            }));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A provider pulling back its intent. Only its own intent is removed, and not once the
+    // opportunity is executing: then the revoker is told its intent stands.
+    #[remote]
+    async fn receive_intent_revocation(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentRevocation", &message_json)?;
+        let MevMessage::IntentRevocation { opp_id } = MevMessage::parse(&message_json, "IntentRevocation")? else {
+            return Ok("ACK".to_string());
+        };
+        let opp_id = self.canonical_opp_id(&opp_id);
+        let sender = source().node;
+        let held = self.submitted_intents.get(&opp_id)
+            .is_some_and(|intents| intents.iter().any(|data| data.submitter_node == sender));
+        if !held {
+            // Broadcast to every peer, so most hold nothing from it
+            return Ok("ACK".to_string());
+        }
+        let executing = self.pending_executions.contains_key(&opp_id)
+            || self.active_opportunities.get(&opp_id)
+                .is_some_and(|data| data.status == OpportunityStatus::Executing || data.allocation_locked);
+        if executing {
+            let reason = "Opportunity is already executing; the intent stands";
+            println!("\n🔒 P2P: Refused revocation from {} for {}: already executing", sender, opp_id);
+            let rejection = MevMessage::IntentRevocationRejection {
+                opp_id: opp_id.clone(),
+                reason: reason.to_string(),
+            };
+            self.send_to_peer(&sender, rejection.handler(), &Self::message_json(&rejection));
+            return Err(serde_json::json!({
+                "error": "RevocationRejected",
+                "opp_id": opp_id,
+                "reason": reason,
+            }).to_string());
+        }
+        if let Some(intents) = self.submitted_intents.get_mut(&opp_id) {
+            intents.retain(|data| data.submitter_node != sender);
+            if intents.is_empty() {
+                self.submitted_intents.remove(&opp_id);
+            }
+        }
+        println!("\n↩️  P2P: {} revoked its intent for {}", sender, opp_id);
+        self.record_journal("PeerIntentRevoked", serde_json::json!({
+            "opp_id": opp_id,
+            "submitter": sender,
+        }));
+        Ok("ACK".to_string())
+    }
+    
+    // An executor refused our revocation: the intent is ours again, capital locked as before
+    #[remote]
+    async fn receive_intent_revocation_rejection(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveIntentRevocationRejection", &message_json)?;
+        if let MevMessage::IntentRevocationRejection { opp_id, reason } =
+            MevMessage::parse(&message_json, "IntentRevocationRejection")? {
+            // Only a peer the intent went to can hold it against us
+            let sender = source().node;
+            if self.revoked_intents.get(&opp_id).is_some_and(|outstanding| !outstanding.sent_to.contains(&sender)) {
+                return Err(self.reject_inbound(serde_json::json!({
+                    "error": "NotARecipient",
+                    "opp_id": opp_id,
+                    "sender": sender,
+                }).to_string()));
+            }
+            let Some(mut outstanding) = self.revoked_intents.remove(&opp_id) else {
+                return Ok("ACK".to_string());
+            };
+            outstanding.revocation_rejected = Some(reason.clone());
+            let (asset, amount) = (outstanding.intent.asset.clone(), outstanding.intent.max_amount.clone());
+            self.our_intents.insert(opp_id.clone(), outstanding);
+            self.set_commitment(&opp_id, &asset, &amount);
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                data.participating = true;
+            }
+            println!("\n⚠️  P2P: {} refused our revocation for {}: {}", source().node, opp_id, reason);
+            self.record_journal("IntentRevocationRejected", serde_json::json!({
+                "opp_id": opp_id,
+                "executor": source().node,
+                "reason": reason,
+                "committed_amount": amount,
+            }));
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    #[remote]
+    async fn receive_backfill_request(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveBackfillRequest", &message_json)?;
+        let MevMessage::BackfillRequest { finder, from_seq, to_seq } = MevMessage::parse(&message_json, "BackfillRequest")? else {
+            return Ok("ACK".to_string());
+        };
+        if finder != our().node {
+            return Err("Backfill requests must be sent to the original finder".to_string());
+        }
+        if from_seq > to_seq {
+            return Err("Invalid backfill range".to_string());
+        }
+        
+        // Replay what we still hold; pruned opportunities are simply not resent
+        let requester = source().node;
+        let mut resent = Vec::new();
+        for (seq, opp_id) in self.originated_by_seq.range(from_seq..=to_seq).take(MAX_BACKFILL_BATCH) {
+            let Some(data) = self.active_opportunities.get(opp_id) else {
+                continue;
+            };
+            let message = self.opportunity_broadcast_message(opp_id, data, *seq, true);
+            self.send_to_peer(&requester, message.handler(), &Self::message_json(&message));
+            resent.push(opp_id.clone());
+        }
+        for opp_id in &resent {
+            if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+                data.broadcast_to.insert(requester.clone());
+            }
+        }
+        let resent = resent.len();
+        println!("🔁 Backfilled {} opportunities ({}..={}) to {}", resent, from_seq, to_seq, requester);
+        
+        Ok(serde_json::json!({ "resent": resent }).to_string())
+    }
+    
+    // A newly connected peer asking for what it missed: our live opportunities, newest first and
+    // each under its original finder, plus the intents we hold open on them
+    #[remote]
+    async fn receive_sync_request(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveSyncRequest", &message_json)?;
+        let MevMessage::SyncRequest { .. } = MevMessage::parse(&message_json, "SyncRequest")? else {
+            return Ok("ACK".to_string());
+        };
+        let requester = source().node;
+        if !self.known_peers.contains_key(&requester) {
+            return Err("Sync requests are only answered for known peers".to_string());
+        }
+        
+        let head = self.current_block();
+        let mut live: Vec<(&String, &OpportunityData)> = self.active_opportunities.iter()
+            .filter(|(opp_id, data)| data.status.dispatchable() && !is_self_test_id(opp_id))
+            .filter(|(_, data)| head.map_or(true, |head| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                .map_or(true, |opportunity| opportunity.deadline_block > head)))
+            .collect();
+        live.sort_by(|a, b| b.1.received_at.cmp(&a.1.received_at).then_with(|| a.0.cmp(b.0)));
+        let truncated = live.len() > MAX_SYNC_OPPORTUNITIES;
+        let synced: Vec<String> = live.into_iter()
+            .take(MAX_SYNC_OPPORTUNITIES)
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        let opportunities: Vec<MevMessage> = synced.iter()
+            .filter_map(|opp_id| self.active_opportunities.get(opp_id).map(|data| self.sync_broadcast_message(opp_id, data)))
+            .collect();
+        let intents: Vec<serde_json::Value> = synced.iter()
+            .filter_map(|opp_id| self.our_intents.get(opp_id))
+            .filter(|outstanding| head.map_or(true, |head| outstanding.intent.expires_block >= head))
+            .filter_map(|outstanding| Some(MevMessage::IntentSubmission {
+                opp_id: outstanding.intent.opp_id.clone(),
+                strategy_id: "aave-liquidation".to_string(),
+                intent: serde_json::to_string(&outstanding.intent).ok()?,
+                max_amount: outstanding.intent.max_amount.clone(),
+                min_bonus_bps: outstanding.intent.min_bonus_bps,
+                expires_block: outstanding.intent.expires_block,
+                fee_bps: outstanding.intent.fee_bps,
+                signature: None,
+            }))
+            .map(|message| self.sign_message(&message))
+            .collect();
+        
+        // The requester now holds these, so cancellations and withdrawals reach it too
+        for opp_id in &synced {
+            if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+                data.broadcast_to.insert(requester.clone());
+            }
+            if let Some(outstanding) = self.our_intents.get_mut(opp_id) {
+                if !outstanding.sent_to.contains(&requester) {
+                    outstanding.sent_to.push(requester.clone());
+                }
+            }
+        }
+        let counts = serde_json::json!({ "opportunities": opportunities.len(), "intents": intents.len() });
+        println!("🔁 Synced {} opportunities and {} intents to {}", opportunities.len(), intents.len(), requester);
+        let response = MevMessage::SyncResponse {
+            opportunities: opportunities.iter().map(Self::message_json).collect(),
+            intents,
+            truncated,
+        };
+        self.send_to_peer(&requester, response.handler(), &Self::message_json(&response));
+        
+        Ok(counts.to_string())
+    }
+    
+    // The answer to our sync request. Each entry goes through the same dedup and validation as a
+    // live broadcast or intent, but isn't forwarded: the rest of the mesh already has it.
+    #[remote]
+    async fn receive_sync_response(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveSyncResponse", &message_json)?;
+        let MevMessage::SyncResponse { opportunities, intents, truncated } = MevMessage::parse(&message_json, "SyncResponse")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        // Unsolicited syncs would slip past the broadcast rate limit
+        if self.pending_syncs.remove(&sender).is_none() {
+            return Err("No sync request outstanding to this peer".to_string());
+        }
+        let entries = |entries: Vec<serde_json::Value>| -> Vec<String> {
+            entries.iter().map(|entry| entry.to_string()).collect()
+        };
+        let (opportunities, intents) = (entries(opportunities), entries(intents));
+        
+        let mut accepted = 0;
+        let mut rejected = 0;
+        for entry in opportunities.iter().take(MAX_SYNC_OPPORTUNITIES) {
+            match self.accept_opportunity_broadcast(sender.clone(), entry, false).await {
+                Ok(()) => accepted += 1,
+                Err(reason) => {
+                    rejected += 1;
+                    println!("   ⚠️  Skipped synced opportunity from {}: {}", sender, reason);
+                }
+            }
+        }
+        // Only the responder's own intents, and none we already hold from it
+        let mut intents_accepted = 0;
+        for entry in intents.iter().take(MAX_SYNC_OPPORTUNITIES) {
+            let Ok(MevMessage::IntentSubmission { opp_id, .. }) = MevMessage::parse(entry, "IntentSubmission") else {
+                rejected += 1;
+                continue;
+            };
+            let opp_id = self.canonical_opp_id(&opp_id);
+            if self.submitted_intents.get(&opp_id).is_some_and(|held| held.iter().any(|intent| intent.submitter_node == sender)) {
+                continue;
+            }
+            if let Err(reason) = self.check_message_signature(&source(), entry).await {
+                rejected += 1;
+                println!("   ⚠️  Skipped synced intent from {}: {}", sender, reason);
+                continue;
+            }
+            match self.accept_intent_submission(sender.clone(), entry, false) {
+                Ok(dispatch) => {
+                    intents_accepted += 1;
+                    for opp_id in dispatch {
+                        self.dispatch_opportunity(&opp_id);
+                    }
+                }
+                Err(reason) => {
+                    rejected += 1;
+                    println!("   ⚠️  Skipped synced intent from {}: {}", sender, reason);
+                }
+            }
+        }
+        
+        println!("\n🔁 P2P: Synced state from {}: {} opportunities, {} intents, {} rejected{}",
+            sender, accepted, intents_accepted, rejected, if truncated { " (truncated)" } else { "" });
+        self.record_journal("PeerStateSynced", serde_json::json!({
+            "peer": sender,
+            "opportunities": opportunities.len(),
+            "opportunities_accepted": accepted,
+            "intents": intents.len(),
+            "intents_accepted": intents_accepted,
+            "rejected": rejected,
+            "truncated": truncated,
+        }));
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A finder pulling back its own opportunity. We drop it unless our bridge already has it, in
+    // which case the execution runs its course and its receipt settles it as usual.
+    #[remote]
+    async fn receive_opportunity_cancellation(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveOpportunityCancellation", &message_json)?;
+        #[derive(Deserialize)]
+        struct Cancellation {
+            opp_id: String,
+            finder: String,
+            reason: String,
+        }
+        
+        let MevMessage::OpportunityCancellation { cancellation: signed_text, signature } =
+            MevMessage::parse(&message_json, "OpportunityCancellation")? else {
+            return Ok("ACK".to_string());
+        };
+        let cancellation: Cancellation = serde_json::from_str(&signed_text)
+            .map_err(|e| format!("Invalid cancellation: {}", e))?;
+        let sender = source().node;
+        if cancellation.finder != sender {
+            return Err("Cancellations must come from the finder".to_string());
+        }
+        if let Some(signature) = &signature {
+            let signature = hex_decode(signature).ok_or("Signature is not valid hex")?;
+            if !Self::verify_signature(source(), signed_text.as_bytes(), signature).await? {
+                return Err("Cancellation signature does not verify".to_string());
+            }
+        }
+        
+        let opp_id = self.canonical_opp_id(&cancellation.opp_id);
+        let Some(data) = self.active_opportunities.get(&opp_id) else {
+            return Ok(serde_json::json!({ "status": "Unknown" }).to_string());
+        };
+        if data.finder_node != sender {
+            return Err("Only the original finder may cancel an opportunity".to_string());
+        }
+        let detail = serde_json::json!({
+            "opp_id": opp_id,
+            "finder": sender,
+            "reason": cancellation.reason,
+            "signed": signature.is_some(),
+        });
+        if self.pending_executions.contains_key(&opp_id) {
+            println!("\n🛑 P2P: {} cancelled {} ({}), but our bridge already has it", sender, opp_id, cancellation.reason);
+            self.record_journal("CancellationAfterDispatch", detail);
+            return Ok(serde_json::json!({ "status": "AlreadyDispatched" }).to_string());
+        }
+        self.retire_opportunity(&opp_id, RetirementOutcome::expired("CancelledByFinder", Some(sender.clone()), false));
+        println!("\n🛑 P2P: {} cancelled opportunity {} ({})", sender, opp_id, cancellation.reason);
+        self.record_journal("OpportunityCancelledByFinder", detail);
+        Ok(serde_json::json!({ "status": "Cancelled" }).to_string())
+    }
+    
+    // Agreement changes, resend requests and restore requests between mirror buddies
+    #[remote]
+    async fn receive_mirror_control(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveMirrorControl", &message_json)?;
+        let MevMessage::MirrorControl { action, from_seq, to_seq } = MevMessage::parse(&message_json, "MirrorControl")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        
+        match action {
+            // Nothing is held until our operator accepts
+            MirrorAction::Offer => {
+                if self.held_mirrors.get(&sender).is_some_and(|held| held.agreement.active()) {
+                    return Ok("ACK".to_string());
+                }
+                self.held_mirrors.insert(sender.clone(), HeldMirror {
+                    agreement: MirrorAgreement::offered(now),
+                    copy: Default::default(),
+                });
+                println!("\n🪞 P2P: {} asks us to mirror its records; accept with accept_mirror", sender);
+                self.record_journal("MirrorOfferReceived", serde_json::json!({ "node": sender }));
+            }
+            MirrorAction::Accept => {
+                let Some(agreement) = self.mirror_buddies.get_mut(&sender).filter(|agreement| !agreement.active()) else {
+                    return Err("No mirror offer pending with this node".to_string());
+                };
+                *agreement = MirrorAgreement { state: MirrorState::Active, since: now };
+                println!("\n🪞 P2P: {} now mirrors our records", sender);
+                self.record_journal("MirrorActive", serde_json::json!({ "buddy": sender }));
+                if self.mirror_log.next_seq == 0 {
+                    self.seed_mirror_log();
+                }
+                let entries: Vec<MirrorEntry> = self.mirror_log.entries.iter().cloned().collect();
+                self.stream_mirror_entries(&sender, entries, false);
+            }
+            MirrorAction::Revoke => {
+                let theirs = self.mirror_buddies.remove(&sender).is_some();
+                let ours = self.held_mirrors.remove(&sender).is_some();
+                if theirs || ours {
+                    println!("\n🪞 P2P: {} revoked our mirror", sender);
+                    self.record_journal("MirrorRevoked", serde_json::json!({
+                        "peer": sender,
+                        "by": sender,
+                    }));
+                }
+            }
+            MirrorAction::ResendRequest => {
+                if !self.mirror_buddies.get(&sender).is_some_and(|agreement| agreement.active()) {
+                    return Err("Not mirrored by this node".to_string());
+                }
+                let from_seq = from_seq.ok_or("Missing from_seq")?;
+                let to_seq = to_seq.ok_or("Missing to_seq")?;
+                let entries = self.mirror_log.range(from_seq, to_seq);
+                println!("🔁 Resending {} mirror entries ({}..={}) to {}", entries.len(), from_seq, to_seq, sender);
+                self.stream_mirror_entries(&sender, entries, false);
+            }
+            // Answered from whatever copy we hold, even one whose owner has forgotten the agreement
+            MirrorAction::RestoreRequest => {
+                let Some(held) = self.held_mirrors.get(&sender).filter(|held| held.agreement.active()) else {
+                    return Err("We hold no mirror for this node".to_string());
+                };
+                let entries = held.copy.latest();
+                println!("\n🪞 P2P: Returning {} mirrored records to {}", entries.len(), sender);
+                self.record_journal("MirrorRestoreServed", serde_json::json!({
+                    "node": sender,
+                    "records": entries.len(),
+                }));
+                self.stream_mirror_entries(&sender, entries, true);
+            }
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // Mirror entries from a node we keep a copy for, or our own records coming back in a restore
+    #[remote]
+    async fn receive_mirror_entries(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveMirrorEntries", &message_json)?;
+        let MevMessage::MirrorEntries { entries, restore, last } = MevMessage::parse(&message_json, "MirrorEntries")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        if entries.len() > MAX_MIRROR_BATCH {
+            return Err(format!("At most {} mirror entries per message", MAX_MIRROR_BATCH));
+        }
+        
+        if !restore {
+            let Some(held) = self.held_mirrors.get_mut(&sender).filter(|held| held.agreement.active()) else {
+                return Err("Not mirroring this node".to_string());
+            };
+            let gaps: Vec<(u64, u64)> = entries.into_iter()
+                .filter_map(|entry| held.copy.store(entry))
+                .collect();
+            for (from_seq, to_seq) in gaps {
+                println!("   ⚠️  Missed mirror entries {}..={} from {}; requesting resend", from_seq, to_seq, sender);
+                let resend = MevMessage::MirrorControl {
+                    action: MirrorAction::ResendRequest,
+                    from_seq: Some(from_seq),
+                    to_seq: Some(to_seq),
+                };
+                self.send_to_peer(&sender, resend.handler(), &Self::message_json(&resend));
+            }
+            return Ok("ACK".to_string());
+        }
+        
+        if !self.mirror_restore.as_ref().is_some_and(|restore| restore.buddy == sender && restore.completed_at.is_none()) {
+            return Err("No restore requested from this node".to_string());
+        }
+        for entry in entries {
+            let mut verified = None;
+            for signature in &entry.signatures {
+                let valid = matches!(
+                    Self::check_bundle_signature(&signature.signer, entry.record.as_bytes(), &signature.signature).await,
+                    Ok(true)
+                );
+                verified = Some(verified.unwrap_or(true) && valid);
+            }
+            self.restore_mirror_entry(&sender, entry, verified);
+        }
+        if last {
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            if let Some(restore) = self.mirror_restore.as_mut() {
+                restore.completed_at = Some(now);
+                println!("\n🪞 Restore from {} complete: {} restored, {} kept local, {} with bad signatures",
+                    sender, restore.restored, restore.kept_local, restore.bad_signatures);
+            }
+            let summary = serde_json::to_value(&self.mirror_restore).unwrap_or_default();
+            self.record_journal("MirrorRestored", summary);
+        }
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A peer's list of the victims its bridge polls, and which of them it leaves to us
+    #[remote]
+    async fn receive_monitoring_coverage(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveMonitoringCoverage", &message_json)?;
+        let MevMessage::MonitoringCoverage { monitoring, subscribed, .. } =
+            MevMessage::parse(&message_json, "MonitoringCoverage")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        let limit = self.node_config.monitoring_policy.max_advertised;
+        let normalize = |victims: Vec<String>| -> BTreeSet<String> {
+            victims.into_iter().take(limit).map(|victim| victim.to_lowercase()).collect()
+        };
+        let coverage = self.monitoring_coverage.peers.entry(sender).or_default();
+        coverage.monitoring = normalize(monitoring);
+        coverage.subscribed = normalize(subscribed);
+        coverage.advertised_at = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A health factor read relayed by the peer we left the victim to
+    #[remote]
+    async fn receive_position_update(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceivePositionUpdate", &message_json)?;
+        let MevMessage::PositionUpdate { borrower, collateral_asset, debt_asset, health_factor, debt_amount, block } =
+            MevMessage::parse(&message_json, "PositionUpdate")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        let victim = borrower.to_lowercase();
+        // A block past our head would keep the assignment from ever going stale
+        if let Some(head) = self.current_block().filter(|head| block > head + 2) {
+            return Err(serde_json::json!({ "error": "FutureBlock", "block": block, "head": head }).to_string());
+        }
+        let Some(assignment) = self.monitoring_coverage.assignments.get_mut(&victim).filter(|assignment| assignment.peer == sender) else {
+            return Err(serde_json::json!({ "error": "NotSubscribed", "borrower": victim }).to_string());
+        };
+        if assignment.last_update_block.is_some_and(|last| last >= block) {
+            return Ok("ACK".to_string());
+        }
+        assignment.last_update_block = Some(block);
+        assignment.last_health_factor = Some(health_factor.clone());
+        assignment.updates += 1;
+        self.monitoring_coverage.updates_received += 1;
+        
+        self.watched_positions.insert(victim.clone(), PositionData {
+            borrower: victim,
+            collateral_asset,
+            debt_asset,
+            last_health_factor: health_factor,
+            last_updated: Self::current_timestamp(),
+            debt_amount,
+            last_checked_block: block,
+            relayed_by: Some(sender),
+        });
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A peer asking for our records of one opportunity; each section is shared as record_sharing
+    // allows, and what we send is signed
+    #[remote]
+    async fn receive_record_request(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveRecordRequest", &message_json)?;
+        let MevMessage::RecordRequest { request_id, opp_id, sections } = MevMessage::parse(&message_json, "RecordRequest")? else {
+            return Ok("ACK".to_string());
+        };
+        let requester = source().node;
+        let opp_id = self.canonical_opp_id(&opp_id);
+        let counterparty = self.took_part(&opp_id, &requester);
+        let mut shared = Vec::new();
+        let mut refused = BTreeMap::new();
+        for section in sections {
+            match self.node_config.record_sharing.refusal(section, counterparty) {
+                Some(reason) => {
+                    refused.insert(section, reason.to_string());
+                }
+                None => shared.push(section),
+            }
+        }
+        
+        let set = RecordSet {
+            opp_id: opp_id.clone(),
+            requested_by: requester.clone(),
+            records: self.shared_records(&opp_id, &requester, &shared),
+            refused: refused.clone(),
+        };
+        let body = set.canonical_json();
+        let signature = match Self::sign_bytes(body.as_bytes()).await {
+            Ok(signature) => hex_encode(&signature),
+            Err(e) => {
+                println!("   Records for {} go out unsigned: {}", requester, e);
+                String::new()
+            }
+        };
+        let response = MevMessage::RecordResponse {
+            request_id: request_id.clone(),
+            responder: our().to_string(),
+            body,
+            signature,
+        };
+        self.send_to_peer(&requester, response.handler(), &Self::message_json(&response));
+        self.record_journal("CounterpartyViewServed", serde_json::json!({
+            "request_id": request_id,
+            "requested_by": requester,
+            "opp_id": opp_id,
+            "shared": shared,
+            "refused": refused,
+            "records": set.records.len(),
+        }));
+        
+        Ok("ACK".to_string())
+    }
+    
+    // A counterparty's records for a view we requested: checked against its signature and
+    // diffed against ours
+    #[remote]
+    async fn receive_record_response(&mut self, message_json: String) -> Result<String, String> {
+        self.admit_peer_message("ReceiveRecordResponse", &message_json)?;
+        let MevMessage::RecordResponse { request_id, responder, body, signature } =
+            MevMessage::parse(&message_json, "RecordResponse")? else {
+            return Ok("ACK".to_string());
+        };
+        let sender = source().node;
+        let Some(view) = self.counterparty_views.get(&request_id)
+            .filter(|view| view.peer == sender && view.received_at.is_none())
+            .cloned() else {
+            return Err(serde_json::json!({ "error": "UnknownRequest", "request_id": request_id }).to_string());
+        };
+        let set: RecordSet = serde_json::from_str(&body)
+            .map_err(|e| format!("Record set does not parse: {}", e))?;
+        if set.opp_id != view.opp_id || set.requested_by != our().node {
+            return Err(serde_json::json!({ "error": "WrongRecordSet", "request_id": request_id }).to_string());
+        }
+        
+        let signed_by_sender = responder.split('@').next() == Some(sender.as_str());
+        let signature_valid = signed_by_sender && !signature.is_empty() && matches!(
+            Self::check_bundle_signature(&responder, body.as_bytes(), &signature).await,
+            Ok(true)
+        );
+        
+        let sections: Vec<RecordSection> = view.sections.iter()
+            .filter(|section| !set.refused.contains_key(section))
+            .copied()
+            .collect();
+        let ours = self.shared_records(&view.opp_id, &sender, &sections);
+        let mut diffs = diff_records(&ours, &set.records, &sections);
+        for diff in diffs.iter_mut() {
+            let slot = |record: &&SharedRecord| record.section == diff.section && record.key == diff.key;
+            for (records, check) in [(&ours, &mut diff.signatures.ours), (&set.records, &mut diff.signatures.theirs)] {
+                let Some(record) = records.iter().find(slot).filter(|record| !record.signatures.is_empty()) else {
+                    continue;
+                };
+                let mut valid = true;
+                for signature in &record.signatures {
+                    valid &= matches!(
+                        Self::check_bundle_signature(&signature.signer, record.canonical.as_bytes(), &signature.signature).await,
+                        Ok(true)
+                    );
+                }
+                *check = Some(valid);
+            }
+        }
+        
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let Some(view) = self.counterparty_views.get_mut(&request_id) else {
+            return Ok("ACK".to_string());
+        };
+        view.received_at = Some(now);
+        view.response_signature_valid = Some(signature_valid);
+        view.refused = set.refused;
+        view.diffs = diffs;
+        let summary = view.summary();
+        println!("\n🔍 Counterparty view of {} from {}: {}", view.opp_id, sender, summary["by_kind"]);
+        self.record_journal("CounterpartyViewReceived", summary);
+        
+        Ok("ACK".to_string())
+    }
+    
+    // HELPER FUNCTIONS
+    
+    #[local]
+    async fn announce_to_peer(&self, peer_node: String, reply: bool) -> Result<(), String> {
+        self.send_announcement(peer_node, reply)
+    }
+    
+    fn send_announcement(&self, peer_node: String, reply: bool) -> Result<(), String> {
+        let node_info = NodeInfo {
+            node_id: our().node.clone(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            roles: self.get_enabled_roles(),
+            capital_assets: self.available_balances.keys().cloned().collect(),
+        };
+        
+        let announcement = MevMessage::NodeAnnouncement {
+            node_info,
+            capabilities: self.node_config.enabled_strategies.clone(),
+            timestamp: Self::current_timestamp(),
+            known_peers: peers_to_share(&self.known_peers, &peer_node),
+            reply,
+            maintenance: self.maintenance_window,
+            signature: None,
+        };
+        let announcement_json = self.sign_message(&announcement);
+        
+        self.peer_process_id(&peer_node)?;
+        self.send_to_peer(&peer_node, announcement.handler(), &announcement_json);
+        Ok(())
+    }
+    
+    // Stores an intent `submitter` sent us, live or as part of a state sync, and returns the
+    // opportunities to dispatch now that it has arrived. Live intents must carry a fresh nonce;
+    // synced ones are deduplicated against what we hold instead.
+    fn accept_intent_submission(&mut self, submitter: String, message_json: &str, live: bool) -> Result<Vec<String>, String> {
+        let nonce = match live {
+            true => self.check_message_nonce(&submitter, "IntentSubmission", message_json)?,
+            false => None,
+        };
+        if let MevMessage::IntentSubmission { opp_id, intent, max_amount, expires_block, .. } =
+            MevMessage::parse(message_json, "IntentSubmission")? {
+            self.check_peer_role(&submitter, Some(NodeRole::CapitalProvider), "IntentSubmission")?;
+            // Intents against a linked duplicate fund the canonical opportunity
+            let opp_id = self.canonical_opp_id(&opp_id);
+            
+            let parsed = serde_json::from_str::<AaveLiquidationIntent>(&intent).ok();
+            let is_simulated = parsed.as_ref().is_some_and(|parsed| parsed.simulated);
+            if is_simulated && !self.node_config.paper_trading {
+                println!("\n🧪 P2P: Refused simulated intent from {} for {}", submitter, opp_id);
+                return Err(serde_json::json!({
+                    "error": "SimulatedIntent",
+                    "opp_id": opp_id,
+                    "reason": "Intent is backed by demo capital and this node is not paper trading",
+                }).to_string());
+            }
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            if let Some(head) = self.chain_head.map(|head| head.estimate(now)).filter(|head| expires_block < *head) {
+                println!("\n⌛ P2P: Refused intent from {} for {}: expired at block {} (head {})", submitter, opp_id, expires_block, head);
+                return Err(serde_json::json!({
+                    "error": "IntentExpired",
+                    "opp_id": opp_id,
+                    "expires_block": expires_block,
+                    "head": head,
+                }).to_string());
+            }
+            if let Err(rejection) = self.check_intent_asset(&opp_id, &intent) {
+                *self.malformed_message_strikes.entry(submitter.clone()).or_insert(0) += 1;
+                self.peer_reputation.entry(submitter.clone()).or_default().note_refused();
+                println!("\n⚠️  P2P: Rejected intent from {} for opportunity {}: {}", submitter, opp_id, rejection);
+                return Err(rejection.to_string());
+            }
+            if let Err(rejection) = self.check_intent_bonus(&opp_id, &intent) {
+                println!("\n⚠️  P2P: Rejected intent from {} for opportunity {}: {}", submitter, opp_id, rejection);
+                return Err(rejection.to_string());
+            }
+            // One intent per provider per opportunity: a resubmission replaces the one we hold,
+            // unless the allocation already went out with it
+            let held = self.submitted_intents.get(&opp_id)
+                .is_some_and(|intents| intents.iter().any(|data| data.submitter_node == submitter));
+            if held && self.active_opportunities.get(&opp_id).is_some_and(|data| data.allocation_locked) {
+                return Err(serde_json::json!({
+                    "error": "AllocationLocked",
+                    "opp_id": opp_id,
+                    "reason": "Allocation already sent for execution; the intent we hold stands",
+                }).to_string());
+            }
+            let entry = IntentData {
+                intent: intent.clone(),
+                submitter_node: submitter.clone(),
+                max_amount: max_amount.to_string(),
+                expires_block,
+                received_at: now,
+                fee_bps: parsed.map_or(0, |parsed| parsed.fee_bps),
+            };
+            let intents = self.submitted_intents.entry(opp_id.clone()).or_default();
+            match merge_intent(intents, entry) {
+                IntentMerge::Added => {}
+                IntentMerge::Replaced { previous_amount } => {
+                    println!("\n🔁 P2P: {} replaced its intent for {} ({} -> {})", submitter, opp_id, previous_amount, max_amount);
+                }
+                IntentMerge::Stale => {
+                    return Err(serde_json::json!({
+                        "error": "StaleIntent",
+                        "opp_id": opp_id,
+                        "reason": "The intent we hold from this provider was received later",
+                    }).to_string());
+                }
+            }
+            self.collection_recency.touch(&opp_id);
+            self.record_message_nonce(&submitter, "IntentSubmission", nonce);
+            
+            if self.active_opportunities.get(&opp_id).is_some_and(|data| data.status == OpportunityStatus::Open) {
+                self.set_opportunity_status(&opp_id, OpportunityStatus::CollectingIntents);
+            }
+            
+            self.notify_ui(UiEventKind::IntentReceived, serde_json::json!({
+                "opp_id": opp_id,
+                "submitter_node": submitter,
+                "max_amount": max_amount,
+                "expires_block": expires_block,
+            }));
+            
+            println!("\n💰 P2P: Received intent from {} for opportunity {}", submitter, opp_id);
+            println!("   Max amount: {}", max_amount);
+            println!("   Total intents for this opp: {}", self.submitted_intents.get(&opp_id).map(|v| v.len()).unwrap_or(0));
+            
+            // Only the opportunity that gained an intent can have become dispatchable; others are
+            // picked up by succession and the maintenance tick
+            if self.node_config.executor_enabled {
+                let mut dispatch = self.check_executor_succession();
+                dispatch.push(opp_id);
+                return Ok(dispatch);
+            }
+        }
+        
+        Ok(Vec::new())
+    }
+    
+    // Stores, evaluates and (when `relay`) forwards a broadcast that `sender` passed us, after the
+    // usual dedup, policy and validation checks. Also takes the entries of a state sync, which
+    // arrive through the sender that held them and are never relayed on.
+    async fn accept_opportunity_broadcast(&mut self, sender: String, message_json: &str, relay: bool) -> Result<(), String> {
+        if let MevMessage::OpportunityBroadcast {
+            opp_id, strategy_id, opportunity, finder_fee_bps, executor_preference, finder_seq, origin, hop_count, finder_node, ..
+        } = MevMessage::parse(message_json, "OpportunityBroadcast")? {
+            if is_self_test_id(&opp_id) {
+                return Err(format!("Refusing self-test opportunity {}", opp_id));
+            }
+            // Relays pass the finder along; the sender is only who it reached us through
+            let finder = finder_node.unwrap_or_else(|| sender.clone());
+            // Finds come from finders. A relay only has to have announced itself; the finder it
+            // passes on is held to its role when it's a peer of ours.
+            if finder == sender {
+                self.check_peer_role(&sender, Some(NodeRole::Finder), "OpportunityBroadcast")?;
+            } else {
+                self.check_peer_role(&sender, None, "OpportunityBroadcast")?;
+                if self.known_peers.get(&finder).is_some_and(|peer| peer.node_info.is_some()) {
+                    self.check_peer_role(&finder, Some(NodeRole::Finder), "OpportunityBroadcast")?;
+                }
+            }
+            
+            // Already handled: acknowledged, but not stored, evaluated or forwarded again
+            let digest = intent_hash(&opportunity);
+            if self.seen_broadcasts.is_duplicate(&opp_id, &digest) {
+                self.seen_broadcasts.duplicates_dropped += 1;
+                return Ok(());
+            }
+            
+            // Relays strip sequence numbers, so one present is the sender's own
+            if let Some(seq) = finder_seq {
+                self.track_finder_sequence(&sender, seq);
+            }
+            *self.finder_sequences.entry(finder.clone()).or_default()
+                .by_origin.entry(origin.class()).or_insert(0) += 1;
+            // A strategy we've disabled is acknowledged but not stored, evaluated or forwarded
+            if !self.node_config.strategy_enabled(&strategy_id) {
+                *self.ignored_broadcasts.entry(strategy_id).or_insert(0) += 1;
+                return Ok(());
+            }
+            // So is anything relayed by or found by a peer whose record the policy ignores
+            let policy = &self.node_config.reputation_policy;
+            if let Some(ignored) = [&sender, &finder].into_iter()
+                .find(|peer| self.peer_reputation.get(*peer).is_some_and(|reputation| policy.ignores(reputation)))
+                .cloned() {
+                if let Some(reputation) = self.peer_reputation.get_mut(&ignored) {
+                    reputation.broadcasts_ignored += 1;
+                }
+                println!("\n🙈 P2P: Ignored broadcast {} from {}: {}'s invalid message share is over policy", opp_id, sender, ignored);
+                return Ok(());
+            }
+            if strategy_id == "aave-liquidation" {
+                let head = self.current_block();
+                let parsed = serde_json::from_str::<AaveLiquidationOpportunity>(&opportunity)
+                    .map_err(|e| e.to_string())
+                    .and_then(|opp| match opp.opp_id == opp_id {
+                        true => opp.validate(head),
+                        false => Err(format!("Embedded opp_id {} does not match {}", opp.opp_id, opp_id)),
+                    });
+                if let Err(reason) = parsed {
+                    *self.malformed_message_strikes.entry(sender.clone()).or_insert(0) += 1;
+                    self.peer_reputation.entry(sender.clone()).or_default().note_refused();
+                    self.finder_sequences.entry(sender.clone()).or_default().invalid_opportunities += 1;
+                    println!("\n⚠️  P2P: Rejected opportunity {} from {}: {}", opp_id, sender, reason);
+                    return Err(serde_json::json!({
+                        "error": "InvalidOpportunity",
+                        "opp_id": opp_id,
+                        "reason": reason,
+                    }).to_string());
+                }
+            }
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            // Over quota isn't malformed: no strike, and not marked seen so a retry can land later
+            if !self.active_opportunities.contains_key(&opp_id) {
+                self.admit_from_finder(&finder, &opp_id, now)?;
+                self.peer_reputation.entry(finder.clone()).or_default().opportunities_sent += 1;
+            }
+            self.seen_broadcasts.record(&opp_id, digest, now);
+            
+            let claim = FinderClaim {
+                finder: finder.clone(),
+                opp_id: opp_id.clone(),
+                seen_at: Self::current_timestamp(),
+                finder_fee_bps,
+                opportunity: opportunity.clone(),
+            };
+            
+            let mut evaluate_id = opp_id.clone();
+            if let Some(existing) = self.active_opportunities.get_mut(&opp_id) {
+                // A repeat broadcast re-prices the opportunity; participation state carries over
+                match existing.claiming_finders.iter_mut().find(|c| c.opp_id == opp_id) {
+                    Some(own_claim) => {
+                        own_claim.opportunity = opportunity.clone();
+                        existing.adopt_best_terms(&opp_id);
+                    }
+                    None => existing.opportunity = opportunity.clone(),
+                }
+            } else if let Some(canonical) = self.link_duplicate_find(&strategy_id, claim.clone()) {
+                // Same victim and debt already known under another opp_id: one record, several claims
+                evaluate_id = canonical;
+            } else {
+                // Store opportunity
+                let mut data = OpportunityData::new(opportunity.clone(), strategy_id.clone(), finder.clone(), executor_preference);
+                data.config_revision = self.config_revision;
+                data.received_block = self.current_block();
+                data.holder_since_block = data.received_block;
+                self.elect_executor(&opp_id, &mut data);
+                data.claiming_finders.push(claim);
+                data.origin = origin.clone();
+                data.received_from = (sender != finder).then(|| sender.clone());
+                self.active_opportunities.insert(opp_id.clone(), data);
+                self.collection_recency.touch(&opp_id);
+                self.origin_stats.entry(origin.class()).or_default().received += 1;
+                self.performance_metrics.strategy(&strategy_id).opportunities_seen += 1;
+                self.notify_ui(UiEventKind::OpportunityAdded, serde_json::json!({
+                    "opp_id": opp_id,
+                    "strategy_id": strategy_id,
+                    "finder_node": finder,
+                    "received_from": (sender != finder).then(|| sender.clone()),
+                    "opportunity": serde_json::from_str::<serde_json::Value>(&opportunity).unwrap_or_default(),
+                }));
+            }
+            
+            if sender == finder {
+                println!("\n🌐 P2P: Received opportunity {} from peer {} ({:?})", opp_id, sender, origin.class());
+            } else {
+                println!("\n🌐 P2P: Received opportunity {} found by {} via {} ({:?})", opp_id, finder, sender, origin.class());
+            }
+            
+            if let Some(hops) = forward_hop(hop_count, self.node_config.max_broadcast_hops).filter(|_| relay) {
+                self.forward_broadcast(message_json, hops);
+            }
+            
+            self.assess_risk(&evaluate_id);
+            
+            // Auto-evaluate and submit intent if we're a capital provider
+            if self.node_config.capital_provider_enabled && strategy_id == "aave-liquidation" {
+                self.evaluate_and_submit_intent(evaluate_id).await?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    #[local]
+    async fn evaluate_and_submit_intent(&mut self, opp_id: String) -> Result<(), String> {
+        self.place_bid(opp_id, None).await.map(|_| ())
+    }
+    
+    // Evaluates an opportunity as capital provider and submits our intent when it passes. Without
+    // a manual amount the bid policy sizes the bid, and with auto_bid off a passing find is left
+    // pending review instead. Declines are recorded on the opportunity with the rule that failed.
+    async fn place_bid(&mut self, opp_id: String, manual_amount: Option<U256>) -> Result<Option<AaveLiquidationIntent>, String> {
+        let opportunity_data = self.active_opportunities.get(&opp_id)
+            .ok_or("Opportunity not found")?;
+        let strategy = self.strategy_config(&opportunity_data.strategy_id)?.clone();
+        
+        if opportunity_data.strategy_id == "aave-liquidation" {
+            let opportunity: AaveLiquidationOpportunity = serde_json::from_str(&opportunity_data.opportunity)
+                .map_err(|e| format!("Failed to parse opportunity: {}", e))?;
+            let participating = opportunity_data.participating;
+            let origin = opportunity_data.origin.class();
+            let strategy_enabled = self.node_config.strategy_enabled(&opportunity_data.strategy_id);
+            let finder = opportunity_data.finder_node.clone();
+            
+            // Entering is judged on the current config; staying in on the one we committed under
+            let committed_under = self.our_intents.get(&opp_id)
+                .filter(|_| participating)
+                .and_then(|outstanding| self.config_history.get(&outstanding.config_revision));
+            let terms = committed_under.map_or(&self.node_config, |revision| &revision.config);
+            let strategy = committed_under
+                .and_then(|revision| revision.strategies.get(&opportunity_data.strategy_id))
+                .unwrap_or(&strategy);
+            
+            let profit = opportunity.estimated_profit_usd.parse::<U256>().unwrap_or(U256::ZERO);
+            let min_profit = terms.min_profit_for(origin, Some(strategy));
+            let clears_bar = clears_profit_bar(
+                profit,
+                min_profit,
+                terms.profit_entry_margin_bps,
+                terms.profit_exit_margin_bps,
+                participating,
+            );
+            
+            // Once in, re-pricing only takes us out below the exit bar
+            if participating {
+                if !clears_bar {
+                    println!("\n📉 Profit on {} fell below the exit bar ({} < threshold {}); withdrawing",
+                        opp_id, profit, min_profit);
+                    if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                        data.participating = false;
+                    }
+                    self.withdraw_intent(&opp_id, "profit below exit bar");
+                }
+                return Ok(None);
+            }
+            // Disabled strategies get no new commitments; intents already out run their course
+            if !strategy_enabled {
+                println!("\n⏸️  Not evaluating {}: aave-liquidation is disabled", opp_id);
+                self.decline_bid(&opp_id, origin, "strategy_enabled");
+                return Ok(None);
+            }
+            
+            let decision = decide_commitment(
+                &self.node_config,
+                &opportunity,
+                origin,
+                Some(strategy),
+                self.free_balance(&opportunity.repay_asset),
+                self.operator_balance(&opportunity.repay_asset),
+                self.capital_commitments.len(),
+                self.current_block(),
+                self.current_gas_price(),
+                manual_amount,
+            );
+            
+            println!("\n🔍 Evaluating opportunity as Capital Provider ({:?} find):", origin);
+            println!("   Available capital: {} USDC", self.free_balance(&opportunity.repay_asset));
+            println!("   Required capital: {} USDC", opportunity.max_repay_amount);
+            
+            if let Some(failed) = decision.checks.iter().find(|check| !check.passed) {
+                println!("   ❌ Not committing: {} failed", failed.rule);
+                let rule = failed.rule.clone();
+                self.decline_bid(&opp_id, origin, &rule);
+                return Ok(None);
+            }
+            // No capital for finds from finders whose record is below the reputation policy; ours always pass
+            let refusal = (finder != "artemis-bot" && finder != our().node)
+                .then(|| self.peer_reputation.get(&finder).cloned().unwrap_or_default())
+                .and_then(|reputation| self.node_config.reputation_policy.evaluation_refusal(&reputation));
+            if let Some(refusal) = refusal {
+                println!("   ❌ Not committing: finder {} {}", finder, refusal);
+                self.decline_bid(&opp_id, origin, "finder_reputation");
+                return Ok(None);
+            }
+            // No new exposure to an executor we've suspended
+            let suspended_executor = self.active_opportunities.get(&opp_id).and_then(|data| {
+                data.claimed_by.iter()
+                    .chain(data.execution_holder())
+                    .find(|executor| self.counterparties.is_suspended(executor))
+                    .cloned()
+            });
+            if let Some(executor) = suspended_executor {
+                println!("   ❌ Not committing: executor {} is a suspended counterparty", executor);
+                self.decline_bid(&opp_id, origin, "suspended_executor");
+                return Ok(None);
+            }
+            // Nor to one that will be down for maintenance when the opportunity is due
+            let away_executor = self.active_opportunities.get(&opp_id).and_then(|data| {
+                let deadline_at = self.deadline_at(data);
+                data.claimed_by.iter()
+                    .chain(data.execution_holder())
+                    .find(|executor| self.in_maintenance_at(executor, deadline_at))
+                    .cloned()
+            });
+            if let Some(executor) = away_executor {
+                println!("   ❌ Not committing: executor {} is in maintenance at the deadline", executor);
+                self.decline_bid(&opp_id, origin, "executor_maintenance");
+                return Ok(None);
+            }
+            let (risk_action, risk_flag) = self.risk_gate(&opp_id);
+            match risk_action {
+                RiskAction::Reject => {
+                    println!("   ❌ Not committing: risk flag {:?} rejects it", risk_flag);
+                    self.decline_bid(&opp_id, origin, "risk_flag");
+                    return Ok(None);
+                }
+                RiskAction::RequireManualApproval => {
+                    println!("   ⏸️  Not committing: risk flag {:?} needs operator approval", risk_flag);
+                    self.decline_bid(&opp_id, origin, "risk_approval");
+                    return Ok(None);
+                }
+                _ => {}
+            }
+            // Our own look at the victim, when enabled; if the query fails we go on the finder's
+            // word, unless this origin class or a risk flag requires independent validation
+            let require_validation = self.node_config.origin_policy(origin).require_independent_validation
+                || risk_action == RiskAction::RequireIndependentValidation;
+            if self.node_config.eth_checks.check_health_factor || require_validation {
+                match self.onchain_health_factor(&opportunity.victim_address) {
+                    Some(health_factor) if health_factor >= U256::from(10u64).pow(U256::from(18u64)) => {
+                        println!("   ❌ Not committing: on-chain health factor {} is not liquidatable (finder claimed {})",
+                            health_factor, opportunity.health_factor);
+                        self.record_journal("HealthFactorMismatch", serde_json::json!({
+                            "opp_id": opp_id,
+                            "claimed": opportunity.health_factor,
+                            "onchain": health_factor.to_string(),
+                            "origin": origin,
+                        }));
+                        self.decline_bid(&opp_id, origin, "onchain_health_factor");
+                        return Ok(None);
+                    }
+                    None if require_validation => {
+                        println!("   ❌ Not committing: {:?} finds need independent validation and the on-chain check is unavailable", origin);
+                        self.decline_bid(&opp_id, origin, "independent_validation");
+                        return Ok(None);
+                    }
+                    _ => {}
+                }
+            }
+            let simulated = decision.simulated;
+            
+            if manual_amount.is_none() && !self.node_config.bid_policy.auto_bid {
+                println!("   ⏸️  Not committing: auto_bid is off; {} is pending review", opp_id);
+                if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                    data.bid_status = Some(BidStatus::PendingReview);
+                }
+                return Ok(None);
+            }
+            
+            // Submit intent
+            let intent = AaveLiquidationIntent {
+                opp_id: opp_id.to_string(),
+                asset: opportunity.repay_asset.clone(),
+                max_amount: decision.amount.clone(),
+                min_bonus_bps: opportunity.min_bonus_bps,
+                expires_block: opportunity.deadline_block,
+                requires_swap: false,
+                simulated,
+                fee_bps: self.node_config.capital_provider_fee_bps,
+            };
+            
+            self.broadcast_intent(intent.clone()).await?;
+            self.set_commitment(&opp_id, &intent.asset, &intent.max_amount);
+            self.origin_stats.entry(origin).or_default().committed += 1;
+            let mut sent_to: Vec<String> = self.reachable_peers().cloned().collect();
+            sent_to.sort();
+            self.performance_metrics.strategy("aave-liquidation").intents_submitted += 1;
+            self.our_intents.insert(opp_id.clone(), OutstandingIntent {
+                intent: intent.clone(),
+                sent_to,
+                submitted_at: Self::current_timestamp(),
+                amendment_rejected: None,
+                config_revision: self.config_revision,
+                revocation_rejected: None,
+            });
+            if let Some(data) = self.active_opportunities.get_mut(&opp_id) {
+                data.participating = true;
+                data.bid_status = Some(BidStatus::Submitted {
+                    amount: intent.max_amount.clone(),
+                    manual: manual_amount.is_some(),
+                });
+            }
+            return Ok(Some(intent));
+        }
+        
+        Ok(None)
+    }
+    
+    #[local]
+    async fn broadcast_intent(&self, intent: AaveLiquidationIntent) -> Result<(), String> {
+        let intent_msg = MevMessage::IntentSubmission {
+            opp_id: intent.opp_id.clone(),
+            strategy_id: "aave-liquidation".to_string(),
+            intent: serde_json::to_string(&intent).map_err(|e| format!("Serialization error: {}", e))?,
+            max_amount: intent.max_amount.clone(),
+            min_bonus_bps: intent.min_bonus_bps,
+            expires_block: intent.expires_block,
+            fee_bps: intent.fee_bps,
+            signature: None,
+        };
+        
+        self.broadcast_message(&intent_msg);
+        
+        Ok(())
+    }
+    
+    #[local]
+    async fn plan_and_execute_opportunities(&mut self) -> Result<(), String> {
+        self.check_executor_succession();
+        
+        // Send available intents to Artemis bot for execution
+        let opp_ids: Vec<String> = self.submitted_intents.keys().cloned().collect();
+        for opp_id in opp_ids {
+            self.dispatch_opportunity(&opp_id);
+        }
+        
+        Ok(())
+    }
+    
+    // The opportunity's declared distribution scheme; the default for ones we no longer hold
+    fn distribution_scheme(&self, opp_id: &str) -> DistributionScheme {
+        self.active_opportunities.get(opp_id)
+            .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok())
+            .map(|opportunity| opportunity.distribution)
+            .unwrap_or_default()
+    }
+    
+    // What an execution earned us: our lines' floored parts of the provider pool (net profit less
+    // the finder and executor fees) by capital used and fee bid, plus the finder fee share and
+    // executor fee (with what fee bids left unclaimed) when those roles were ours, plus the dust
+    // when the scheme sinks it to us. A receipt whose
+    // used_amounts leave us out pays us no provider share.
+    fn calculate_our_proceeds_share(&self, opp_id: &str, executor: &str, receipt_json: &str, proceeds: &ProceedsBreakdown) -> String {
+        let me = our().node;
+        let amount = |value: &str| value.parse::<U256>().unwrap_or(U256::ZERO);
+        let finder_fee = amount(&proceeds.finder_fee);
+        let executor_fee = amount(&proceeds.executor_fee);
+        
+        let lines: Vec<AllocationLine> = serde_json::from_str::<AaveLiquidationReceipt>(receipt_json)
+            .map(|receipt| receipt.used_amounts)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|usage| AllocationLine {
+                intent_hash: String::new(),
+                provider: usage.node_id,
+                amount: usage.amount_used,
+                fee_bps: usage.fee_bps,
+            })
+            .collect();
+        let pool = amount(&proceeds.net_profit).saturating_sub(finder_fee).saturating_sub(executor_fee);
+        let scheme = self.distribution_scheme(opp_id);
+        let (shares, dust, unclaimed) = distribute_pool(pool.to_string().parse::<u128>().unwrap_or(0), &lines, &scheme)
+            .unwrap_or_default();
+        let mut share = lines.iter().zip(shares)
+            .filter(|(line, _)| line.provider == me)
+            .fold(U256::ZERO, |sum, (_, share)| sum + U256::from(share));
+        let finder = self.active_opportunities.get(opp_id)
+            .map(|data| if data.finder_node == "artemis-bot" { me.clone() } else { data.finder_node.clone() });
+        let dust_is_ours = match &scheme.dust_sink {
+            DustSink::FirstProvider => false,
+            DustSink::Executor => executor == me,
+            DustSink::Finder => finder.as_deref() == Some(me.as_str()),
+            DustSink::Treasury(node) => *node == me,
+        };
+        if dust_is_ours {
+            share += U256::from(dust);
+        }
+        
+        // The executor's declared recipients decide the finder fee; older executors declare none,
+        // in which case our own record of the claims does
+        let recipients = if proceeds.finder_fee_recipients.is_empty() {
+            self.finder_fee_split(opp_id).1
+        } else {
+            proceeds.finder_fee_recipients.clone()
+        };
+        if let Some(ours) = recipients.iter().find(|recipient| recipient.finder == me) {
+            share += finder_fee * U256::from(ours.share_bps) / U256::from(10_000u64);
+        }
+        if executor == me {
+            share += executor_fee + U256::from(unclaimed);
+        }
+        share.to_string()
+    }
+    
+    // The breakdown we declare on receipts we broadcast: gas off the top, then our executor fee and
+    // the finder fee in bps of the rest, split over the capital the bridge actually used
+    fn proceeds_breakdown(&self, receipt: &AaveLiquidationReceipt) -> ProceedsBreakdown {
+        let (finder_fee_policy, finder_fee_recipients) = self.finder_fee_split(&receipt.opp_id);
+        let finder_fee_bps = self.active_opportunities.get(&receipt.opp_id)
+            .and_then(|data| best_claim(&data.claiming_finders))
+            .map_or(0, |claim| claim.finder_fee_bps);
+        let executor_fee_bps = self.config_for(&receipt.opp_id).executor_fee_bps;
+        let lines: Vec<AllocationLine> = receipt.used_amounts.iter()
+            .map(|usage| AllocationLine {
+                intent_hash: String::new(),
+                provider: usage.node_id.clone(),
+                amount: usage.amount_used.clone(),
+                fee_bps: usage.fee_bps,
+            })
+            .collect();
+        let scheme = self.distribution_scheme(&receipt.opp_id);
+        let split = split_proceeds(&receipt.total_proceeds, &receipt.gas_paid_usdc, finder_fee_bps, executor_fee_bps, &lines, &scheme);
+        let (finder_fee, executor_fee, net_profit, dust) = match split {
+            Ok(split) => {
+                let after_gas = receipt.total_proceeds.parse::<U256>().unwrap_or(U256::ZERO)
+                    .saturating_sub(receipt.gas_paid_usdc.parse::<U256>().unwrap_or(U256::ZERO));
+                (split.finder_fee, split.executor_fee, after_gas.to_string(), split.dust)
+            }
+            Err(e) => {
+                println!("   Cannot split proceeds for {}: {}; declaring no fees", receipt.opp_id, e);
+                ("0".to_string(), "0".to_string(), receipt.total_proceeds.clone(), "0".to_string())
+            }
+        };
+        let legs = receipt.seized.iter()
+            .filter_map(|leg| match split_proceeds(&leg.amount, "0", finder_fee_bps, executor_fee_bps, &lines, &scheme) {
+                Ok(split) => Some(LegProceeds {
+                    asset: leg.asset.clone(),
+                    seized: leg.amount.clone(),
+                    finder_fee: split.finder_fee,
+                    executor_fee: split.executor_fee,
+                    provider_pool: split.provider_pool,
+                    dust: split.dust,
+                }),
+                Err(e) => {
+                    println!("   Cannot split the {} leg of {}: {}", leg.asset, receipt.opp_id, e);
+                    None
+                }
+            })
+            .collect();
+        ProceedsBreakdown {
+            asset: "ETH".to_string(),
+            total: receipt.total_proceeds.clone(),
+            gas_cost_usd: receipt.gas_paid_usdc.clone(),
+            finder_fee,
+            executor_fee,
+            net_profit,
+            finder_fee_policy,
+            finder_fee_recipients,
+            legs,
+            dust,
+            dust_sink: scheme.dust_sink,
+        }
+    }
+    
+    // Who our execution pays and how much, by the same arithmetic each node runs on the receipt
+    // for itself: provider lines in used_amounts order, finder fee shares by declared recipient,
+    // our executor fee with what fee bids left unclaimed, and dust where the scheme sinks it
+    fn proceeds_distribution(&self, receipt: &AaveLiquidationReceipt, proceeds: &ProceedsBreakdown) -> ProceedsDistribution {
+        let me = our().node;
+        let amount = |value: &str| value.parse::<U256>().unwrap_or(U256::ZERO);
+        let entry = |node_id: String, value: U256, role: DistributionRole| DistributionEntry {
+            node_id,
+            asset: proceeds.asset.clone(),
+            amount: value.to_string(),
+            role,
+            amount_used: None,
+            fee_bps: None,
+        };
+        let finder_fee = amount(&proceeds.finder_fee);
+        let executor_fee = amount(&proceeds.executor_fee);
+        let lines: Vec<AllocationLine> = receipt.used_amounts.iter()
+            .map(|usage| AllocationLine {
+                intent_hash: String::new(),
+                provider: usage.node_id.clone(),
+                amount: usage.amount_used.clone(),
+                fee_bps: usage.fee_bps,
+            })
+            .collect();
+        let pool = amount(&proceeds.net_profit).saturating_sub(finder_fee).saturating_sub(executor_fee);
+        let scheme = self.distribution_scheme(&receipt.opp_id);
+        let (shares, dust, unclaimed) = distribute_pool(pool.to_string().parse::<u128>().unwrap_or(0), &lines, &scheme)
+            .unwrap_or_default();
+        
+        let mut entries: Vec<DistributionEntry> = lines.iter().zip(shares)
+            .map(|(line, share)| DistributionEntry {
+                amount_used: Some(line.amount.clone()),
+                fee_bps: Some(line.fee_bps),
+                ..entry(line.provider.clone(), U256::from(share), DistributionRole::Provider)
+            })
+            .collect();
+        let recipients = if proceeds.finder_fee_recipients.is_empty() {
+            self.finder_fee_split(&receipt.opp_id).1
+        } else {
+            proceeds.finder_fee_recipients.clone()
+        };
+        entries.extend(recipients.iter().map(|recipient| entry(
+            recipient.finder.clone(),
+            finder_fee * U256::from(recipient.share_bps) / U256::from(10_000u64),
+            DistributionRole::Finder,
+        )));
+        entries.push(entry(me.clone(), executor_fee + U256::from(unclaimed), DistributionRole::Executor));
+        let finder = self.active_opportunities.get(&receipt.opp_id)
+            .map(|data| if data.finder_node == "artemis-bot" { me.clone() } else { data.finder_node.clone() });
+        let sink = match &scheme.dust_sink {
+            DustSink::FirstProvider => None,
+            DustSink::Executor => Some(me.clone()),
+            DustSink::Finder => finder,
+            DustSink::Treasury(node) => Some(node.clone()),
+        };
+        if let Some(sink) = sink.filter(|_| dust > 0) {
+            entries.push(entry(sink, U256::from(dust), DistributionRole::DustSink));
+        }
+        
+        ProceedsDistribution {
+            opp_id: receipt.opp_id.clone(),
+            executor: me,
+            entries,
+        }
+    }
+    
+    
+    #[local]
+    async fn send_to_artemis_json(&self, json_message: String) -> Result<(), String> {
+        self.send_to_artemis_json_sync(&json_message)
+    }
+
+
+
+
+    #[local]
+    // Pulls back one of our own finds, asked by the operator or by our bridge when the position
+    // turned healthy: retires it here, releasing any commitment, and sends a signed cancellation
+    // to every peer our broadcast went to and to its executor. An execution already claimed or
+    // with our bridge is reported, and told all the same.
+    fn cancel_own_opportunity(&mut self, opp_id: &str, reason: &str) -> Result<serde_json::Value, String> {
+        if reason.trim().is_empty() {
+            return Err("A cancellation needs a reason".to_string());
+        }
+        let opp_id = self.canonical_opp_id(opp_id);
+        let data = self.active_opportunities.get(&opp_id).ok_or("Opportunity not found")?;
+        if data.finder_node != "artemis-bot" {
+            return Err(format!("{} was found by {}; only our own finds can be cancelled", opp_id, data.finder_node));
+        }
+        let claimed_by = data.claimed_by.clone();
+        let holder = data.execution_holder().cloned();
+        let mut recipients = data.broadcast_to.clone();
+        recipients.extend(claimed_by.iter().chain(holder.iter()).cloned());
+        recipients.remove(&our().node);
+        let dispatched = self.pending_executions.contains_key(&opp_id);
+        
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let cancellation = serde_json::json!({
+            "opp_id": opp_id,
+            "finder": our().node,
+            "reason": reason,
+            "cancelled_at": now,
+        }).to_string();
+        let signature = match Self::sign_bytes_now(cancellation.as_bytes()) {
+            Ok(signature) => Some(hex_encode(&signature)),
+            Err(e) => {
+                println!("   Cancellation of {} goes out unsigned: {}", opp_id, e);
+                None
+            }
+        };
+        // Our bridge already has it: the receipt still has to land here to settle it
+        if !dispatched {
+            self.retire_opportunity(&opp_id, RetirementOutcome::expired("CancelledByFinder", None, false));
+        }
+        let message = MevMessage::OpportunityCancellation { cancellation, signature };
+        for peer in &recipients {
+            self.send_to_peer(peer, message.handler(), &Self::message_json(&message));
+        }
+        
+        println!("\n🛑 Cancelled our opportunity {} ({}); told {} peer(s)", opp_id, reason, recipients.len());
+        let outcome = serde_json::json!({
+            "opp_id": opp_id,
+            "reason": reason,
+            "retired": !dispatched,
+            "dispatched_by_us": dispatched,
+            "claimed_by": claimed_by,
+            "execution_holder": holder,
+            "notified": recipients,
+            "signed": signature.is_some(),
+        });
+        self.record_journal("OpportunityCancelled", outcome.clone());
+        Ok(outcome)
+    }
+    
+    // A peer may only send what the roles it announced cover (`None`: any announced peer). A
+    // sender we have no announcement from yet is asked for one and told to retry once announced;
+    // role changes take effect with the peer's next announcement.
+    fn check_peer_role(&mut self, peer: &str, role: Option<NodeRole>, kind: &str) -> Result<(), String> {
+        // Announced peers are judged by their roles without asking the runtime who we are
+        if !self.known_peers.get(peer).is_some_and(|info| info.node_info.is_some()) && peer == our().node {
+            return Ok(());
+        }
+        let roles = self.known_peers.get(peer)
+            .and_then(|info| info.node_info.as_ref())
+            .map(|info| info.roles.clone());
+        match roles {
+            Some(roles) if role.map_or(true, |role| roles.contains(&role)) => Ok(()),
+            Some(roles) => {
+                println!("\n🎭 P2P: Refused {} from {}: announced roles {:?} lack {:?}", kind, peer, roles, role);
+                Err(serde_json::json!({
+                    "error": "RoleNotAnnounced",
+                    "kind": kind,
+                    "peer": peer,
+                    "required_role": role,
+                    "announced_roles": roles,
+                    "reason": "Enable the role and re-announce before sending this",
+                }).to_string())
+            }
+            None => {
+                self.request_announcement(peer);
+                Err(serde_json::json!({
+                    "error": "AnnouncementRequired",
+                    "kind": kind,
+                    "peer": peer,
+                    "required_role": role,
+                    "reason": "We have no announcement from you; we asked for one, resend once announced",
+                }).to_string())
+            }
+        }
+    }
+    
+    // Our announcement draws the sender's back; sent at most once per interval per sender
+    fn request_announcement(&mut self, peer: &str) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if self.announcement_requests.get(peer).is_some_and(|asked_at| now < asked_at + ANNOUNCEMENT_REQUEST_INTERVAL_SECS) {
+            return;
+        }
+        self.announcement_requests.insert(peer.to_string(), now);
+        println!("   📣 Asking {} to announce itself", peer);
+        if let Err(e) = self.send_announcement(peer.to_string(), false) {
+            println!("   Could not ask {} for its announcement: {}", peer, e);
+        }
+    }
+    
+    fn get_enabled_roles(&self) -> Vec<NodeRole> {
+        let mut roles = Vec::new();
+        if self.node_config.finder_enabled {
+            roles.push(NodeRole::Finder);
+        }
+        if self.node_config.capital_provider_enabled {
+            roles.push(NodeRole::CapitalProvider);
+        }
+        if self.node_config.executor_enabled {
+            roles.push(NodeRole::Executor);
+        }
+        roles
+    }
+    
+    // Another turn for bridge frames the last one left over; requested by drain_artemis_inbound
+    #[local]
+    async fn continue_artemis_inbound(&mut self) -> Result<(), String> {
+        if source().node != our().node || source().process != our().process {
+            return Err("Only this process may continue its own inbound queue".to_string());
+        }
+        self.artemis_inbound.continuation_pending = false;
+        self.drain_artemis_inbound();
+        Ok(())
+    }
+    
+    // TYPED LOCAL API
+    // For other processes on this node; each handler checks the caller's grant in local_api_grants
+    
+    // Opportunities we track, newest first
+    #[local]
+    async fn local_query_opportunities(&self, filter: LocalOpportunityFilter) -> Result<Vec<LocalOpportunity>, String> {
+        self.check_local_grant(LocalScope::Query)?;
+        let mut matching: Vec<(&String, &OpportunityData)> = self.active_opportunities.iter()
+            .filter(|(_, data)| filter.strategy_id.as_ref().map_or(true, |strategy| *strategy == data.strategy_id))
+            .filter(|(_, data)| filter.status.map_or(true, |status| status == data.status))
+            .filter(|(_, data)| filter.participating.map_or(true, |participating| participating == data.participating))
+            .collect();
+        matching.sort_by(|a, b| b.1.received_at.cmp(&a.1.received_at).then_with(|| a.0.cmp(b.0)));
+        matching.truncate(filter.limit.map_or(usize::MAX, |limit| limit as usize));
+        Ok(matching.into_iter()
+            .map(|(opp_id, data)| LocalOpportunity {
+                opp_id: opp_id.clone(),
+                strategy_id: data.strategy_id.clone(),
+                status: data.status,
+                finder: data.finder_node.clone(),
+                participating: data.participating,
+                opportunity: serde_json::from_str(&data.opportunity).ok(),
+            })
+            .collect())
+    }
+    
+    // Our balances, what our intents lock and what we have earned
+    #[local]
+    async fn local_query_capital(&self) -> Result<LocalCapital, String> {
+        self.check_local_grant(LocalScope::Query)?;
+        let amounts = |balances: &HashMap<String, String>| -> Vec<AssetAmount> {
+            let mut amounts: Vec<AssetAmount> = balances.iter()
+                .map(|(asset, amount)| AssetAmount { asset: asset.clone(), amount: amount.clone() })
+                .collect();
+            amounts.sort_by(|a, b| a.asset.cmp(&b.asset));
+            amounts
+        };
+        let free = amounts(&self.available_balances).into_iter()
+            .map(|balance| AssetAmount { amount: self.free_balance(&balance.asset).to_string(), asset: balance.asset })
+            .collect();
+        let mut commitments: Vec<LocalCommitment> = self.capital_commitments.iter()
+            .map(|(opp_id, commitment)| LocalCommitment {
+                opp_id: opp_id.clone(),
+                asset: commitment.asset.clone(),
+                amount: commitment.amount.clone(),
+            })
+            .collect();
+        commitments.sort_by(|a, b| a.opp_id.cmp(&b.opp_id));
+        Ok(LocalCapital {
+            balances: amounts(&self.available_balances),
+            committed: amounts(&self.committed_amounts),
+            free,
+            earned_proceeds: amounts(&self.earned_proceeds),
+            commitments,
+        })
+    }
+    
+    // Net position with every counterparty that has statement lines in [from, to)
+    #[local]
+    async fn local_query_settlements(&self, from: u64, to: u64) -> Result<Vec<LocalSettlementBalance>, String> {
+        self.check_local_grant(LocalScope::Query)?;
+        if from >= to {
+            return Err("Statement period is empty".to_string());
+        }
+        let me = our().node;
+        let peers: BTreeSet<&String> = self.counterparties.records.keys().chain(self.known_peers.keys()).collect();
+        Ok(peers.into_iter()
+            .map(|peer| (peer, self.build_statement(peer, from, to)))
+            .filter(|(_, statement)| !statement.lines.is_empty())
+            .map(|(peer, statement)| LocalSettlementBalance {
+                peer: peer.clone(),
+                counterparty_state: self.counterparties.state(peer),
+                owed_to_us: statement.owed_to.get(&me).cloned().unwrap_or_else(|| "0".to_string()),
+                owed_by_us: statement.owed_to.get(peer).cloned().unwrap_or_else(|| "0".to_string()),
+                lines: statement.lines,
+            })
+            .collect())
+    }
+    
+    // Registers the calling process for PoolEvents of `kinds` (all when empty), delivered as
+    // local requests. Registering again replaces the earlier subscription.
+    #[local]
+    async fn local_subscribe(&mut self, kinds: Vec<PoolEventKind>) -> Result<String, String> {
+        self.check_local_grant(LocalScope::Subscribe)?;
+        let address = source().to_string();
+        self.local_subscribers.register(address.clone(), kinds.clone(), Self::current_timestamp())?;
+        self.record_journal("LocalSubscribed", serde_json::json!({
+            "address": address,
+            "kinds": kinds,
+        }));
+        Ok(address)
+    }
+    
+    #[local]
+    async fn local_unsubscribe(&mut self) -> Result<(), String> {
+        let address = source().to_string();
+        if self.local_subscribers.subscribers.remove(&address).is_some() {
+            self.record_journal("LocalUnsubscribed", serde_json::json!({
+                "address": address,
+                "reason": "Requested",
+            }));
+        }
+        Ok(())
+    }
+    
+}
+
+impl HyperMevApp {
+    // Helper to get current timestamp
+    fn current_timestamp() -> String {
+        format!("{}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs())
+    }
+    
+    fn current_timestamp_ms() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+    
+    // Periodic housekeeping driven by the loop started in initialize
+    fn run_maintenance(&mut self) {
+        self.revert_expired_config_trial();
+        self.end_recording_past_deadline();
+        // A continuation turn that never arrived must not hold up the rest of the queue
+        self.artemis_inbound.continuation_pending = false;
+        self.drain_artemis_inbound();
+        
+        for opp_id in self.check_executor_succession() {
+            self.dispatch_opportunity(&opp_id);
+        }
+        
+        // Give executions waiting on more capital providers, or on gas coming back under our cap,
+        // a chance to dispatch or expire
+        let waiting: Vec<String> = self.active_opportunities.iter()
+            .filter(|(_, data)| data.provider_shortfall.is_some() || data.gas_deferral.is_some())
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        for opp_id in waiting {
+            self.dispatch_opportunity(&opp_id);
+        }
+        
+        let assets: HashSet<String> = self.our_intents.values()
+            .map(|outstanding| outstanding.intent.asset.clone())
+            .collect();
+        for asset in assets {
+            self.amend_intents_to_balance(&asset);
+        }
+        
+        self.expire_stale_opportunities();
+        self.advance_validation_block();
+        self.revalidate_participations();
+        self.expire_bridge_sync();
+        self.expire_reservations();
+        self.prune_expired_intents();
+        self.resolve_stale_pending_executions();
+        self.repair_skipped_sends();
+        self.drain_peer_queues();
+        self.probe_local_subscribers();
+        self.heartbeat_peers();
+        self.clear_ended_maintenance_windows();
+        self.prune_stale_artemis_channels();
+        self.reconcile_balances();
+        self.prune_origination_index();
+        self.prune_duplicate_index();
+        self.prune_risk_probes();
+        self.prune_rate_limiters();
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.announcement_requests.retain(|_, asked_at| now < *asked_at + ANNOUNCEMENT_REQUEST_INTERVAL_SECS);
+        self.seen_broadcasts.prune(now, self.node_config.seen_broadcast_ttl_secs);
+        self.reverify_restored_records();
+        self.verify_pending_receipts();
+        self.confirm_receipts();
+        self.refresh_monitoring_coverage();
+        self.archive_receipts();
+        self.enforce_collection_caps();
+        self.close_settlement_epoch();
+    }
+    
+    // Takes on every bootstrap peer we don't know yet, pending until it announces back
+    fn connect_bootstrap_peers(&mut self) {
+        let our_node = our().node;
+        let new: Vec<String> = self.node_config.bootstrap_peers.iter()
+            .filter(|peer| **peer != our_node && !self.known_peers.contains_key(*peer))
+            .filter(|peer| self.node_config.peer_blocked(peer).is_none())
+            .cloned()
+            .collect();
+        for peer in new {
+            println!("🔗 Connecting to bootstrap peer {}", peer);
+            self.known_peers.insert(peer.clone(), PeerInfo::pending(Self::current_timestamp()));
+            if let Err(e) = self.send_announcement(peer.clone(), false) {
+                println!("   Announcement to bootstrap peer {} failed: {}", peer, e);
+            }
+        }
+    }
+    
+    // After a restart, peers still hold whatever roles and assets we last announced. Announces
+    // afresh to every saved peer and bootstrap peer, a short random pause apart; a peer we can't
+    // address is marked unreachable and left to the heartbeat to evict or recover.
+    async fn reannounce_on_startup(&mut self) {
+        let our_node = our().node;
+        let now = Self::current_timestamp();
+        for peer in self.node_config.bootstrap_peers.clone() {
+            if peer != our_node && self.node_config.peer_blocked(&peer).is_none() {
+                self.known_peers.entry(peer).or_insert_with(|| PeerInfo::pending(now.clone()));
+            }
+        }
+        let mut peers: Vec<String> = self.known_peers.keys().cloned().collect();
+        peers.sort();
+        if !peers.is_empty() {
+            println!("Re-announcing to {} peers", peers.len());
+        }
+        for peer in peers {
+            let _ = hyperware_app_common::sleep(Self::current_timestamp_ms() % STARTUP_ANNOUNCE_JITTER_MS).await;
+            let Err(e) = self.send_announcement(peer.clone(), false) else {
+                continue;
+            };
+            println!("   Startup announcement to {} failed: {}", peer, e);
+            let Some(info) = self.known_peers.get_mut(&peer) else {
+                continue;
+            };
+            if info.unreachable_since.is_none() {
+                info.unreachable_since = Some(Self::current_timestamp());
+                self.record_journal("PeerUnreachable", serde_json::json!({ "peer": peer, "reason": e }));
+            }
+        }
+    }
+    
+    // Tells every known peer our current maintenance window (or that there is none). Sent as a
+    // reply announcement, so peers take it in without announcing back.
+    fn announce_maintenance(&self) {
+        let peers: Vec<String> = self.known_peers.keys().cloned().collect();
+        for peer in peers {
+            if let Err(e) = self.send_announcement(peer.clone(), true) {
+                println!("   Maintenance notice to {} not sent: {}", peer, e);
+            }
+        }
+    }
+    
+    // Windows clear themselves once over, ours and those peers announced
+    fn clear_ended_maintenance_windows(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if let Some(window) = self.maintenance_window.filter(|window| window.end() <= now) {
+            self.maintenance_window = None;
+            self.record_journal("MaintenanceEnded", serde_json::json!({
+                "start": window.start,
+                "duration_secs": window.duration_secs,
+            }));
+            println!("\n🛠️  Maintenance window over");
+        }
+        for info in self.known_peers.values_mut() {
+            if info.maintenance.is_some_and(|window| window.end() <= now) {
+                info.maintenance = None;
+            }
+        }
+    }
+    
+    // Whether `node` (us included) announced downtime covering `at`
+    fn in_maintenance_at(&self, node: &str, at: u64) -> bool {
+        let window = if node == our().node {
+            self.maintenance_window
+        } else {
+            self.known_peers.get(node).and_then(|info| info.maintenance)
+        };
+        window.is_some_and(|window| window.covers(at))
+    }
+    
+    // Expected unix time of an opportunity's deadline: from the chain head when known, otherwise
+    // opportunity_ttl_secs after we received it
+    fn deadline_at(&self, data: &OpportunityData) -> u64 {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let deadline_block = serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+            .map(|opportunity| opportunity.deadline_block)
+            .ok();
+        match (self.chain_head.map(|head| head.estimate(now)), deadline_block) {
+            (Some(head), Some(deadline_block)) => now + deadline_block.saturating_sub(head) * SECONDS_PER_BLOCK,
+            _ => data.received_at + self.node_config.opportunity_ttl_secs,
+        }
+    }
+    
+    // One heartbeat round per maintenance tick: counts a miss for every peer not heard from since
+    // the last round, marks peers past heartbeat_miss_limit unreachable, evicts those unreachable
+    // beyond the grace period, then pings everyone still known (unreachable peers included, so
+    // they can come back)
+    fn heartbeat_peers(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let miss_limit = self.node_config.heartbeat_miss_limit;
+        let grace = self.node_config.unreachable_peer_grace_secs;
+        
+        if let Some(previous_round) = self.last_heartbeat_at {
+            let mut unreachable = Vec::new();
+            let mut evicted = Vec::new();
+            for (peer, info) in self.known_peers.iter_mut() {
+                let heard_at = info.last_seen.as_deref().unwrap_or(&info.added_at).parse::<u64>().unwrap_or(0);
+                if heard_at >= previous_round {
+                    continue;
+                }
+                info.missed_heartbeats += 1;
+                match info.unreachable_since.as_deref().map(|since| since.parse::<u64>().unwrap_or(now)) {
+                    None if info.missed_heartbeats >= miss_limit => {
+                        info.unreachable_since = Some(now.to_string());
+                        unreachable.push(peer.clone());
+                    }
+                    Some(since) if now.saturating_sub(since) >= grace => evicted.push(peer.clone()),
+                    _ => {}
+                }
+            }
+            for peer in unreachable {
+                println!("\n💔 Peer {} missed {} heartbeats; marking unreachable", peer, miss_limit);
+                self.record_journal("PeerUnreachable", serde_json::json!({ "peer": peer }));
+            }
+            for peer in evicted {
+                println!("\n🗑️  Evicting peer {}: unreachable past the grace period", peer);
+                self.known_peers.remove(&peer);
+                self.record_journal("PeerEvicted", serde_json::json!({ "peer": peer }));
+            }
+        }
+        
+        let ping = MevMessage::Ping { sent_at: now.to_string(), reply: false };
+        let peers: Vec<String> = self.known_peers.keys().cloned().collect();
+        for peer in &peers {
+            self.send_to_peer(peer, ping.handler(), &Self::message_json(&ping));
+        }
+        self.last_heartbeat_at = Some(now);
+    }
+    
+    fn observe_block(&mut self, block: u64) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if self.chain_head.map_or(true, |head| block >= head.estimate(now)) {
+            let base_fee_wei = self.chain_head.and_then(|head| head.base_fee_wei);
+            self.chain_head = Some(ChainHeadEstimate { block, observed_at: now, block_timestamp: None, base_fee_wei });
+            self.advance_validation_block();
+        }
+    }
+    
+    // A BlockUpdate is the bridge's actual head, so it replaces the estimate even where the
+    // extrapolation had run ahead of a slower chain; only an older report than the last is ignored
+    fn observe_block_update(&mut self, number: u64, timestamp: u64, base_fee_wei: Option<u128>) {
+        if self.chain_head.is_some_and(|head| number < head.block) {
+            return;
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let base_fee_wei = base_fee_wei.or_else(|| self.chain_head.and_then(|head| head.base_fee_wei));
+        self.chain_head = Some(ChainHeadEstimate { block: number, observed_at: now, block_timestamp: Some(timestamp), base_fee_wei });
+        self.advance_validation_block();
+    }
+    
+    fn current_gas_price(&self) -> Option<U256> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.chain_head.and_then(|head| head.gas_price_wei(now))
+    }
+    
+    // Best estimate of the chain head now, if the bridge ever reported one
+    fn current_block(&self) -> Option<u64> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.chain_head.map(|head| head.estimate(now))
+    }
+    
+    // Moves the validation broker to the current head; pre-dispatch checks whose request was
+    // dropped retry at the new block
+    fn advance_validation_block(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let Some(head) = self.chain_head.map(|head| head.estimate(now)) else {
+            return;
+        };
+        let (promoted, dropped) = self.validation_broker.on_block(head, self.node_config.max_outstanding_validations);
+        for key in promoted {
+            self.send_validation_request(&key);
+        }
+        for waiter in dropped {
+            if waiter.priority == ValidationPriority::PreDispatch {
+                self.dispatch_opportunity(&waiter.opp_id);
+            }
+        }
+    }
+    
+    // Bridge re-check of a victim at the current head, through the broker. Returns the answer when
+    // this block's is already known; otherwise `opp_id` is resumed by resume_validation_waiters.
+    fn request_validation(&mut self, opp_id: &str, victim: &str, priority: ValidationPriority) -> Option<ValidationResult> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let block = self.chain_head.map_or(0, |head| head.estimate(now));
+        let waiter = ValidationWaiter { opp_id: opp_id.to_string(), priority };
+        match self.validation_broker.request(ValidationKey::new(victim, block), waiter, self.node_config.max_outstanding_validations) {
+            Admission::Cached(result) => Some(result),
+            Admission::Send(key) => {
+                self.send_validation_request(&key);
+                None
+            }
+            Admission::Coalesced | Admission::Queued => None,
+        }
+    }
+    
+    fn send_validation_request(&self, key: &ValidationKey) {
+        let request = serde_json::json!({
+            "type": "ValidateOpportunity",
+            "victim": key.victim,
+            "block": key.block,
+        });
+        if let Err(e) = self.send_to_artemis_json_sync(&request.to_string()) {
+            println!("   Validation request for {} not sent: {}", key.victim, e);
+        }
+    }
+    
+    // Acts on a bridge validation for everyone who was waiting on it
+    fn resume_validation_waiters(&mut self, result: &ValidationResult, waiters: Vec<ValidationWaiter>) {
+        if let Some(error) = &result.error {
+            println!("   Bridge could not validate {}: {}", result.victim, error);
+        }
+        for waiter in waiters {
+            match waiter.priority {
+                ValidationPriority::PreDispatch => self.dispatch_opportunity(&waiter.opp_id),
+                ValidationPriority::Repricing if result.error.is_none() && !result.liquidatable => {
+                    self.withdraw_intents_for_victim(&result.victim, "no longer liquidatable per the bridge");
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    // Raises the risk flags on an opportunity from what we know now, noting changes on its audit
+    // trail. The bridge is asked for the lookups behind the probed flags only when one of them is
+    // configured to do something.
+    fn assess_risk(&mut self, opp_id: &str) {
+        let Some(data) = self.active_opportunities.get(opp_id) else {
+            return;
+        };
+        let Ok(opportunity) = serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity) else {
+            return;
+        };
+        // The broadcast being assessed is already counted
+        let finder_history = self.finder_sequences.get(&data.finder_node)
+            .map_or(0, |stats| stats.by_origin.values().sum::<u64>().saturating_sub(1));
+        let victim = opportunity.victim_address.to_lowercase();
+        let probe = self.risk_probes.get(&victim);
+        let flags = risk::compute_risk_flags(&RiskContext {
+            opportunity: &opportunity,
+            local_find: data.finder_node == "artemis-bot",
+            finder_history,
+            probe,
+        });
+        let probed = probe.is_some();
+        let wants_probe = !probed && PROBED_FLAGS.iter()
+            .any(|flag| self.node_config.risk_flag_actions.get(flag).is_some_and(|action| *action != RiskAction::Ignore));
+        
+        if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+            if data.risk_flags != flags {
+                println!("   ⚑ Risk flags on {}: {:?}", opp_id, flags);
+                data.audit_trail.push(JournalEntry {
+                    timestamp: Self::current_timestamp(),
+                    kind: "RiskFlags".to_string(),
+                    detail: serde_json::json!({
+                        "flags": flags,
+                        "probed": probed,
+                    }),
+                });
+                data.risk_flags = flags;
+            }
+        }
+        
+        if wants_probe && self.artemis_channels.is_connected() && self.risk_probes_pending.insert(victim.clone()) {
+            let assets: Vec<String> = std::iter::once(opportunity.repay_asset.clone())
+                .chain(opportunity.seize_legs().into_iter().map(|leg| leg.asset))
+                .collect();
+            let request = serde_json::json!({
+                "type": "ProbeRisk",
+                "victim": victim,
+                "assets": assets,
+            });
+            if let Err(e) = self.send_to_artemis_json_sync(&request.to_string()) {
+                println!("   Risk probe for {} not sent: {}", victim, e);
+                self.risk_probes_pending.remove(&victim);
+            }
+        }
+    }
+    
+    // The strictest action the opportunity's risk flags call for, and the flag behind it
+    fn risk_gate(&self, opp_id: &str) -> (RiskAction, Option<RiskFlag>) {
+        self.active_opportunities.get(opp_id).map_or((RiskAction::Ignore, None), |data| {
+            risk::strictest_action(&data.risk_flags, &self.node_config.risk_flag_actions, data.risk_approved)
+        })
+    }
+    
+    // Re-flags a victim's opportunities once the bridge's probe is in. Evaluation doesn't wait for
+    // the probe, so an intent already out on a now-blocked opportunity is withdrawn.
+    fn reassess_victim_risk(&mut self, victim: &str) {
+        let opp_ids: Vec<String> = self.active_opportunities.iter()
+            .filter(|(_, data)| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                .map_or(false, |opportunity| opportunity.victim_address.eq_ignore_ascii_case(victim)))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        let mut blocked = None;
+        for opp_id in opp_ids {
+            self.assess_risk(&opp_id);
+            if let (RiskAction::Reject | RiskAction::RequireManualApproval, Some(flag)) = self.risk_gate(&opp_id) {
+                blocked = Some(flag);
+            }
+        }
+        if let Some(flag) = blocked {
+            self.withdraw_intents_for_victim(victim, &format!("risk flag {:?}", flag));
+        }
+    }
+    
+    // Once per block, asks the bridge whether victims we hold intents on are still liquidatable
+    fn revalidate_participations(&mut self) {
+        if !self.node_config.bridge_validation || !self.artemis_channels.is_connected() || self.chain_head.is_none() {
+            return;
+        }
+        let participations: Vec<(String, String)> = self.our_intents.iter()
+            .filter(|(opp_id, outstanding)| outstanding.intent.max_amount != "0" && !self.pending_executions.contains_key(*opp_id))
+            .filter_map(|(opp_id, _)| self.active_opportunities.get(opp_id)
+                .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok())
+                .map(|opportunity| (opp_id.clone(), opportunity.victim_address)))
+            .collect();
+        for (opp_id, victim) in participations {
+            if let Some(result) = self.request_validation(&opp_id, &victim, ValidationPriority::Repricing) {
+                if result.error.is_none() && !result.liquidatable {
+                    self.withdraw_intents_for_victim(&victim, "no longer liquidatable per the bridge");
+                }
+            }
+        }
+    }
+    
+    // Moves an opportunity to `status`, noting the change on its audit trail
+    fn set_opportunity_status(&mut self, opp_id: &str, status: OpportunityStatus) {
+        let Some(data) = self.active_opportunities.get_mut(opp_id) else {
+            return;
+        };
+        if data.status == status {
+            return;
+        }
+        data.audit_trail.push(JournalEntry {
+            timestamp: Self::current_timestamp(),
+            kind: "StatusChanged".to_string(),
+            detail: serde_json::json!({
+                "from": data.status,
+                "to": status,
+            }),
+        });
+        data.status = status;
+        let opportunity = serde_json::from_str(&data.opportunity).ok();
+        self.collection_recency.touch(opp_id);
+        self.notify_local(PoolEvent::OpportunityStatusChanged { opp_id: opp_id.to_string(), status, opportunity });
+    }
+    
+    // Approves or suspends a counterparty from a `{peer, reason}` request, journaling the move
+    fn move_counterparty(&mut self, request_body: &str, to: CounterpartyState) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct CounterpartyChange {
+            peer: String,
+            reason: String,
+        }
+        
+        let change: CounterpartyChange = serde_json::from_str(request_body)
+            .map_err(|e| format!("Invalid counterparty change: {}", e))?;
+        if change.reason.trim().is_empty() {
+            return Err("A reason is required".to_string());
+        }
+        let now = Self::current_timestamp();
+        let from = match to {
+            CounterpartyState::Suspended => self.counterparties.suspend(&change.peer, change.reason.clone(), now)?,
+            _ => self.counterparties.approve(&change.peer, change.reason.clone(), now)?,
+        };
+        self.record_journal("CounterpartyStateChanged", serde_json::json!({
+            "peer": change.peer,
+            "from": from,
+            "to": to,
+            "reason": change.reason,
+        }));
+        Ok(format!("{} moved from {:?} to {:?}", change.peer, from, to))
+    }
+    
+    fn count_origin_skip(&mut self, origin: OriginClass, rule: &str) {
+        self.record_frame(Boundary::Decision, None, "CommitmentSkipped", serde_json::json!({ "origin": origin, "rule": rule }));
+        *self.origin_stats.entry(origin).or_default().skipped.entry(rule.to_string()).or_insert(0) += 1;
+    }
+    
+    // A declined evaluation, counted for its origin class and kept on the opportunity so
+    // get_opportunities can say why we didn't bid
+    fn decline_bid(&mut self, opp_id: &str, origin: OriginClass, rule: &str) {
+        self.count_origin_skip(origin, rule);
+        if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+            data.bid_status = Some(BidStatus::Declined { reason: rule.to_string() });
+        }
+    }
+    
+    // First receipt for a live opportunity counts toward its origin class; late receipts for
+    // expired ones are counted by amend_expiry_for_receipt
+    fn count_origin_execution(&mut self, opp_id: &str) {
+        if self.execution_receipts.contains_key(opp_id) {
+            return;
+        }
+        if let Some(class) = self.active_opportunities.get(opp_id).map(|data| data.origin.class()) {
+            self.origin_stats.entry(class).or_default().executed += 1;
+        }
+    }
+    
+    // The first settled receipt for a finder's opportunity goes on its reputation: what the finder
+    // estimated against what the receipt realized after gas. Call before the receipt is stored;
+    // a pending or unverified receipt settles nothing and doesn't block the one that follows it.
+    fn credit_finder_settlement(&mut self, opp_id: &str, receipt: &AaveLiquidationReceipt) {
+        let realized = match receipt.status {
+            ExecutionStatus::Pending => return,
+            ExecutionStatus::Success => {
+                let proceeds = receipt.total_proceeds.parse::<U256>().unwrap_or(U256::ZERO);
+                proceeds.saturating_sub(receipt.gas_paid_usdc.parse::<U256>().unwrap_or(U256::ZERO))
+            }
+            ExecutionStatus::Failed(_) => U256::ZERO,
+        };
+        if self.execution_receipts.get(opp_id).is_some_and(|stored| stored.verification == ReceiptVerification::Verified
+            && stored.summary().status != "Pending") {
+            return;
+        }
+        // An opportunity that already expired only has its finder left, not the estimate
+        let (finder, estimated) = match self.active_opportunities.get(opp_id) {
+            Some(data) => (
+                data.finder_node.clone(),
+                serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                    .ok()
+                    .and_then(|opportunity| opportunity.estimated_profit_usd.parse::<U256>().ok()),
+            ),
+            None => match self.expired_opportunities.get(opp_id) {
+                Some(record) => (record.finder_node.clone(), None),
+                None => return,
+            },
+        };
+        if finder.is_empty() || finder == "artemis-bot" || finder == our().node {
+            return;
+        }
+        self.peer_reputation.entry(finder).or_default().note_settled(estimated, realized);
+    }
+    
+    // The first settled receipt of an execution we ran or lent capital to goes into the performance
+    // metrics. Same rules as credit_finder_settlement: call before the receipt is stored, and a
+    // pending receipt counts for nothing. Returns the strategy a success was counted under.
+    fn note_execution_metrics(&mut self, opp_id: &str, receipt: &AaveLiquidationReceipt, executor: &str, asset: &str, our_share: &str) -> Option<String> {
+        let succeeded = match receipt.status {
+            ExecutionStatus::Pending => return None,
+            ExecutionStatus::Success => true,
+            ExecutionStatus::Failed(_) => false,
+        };
+        if self.execution_receipts.get(opp_id).is_some_and(|stored| stored.verification == ReceiptVerification::Verified
+            && stored.summary().status != "Pending") {
+            return None;
+        }
+        let our_node = our().node;
+        let executed_by_us = executor == "artemis-bot" || executor == our_node;
+        if !executed_by_us && !receipt.used_amounts.iter().any(|usage| usage.node_id == our_node) {
+            return None;
+        }
+        let (strategy_id, estimated) = match self.active_opportunities.get(opp_id) {
+            Some(data) => (
+                data.strategy_id.clone(),
+                serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                    .ok()
+                    .and_then(|opportunity| opportunity.estimated_profit_usd.parse::<U256>().ok()),
+            ),
+            None => ("aave-liquidation".to_string(), None),
+        };
+        let realized = if succeeded {
+            receipt.total_proceeds.parse::<U256>().unwrap_or(U256::ZERO)
+                .saturating_sub(receipt.gas_paid_usdc.parse::<U256>().unwrap_or(U256::ZERO))
+        } else {
+            U256::ZERO
+        };
+        let metrics = self.performance_metrics.strategy(&strategy_id);
+        metrics.note_settled(succeeded, executed_by_us, &receipt.gas_paid_usdc, estimated, realized);
+        let share = our_share.parse::<U256>().unwrap_or(U256::ZERO);
+        if succeeded && !share.is_zero() {
+            metrics.note_proceeds(asset, share);
+        }
+        succeeded.then_some(strategy_id)
+    }
+    
+    // Lets a verified peer receipt move our balances and metrics, and stores it: our share
+    // credited, our commitment settled with what it drew posted to the ledger, the execution
+    // counted, disputes raised, and a distribution that arrived while it awaited its check
+    // posted. Call with the receipt out of execution_receipts, since what is stored there is
+    // what has already counted.
+    fn apply_peer_receipt(&mut self, opp_id: &str, mut receipt_data: ReceiptData) {
+        let receipt = receipt_data.receipt.clone();
+        let executor = receipt_data.executor_node.clone();
+        let our_share = receipt_data.our_proceeds.clone();
+        // Breakdowns declared their asset before it was kept on the receipt, always as ETH
+        let asset = receipt_data.proceeds_asset.clone().unwrap_or_else(|| "ETH".to_string());
+        receipt_data.verification = ReceiptVerification::Verified;
+        // Checked before settling, while our commitment and intent are still on record
+        let disputes = self.detect_receipt_disputes(opp_id, &receipt);
+        
+        let credited = self.credit_proceeds(opp_id, &receipt, &asset, &our_share);
+        let capital_asset = self.capital_commitments.get(opp_id).map(|commitment| commitment.asset.clone());
+        let drawn = self.settle_commitment(opp_id, &receipt);
+        let mut postings = Vec::new();
+        if let Some(capital_asset) = capital_asset.as_ref().filter(|_| !drawn.is_zero()) {
+            let delta = SignedAmount::owed_to_us(drawn);
+            if self.post_settlement(opp_id, &executor, capital_asset, delta, "CapitalDrawn") {
+                postings.push(LedgerPosting { peer: executor.clone(), asset: capital_asset.clone(), delta });
+            }
+        }
+        if !drawn.is_zero()
+            && self.counterparties.note_exposure(&executor, opp_id, Self::current_timestamp()) {
+            println!("\n🤝 First execution by {} with our capital; approve them with approve_counterparty", executor);
+            self.record_journal("CounterpartyPendingApproval", serde_json::json!({
+                "peer": executor,
+                "opp_id": opp_id,
+            }));
+        }
+        self.count_origin_execution(opp_id);
+        let mut metrics_strategy = None;
+        if let Ok(parsed) = serde_json::from_str::<AaveLiquidationReceipt>(&receipt) {
+            metrics_strategy = self.note_execution_metrics(opp_id, &parsed, &executor, &asset, &our_share);
+            self.credit_finder_settlement(opp_id, &parsed);
+            self.set_opportunity_status(opp_id, OpportunityStatus::from_receipt(&parsed.status));
+        }
+        self.begin_finality(opp_id, &mut receipt_data, AppliedEffects {
+            proceeds_asset: asset,
+            proceeds: credited.to_string(),
+            capital_asset,
+            drawn: drawn.to_string(),
+            postings,
+            metrics_strategy,
+        });
+        self.execution_receipts.insert(opp_id.to_string(), receipt_data);
+        for dispute in disputes {
+            self.raise_receipt_dispute(dispute, &executor);
+        }
+        self.withhold_disputed_share(opp_id);
+        let awaiting = self.proceeds_distributions.records.get(opp_id)
+            .filter(|record| record.awaiting_verification)
+            .map(|record| record.distribution.clone());
+        if let Some(distribution) = awaiting {
+            self.post_distribution(&distribution);
+            if let Some(record) = self.proceeds_distributions.records.get_mut(opp_id) {
+                record.awaiting_verification = false;
+            }
+        }
+        self.amend_expiry_for_receipt(opp_id);
+        self.mirror_receipt(opp_id);
+        self.notify_receipt_settled(opp_id);
+    }
+    
+    // Retries the chain check on peer receipts still awaiting it, a few per tick. With receipt
+    // checks since turned off, the executor's word stands as it would have on arrival.
+    fn verify_pending_receipts(&mut self) {
+        let mut pending: Vec<String> = self.execution_receipts.iter()
+            .filter(|(_, data)| data.verification == ReceiptVerification::Pending)
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        pending.sort();
+        for opp_id in pending.into_iter().take(MAX_RECEIPT_VERIFICATIONS_PER_TICK) {
+            let Some(mut data) = self.execution_receipts.remove(&opp_id) else {
+                continue;
+            };
+            self.check_receipt_against_chain(&opp_id, &mut data);
+            if data.verification == ReceiptVerification::Verified {
+                println!("\n✅ Receipt for {} verified on-chain", opp_id);
+                self.apply_peer_receipt(&opp_id, data);
+            } else {
+                self.execution_receipts.insert(opp_id, data);
+            }
+        }
+    }
+    
+    // Starts tracking a receipt about to be stored: what counting it changed and, when it succeeded
+    // with receipt checks on, its confirmations. A copy replacing one that already counted keeps
+    // the original's, since nothing was applied again.
+    fn begin_finality(&self, opp_id: &str, data: &mut ReceiptData, effects: AppliedEffects) {
+        if let Some(stored) = self.execution_receipts.get(opp_id).filter(|stored| stored.applied.is_some()) {
+            data.applied = stored.applied.clone();
+            data.finality = stored.finality;
+            return;
+        }
+        if data.summary().status != "Success" {
+            return;
+        }
+        data.applied = Some(effects);
+        if self.node_config.eth_checks.check_receipts {
+            data.finality = ReceiptFinality::Confirming { block_number: data.summary().block_number };
+        }
+    }
+    
+    // Looks up confirming receipts again once the head is required_confirmations past their block,
+    // a few per tick. Still mined where we saw it: final. Re-mined elsewhere: confirming again from
+    // the new block. Gone or reverted: reversed. A check that can't run is retried next tick.
+    fn confirm_receipts(&mut self) {
+        // With receipt checks since turned off, what counted stands as it would have
+        if !self.node_config.eth_checks.check_receipts {
+            for data in self.execution_receipts.values_mut().filter(|data| matches!(data.finality, ReceiptFinality::Confirming { .. })) {
+                data.finality = ReceiptFinality::Final;
+                data.applied = None;
+            }
+            return;
+        }
+        let Some(head) = self.current_block() else {
+            return;
+        };
+        let required = self.node_config.eth_checks.required_confirmations;
+        let mut due: Vec<(String, u64)> = self.execution_receipts.iter()
+            .filter_map(|(opp_id, data)| match data.finality {
+                ReceiptFinality::Confirming { block_number } if head.saturating_sub(block_number) >= required =>
+                    Some((opp_id.clone(), block_number)),
+                _ => None,
+            })
+            .collect();
+        due.sort();
+        for (opp_id, block_number) in due.into_iter().take(MAX_RECEIPT_VERIFICATIONS_PER_TICK) {
+            let Some(receipt) = self.execution_receipts.get(&opp_id).map(|data| data.receipt.clone()) else {
+                continue;
+            };
+            let verification = self.verify_receipt_onchain(&receipt);
+            let mined = match verification {
+                ChainVerification::Confirmed { block_number: mined } => mined,
+                ChainVerification::BlockMismatch { mined, .. } => mined,
+                // Still mined in the block it claims; the target was accepted when it counted
+                ChainVerification::UnexpectedTarget { .. } => block_number,
+                ChainVerification::Unchecked { .. } => continue,
+                _ => {
+                    self.reverse_reorged_receipt(&opp_id, head, verification);
+                    continue;
+                }
+            };
+            if mined == block_number {
+                if let Some(data) = self.execution_receipts.get_mut(&opp_id) {
+                    data.finality = ReceiptFinality::Final;
+                    data.applied = None;
+                }
+                self.record_journal("ReceiptFinalized", serde_json::json!({
+                    "opp_id": opp_id,
+                    "block_number": block_number,
+                    "head": head,
+                }));
+                self.mirror_receipt(&opp_id);
+            } else if let Some(data) = self.execution_receipts.get_mut(&opp_id) {
+                println!("   Receipt for {} re-mined at block {} (was {})", opp_id, mined, block_number);
+                data.finality = ReceiptFinality::Confirming { block_number: mined };
+            }
+        }
+    }
+    
+    // A reorg dropped a counted receipt's transaction: the receipt becomes Failed and everything
+    // counting it changed is put back, from its recorded effects and its distribution's postings
+    fn reverse_reorged_receipt(&mut self, opp_id: &str, head: u64, verification: ChainVerification) {
+        let Some(data) = self.execution_receipts.get_mut(opp_id) else {
+            return;
+        };
+        println!("\n⚠️  Receipt for {} by {} was reorged out: {:?} at head {}", opp_id, data.executor_node, verification, head);
+        if let Ok(mut receipt) = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt) {
+            receipt.status = ExecutionStatus::Failed("reorged out".to_string());
+            data.receipt = serde_json::to_string(&receipt).unwrap_or_else(|_| data.receipt.clone());
+        }
+        data.summary = Some(ReceiptSummary::from_receipt(&data.receipt));
+        data.chain_verification = Some(verification.clone());
+        data.finality = ReceiptFinality::Reorged { head };
+        data.our_proceeds = "0".to_string();
+        data.retention = ReceiptRetention::Settled;
+        let withheld = data.withheld.take();
+        let effects = data.applied.take().unwrap_or_default();
+        let amount = |value: &str| value.parse::<U256>().unwrap_or(U256::ZERO);
+        
+        // A withheld share already left earned proceeds and metrics
+        let proceeds = if withheld.is_some() { U256::ZERO } else { amount(&effects.proceeds) };
+        capital::debit(&mut self.earned_proceeds, &effects.proceeds_asset, proceeds);
+        if let Some(strategy_id) = &effects.metrics_strategy {
+            self.performance_metrics.strategy(strategy_id).note_reorged(&effects.proceeds_asset, proceeds);
+        }
+        let drawn = amount(&effects.drawn);
+        if let Some(asset) = effects.capital_asset.as_ref().filter(|_| !drawn.is_zero()) {
+            capital::debit(&mut self.used_capital, asset, drawn);
+            let balance = capital::credit(&mut self.available_balances, asset, drawn);
+            self.amend_intents_to_balance(asset);
+            self.notify_ui(UiEventKind::BalanceChanged, serde_json::json!({ "asset": asset, "balance": balance.to_string() }));
+        }
+        for posting in &effects.postings {
+            self.post_settlement(opp_id, &posting.peer, &posting.asset, posting.delta.negated(), "Reorged");
+        }
+        let posted = self.proceeds_distributions.records.get(opp_id)
+            .filter(|record| !record.awaiting_verification)
+            .map(|record| record.distribution.clone());
+        if let Some(distribution) = posted {
+            for ((peer, asset), delta) in distribution_postings(&distribution, &our().node) {
+                self.post_settlement(opp_id, &peer, &asset, delta.negated(), "Reorged");
+            }
+            // Posted again should a later copy of the receipt verify
+            if let Some(record) = self.proceeds_distributions.records.get_mut(opp_id) {
+                record.awaiting_verification = true;
+            }
+        }
+        self.set_opportunity_status(opp_id, OpportunityStatus::Failed);
+        let event = serde_json::json!({
+            "opp_id": opp_id,
+            "head": head,
+            "verification": verification,
+            "proceeds_asset": effects.proceeds_asset,
+            "proceeds_reversed": proceeds.to_string(),
+            "capital_returned": drawn.to_string(),
+        });
+        self.record_journal("ReceiptReorged", event.clone());
+        self.notify_ui(UiEventKind::ReceiptReorged, event);
+        self.mirror_receipt(opp_id);
+    }
+    
+    // What a peer's successful receipt gets wrong about our part in it: capital drawn from us
+    // further than receipt_dispute_tolerance_bps from what we expected, or a fee other than our
+    // bid. We expect our line of the executor's declared allocation when the receipt carries one,
+    // and our whole commitment when it doesn't.
+    fn detect_receipt_disputes(&self, opp_id: &str, receipt_json: &str) -> Vec<ReceiptDispute> {
+        let Some(receipt) = serde_json::from_str::<AaveLiquidationReceipt>(receipt_json).ok()
+            .filter(|receipt| matches!(receipt.status, ExecutionStatus::Success)) else {
+            return Vec::new();
+        };
+        let Some(commitment) = self.capital_commitments.get(opp_id) else {
+            return Vec::new();
+        };
+        let me = our().node;
+        let amount = |value: &str| value.parse::<U256>().unwrap_or(U256::ZERO);
+        let ours: Vec<&CapitalUsage> = receipt.used_amounts.iter()
+            .filter(|usage| usage.node_id == me)
+            .collect();
+        let received = ours.iter()
+            .filter(|usage| usage.asset.eq_ignore_ascii_case(&commitment.asset))
+            .fold(U256::ZERO, |sum, usage| sum + amount(&usage.amount_used));
+        let expected = match &receipt.allocation {
+            Some(record) => record.allocation.iter()
+                .filter(|line| line.provider == me)
+                .fold(U256::ZERO, |sum, line| sum + amount(&line.amount)),
+            None => amount(&commitment.amount),
+        };
+        
+        let mut disputes = Vec::new();
+        if usage_diverges(expected, received, self.node_config.receipt_dispute_tolerance_bps) {
+            disputes.push(ReceiptDispute {
+                opp_id: opp_id.to_string(),
+                reason: DisputeReason::CapitalUsageMismatch,
+                expected: expected.to_string(),
+                received: received.to_string(),
+            });
+        }
+        let bid = self.our_intents.get(opp_id).map(|outstanding| outstanding.intent.fee_bps);
+        if let Some((bid, usage)) = bid.and_then(|bid| ours.iter().find(|usage| usage.fee_bps != bid).map(|usage| (bid, usage))) {
+            disputes.push(ReceiptDispute {
+                opp_id: opp_id.to_string(),
+                reason: DisputeReason::FeeMismatch,
+                expected: bid.to_string(),
+                received: usage.fee_bps.to_string(),
+            });
+        }
+        disputes
+    }
+    
+    // Attaches a dispute we raised to our copy of the receipt and sends it to the executor
+    fn raise_receipt_dispute(&mut self, dispute: ReceiptDispute, executor: &str) {
+        println!("\n⚖️  Disputing receipt for {} by {}: {:?} (expected {}, received {})",
+            dispute.opp_id, executor, dispute.reason, dispute.expected, dispute.received);
+        let message = serde_json::json!({
+            "type": "ReceiptDispute",
+            "opp_id": dispute.opp_id,
+            "dispute": dispute,
+        });
+        if self.attach_dispute(DisputeRecord {
+            dispute,
+            raised_by: our().node,
+            raised_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            resolution: None,
+        }) {
+            self.send_to_peer(executor, "ReceiveReceiptDispute", &message);
+        }
+    }
+    
+    // Attaches a dispute to its stored receipt, holding our share out of PnL while any dispute is
+    // open. One already open from the same node for the same reason isn't added again. Returns
+    // whether it was added.
+    fn attach_dispute(&mut self, record: DisputeRecord) -> bool {
+        let opp_id = record.dispute.opp_id.clone();
+        let Some(data) = self.execution_receipts.get_mut(&opp_id) else {
+            return false;
+        };
+        if data.disputes.iter().any(|existing| existing.is_open()
+            && existing.raised_by == record.raised_by
+            && existing.dispute.reason == record.dispute.reason) {
+            return false;
+        }
+        data.disputes.push(record.clone());
+        data.retention = ReceiptRetention::Verified;
+        self.withhold_disputed_share(&opp_id);
+        let withheld = self.execution_receipts.get(&opp_id).and_then(|data| data.withheld.clone());
+        self.record_journal("ReceiptDisputed", serde_json::json!({
+            "opp_id": opp_id,
+            "raised_by": record.raised_by,
+            "dispute": record.dispute,
+            "withheld": withheld,
+        }));
+        true
+    }
+    
+    // Takes our share of a disputed receipt back out of earned proceeds and metrics, once: only a
+    // successful, verified receipt has been credited, and only one we took part in reached the
+    // metrics
+    fn withhold_disputed_share(&mut self, opp_id: &str) {
+        let me = our().node;
+        let strategy_id = self.active_opportunities.get(opp_id).map(|data| data.strategy_id.clone());
+        let Some(data) = self.execution_receipts.get_mut(opp_id) else {
+            return;
+        };
+        let share = data.our_proceeds.parse::<U256>().unwrap_or(U256::ZERO);
+        if !data.is_disputed() || data.withheld.is_some() || share.is_zero()
+            || data.verification != ReceiptVerification::Verified || data.summary().status != "Success" {
+            return;
+        }
+        let participated = data.executor_node == "artemis-bot" || data.executor_node == me
+            || serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt)
+                .map_or(false, |receipt| receipt.used_amounts.iter().any(|usage| usage.node_id == me));
+        let withheld = WithheldProceeds {
+            // Breakdowns declared their asset before it was kept on the receipt, always as ETH
+            asset: data.proceeds_asset.clone().unwrap_or_else(|| "ETH".to_string()),
+            amount: share.to_string(),
+            strategy_id: strategy_id.unwrap_or_else(|| data.summary().strategy_id),
+            from_metrics: participated,
+        };
+        data.withheld = Some(withheld.clone());
+        capital::debit(&mut self.earned_proceeds, &withheld.asset, share);
+        if withheld.from_metrics {
+            self.performance_metrics.strategy(&withheld.strategy_id).withhold_proceeds(&withheld.asset, share);
+        }
+    }
+    
+    fn finder_tier(&self, finder: &str) -> u8 {
+        self.peer_reputation.get(finder).map_or(0, |reputation| reputation.tier())
+    }
+    
+    // Balance not already committed to an execution
+    fn free_balance(&self, asset: &str) -> U256 {
+        let available = self.available_balances.get(asset)
+            .and_then(|amount| amount.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        let committed = self.committed_amounts.get(asset)
+            .and_then(|amount| amount.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        available.saturating_sub(committed)
+    }
+    
+    // Free balance excluding demo capital
+    fn operator_balance(&self, asset: &str) -> U256 {
+        let demo = self.demo_balances.get(asset)
+            .and_then(|amount| amount.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        self.free_balance(asset).saturating_sub(demo)
+    }
+    
+    fn credit_capital(&mut self, asset: &str, amount: &str) -> Result<String, String> {
+        asset.parse::<Address>()
+            .map_err(|_| "Invalid asset address")?;
+        let amount = amount.parse::<U256>()
+            .map_err(|_| "Invalid amount")?;
+        
+        let balance = capital::credit(&mut self.available_balances, asset, amount);
+        self.record_journal("CapitalAdded", serde_json::json!({
+            "asset": asset,
+            "amount": amount.to_string(),
+            "balance": balance.to_string(),
+        }));
+        self.notify_local(PoolEvent::CapitalChanged { asset: asset.to_string(), balance: balance.to_string() });
+        self.notify_ui(UiEventKind::BalanceChanged, serde_json::json!({ "asset": asset, "balance": balance.to_string() }));
+        Ok(format!("Added {} of asset {}", amount, asset))
+    }
+    
+    // Only the free balance can go: what our intents lock stays, and demo capital is removed
+    // through seed_demo_capital instead. `"all"` drains the asset's withdrawable balance.
+    fn debit_capital(&mut self, asset: &str, amount: &str) -> Result<String, String> {
+        let withdrawable = self.operator_balance(asset);
+        let amount = if amount == "all" {
+            withdrawable
+        } else {
+            amount.parse::<U256>().map_err(|_| "Invalid amount")?
+        };
+        if amount > withdrawable {
+            return Err(format!("Cannot remove {} of asset {}: only {} is withdrawable (the rest is committed to intents or demo capital)",
+                amount, asset, withdrawable));
+        }
+        if amount.is_zero() {
+            return Err(format!("Nothing to remove: {} of asset {} is withdrawable", withdrawable, asset));
+        }
+        
+        let remaining = capital::debit(&mut self.available_balances, asset, amount);
+        self.amend_intents_to_balance(asset);
+        self.record_journal("CapitalRemoved", serde_json::json!({
+            "asset": asset,
+            "amount": amount.to_string(),
+            "remaining": remaining.to_string(),
+        }));
+        self.notify_local(PoolEvent::CapitalChanged { asset: asset.to_string(), balance: remaining.to_string() });
+        self.notify_ui(UiEventKind::BalanceChanged, serde_json::json!({ "asset": asset, "balance": remaining.to_string() }));
+        Ok(format!("Removed {} of asset {}; {} remains", amount, asset, remaining))
+    }
+    
+    // Logs a capital operation's outcome with the balance it left. Refusals are only kept when
+    // they carry an operation id, so a retry gets the same answer.
+    fn finish_capital_operation(
+        &mut self,
+        operation_id: Option<String>,
+        kind: CapitalOperationKind,
+        asset: &str,
+        amount: &str,
+        outcome: Result<String, String>,
+    ) -> Result<String, String> {
+        if outcome.is_ok() || operation_id.is_some() {
+            self.capital_ledger.record(CapitalOperation {
+                operation_id,
+                kind,
+                asset: asset.to_string(),
+                amount: amount.to_string(),
+                balance_after: capital::balance_of(&self.available_balances, asset).to_string(),
+                applied_at: Self::current_timestamp(),
+                outcome: outcome.clone(),
+            });
+        }
+        outcome
+    }
+    
+    // Locks `amount` of `asset` behind our intent for `opp_id`, replacing any earlier lock for it
+    fn set_commitment(&mut self, opp_id: &str, asset: &str, amount: &str) {
+        self.release_commitment(opp_id);
+        let amount_value = amount.parse::<U256>().unwrap_or(U256::ZERO);
+        if amount_value.is_zero() {
+            return;
+        }
+        let total = self.committed_amounts.entry(asset.to_string()).or_insert("0".to_string());
+        *total = (total.parse::<U256>().unwrap_or(U256::ZERO) + amount_value).to_string();
+        self.capital_commitments.insert(opp_id.to_string(), CapitalCommitment {
+            asset: asset.to_string(),
+            amount: amount_value.to_string(),
+            committed_at: Self::current_timestamp(),
+        });
+    }
+    
+    fn release_commitment(&mut self, opp_id: &str) -> Option<CapitalCommitment> {
+        let commitment = self.capital_commitments.remove(opp_id)?;
+        let amount = commitment.amount.parse::<U256>().unwrap_or(U256::ZERO);
+        if let Some(total) = self.committed_amounts.get_mut(&commitment.asset) {
+            let remaining = total.parse::<U256>().unwrap_or(U256::ZERO).saturating_sub(amount);
+            if remaining.is_zero() {
+                self.committed_amounts.remove(&commitment.asset);
+            } else {
+                *total = remaining.to_string();
+            }
+        }
+        Some(commitment)
+    }
+    
+    // A terminal receipt ends our intent and frees its lock; on success what the executor drew
+    // from us counts as used. Returns the amount drawn.
+    fn settle_commitment(&mut self, opp_id: &str, receipt_json: &str) -> U256 {
+        if let Some(outstanding) = self.our_intents.remove(opp_id) {
+            self.settled_intents.insert(opp_id.to_string(), outstanding);
+        }
+        self.capital_reservations.remove(opp_id);
+        let Some(commitment) = self.release_commitment(opp_id) else {
+            return U256::ZERO;
+        };
+        let Ok(receipt) = serde_json::from_str::<AaveLiquidationReceipt>(receipt_json) else {
+            return U256::ZERO;
+        };
+        if !matches!(receipt.status, ExecutionStatus::Success) {
+            return U256::ZERO;
+        }
+        let our_node = our().node;
+        let drawn = receipt.used_amounts.iter()
+            .filter(|usage| usage.node_id == our_node && usage.asset.eq_ignore_ascii_case(&commitment.asset))
+            .map(|usage| usage.amount_used.parse::<U256>().unwrap_or(U256::ZERO))
+            .fold(U256::ZERO, |acc, amount| acc + amount);
+        if drawn.is_zero() {
+            return drawn;
+        }
+        capital::credit(&mut self.used_capital, &commitment.asset, drawn);
+        // What the execution drew has left our balance; the rest of the lock is already released
+        let balance = capital::debit(&mut self.available_balances, &commitment.asset, drawn);
+        self.amend_intents_to_balance(&commitment.asset);
+        self.record_journal("CapitalUsed", serde_json::json!({
+            "opp_id": opp_id,
+            "asset": commitment.asset,
+            "committed": commitment.amount,
+            "used": drawn.to_string(),
+            "balance": balance.to_string(),
+        }));
+        self.notify_ui(UiEventKind::BalanceChanged, serde_json::json!({ "asset": commitment.asset, "balance": balance.to_string() }));
+        drawn
+    }
+    
+    // Credits our share of a successful execution to earned_proceeds. Call before the receipt is
+    // stored: one replacing an already successful, verified receipt for the opportunity is not
+    // credited again. Returns what was credited.
+    fn credit_proceeds(&mut self, opp_id: &str, receipt_json: &str, asset: &str, our_share: &str) -> U256 {
+        let Some(receipt) = serde_json::from_str::<AaveLiquidationReceipt>(receipt_json).ok()
+            .filter(|receipt| matches!(receipt.status, ExecutionStatus::Success)) else {
+            return U256::ZERO;
+        };
+        let already_credited = self.execution_receipts.get(opp_id)
+            .map_or(false, |data| data.verification == ReceiptVerification::Verified && data.summary().status == "Success");
+        let share = our_share.parse::<U256>().unwrap_or(U256::ZERO);
+        if already_credited || share.is_zero() {
+            return U256::ZERO;
+        }
+        let earned = capital::credit(&mut self.earned_proceeds, asset, share);
+        self.record_journal("ProceedsEarned", serde_json::json!({
+            "opp_id": opp_id,
+            "asset": asset,
+            "amount": share.to_string(),
+            "earned": earned.to_string(),
+        }));
+        self.notify_local(PoolEvent::ProceedsEarned {
+            opp_id: opp_id.to_string(),
+            asset: asset.to_string(),
+            amount: share.to_string(),
+            receipt,
+        });
+        share
+    }
+    
+    // Per asset: balance, how much of it our intents lock, what is left to bid, and lifetime use
+    fn capital_summary(&self) -> serde_json::Value {
+        let mut assets: Vec<&String> = self.available_balances.keys().chain(self.committed_amounts.keys()).collect();
+        assets.sort();
+        assets.dedup();
+        let summary: serde_json::Map<String, serde_json::Value> = assets.into_iter()
+            .map(|asset| (asset.clone(), serde_json::json!({
+                "balance": self.available_balances.get(asset).cloned().unwrap_or_else(|| "0".to_string()),
+                "committed": self.committed_amounts.get(asset).cloned().unwrap_or_else(|| "0".to_string()),
+                "available": self.free_balance(asset).to_string(),
+                "used": self.used_capital.get(asset).cloned().unwrap_or_else(|| "0".to_string()),
+            })))
+            .collect();
+        serde_json::Value::Object(summary)
+    }
+    
+    fn capital_provenance(&self) -> serde_json::Value {
+        let provenance: serde_json::Map<String, serde_json::Value> = self.available_balances.iter()
+            .map(|(asset, amount)| {
+                let total = amount.parse::<U256>().unwrap_or(U256::ZERO);
+                let demo = self.demo_balances.get(asset)
+                    .and_then(|amount| amount.parse::<U256>().ok())
+                    .unwrap_or(U256::ZERO)
+                    .min(total);
+                (asset.clone(), serde_json::json!({
+                    "operator": (total - demo).to_string(),
+                    "demo": demo.to_string(),
+                }))
+            })
+            .collect();
+        serde_json::Value::Object(provenance)
+    }
+    
+    // Settings for a registered strategy; anything else is refused by name
+    fn strategy_config(&self, strategy_id: &str) -> Result<&StrategyConfig, String> {
+        self.strategy_configs.get(strategy_id).ok_or_else(|| {
+            let mut known: Vec<&String> = self.strategy_configs.keys().collect();
+            known.sort();
+            format!("Unknown strategy id {}; registered strategies: {:?}", strategy_id, known)
+        })
+    }
+    
+    // Snapshots the config and strategy settings now in force as a new revision, dropping old
+    // revisions no open opportunity or outstanding intent refers to
+    fn record_config_revision(&mut self, reason: &str) {
+        self.config_revision += 1;
+        let revision = ConfigRevision {
+            revision: self.config_revision,
+            applied_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            reason: reason.to_string(),
+            config: self.node_config.clone(),
+            strategies: self.strategy_configs.iter().map(|(id, strategy)| (id.clone(), strategy.clone())).collect(),
+        };
+        self.config_history.insert(self.config_revision, revision);
+        
+        let in_use: HashSet<u64> = self.active_opportunities.values().map(|data| data.config_revision)
+            .chain(self.our_intents.values().map(|outstanding| outstanding.config_revision))
+            .collect();
+        let oldest_kept = self.config_revision.saturating_sub(CONFIG_HISTORY_CAPACITY as u64 - 1);
+        self.config_history.retain(|revision, _| *revision >= oldest_kept || in_use.contains(revision));
+    }
+    
+    // The config an opportunity was created under; the current one when its revision is unknown
+    fn config_for(&self, opp_id: &str) -> &NodeConfig {
+        self.revision_for(opp_id).map_or(&self.node_config, |revision| &revision.config)
+    }
+    
+    fn revision_for(&self, opp_id: &str) -> Option<&ConfigRevision> {
+        let data = self.active_opportunities.get(opp_id)?;
+        self.config_history.get(&data.config_revision)
+    }
+    
+    // Applies an update to a copy of the current config and validates the result as a whole;
+    // shared by update_node_config and apply_preset
+    fn stage_config_update(&self, update: ConfigUpdate) -> Result<NodeConfig, String> {
+        // Everything is applied to a copy and validated as a whole; nothing changes on error
+        let mut staged = self.node_config.clone();
+        if let Some(finder_enabled) = update.finder_enabled {
+            staged.finder_enabled = finder_enabled;
+        }
+        if let Some(capital_provider_enabled) = update.capital_provider_enabled {
+            staged.capital_provider_enabled = capital_provider_enabled;
+        }
+        if let Some(executor_enabled) = update.executor_enabled {
+            staged.executor_enabled = executor_enabled;
+        }
+        if let Some(finder_fee_bps) = update.finder_fee_bps {
+            staged.finder_fee_bps = finder_fee_bps;
+        }
+        if let Some(executor_fee_bps) = update.executor_fee_bps {
+            staged.executor_fee_bps = executor_fee_bps;
+        }
+        if let Some(min_profit_str) = update.min_profit_threshold_usd {
+            // Validate it's a valid U256 string
+            min_profit_str.parse::<U256>()
+                .map_err(|_| "Invalid min profit threshold")?;
+            staged.min_profit_threshold_usd = min_profit_str;
+        }
+        if let Some(max_gas_str) = update.max_gas_price_gwei {
+            // Validate it's a valid U256 string
+            max_gas_str.parse::<U256>()
+                .map_err(|_| "Invalid max gas price")?;
+            staged.max_gas_price_gwei = max_gas_str;
+        }
+        if let Some(executor_preference) = update.executor_preference {
+            staged.executor_preference = executor_preference;
+        }
+        if let Some(failover_blocks) = update.executor_failover_blocks {
+            if failover_blocks == 0 {
+                return Err("Executor failover window must be at least one block".to_string());
+            }
+            staged.executor_failover_blocks = failover_blocks;
+        }
+        if let Some(rules) = update.min_distinct_providers_above_usd {
+            for rule in &rules {
+                rule.threshold_usd.parse::<U256>()
+                    .map_err(|_| format!("Invalid provider threshold: {}", rule.threshold_usd))?;
+            }
+            staged.min_distinct_providers_above_usd = rules;
+        }
+        if let Some(allow) = update.allow_under_provisioned_execution {
+            staged.allow_under_provisioned_execution = allow;
+        }
+        if let Some(wait_blocks) = update.provider_wait_blocks {
+            staged.provider_wait_blocks = wait_blocks;
+        }
+        if let Some(coverage_bps) = update.window_extension_coverage_bps {
+            if coverage_bps > 10_000 {
+                return Err("Window extension coverage cannot exceed 10000 bps".to_string());
+            }
+            staged.window_extension_coverage_bps = coverage_bps;
+        }
+        if let Some(entry_margin) = update.profit_entry_margin_bps {
+            staged.profit_entry_margin_bps = entry_margin;
+        }
+        if let Some(exit_margin) = update.profit_exit_margin_bps {
+            if exit_margin > 10_000 {
+                return Err("Profit exit margin cannot exceed 10000 bps".to_string());
+            }
+            staged.profit_exit_margin_bps = exit_margin;
+        }
+        if let Some(cooldown) = update.origination_cooldown_secs {
+            staged.origination_cooldown_secs = cooldown;
+        }
+        if let Some(rules) = update.fast_path_rules {
+            let ceiling = FAST_PATH_SIZE_CEILING_USD.parse::<U256>().unwrap_or(U256::ZERO);
+            for rule in &rules {
+                let max_size = rule.max_size_usd.parse::<U256>()
+                    .map_err(|_| format!("Invalid fast-path size: {}", rule.max_size_usd))?;
+                if max_size > ceiling {
+                    return Err(format!("Fast-path size {} exceeds the ceiling of {}", max_size, ceiling));
+                }
+            }
+            staged.fast_path_rules = rules;
+        }
+        if let Some(seed) = update.seed_demo_capital {
+            staged.seed_demo_capital = seed;
+        }
+        if let Some(paper_trading) = update.paper_trading {
+            staged.paper_trading = paper_trading;
+        }
+        if let Some(coordinator) = update.pool_coordinator {
+            staged.pool_coordinator = if coordinator.is_empty() { None } else { Some(coordinator) };
+        }
+        if let Some(bootstrap) = update.bootstrap_peers {
+            let our_node = our().node;
+            staged.bootstrap_peers = bootstrap.into_iter().filter(|peer| *peer != our_node).collect();
+        }
+        if let Some(policy) = update.duplicate_finder_policy {
+            if self.pool_manifest.as_ref().map_or(false, |manifest| manifest.duplicate_finder_policy.is_some()) {
+                return Err("Duplicate finder policy is set by the pool manifest".to_string());
+            }
+            staged.duplicate_finder_policy = policy;
+        }
+        if let Some(policy) = update.allocation_policy {
+            staged.allocation_policy = policy;
+        }
+        if let Some(eth_checks) = update.eth_checks {
+            if let Some(wallet) = &eth_checks.wallet_address {
+                wallet.parse::<EthAddress>().map_err(|_| "Invalid wallet address")?;
+            }
+            for contract in &eth_checks.liquidation_contracts {
+                contract.parse::<EthAddress>().map_err(|_| format!("Invalid liquidation contract {}", contract))?;
+            }
+            staged.eth_checks = eth_checks;
+        }
+        if let Some(limit) = update.outbound_requests_per_sec {
+            staged.outbound_requests_per_sec = limit;
+        }
+        if let Some(peers) = update.pressure_fanout_peers {
+            if peers == 0 {
+                return Err("Pressure fan-out must reach at least one peer".to_string());
+            }
+            staged.pressure_fanout_peers = peers;
+        }
+        if let Some(ttl) = update.opportunity_ttl_secs {
+            if ttl == 0 {
+                return Err("Opportunity TTL must be positive".to_string());
+            }
+            staged.opportunity_ttl_secs = ttl;
+        }
+        if let Some(policies) = update.origin_policies {
+            for policy in policies.values() {
+                if let Some(threshold) = &policy.min_profit_threshold_usd {
+                    threshold.parse::<U256>().map_err(|_| format!("Invalid profit threshold: {}", threshold))?;
+                }
+            }
+            staged.origin_policies = policies;
+        }
+        if let Some(enabled) = update.bridge_validation {
+            staged.bridge_validation = enabled;
+        }
+        if let Some(limit) = update.max_outstanding_validations {
+            if limit == 0 {
+                return Err("At least one validation must be allowed in flight".to_string());
+            }
+            staged.max_outstanding_validations = limit;
+        }
+        if let Some(limit) = update.heartbeat_miss_limit {
+            if limit == 0 {
+                return Err("Heartbeat miss limit must be positive".to_string());
+            }
+            staged.heartbeat_miss_limit = limit;
+        }
+        if let Some(grace) = update.unreachable_peer_grace_secs {
+            staged.unreachable_peer_grace_secs = grace;
+        }
+        if let Some(max_peers) = update.max_peers {
+            staged.max_peers = max_peers;
+        }
+        if let Some(hops) = update.max_broadcast_hops {
+            staged.max_broadcast_hops = hops;
+        }
+        if let Some(ttl) = update.seen_broadcast_ttl_secs {
+            staged.seen_broadcast_ttl_secs = ttl;
+        }
+        if let Some(actions) = update.risk_flag_actions {
+            staged.risk_flag_actions = actions;
+        }
+        if let Some(enabled) = update.capital_reservation {
+            staged.capital_reservation = enabled;
+        }
+        if let Some(ttl) = update.reservation_ttl_secs {
+            if ttl == 0 {
+                return Err("Reservation TTL must be positive".to_string());
+            }
+            staged.reservation_ttl_secs = ttl;
+        }
+        if let Some(scheme) = update.distribution_scheme {
+            scheme.min_payable_share.parse::<U256>()
+                .map_err(|_| "Invalid min_payable_share")?;
+            staged.distribution_scheme = scheme;
+        }
+        if let Some(grants) = update.local_api_grants {
+            if let Some(key) = grants.keys().find(|key| !matches!(key.split(':').count(), 2 | 3)) {
+                return Err(format!("Grant key {} is neither a process id nor package:publisher", key));
+            }
+            staged.local_api_grants = grants;
+        }
+        if let Some(quota) = update.finder_quota {
+            staged.finder_quota = quota;
+        }
+        if let Some(policy) = update.reputation_policy {
+            staged.reputation_policy = policy;
+        }
+        if let Some(blacklisted) = update.blacklisted_peers {
+            staged.blacklisted_peers = blacklisted;
+        }
+        if let Some(allowlist_mode) = update.allowlist_mode {
+            staged.allowlist_mode = allowlist_mode;
+        }
+        if let Some(allowed) = update.allowed_peers {
+            staged.allowed_peers = allowed;
+        }
+        if let Some(policy) = update.monitoring_policy {
+            staged.monitoring_policy = policy;
+        }
+        if let Some(limits) = update.inbound_rate_limits {
+            staged.inbound_rate_limits = limits;
+        }
+        if let Some(policy) = update.record_sharing {
+            staged.record_sharing = policy;
+        }
+        if let Some(event_log) = update.event_log {
+            event_log.validate()?;
+            staged.event_log = event_log;
+        }
+        if let Some(required) = update.require_signed_messages {
+            staged.require_signed_messages = required;
+        }
+        if let Some(grace) = update.key_rotation_grace_secs {
+            staged.key_rotation_grace_secs = grace;
+        }
+        if let Some(policy) = update.bid_policy {
+            policy.validate()?;
+            staged.bid_policy = policy;
+        }
+        if let Some(model) = update.gas_cost_model {
+            model.validate()?;
+            staged.gas_cost_model = model;
+        }
+        if let Some(fee_bps) = update.capital_provider_fee_bps {
+            if fee_bps > 10_000 {
+                return Err("capital_provider_fee_bps cannot exceed 10000".to_string());
+            }
+            staged.capital_provider_fee_bps = fee_bps;
+        }
+        if let Some(tolerance_bps) = update.receipt_dispute_tolerance_bps {
+            if tolerance_bps > 10_000 {
+                return Err("receipt_dispute_tolerance_bps cannot exceed 10000".to_string());
+            }
+            staged.receipt_dispute_tolerance_bps = tolerance_bps;
+        }
+        if let Some(epoch) = update.settlement_epoch {
+            epoch.validate()?;
+            staged.settlement_epoch = epoch;
+        }
+        if let Some(caps) = update.collection_caps {
+            caps.validate()?;
+            staged.collection_caps = caps;
+        }
+        
+        staged.validate()?;
+        Ok(staged)
+    }
+    
+    // Swaps in a new config and carries out what its changes imply: demo capital, eth check
+    // state, re-announcing changed roles and pushing the config to the bridge. Forward changes,
+    // trial reverts and presets all come through here; returns false when nothing changed, and
+    // otherwise records a new config revision.
+    fn apply_node_config(&mut self, config: NodeConfig, reason: &str) -> bool {
+        let changed = changed_config_fields(&self.node_config, &config);
+        if changed.is_empty() {
+            return false;
+        }
+        self.node_config = config;
+        
+        let touched = |field: &str| changed.iter().any(|c| c == field);
+        if touched("seed_demo_capital") {
+            self.apply_demo_capital_setting();
+        }
+        if touched("eth_checks") {
+            self.eth_degraded.clear();
+        }
+        if touched("collection_caps") {
+            self.enforce_collection_caps();
+        }
+        if ["blacklisted_peers", "allowlist_mode", "allowed_peers"].iter().any(|field| touched(field)) {
+            self.drop_blocked_peers();
+        }
+        if touched("bootstrap_peers") {
+            self.connect_bootstrap_peers();
+        }
+        if ["finder_enabled", "capital_provider_enabled", "executor_enabled", "enabled_strategies"].iter().any(|field| touched(field)) {
+            let peers: Vec<String> = self.reachable_peers().cloned().collect();
+            for peer in peers {
+                let _ = self.send_announcement(peer, false);
+            }
+        }
+        // Every connected bridge runs on our config, not just the primary
+        let config_json = serde_json::json!({
+            "type": "NodeConfig",
+            "config": self.node_config
+        });
+        for channel_id in self.artemis_channels.channel_ids() {
+            self.push_to_artemis_channel(channel_id, &config_json.to_string());
+        }
+        
+        println!("\n⚙️  Config {}: {}", reason.to_lowercase(), changed.join(", "));
+        self.record_config_revision(reason);
+        self.record_journal("ConfigChanged", serde_json::json!({
+            "reason": reason,
+            "changed": changed,
+            "revision": self.config_revision,
+        }));
+        true
+    }
+    
+    // Restores the pre-trial config once an uncommitted trial's window has closed
+    fn revert_expired_config_trial(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if self.config_trial.as_ref().map_or(true, |trial| now < trial.revert_at) {
+            return;
+        }
+        if let Some(trial) = self.config_trial.take() {
+            self.apply_node_config(trial.previous, "TrialReverted");
+        }
+    }
+    
+    // Adds or removes tagged demo capital to match the seed_demo_capital setting
+    fn apply_demo_capital_setting(&mut self) {
+        let seeded = self.demo_balances.contains_key(DEMO_CAPITAL_ASSET);
+        if self.node_config.seed_demo_capital == seeded {
+            return;
+        }
+        let demo = DEMO_CAPITAL_AMOUNT.parse::<U256>().unwrap_or(U256::ZERO);
+        if self.node_config.seed_demo_capital {
+            capital::credit(&mut self.available_balances, DEMO_CAPITAL_ASSET, demo);
+            self.demo_balances.insert(DEMO_CAPITAL_ASSET.to_string(), demo.to_string());
+        } else {
+            let seeded_amount = self.demo_balances.remove(DEMO_CAPITAL_ASSET)
+                .and_then(|amount| amount.parse::<U256>().ok())
+                .unwrap_or(U256::ZERO);
+            capital::debit(&mut self.available_balances, DEMO_CAPITAL_ASSET, seeded_amount);
+            self.amend_intents_to_balance(DEMO_CAPITAL_ASSET);
+        }
+    }
+    
+    // Call after anything that reduces an asset's free balance: shrinks our outstanding intents
+    // (newest first) until they fit, and tells the peers that received each one
+    fn amend_intents_to_balance(&mut self, asset: &str) {
+        let available = self.available_balances.get(asset)
+            .and_then(|amount| amount.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        let committed = self.committed_amounts.get(asset)
+            .and_then(|amount| amount.parse::<U256>().ok())
+            .unwrap_or(U256::ZERO);
+        let mut amendable: Vec<(String, String, U256)> = self.our_intents.iter()
+            .filter(|(opp_id, outstanding)| outstanding.intent.asset == asset
+                && outstanding.amendment_rejected.is_none()
+                && !self.capital_reservations.contains_key(*opp_id))
+            .map(|(opp_id, outstanding)| (
+                opp_id.clone(),
+                outstanding.submitted_at.clone(),
+                outstanding.intent.max_amount.parse::<U256>().unwrap_or(U256::ZERO),
+            ))
+            .collect();
+        let outstanding_total = amendable.iter().fold(U256::ZERO, |acc, (_, _, amount)| acc + *amount);
+        // Room for the amendable intents: the balance less what stays locked regardless
+        let free = available.saturating_sub(committed.saturating_sub(outstanding_total));
+        if outstanding_total <= free {
+            return;
+        }
+        
+        let mut excess = outstanding_total - free;
+        amendable.sort_by(|a, b| b.1.parse::<u64>().unwrap_or(0).cmp(&a.1.parse::<u64>().unwrap_or(0)).then(a.0.cmp(&b.0)));
+        for (opp_id, _, amount) in amendable {
+            if excess.is_zero() {
+                break;
+            }
+            let reduction = excess.min(amount);
+            if reduction.is_zero() {
+                continue;
+            }
+            excess -= reduction;
+            let new_max_amount = (amount - reduction).to_string();
+            
+            let Some(outstanding) = self.our_intents.get_mut(&opp_id) else {
+                continue;
+            };
+            outstanding.intent.max_amount = new_max_amount.clone();
+            let recipients = outstanding.sent_to.clone();
+            self.set_commitment(&opp_id, asset, &new_max_amount);
+            println!("\n✂️  Shrinking our intent for {} to {} (free {} balance dropped)", opp_id, new_max_amount, asset);
+            
+            let amendment = MevMessage::IntentAmendment {
+                opp_id: opp_id.clone(),
+                new_max_amount: new_max_amount.clone(),
+            };
+            for peer in &recipients {
+                self.send_to_peer(peer, amendment.handler(), &Self::message_json(&amendment));
+            }
+        }
+    }
+    
+    // Spends one call from this minute's eth budget
+    fn take_eth_call(&mut self) -> bool {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if now.saturating_sub(self.eth_budget.window_start) >= 60 {
+            self.eth_budget = EthCallBudget { window_start: now, used: 0 };
+        }
+        if self.eth_budget.used >= self.node_config.eth_checks.calls_per_minute {
+            return false;
+        }
+        self.eth_budget.used += 1;
+        true
+    }
+    
+    fn note_eth_degraded(&mut self, check: &str, error: String) {
+        println!("   ⚠️  On-chain {} check unavailable ({}); trusting the bridge", check, error);
+        let since = self.eth_degraded.get(check)
+            .map(|degradation| degradation.since.clone())
+            .unwrap_or_else(Self::current_timestamp);
+        self.eth_degraded.insert(check.to_string(), EthDegradation { error, since });
+    }
+    
+    fn eth_provider(&self) -> EthProvider {
+        EthProvider::new(self.node_config.eth_checks.chain_id, ETH_REQUEST_TIMEOUT_SECS)
+    }
+    
+    // eth_call on behalf of `check`; None (and the check marked degraded) on any failure
+    fn eth_call(&mut self, check: &str, to: &str, calldata: Option<Vec<u8>>) -> Option<Vec<u8>> {
+        let (Ok(to), Some(calldata)) = (to.parse::<EthAddress>(), calldata) else {
+            self.note_eth_degraded(check, "invalid call target or argument".to_string());
+            return None;
+        };
+        if !self.take_eth_call() {
+            self.note_eth_degraded(check, "per-minute call budget exhausted".to_string());
+            return None;
+        }
+        let request = TransactionRequest::default()
+            .to(to)
+            .input(TransactionInput::new(EthBytes::from(calldata)));
+        match self.eth_provider().call(request, None) {
+            Ok(output) => {
+                self.eth_degraded.remove(check);
+                Some(output.to_vec())
+            }
+            Err(e) => {
+                self.note_eth_degraded(check, format!("{:?}", e));
+                None
+            }
+        }
+    }
+    
+    // Aave's own health factor for the victim (1e18 = 1.0)
+    fn onchain_health_factor(&mut self, victim: &str) -> Option<U256> {
+        let pool = self.node_config.aave_pool_address.clone();
+        let output = self.eth_call("health_factor", &pool, address_calldata(AAVE_GET_USER_ACCOUNT_DATA, victim))?;
+        // Six uint256 words; the health factor is the last
+        let Some(word) = output.get(160..192) else {
+            self.note_eth_degraded("health_factor", format!("short getUserAccountData output ({} bytes)", output.len()));
+            return None;
+        };
+        Some(U256::from_be_slice(word))
+    }
+    
+    fn verify_receipt_onchain(&mut self, receipt_json: &str) -> ChainVerification {
+        let unchecked = |reason: &str| ChainVerification::Unchecked { reason: reason.to_string() };
+        let Ok(receipt) = serde_json::from_str::<AaveLiquidationReceipt>(receipt_json) else {
+            return unchecked("unreadable receipt");
+        };
+        let Ok(tx_hash) = receipt.tx_hash.parse::<TxHash>() else {
+            return unchecked("no transaction hash");
+        };
+        if !self.take_eth_call() {
+            self.note_eth_degraded("receipts", "per-minute call budget exhausted".to_string());
+            return unchecked("call budget exhausted");
+        }
+        match self.eth_provider().get_transaction_receipt(tx_hash) {
+            Ok(Some(onchain)) => {
+                self.eth_degraded.remove("receipts");
+                let mined = onchain.block_number.unwrap_or(0);
+                let to = onchain.to.map(|to| to.to_string());
+                if !onchain.status() {
+                    ChainVerification::Reverted
+                } else if mined != receipt.block_number {
+                    ChainVerification::BlockMismatch { claimed: receipt.block_number, mined }
+                } else if !self.is_liquidation_target(to.as_deref()) {
+                    ChainVerification::UnexpectedTarget { to }
+                } else {
+                    ChainVerification::Confirmed { block_number: mined }
+                }
+            }
+            Ok(None) => {
+                self.eth_degraded.remove("receipts");
+                ChainVerification::NotFound
+            }
+            Err(e) => {
+                let error = format!("{:?}", e);
+                self.note_eth_degraded("receipts", error.clone());
+                ChainVerification::Unchecked { reason: error }
+            }
+        }
+    }
+    
+    fn is_liquidation_target(&self, to: Option<&str>) -> bool {
+        let Some(to) = to else {
+            return false;
+        };
+        std::iter::once(&self.node_config.aave_pool_address)
+            .chain(&self.node_config.eth_checks.liquidation_contracts)
+            .any(|address| address.eq_ignore_ascii_case(to))
+    }
+    
+    // Settles a peer receipt's verification. A successful one is checked against the chain while
+    // check_receipts is on, and stays Pending while the check can't run; others need no check.
+    fn check_receipt_against_chain(&mut self, opp_id: &str, data: &mut ReceiptData) {
+        if !self.node_config.eth_checks.check_receipts || data.summary().status != "Success" {
+            data.verification = ReceiptVerification::Verified;
+            return;
+        }
+        let verification = self.verify_receipt_onchain(&data.receipt);
+        data.chain_verification = Some(verification.clone());
+        data.verification = match verification {
+            ChainVerification::Confirmed { .. } => ReceiptVerification::Verified,
+            ChainVerification::Unchecked { .. } => ReceiptVerification::Pending,
+            rejected => {
+                println!("\n⚠️  Receipt for {} by {} claims success but its transaction is {:?} on-chain",
+                    opp_id, data.executor_node, rejected);
+                self.record_journal("ReceiptRejected", serde_json::json!({
+                    "opp_id": opp_id,
+                    "executor": data.executor_node,
+                    "verification": rejected,
+                }));
+                data.retention = ReceiptRetention::Verified;
+                ReceiptVerification::Rejected { verification: rejected }
+            }
+        };
+    }
+    
+    // Compares recorded operator capital with the wallet's ERC-20 balances, at most every
+    // BALANCE_RECONCILE_INTERVAL_SECS; mismatches are reported, never auto-corrected
+    fn reconcile_balances(&mut self) {
+        let config = self.node_config.eth_checks.clone();
+        let Some(wallet) = config.wallet_address.filter(|_| config.check_balances) else {
+            return;
+        };
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if now.saturating_sub(self.last_balance_check) < BALANCE_RECONCILE_INTERVAL_SECS {
+            return;
+        }
+        self.last_balance_check = now;
+        
+        let mut assets: Vec<String> = self.available_balances.keys().cloned().collect();
+        assets.sort();
+        for asset in assets {
+            let Some(output) = self.eth_call("balances", &asset, address_calldata(ERC20_BALANCE_OF, &wallet)) else {
+                continue;
+            };
+            let Some(word) = output.get(0..32) else {
+                continue;
+            };
+            let onchain = U256::from_be_slice(word);
+            let total = self.available_balances.get(&asset)
+                .and_then(|amount| amount.parse::<U256>().ok())
+                .unwrap_or(U256::ZERO);
+            let demo = self.demo_balances.get(&asset)
+                .and_then(|amount| amount.parse::<U256>().ok())
+                .unwrap_or(U256::ZERO);
+            let recorded = total.saturating_sub(demo);
+            let shortfall = onchain < recorded;
+            if shortfall && !self.balance_reconciliation.get(&asset).map_or(false, |previous| previous.shortfall) {
+                println!("\n⚠️  Recorded {} capital {} exceeds the wallet's on-chain balance {}", asset, recorded, onchain);
+                self.record_journal("BalanceShortfall", serde_json::json!({
+                    "asset": asset,
+                    "recorded": recorded.to_string(),
+                    "onchain": onchain.to_string(),
+                }));
+            }
+            self.balance_reconciliation.insert(asset, BalanceReconciliation {
+                onchain: onchain.to_string(),
+                recorded: recorded.to_string(),
+                checked_at: now.to_string(),
+                shortfall,
+            });
+        }
+    }
+    
+    // Everything we hold on one opportunity, each record re-serialized from its parsed form
+    fn bundle_items(&self, opp_id: &str, lines: &[StatementLine]) -> Result<Vec<BundleItem>, String> {
+        let unreadable = |kind: BundleItemKind| format!("Stored {:?} for {} is unreadable", kind, opp_id);
+        let mut items = Vec::new();
+        if let Some(data) = self.active_opportunities.get(opp_id) {
+            let opportunity = serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+                .and_then(|opportunity| serde_json::to_string(&opportunity))
+                .map_err(|_| unreadable(BundleItemKind::Opportunity))?;
+            items.push(BundleItem::new(BundleItemKind::Opportunity, opp_id, opportunity));
+            for claim in &data.claiming_finders {
+                let claim = serde_json::to_string(claim).map_err(|_| unreadable(BundleItemKind::FinderClaim))?;
+                items.push(BundleItem::new(BundleItemKind::FinderClaim, opp_id, claim));
+            }
+        }
+        for intent in self.submitted_intents.get(opp_id).into_iter().flatten() {
+            let intent = intent.parsed_intent()
+                .and_then(|intent| serde_json::to_string(&intent).ok())
+                .ok_or_else(|| unreadable(BundleItemKind::Intent))?;
+            items.push(BundleItem::new(BundleItemKind::Intent, opp_id, intent));
+        }
+        if let Some(data) = self.execution_receipts.get(opp_id) {
+            let receipt = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt)
+                .and_then(|receipt| serde_json::to_string(&receipt))
+                .map_err(|_| unreadable(BundleItemKind::Receipt))?;
+            items.push(BundleItem::new(BundleItemKind::Receipt, opp_id, receipt));
+        }
+        if let Some(archived) = self.receipt_history.get(opp_id) {
+            let archived = serde_json::to_string(archived).map_err(|_| unreadable(BundleItemKind::ArchivedReceipt))?;
+            items.push(BundleItem::new(BundleItemKind::ArchivedReceipt, opp_id, archived));
+        }
+        if let Some(expiry) = self.expired_opportunities.get(opp_id) {
+            let expiry = serde_json::to_string(expiry).map_err(|_| unreadable(BundleItemKind::Expiry))?;
+            items.push(BundleItem::new(BundleItemKind::Expiry, opp_id, expiry));
+        }
+        for line in lines.iter().filter(|line| line.opp_id == opp_id) {
+            let line = serde_json::to_string(line).map_err(|_| unreadable(BundleItemKind::StatementLine))?;
+            items.push(BundleItem::new(BundleItemKind::StatementLine, opp_id, line));
+        }
+        Ok(items)
+    }
+    
+    // Whether `node` took part in `opp_id`: as its finder, as a provider to or from us, or in
+    // its execution
+    fn took_part(&self, opp_id: &str, node: &str) -> bool {
+        let executor = |executor: &str| if executor == "artemis-bot" { our().node } else { executor.to_string() };
+        self.active_opportunities.get(opp_id).is_some_and(|data| data.finder_node == node)
+            || self.our_intents.get(opp_id).is_some_and(|outstanding| outstanding.sent_to.iter().any(|peer| peer == node))
+            || self.submitted_intents.get(opp_id).is_some_and(|intents| intents.iter().any(|intent| intent.submitter_node == node))
+            || self.execution_receipts.get(opp_id).is_some_and(|data| {
+                executor(&data.executor_node) == node
+                    || serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt)
+                        .is_ok_and(|receipt| receipt.used_amounts.iter().any(|used| used.node_id == node))
+            })
+            || self.receipt_history.get(opp_id).is_some_and(|archived| {
+                executor(&archived.executor_node) == node || archived.participants.iter().any(|participant| participant == node)
+            })
+    }
+    
+    // Our records of `opp_id` in the sections asked for, as both sides share them with each
+    // other: neutral fields only, intents and statement lines between us and `counterparty`
+    // only, and every other provider's figures bucketed
+    fn shared_records(&self, opp_id: &str, counterparty: &str, sections: &[RecordSection]) -> Vec<SharedRecord> {
+        let our_node = our().node;
+        let parties = [our_node.as_str(), counterparty];
+        let record = |section: RecordSection, key: &str, canonical: String, redacted: bool| SharedRecord {
+            section,
+            key: key.to_string(),
+            canonical,
+            redacted,
+            signatures: Vec::new(),
+        };
+        let mut records = Vec::new();
+        
+        if sections.contains(&RecordSection::Opportunity) {
+            let opportunity = self.active_opportunities.get(opp_id)
+                .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok())
+                .and_then(|opportunity| serde_json::to_string(&opportunity).ok());
+            if let Some(opportunity) = opportunity {
+                records.push(record(RecordSection::Opportunity, opp_id, opportunity, false));
+            }
+        }
+        
+        if sections.contains(&RecordSection::Intents) {
+            // Keyed by the providing node, which both sides know it under
+            let ours = self.our_intents.get(opp_id)
+                .filter(|outstanding| outstanding.sent_to.iter().any(|peer| peer == counterparty))
+                .and_then(|outstanding| serde_json::to_string(&outstanding.intent).ok());
+            if let Some(intent) = ours {
+                records.push(record(RecordSection::Intents, &our_node, intent, false));
+            }
+            let theirs = self.submitted_intents.get(opp_id).into_iter().flatten()
+                .filter(|intent| intent.submitter_node == counterparty)
+                .find_map(|intent| intent.parsed_intent().and_then(|intent| serde_json::to_string(&intent).ok()));
+            if let Some(intent) = theirs {
+                records.push(record(RecordSection::Intents, counterparty, intent, false));
+            }
+        }
+        
+        if sections.contains(&RecordSection::Receipt) {
+            let executor = |executor: &str| if executor == "artemis-bot" { our_node.clone() } else { executor.to_string() };
+            let live = self.execution_receipts.get(opp_id).and_then(|data| {
+                let receipt = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt).ok()?;
+                let summary = data.summary();
+                Some(ReceiptView {
+                    opp_id: opp_id.to_string(),
+                    executor: executor(&data.executor_node),
+                    status: summary.status,
+                    tx_hash: summary.tx_hash,
+                    block_number: summary.block_number,
+                    total_proceeds: summary.total_proceeds,
+                    gas_paid_usdc: summary.gas_paid_usdc,
+                    used_amounts: receipt.used_amounts,
+                    allocation: receipt.allocation,
+                })
+            });
+            let view = live.or_else(|| self.receipt_history.get(opp_id).map(|archived| ReceiptView {
+                opp_id: opp_id.to_string(),
+                executor: executor(&archived.executor_node),
+                status: archived.status.clone(),
+                tx_hash: archived.tx_hash.clone(),
+                block_number: archived.block_number,
+                total_proceeds: archived.total_proceeds.clone(),
+                gas_paid_usdc: archived.gas_paid_usdc.clone(),
+                used_amounts: archived.capital_usage.clone(),
+                allocation: None,
+            }));
+            if let Some(mut view) = view {
+                let redacted = view.redact(&parties);
+                if let Ok(canonical) = serde_json::to_string(&view) {
+                    records.push(record(RecordSection::Receipt, opp_id, canonical, redacted));
+                }
+            }
+        }
+        
+        if sections.contains(&RecordSection::Settlement) {
+            let lines = self.execution_lines(0, u64::MAX).into_iter()
+                .filter(|line| line.opp_id == opp_id)
+                .filter(|line| parties.contains(&line.provider.as_str()) && parties.contains(&line.executor.as_str()));
+            for line in lines {
+                if let Ok(canonical) = serde_json::to_string(&line) {
+                    records.push(record(RecordSection::Settlement, &line.provider, canonical, false));
+                }
+            }
+        }
+        records
+    }
+    
+    // One line per provider whose capital an execution recorded in [from, to) used, live or
+    // archived, in block order
+    fn execution_lines(&self, from: u64, to: u64) -> Vec<StatementLine> {
+        let our_node = our().node;
+        let live = self.execution_receipts.iter().filter_map(|(opp_id, data)| {
+            let receipt = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt).ok()?;
+            Some((opp_id, data.executor_node.as_str(), receipt.status.name().to_string(), receipt.tx_hash,
+                receipt.block_number, data.verified_at, receipt.used_amounts))
+        });
+        let archived = self.receipt_history.iter().map(|(opp_id, archived)| {
+            (opp_id, archived.executor_node.as_str(), archived.status.clone(), archived.tx_hash.clone(),
+                archived.block_number, archived.verified_at, archived.capital_usage.clone())
+        });
+        
+        let mut lines = Vec::new();
+        for (opp_id, executor, status, tx_hash, block_number, recorded_at, usage) in live.chain(archived) {
+            if recorded_at < from || recorded_at >= to {
+                continue;
+            }
+            // Our own bridge's receipts are stored under its name rather than ours
+            let executor = if executor == "artemis-bot" { our_node.as_str() } else { executor };
+            for used in usage {
+                if used.node_id == executor {
+                    continue;
+                }
+                lines.push(StatementLine {
+                    opp_id: opp_id.clone(),
+                    block_number,
+                    tx_hash: tx_hash.clone(),
+                    status: status.clone(),
+                    executor: executor.to_string(),
+                    provider: used.node_id,
+                    asset: used.asset,
+                    amount_used: used.amount_used,
+                    share_owed: used.profit_share,
+                });
+            }
+        }
+        lines.sort_by(|a, b| (a.block_number, &a.opp_id, &a.provider).cmp(&(b.block_number, &b.opp_id, &b.provider)));
+        lines
+    }
+    
+    // Every execution between us and `peer` recorded in [from, to), live or archived, where one
+    // executed with the other's capital
+    fn build_statement(&self, peer: &str, from: u64, to: u64) -> SettlementStatement {
+        let mut parties = vec![our().node, peer.to_string()];
+        parties.sort();
+        let lines: Vec<StatementLine> = self.execution_lines(from, to).into_iter()
+            .filter(|line| parties.contains(&line.provider) && parties.contains(&line.executor))
+            .collect();
+        
+        let mut owed: BTreeMap<String, U256> = parties.iter().map(|p| (p.clone(), U256::ZERO)).collect();
+        for line in &lines {
+            let share = line.share_owed.parse::<U256>().unwrap_or(U256::ZERO);
+            if let Some(total) = owed.get_mut(&line.provider) {
+                *total += share;
+            }
+        }
+        let (first, second) = (owed[&parties[0]], owed[&parties[1]]);
+        let (net_creditor, net_amount) = match first.cmp(&second) {
+            std::cmp::Ordering::Greater => (Some(parties[0].clone()), first - second),
+            std::cmp::Ordering::Less => (Some(parties[1].clone()), second - first),
+            std::cmp::Ordering::Equal => (None, U256::ZERO),
+        };
         
-        // Initialize node configuration
-        self.node_config.node_id = our().node.clone();
-        self.active_strategy = Some("aave-liquidation".to_string());
+        SettlementStatement {
+            parties,
+            from,
+            to,
+            lines,
+            owed_to: owed.into_iter().map(|(party, amount)| (party, amount.to_string())).collect(),
+            net_creditor,
+            net_amount: net_amount.to_string(),
+        }
+    }
+    
+    // Replays the allocation an executor declared on its receipt. Our own intent, if we sent it
+    // to that executor, must be among the candidates it considered.
+    fn check_declared_allocation(&self, opp_id: &str, executor: &str, receipt_json: &str) -> Option<AllocationMismatch> {
+        let receipt: AaveLiquidationReceipt = serde_json::from_str(receipt_json).ok()?;
+        let record = receipt.allocation?;
+        let known: Vec<AllocationCandidate> = self.our_intents.get(opp_id)
+            .filter(|outstanding| outstanding.sent_to.iter().any(|peer| peer == executor))
+            .and_then(|outstanding| serde_json::to_string(&outstanding.intent).ok())
+            .map(|intent_json| AllocationCandidate {
+                intent_hash: intent_hash(&intent_json),
+                provider: our().node,
+                max_amount: String::new(),
+                fee_bps: 0,
+                received_at: 0,
+            })
+            .into_iter()
+            .collect();
+        verify_allocation(&record, &known).err()
+    }
+    
+    // Shrinks our intents on the victim's opportunities to zero once its position is gone; an
+    // executor that already locked the allocation rejects this and the commitment stands
+    fn withdraw_intents_for_victim(&mut self, borrower: &str, reason: &str) {
+        let withdrawn: Vec<String> = self.our_intents.iter()
+            .filter(|(opp_id, outstanding)| outstanding.intent.max_amount != "0"
+                && self.active_opportunities.get(*opp_id)
+                    .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok())
+                    .map_or(false, |opportunity| opportunity.victim_address.eq_ignore_ascii_case(borrower)))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        
+        for opp_id in withdrawn {
+            self.withdraw_intent(&opp_id, reason);
+        }
+    }
+    
+    // Amends our intent for `opp_id` to zero and releases its lock
+    fn withdraw_intent(&mut self, opp_id: &str, reason: &str) {
+        let Some(outstanding) = self.our_intents.get_mut(opp_id) else {
+            return;
+        };
+        outstanding.intent.max_amount = "0".to_string();
+        let recipients = outstanding.sent_to.clone();
+        self.release_commitment(opp_id);
+        self.capital_reservations.remove(opp_id);
+        println!("   Withdrawing our intent for {} ({})", opp_id, reason);
+        let amendment = MevMessage::IntentAmendment {
+            opp_id: opp_id.to_string(),
+            new_max_amount: "0".to_string(),
+        };
+        for peer in &recipients {
+            self.send_to_peer(peer, amendment.handler(), &Self::message_json(&amendment));
+        }
+    }
+    
+    // Appends one of our public records to the mirror log and streams it to active buddies.
+    // Nothing is logged while no buddy has accepted; the first to accept gets a seeded log.
+    fn mirror_record(&mut self, kind: MirrorRecordKind, key: &str, record: String) {
+        let buddies: Vec<String> = self.mirror_buddies.iter()
+            .filter(|(_, agreement)| agreement.active())
+            .map(|(buddy, _)| buddy.clone())
+            .collect();
+        if buddies.is_empty() {
+            return;
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let entry = self.mirror_log.append(kind, key, record, now);
+        for buddy in buddies {
+            self.stream_mirror_entries(&buddy, vec![entry.clone()], false);
+        }
+    }
+    
+    // Logs a stored receipt that names us, and the statement lines it puts us on
+    fn mirror_receipt(&mut self, opp_id: &str) {
+        self.collection_recency.touch(opp_id);
+        for (kind, key, record) in self.receipt_mirror_records(opp_id) {
+            self.mirror_record(kind, &key, record);
+        }
+    }
+    
+    fn receipt_mirror_records(&self, opp_id: &str) -> Vec<(MirrorRecordKind, String, String)> {
+        let our_node = our().node;
+        let Some(data) = self.execution_receipts.get(opp_id) else {
+            return Vec::new();
+        };
+        let lines: Vec<StatementLine> = self.execution_lines(0, u64::MAX).into_iter()
+            .filter(|line| line.opp_id == opp_id && (line.executor == our_node || line.provider == our_node))
+            .collect();
+        let names_us = data.executor_node == "artemis-bot"
+            || !lines.is_empty()
+            || data.our_proceeds.parse::<U256>().is_ok_and(|share| !share.is_zero());
+        if !names_us {
+            return Vec::new();
+        }
+        let receipt = serde_json::json!({
+            "executor_node": data.executor_node,
+            "receipt": data.receipt,
+            "our_proceeds": data.our_proceeds,
+            "verified_at": data.verified_at,
+        });
+        std::iter::once((MirrorRecordKind::Receipt, opp_id.to_string(), receipt.to_string()))
+            .chain(lines.into_iter().map(|line| (
+                MirrorRecordKind::StatementLine,
+                format!("{}/{}", line.opp_id, line.provider),
+                serde_json::to_string(&line).unwrap_or_default(),
+            )))
+            .collect()
+    }
+    
+    // Fills a fresh log with the records we hold now, so a new buddy starts with a full copy
+    fn seed_mirror_log(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut records: Vec<(MirrorRecordKind, String, String)> = self.originated_by_seq.iter()
+            .filter_map(|(seq, opp_id)| {
+                let data = self.active_opportunities.get(opp_id)?;
+                let record = serde_json::json!({ "finder_seq": seq, "opportunity": data.opportunity });
+                Some((MirrorRecordKind::Opportunity, opp_id.clone(), record.to_string()))
+            })
+            .collect();
+        let mut receipts: Vec<&String> = self.execution_receipts.keys().collect();
+        receipts.sort();
+        for opp_id in receipts {
+            records.extend(self.receipt_mirror_records(opp_id));
+        }
+        for (kind, key, record) in records {
+            self.mirror_log.append(kind, &key, record, now);
+        }
+    }
+    
+    fn send_mirror_control(&self, peer: &str, action: MirrorAction) {
+        let control = MevMessage::MirrorControl { action, from_seq: None, to_seq: None };
+        self.send_to_peer(peer, control.handler(), &Self::message_json(&control));
+    }
+    
+    fn stream_mirror_entries(&self, peer: &str, entries: Vec<MirrorEntry>, restore: bool) {
+        let batches: Vec<&[MirrorEntry]> = entries.chunks(MAX_MIRROR_BATCH).collect();
+        let count = batches.len();
+        for (index, batch) in batches.into_iter().enumerate() {
+            let message = MevMessage::MirrorEntries {
+                entries: batch.to_vec(),
+                restore,
+                last: index + 1 == count,
+            };
+            self.send_to_peer(peer, message.handler(), &Self::message_json(&message));
+        }
+        if restore && count == 0 {
+            let message = MevMessage::MirrorEntries { entries: Vec::new(), restore: true, last: true };
+            self.send_to_peer(peer, message.handler(), &Self::message_json(&message));
+        }
+    }
+    
+    // Rebuilds one record from a buddy's copy. Anything we hold locally is newer than the copy
+    // and is kept; restored receipts are stored live, everything is listed as restored.
+    fn restore_mirror_entry(&mut self, buddy: &str, entry: MirrorEntry, signatures_verified: Option<bool>) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let slot = entry.slot();
+        let held_locally = match entry.kind {
+            MirrorRecordKind::Opportunity => self.active_opportunities.contains_key(&entry.key)
+                || self.expired_opportunities.contains_key(&entry.key),
+            MirrorRecordKind::Receipt => self.execution_receipts.contains_key(&entry.key)
+                || self.receipt_history.contains_key(&entry.key),
+            MirrorRecordKind::StatementLine => false,
+        } || self.restored_records.get(&slot).is_some_and(|restored| restored.recorded_at >= entry.recorded_at);
+        let Some(restore) = self.mirror_restore.as_mut() else {
+            return;
+        };
+        if held_locally {
+            restore.kept_local += 1;
+            return;
+        }
+        if signatures_verified == Some(false) {
+            restore.bad_signatures += 1;
+            return;
+        }
+        restore.restored += 1;
+        
+        let record: serde_json::Value = serde_json::from_str(&entry.record).unwrap_or_default();
+        match entry.kind {
+            // Our sequence must not run backwards for peers that saw it before we lost state
+            MirrorRecordKind::Opportunity => {
+                if let Some(seq) = record["finder_seq"].as_u64() {
+                    self.next_finder_seq = self.next_finder_seq.max(seq);
+                }
+            }
+            MirrorRecordKind::Receipt => {
+                let receipt = record["receipt"].as_str().unwrap_or_default().to_string();
+                let executor = record["executor_node"].as_str().unwrap_or_default().to_string();
+                let our_proceeds = record["our_proceeds"].as_str().unwrap_or("0").to_string();
+                let mut data = ReceiptData::new(receipt, executor, our_proceeds);
+                // Numeric now; older mirrors hold the decimal string
+                if let Some(verified_at) = record["verified_at"].as_u64()
+                    .or_else(|| record["verified_at"].as_str().and_then(|at| at.parse().ok())) {
+                    data.verified_at = verified_at;
+                }
+                self.execution_receipts.insert(entry.key.clone(), data);
+            }
+            MirrorRecordKind::StatementLine => {}
+        }
+        self.restored_records.insert(slot, RestoredRecord {
+            kind: entry.kind,
+            key: entry.key,
+            record: entry.record,
+            recorded_at: entry.recorded_at,
+            restored_from: buddy.to_string(),
+            restored_at: now,
+            signatures_verified,
+            reverification: Reverification::Pending,
+        });
+    }
+    
+    // Checks restored receipts against the chain, a few per tick, and settles the opportunity
+    // and statement lines restored with each receipt the same way
+    fn reverify_restored_records(&mut self) {
+        if !self.node_config.eth_checks.check_receipts {
+            return;
+        }
+        let pending: Vec<String> = self.restored_records.values()
+            .filter(|restored| restored.kind == MirrorRecordKind::Receipt && restored.reverification == Reverification::Pending)
+            .map(|restored| restored.key.clone())
+            .take(10)
+            .collect();
+        for opp_id in pending {
+            let receipt = self.execution_receipts.get(&opp_id).map(|data| data.receipt.clone());
+            let outcome = match receipt.map(|receipt| self.verify_receipt_onchain(&receipt)) {
+                Some(ChainVerification::Confirmed { .. }) => Reverification::Confirmed,
+                Some(ChainVerification::Unchecked { .. }) => continue,
+                Some(_) => Reverification::Contradicted,
+                // Archived since it was restored; a stored summary is live enough
+                None => Reverification::Confirmed,
+            };
+            let prefix = format!("{}/", opp_id);
+            for restored in self.restored_records.values_mut().filter(|restored| {
+                restored.key == opp_id || restored.key.starts_with(&prefix)
+            }) {
+                restored.reverification = outcome;
+            }
+            if outcome == Reverification::Contradicted {
+                println!("\n⚠️  Restored receipt for {} does not match the chain", opp_id);
+                self.record_journal("RestoredRecordContradicted", serde_json::json!({ "opp_id": opp_id }));
+            }
+        }
+    }
+    
+    // Reputation tier of a peer we could leave victims to; None while it's blocked or unreachable
+    fn coverage_tier(&self, peer: &str) -> Option<u8> {
+        let info = self.known_peers.get(peer)?;
+        if info.unreachable_since.is_some() || self.node_config.peer_blocked(peer).is_some() {
+            return None;
+        }
+        Some(self.finder_tier(peer))
+    }
+    
+    // Per tick: ends assignments whose peer went quiet, withdrew or no longer qualifies, leaves
+    // newly covered victims to the best peer, and advertises what we monitor to every peer
+    fn refresh_monitoring_coverage(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let policy = self.node_config.monitoring_policy.clone();
+        if !policy.enabled {
+            if !self.monitoring_coverage.assignments.is_empty() {
+                self.monitoring_coverage.assignments.clear();
+                self.push_bridge_delegation(false);
+            }
+            return;
+        }
+        let head = self.current_block().unwrap_or(0);
+        self.monitoring_coverage.count_delegated_blocks(head);
+        self.monitoring_coverage.distrusted.retain(|_, until| *until > now);
+        
+        let mut ended = Vec::new();
+        for (victim, assignment) in &self.monitoring_coverage.assignments {
+            let reason = if !self.watched_positions.contains_key(victim) {
+                None
+            } else if self.coverage_tier(&assignment.peer).map_or(true, |tier| tier < policy.min_peer_tier) {
+                Some(FallbackReason::PeerUnavailable)
+            } else if !self.monitoring_coverage.peers.get(&assignment.peer).is_some_and(|coverage| coverage.monitoring.contains(victim)) {
+                Some(FallbackReason::PeerWithdrew)
+            } else if assignment.silent_blocks(head) > policy.stale_after_blocks {
+                Some(FallbackReason::Stale)
+            } else {
+                continue;
+            };
+            ended.push((victim.clone(), reason));
+        }
+        for (victim, reason) in ended {
+            match reason {
+                Some(reason) => self.fall_back_to_local(&victim, reason, None),
+                // No longer on our watchlist, so there's nothing to poll
+                None => {
+                    self.monitoring_coverage.assignments.remove(&victim);
+                }
+            }
+        }
+        
+        let mut unassigned: Vec<String> = self.watched_positions.keys()
+            .filter(|victim| !self.monitoring_coverage.assignments.contains_key(*victim))
+            .cloned()
+            .collect();
+        unassigned.sort();
+        for victim in unassigned {
+            let best = self.monitoring_coverage
+                .candidates(&victim, now, |peer| self.coverage_tier(peer), policy.min_peer_tier)
+                .into_iter()
+                .next();
+            if let Some((_, peer)) = best {
+                self.monitoring_coverage.assignments.insert(victim, CoverageAssignment::new(&peer, now, head));
+            }
+        }
+        self.push_bridge_delegation(false);
+        
+        // What we poll ourselves; victims left to others aren't ours to vouch for
+        let mut monitoring: Vec<String> = self.watched_positions.keys()
+            .filter(|victim| !self.monitoring_coverage.assignments.contains_key(*victim))
+            .cloned()
+            .collect();
+        monitoring.sort();
+        monitoring.truncate(policy.max_advertised);
+        let peers: Vec<String> = self.known_peers.keys().cloned().collect();
+        for peer in peers {
+            let subscribed = self.monitoring_coverage.delegated_to(&peer);
+            let advert = MevMessage::MonitoringCoverage {
+                monitoring: monitoring.clone(),
+                subscribed: subscribed.into_iter().collect(),
+                block: head,
+            };
+            self.send_to_peer(&peer, advert.handler(), &Self::message_json(&advert));
+        }
+    }
+    
+    // Takes `victim` back from its peer; our bridge polls it again from the next block
+    fn fall_back_to_local(&mut self, victim: &str, reason: FallbackReason, detail: Option<String>) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let head = self.current_block().unwrap_or(0);
+        let Some(fallback) = self.monitoring_coverage.fall_back(victim, reason, head, now, detail) else {
+            return;
+        };
+        println!("\n📡 Polling {} locally again: {:?} from {}", victim, reason, fallback.peer);
+        self.record_journal("MonitoringFallback", serde_json::json!(fallback));
+        self.push_bridge_delegation(false);
+    }
+    
+    // Tells the bridge which victims to leave to peers, when that changed or it just connected
+    fn push_bridge_delegation(&mut self, force: bool) {
+        let delegated: BTreeSet<String> = self.monitoring_coverage.assignments.keys().cloned().collect();
+        if !force && delegated == self.monitoring_coverage.sent_to_bridge {
+            return;
+        }
+        let message = serde_json::json!({
+            "type": "MonitoringAssignment",
+            "delegated": delegated,
+            "spot_check_bps": self.node_config.monitoring_policy.spot_check_bps,
+        });
+        if let Err(e) = self.send_to_artemis_json_sync(&message.to_string()) {
+            println!("   Monitoring assignment not sent: {}", e);
+            return;
+        }
+        self.monitoring_coverage.sent_to_bridge = delegated;
+    }
+    
+    // Our bridge read `victim`: a spot check if it's delegated, otherwise a read our subscribers
+    // get relayed
+    fn on_local_position_read(&mut self, victim: &str) {
+        if !self.node_config.monitoring_policy.enabled {
+            return;
+        }
+        let Some(position) = self.watched_positions.get(victim).cloned() else {
+            return;
+        };
+        
+        if let Some(assignment) = self.monitoring_coverage.assignments.get_mut(victim) {
+            assignment.spot_checks += 1;
+            self.monitoring_coverage.spot_checks += 1;
+            // Only a relayed read of a nearby block says anything about the peer
+            let relayed = assignment.last_health_factor.clone()
+                .zip(assignment.last_update_block)
+                .filter(|(_, block)| block.abs_diff(position.last_checked_block) <= 2);
+            let Some((relayed_health_factor, relayed_block)) = relayed else {
+                return;
+            };
+            let tolerance_bps = self.node_config.monitoring_policy.health_factor_tolerance_bps;
+            if health_factors_agree(&position.last_health_factor, &relayed_health_factor, tolerance_bps) {
+                return;
+            }
+            let peer = assignment.peer.clone();
+            self.monitoring_coverage.spot_check_failures += 1;
+            let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+            self.monitoring_coverage.distrusted.insert(peer.clone(), now + self.node_config.monitoring_policy.distrust_secs);
+            self.peer_reputation.entry(peer.clone()).or_default().note_refused();
+            let detail = format!("local {} at block {}, relayed {} at block {}",
+                position.last_health_factor, position.last_checked_block, relayed_health_factor, relayed_block);
+            // Nothing else that peer covers for us can be trusted either
+            for victim in self.monitoring_coverage.delegated_to(&peer) {
+                self.fall_back_to_local(&victim, FallbackReason::Inaccurate, Some(detail.clone()));
+            }
+            return;
+        }
+        
+        let subscribers = self.monitoring_coverage.subscribers(victim);
+        if subscribers.is_empty() {
+            return;
+        }
+        let update = MevMessage::PositionUpdate {
+            borrower: position.borrower.clone(),
+            collateral_asset: position.collateral_asset.clone(),
+            debt_asset: position.debt_asset.clone(),
+            health_factor: position.last_health_factor.clone(),
+            debt_amount: position.debt_amount.clone(),
+            block: position.last_checked_block,
+        };
+        for peer in &subscribers {
+            self.send_to_peer(peer, update.handler(), &Self::message_json(&update));
+        }
+        self.monitoring_coverage.updates_relayed += subscribers.len() as u64;
+    }
+    
+    // Peers a receipt moved money with besides us: its executor and the providers it drew from
+    fn receipt_counterparties(data: &ReceiptData) -> Vec<String> {
+        let me = our().node;
+        let mut peers: Vec<String> = serde_json::from_str::<AaveLiquidationReceipt>(&data.receipt)
+            .map(|receipt| receipt.used_amounts.into_iter().map(|usage| usage.node_id).collect())
+            .unwrap_or_default();
+        peers.push(data.executor_node.clone());
+        peers.retain(|peer| *peer != me && peer != "artemis-bot");
+        peers.sort();
+        peers.dedup();
+        peers
+    }
+    
+    // Receipts whose outcome isn't settled yet: still confirming, awaiting their chain check, or
+    // under an open dispute
+    fn receipt_in_flight(&self, data: &ReceiptData) -> bool {
+        data.verification == ReceiptVerification::Pending
+            || matches!(data.finality, ReceiptFinality::Confirming { .. })
+            || data.is_disputed()
+    }
+    
+    // Whether we still hold a settlement position with any of the receipt's counterparties
+    fn receipt_position_open(&self, data: &ReceiptData) -> bool {
+        Self::receipt_counterparties(data).iter().any(|peer| self.settlement_ledger.contains_key(peer))
+    }
+    
+    // Reduces settled receipts past the retention age to summaries, bounded per tick. Receipts in
+    // flight or with a settlement position still open are kept whatever their age.
+    fn archive_receipts(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let max_age = self.node_config.receipt_archive_after_secs;
+        
+        let pinned: HashSet<String> = self.execution_receipts.iter()
+            .filter(|(_, data)| self.receipt_in_flight(data) || self.receipt_position_open(data))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        let mut archivable = Vec::new();
+        for (opp_id, data) in self.execution_receipts.iter_mut() {
+            if pinned.contains(opp_id) {
+                continue;
+            }
+            let age = now.saturating_sub(data.verified_at);
+            if data.retention == ReceiptRetention::Settled && age >= max_age {
+                data.retention = ReceiptRetention::Archivable;
+            }
+            if data.retention == ReceiptRetention::Archivable {
+                archivable.push(opp_id.clone());
+            }
+        }
+        archivable.sort();
+        
+        for opp_id in archivable.into_iter().take(self.node_config.receipt_archive_budget) {
+            if let Some(data) = self.execution_receipts.remove(&opp_id) {
+                let summary = data.summarize(&opp_id, now.to_string());
+                println!("🗄️  Archived receipt for {} (status {}, tx {})", opp_id, summary.status, summary.tx_hash);
+                self.record_journal("ReceiptArchived", serde_json::json!({
+                    "opp_id": opp_id,
+                    "summary": summary,
+                }));
+                self.receipt_history.insert(opp_id, summary);
+            }
+        }
+    }
+    
+    // Whether capital of ours hangs on the opportunity: an intent out, a lock, or a reservation
+    fn holds_our_capital(&self, opp_id: &str) -> bool {
+        self.our_intents.contains_key(opp_id)
+            || self.capital_commitments.contains_key(opp_id)
+            || self.capital_reservations.contains_key(opp_id)
+    }
+    
+    // Holds opportunities, intents and receipts to collection_caps, least recently touched and
+    // finished first. Evicted opportunities take everything keyed by them; evicted receipts keep
+    // their summary, as archiving does. Kept regardless: anything holding our capital or sent to
+    // our bridge, and receipts still confirming, awaiting their chain check or disputed.
+    fn enforce_collection_caps(&mut self) {
+        let caps = self.node_config.collection_caps.clone();
+        
+        let excess = self.active_opportunities.len().saturating_sub(caps.opportunities);
+        if excess > 0 {
+            let candidates = self.active_opportunities.iter()
+                .filter(|(opp_id, _)| !self.holds_our_capital(opp_id) && !self.pending_executions.contains_key(*opp_id))
+                .map(|(opp_id, data)| (opp_id.clone(), matches!(data.status,
+                    OpportunityStatus::Settled | OpportunityStatus::Failed | OpportunityStatus::Expired)))
+                .collect();
+            for opp_id in eviction_order(candidates, &self.collection_recency).into_iter().take(excess) {
+                let (data, intents, _) = self.drop_opportunity_state(&opp_id);
+                self.note_eviction("opportunities", &opp_id, serde_json::json!({
+                    "status": data.map(|data| data.status),
+                    "intents": intents.len(),
+                }));
+            }
+        }
+        
+        let mut intents_held: usize = self.submitted_intents.values().map(Vec::len).sum();
+        if intents_held > caps.intents {
+            let candidates = self.submitted_intents.keys()
+                .filter(|opp_id| !self.holds_our_capital(opp_id) && !self.pending_executions.contains_key(*opp_id))
+                .map(|opp_id| (opp_id.clone(), self.active_opportunities.get(opp_id)
+                    .map_or(true, |data| !data.status.dispatchable() && data.status != OpportunityStatus::Executing)))
+                .collect();
+            for opp_id in eviction_order(candidates, &self.collection_recency) {
+                if intents_held <= caps.intents {
+                    break;
+                }
+                let intents = self.submitted_intents.remove(&opp_id).unwrap_or_default();
+                intents_held -= intents.len();
+                self.note_eviction("intents", &opp_id, serde_json::json!({ "intents": intents.len() }));
+            }
+        }
+        
+        let excess = self.execution_receipts.len().saturating_sub(caps.receipts);
+        if excess > 0 {
+            let candidates = self.execution_receipts.iter()
+                .filter(|(_, data)| !self.receipt_in_flight(data))
+                .map(|(opp_id, data)| (opp_id.clone(), data.retention != ReceiptRetention::Verified))
+                .collect();
+            let now = Self::current_timestamp();
+            for opp_id in eviction_order(candidates, &self.collection_recency).into_iter().take(excess) {
+                let Some(data) = self.execution_receipts.remove(&opp_id) else {
+                    continue;
+                };
+                let summary = data.summarize(&opp_id, now.clone());
+                self.note_eviction("receipts", &opp_id, serde_json::json!({
+                    "status": summary.status,
+                    "retention": data.retention,
+                }));
+                self.receipt_history.insert(opp_id, summary);
+            }
+        }
+        
+        let Self { active_opportunities, submitted_intents, execution_receipts, collection_recency, .. } = self;
+        collection_recency.retain(|opp_id| active_opportunities.contains_key(opp_id)
+            || submitted_intents.contains_key(opp_id)
+            || execution_receipts.contains_key(opp_id));
+    }
+    
+    fn note_eviction(&mut self, collection: &str, opp_id: &str, detail: serde_json::Value) {
+        println!("🧹 Evicted {} for {} past its cap", collection, opp_id);
+        self.record_journal("CollectionEvicted", serde_json::json!({
+            "collection": collection,
+            "opp_id": opp_id,
+            "detail": detail,
+        }));
+    }
+    
+    // Rejects intents whose asset differs from the opportunity's repay asset (unless they opt into a swap)
+    fn check_intent_asset(&self, opp_id: &str, intent_json: &str) -> Result<(), serde_json::Value> {
+        let intent: AaveLiquidationIntent = serde_json::from_str(intent_json)
+            .map_err(|e| serde_json::json!({
+                "error": "MalformedIntent",
+                "opp_id": opp_id,
+                "reason": e.to_string(),
+            }))?;
+        let Some(opportunity) = self.active_opportunities.get(opp_id)
+            .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok()) else {
+            // Unknown opportunities are checked again by the planner before dispatch
+            return Ok(());
+        };
+        if !intent.matches_repay_asset(&opportunity) {
+            return Err(serde_json::json!({
+                "error": "AssetMismatch",
+                "opp_id": opp_id,
+                "intent_asset": intent.asset,
+                "repay_asset": opportunity.repay_asset,
+            }));
+        }
+        Ok(())
+    }
+    
+    // Refuses an intent asking a higher bonus than the stored opportunity offers. Not a strike:
+    // the finder may have re-priced the opportunity after the intent was made.
+    fn check_intent_bonus(&self, opp_id: &str, intent_json: &str) -> Result<(), serde_json::Value> {
+        let Ok(intent) = serde_json::from_str::<AaveLiquidationIntent>(intent_json) else {
+            return Ok(());
+        };
+        let Some(opportunity) = self.active_opportunities.get(opp_id)
+            .and_then(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok()) else {
+            return Ok(());
+        };
+        if !intent.accepts_bonus(&opportunity) {
+            return Err(serde_json::json!({
+                "error": "BonusMismatch",
+                "opp_id": opp_id,
+                "intent_min_bonus_bps": intent.min_bonus_bps,
+                "opportunity_min_bonus_bps": opportunity.min_bonus_bps,
+            }));
+        }
+        Ok(())
+    }
+    
+    fn recording_active(&self) -> bool {
+        self.recording.borrow().as_ref().is_some_and(|recording| recording.is_active())
+    }
+    
+    // Adds a frame to the running recording, if any; announces it loudly when a cap stops it
+    fn record_frame(&self, boundary: Boundary, counterparty: Option<String>, label: &str, payload: serde_json::Value) {
+        if !self.recording_active() {
+            return;
+        }
+        let label = if label.is_empty() { payload["type"].as_str().unwrap_or("Unknown").to_string() } else { label.to_string() };
+        let frame = RecordedFrame {
+            at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            boundary,
+            counterparty,
+            label,
+            payload,
+        };
+        let mut recording = self.recording.borrow_mut();
+        if let Some(reason) = recording.as_mut().and_then(|recording| recording.capture(frame)) {
+            println!("\n⏺️  Traffic recording stopped: {} reached; export it with export_recording", reason);
+        }
+    }
+    
+    // A quiet node may reach the duration cap without any frame to notice it
+    fn end_recording_past_deadline(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let status = {
+            let mut recording = self.recording.borrow_mut();
+            let Some(recording) = recording.as_mut().filter(|recording| recording.is_active() && now >= recording.until) else {
+                return;
+            };
+            recording.stop("DurationCap", now);
+            recording.status()
+        };
+        println!("\n⏺️  Traffic recording stopped: DurationCap reached; export it with export_recording");
+        self.record_journal("RecordingStopped", status);
+    }
+    
+    // Logs a raw message to the event log and, while recording, captures it like record_frame
+    fn record_message(&self, boundary: Boundary, counterparty: Option<String>, label: &str, message: &str) {
+        let seq = self.log_event(boundary, counterparty.clone(), label, message, EventOutcome::Accepted, None);
+        if boundary == Boundary::PeerInbound {
+            self.inbound_event.set(Some(seq));
+        }
+        if !self.recording_active() {
+            return;
+        }
+        let payload = serde_json::from_str(message).unwrap_or_else(|_| serde_json::Value::String(message.to_string()));
+        self.record_frame(boundary, counterparty, label, payload);
+    }
+    
+    // Appends to the protocol event log. An inbound message that isn't JSON is logged as an error
+    // whatever the caller made of it.
+    fn log_event(&self, direction: Boundary, counterparty: Option<String>, message_type: &str, message: &str,
+                 outcome: EventOutcome, reason: Option<String>) -> u64 {
+        let parsed = serde_json::from_str::<serde_json::Value>(message).ok();
+        let inbound = matches!(direction, Boundary::PeerInbound | Boundary::ArtemisInbound);
+        let (outcome, reason) = match parsed {
+            None if inbound && !message.is_empty() && outcome == EventOutcome::Accepted =>
+                (EventOutcome::Error, Some("Body is not JSON".to_string())),
+            _ => (outcome, reason),
+        };
+        let message_type = match message_type {
+            "" => parsed.as_ref().and_then(|parsed| parsed["type"].as_str()).unwrap_or("Unknown").to_string(),
+            label => label.to_string(),
+        };
+        let config = &self.node_config.event_log;
+        let (payload, payload_bytes) = truncate_payload(message, config.payload_max_bytes);
+        self.event_log.borrow_mut().push(EventLogEntry {
+            seq: 0,
+            at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            direction,
+            counterparty,
+            message_type,
+            opp_id: parsed.as_ref().and_then(message_opp_id),
+            outcome,
+            reason,
+            payload,
+            payload_bytes,
+        }, config.capacity)
+    }
+    
+    // A handler's verdict on the peer message it is handling, once that passed the gates
+    fn mark_inbound_event(&self, outcome: EventOutcome, reason: &str) {
+        if let Some(seq) = self.inbound_event.get() {
+            self.event_log.borrow_mut().set_outcome(seq, outcome, Some(reason.to_string()));
+        }
+    }
+    
+    // For map_err in remote handlers: logs the refusal and passes the error on
+    fn reject_inbound(&self, error: String) -> String {
+        self.mark_inbound_event(EventOutcome::Rejected, &error);
+        error
+    }
+    
+    // What every remote handler does before anything else: access control, then recording the
+    // message, then screening its body
+    fn admit_peer_message(&mut self, handler: &str, message_json: &str) -> Result<(), String> {
+        self.check_sender_access(handler)?;
+        let sender = source().node;
+        self.record_message(Boundary::PeerInbound, Some(sender.clone()), handler, message_json);
+        self.screen_body(sender, handler, message_json)
+    }
+    
+    // Access control, ahead of everything else a remote handler does. Blacklisted nodes, and any
+    // node off the allowlist while allowlist_mode is on, get a PeerBlocked error rather than an ACK.
+    // Past that, announcements, broadcasts, intents and receipts draw on the sender's rate limits.
+    fn check_sender_access(&mut self, handler: &str) -> Result<(), String> {
+        let sender = source().node;
+        if let Some(reason) = self.node_config.peer_blocked(&sender) {
+            self.log_event(Boundary::PeerInbound, Some(sender.clone()), handler, "", EventOutcome::Rejected,
+                Some(format!("PeerBlocked: {}", reason)));
+            *self.blocked_messages.entry(sender).or_insert(0) += 1;
+            return Err(serde_json::json!({
+                "error": "PeerBlocked",
+                "handler": handler,
+                "reason": reason,
+            }).to_string());
+        }
+        let Some(class) = InboundClass::for_handler(handler).filter(|_| sender != our().node) else {
+            return Ok(());
+        };
+        let limits = &self.node_config.inbound_rate_limits;
+        if self.inbound_rate_limiters.entry(sender.clone()).or_default().admit(class, limits, Self::current_timestamp_ms()) {
+            return Ok(());
+        }
+        self.log_event(Boundary::PeerInbound, Some(sender.clone()), handler, "", EventOutcome::Rejected,
+            Some(format!("RateLimited: {:?}", class)));
+        self.peer_reputation.entry(sender).or_default().rate_limited += 1;
+        Err(serde_json::json!({
+            "error": "RateLimited",
+            "handler": handler,
+            "class": class,
+            "per_min": limits.per_min(class),
+        }).to_string())
+    }
+    
+    // Forgets the rate limiters of senders that are no longer peers and have been quiet for a
+    // minute; their buckets would be full again anyway
+    fn prune_rate_limiters(&mut self) {
+        let cutoff = Self::current_timestamp_ms().saturating_sub(60_000);
+        let known_peers = &self.known_peers;
+        self.inbound_rate_limiters.retain(|sender, limiter| {
+            known_peers.contains_key(sender) || limiter.last_used_ms() >= cutoff
+        });
+    }
+    
+    // Drops every known peer the access lists now block, with its queued sends and the intents
+    // it submitted to us
+    fn drop_blocked_peers(&mut self) {
+        let blocked: Vec<(String, &'static str)> = self.known_peers.keys()
+            .filter_map(|peer| self.node_config.peer_blocked(peer).map(|reason| (peer.clone(), reason)))
+            .collect();
+        for (peer, reason) in blocked {
+            self.known_peers.remove(&peer);
+            self.outbound_queues.borrow_mut().peers.remove(&peer);
+            let mut purged = 0;
+            for intents in self.submitted_intents.values_mut() {
+                let before = intents.len();
+                intents.retain(|intent| intent.submitter_node != peer);
+                purged += before - intents.len();
+            }
+            self.submitted_intents.retain(|_, intents| !intents.is_empty());
+            println!("\n🚫 Dropped peer {} ({}); purged {} of its intents", peer, reason, purged);
+            self.record_journal("PeerBlocked", serde_json::json!({
+                "peer": peer,
+                "reason": reason,
+                "purged_intents": purged,
+            }));
+        }
+    }
+    
+    // Strict pre-parse of a peer message body. A body that isn't JSON is dead-lettered and counted
+    // against the sender, who gets a MalformedBody error back rather than nothing attributable.
+    // Either way the message goes on the sender's reputation.
+    fn screen_body(&mut self, sender: String, handler: &str, message_json: &str) -> Result<(), String> {
+        let Err(error) = serde_json::from_str::<serde_json::Value>(message_json) else {
+            self.peer_reputation.entry(sender).or_default().valid_messages += 1;
+            return Ok(());
+        };
+        self.mark_inbound_event(EventOutcome::Error, &error.to_string());
+        *self.malformed_message_strikes.entry(sender.clone()).or_insert(0) += 1;
+        self.peer_reputation.entry(sender.clone()).or_default().note_malformed();
+        println!("\n⚠️  P2P: Malformed body from {} to {} ({} bytes): {}", sender, handler, message_json.len(), error);
+        self.dead_letters.push(DeadLetter {
+            from: sender,
+            handler: handler.to_string(),
+            received_at: Self::current_timestamp(),
+            body_len: message_json.len(),
+            sample: escaped_sample(message_json),
+            error: error.to_string(),
+        });
+        if self.dead_letters.len() > DEAD_LETTER_CAPACITY {
+            let excess = self.dead_letters.len() - DEAD_LETTER_CAPACITY;
+            self.dead_letters.drain(..excess);
+        }
+        Err(serde_json::json!({
+            "error": "MalformedBody",
+            "handler": handler,
+            "body_len": message_json.len(),
+            "reason": error.to_string(),
+        }).to_string())
+    }
+    
+    // Local API callers must be processes on this node holding `scope` in local_api_grants
+    fn check_local_grant(&self, scope: LocalScope) -> Result<(), String> {
+        let caller = source();
+        if caller.node != our().node {
+            return Err("The local API only serves processes on this node".to_string());
+        }
+        if !local_api::granted(&self.node_config.local_api_grants, &caller.process, scope) {
+            return Err(format!("{} has no {:?} grant for the local API", caller.process, scope));
+        }
+        Ok(())
+    }
+    
+    // Handles queued bridge frames, most urgent first, until this turn's budget is spent. Anything
+    // left is picked up by a continuation turn we send ourselves, so other messages get a turn
+    // in between and a burst never monopolizes the process.
+    fn drain_artemis_inbound(&mut self) {
+        let started = std::time::Instant::now();
+        while let Some(frame) = self.artemis_inbound.pop() {
+            if let Err(e) = self.handle_artemis_message_json(frame.channel_id, &frame.text) {
+                println!("Failed to handle Artemis message: {}", e);
+            }
+            if started.elapsed().as_millis() >= INBOUND_TURN_BUDGET_MS {
+                break;
+            }
+        }
+        if self.artemis_inbound.is_empty() || self.artemis_inbound.continuation_pending {
+            return;
+        }
+        self.artemis_inbound.carried_over += 1;
+        let continued = Request::new()
+            .target(our())
+            .body(serde_json::to_vec(&serde_json::json!({ "ContinueArtemisInbound": null })).unwrap())
+            .expects_response(30)
+            .send();
+        // Without a continuation the rest waits for the next frame or maintenance
+        self.artemis_inbound.continuation_pending = continued.is_ok();
+    }
+    
+    fn open_ui_channel(&mut self, channel_id: u32) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let reply = match self.ui_channels.open(channel_id, now) {
+            Ok(()) => {
+                println!("UI subscribed to live updates (channel {})", channel_id);
+                serde_json::json!({ "type": "Subscribed", "node_id": our().node, "timestamp": now })
+            }
+            Err(reason) => serde_json::json!({ "type": "SubscribeRefused", "reason": reason, "timestamp": now }),
+        };
+        Self::push_to_ui_channel(channel_id, &reply.to_string());
+    }
+    
+    // Pushes an event to every subscribed UI tab
+    fn notify_ui(&mut self, kind: UiEventKind, payload: serde_json::Value) {
+        if self.ui_channels.channels.is_empty() {
+            return;
+        }
+        let event = UiEvent {
+            kind,
+            payload,
+            timestamp: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+        };
+        let json = serde_json::to_string(&event).unwrap_or_default();
+        for (channel_id, channel) in self.ui_channels.channels.iter_mut() {
+            Self::push_to_ui_channel(*channel_id, &json);
+            channel.pushed += 1;
+        }
+    }
+    
+    fn notify_receipt_settled(&mut self, opp_id: &str) {
+        let Some(data) = self.execution_receipts.get(opp_id) else {
+            return;
+        };
+        let payload = serde_json::json!({
+            "opp_id": opp_id,
+            "executor_node": data.executor_node,
+            "our_proceeds": data.our_proceeds,
+            "summary": data.summary,
+        });
+        self.notify_ui(UiEventKind::ReceiptSettled, payload);
+    }
+    
+    fn push_to_ui_channel(channel_id: u32, json_message: &str) {
+        send_ws_push(
+            channel_id,
+            WsMessageType::Text,
+            LazyLoadBlob {
+                mime: None,
+                bytes: json_message.as_bytes().to_vec(),
+            },
+        );
+    }
+    
+    // Sends an event to every local subscriber that wants it, dropping any that keep failing
+    fn notify_local(&mut self, event: PoolEvent) {
+        if self.local_subscribers.subscribers.is_empty() {
+            return;
+        }
+        let body = serde_json::to_vec(&serde_json::json!({ "PoolEvent": event })).unwrap_or_default();
+        let mut dropped = Vec::new();
+        for (address, subscriber) in self.local_subscribers.subscribers.iter_mut() {
+            if !subscriber.wants(&event) {
+                continue;
+            }
+            let delivered = address.parse::<Address>().is_ok_and(|target| {
+                Request::new().target(target).body(body.clone()).expects_response(30).send().is_ok()
+            });
+            if subscriber.note_delivery(delivered) {
+                dropped.push(address.clone());
+            }
+        }
+        for address in dropped {
+            self.drop_local_subscriber(&address, "DeliveryFailed");
+        }
+    }
+    
+    // Fire-and-forget delivery can't see a subscriber process that has gone away, so each
+    // maintenance tick asks every subscriber for an answer
+    fn probe_local_subscribers(&mut self) {
+        let body = serde_json::to_vec(&serde_json::json!({ "PoolEvent": PoolEvent::Probe })).unwrap_or_default();
+        let mut dropped = Vec::new();
+        for (address, subscriber) in self.local_subscribers.subscribers.iter_mut() {
+            let answered = address.parse::<Address>().is_ok_and(|target| matches!(
+                Request::new().target(target).body(body.clone()).send_and_await_response(LOCAL_PROBE_TIMEOUT_SECS),
+                Ok(Ok(_))
+            ));
+            if answered {
+                subscriber.failures = 0;
+            } else if subscriber.note_delivery(false) {
+                dropped.push(address.clone());
+            }
+        }
+        for address in dropped {
+            self.drop_local_subscriber(&address, "Unresponsive");
+        }
+    }
+    
+    fn drop_local_subscriber(&mut self, address: &str, reason: &str) {
+        if self.local_subscribers.subscribers.remove(address).is_some() {
+            println!("   🔕 Dropped local subscriber {}: {}", address, reason);
+            self.record_journal("LocalUnsubscribed", serde_json::json!({
+                "address": address,
+                "reason": reason,
+            }));
+        }
+    }
+    
+    // Member changes a manifest would make relative to the one currently imported
+    fn diff_pool_manifest(&self, manifest: &PoolManifest) -> serde_json::Value {
+        let current: HashMap<&str, &PoolMember> = self.pool_manifest.iter()
+            .flat_map(|current| current.members.iter())
+            .map(|member| (member.node.as_str(), member))
+            .collect();
+        let incoming: HashMap<&str, &PoolMember> = manifest.members.iter()
+            .map(|member| (member.node.as_str(), member))
+            .collect();
         
-        // Initialize strategy config
-        self.aave_strategy_config.min_health_factor = "100000000000000000".to_string(); // 0.1
+        let mut added: Vec<&str> = incoming.keys().filter(|node| !current.contains_key(*node)).copied().collect();
+        let mut removed: Vec<&str> = current.keys().filter(|node| !incoming.contains_key(*node)).copied().collect();
+        let mut changed: Vec<&str> = incoming.iter()
+            .filter(|(node, member)| current.get(*node).map_or(false, |old| {
+                old.publisher != member.publisher
+                    || old.public_key != member.public_key
+                    || old.payout_address != member.payout_address
+            }))
+            .map(|(node, _)| *node)
+            .collect();
+        added.sort();
+        removed.sort();
+        changed.sort();
         
-        // Add some initial capital for demo purposes (10,000 USDC)
-        self.available_balances.insert(
-            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(), // USDC mainnet
-            "10000000000".to_string() // 10,000 USDC (6 decimals)
-        );
+        serde_json::json!({
+            "pool_id": manifest.pool_id,
+            "from_version": self.pool_manifest.as_ref().map(|current| current.version),
+            "to_version": manifest.version,
+            "added": added,
+            "removed": removed,
+            "changed": changed,
+        })
+    }
+    
+    fn apply_pool_manifest(&mut self, manifest: PoolManifest) {
+        let our_node = our().node;
+        if let Some(previous) = self.pool_manifest.take() {
+            for member in previous.members {
+                if manifest.members.iter().any(|m| m.node == member.node) {
+                    continue;
+                }
+                self.node_config.peer_publishers.remove(&member.node);
+                self.known_peers.remove(&member.node);
+                if matches!(self.peer_registry.get(&member.node),
+                    Some(PeerRegistration { status: RegistrationStatus::ManifestAsserted { .. }, .. })) {
+                    self.peer_registry.remove(&member.node);
+                }
+            }
+        }
         
-        // Set up WebSocket server for Artemis MEV bot
-        let mut http_server = HttpServer::new(5);
-        let ws_config = WsBindingConfig::new(false, false, false);
-        http_server.bind_ws_path("/artemis", ws_config).unwrap();
-        self.http_server = Some(http_server);
+        for member in &manifest.members {
+            if member.node == our_node {
+                continue;
+            }
+            if member.publisher != self.node_config.publisher {
+                self.node_config.peer_publishers.insert(member.node.clone(), member.publisher.clone());
+            } else {
+                self.node_config.peer_publishers.remove(&member.node);
+            }
+            
+            // Never downgrade details we already verified with the peer directly
+            let verified = matches!(self.peer_registry.get(&member.node),
+                Some(PeerRegistration { status: RegistrationStatus::Verified, .. }));
+            if !verified && (member.public_key.is_some() || member.payout_address.is_some()) {
+                self.peer_registry.insert(member.node.clone(), PeerRegistration {
+                    public_key: member.public_key.clone(),
+                    payout_address: member.payout_address.clone(),
+                    status: RegistrationStatus::ManifestAsserted {
+                        pool_id: manifest.pool_id.clone(),
+                        version: manifest.version,
+                    },
+                });
+            }
+        }
         
-        println!("Hyper-MEV P2P Pool initialized on node: {}", our().node);
-        println!("Strategy: {}", self.active_strategy.as_ref().unwrap());
-        println!("WebSocket endpoint available at /artemis for Artemis MEV bot");
+        self.node_config.pool_id = Some(manifest.pool_id.clone());
+        if let Some(policy) = manifest.duplicate_finder_policy {
+            self.node_config.duplicate_finder_policy = policy;
+        }
+        self.node_config.bootstrap_peers = manifest.bootstrap_peers.iter()
+            .filter(|peer| **peer != our_node)
+            .cloned()
+            .collect();
+        self.connect_bootstrap_peers();
+        println!("📜 Imported pool manifest {} v{} ({} members)",
+            manifest.pool_id, manifest.version, manifest.members.len());
+        self.pool_manifest = Some(manifest);
     }
     
-    // HTTP ENDPOINTS FOR FRONTEND
-    
-    #[http]
-    async fn get_node_status(&self, _request_body: String) -> Result<String, String> {
-        let status = serde_json::json!({
-            "node_id": self.node_config.node_id,
-            "active_strategy": self.active_strategy,
-            "peer_count": self.known_peers.len(),
-            "opportunity_count": self.active_opportunities.len(),
-            "intent_count": self.submitted_intents.len(),
-            "available_capital": self.available_balances,
-            "roles": {
-                "finder_enabled": self.node_config.finder_enabled,
-                "capital_provider_enabled": self.node_config.capital_provider_enabled,
-                "executor_enabled": self.node_config.executor_enabled
-            },
-            "config": {
-                "finder_fee_bps": self.node_config.finder_fee_bps,
-                "executor_fee_bps": self.node_config.executor_fee_bps,
-                "min_profit_threshold_usd": self.node_config.min_profit_threshold_usd.to_string(),
-                "max_gas_price_gwei": self.node_config.max_gas_price_gwei.to_string()
+    // Returns the opportunity a new origination for `key` should fold into, if the victim is live
+    // or cooling down; otherwise records `opp_id` as the victim's current opportunity
+    fn throttle_origination(&mut self, key: &str, opp_id: &str) -> Option<String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if let Some(record) = self.origination_index.get_mut(key) {
+            let live = self.active_opportunities.contains_key(&record.opp_id);
+            if live || now.saturating_sub(record.last_seen) <= self.node_config.origination_cooldown_secs {
+                if live {
+                    record.last_seen = now;
+                }
+                return Some(record.opp_id.clone());
             }
+        }
+        self.origination_index.insert(key.to_string(), OriginationRecord {
+            opp_id: opp_id.to_string(),
+            last_seen: now,
         });
-        
-        Ok(status.to_string())
+        None
     }
     
-    #[http] 
-    async fn update_node_config(&mut self, request_body: String) -> Result<String, String> {
-        #[derive(Deserialize)]
-        struct ConfigUpdate {
-            finder_enabled: Option<bool>,
-            capital_provider_enabled: Option<bool>,
-            executor_enabled: Option<bool>,
-            finder_fee_bps: Option<u16>,
-            executor_fee_bps: Option<u16>,
-            min_profit_threshold_usd: Option<String>,
-            max_gas_price_gwei: Option<String>,
-        }
+    // Drops victims whose opportunity is gone and whose cooldown has lapsed
+    fn prune_origination_index(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let cooldown = self.node_config.origination_cooldown_secs;
+        let active = &self.active_opportunities;
+        self.origination_index.retain(|_, record| {
+            active.contains_key(&record.opp_id) || now.saturating_sub(record.last_seen) <= cooldown
+        });
+    }
+    
+    // Folds refreshed numbers into a stored opportunity; the deadline only ever moves later.
+    // Returns the merged opportunity, or None if the update doesn't describe the same position.
+    // `opp_id` may be a duplicate linked under another record; the finder's own claim is updated
+    // and the record re-priced against the best terms on offer
+    fn apply_opportunity_update(&mut self, opp_id: &str, update: &AaveLiquidationOpportunity) -> Option<String> {
+        // The deadline is merged as the later of the two, so only the stored one needs to be ahead
+        update.validate(None).ok()?;
+        let canonical = self.canonical_opp_id(opp_id);
+        let data = self.active_opportunities.get_mut(&canonical)?;
+        let Some(claim) = data.claiming_finders.iter_mut().find(|claim| claim.opp_id == opp_id) else {
+            data.opportunity = merge_opportunity_update(&data.opportunity, update)?;
+            return Some(data.opportunity.clone());
+        };
+        claim.opportunity = merge_opportunity_update(&claim.opportunity, update)?;
+        let merged = claim.opportunity.clone();
+        data.adopt_best_terms(&canonical);
+        Some(merged)
+    }
+    
+    // Node credited with finding an opportunity we hold or retired; our own finds are ours
+    fn opportunity_finder(&self, opp_id: &str) -> Option<&str> {
+        let finder = self.active_opportunities.get(opp_id).map(|data| data.finder_node.as_str())
+            .or_else(|| self.expired_opportunities.get(opp_id).map(|record| record.finder_node.as_str()))?;
+        Some(if finder == "artemis-bot" { self.node_config.node_id.as_str() } else { finder })
+    }
+    
+    fn canonical_opp_id(&self, opp_id: &str) -> String {
+        self.opp_aliases.get(opp_id).cloned().unwrap_or_else(|| opp_id.to_string())
+    }
+    
+    // Links a find to the live opportunity for the same victim and debt, returning its opp_id;
+    // otherwise registers `claim` as the canonical find and returns None
+    fn link_duplicate_find(&mut self, strategy_id: &str, claim: FinderClaim) -> Option<String> {
+        let opportunity: AaveLiquidationOpportunity = serde_json::from_str(&claim.opportunity).ok()?;
+        let key = duplicate_key(strategy_id, &opportunity.victim_address, &opportunity.repay_asset);
+        let canonical = match self.duplicate_index.get(&key) {
+            Some(canonical) if *canonical != claim.opp_id && self.active_opportunities.contains_key(canonical) => canonical.clone(),
+            _ => {
+                self.duplicate_index.insert(key, claim.opp_id.clone());
+                return None;
+            }
+        };
         
-        let update: ConfigUpdate = serde_json::from_str(&request_body)
-            .map_err(|e| format!("Invalid config update: {}", e))?;
+        let data = self.active_opportunities.get_mut(&canonical)?;
+        let detail = serde_json::json!({
+            "canonical_opp_id": canonical,
+            "opp_id": claim.opp_id,
+            "finder": claim.finder,
+            "finder_fee_bps": claim.finder_fee_bps,
+            "claims": data.claiming_finders.len() + 1,
+        });
+        // A re-broadcast of a claim we already linked only refreshes its terms
+        let repeat = if let Some(existing) = data.claiming_finders.iter_mut().find(|c| c.opp_id == claim.opp_id) {
+            existing.opportunity = claim.opportunity;
+            existing.finder_fee_bps = claim.finder_fee_bps;
+            true
+        } else {
+            data.audit_trail.push(JournalEntry {
+                timestamp: claim.seen_at.clone(),
+                kind: "DuplicateFindLinked".to_string(),
+                detail: detail.clone(),
+            });
+            data.claiming_finders.push(claim.clone());
+            false
+        };
+        data.adopt_best_terms(&canonical);
         
-        if let Some(finder_enabled) = update.finder_enabled {
-            self.node_config.finder_enabled = finder_enabled;
-        }
-        if let Some(capital_provider_enabled) = update.capital_provider_enabled {
-            self.node_config.capital_provider_enabled = capital_provider_enabled;
+        if !repeat {
+            println!("\n🔗 Opportunity {} from {} duplicates {}; linked", claim.opp_id, claim.finder, canonical);
+            self.opp_aliases.insert(claim.opp_id, canonical.clone());
+            self.record_journal("DuplicateFindLinked", detail);
         }
-        if let Some(executor_enabled) = update.executor_enabled {
-            self.node_config.executor_enabled = executor_enabled;
+        Some(canonical)
+    }
+    
+    // Drops duplicate links whose canonical opportunity is gone
+    fn prune_duplicate_index(&mut self) {
+        let live = &self.active_opportunities;
+        self.duplicate_index.retain(|_, canonical| live.contains_key(canonical));
+        self.opp_aliases.retain(|_, canonical| live.contains_key(canonical));
+    }
+    
+    // Forgets probe results for victims with no live opportunity left
+    fn prune_risk_probes(&mut self) {
+        let victims: HashSet<String> = self.active_opportunities.values()
+            .filter_map(|data| serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity).ok())
+            .map(|opportunity| opportunity.victim_address.to_lowercase())
+            .collect();
+        self.risk_probes.retain(|victim, _| victims.contains(victim));
+    }
+    
+    // Finder-fee policy and recipients for an opportunity's proceeds
+    fn finder_fee_split(&self, opp_id: &str) -> (DuplicateFinderPolicy, Vec<FinderFeeShare>) {
+        let policy = self.node_config.duplicate_finder_policy;
+        let recipients = self.active_opportunities.get(opp_id)
+            .map(|data| finder_fee_recipients(policy, &data.claiming_finders))
+            .unwrap_or_default();
+        (policy, recipients)
+    }
+    
+    // Applies an update to an opportunity we originated and relays it to peers
+    fn publish_opportunity_update(&mut self, opp_id: &str, update: &AaveLiquidationOpportunity) {
+        let Some(merged) = self.apply_opportunity_update(opp_id, update) else {
+            println!("   Ignoring update for {}: unknown or mismatched opportunity", opp_id);
+            return;
+        };
+        let strategy_id = self.active_opportunities.get(&self.canonical_opp_id(opp_id))
+            .map(|data| data.strategy_id.clone())
+            .unwrap_or_default();
+        let message = MevMessage::OpportunityUpdate {
+            opp_id: opp_id.to_string(),
+            strategy_id,
+            opportunity: merged,
+        };
+        self.send_to_peers(message.handler(), &Self::message_json(&message));
+    }
+    
+    fn message_json(message: &MevMessage) -> serde_json::Value {
+        serde_json::to_value(message).unwrap_or(serde_json::Value::Null)
+    }
+    
+    fn broadcast_message(&self, message: &MevMessage) {
+        match message {
+            MevMessage::OpportunityBroadcast { opp_id, strategy_id, .. } => {
+                self.fan_out_opportunity(opp_id, strategy_id, message.handler(), &Self::message_json(message));
+            }
+            _ => self.send_to_peers(message.handler(), &self.sign_message(message)),
         }
-        if let Some(finder_fee_bps) = update.finder_fee_bps {
-            self.node_config.finder_fee_bps = finder_fee_bps;
+    }
+    
+    // Charges a new opportunity to its finder's quota, or refuses it with a QuotaExceeded error.
+    // Live opportunities are counted from what we hold, so retiring one frees its slot at once.
+    fn admit_from_finder(&mut self, finder: &str, opp_id: &str, now: u64) -> Result<(), String> {
+        let live = self.active_opportunities.values()
+            .filter(|data| data.finder_node == finder)
+            .count();
+        let tier = self.finder_tier(finder);
+        let stats = self.finder_sequences.entry(finder.to_string()).or_default();
+        let (max_live, max_per_hour) = self.node_config.finder_quota.scaled(tier);
+        let accepted = stats.accepted_within_hour(now);
+        let exceeded = if max_live > 0 && live >= max_live {
+            Some(("live", live, max_live))
+        } else if max_per_hour > 0 && accepted >= max_per_hour {
+            Some(("hourly", accepted, max_per_hour))
+        } else {
+            None
+        };
+        if let Some((limit, count, allowed)) = exceeded {
+            stats.quota_rejected += 1;
+            println!("\n🚦 P2P: Refused {} from {}: {} quota of {} reached (tier {})", opp_id, finder, limit, allowed, tier);
+            return Err(serde_json::json!({
+                "error": "QuotaExceeded",
+                "opp_id": opp_id,
+                "finder": finder,
+                "limit": limit,
+                "count": count,
+                "allowed": allowed,
+                "reputation_tier": tier,
+            }).to_string());
         }
-        if let Some(executor_fee_bps) = update.executor_fee_bps {
-            self.node_config.executor_fee_bps = executor_fee_bps;
+        stats.recent_accepts.push_back(now);
+        Ok(())
+    }
+    
+    // P2P broadcast for an opportunity we originated, stamped with its finder sequence number
+    fn opportunity_broadcast_message(&self, opp_id: &str, data: &OpportunityData, finder_seq: u64, backfill: bool) -> MevMessage {
+        let deadline_block = serde_json::from_str::<AaveLiquidationOpportunity>(&data.opportunity)
+            .map(|opportunity| opportunity.deadline_block)
+            .unwrap_or(0);
+        MevMessage::OpportunityBroadcast {
+            opp_id: opp_id.to_string(),
+            strategy_id: data.strategy_id.clone(),
+            opportunity: data.opportunity.clone(),
+            finder_fee_bps: self.node_config.finder_fee_bps,
+            deadline_block,
+            executor_preference: data.executor_preference.clone(),
+            finder_seq: Some(finder_seq),
+            backfill,
+            origin: data.origin.clone(),
+            hop_count: Some(0),
+            finder_node: Some(our().node),
         }
-        if let Some(min_profit_str) = update.min_profit_threshold_usd {
-            // Validate it's a valid U256 string
-            min_profit_str.parse::<U256>()
-                .map_err(|_| "Invalid min profit threshold")?;
-            self.node_config.min_profit_threshold_usd = min_profit_str;
+    }
+    
+    // One of our live opportunities as sent in a state sync: under its original finder and terms,
+    // with no sequence number, since it isn't part of our own stream unless we found it
+    fn sync_broadcast_message(&self, opp_id: &str, data: &OpportunityData) -> MevMessage {
+        let claim = data.claiming_finders.iter().find(|claim| claim.opp_id == opp_id);
+        let finder = claim.map_or(data.finder_node.as_str(), |claim| claim.finder.as_str());
+        let finder = if finder == "artemis-bot" { our().node } else { finder.to_string() };
+        let opportunity = claim.map_or(&data.opportunity, |claim| &claim.opportunity);
+        let deadline_block = serde_json::from_str::<AaveLiquidationOpportunity>(opportunity)
+            .map(|opportunity| opportunity.deadline_block)
+            .unwrap_or(0);
+        MevMessage::OpportunityBroadcast {
+            opp_id: opp_id.to_string(),
+            strategy_id: data.strategy_id.clone(),
+            opportunity: opportunity.clone(),
+            finder_fee_bps: claim.map_or(self.node_config.finder_fee_bps, |claim| claim.finder_fee_bps),
+            deadline_block,
+            // An election is ours to make; the requester makes its own
+            executor_preference: if data.executor_elected { Vec::new() } else { data.executor_preference.clone() },
+            finder_seq: None,
+            backfill: true,
+            origin: data.origin.clone(),
+            hop_count: Some(0),
+            finder_node: Some(finder),
         }
-        if let Some(max_gas_str) = update.max_gas_price_gwei {
-            // Validate it's a valid U256 string
-            max_gas_str.parse::<U256>()
-                .map_err(|_| "Invalid max gas price")?;
-            self.node_config.max_gas_price_gwei = max_gas_str;
+    }
+    
+    // Asks a peer that just joined us for its live state, unless a request to it is still awaiting
+    // an answer
+    fn request_state_sync(&mut self, peer: &str) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if self.pending_syncs.get(peer).is_some_and(|sent_at| now.saturating_sub(*sent_at) < SYNC_RESPONSE_TIMEOUT_SECS) {
+            return;
         }
-        
-        Ok("Configuration updated successfully".to_string())
+        self.pending_syncs.insert(peer.to_string(), now);
+        let request = MevMessage::SyncRequest { max_opportunities: MAX_SYNC_OPPORTUNITIES };
+        self.send_to_peer(peer, request.handler(), &Self::message_json(&request));
     }
     
-    #[http]
-    async fn add_capital(&mut self, request_body: String) -> Result<String, String> {
-        #[derive(Deserialize)]
-        struct CapitalAddition {
-            asset: String,
-            amount: String,
+    // Passes a received broadcast on to every reachable peer but the one it came from. The
+    // sequence number belongs to the sender's stream, so it is dropped from the forwarded copy.
+    fn forward_broadcast(&self, message_json: &str, hop_count: u8) {
+        let Ok(mut message) = serde_json::from_str::<serde_json::Value>(message_json) else {
+            return;
+        };
+        message["hop_count"] = serde_json::json!(hop_count);
+        message["finder_seq"] = serde_json::Value::Null;
+        let sender = source().node;
+        for peer in self.reachable_peers().filter(|peer| **peer != sender) {
+            self.send_to_peer(peer, "ReceiveOpportunityBroadcast", &message);
         }
-        
-        let addition: CapitalAddition = serde_json::from_str(&request_body)
-            .map_err(|e| format!("Invalid capital addition: {}", e))?;
-        
-        // Validate the address format
-        addition.asset.parse::<Address>()
-            .map_err(|_| "Invalid asset address")?;
-        // Validate the amount format
-        let amount = addition.amount.parse::<U256>()
-            .map_err(|_| "Invalid amount")?;
-        
-        let current = self.available_balances.entry(addition.asset.clone()).or_insert("0".to_string());
-        let current_amount = current.parse::<U256>().unwrap_or(U256::ZERO);
-        let new_amount = current_amount + amount;
-        *current = new_amount.to_string();
-        
-        Ok(format!("Added {} of asset {}", amount, addition.asset))
     }
     
-    #[http]
-    async fn connect_to_peer(&mut self, request_body: String) -> Result<String, String> {
-        let peer_node: String = serde_json::from_str(&request_body)
-            .map_err(|e| format!("Invalid peer node: {}", e))?;
+    // Updates delivery stats for a finder and asks it to resend any range we skipped over
+    fn track_finder_sequence(&mut self, finder: &str, seq: u64) {
+        let stats = self.finder_sequences.entry(finder.to_string()).or_default();
+        let Some((from_seq, to_seq)) = stats.observe(seq) else {
+            return;
+        };
+        println!("   ⚠️  Missed opportunities {}..={} from {}; requesting backfill", from_seq, to_seq, finder);
+        let request = MevMessage::BackfillRequest {
+            finder: finder.to_string(),
+            from_seq,
+            to_seq,
+        };
+        self.send_to_peer(finder, request.handler(), &Self::message_json(&request));
+    }
+    
+    // Passes execution rights down each opportunity's preference list when the current
+    // holder has neither claimed nor produced a receipt in time, or at once when it announced
+    // maintenance over the deadline. Returns the opportunities
+    // whose rights just passed to us.
+    fn check_executor_succession(&mut self) -> Vec<String> {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let head = self.current_block();
+        let window = self.node_config.executor_failover_blocks;
+        let mut successions = Vec::new();
+        // Holders that announced maintenance over the deadline are passed over without waiting
+        let holders_away: HashSet<String> = self.active_opportunities.iter()
+            .filter(|(_, data)| data.execution_holder()
+                .is_some_and(|holder| self.in_maintenance_at(holder, self.deadline_at(data))))
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
         
-        if peer_node == our().node {
-            return Err("Cannot connect to self".to_string());
+        for (opp_id, data) in self.active_opportunities.iter_mut() {
+            if data.executor_preference.is_empty()
+                || data.claimed_by.is_some()
+                || self.execution_receipts.contains_key(opp_id)
+            {
+                continue;
+            }
+            let held_for = blocks_since(head, data.holder_since_block, now, data.holder_since.parse::<u64>().unwrap_or(now));
+            if (held_for < window && !holders_away.contains(opp_id))
+                || data.execution_holder_index + 1 >= data.executor_preference.len()
+            {
+                continue;
+            }
+            data.execution_holder_index += 1;
+            data.holder_since = now.to_string();
+            data.holder_since_block = head;
+            successions.push((opp_id.clone(), data.execution_holder_index));
         }
         
-        // Add to known peers
-        self.known_peers.insert(peer_node.clone());
-        
-        // Send node announcement to new peer
-        self.announce_to_peer(peer_node.clone()).await?;
-        
-        Ok(format!("Connected to peer: {}", peer_node))
+        let mut ours = Vec::new();
+        for (opp_id, holder_index) in successions {
+            let preference = &self.active_opportunities[&opp_id].executor_preference;
+            let (previous, holder) = (preference[holder_index - 1].clone(), preference[holder_index].clone());
+            println!("\n🔁 Execution rights for {} passed to {} (preference #{})", opp_id, holder, holder_index + 1);
+            self.record_journal("ExecutorSucceeded", serde_json::json!({
+                "opp_id": opp_id,
+                "from": previous,
+                "to": holder,
+                "source": our().node,
+            }));
+            let succession = MevMessage::ExecutorSuccession {
+                opp_id: opp_id.clone(),
+                holder: holder.clone(),
+                holder_index,
+            };
+            self.send_to_peers(succession.handler(), &Self::message_json(&succession));
+            if holder == our().node {
+                ours.push(opp_id);
+            }
+        }
+        ours
     }
     
-    #[http]
-    async fn get_opportunities(&self, _request_body: String) -> Result<String, String> {
-        let opportunities: Vec<_> = self.active_opportunities.iter()
-            .map(|(opp_id, data)| serde_json::json!({
-                "opp_id": opp_id,
-                "strategy_id": data.strategy_id,
-                "finder_node": data.finder_node,
-                "received_at": data.received_at,
-                "opportunity": data.opportunity
-            }))
+    // Without a finder preference, ranks every executor-capable node we know of (ourselves
+    // included, peers by their announced roles) by election_order. Succession then walks the
+    // ranking like a preference list, so the runner-up takes over if the winner neither claims nor
+    // produces a receipt within executor_failover_blocks. Executors down for maintenance at the
+    // deadline aren't candidates. No candidates leaves the list empty: anyone may execute.
+    fn elect_executor(&self, opp_id: &str, data: &mut OpportunityData) {
+        if !data.executor_preference.is_empty() {
+            return;
+        }
+        let our_node = our().node;
+        let deadline_at = self.deadline_at(data);
+        let peers = self.known_peers.iter()
+            .filter(|(_, info)| info.reachable() && info.roles().iter().any(|role| matches!(role, NodeRole::Executor)))
+            .map(|(node, _)| node.clone());
+        let candidates: Vec<String> = peers
+            .chain(self.node_config.executor_enabled.then(|| our_node.clone()))
+            .filter(|node| !self.in_maintenance_at(node, deadline_at))
             .collect();
-        
-        Ok(serde_json::to_string(&opportunities)
-            .unwrap_or_else(|_| "[]".to_string()))
+        data.executor_preference = election_order(opp_id, candidates);
+        data.executor_elected = !data.executor_preference.is_empty();
+        if let Some(winner) = data.execution_holder() {
+            println!("   Elected executor for {}: {}{}", opp_id, winner, if *winner == our_node { " (us)" } else { "" });
+        }
     }
     
-    #[http]
-    async fn get_execution_receipts(&self, _request_body: String) -> Result<String, String> {
-        let receipts: Vec<_> = self.execution_receipts.iter()
-            .map(|(opp_id, data)| serde_json::json!({
-                "opp_id": opp_id,
-                "executor_node": data.executor_node,
-                "our_proceeds": data.our_proceeds.to_string(),
-                "verified_at": data.verified_at,
-                "receipt": data.receipt
-            }))
-            .collect();
+    // Asks each provider in the allocation to reserve its line, reserving our own lines directly.
+    // True once every line holds a live reservation; until then dispatch waits on the answers,
+    // which re-run it.
+    fn reserve_allocation(&mut self, opp_id: &str, allocation: &[AllocationLine]) -> bool {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let expires_at = now + self.node_config.reservation_ttl_secs;
+        let our_node = our().node;
+        let round = self.reservation_rounds.entry(opp_id.to_string()).or_default();
+        if round.confirmed(allocation, now) {
+            return true;
+        }
+        let requests = round.to_request(allocation, now);
+        for line in &requests {
+            round.mark_requested(line, expires_at);
+        }
         
-        Ok(serde_json::to_string(&receipts)
-            .unwrap_or_else(|_| "[]".to_string()))
+        let mut replan = false;
+        for line in requests {
+            if line.provider == our_node {
+                let granted = self.reserve_capital(&our_node, opp_id, &line.intent_hash, &line.amount, expires_at);
+                let (confirmed, granted_until, reason) = match &granted {
+                    Ok(until) => (true, *until, String::new()),
+                    Err(reason) => (false, expires_at, reason.clone()),
+                };
+                if let Some(round) = self.reservation_rounds.get_mut(opp_id) {
+                    replan |= round.record_response(&line.intent_hash, &our_node, confirmed, granted_until, &reason) == Some(true);
+                }
+                continue;
+            }
+            let request = MevMessage::CapitalReserveRequest {
+                opp_id: opp_id.to_string(),
+                intent_hash: line.intent_hash.clone(),
+                amount: line.amount.clone(),
+                expires_at,
+            };
+            self.send_to_peer(&line.provider, request.handler(), &Self::message_json(&request));
+        }
+        if replan {
+            self.dispatch_opportunity(opp_id);
+            return false;
+        }
+        
+        let confirmed = self.reservation_rounds.get(opp_id).is_some_and(|round| round.confirmed(allocation, now));
+        if !confirmed {
+            println!("   Holding {}: waiting on capital reservations", opp_id);
+        }
+        confirmed
     }
     
-    
-    #[http]
-    async fn get_node_config(&self, _request_body: String) -> Result<String, String> {
-        let config = serde_json::json!({
-            "node_id": self.node_config.node_id,
-            "finder_enabled": self.node_config.finder_enabled,
-            "capital_provider_enabled": self.node_config.capital_provider_enabled,
-            "executor_enabled": self.node_config.executor_enabled,
-            "finder_fee_bps": self.node_config.finder_fee_bps,
-            "executor_fee_bps": self.node_config.executor_fee_bps,
-            "min_profit_threshold_usd": self.node_config.min_profit_threshold_usd,
-            "max_gas_price_gwei": self.node_config.max_gas_price_gwei
+    // Provider side of a reservation: pins the lock behind our intent for `executor` until
+    // `expires_at` (at most reservation_ttl_secs away), provided the intent is the one allocated,
+    // the executor may run it, and our balance still backs every lock. Returns the expiry granted.
+    fn reserve_capital(&mut self, executor: &str, opp_id: &str, intent_hash_requested: &str, amount: &str, expires_at: u64) -> Result<u64, String> {
+        let outstanding = self.our_intents.get(opp_id).ok_or("NoIntent")?;
+        let ours = serde_json::to_string(&outstanding.intent).map(|json| intent_hash(&json)).unwrap_or_default();
+        if ours != intent_hash_requested {
+            return Err("UnknownIntent".to_string());
+        }
+        if !self.active_opportunities.get(opp_id).is_some_and(|data| data.may_execute(executor)) {
+            return Err("NotExecutor".to_string());
+        }
+        let commitment = self.capital_commitments.get(opp_id);
+        let asset = commitment.map_or_else(|| outstanding.intent.asset.clone(), |commitment| commitment.asset.clone());
+        let balance = self.available_balances.get(&asset).cloned().unwrap_or_else(|| "0".to_string());
+        let committed_total = self.committed_amounts.get(&asset).cloned().unwrap_or_else(|| "0".to_string());
+        if let Some(refusal) = reservation_refusal(amount, commitment.map(|commitment| commitment.amount.as_str()), &balance, &committed_total) {
+            return Err(refusal);
+        }
+        
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let expires_at = expires_at.min(now + self.node_config.reservation_ttl_secs);
+        self.capital_reservations.insert(opp_id.to_string(), CapitalReservation {
+            executor: executor.to_string(),
+            intent_hash: intent_hash_requested.to_string(),
+            asset: asset.clone(),
+            amount: amount.to_string(),
+            reserved_at: now,
+            expires_at,
         });
+        self.record_journal("CapitalReserved", serde_json::json!({
+            "opp_id": opp_id,
+            "executor": executor,
+            "asset": asset,
+            "amount": amount,
+            "expires_at": expires_at,
+        }));
+        Ok(expires_at)
+    }
+    
+    // Reservations past their expiry: as provider, the lock is released and our intent withdrawn
+    // (the executor had until then to dispatch); as executor, unanswered requests count as
+    // rejections and the opportunity is re-planned without them
+    fn expire_reservations(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let lapsed: Vec<String> = self.capital_reservations.iter()
+            .filter(|(_, reservation)| reservation.expires_at <= now)
+            .map(|(opp_id, _)| opp_id.clone())
+            .collect();
+        for opp_id in lapsed {
+            let Some(reservation) = self.capital_reservations.remove(&opp_id) else {
+                continue;
+            };
+            self.record_journal("ReservationExpired", serde_json::json!({
+                "opp_id": opp_id,
+                "executor": reservation.executor,
+                "amount": reservation.amount,
+            }));
+            self.withdraw_intent(&opp_id, "reservation expired");
+        }
         
-        Ok(config.to_string())
+        let active = &self.active_opportunities;
+        self.reservation_rounds.retain(|opp_id, _| active.contains_key(opp_id));
+        let mut replan = Vec::new();
+        for (opp_id, round) in self.reservation_rounds.iter_mut() {
+            if !round.expire_unanswered(now).is_empty() && !self.pending_executions.contains_key(opp_id) {
+                replan.push(opp_id.clone());
+            }
+        }
+        for opp_id in replan {
+            println!("   Re-planning {}: capital reservations went unanswered", opp_id);
+            self.dispatch_opportunity(&opp_id);
+        }
     }
     
-    // WEBSOCKET HANDLER FOR ARTEMIS MEV BOT
+    // Sends an opportunity's intents to Artemis if we are allowed to execute it
+    fn dispatch_opportunity(&mut self, opp_id: &str) {
+        if let Some(collection) = self.intent_collection_for(opp_id) {
+            let _ = self.send_to_artemis_json_sync(&collection.to_string());
+        }
+    }
     
-    #[ws]
-    fn handle_artemis_websocket(&mut self, channel_id: u32, message_type: WsMessageType, payload: LazyLoadBlob) {
-        match message_type {
-            WsMessageType::Text => {
-                // Handle first connection - send node config
-                if self.artemis_channel_id.is_none() {
-                    println!("Artemis MEV bot connected via WebSocket");
-                    self.artemis_channel_id = Some(channel_id);
-                    
-                    // Send initial node config to Artemis bot
-                    let config_json = serde_json::json!({
-                        "type": "NodeConfig",
-                        "config": self.node_config
-                    });
-                    if let Err(e) = self.send_to_artemis_json_sync(&config_json.to_string()) {
-                        println!("Failed to send config to Artemis: {}", e);
-                    }
-                    return;
+    // Everything dispatch does short of the send: checks that we may execute now, marks the
+    // opportunity executing and returns the IntentCollection for the bridge, or None to wait
+    fn intent_collection_for(&mut self, opp_id: &str) -> Option<serde_json::Value> {
+        if !self.node_config.executor_enabled || self.execution_receipts.contains_key(opp_id) {
+            return None;
+        }
+        // Already with the bridge; dispatching again could execute twice
+        if self.pending_executions.contains_key(opp_id) {
+            return None;
+        }
+        let Some(intents) = self.submitted_intents.get(opp_id).filter(|i| !i.is_empty()) else {
+            return None;
+        };
+        let Some(opportunity_data) = self.active_opportunities.get(opp_id) else {
+            return None;
+        };
+        // One IntentCollection per opportunity: executing, settled, failed and expired ones stay put
+        if !opportunity_data.status.dispatchable() {
+            return None;
+        }
+        if opportunity_data.strategy_id != "aave-liquidation" {
+            return None;
+        }
+        let Ok(opportunity) = serde_json::from_str::<AaveLiquidationOpportunity>(&opportunity_data.opportunity) else {
+            println!("   Cannot execute {}: stored opportunity is unreadable", opp_id);
+            return None;
+        };
+        // Re-check assets and bonus even though receive_intent_submission already did, since the
+        // opportunity may have been re-priced since, and leave out intents that expired since they arrived
+        let head = self.chain_head.map(|head| head.estimate(Self::current_timestamp().parse::<u64>().unwrap_or(0)));
+        let intents: Vec<IntentData> = intents.iter()
+            .filter(|data| data.parsed_intent().map(|i| i.can_fund(&opportunity)).unwrap_or(false))
+            .filter(|data| head.map_or(true, |head| data.expires_block >= head))
+            .cloned()
+            .collect();
+        if intents.is_empty() {
+            return None;
+        }
+        let our_node = self.node_config.node_id.clone();
+        if !opportunity_data.may_execute(&our_node) {
+            println!("   Standing by on {}: execution rights held by {:?}", opp_id, opportunity_data.execution_holder());
+            return None;
+        }
+        // Settlement runs until the deadline plus the grace peers give the receipt; none of
+        // that may fall in our own maintenance window
+        let horizon = self.deadline_at(opportunity_data) + EXPIRY_GRACE_BLOCKS * SECONDS_PER_BLOCK;
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        if let Some(window) = self.maintenance_window.filter(|window| window.overlaps(now, horizon)) {
+            println!("   Not dispatching {}: settlement by {} overlaps our maintenance from {} to {}",
+                opp_id, horizon, window.start, window.end());
+            return None;
+        }
+        let needs_claim = !opportunity_data.executor_preference.is_empty() && opportunity_data.claimed_by.is_none();
+        let (received_at, received_block) = (opportunity_data.received_at, opportunity_data.received_block);
+        let was_armed = opportunity_data.dispatch_armed;
+        // Terms come from the revision the opportunity arrived under, not whatever was applied since
+        let terms = self.config_for(opp_id).clone();
+        let window_blocks = opportunity_data.intent_window_blocks(terms.provider_wait_blocks);
+        let extended = opportunity_data.window_extension.is_some();
+        
+        // Never dispatch so close to the deadline that the transaction can't land in time
+        if let Some(head) = self.chain_head.map(|head| head.estimate(now)) {
+            if head + DISPATCH_SAFETY_MARGIN_BLOCKS > opportunity.deadline_block {
+                println!("   Holding {}: block {} is within {} blocks of its deadline {}",
+                    opp_id, head, DISPATCH_SAFETY_MARGIN_BLOCKS, opportunity.deadline_block);
+                return None;
+            }
+        }
+        
+        // Above our gas cap we wait for it to drop; expiry takes the opportunity if it doesn't
+        let max_gas_price = terms.max_gas_price_wei(self.strategy_configs.get("aave-liquidation"));
+        let gas_price = self.current_gas_price();
+        match gas_price.filter(|price| *price > max_gas_price) {
+            Some(price) => {
+                let Some(data) = self.active_opportunities.get_mut(opp_id) else {
+                    return None;
+                };
+                let first = data.gas_deferral.is_none();
+                data.gas_deferral = Some(GasDeferral {
+                    since: data.gas_deferral.as_ref().map_or(now, |deferral| deferral.since),
+                    gas_price_wei: price.to_string(),
+                    max_gas_price_gwei: (max_gas_price / U256::from(1_000_000_000u64)).to_string(),
+                });
+                if first {
+                    println!("   Deferring {}: gas price {} wei is above our cap of {} wei", opp_id, price, max_gas_price);
+                    self.record_journal("DispatchDeferredForGas", serde_json::json!({
+                        "opp_id": opp_id,
+                        "gas_price_wei": price.to_string(),
+                        "max_gas_price_wei": max_gas_price.to_string(),
+                    }));
                 }
-                
-                // Handle incoming text messages from Artemis
-                if let Ok(text) = String::from_utf8(payload.bytes.clone()) {
-                    if let Err(e) = self.handle_artemis_message_json(&text) {
-                        println!("Failed to handle Artemis message: {}", e);
-                    }
+                return None;
+            }
+            None => {
+                let resumed = self.active_opportunities.get_mut(opp_id).and_then(|data| data.gas_deferral.take());
+                if let Some(deferral) = resumed {
+                    println!("   Gas is back under our cap; resuming dispatch of {} (deferred since {})", opp_id, deferral.since);
+                }
+            }
+        }
+        
+        // Same hysteresis as capital providers, so re-pricing near the bar doesn't flip dispatch on and off
+        let profit = opportunity.estimated_profit_usd.parse::<U256>().unwrap_or(U256::ZERO);
+        let min_profit = terms.min_profit_threshold_usd.parse::<U256>().unwrap_or(U256::ZERO);
+        let armed = clears_profit_bar(
+            profit,
+            min_profit,
+            terms.profit_entry_margin_bps,
+            terms.profit_exit_margin_bps,
+            was_armed,
+        );
+        if armed != was_armed {
+            if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+                data.dispatch_armed = armed;
+            }
+        }
+        if !armed {
+            println!("   Holding {}: profit {} is below the dispatch bar", opp_id, profit);
+            return None;
+        }
+        
+        let size = opportunity.max_repay_amount.parse::<U256>().unwrap_or(U256::ZERO);
+        let path = self.decide_evaluation_path(opp_id, size);
+        let skips = |check: SkippableCheck| matches!(&path,
+            EvaluationPath::Fast { skipped_checks, .. } if skipped_checks.contains(&check));
+        
+        // Large opportunities wait for enough distinct capital providers before dispatch
+        let mut provider_rule = None;
+        let provider_requirement = if skips(SkippableCheck::ProviderWait) {
+            None
+        } else {
+            terms.provider_requirement_for(size).cloned()
+        };
+        if let Some(rule) = provider_requirement {
+            let distinct_providers = intents.iter()
+                .map(|data| data.submitter_node.as_str())
+                .collect::<HashSet<_>>()
+                .len() as u32;
+            let mut fallback_used = false;
+            if distinct_providers < rule.min_providers {
+                let head = self.chain_head.map(|head| head.estimate(now));
+                let waited_blocks = blocks_since(head, received_block, now, received_at);
+                if !provider_wait_over(waited_blocks, window_blocks, head, opportunity.deadline_block) {
+                    self.announce_provider_shortfall(opp_id, distinct_providers, rule.min_providers);
+                    return None;
                 }
-            }
-            WsMessageType::Binary => {
-                // Handle binary messages from Artemis
-                if let Ok(text) = String::from_utf8(payload.bytes.clone()) {
-                    if let Err(e) = self.handle_artemis_message_json(&text) {
-                        println!("Failed to handle Artemis message: {}", e);
+                if !terms.allow_under_provisioned_execution {
+                    if !extended && self.extend_intent_window(opp_id, &opportunity, &intents) {
+                        return None;
                     }
+                    println!("\n⌛ Expiring {}: only {} of {} required capital providers committed",
+                        opp_id, distinct_providers, rule.min_providers);
+                    self.retire_opportunity(opp_id, RetirementOutcome::expired("InsufficientProviders", None, true));
+                    return None;
                 }
+                fallback_used = true;
             }
-            WsMessageType::Close => {
-                println!("Artemis MEV bot disconnected");
-                self.artemis_channel_id = None;
+            provider_rule = Some(ProviderRuleOutcome {
+                threshold_usd: rule.threshold_usd,
+                min_providers: rule.min_providers,
+                distinct_providers,
+                fallback_used,
+            });
+        }
+        
+        // Intents refused a reservation, or never answered, are re-planned without
+        let excluded = self.reservation_rounds.get(opp_id).map(|round| round.excluded.clone()).unwrap_or_default();
+        let intents: Vec<IntentData> = intents.into_iter()
+            .filter(|data| !excluded.contains(&data.intent_hash()))
+            .collect();
+        if intents.is_empty() {
+            println!("   Holding {}: no intent left that its provider would reserve", opp_id);
+            return None;
+        }
+        
+        // Allocate under the declared policy; the record travels with the receipt for replay
+        let policy = terms.allocation_policy;
+        let candidates: Vec<AllocationCandidate> = intents.iter().map(|data| data.allocation_candidate()).collect();
+        let allocation = match allocate(policy, &opportunity.max_repay_amount, &candidates) {
+            Ok(allocation) => allocation,
+            Err(e) => {
+                println!("   Cannot allocate {}: {}", opp_id, e);
+                return None;
             }
-            _ => {
-                // Handle other message types if needed
+        };
+        let allocation_record = AllocationRecord {
+            policy,
+            target_amount: opportunity.max_repay_amount.clone(),
+            candidates,
+            allocation,
+        };
+        if !self.artemis_channels.is_connected() {
+            println!("   Holding {}: no Artemis bridge connected", opp_id);
+            return None;
+        }
+        // Last look at the victim before the bridge commits capital
+        if self.node_config.bridge_validation && self.chain_head.is_some() {
+            match self.request_validation(opp_id, &opportunity.victim_address, ValidationPriority::PreDispatch) {
+                Some(result) if result.liquidatable => {}
+                // The bridge couldn't check; as with the on-chain checks, go on the finder's word
+                Some(result) if result.error.is_some() => {
+                    println!("   Dispatching {} unvalidated: {}", opp_id, result.error.unwrap_or_default());
+                }
+                Some(result) => {
+                    println!("   Not dispatching {}: the bridge reports health factor {}", opp_id, result.health_factor);
+                    self.retire_opportunity(opp_id, RetirementOutcome::expired("NotLiquidatable", None, true));
+                    return None;
+                }
+                None => {
+                    println!("   Holding {}: waiting on the bridge's validation", opp_id);
+                    return None;
+                }
             }
         }
-    }
-    
-    // P2P REMOTE HANDLERS
-    
-    #[remote]
-    async fn receive_node_announcement(&mut self, message_json: String) -> Result<String, String> {
-        let announcement: serde_json::Value = serde_json::from_str(&message_json)
-            .map_err(|e| format!("Invalid announcement: {}", e))?;
+        // Two-phase commitment: only capital its providers have reserved goes to the bridge
+        let intents = if self.node_config.capital_reservation {
+            if !self.reserve_allocation(opp_id, &allocation_record.allocation) {
+                return None;
+            }
+            intents.into_iter()
+                .filter(|data| allocation_record.allocation.iter().any(|line| line.intent_hash == data.intent_hash()))
+                .collect()
+        } else {
+            intents
+        };
+        self.pending_executions.insert(opp_id.to_string(), PendingExecution {
+            dispatched_at: Self::current_timestamp(),
+            allocation: allocation_record.clone(),
+            bridge_id: self.artemis_channels.bridge_id(),
+            last_status: None,
+            last_queried_at: None,
+        });
+        self.set_opportunity_status(opp_id, OpportunityStatus::Executing);
         
-        if announcement["type"] == "NodeAnnouncement" {
-            let node_info: NodeInfo = serde_json::from_value(announcement["node_info"].clone())
-                .map_err(|e| format!("Invalid node info: {}", e))?;
-            let capabilities = announcement["capabilities"].as_array()
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<_>>())
-                .unwrap_or_default();
-            
-            // Add to known peers
-            self.known_peers.insert(node_info.node_id.clone());
-            
-            println!("Received announcement from node: {} with capabilities: {:?}", 
-                node_info.node_id, capabilities);
-            
-            // Respond with our own announcement
-            self.announce_to_peer(node_info.node_id.clone()).await?;
-        }
+        // Send intents to Artemis for execution
+        let intent_msg = serde_json::json!({
+            "type": "IntentCollection",
+            "opp_id": opp_id,
+            "repay_asset": opportunity.repay_asset,
+            "max_repay_amount": opportunity.max_repay_amount,
+            "seize_assets": opportunity.seize_legs(),
+            "distribution": opportunity.distribution,
+            "intents": intents,
+            "provider_rule": provider_rule,
+            "allocation": allocation_record,
+        });
+        println!("\n🎮 Executing opportunity {}:", opp_id);
+        println!("   Sending {} intents to Artemis for execution", intents.len());
+        println!("   Coverage: {} / {}", intent_coverage(&opportunity, &intents), opportunity.max_repay_amount);
         
-        Ok("ACK".to_string())
+        if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+            data.provider_shortfall = None;
+            data.allocation_locked = true;
+        }
+        if extended {
+            self.record_journal("IntentWindowOutcome", serde_json::json!({
+                "opp_id": opp_id,
+                "outcome": "Dispatched",
+                "coverage": intent_coverage(&opportunity, &intents).to_string(),
+            }));
+        }
+        if needs_claim {
+            if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+                data.claimed_by = Some(our_node);
+            }
+            let claim = MevMessage::ExecutionClaim { opp_id: opp_id.to_string() };
+            self.send_to_peers(claim.handler(), &Self::message_json(&claim));
+        }
+        Some(intent_msg)
     }
     
-    #[remote]
-    async fn receive_opportunity_broadcast(&mut self, message_json: String) -> Result<String, String> {
-        let broadcast: serde_json::Value = serde_json::from_str(&message_json)
-            .map_err(|e| format!("Invalid opportunity broadcast: {}", e))?;
-        
-        if broadcast["type"] == "OpportunityBroadcast" {
-            let opp_id = broadcast["opp_id"].as_str().unwrap_or("").to_string();
-            let strategy_id = broadcast["strategy_id"].as_str().unwrap_or("").to_string();
-            let opportunity = broadcast["opportunity"].to_string();
-            // Store opportunity
-            self.active_opportunities.insert(opp_id.clone(), OpportunityData {
-                opportunity: opportunity.clone(),
-                strategy_id: strategy_id.clone(),
-                finder_node: source().node,
-                received_at: Self::current_timestamp(),
+    // Picks the fast or full path the first time an opportunity is evaluated for dispatch and
+    // records the choice on its audit trail; later calls return the recorded path
+    fn decide_evaluation_path(&mut self, opp_id: &str, size: U256) -> EvaluationPath {
+        if let Some(path) = self.active_opportunities.get(opp_id).and_then(|data| data.evaluation_path.clone()) {
+            return path;
+        }
+        let finder_tier = self.active_opportunities.get(opp_id)
+            .map_or(0, |data| self.finder_tier(&data.finder_node));
+        let path = match self.node_config.fast_path_rule_for(size, finder_tier) {
+            Some((rule_index, rule)) => EvaluationPath::Fast {
+                rule_index,
+                skipped_checks: rule.skip_checks.clone(),
+            },
+            None => EvaluationPath::Full,
+        };
+        let rule = match &path {
+            EvaluationPath::Fast { rule_index, .. } => self.node_config.fast_path_rules.get(*rule_index).cloned(),
+            EvaluationPath::Full => None,
+        };
+        if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+            data.evaluation_path = Some(path.clone());
+            data.audit_trail.push(JournalEntry {
+                timestamp: Self::current_timestamp(),
+                kind: "EvaluationPath".to_string(),
+                detail: serde_json::json!({
+                    "path": path,
+                    "size": size.to_string(),
+                    "rule": rule,
+                }),
             });
-            
-            println!("\n🌐 P2P: Received opportunity {} from peer {}", opp_id, source().node);
-            
-            // Auto-evaluate and submit intent if we're a capital provider
-            if self.node_config.capital_provider_enabled && strategy_id == "aave-liquidation" {
-                self.evaluate_and_submit_intent(opp_id.clone()).await?;
-            }
         }
-        
-        Ok("ACK".to_string())
+        path
     }
     
-    #[remote]
-    async fn receive_intent_submission(&mut self, message_json: String) -> Result<String, String> {
-        let intent_msg: serde_json::Value = serde_json::from_str(&message_json)
-            .map_err(|e| format!("Invalid intent submission: {}", e))?;
-        
-        if intent_msg["type"] == "IntentSubmission" {
-            let opp_id = intent_msg["opp_id"].as_str().unwrap_or("").to_string();
-            let intent = intent_msg["intent"].to_string();
-            let max_amount = intent_msg["max_amount"].as_str().unwrap_or("0").to_string();
-            let expires_block = intent_msg["expires_block"].as_u64().unwrap_or(0);
-            // Store intent
-            self.submitted_intents
-                .entry(opp_id.clone())
-                .or_default()
-                .push(IntentData {
-                    intent: intent.clone(),
-                    submitter_node: source().node,
-                    max_amount: max_amount.to_string(),
-                    expires_block,
-                    received_at: Self::current_timestamp(),
-                });
-            
-            println!("\n💰 P2P: Received intent from {} for opportunity {}", source().node, opp_id);
-            println!("   Max amount: {}", max_amount);
-            println!("   Total intents for this opp: {}", self.submitted_intents.get(&opp_id).map(|v| v.len()).unwrap_or(0) + 1);
-            
-            // Trigger allocation planning if we're an executor
-            if self.node_config.executor_enabled {
-                self.plan_and_execute_opportunities().await?;
-            }
+    // Tells peers an execution is waiting on more providers; repeated identical notices are suppressed
+    fn announce_provider_shortfall(&mut self, opp_id: &str, distinct_providers: u32, min_providers: u32) {
+        let shortfall = ProviderShortfall { distinct_providers, min_providers };
+        let Some(data) = self.active_opportunities.get_mut(opp_id) else {
+            return;
+        };
+        if data.provider_shortfall.as_ref() == Some(&shortfall) {
+            return;
         }
+        data.provider_shortfall = Some(shortfall);
         
-        Ok("ACK".to_string())
+        println!("\n🙋 Opportunity {} is waiting on capital providers: {} of {} committed",
+            opp_id, distinct_providers, min_providers);
+        let notice = MevMessage::ProviderShortfall {
+            opp_id: opp_id.to_string(),
+            distinct_providers,
+            min_providers,
+        };
+        self.send_to_peers(notice.handler(), &Self::message_json(&notice));
     }
     
-    #[remote]
-    async fn receive_execution_receipt(&mut self, message_json: String) -> Result<String, String> {
-        let receipt_msg: serde_json::Value = serde_json::from_str(&message_json)
-            .map_err(|e| format!("Invalid execution receipt: {}", e))?;
-        
-        if receipt_msg["type"] == "ExecutionReceipt" {
-            let opp_id = receipt_msg["opp_id"].as_str().unwrap_or("").to_string();
-            let receipt = receipt_msg["receipt"].to_string();
-            let proceeds: ProceedsBreakdown = serde_json::from_value(receipt_msg["proceeds"].clone())
-                .unwrap_or(ProceedsBreakdown {
-                    asset: "ETH".to_string(),
-                    total: "0".to_string(),
-                    gas_cost_usd: "0".to_string(),
-                    finder_fee: "0".to_string(),
-                    executor_fee: "0".to_string(),
-                    net_profit: "0".to_string(),
-                });
-            // Calculate our share of proceeds
-            let our_share_str = self.calculate_our_proceeds_share(proceeds);
-            
-            // Store receipt
-            self.execution_receipts.insert(opp_id.clone(), ReceiptData {
-                receipt: receipt.clone(),
-                executor_node: source().node,
-                our_proceeds: our_share_str.clone(),
-                verified_at: Self::current_timestamp(),
-            });
-            
-            println!("Received execution receipt for opportunity {} with our proceeds: {}", 
-                opp_id, our_share_str);
+    // Extends a lapsing intent window once when coverage clears window_extension_coverage_bps
+    // and the new window still ends DISPATCH_SAFETY_MARGIN_BLOCKS before the execution deadline.
+    // Needs a chain head to check that; without one the window lapses as before.
+    fn extend_intent_window(&mut self, opp_id: &str, opportunity: &AaveLiquidationOpportunity, intents: &[IntentData]) -> bool {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let Some(head) = self.chain_head.map(|head| head.estimate(now)) else {
+            return false;
+        };
+        let coverage = intent_coverage(opportunity, intents);
+        let max_repay = opportunity.max_repay_amount.parse::<U256>().unwrap_or(U256::ZERO);
+        let threshold = U256::from(self.config_for(opp_id).window_extension_coverage_bps);
+        let new_deadline = head + INTENT_WINDOW_EXTENSION_BLOCKS;
+        let covered_enough = !max_repay.is_zero() && coverage * U256::from(10_000u64) >= max_repay * threshold;
+        let room = new_deadline + DISPATCH_SAFETY_MARGIN_BLOCKS <= opportunity.deadline_block;
+        
+        let extension = IntentWindowExtension {
+            new_deadline,
+            coverage: coverage.to_string(),
+            max_repay_amount: max_repay.to_string(),
+            extended_by: our().node,
+            extended_at: now.to_string(),
+        };
+        self.record_journal("IntentWindowExtension", serde_json::json!({
+            "opp_id": opp_id,
+            "extended": covered_enough && room,
+            "coverage": extension.coverage,
+            "max_repay_amount": extension.max_repay_amount,
+            "coverage_threshold_bps": self.config_for(opp_id).window_extension_coverage_bps,
+            "new_deadline": new_deadline,
+            "deadline_block": opportunity.deadline_block,
+        }));
+        if !covered_enough || !room {
+            return false;
         }
         
-        Ok("ACK".to_string())
+        println!("\n⏳ Extending intent window for {} to block {}: coverage {} / {}",
+            opp_id, new_deadline, coverage, max_repay);
+        if let Some(data) = self.active_opportunities.get_mut(opp_id) {
+            data.window_extension = Some(extension);
+        }
+        let notice = MevMessage::IntentWindowExtended {
+            opp_id: opp_id.to_string(),
+            new_deadline,
+        };
+        self.send_to_peers(notice.handler(), &Self::message_json(&notice));
+        true
     }
     
-    // HELPER FUNCTIONS
-    
-    #[local]
-    async fn announce_to_peer(&self, peer_node: String) -> Result<(), String> {
-        let node_info = NodeInfo {
-            node_id: our().node.clone(),
-            app_version: env!("CARGO_PKG_VERSION").to_string(),
-            roles: self.get_enabled_roles(),
-            capital_assets: self.available_balances.keys().cloned().collect(),
-        };
+    // Stores a receipt from our bridge, closes its pending execution and shares it with peers
+    fn store_artemis_receipt(&mut self, receipt: AaveLiquidationReceipt) {
+        if !matches!(receipt.status, ExecutionStatus::Pending) {
+            self.pending_executions.remove(&receipt.opp_id);
+        }
+        println!("\n✅ Execution Receipt from Artemis:");
+        println!("   Opp ID: {}", receipt.opp_id);
+        println!("   Status: {:?}", receipt.status);
+        println!("   Total proceeds: ${}", receipt.total_proceeds);
+        println!("   Gas cost: ${} USDC", receipt.gas_paid_usdc);
         
-        let announcement = serde_json::json!({
-            "type": "NodeAnnouncement",
-            "node_info": node_info,
-            "capabilities": self.node_config.enabled_strategies.clone(),
-            "timestamp": Self::current_timestamp(),
+        // Store the receipt
+        let proceeds = self.proceeds_breakdown(&receipt);
+        let receipt_json = serde_json::to_string(&receipt).unwrap();
+        let our_share = self.calculate_our_proceeds_share(&receipt.opp_id, &our().node, &receipt_json, &proceeds);
+        let credited = self.credit_proceeds(&receipt.opp_id, &receipt_json, &proceeds.asset, &our_share);
+        let postings = self.post_capital_owed(&receipt);
+        self.count_origin_execution(&receipt.opp_id);
+        let metrics_strategy = self.note_execution_metrics(&receipt.opp_id, &receipt, "artemis-bot", &proceeds.asset, &our_share);
+        self.credit_finder_settlement(&receipt.opp_id, &receipt);
+        self.set_opportunity_status(&receipt.opp_id, OpportunityStatus::from_receipt(&receipt.status));
+        let capital_asset = self.capital_commitments.get(&receipt.opp_id).map(|commitment| commitment.asset.clone());
+        let drawn = if matches!(receipt.status, ExecutionStatus::Pending) {
+            U256::ZERO
+        } else {
+            self.settle_commitment(&receipt.opp_id, &receipt_json)
+        };
+        let mut receipt_data = ReceiptData::new(receipt_json, "artemis-bot".to_string(), our_share);
+        receipt_data.proceeds_asset = Some(proceeds.asset.clone());
+        self.begin_finality(&receipt.opp_id, &mut receipt_data, AppliedEffects {
+            proceeds_asset: proceeds.asset.clone(),
+            proceeds: credited.to_string(),
+            capital_asset,
+            drawn: drawn.to_string(),
+            postings,
+            metrics_strategy,
         });
+        self.execution_receipts.insert(receipt.opp_id.clone(), receipt_data);
+        self.mirror_receipt(&receipt.opp_id);
+        self.notify_receipt_settled(&receipt.opp_id);
         
-        let process_id = format!("hyper-mev:hyper-mev:{}", self.node_config.publisher)
-            .parse::<ProcessId>()
-            .map_err(|e| format!("Invalid ProcessId: {}", e))?;
-        
-        let target = Address::new(peer_node, process_id);
-        let wrapper = serde_json::json!({ "ReceiveNodeAnnouncement": announcement.to_string() });
-        
-        let _ = Request::new()
-            .target(target)
-            .body(serde_json::to_vec(&wrapper).unwrap())
-            .expects_response(30)
-            .send();
-        
-        Ok(())
+        println!("Stored execution receipt for opportunity {}", receipt.opp_id);
+        self.amend_expiry_for_receipt(&receipt.opp_id);
+        if receipt.block_number > 0 {
+            self.observe_block(receipt.block_number);
+        }
+
+        let distribution = matches!(receipt.status, ExecutionStatus::Success)
+            .then(|| self.proceeds_distribution(&receipt, &proceeds));
+
+        // Also broadcast receipt to P2P peers (synchronously, fire-and-forget)
+        let receipt_msg = MevMessage::ExecutionReceipt {
+            opp_id: receipt.opp_id.clone(),
+            strategy_id: "aave-liquidation".to_string(),
+            receipt: serde_json::to_string(&receipt).unwrap(),
+            block_number: receipt.block_number,
+            tx_hash: receipt.tx_hash.clone(),
+            gas_used: receipt.gas_paid_usdc.parse::<u64>().unwrap_or(0).to_string(),
+            proceeds,
+            signature: None,
+        };
+
+        self.broadcast_message(&receipt_msg);
+        if let Some(distribution) = distribution {
+            self.publish_proceeds_distribution(distribution);
+        }
     }
     
-    #[local]
-    async fn evaluate_and_submit_intent(&mut self, opp_id: String) -> Result<(), String> {
-        let opportunity_data = self.active_opportunities.get(&opp_id)
-            .ok_or("Opportunity not found")?;
-        
-        if opportunity_data.strategy_id == "aave-liquidation" {
-            let opportunity: AaveLiquidationOpportunity = serde_json::from_str(&opportunity_data.opportunity)
-                .map_err(|e| format!("Failed to parse opportunity: {}", e))?;
-            
-            // Check if we have capital for this asset
-            let default_balance = "0".to_string();
-            let available_str = self.available_balances.get(&opportunity.repay_asset).unwrap_or(&default_balance);
-            let available = available_str.parse::<U256>().unwrap_or(U256::ZERO);
-            let max_repay = opportunity.max_repay_amount.parse::<U256>().unwrap_or(U256::ZERO);
-            
-            println!("\n🔍 Evaluating opportunity as Capital Provider:");
-            println!("   Available capital: {} USDC", available);
-            println!("   Required capital: {} USDC", max_repay);
-            
-            if available < max_repay {
-                println!("   ❌ Insufficient capital");
-                return Ok(()); // Not enough capital
-            }
-            
-            // Check profitability
-            let profit = opportunity.estimated_profit_usd.parse::<U256>().unwrap_or(U256::ZERO);
-            let min_profit = self.node_config.min_profit_threshold_usd.parse::<U256>().unwrap_or(U256::ZERO);
-            if profit < min_profit {
-                return Ok(()); // Not profitable enough
+    // As executor we owe each other provider the capital a successful execution drew from it. Call
+    // before the receipt is stored: one replacing an already successful receipt posts nothing.
+    fn post_capital_owed(&mut self, receipt: &AaveLiquidationReceipt) -> Vec<LedgerPosting> {
+        let already_posted = self.execution_receipts.get(&receipt.opp_id)
+            .map_or(false, |data| data.summary().status == "Success");
+        if already_posted || !matches!(receipt.status, ExecutionStatus::Success) {
+            return Vec::new();
+        }
+        let me = our().node;
+        let mut postings = Vec::new();
+        for usage in receipt.used_amounts.iter().filter(|usage| usage.node_id != me) {
+            let drawn = usage.amount_used.parse::<U256>().unwrap_or(U256::ZERO);
+            let delta = SignedAmount::owed_by_us(drawn);
+            if !drawn.is_zero() && self.post_settlement(&receipt.opp_id, &usage.node_id, &usage.asset, delta, "CapitalDrawn") {
+                postings.push(LedgerPosting { peer: usage.node_id.clone(), asset: usage.asset.clone(), delta });
             }
-            
-            // Submit intent
-            let intent = AaveLiquidationIntent {
-                opp_id: opp_id.to_string(),
-                asset: opportunity.repay_asset.clone(),
-                max_amount: available.min(max_repay).to_string(),
-                min_bonus_bps: opportunity.min_bonus_bps,
-                expires_block: opportunity.deadline_block,
-            };
-            
-            self.broadcast_intent(intent).await?;
         }
-        
-        Ok(())
+        postings
     }
     
-    #[local]
-    async fn broadcast_intent(&self, intent: AaveLiquidationIntent) -> Result<(), String> {
-        let intent_msg = serde_json::json!({
-            "type": "IntentSubmission",
-            "opp_id": intent.opp_id.clone(),
-            "strategy_id": "aave-liquidation",
-            "intent": serde_json::to_string(&intent).map_err(|e| format!("Serialization error: {}", e))?,
-            "max_amount": intent.max_amount.clone(),
-            "min_bonus_bps": intent.min_bonus_bps,
-            "expires_block": intent.expires_block,
-        });
-        
-        let process_id = format!("hyper-mev:hyper-mev:{}", self.node_config.publisher)
-            .parse::<ProcessId>()
-            .map_err(|e| format!("Invalid ProcessId: {}", e))?;
-        
-        for peer_node in &self.known_peers {
-            let target = Address::new(peer_node.clone(), process_id.clone());
-            let wrapper = serde_json::json!({ "ReceiveIntentSubmission": intent_msg.to_string() });
-            
-            let _ = Request::new()
-                .target(target)
-                .body(serde_json::to_vec(&wrapper).unwrap())
-                .expects_response(30)
-                .send();
+    // Posts what a distribution moves between us and its other parties. One replacing an earlier
+    // posted distribution for the opportunity posts only the difference. Call before the record
+    // is stored.
+    fn post_distribution(&mut self, distribution: &ProceedsDistribution) {
+        let me = our().node;
+        let previous = self.proceeds_distributions.records.get(&distribution.opp_id)
+            .filter(|record| !record.awaiting_verification)
+            .map(|record| distribution_postings(&record.distribution, &me))
+            .unwrap_or_default();
+        let mut postings = distribution_postings(distribution, &me);
+        for (key, earlier) in previous {
+            let posting = postings.entry(key).or_default();
+            *posting = posting.checked_sub(earlier).unwrap_or(*posting);
+        }
+        for ((peer, asset), delta) in postings.into_iter().filter(|(_, delta)| !delta.is_zero()) {
+            self.post_settlement(&distribution.opp_id, &peer, &asset, delta, "ProceedsDistributed");
         }
-        
-        Ok(())
     }
     
-    #[local]
-    async fn plan_and_execute_opportunities(&mut self) -> Result<(), String> {
-        // Send available intents to Artemis bot for execution
-        for (opp_id, intents) in &self.submitted_intents {
-            if !intents.is_empty() && self.active_opportunities.contains_key(opp_id) {
-                let opportunity_data = self.active_opportunities.get(opp_id).unwrap();
-                
-                if opportunity_data.strategy_id == "aave-liquidation" {
-                    // Send intents to Artemis for execution
-                    let intent_msg = serde_json::json!({
-                        "type": "IntentCollection",
-                        "opp_id": opp_id.clone(),
-                        "intents": intents.clone(),
-                    });
-                    self.send_to_artemis_json(intent_msg.to_string()).await?;
-                    
-                    println!("\n🎮 Executing opportunity {}:", opp_id);
-                    println!("   Sending {} intents to Artemis for execution", intents.len());
-                }
+    // Moves our position with `peer` and journals the posting; false if it couldn't be posted
+    fn post_settlement(&mut self, opp_id: &str, peer: &str, asset: &str, delta: SignedAmount, cause: &str) -> bool {
+        match ledger::post(&mut self.settlement_ledger, peer, asset, delta) {
+            Ok(position) => {
+                let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+                self.settlement_epochs.record(opp_id, peer, asset, cause, delta, now);
+                self.record_journal("SettlementPosted", serde_json::json!({
+                    "opp_id": opp_id,
+                    "peer": peer,
+                    "asset": asset,
+                    "cause": cause,
+                    "delta": delta,
+                    "position": position,
+                }));
+                true
+            }
+            Err(e) => {
+                println!("   Cannot post {} {} for {} with {}: {}", cause, delta, opp_id, peer, e);
+                false
             }
         }
-        
-        Ok(())
     }
     
-    #[local]
-    fn calculate_our_proceeds_share(&self, proceeds: ProceedsBreakdown) -> String {
-        // Simple calculation - in reality would be based on capital contribution
-        let net_profit = proceeds.net_profit.parse::<U256>().unwrap_or(U256::ZERO);
-        let share = net_profit / U256::from(2); // Example: 50% share
-        share.to_string()
+    // Once the settlement epoch has run its length, closes it and sends a netting statement to
+    // each peer its entries leave us owing in an asset, as long as the ledger still shows us owing
+    fn close_settlement_epoch(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let head = self.current_block();
+        let epochs = &mut self.settlement_epochs;
+        if epochs.started_at == 0 {
+            epochs.started_at = now;
+        }
+        if epochs.started_block.is_none() {
+            epochs.started_block = head;
+        }
+        if !self.node_config.settlement_epoch.elapsed(epochs.started_at, epochs.started_block, now, head) {
+            return;
+        }
+        let closed = epochs.close(now, head);
+        let recorded_in = epochs.epoch;
+        let proposals = epochs.proposals(closed);
+        let entries = epochs.entries.iter().filter(|entry| entry.epoch == closed).count();
+        let me = our().node;
+        let mut sent = Vec::new();
+        for proposal in proposals {
+            // A payment recorded since may already have closed the position
+            if !ledger::position_of(&self.settlement_ledger, &proposal.peer, &proposal.asset).is_negative() {
+                continue;
+            }
+            let statement = NettingStatement {
+                statement_id: NettingStatement::id(closed, &me, &proposal.peer, &proposal.asset),
+                epoch: closed,
+                recorded_in,
+                debtor: me.clone(),
+                creditor: proposal.peer.clone(),
+                asset: proposal.asset.clone(),
+                items: proposal.items,
+                net: proposal.net,
+                proposed_at: now,
+                status: StatementStatus::Proposed,
+            };
+            let opp_ids: Vec<String> = statement.items.iter().map(|item| item.opp_id.clone()).collect();
+            self.settlement_epochs.mark(&statement.creditor, &statement.asset, &opp_ids,
+                EntryNetting::Proposed { statement_id: statement.statement_id.clone() });
+            let message = MevMessage::NettingStatement {
+                statement_id: statement.statement_id.clone(),
+                epoch: closed,
+                asset: statement.asset.clone(),
+                items: statement.items.clone(),
+                net: statement.net,
+                signature: None,
+            };
+            self.send_to_peer(&statement.creditor, message.handler(), &self.sign_message(&message));
+            self.record_journal("NettingStatementSent", serde_json::json!({
+                "statement_id": statement.statement_id,
+                "creditor": statement.creditor,
+                "asset": statement.asset,
+                "items": statement.items.len(),
+                "net": statement.net,
+            }));
+            sent.push(statement.statement_id.clone());
+            self.settlement_epochs.statements.insert(statement.statement_id.clone(), statement);
+        }
+        println!("\n🧾 Settlement epoch {} closed: {} entries, {} netting statements sent", closed, entries, sent.len());
+        self.record_journal("SettlementEpochClosed", serde_json::json!({
+            "epoch": closed,
+            "closed_at": now,
+            "head": head,
+            "entries": entries,
+            "statements": sent,
+        }));
     }
     
+    // Records our distribution for a successful execution and sends it to peers behind the receipt
+    fn publish_proceeds_distribution(&mut self, distribution: ProceedsDistribution) {
+        let owed_to_us = distribution.owed_to(&our().node).to_string();
+        self.post_distribution(&distribution);
+        self.proceeds_distributions.insert(DistributionRecord {
+            distribution: distribution.clone(),
+            received_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+            owed_to_us,
+            flags: Vec::new(),
+            awaiting_verification: false,
+        });
+        self.send_to_peers("ReceiveProceedsDistribution", &serde_json::json!({
+            "type": "ProceedsDistribution",
+            "opp_id": distribution.opp_id,
+            "distribution": distribution,
+        }));
+    }
     
-    #[local]
-    async fn send_to_artemis_json(&self, json_message: String) -> Result<(), String> {
-        if let Some(channel_id) = self.artemis_channel_id {
-            send_ws_push(
-                channel_id,
-                WsMessageType::Text,
-                LazyLoadBlob {
-                    mime: None,
-                    bytes: json_message.as_bytes().to_vec(),
-                },
-            );
-            println!("Sent message to Artemis bot: {}", json_message);
+    // Synchronous helper functions for WebSocket handler. Sends to the primary bridge channel.
+    fn send_to_artemis_json_sync(&self, json_message: &str) -> Result<(), String> {
+        let opp_id = serde_json::from_str::<serde_json::Value>(json_message).ok()
+            .and_then(|message| message["opp_id"].as_str().map(str::to_string));
+        if opp_id.as_deref().is_some_and(is_self_test_id) {
+            return Err("Self-test artifacts never go to the bridge".to_string());
+        }
+        if let Some(channel_id) = self.artemis_channels.primary() {
+            self.push_to_artemis_channel(channel_id, json_message);
         } else {
             println!("No Artemis bot connected");
         }
         Ok(())
     }
-
-
-
-
-    #[local]
-    fn get_enabled_roles(&self) -> Vec<NodeRole> {
-        let mut roles = Vec::new();
-        if self.node_config.finder_enabled {
-            roles.push(NodeRole::Finder);
+    
+    fn push_to_artemis_channel(&self, channel_id: u32, json_message: &str) {
+        self.record_message(Boundary::ArtemisOutbound, Some(channel_id.to_string()), "", json_message);
+        send_ws_push(
+            channel_id,
+            WsMessageType::Text,
+            LazyLoadBlob {
+                mime: None,
+                bytes: json_message.as_bytes().to_vec(),
+            },
+        );
+        println!("Sent message to Artemis bot (channel {}): {}", channel_id, json_message);
+    }
+    
+    // Validations sent to a lost primary won't be answered; their consumers ask again on the
+    // next tick. Executions it held are put to the channel taking over, if any.
+    fn on_primary_channel_lost(&mut self) {
+        self.validation_broker.abandon_in_flight();
+        // Probes sent on the lost channel won't be answered; the next assessment asks again
+        self.risk_probes_pending.clear();
+        if let Some(channel_id) = self.artemis_channels.primary() {
+            println!("   Channel {} is now the primary bridge connection", channel_id);
+            self.begin_bridge_sync(channel_id);
         }
-        if self.node_config.capital_provider_enabled {
-            roles.push(NodeRole::CapitalProvider);
+    }
+    
+    // Drops bridge channels that have gone silent while another is live
+    fn prune_stale_artemis_channels(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let (dropped, primary_dropped) = self.artemis_channels.prune_stale(now);
+        if dropped.is_empty() {
+            return;
         }
-        if self.node_config.executor_enabled {
-            roles.push(NodeRole::Executor);
+        println!("\n🔌 Dropped silent Artemis channels {:?}", dropped);
+        if primary_dropped {
+            self.on_primary_channel_lost();
         }
-        roles
     }
     
-}
+    // Stores and broadcasts an opportunity found on this node, by the bridge or entered by hand.
+    // Local finds are recorded under the artemis-bot finder whatever their origin.
+    fn originate_opportunity(&mut self, mut opportunity: AaveLiquidationOpportunity, origin: OpportunityOrigin) {
+        println!("   Opp ID: {}", opportunity.opp_id);
+        println!("   Victim: {}", opportunity.victim_address);
+        println!("   Health Factor: {}", opportunity.health_factor);
+        println!("   Profit: ${} USD", opportunity.estimated_profit_usd);
+        if let Err(reason) = opportunity.validate(self.current_block()) {
+            println!("   ❌ Not originating: {}", reason);
+            return;
+        }
+        if is_self_test_id(&opportunity.opp_id) {
+            println!("   ❌ Not originating: self-test ids never enter live state");
+            return;
+        }
+        if !self.node_config.strategy_enabled("aave-liquidation") {
+            println!("   ❌ Not originating: aave-liquidation is disabled");
+            return;
+        }
+        
+        // A victim that keeps crossing the threshold is the same opportunity, not a new one
+        let key = origination_key("aave-liquidation", &opportunity.victim_address);
+        if let Some(existing_opp_id) = self.throttle_origination(&key, &opportunity.opp_id) {
+            if self.active_opportunities.contains_key(&self.canonical_opp_id(&existing_opp_id)) {
+                println!("   ↪️  Victim already has live opportunity {}; sending an update instead", existing_opp_id);
+                self.publish_opportunity_update(&existing_opp_id, &opportunity);
+            } else {
+                println!("   ⏸️  Victim is cooling down after {}; not originating", existing_opp_id);
+            }
+            return;
+        }
+        
+        // Store the opportunity under our next finder sequence number, declaring our split rules
+        opportunity.distribution = self.node_config.distribution_scheme.clone();
+        let deadline_blocks = self.strategy_configs.get("aave-liquidation").map_or(0, |strategy| strategy.execution_deadline_blocks);
+        if let Some(head) = self.current_block().filter(|_| deadline_blocks > 0) {
+            opportunity.deadline_block = opportunity.deadline_block.min(head + deadline_blocks);
+        }
+        let received_at = Self::current_timestamp();
+        self.next_finder_seq += 1;
+        let finder_seq = self.next_finder_seq;
+        self.originated_by_seq.insert(finder_seq, opportunity.opp_id.clone());
+        while self.originated_by_seq.len() > MAX_TRACKED_GAPS as usize {
+            self.originated_by_seq.pop_first();
+        }
+        let opportunity_json = serde_json::to_string(&opportunity).unwrap();
+        self.mirror_record(MirrorRecordKind::Opportunity, &opportunity.opp_id, serde_json::json!({
+            "finder_seq": finder_seq,
+            "opportunity": opportunity_json,
+        }).to_string());
+        // Our own broadcast echoed back through a forwarding peer is a duplicate
+        let now = received_at.parse::<u64>().unwrap_or(0);
+        self.seen_broadcasts.record(&opportunity.opp_id, intent_hash(&opportunity_json), now);
+        let claim = FinderClaim {
+            finder: our().node,
+            opp_id: opportunity.opp_id.clone(),
+            seen_at: received_at,
+            finder_fee_bps: self.node_config.finder_fee_bps,
+            opportunity: opportunity_json.clone(),
+        };
+        let mut data = OpportunityData::new(
+            opportunity_json,
+            "aave-liquidation".to_string(),
+            "artemis-bot".to_string(),
+            self.node_config.executor_preference.clone(),
+        );
+        data.config_revision = self.config_revision;
+        data.received_block = self.current_block();
+        data.holder_since_block = data.received_block;
+        // Executors down for maintenance at the deadline don't get execution rights
+        let deadline_at = self.deadline_at(&data);
+        let preferred = data.executor_preference.len();
+        data.executor_preference.retain(|executor| !self.in_maintenance_at(executor, deadline_at));
+        if data.executor_preference.len() < preferred {
+            println!("   Left {} executor(s) in maintenance out of the preference list", preferred - data.executor_preference.len());
+        }
+        self.elect_executor(&opportunity.opp_id, &mut data);
+        data.claiming_finders.push(claim.clone());
+        data.origin = origin;
+        let broadcast_msg = self.opportunity_broadcast_message(&opportunity.opp_id, &data, finder_seq, false);
+        // A peer found it first: link our claim to theirs, and still broadcast ours so
+        // other peers can link it too
+        self.origin_stats.entry(data.origin.class()).or_default().received += 1;
+        let canonical = self.link_duplicate_find("aave-liquidation", claim);
+        if canonical.is_none() {
+            self.active_opportunities.insert(opportunity.opp_id.clone(), data);
+            self.collection_recency.touch(&opportunity.opp_id);
+            self.performance_metrics.strategy("aave-liquidation").opportunities_seen += 1;
+            self.notify_ui(UiEventKind::OpportunityAdded, serde_json::json!({
+                "opp_id": opportunity.opp_id,
+                "strategy_id": "aave-liquidation",
+                "finder_node": our().node,
+                "received_from": null,
+                "opportunity": opportunity,
+            }));
+        }
+        self.assess_risk(&canonical.unwrap_or_else(|| opportunity.opp_id.clone()));
+        
+        println!("   ✅ Broadcasting to {} P2P peers...", self.known_peers.len());
 
-impl HyperMevApp {
-    // Helper to get current timestamp
-    fn current_timestamp() -> String {
-        format!("{}", std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs())
-    }
-    
-    // Synchronous helper functions for WebSocket handler
-    fn send_to_artemis_json_sync(&self, json_message: &str) -> Result<(), String> {
-        if let Some(channel_id) = self.artemis_channel_id {
-            send_ws_push(
-                channel_id,
-                WsMessageType::Text,
-                LazyLoadBlob {
-                    mime: None,
-                    bytes: json_message.as_bytes().to_vec(),
-                },
-            );
-            println!("Sent message to Artemis bot: {}", json_message);
-        } else {
-            println!("No Artemis bot connected");
+        // Also broadcast to P2P peers (synchronously, fire-and-forget), noting who it went to
+        let reached = self.fan_out_opportunity(&opportunity.opp_id, "aave-liquidation", broadcast_msg.handler(), &Self::message_json(&broadcast_msg));
+        if let Some(data) = self.active_opportunities.get_mut(&opportunity.opp_id) {
+            data.broadcast_to.extend(reached);
         }
-        Ok(())
     }
     
-    fn handle_artemis_message_json(&mut self, json_str: &str) -> Result<(), String> {
+    fn handle_artemis_message_json(&mut self, channel_id: u32, json_str: &str) -> Result<(), String> {
         let message: serde_json::Value = serde_json::from_str(json_str)
             .map_err(|e| format!("Failed to parse JSON: {}", e))?;
         
@@ -824,93 +12656,113 @@ impl HyperMevApp {
                 let opportunity: AaveLiquidationOpportunity = serde_json::from_value(message["opportunity"].clone())
                     .map_err(|e| format!("Failed to parse opportunity: {}", e))?;
                 println!("\n📡 Received opportunity from Artemis:");
-                println!("   Opp ID: {}", opportunity.opp_id);
-                println!("   Victim: {}", opportunity.victim_address);
-                println!("   Health Factor: {}", opportunity.health_factor);
-                println!("   Profit: ${} USD", opportunity.estimated_profit_usd);
-                
-                // Store the opportunity
-                self.active_opportunities.insert(opportunity.opp_id.clone(), OpportunityData {
-                    opportunity: serde_json::to_string(&opportunity).unwrap(),
-                    strategy_id: "aave-liquidation".to_string(),
-                    finder_node: "artemis-bot".to_string(),
-                    received_at: Self::current_timestamp(),
-                });
-                
-                println!("   ✅ Broadcasting to {} P2P peers...", self.known_peers.len());
-
-                // Also broadcast to P2P peers (synchronously, fire-and-forget)
-                let broadcast_msg = serde_json::json!({
-                    "type": "OpportunityBroadcast",
-                    "opp_id": opportunity.opp_id.clone(),
-                    "strategy_id": "aave-liquidation",
-                    "opportunity": serde_json::to_string(&opportunity).unwrap(),
-                    "finder_fee_bps": self.node_config.finder_fee_bps,
-                    "deadline_block": opportunity.deadline_block,
-                });
-
-                if let Ok(process_id) = format!("hyper-mev:hyper-mev:{}", self.node_config.publisher)
-                    .parse::<ProcessId>() {
-                    for peer_node in &self.known_peers {
-                        let target = Address::new(peer_node.clone(), process_id.clone());
-                        let wrapper = serde_json::json!({ "ReceiveOpportunityBroadcast": broadcast_msg.to_string() });
-                        let _ = Request::new()
-                            .target(target)
-                            .body(serde_json::to_vec(&wrapper).unwrap())
-                            .expects_response(30)
-                            .send();
-                    }
-                }
+                let bridge_version = self.artemis_channels.connection(channel_id)
+                    .and_then(|connection| connection.bridge_version.clone())
+                    .unwrap_or_else(|| "unknown".to_string());
+                self.originate_opportunity(opportunity, OpportunityOrigin::Bot { bridge_version });
             }
             Some("ExecutionReceipt") => {
                 let receipt: AaveLiquidationReceipt = serde_json::from_value(message["receipt"].clone())
                     .map_err(|e| format!("Failed to parse receipt: {}", e))?;
-                println!("\n✅ Execution Receipt from Artemis:");
-                println!("   Opp ID: {}", receipt.opp_id);
-                println!("   Status: {:?}", receipt.status);
-                println!("   Total proceeds: ${}", receipt.total_proceeds);
-                println!("   Gas cost: ${} USDC", receipt.gas_paid_usdc);
-                
-                // Store the receipt
-                self.execution_receipts.insert(receipt.opp_id.clone(), ReceiptData {
-                    receipt: serde_json::to_string(&receipt).unwrap(),
-                    executor_node: "artemis-bot".to_string(),
-                    our_proceeds: receipt.total_proceeds.clone(),
-                    verified_at: Self::current_timestamp(),
-                });
-                
-                println!("Stored execution receipt for opportunity {}", receipt.opp_id);
-
-                // Also broadcast receipt to P2P peers (synchronously, fire-and-forget)
-                let receipt_msg = serde_json::json!({
-                    "type": "ExecutionReceipt",
-                    "opp_id": receipt.opp_id.clone(),
-                    "strategy_id": "aave-liquidation",
-                    "receipt": serde_json::to_string(&receipt).unwrap(),
-                    "block_number": receipt.block_number,
-                    "tx_hash": receipt.tx_hash.clone(),
-                    "gas_used": receipt.gas_paid_usdc.parse::<u64>().unwrap_or(0).to_string(),
-                    "proceeds": {
-                        "asset": "ETH",
-                        "total": receipt.total_proceeds.clone(),
-                        "gas_cost_usd": receipt.gas_paid_usdc.clone(),
-                        "finder_fee": "0",
-                        "executor_fee": "0",
-                        "net_profit": receipt.total_proceeds.clone(),
-                    },
-                });
-
-                if let Ok(process_id) = format!("hyper-mev:hyper-mev:{}", self.node_config.publisher)
-                    .parse::<ProcessId>() {
-                    for peer_node in &self.known_peers {
-                        let target = Address::new(peer_node.clone(), process_id.clone());
-                        let wrapper = serde_json::json!({ "ReceiveExecutionReceipt": receipt_msg.to_string() });
-                        let _ = Request::new()
-                            .target(target)
-                            .body(serde_json::to_vec(&wrapper).unwrap())
-                            .send();
+                self.store_artemis_receipt(receipt);
+            }
+            Some("ValidationResult") => {
+                let result: ValidationResult = serde_json::from_value(message.clone())
+                    .map_err(|e| format!("Failed to parse validation result: {}", e))?;
+                let (waiters, promoted) = self.validation_broker.complete(result.clone(), self.node_config.max_outstanding_validations);
+                for key in promoted {
+                    self.send_validation_request(&key);
+                }
+                self.resume_validation_waiters(&result, waiters);
+            }
+            Some("SyncResponse") => {
+                self.reconcile_bridge_sync(channel_id, &message);
+            }
+            Some("RiskProbeResult") => {
+                let victim = message["victim"].as_str().unwrap_or("").to_lowercase();
+                self.risk_probes_pending.remove(&victim);
+                if let Some(error) = message["error"].as_str() {
+                    println!("   Bridge could not probe {}: {}", victim, error);
+                    return Ok(());
+                }
+                let probe: RiskProbe = serde_json::from_value(message.clone())
+                    .map_err(|e| format!("Failed to parse risk probe: {}", e))?;
+                self.risk_probes.insert(victim.clone(), probe);
+                self.reassess_victim_risk(&victim);
+            }
+            Some("ExecutionStatusReport") => {
+                let opp_id = message["opp_id"].as_str().unwrap_or("").to_string();
+                let status = message["status"].as_str().unwrap_or("Unknown").to_string();
+                let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+                let held = self.execution_receipts.contains_key(&opp_id);
+                match resume_execution(self.pending_executions.get(&opp_id), held, Some(status.as_str()), now) {
+                    ExecutionResume::RecoverReceipt => {
+                        let receipt: AaveLiquidationReceipt = serde_json::from_value(message["receipt"].clone())
+                            .map_err(|e| format!("Failed to parse recovered receipt: {}", e))?;
+                        println!("\n🔁 Recovered receipt for {} from the bridge", opp_id);
+                        self.store_artemis_receipt(receipt);
                     }
+                    // InProgress: keep waiting. Unknown: the bridge has no record; the timeout decides
+                    ExecutionResume::Await(_) | ExecutionResume::Redispatch | ExecutionResume::GiveUp => {
+                        println!("\n🔁 Bridge reports execution {} as {}", opp_id, status);
+                        if let Some(pending) = self.pending_executions.get_mut(&opp_id) {
+                            pending.last_status = Some(status);
+                        }
+                    }
+                    ExecutionResume::Settled | ExecutionResume::Dispatch | ExecutionResume::Query => {}
+                }
+            }
+            Some("OpportunityUpdate") => {
+                let opportunity: AaveLiquidationOpportunity = serde_json::from_value(message["opportunity"].clone())
+                    .map_err(|e| format!("Failed to parse opportunity: {}", e))?;
+                println!("\n🔄 Artemis updated opportunity {} (profit ${})", opportunity.opp_id, opportunity.estimated_profit_usd);
+                let key = origination_key("aave-liquidation", &opportunity.victim_address);
+                if let Some(record) = self.origination_index.get_mut(&key) {
+                    record.last_seen = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+                }
+                let opp_id = opportunity.opp_id.clone();
+                self.publish_opportunity_update(&opp_id, &opportunity);
+            }
+            Some("PositionWatched") => {
+                let borrower = message["borrower"].as_str().unwrap_or("").to_lowercase();
+                if borrower.is_empty() {
+                    return Err("PositionWatched without a borrower".to_string());
+                }
+                self.watched_positions.insert(borrower.clone(), PositionData {
+                    borrower: borrower.clone(),
+                    collateral_asset: message["collateral_asset"].as_str().unwrap_or("").to_string(),
+                    debt_asset: message["debt_asset"].as_str().unwrap_or("").to_string(),
+                    last_health_factor: message["health_factor"].as_str().unwrap_or("").to_string(),
+                    last_updated: Self::current_timestamp(),
+                    debt_amount: message["debt_amount"].as_str().unwrap_or("0").to_string(),
+                    last_checked_block: message["block"].as_u64().unwrap_or(0),
+                    relayed_by: None,
+                });
+                if let Some(block) = message["block"].as_u64() {
+                    self.observe_block(block);
+                }
+                self.on_local_position_read(&borrower);
+            }
+            Some("OpportunityCancellation") => {
+                // Only our own bridge speaks for our finds, and only for those
+                if !self.artemis_channels.contains(channel_id) {
+                    return Err(format!("Cancellation on channel {} that never completed a handshake", channel_id));
                 }
+                let opp_id = message["opp_id"].as_str().ok_or("OpportunityCancellation without an opp_id")?;
+                let reason = message["reason"].as_str().unwrap_or("CancelledByBridge");
+                println!("\n🛑 Artemis cancels opportunity {} ({})", opp_id, reason);
+                self.cancel_own_opportunity(opp_id, reason)?;
+            }
+            Some("PositionClosed") => {
+                let borrower = message["borrower"].as_str().unwrap_or("").to_lowercase();
+                let reason = message["reason"].as_str().unwrap_or("Unknown").to_string();
+                println!("\n🧹 Artemis closed watched position {} ({})", borrower, reason);
+                self.watched_positions.remove(&borrower);
+                self.withdraw_intents_for_victim(&borrower, &reason);
+                self.record_journal("PositionClosed", serde_json::json!({
+                    "borrower": borrower,
+                    "reason": reason,
+                }));
             }
             Some("IntentCollection") => {
                 println!("Artemis requested intents (unexpected direction)");
@@ -921,6 +12773,26 @@ impl HyperMevApp {
             Some("NodeConfig") => {
                 println!("Artemis acknowledged node config");
             }
+            Some("BlockUpdate") => {
+                let number = message["number"].as_u64()
+                    .ok_or("BlockUpdate without a block number")?;
+                let base_fee_wei = message["base_fee_per_gas"].as_str().and_then(|fee| fee.parse::<u128>().ok());
+                self.observe_block_update(number, message["timestamp"].as_u64().unwrap_or(0), base_fee_wei);
+            }
+            Some("BridgeHealth") => {
+                // Sent by the bridge's engine watchdog when it rebuilds (or gives up on) a wedged engine,
+                // and periodically as a "Report" carrying the execution wallet summary
+                let event = message["event"].as_str().unwrap_or("Unknown").to_string();
+                if let Some(block) = message["latest_block"].as_u64() {
+                    self.observe_block(block);
+                }
+                if event != "Report" {
+                    println!("\n🩺 Artemis bridge health: {} (engine restarts: {})",
+                        event, message["engine_restarts"]);
+                    self.record_journal(&format!("Artemis{}", event), message.clone());
+                }
+                self.artemis_health = Some(message);
+            }
             _ => {
                 println!("Unknown Artemis message type");
             }
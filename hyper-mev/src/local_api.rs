@@ -0,0 +1,172 @@
+// LOCAL API
+// Typed handlers for other processes on this node (a portfolio tracker, a notifier) so they don't
+// scrape the UI's HTTP endpoints. Access is granted per process in NodeConfig.local_api_grants:
+// Query covers the read handlers, Subscribe registering for PoolEvents. Subscribers are persisted;
+// one that fails LOCAL_SUBSCRIBER_MAX_FAILURES deliveries or probes in a row is dropped.
+
+use hyperware_process_lib::ProcessId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::settlement::CounterpartyState;
+use crate::{AaveLiquidationOpportunity, AaveLiquidationReceipt, OpportunityStatus, StatementLine};
+
+pub const LOCAL_SUBSCRIBER_CAPACITY: usize = 32;
+pub const LOCAL_SUBSCRIBER_MAX_FAILURES: u32 = 3;
+// How long a liveness probe waits for the subscriber to answer
+pub const LOCAL_PROBE_TIMEOUT_SECS: u64 = 2;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum LocalScope {
+    Query,
+    Subscribe,
+}
+
+// Grants are keyed by full process id (`name:package:publisher`) or by package (`package:publisher`)
+pub fn granted(grants: &BTreeMap<String, Vec<LocalScope>>, process: &ProcessId, scope: LocalScope) -> bool {
+    let package = format!("{}:{}", process.package(), process.publisher());
+    [process.to_string(), package].iter()
+        .filter_map(|key| grants.get(key))
+        .any(|scopes| scopes.contains(&scope))
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct LocalOpportunityFilter {
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<OpportunityStatus>,
+    // Only those we hold an intent on
+    #[serde(default)]
+    pub participating: Option<bool>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LocalOpportunity {
+    pub opp_id: String,
+    pub strategy_id: String,
+    pub status: OpportunityStatus,
+    pub finder: String,
+    pub participating: bool,
+    // Parsed when the strategy is aave-liquidation
+    pub opportunity: Option<AaveLiquidationOpportunity>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AssetAmount {
+    pub asset: String,
+    pub amount: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LocalCommitment {
+    pub opp_id: String,
+    pub asset: String,
+    pub amount: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LocalCapital {
+    pub balances: Vec<AssetAmount>,
+    pub committed: Vec<AssetAmount>,
+    pub free: Vec<AssetAmount>,
+    pub earned_proceeds: Vec<AssetAmount>,
+    pub commitments: Vec<LocalCommitment>,
+}
+
+// Where we stand with one counterparty over a period, from the same lines as its statement
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LocalSettlementBalance {
+    pub peer: String,
+    pub counterparty_state: CounterpartyState,
+    pub owed_to_us: String,
+    pub owed_by_us: String,
+    pub lines: Vec<StatementLine>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum PoolEventKind {
+    OpportunityStatus,
+    Capital,
+    Proceeds,
+}
+
+// Delivered to subscribers as a local request with body `{"PoolEvent": <event>}`
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum PoolEvent {
+    OpportunityStatusChanged {
+        opp_id: String,
+        status: OpportunityStatus,
+        opportunity: Option<AaveLiquidationOpportunity>,
+    },
+    CapitalChanged {
+        asset: String,
+        balance: String,
+    },
+    ProceedsEarned {
+        opp_id: String,
+        asset: String,
+        amount: String,
+        receipt: AaveLiquidationReceipt,
+    },
+    // Liveness check; any response counts as alive
+    Probe,
+}
+
+impl PoolEvent {
+    pub fn kind(&self) -> Option<PoolEventKind> {
+        match self {
+            PoolEvent::OpportunityStatusChanged { .. } => Some(PoolEventKind::OpportunityStatus),
+            PoolEvent::CapitalChanged { .. } => Some(PoolEventKind::Capital),
+            PoolEvent::ProceedsEarned { .. } => Some(PoolEventKind::Proceeds),
+            PoolEvent::Probe => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LocalSubscriber {
+    // Empty means every kind
+    pub kinds: Vec<PoolEventKind>,
+    pub registered_at: String,
+    #[serde(default)]
+    pub delivered: u64,
+    // Consecutive failed deliveries or probes
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl LocalSubscriber {
+    pub fn wants(&self, event: &PoolEvent) -> bool {
+        event.kind().map_or(true, |kind| self.kinds.is_empty() || self.kinds.contains(&kind))
+    }
+
+    // Returns true once the subscriber has failed often enough to be dropped
+    pub fn note_delivery(&mut self, delivered: bool) -> bool {
+        if delivered {
+            self.delivered += 1;
+            self.failures = 0;
+        } else {
+            self.failures += 1;
+        }
+        self.failures >= LOCAL_SUBSCRIBER_MAX_FAILURES
+    }
+}
+
+// Keyed by the subscriber's process address
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct LocalSubscribers {
+    pub subscribers: BTreeMap<String, LocalSubscriber>,
+}
+
+impl LocalSubscribers {
+    pub fn register(&mut self, address: String, kinds: Vec<PoolEventKind>, now: String) -> Result<(), String> {
+        if !self.subscribers.contains_key(&address) && self.subscribers.len() >= LOCAL_SUBSCRIBER_CAPACITY {
+            return Err(format!("At most {} local subscribers", LOCAL_SUBSCRIBER_CAPACITY));
+        }
+        self.subscribers.insert(address, LocalSubscriber { kinds, registered_at: now, delivered: 0, failures: 0 });
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+// PERFORMANCE METRICS
+// Running totals of what this node has seen, committed to and made, per strategy. They are bumped
+// as opportunities arrive, intents go out and receipts settle, and kept in app state, so
+// get_metrics never has to re-parse the stored receipt payloads. Totals only cover what happened
+// since tracking began (`since`).
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::capital;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct StrategyMetrics {
+    // New opportunities stored, ours and peers'
+    pub opportunities_seen: u64,
+    pub intents_submitted: u64,
+    // Settled executions we ran or lent capital to, and how they ended
+    pub executions_participated: u64,
+    pub executions_succeeded: u64,
+    pub executions_failed: u64,
+    // Our share of successful executions, per asset
+    pub proceeds: HashMap<String, String>,
+    // Gas on the executions we ran ourselves; providers only see it netted off their share
+    pub gas_paid_usdc: String,
+    // Per execution with a known estimate: realized profit after gas as bps of the estimate,
+    // summed, and how many went in
+    pub realization_bps_total: u64,
+    pub realization_samples: u64,
+}
+
+impl StrategyMetrics {
+    pub fn note_settled(&mut self, succeeded: bool, executed_by_us: bool, gas_paid_usdc: &str, estimated: Option<U256>, realized: U256) {
+        self.executions_participated += 1;
+        if succeeded {
+            self.executions_succeeded += 1;
+        } else {
+            self.executions_failed += 1;
+        }
+        if executed_by_us {
+            let gas = gas_paid_usdc.parse::<U256>().unwrap_or(U256::ZERO);
+            self.gas_paid_usdc = (self.gas_paid_usdc.parse::<U256>().unwrap_or(U256::ZERO) + gas).to_string();
+        }
+        if let Some(estimated) = estimated.filter(|estimated| !estimated.is_zero()) {
+            let bps = realized.saturating_mul(U256::from(10_000u64)) / estimated;
+            self.realization_bps_total = self.realization_bps_total.saturating_add(u64::try_from(bps).unwrap_or(u64::MAX));
+            self.realization_samples += 1;
+        }
+    }
+
+    pub fn note_proceeds(&mut self, asset: &str, amount: U256) {
+        capital::credit(&mut self.proceeds, asset, amount);
+    }
+
+    // Takes back proceeds noted earlier, while the receipt they came from is disputed
+    pub fn withhold_proceeds(&mut self, asset: &str, amount: U256) {
+        capital::debit(&mut self.proceeds, asset, amount);
+    }
+
+    // A success a reorg removed: counted as a failure instead, without its proceeds
+    pub fn note_reorged(&mut self, asset: &str, amount: U256) {
+        self.executions_succeeded = self.executions_succeeded.saturating_sub(1);
+        self.executions_failed += 1;
+        capital::debit(&mut self.proceeds, asset, amount);
+    }
+
+    // Mean over executions of realized vs estimated profit; None before any had an estimate
+    pub fn average_realization_bps(&self) -> Option<u64> {
+        (self.realization_samples > 0).then(|| self.realization_bps_total / self.realization_samples)
+    }
+
+    pub fn add(&mut self, other: &StrategyMetrics) {
+        self.opportunities_seen += other.opportunities_seen;
+        self.intents_submitted += other.intents_submitted;
+        self.executions_participated += other.executions_participated;
+        self.executions_succeeded += other.executions_succeeded;
+        self.executions_failed += other.executions_failed;
+        for (asset, amount) in &other.proceeds {
+            self.note_proceeds(asset, amount.parse::<U256>().unwrap_or(U256::ZERO));
+        }
+        let gas = |sum: &str| sum.parse::<U256>().unwrap_or(U256::ZERO);
+        self.gas_paid_usdc = (gas(&self.gas_paid_usdc) + gas(&other.gas_paid_usdc)).to_string();
+        self.realization_bps_total = self.realization_bps_total.saturating_add(other.realization_bps_total);
+        self.realization_samples += other.realization_samples;
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "opportunities_seen": self.opportunities_seen,
+            "intents_submitted": self.intents_submitted,
+            "executions_participated": self.executions_participated,
+            "executions_succeeded": self.executions_succeeded,
+            "executions_failed": self.executions_failed,
+            "success_bps": (self.executions_participated > 0)
+                .then(|| self.executions_succeeded * 10_000 / self.executions_participated),
+            "proceeds": self.proceeds,
+            "gas_paid_usdc": if self.gas_paid_usdc.is_empty() { "0" } else { self.gas_paid_usdc.as_str() },
+            "average_realization_bps": self.average_realization_bps(),
+            "realization_samples": self.realization_samples,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PerformanceMetrics {
+    // When tracking began, in unix seconds
+    pub since: u64,
+    pub by_strategy: BTreeMap<String, StrategyMetrics>,
+}
+
+impl PerformanceMetrics {
+    pub fn strategy(&mut self, strategy_id: &str) -> &mut StrategyMetrics {
+        self.by_strategy.entry(strategy_id.to_string()).or_default()
+    }
+
+    pub fn totals(&self) -> StrategyMetrics {
+        let mut totals = StrategyMetrics::default();
+        for metrics in self.by_strategy.values() {
+            totals.add(metrics);
+        }
+        totals
+    }
+}
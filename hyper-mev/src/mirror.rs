@@ -0,0 +1,219 @@
+// WATCH-ONLY MIRRORING
+// A buddy node keeps a copy of our public coordination records (opportunities we originated,
+// receipts naming us, settlement lines we're party to) so we can rebuild them after losing state.
+// We append each record to a sequenced log and stream it to every buddy that accepted; a buddy
+// that sees a sequence gap asks for the range again. Nothing private (balances, locks, config) is
+// mirrored. Both the log and each held copy are bounded, and either side can revoke at any time.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::bundle::ItemSignature;
+
+// Most entries in our log and in any copy we hold for a buddy, and most sent per message
+pub const MAX_MIRROR_ENTRIES: usize = 2000;
+pub const MAX_MIRROR_BATCH: usize = 100;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum MirrorState {
+    // Proposed by the node to be mirrored, waiting for the buddy's accept_mirror
+    Offered,
+    Active,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MirrorAgreement {
+    pub state: MirrorState,
+    pub since: u64,
+}
+
+impl MirrorAgreement {
+    pub fn offered(now: u64) -> Self {
+        Self { state: MirrorState::Offered, since: now }
+    }
+
+    pub fn active(&self) -> bool {
+        self.state == MirrorState::Active
+    }
+}
+
+// What a MirrorControl message asks of the other side
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum MirrorAction {
+    Offer,
+    Accept,
+    Revoke,
+    // Entries from_seq..=to_seq again, after a gap in what we hold
+    ResendRequest,
+    // Our records back, from the copy the buddy holds
+    RestoreRequest,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MirrorRecordKind {
+    // `{finder_seq, opportunity}` for an opportunity we originated
+    Opportunity,
+    // `{executor_node, receipt, our_proceeds, verified_at}` for a receipt naming us
+    Receipt,
+    // A StatementLine we are executor or provider on
+    StatementLine,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MirrorEntry {
+    pub seq: u64,
+    pub kind: MirrorRecordKind,
+    // opp_id, plus `/provider` for statement lines
+    pub key: String,
+    pub record: String,
+    pub recorded_at: u64,
+    // Signatures the record carried, kept as they were
+    #[serde(default)]
+    pub signatures: Vec<ItemSignature>,
+}
+
+impl MirrorEntry {
+    pub fn slot(&self) -> String {
+        record_slot(self.kind, &self.key)
+    }
+}
+
+pub fn record_slot(kind: MirrorRecordKind, key: &str) -> String {
+    format!("{:?}:{}", kind, key)
+}
+
+// Our side: every record we've mirrored, in sequence order, oldest dropped past the cap
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct MirrorLog {
+    pub next_seq: u64,
+    pub entries: VecDeque<MirrorEntry>,
+}
+
+impl MirrorLog {
+    pub fn append(&mut self, kind: MirrorRecordKind, key: &str, record: String, now: u64) -> MirrorEntry {
+        self.next_seq += 1;
+        let entry = MirrorEntry {
+            seq: self.next_seq,
+            kind,
+            key: key.to_string(),
+            record,
+            recorded_at: now,
+            signatures: Vec::new(),
+        };
+        self.entries.push_back(entry.clone());
+        while self.entries.len() > MAX_MIRROR_ENTRIES {
+            self.entries.pop_front();
+        }
+        entry
+    }
+
+    // Entries still held in from_seq..=to_seq; what was trimmed is gone for good
+    pub fn range(&self, from_seq: u64, to_seq: u64) -> Vec<MirrorEntry> {
+        self.entries.iter()
+            .filter(|entry| entry.seq >= from_seq && entry.seq <= to_seq)
+            .cloned()
+            .collect()
+    }
+}
+
+// A buddy's side: the copy of its log we hold for it
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct MirrorCopy {
+    pub highest_seq: u64,
+    pub entries: BTreeMap<u64, MirrorEntry>,
+    // Sequence numbers skipped over and not yet resent
+    pub missing: BTreeSet<u64>,
+    pub received: u64,
+    pub trimmed: u64,
+}
+
+impl MirrorCopy {
+    // Stores an entry and returns the range it revealed as missing, if any
+    pub fn store(&mut self, entry: MirrorEntry) -> Option<(u64, u64)> {
+        let seq = entry.seq;
+        if seq == 0 || self.entries.contains_key(&seq) {
+            return None;
+        }
+        self.received += 1;
+        self.missing.remove(&seq);
+        let gap = (seq > self.highest_seq + 1).then(|| (self.highest_seq + 1, seq - 1));
+        if let Some((from_seq, to_seq)) = gap {
+            // Only the most recent part of a long gap is worth asking for; the log is bounded too
+            let from_seq = from_seq.max(to_seq.saturating_sub(MAX_MIRROR_ENTRIES as u64 - 1));
+            self.missing.extend(from_seq..=to_seq);
+        }
+        self.highest_seq = self.highest_seq.max(seq);
+        self.entries.insert(seq, entry);
+        while self.entries.len() > MAX_MIRROR_ENTRIES {
+            self.entries.pop_first();
+            self.trimmed += 1;
+        }
+        while self.missing.len() > MAX_MIRROR_ENTRIES {
+            self.missing.pop_first();
+        }
+        gap
+    }
+
+    // The newest entry for each record: what a restore hands back
+    pub fn latest(&self) -> Vec<MirrorEntry> {
+        let mut latest: BTreeMap<String, &MirrorEntry> = BTreeMap::new();
+        for entry in self.entries.values() {
+            latest.insert(entry.slot(), entry);
+        }
+        let mut entries: Vec<MirrorEntry> = latest.into_values().cloned().collect();
+        entries.sort_by_key(|entry| entry.seq);
+        entries
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "highest_seq": self.highest_seq,
+            "held": self.entries.len(),
+            "missing": self.missing.len(),
+            "received": self.received,
+            "trimmed": self.trimmed,
+        })
+    }
+}
+
+// A buddy we hold a copy for, and the terms it's held on
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct HeldMirror {
+    pub agreement: MirrorAgreement,
+    pub copy: MirrorCopy,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Reverification {
+    // Restored from a buddy and not yet checked against anything live
+    Pending,
+    Confirmed,
+    // A live check disagreed with the restored record
+    Contradicted,
+}
+
+// A record rebuilt from a buddy's copy; live records always win over it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RestoredRecord {
+    pub kind: MirrorRecordKind,
+    pub key: String,
+    pub record: String,
+    pub recorded_at: u64,
+    pub restored_from: String,
+    pub restored_at: u64,
+    // None when the record carried no signature
+    pub signatures_verified: Option<bool>,
+    pub reverification: Reverification,
+}
+
+// A restore we asked a buddy for and what has come back so far
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MirrorRestore {
+    pub buddy: String,
+    pub requested_at: u64,
+    pub restored: u64,
+    // Entries not applied because we hold a newer local record for them
+    pub kept_local: u64,
+    pub bad_signatures: u64,
+    pub completed_at: Option<u64>,
+}
@@ -0,0 +1,391 @@
+// SETTLEMENT EPOCHS AND NETTING
+// Every posting to the settlement ledger is also kept as a gross entry of the epoch it fell in.
+// When an epoch closes, each peer and asset whose entries net to something we owe gets a signed,
+// itemized statement from us, one item per opportunity. The peer checks each item against its own
+// entries and acknowledges those that match. The acknowledged items collapse into one net payable,
+// tracked until a payment recorded with mark_settled pays it. Items the peer disputes stay gross
+// and are settled entry by entry. Netting never moves the ledger position, which already holds the
+// net; it only decides what one payment covers.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::ledger::SignedAmount;
+
+// Closed epochs whose entries and statements are kept, for late answers and for display
+pub const EPOCHS_KEPT: u64 = 7;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum EpochLength {
+    Seconds(u64),
+    Blocks(u64),
+}
+
+impl Default for EpochLength {
+    fn default() -> Self {
+        Self::Seconds(24 * 60 * 60)
+    }
+}
+
+impl EpochLength {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Seconds(0) | Self::Blocks(0) => Err("A settlement epoch must be longer than zero".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    // Whether an epoch begun at `started_at`, and at `started_block` when the head was known, is
+    // over. A block-length epoch never ends without both blocks.
+    pub fn elapsed(&self, started_at: u64, started_block: Option<u64>, now: u64, head: Option<u64>) -> bool {
+        match *self {
+            Self::Seconds(secs) => now >= started_at.saturating_add(secs),
+            Self::Blocks(blocks) => match (started_block, head) {
+                (Some(start), Some(head)) => head >= start.saturating_add(blocks),
+                _ => false,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum EntryNetting {
+    Open,
+    // On a statement we sent that awaits the peer's answer
+    Proposed { statement_id: String },
+    Netted { statement_id: String },
+    // Disputed on a statement; settled on its own
+    Disputed { statement_id: String },
+}
+
+// One ledger posting, as it was made
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct EpochEntry {
+    pub epoch: u64,
+    pub opp_id: String,
+    pub peer: String,
+    pub asset: String,
+    pub cause: String,
+    pub delta: SignedAmount,
+    pub posted_at: u64,
+    pub netting: EntryNetting,
+}
+
+// An opportunity's entries with the creditor, summed, as the debtor sees them
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatementItem {
+    pub opp_id: String,
+    pub delta: SignedAmount,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum StatementStatus {
+    Proposed,
+    Acknowledged { accepted: Vec<String>, disputed: Vec<String> },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct NettingStatement {
+    pub statement_id: String,
+    // The debtor's epoch
+    pub epoch: u64,
+    // Our epoch when we sent or received it, which retention goes by
+    pub recorded_in: u64,
+    pub debtor: String,
+    pub creditor: String,
+    pub asset: String,
+    pub items: Vec<StatementItem>,
+    // Sum of the items, negative as the debtor owes
+    pub net: SignedAmount,
+    pub proposed_at: u64,
+    pub status: StatementStatus,
+}
+
+impl NettingStatement {
+    pub fn id(epoch: u64, debtor: &str, creditor: &str, asset: &str) -> String {
+        format!("{}:{}:{}:{}", debtor, creditor, asset, epoch)
+    }
+
+    // The one payment the accepted items come to, if they don't cancel out
+    pub fn collapse(&self, accepted: &[String], now: u64) -> Option<NetPayable> {
+        let net = net_of(self.items.iter().filter(|item| accepted.contains(&item.opp_id)));
+        if net.is_zero() {
+            return None;
+        }
+        // The items are the debtor's view: negative is what it pays
+        let (payer, payee) = if net.is_negative() {
+            (&self.debtor, &self.creditor)
+        } else {
+            (&self.creditor, &self.debtor)
+        };
+        Some(NetPayable {
+            statement_id: self.statement_id.clone(),
+            recorded_in: self.recorded_in,
+            payer: payer.clone(),
+            payee: payee.clone(),
+            asset: self.asset.clone(),
+            amount: net.magnitude().to_string(),
+            opp_ids: accepted.to_vec(),
+            acknowledged_at: now,
+            paid: None,
+        })
+    }
+}
+
+// Acknowledged statement items collapsed into one payment
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct NetPayable {
+    pub statement_id: String,
+    pub recorded_in: u64,
+    pub payer: String,
+    pub payee: String,
+    pub asset: String,
+    pub amount: String,
+    pub opp_ids: Vec<String>,
+    pub acknowledged_at: u64,
+    // tx_hash of the payment mark_settled recorded for it
+    pub paid: Option<String>,
+}
+
+// A statement to propose at epoch close: what the entries with `peer` in `asset` net to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NettingProposal {
+    pub peer: String,
+    pub asset: String,
+    pub items: Vec<StatementItem>,
+    pub net: SignedAmount,
+}
+
+pub fn net_of<'a>(items: impl IntoIterator<Item = &'a StatementItem>) -> SignedAmount {
+    items.into_iter().fold(SignedAmount::ZERO, |net, item| net.checked_add(item.delta).unwrap_or(net))
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct SettlementEpochs {
+    pub epoch: u64,
+    pub started_at: u64,
+    pub started_block: Option<u64>,
+    pub entries: Vec<EpochEntry>,
+    pub statements: BTreeMap<String, NettingStatement>,
+    pub net_payables: Vec<NetPayable>,
+}
+
+impl SettlementEpochs {
+    pub fn record(&mut self, opp_id: &str, peer: &str, asset: &str, cause: &str, delta: SignedAmount, now: u64) {
+        self.entries.push(EpochEntry {
+            epoch: self.epoch,
+            opp_id: opp_id.to_string(),
+            peer: peer.to_string(),
+            asset: asset.to_string(),
+            cause: cause.to_string(),
+            delta,
+            posted_at: now,
+            netting: EntryNetting::Open,
+        });
+    }
+
+    // Starts the next epoch and returns the one closed. Entries, statements and paid payables
+    // from before the kept epochs are dropped; unpaid payables stay until paid.
+    pub fn close(&mut self, now: u64, head: Option<u64>) -> u64 {
+        let closed = self.epoch;
+        self.epoch += 1;
+        self.started_at = now;
+        self.started_block = head;
+        let kept_from = self.epoch.saturating_sub(EPOCHS_KEPT);
+        self.entries.retain(|entry| entry.epoch >= kept_from);
+        self.statements.retain(|_, statement| statement.recorded_in >= kept_from);
+        self.net_payables.retain(|payable| payable.paid.is_none() || payable.recorded_in >= kept_from);
+        closed
+    }
+
+    // For each peer and asset, the open entries of `epoch` summed per opportunity, where they net
+    // to something we owe
+    pub fn proposals(&self, epoch: u64) -> Vec<NettingProposal> {
+        let mut grouped: BTreeMap<(&str, &str), BTreeMap<&str, SignedAmount>> = BTreeMap::new();
+        for entry in self.entries.iter().filter(|entry| entry.epoch == epoch && entry.netting == EntryNetting::Open) {
+            let item = grouped.entry((entry.peer.as_str(), entry.asset.as_str())).or_default()
+                .entry(entry.opp_id.as_str()).or_default();
+            *item = item.checked_add(entry.delta).unwrap_or(*item);
+        }
+        grouped.into_iter()
+            .map(|((peer, asset), items)| {
+                let items: Vec<StatementItem> = items.into_iter()
+                    .filter(|(_, delta)| !delta.is_zero())
+                    .map(|(opp_id, delta)| StatementItem { opp_id: opp_id.to_string(), delta })
+                    .collect();
+                NettingProposal { peer: peer.to_string(), asset: asset.to_string(), net: net_of(&items), items }
+            })
+            .filter(|proposal| proposal.net.is_negative())
+            .collect()
+    }
+
+    // Splits a statement from `debtor` into the items our own entries agree with (the same amount
+    // the other way) and those they don't. Entries already netted don't count.
+    pub fn judge_items(&self, debtor: &str, asset: &str, items: &[StatementItem]) -> (Vec<String>, Vec<String>) {
+        let mut ours: BTreeMap<&str, SignedAmount> = BTreeMap::new();
+        for entry in self.entries.iter().filter(|entry| entry.peer == debtor && entry.asset == asset
+            && !matches!(entry.netting, EntryNetting::Netted { .. })) {
+            let sum = ours.entry(entry.opp_id.as_str()).or_default();
+            *sum = sum.checked_add(entry.delta).unwrap_or(*sum);
+        }
+        let (accepted, disputed): (Vec<&StatementItem>, Vec<&StatementItem>) = items.iter()
+            .partition(|item| ours.get(item.opp_id.as_str()).copied().unwrap_or_default() == item.delta.negated());
+        let opp_ids = |items: Vec<&StatementItem>| items.into_iter().map(|item| item.opp_id.clone()).collect();
+        (opp_ids(accepted), opp_ids(disputed))
+    }
+
+    // Sets the netting of our entries with `peer` in `asset` for `opp_ids`, leaving netted ones be
+    pub fn mark(&mut self, peer: &str, asset: &str, opp_ids: &[String], netting: EntryNetting) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.peer == peer && entry.asset == asset
+            && opp_ids.contains(&entry.opp_id) && !matches!(entry.netting, EntryNetting::Netted { .. })) {
+            entry.netting = netting.clone();
+        }
+    }
+
+    // A payment recorded with `peer` in `asset` pays every open net payable between us there
+    pub fn mark_paid(&mut self, peer: &str, asset: &str, tx_hash: &str) -> Vec<String> {
+        let mut paid = Vec::new();
+        for payable in self.net_payables.iter_mut().filter(|payable| payable.paid.is_none()
+            && payable.asset.eq_ignore_ascii_case(asset) && (payable.payer == peer || payable.payee == peer)) {
+            payable.paid = Some(tx_hash.to_string());
+            paid.push(payable.statement_id.clone());
+        }
+        paid
+    }
+
+    // Unpaid net payables per (payer, payee, asset), summed
+    pub fn outstanding(&self) -> BTreeMap<(String, String, String), U256> {
+        let mut outstanding: BTreeMap<(String, String, String), U256> = BTreeMap::new();
+        for payable in self.net_payables.iter().filter(|payable| payable.paid.is_none()) {
+            let amount = payable.amount.parse::<U256>().unwrap_or(U256::ZERO);
+            let total = outstanding.entry((payable.payer.clone(), payable.payee.clone(), payable.asset.clone())).or_insert(U256::ZERO);
+            *total = total.saturating_add(amount);
+        }
+        outstanding
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owed_by_us(amount: u64) -> SignedAmount {
+        SignedAmount::owed_by_us(U256::from(amount))
+    }
+
+    fn owed_to_us(amount: u64) -> SignedAmount {
+        SignedAmount::owed_to_us(U256::from(amount))
+    }
+
+    fn item(opp_id: &str, delta: SignedAmount) -> StatementItem {
+        StatementItem { opp_id: opp_id.to_string(), delta }
+    }
+
+    #[test]
+    fn epochs_end_by_time_or_by_blocks() {
+        assert!(!EpochLength::Seconds(100).elapsed(1_000, None, 1_099, None));
+        assert!(EpochLength::Seconds(100).elapsed(1_000, None, 1_100, None));
+        assert!(!EpochLength::Blocks(10).elapsed(0, Some(50), u64::MAX, Some(59)));
+        assert!(EpochLength::Blocks(10).elapsed(0, Some(50), 0, Some(60)));
+        // Without a head there's no telling how many blocks have passed
+        assert!(!EpochLength::Blocks(10).elapsed(0, None, u64::MAX, Some(60)));
+        assert!(EpochLength::Seconds(0).validate().is_err());
+    }
+
+    #[test]
+    fn closed_epoch_nets_per_peer_and_asset_and_proposes_only_what_we_owe() {
+        let mut epochs = SettlementEpochs::default();
+        epochs.record("opp-1", "bob.os", "USDC", "CapitalDrawn", owed_by_us(100), 1);
+        epochs.record("opp-1", "bob.os", "USDC", "ProceedsDistributed", owed_by_us(20), 2);
+        epochs.record("opp-2", "bob.os", "USDC", "ProceedsDistributed", owed_to_us(30), 3);
+        // Cancelled out by its reorg, so left off the statement
+        epochs.record("opp-3", "bob.os", "USDC", "CapitalDrawn", owed_by_us(50), 4);
+        epochs.record("opp-3", "bob.os", "USDC", "Reorged", owed_to_us(50), 5);
+        // Owes us on balance, so carol proposes, not us
+        epochs.record("opp-4", "carol.os", "USDC", "ProceedsDistributed", owed_to_us(10), 6);
+        assert_eq!(epochs.close(100, None), 0);
+        epochs.record("opp-5", "bob.os", "USDC", "CapitalDrawn", owed_by_us(7), 101);
+
+        let proposals = epochs.proposals(0);
+        assert_eq!(proposals, vec![NettingProposal {
+            peer: "bob.os".to_string(),
+            asset: "USDC".to_string(),
+            items: vec![item("opp-1", owed_by_us(120)), item("opp-2", owed_to_us(30))],
+            net: owed_by_us(90),
+        }]);
+    }
+
+    #[test]
+    fn creditor_accepts_the_items_it_agrees_with_and_disputes_the_rest() {
+        let mut creditor = SettlementEpochs::default();
+        creditor.record("opp-1", "alice.os", "USDC", "CapitalDrawn", owed_to_us(120), 1);
+        creditor.record("opp-2", "alice.os", "USDC", "ProceedsDistributed", owed_by_us(25), 2);
+        let items = vec![item("opp-1", owed_by_us(120)), item("opp-2", owed_to_us(30)), item("opp-9", owed_by_us(5))];
+        let (accepted, disputed) = creditor.judge_items("alice.os", "USDC", &items);
+        assert_eq!(accepted, vec!["opp-1".to_string()]);
+        assert_eq!(disputed, vec!["opp-2".to_string(), "opp-9".to_string()]);
+
+        // Netted entries aren't agreed to a second time
+        creditor.mark("alice.os", "USDC", &accepted, EntryNetting::Netted { statement_id: "s".to_string() });
+        let (accepted, _) = creditor.judge_items("alice.os", "USDC", &items);
+        assert!(accepted.is_empty());
+    }
+
+    #[test]
+    fn accepted_items_collapse_into_one_payable_that_a_payment_clears() {
+        let statement = NettingStatement {
+            statement_id: NettingStatement::id(0, "alice.os", "bob.os", "USDC"),
+            epoch: 0,
+            recorded_in: 0,
+            debtor: "alice.os".to_string(),
+            creditor: "bob.os".to_string(),
+            asset: "USDC".to_string(),
+            items: vec![item("opp-1", owed_by_us(120)), item("opp-2", owed_to_us(30)), item("opp-3", owed_by_us(5))],
+            net: owed_by_us(95),
+            proposed_at: 0,
+            status: StatementStatus::Proposed,
+        };
+        let accepted = vec!["opp-1".to_string(), "opp-2".to_string()];
+        let payable = statement.collapse(&accepted, 10).unwrap();
+        assert_eq!((payable.payer.as_str(), payable.payee.as_str(), payable.amount.as_str()), ("alice.os", "bob.os", "90"));
+        // Only the credit accepted: the creditor owes it back
+        let reversed = statement.collapse(&["opp-2".to_string()], 10).unwrap();
+        assert_eq!((reversed.payer.as_str(), reversed.payee.as_str()), ("bob.os", "alice.os"));
+        assert!(statement.collapse(&[], 10).is_none());
+
+        let mut epochs = SettlementEpochs::default();
+        epochs.net_payables.push(payable);
+        assert_eq!(epochs.outstanding().len(), 1);
+        assert_eq!(epochs.mark_paid("bob.os", "usdc", "0xpaid"), vec![statement.statement_id.clone()]);
+        assert!(epochs.outstanding().is_empty());
+        assert!(epochs.mark_paid("bob.os", "USDC", "0xagain").is_empty());
+    }
+
+    #[test]
+    fn closing_drops_what_falls_out_of_the_kept_epochs_but_not_unpaid_payables() {
+        let mut epochs = SettlementEpochs::default();
+        epochs.record("opp-1", "bob.os", "USDC", "CapitalDrawn", owed_by_us(1), 0);
+        let unpaid = NettingStatement {
+            statement_id: "s".to_string(),
+            epoch: 0,
+            recorded_in: 0,
+            debtor: "alice.os".to_string(),
+            creditor: "bob.os".to_string(),
+            asset: "USDC".to_string(),
+            items: vec![item("opp-1", owed_by_us(1))],
+            net: owed_by_us(1),
+            proposed_at: 0,
+            status: StatementStatus::Proposed,
+        }.collapse(&["opp-1".to_string()], 0).unwrap();
+        epochs.net_payables.push(unpaid);
+        for _ in 0..EPOCHS_KEPT {
+            epochs.close(0, None);
+        }
+        assert_eq!(epochs.entries.len(), 1);
+        epochs.close(0, None);
+        assert!(epochs.entries.is_empty());
+        assert_eq!(epochs.net_payables.len(), 1);
+    }
+}
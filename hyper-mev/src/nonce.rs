@@ -0,0 +1,46 @@
+// MESSAGE NONCES
+// Every live P2P message carries a nonce from its sender's rising sequence (see sign_message).
+// We keep the last one accepted per sender and message kind, and refuse anything at or below it,
+// so a captured message can't be replayed to us.
+
+use crate::HyperMevApp;
+
+// A message's nonce against the last one accepted from its sender for its kind: Ok with the nonce
+// to record, or the error kind. A message without one passes only from a sender that has never
+// sent one, and not at all once signed messages are required.
+pub fn judge_nonce(nonce: Option<u64>, last_seen: Option<u64>, required: bool) -> Result<Option<u64>, &'static str> {
+    match (nonce, last_seen) {
+        (None, None) if required => Err("MissingNonce"),
+        (None, None) => Ok(None),
+        (Some(nonce), last_seen) if last_seen.map_or(true, |last| nonce > last) => Ok(Some(nonce)),
+        _ => Err("Replay"),
+    }
+}
+
+impl HyperMevApp {
+    // Replay protection for a live message of `kind` from `sender`: its nonce must be above the
+    // last one we accepted from that sender for that kind. Kinds are tracked apart because our
+    // outbound queues may deliver different kinds out of order. The nonce to record once the
+    // handler accepts the message comes back; recording it any earlier would let a message
+    // refused for a passing reason burn its nonce.
+    pub(crate) fn check_message_nonce(&self, sender: &str, kind: &str, message_json: &str) -> Result<Option<u64>, String> {
+        let nonce = serde_json::from_str::<serde_json::Value>(message_json).ok()
+            .and_then(|message| message["nonce"].as_u64());
+        let last_seen = self.peer_nonces.get(sender).and_then(|nonces| nonces.get(kind)).copied();
+        judge_nonce(nonce, last_seen, self.node_config.require_signed_messages).map_err(|error| {
+            println!("\n🔁 P2P: Refused {} from {}: {} (nonce {:?}, last seen {:?})", kind, sender, error, nonce, last_seen);
+            serde_json::json!({
+                "error": error,
+                "kind": kind,
+                "nonce": nonce,
+                "last_seen": last_seen,
+            }).to_string()
+        })
+    }
+
+    pub(crate) fn record_message_nonce(&mut self, sender: &str, kind: &str, nonce: Option<u64>) {
+        if let Some(nonce) = nonce {
+            self.peer_nonces.entry(sender.to_string()).or_default().insert(kind.to_string(), nonce);
+        }
+    }
+}
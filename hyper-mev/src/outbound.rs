@@ -0,0 +1,483 @@
+// PER-PEER OUTBOUND QUEUES
+// Every P2P send is queued for its peer and drained from there, so a peer that stops answering
+// only backs up its own queue. Each queue is bounded: on overflow the lowest-priority send goes
+// first (oldest first within a class), so receipts, expiries and other Critical traffic are kept
+// longest. Each peer carries its own backoff, which the drain honours while the peer is silent.
+// Draining draws on a per-second request budget shared by every peer; opportunity broadcasts that
+// don't fit it go to the most relevant peers and the rest are resent once it frees up.
+
+use hyperware_process_lib::{Address, ProcessId, Request};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::audit::EventOutcome;
+use crate::recording::Boundary;
+use crate::selftest::is_self_test_id;
+use crate::{HyperMevApp, NodeRole};
+
+// Sends held per peer before the lowest-priority one is dropped
+pub const PEER_QUEUE_CAPACITY: usize = 64;
+// Non-Critical sends older than this are dropped instead of delivered late
+pub const QUEUED_SEND_TTL_SECS: u64 = 120;
+const BACKOFF_BASE_SECS: u64 = 2;
+const MAX_BACKOFF_SECS: u64 = 120;
+
+// Outbound P2P traffic classes. Under budget pressure the lowest classes wait in their peer's queue
+// first and are the first dropped when it overflows; Critical traffic (receipts, expiries,
+// amendments, succession) is counted but never held back.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OutboundPriority {
+    Critical,
+    Normal,
+    Bulk,
+    Low,
+}
+
+impl OutboundPriority {
+    // Keyed by MevMessage::handler(), plus the handlers whose messages have their own types
+    pub fn for_handler(handler: &str) -> Self {
+        match handler {
+            "ReceiveExecutionReceipt" | "ReceiveOpportunityExpiry" | "ReceiveIntentAmendment"
+            | "ReceiveIntentAmendmentRejection" | "ReceiveExecutorSuccession" | "ReceiveExecutionClaim"
+            | "ReceiveCapitalReserveRequest" | "ReceiveCapitalReserveResponse" | "ReceiveOpportunityCancellation"
+            // Same lane as the receipt it follows, so it can't arrive first
+            | "ReceiveProceedsDistribution" => OutboundPriority::Critical,
+            "ReceiveOpportunityBroadcast" | "ReceiveSyncResponse" => OutboundPriority::Bulk,
+            // A sync request goes in the same lane as the announcement it follows, so it can't overtake it
+            "ReceiveNodeAnnouncement" | "ReceiveMonitoringCoverage" | "ReceiveSyncRequest" => OutboundPriority::Low,
+            _ => OutboundPriority::Normal,
+        }
+    }
+}
+
+// A send dropped by opportunity fan-out reduction, kept so it can be resent once the budget allows
+#[derive(Clone, Debug)]
+pub struct SkippedSend {
+    pub opp_id: String,
+    pub peer: String,
+    pub handler: String,
+    pub message: serde_json::Value,
+}
+
+// Per-second outbound request budget and what it has shed
+#[derive(Default, Debug)]
+pub struct OutboundBudget {
+    pub window_start: u64,
+    pub sent_in_window: u32,
+    pub sent_total: u64,
+    pub shed: BTreeMap<String, u64>,
+    // Opportunity broadcasts sent to a reduced peer set
+    pub fanout_reduced: u64,
+    pub skipped: Vec<SkippedSend>,
+}
+
+const MAX_SKIPPED_SENDS: usize = 500;
+
+#[derive(Clone, Debug)]
+pub struct QueuedSend {
+    pub handler: String,
+    pub message: serde_json::Value,
+    pub priority: OutboundPriority,
+    pub enqueued_at: u64,
+}
+
+// A send that left a queue without being delivered, held until maintenance journals it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DroppedSend {
+    pub peer: String,
+    pub handler: String,
+    pub priority: OutboundPriority,
+    #[serde(default)]
+    pub opp_id: Option<String>,
+    pub reason: String,
+    pub at: u64,
+}
+
+#[derive(Default, Debug)]
+pub struct PeerQueue {
+    pub sends: VecDeque<QueuedSend>,
+    // Consecutive sends made while backed off; reset once the peer is heard from
+    pub attempts: u32,
+    pub retry_at: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+}
+
+impl PeerQueue {
+    // Queues a send, returning the one dropped if that overflowed the queue
+    pub fn push(&mut self, send: QueuedSend) -> Option<QueuedSend> {
+        self.sends.push_back(send);
+        if self.sends.len() <= PEER_QUEUE_CAPACITY {
+            return None;
+        }
+        let lowest = self.sends.iter().map(|send| send.priority).max()?;
+        let victim = self.sends.iter().position(|send| send.priority == lowest)?;
+        self.dropped += 1;
+        self.sends.remove(victim)
+    }
+
+    // The send to deliver next: highest priority, oldest within it
+    pub fn next_index(&self) -> Option<usize> {
+        let highest = self.sends.iter().map(|send| send.priority).min()?;
+        self.sends.iter().position(|send| send.priority == highest)
+    }
+
+    pub fn ready(&self, now: u64) -> bool {
+        self.retry_at <= now
+    }
+
+    pub fn back_off(&mut self, now: u64) {
+        let delay = BACKOFF_BASE_SECS.saturating_mul(1 << self.attempts.min(16)).min(MAX_BACKOFF_SECS);
+        self.attempts += 1;
+        self.retry_at = now + delay;
+    }
+
+    pub fn recover(&mut self) {
+        self.attempts = 0;
+        self.retry_at = 0;
+    }
+
+    // Removes non-Critical sends past QUEUED_SEND_TTL_SECS
+    pub fn expire(&mut self, now: u64) -> Vec<QueuedSend> {
+        let (expired, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.sends).into_iter()
+            .partition(|send| send.priority != OutboundPriority::Critical
+                && now.saturating_sub(send.enqueued_at) > QUEUED_SEND_TTL_SECS);
+        self.sends = kept.into();
+        self.dropped += expired.len() as u64;
+        expired
+    }
+
+    pub fn idle(&self) -> bool {
+        self.sends.is_empty() && self.attempts == 0
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "depth": self.sends.len(),
+            "attempts": self.attempts,
+            "retry_at": if self.attempts == 0 { None } else { Some(self.retry_at) },
+            "delivered": self.delivered,
+            "dropped": self.dropped,
+        })
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct OutboundQueues {
+    pub peers: BTreeMap<String, PeerQueue>,
+    // Drops not yet journaled
+    pub dropped: Vec<DroppedSend>,
+}
+
+impl OutboundQueues {
+    pub fn enqueue(&mut self, peer: &str, send: QueuedSend) {
+        let at = send.enqueued_at;
+        if let Some(evicted) = self.peers.entry(peer.to_string()).or_default().push(send) {
+            self.note_dropped(peer, evicted, "queue_full", at);
+        }
+    }
+
+    // Takes the send a peer's queue may make now: none while it is backed off or `take` refuses
+    // the budget. A silent peer is backed off again for each send it is given.
+    pub fn next_send(&mut self, peer: &str, now: u64, silent: bool, take: impl FnOnce(OutboundPriority) -> bool) -> Option<QueuedSend> {
+        let queue = self.peers.get_mut(peer)?;
+        if !silent {
+            queue.recover();
+        }
+        if !queue.ready(now) {
+            return None;
+        }
+        let index = queue.next_index()?;
+        if !take(queue.sends[index].priority) {
+            return None;
+        }
+        if silent {
+            queue.back_off(now);
+        }
+        queue.sends.remove(index)
+    }
+
+    // Records how a send from next_send went. An undelivered one goes back to the front of its
+    // queue and backs the peer off; returns whether the drain may carry on.
+    pub fn settle(&mut self, peer: &str, send: QueuedSend, delivered: bool, now: u64) -> bool {
+        let Some(queue) = self.peers.get_mut(peer) else {
+            return false;
+        };
+        if delivered {
+            queue.delivered += 1;
+        } else {
+            queue.sends.push_front(send);
+            queue.back_off(now);
+        }
+        delivered
+    }
+
+    pub fn note_dropped(&mut self, peer: &str, send: QueuedSend, reason: &str, at: u64) {
+        self.dropped.push(DroppedSend {
+            peer: peer.to_string(),
+            handler: send.handler,
+            priority: send.priority,
+            opp_id: send.message["opp_id"].as_str().map(str::to_string),
+            reason: reason.to_string(),
+            at,
+        });
+    }
+
+    // Drops sends past their TTL, and the whole queue of any peer `known` no longer accepts
+    pub fn prune(&mut self, now: u64, known: impl Fn(&str) -> bool) {
+        let mut dropped = Vec::new();
+        self.peers.retain(|peer, queue| {
+            let forgotten = !known(peer);
+            let (sends, reason) = if forgotten {
+                (std::mem::take(&mut queue.sends).into_iter().collect(), "peer_forgotten")
+            } else {
+                (queue.expire(now), "expired")
+            };
+            dropped.extend(sends.into_iter().map(|send| (peer.clone(), send, reason)));
+            !forgotten
+        });
+        for (peer, send, reason) in dropped {
+            self.note_dropped(&peer, send, reason, now);
+        }
+    }
+
+    pub fn depths(&self) -> BTreeMap<String, usize> {
+        self.peers.iter()
+            .filter(|(_, queue)| !queue.sends.is_empty())
+            .map(|(peer, queue)| (peer.clone(), queue.sends.len()))
+            .collect()
+    }
+}
+
+// The app's side: queueing, draining and budgeting its P2P sends
+impl HyperMevApp {
+    // A peer's hyper-mev process, under the publisher the pool manifest lists for it
+    pub(crate) fn peer_process_id(&self, peer_node: &str) -> Result<ProcessId, String> {
+        let publisher = self.node_config.peer_publishers.get(peer_node)
+            .unwrap_or(&self.node_config.publisher);
+        format!("hyper-mev:hyper-mev:{}", publisher)
+            .parse::<ProcessId>()
+            .map_err(|e| format!("Invalid ProcessId: {}", e))
+    }
+
+    // Queues a JSON message for one peer's remote handler and sends what that peer's queue may
+    // send now; nothing waits on the peer
+    pub(crate) fn send_to_peer(&self, peer_node: &str, handler: &str, message: &serde_json::Value) {
+        if message["opp_id"].as_str().is_some_and(is_self_test_id) {
+            println!("   Dropped {} to {}: self-test artifacts never leave the node", handler, peer_node);
+            return;
+        }
+        // Nothing goes to a node we refuse to hear from
+        if self.peer_process_id(peer_node).is_err() || self.node_config.peer_blocked(peer_node).is_some() {
+            return;
+        }
+        self.outbound_queues.borrow_mut().enqueue(peer_node, QueuedSend {
+            handler: handler.to_string(),
+            message: message.clone(),
+            priority: OutboundPriority::for_handler(handler),
+            enqueued_at: Self::current_timestamp().parse::<u64>().unwrap_or(0),
+        });
+        self.drain_peer_queue(peer_node);
+    }
+
+    // Sends from one peer's queue, highest priority first, while the shared budget allows. A peer
+    // that missed a heartbeat is backed off and gets one send per backoff interval, so its backlog
+    // neither spends the budget healthy peers need nor holds up their queues.
+    fn drain_peer_queue(&self, peer_node: &str) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let silent = self.known_peers.get(peer_node).is_some_and(|info| info.missed_heartbeats > 0);
+        loop {
+            let send = self.outbound_queues.borrow_mut()
+                .next_send(peer_node, now, silent, |priority| self.take_outbound(priority));
+            let Some(send) = send else {
+                return;
+            };
+            let delivered = self.deliver_to_peer(peer_node, &send.handler, &send.message);
+            if !self.outbound_queues.borrow_mut().settle(peer_node, send, delivered, now) || silent {
+                return;
+            }
+        }
+    }
+
+    fn deliver_to_peer(&self, peer_node: &str, handler: &str, message: &serde_json::Value) -> bool {
+        let Ok(process_id) = self.peer_process_id(peer_node) else {
+            self.log_event(Boundary::PeerOutbound, Some(peer_node.to_string()), handler, &message.to_string(),
+                EventOutcome::Error, Some("No process id for peer".to_string()));
+            return false;
+        };
+        self.record_frame(Boundary::PeerOutbound, Some(peer_node.to_string()), handler, message.clone());
+        let target = Address::new(peer_node.to_string(), process_id);
+        let wrapper = serde_json::json!({ handler: message.to_string() });
+        let sent = Request::new()
+            .target(target)
+            .body(serde_json::to_vec(&wrapper).unwrap())
+            .expects_response(30)
+            .send()
+            .is_ok();
+        let (outcome, reason) = if sent { (EventOutcome::Accepted, None) } else { (EventOutcome::Error, Some("Send failed".to_string())) };
+        self.log_event(Boundary::PeerOutbound, Some(peer_node.to_string()), handler, &message.to_string(), outcome, reason);
+        sent
+    }
+
+    // Maintenance pass over every queue: expires stale sends, drains what the budget allows,
+    // forgets idle queues and journals everything dropped since the last pass, per peer
+    pub(crate) fn drain_peer_queues(&mut self) {
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        self.outbound_queues.borrow_mut().prune(now, |peer| self.known_peers.contains_key(peer));
+        let peers: Vec<String> = self.outbound_queues.borrow().peers.keys().cloned().collect();
+        for peer in &peers {
+            self.drain_peer_queue(peer);
+        }
+        let dropped = {
+            let mut queues = self.outbound_queues.borrow_mut();
+            queues.peers.retain(|_, queue| !queue.idle());
+            std::mem::take(&mut queues.dropped)
+        };
+        let mut by_peer: BTreeMap<String, Vec<DroppedSend>> = BTreeMap::new();
+        for drop in dropped {
+            by_peer.entry(drop.peer.clone()).or_default().push(drop);
+        }
+        for (peer, drops) in by_peer {
+            println!("   📭 Dropped {} queued send(s) to {}", drops.len(), peer);
+            self.record_journal("OutboundDropped", serde_json::json!({
+                "peer": peer,
+                "sends": drops,
+            }));
+        }
+    }
+
+    // Fire-and-forget a JSON message to every known peer's remote handler
+    pub(crate) fn send_to_peers(&self, handler: &str, message: &serde_json::Value) {
+        for peer_node in self.reachable_peers() {
+            self.send_to_peer(peer_node, handler, message);
+        }
+    }
+
+    // Peers broadcasts go to: everyone known except those marked unreachable
+    pub(crate) fn reachable_peers(&self) -> impl Iterator<Item = &String> {
+        self.known_peers.iter()
+            .filter(|(_, info)| info.reachable())
+            .map(|(peer, _)| peer)
+    }
+
+    // Draws one request from this second's outbound budget. Critical traffic always goes (and
+    // still counts); Low only uses the first half of the budget so it waits first under load.
+    fn take_outbound(&self, priority: OutboundPriority) -> bool {
+        let limit = self.node_config.outbound_requests_per_sec;
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let mut budget = self.outbound_budget.borrow_mut();
+        if budget.window_start != now {
+            budget.window_start = now;
+            budget.sent_in_window = 0;
+        }
+        let allowed = limit == 0 || match priority {
+            OutboundPriority::Critical => true,
+            OutboundPriority::Normal | OutboundPriority::Bulk => budget.sent_in_window < limit,
+            OutboundPriority::Low => budget.sent_in_window < limit / 2,
+        };
+        if allowed {
+            budget.sent_in_window += 1;
+            budget.sent_total += 1;
+        } else {
+            *budget.shed.entry(format!("{:?}", priority)).or_insert(0) += 1;
+        }
+        allowed
+    }
+
+    fn outbound_remaining(&self) -> Option<usize> {
+        let limit = self.node_config.outbound_requests_per_sec;
+        if limit == 0 {
+            return None;
+        }
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let budget = self.outbound_budget.borrow();
+        let used = if budget.window_start == now { budget.sent_in_window } else { 0 };
+        Some(limit.saturating_sub(used) as usize)
+    }
+
+    // Peers ordered by how likely they are to act on an opportunity for `strategy_id`: announced
+    // support for the strategy, then a capital or executor role, then fewest malformed messages
+    fn rank_peers_for(&self, strategy_id: &str) -> Vec<String> {
+        let mut peers: Vec<String> = self.reachable_peers().cloned().collect();
+        peers.sort_by_key(|peer| {
+            let info = &self.known_peers[peer];
+            let supports = info.capabilities.iter().any(|c| c == strategy_id);
+            let acts = info.roles().iter()
+                .any(|role| matches!(role, NodeRole::CapitalProvider | NodeRole::Executor));
+            let strikes = self.malformed_message_strikes.get(peer).copied().unwrap_or(0);
+            (!supports, !acts, strikes, peer.clone())
+        });
+        peers
+    }
+
+    // Sends an opportunity to every peer while the budget covers them all, otherwise to the
+    // pressure_fanout_peers most relevant; the rest are remembered for repair_skipped_sends.
+    // Returns every peer it was sent or queued for.
+    pub(crate) fn fan_out_opportunity(&self, opp_id: &str, strategy_id: &str, handler: &str, message: &serde_json::Value) -> Vec<String> {
+        let peers = self.rank_peers_for(strategy_id);
+        let reach = match self.outbound_remaining() {
+            Some(remaining) if remaining < peers.len() => self.node_config.pressure_fanout_peers.min(peers.len()),
+            _ => peers.len(),
+        };
+        for peer in &peers[..reach] {
+            self.send_to_peer(peer, handler, message);
+        }
+        if reach == peers.len() {
+            return peers;
+        }
+        println!("   📉 Outbound budget tight: {} sent to {} of {} peers", opp_id, reach, peers.len());
+        let mut budget = self.outbound_budget.borrow_mut();
+        budget.fanout_reduced += 1;
+        for peer in &peers[reach..] {
+            budget.skipped.push(SkippedSend {
+                opp_id: opp_id.to_string(),
+                peer: peer.clone(),
+                handler: handler.to_string(),
+                message: message.clone(),
+            });
+        }
+        let overflow = budget.skipped.len().saturating_sub(MAX_SKIPPED_SENDS);
+        budget.skipped.drain(..overflow);
+        peers
+    }
+
+    // Resends opportunity broadcasts dropped by fan-out reduction while there is spare budget.
+    // Peers that miss one anyway see the sequence gap and ask for a backfill.
+    pub(crate) fn repair_skipped_sends(&mut self) {
+        let pending = std::mem::take(&mut self.outbound_budget.borrow_mut().skipped);
+        let mut kept = Vec::new();
+        for send in pending {
+            if !self.active_opportunities.contains_key(&send.opp_id) || !self.known_peers.get(&send.peer).map_or(false, |peer| peer.reachable()) {
+                continue;
+            }
+            // Keep a margin so repairs never crowd out fresh traffic
+            if self.outbound_remaining().map_or(true, |remaining| remaining > self.known_peers.len()) {
+                self.send_to_peer(&send.peer, &send.handler, &send.message);
+            } else {
+                kept.push(send);
+            }
+        }
+        let mut budget = self.outbound_budget.borrow_mut();
+        kept.append(&mut budget.skipped);
+        budget.skipped = kept;
+    }
+
+    pub(crate) fn outbound_metrics(&self) -> serde_json::Value {
+        let limit = self.node_config.outbound_requests_per_sec;
+        let used = limit.saturating_sub(self.outbound_remaining().unwrap_or(limit as usize) as u32);
+        let budget = self.outbound_budget.borrow();
+        let queues = self.outbound_queues.borrow();
+        serde_json::json!({
+            "requests_per_sec_limit": limit,
+            "used_this_second": used,
+            "utilization_bps": if limit == 0 { 0 } else { used as u64 * 10_000 / limit as u64 },
+            "sent_total": budget.sent_total,
+            "shed": budget.shed,
+            "fanout_reduced": budget.fanout_reduced,
+            "skipped_awaiting_repair": budget.skipped.len(),
+            "queued": queues.peers.values().map(|queue| queue.sends.len()).sum::<usize>(),
+            "queue_depths": queues.depths(),
+            "queue_dropped": queues.peers.values().map(|queue| queue.dropped).sum::<u64>(),
+        })
+    }
+}
@@ -0,0 +1,121 @@
+// PERSISTED STATE
+// The app's state is saved whole on every change and loaded as-is on start, so it has to read
+// whatever older versions wrote: the deserializers here accept the older shapes, and the
+// migrations run once at init to bring loaded state up to date. The journal, kept with it,
+// records what the node did and why.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use hyperware_process_lib::eth::U256;
+
+use crate::recording::Boundary;
+use crate::{merge_intent, HyperMevApp, PeerInfo, StrategyConfig, DEMO_CAPITAL_AMOUNT, DEMO_CAPITAL_ASSET};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct JournalEntry {
+    pub timestamp: String,
+    pub kind: String,
+    pub detail: serde_json::Value,
+}
+
+const JOURNAL_CAPACITY: usize = 1000;
+
+// Accepts a unix-seconds timestamp as a number or as the decimal string older state stored
+pub(crate) fn numeric_timestamp<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Secs(u64),
+        Text(String),
+    }
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Secs(secs) => Ok(secs),
+        Timestamp::Text(text) => text.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+// known_peers used to be a plain set of node names; those load as pending peers
+pub(crate) fn peer_map<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, PeerInfo>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Peers(HashMap<String, PeerInfo>),
+        Names(Vec<String>),
+    }
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Peers(peers) => peers,
+        Stored::Names(names) => names.into_iter().map(|name| (name, PeerInfo::pending(String::new()))).collect(),
+    })
+}
+
+impl HyperMevApp {
+    pub(crate) fn record_journal(&mut self, kind: &str, detail: serde_json::Value) {
+        self.record_frame(Boundary::Decision, None, kind, detail.clone());
+        self.journal.push(JournalEntry {
+            timestamp: Self::current_timestamp(),
+            kind: kind.to_string(),
+            detail,
+        });
+        if self.journal.len() > JOURNAL_CAPACITY {
+            let excess = self.journal.len() - JOURNAL_CAPACITY;
+            self.journal.drain(..excess);
+        }
+    }
+
+    // Moves older state's aave_strategy_config into strategy_configs and watched_positions, and
+    // registers every strategy we support that has no config yet
+    pub(crate) fn migrate_strategy_configs(&mut self) {
+        if let Some(legacy) = self.legacy_aave_strategy_config.take() {
+            let mut config = StrategyConfig::aave_default();
+            if !legacy.min_health_factor.is_empty() {
+                config.params["min_health_factor"] = serde_json::json!(legacy.min_health_factor);
+            }
+            self.strategy_configs.entry("aave-liquidation".to_string()).or_insert(config);
+            for (borrower, position) in legacy.monitored_positions {
+                self.watched_positions.entry(borrower).or_insert(position);
+            }
+        }
+        self.strategy_configs.entry("aave-liquidation".to_string()).or_insert_with(StrategyConfig::aave_default);
+    }
+
+    // Older versions kept every intent a provider sent; only its newest counts now
+    pub(crate) fn collapse_duplicate_intents(&mut self) {
+        for (opp_id, intents) in self.submitted_intents.iter_mut() {
+            let before = intents.len();
+            let mut collapsed = Vec::new();
+            for data in intents.drain(..) {
+                merge_intent(&mut collapsed, data);
+            }
+            collapsed.sort_by_key(|data| data.received_at);
+            *intents = collapsed;
+            if intents.len() < before {
+                println!("Collapsed {} superseded intents on {}", before - intents.len(), opp_id);
+            }
+        }
+    }
+
+    // One-time strip of the 10,000 USDC older versions wrote into available_balances on every start
+    pub(crate) fn migrate_seed_capital(&mut self) {
+        if self.seed_capital_migrated {
+            return;
+        }
+        self.seed_capital_migrated = true;
+        let seed = DEMO_CAPITAL_AMOUNT.parse::<U256>().unwrap_or(U256::ZERO);
+        let Some(balance) = self.available_balances.get_mut(DEMO_CAPITAL_ASSET) else {
+            return;
+        };
+        let current = balance.parse::<U256>().unwrap_or(U256::ZERO);
+        let stripped = current.min(seed);
+        if stripped.is_zero() {
+            return;
+        }
+        *balance = (current - stripped).to_string();
+        self.stripped_seed_capital = Some(stripped.to_string());
+        println!("Removed {} of phantom seed capital; call confirm_seed_capital if it is real", stripped);
+        self.record_journal("SeedCapitalStripped", serde_json::json!({
+            "asset": DEMO_CAPITAL_ASSET,
+            "amount": stripped.to_string(),
+        }));
+    }
+}
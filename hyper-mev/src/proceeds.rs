@@ -0,0 +1,141 @@
+// PROCEEDS DISTRIBUTION
+// The executor's account of who is owed what from one execution, built from its receipt and the
+// split it declared, and sent right behind the receipt. Without it every node works out only its
+// own share, so two nodes can disagree about an execution without either noticing. With it every
+// node keeps the same ledger, and providers check their own lines against the intent they sent.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::OutstandingIntent;
+
+// Distributions kept; the oldest received go first
+pub const DISTRIBUTION_LEDGER_CAPACITY: usize = 1000;
+// How long our settled intents are kept to check distributions against, from submission
+pub const SETTLED_INTENT_RETENTION_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DistributionRole {
+    Provider,
+    Finder,
+    Executor,
+    // Rounding dust, where the scheme doesn't fold it into the first provider's share
+    DustSink,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DistributionEntry {
+    pub node_id: String,
+    pub asset: String,
+    pub amount: String,
+    pub role: DistributionRole,
+    // Provider lines: the capital drawn and the fee bid it was drawn at
+    #[serde(default)]
+    pub amount_used: Option<String>,
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ProceedsDistribution {
+    pub opp_id: String,
+    pub executor: String,
+    pub entries: Vec<DistributionEntry>,
+}
+
+impl ProceedsDistribution {
+    // Everything the distribution gives `node`, across roles
+    pub fn owed_to(&self, node: &str) -> U256 {
+        self.entries.iter()
+            .filter(|entry| entry.node_id == node)
+            .map(|entry| entry.amount.parse::<U256>().unwrap_or(U256::ZERO))
+            .fold(U256::ZERO, |acc, amount| acc + amount)
+    }
+
+    // The distribution as `node` sees it: its provider lines against the intent it submitted (no
+    // more drawn than it offered, at the fee it bid), and its total against the one it worked out
+    // from the receipt for itself
+    pub fn check_for(&self, node: &str, intent: Option<&OutstandingIntent>, computed_share: U256) -> Vec<DistributionFlag> {
+        let lines: Vec<&DistributionEntry> = self.entries.iter()
+            .filter(|entry| entry.node_id == node && entry.role == DistributionRole::Provider)
+            .collect();
+        let mut flags = Vec::new();
+        match intent {
+            None if !lines.is_empty() => flags.push(DistributionFlag::NoIntent),
+            None => {}
+            Some(outstanding) => {
+                let max_amount = outstanding.intent.max_amount.parse::<U256>().unwrap_or(U256::ZERO);
+                let used = lines.iter()
+                    .map(|entry| entry.amount_used.as_deref().unwrap_or("0").parse::<U256>().unwrap_or(U256::ZERO))
+                    .fold(U256::ZERO, |acc, amount| acc + amount);
+                if used > max_amount {
+                    flags.push(DistributionFlag::AmountExceedsIntent {
+                        amount_used: used.to_string(),
+                        max_amount: max_amount.to_string(),
+                    });
+                }
+                for entry in &lines {
+                    let declared = entry.fee_bps.unwrap_or(0);
+                    if declared != outstanding.intent.fee_bps {
+                        flags.push(DistributionFlag::FeeMismatch {
+                            intent_fee_bps: outstanding.intent.fee_bps,
+                            declared_fee_bps: declared,
+                        });
+                    }
+                }
+            }
+        }
+        let declared = self.owed_to(node);
+        if declared != computed_share {
+            flags.push(DistributionFlag::ShareMismatch {
+                declared: declared.to_string(),
+                computed: computed_share.to_string(),
+            });
+        }
+        flags
+    }
+}
+
+// Why a node doesn't accept its part of a distribution as it stands
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum DistributionFlag {
+    // Lines for us without an intent of ours on record to check them against
+    NoIntent,
+    AmountExceedsIntent { amount_used: String, max_amount: String },
+    FeeMismatch { intent_fee_bps: u16, declared_fee_bps: u16 },
+    // The executor's total for us differs from the one we derive from the receipt
+    ShareMismatch { declared: String, computed: String },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DistributionRecord {
+    pub distribution: ProceedsDistribution,
+    pub received_at: u64,
+    pub owed_to_us: String,
+    #[serde(default)]
+    pub flags: Vec<DistributionFlag>,
+    // Its receipt awaits the chain check, so nothing has been posted to the ledger from it yet
+    #[serde(default)]
+    pub awaiting_verification: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct DistributionLedger {
+    pub records: BTreeMap<String, DistributionRecord>,
+}
+
+impl DistributionLedger {
+    // Records (or replaces) an opportunity's distribution, dropping the oldest past capacity
+    pub fn insert(&mut self, record: DistributionRecord) {
+        self.records.insert(record.distribution.opp_id.clone(), record);
+        while self.records.len() > DISTRIBUTION_LEDGER_CAPACITY {
+            let Some(oldest) = self.records.iter()
+                .min_by_key(|(opp_id, record)| (record.received_at, (*opp_id).clone()))
+                .map(|(opp_id, _)| opp_id.clone()) else {
+                break;
+            };
+            self.records.remove(&oldest);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// INBOUND RATE LIMITING
+// One token bucket per peer and message class, so a flooding peer can't fill memory or run an
+// evaluation for every broadcast it sends. Each bucket holds up to a minute's allowance and
+// refills continuously. Over-limit messages are refused before they're parsed. Buckets live in
+// memory only; the refusal counts go into the peer's reputation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Tokens are counted in thousandths so slow refill rates don't round away
+const MILLI: u64 = 1_000;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InboundClass {
+    Announcement,
+    Broadcast,
+    Intent,
+    Receipt,
+}
+
+impl InboundClass {
+    // Handlers outside these classes aren't limited
+    pub fn for_handler(handler: &str) -> Option<Self> {
+        match handler {
+            "ReceiveNodeAnnouncement" | "ReceiveSyncRequest" | "ReceiveKeyRotation" => Some(Self::Announcement),
+            "ReceiveOpportunityBroadcast" | "ReceiveOpportunityUpdate" => Some(Self::Broadcast),
+            "ReceiveIntentSubmission" | "ReceiveIntentAmendment" | "ReceiveIntentRevocation" => Some(Self::Intent),
+            "ReceiveExecutionReceipt" | "ReceiveProceedsDistribution" | "ReceiveReceiptDispute"
+            | "ReceiveNettingStatement" | "ReceiveNettingAcknowledgment" => Some(Self::Receipt),
+            _ => None,
+        }
+    }
+}
+
+// Messages per minute each peer may send per class; 0 leaves the class unlimited
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct InboundRateLimits {
+    pub announcements_per_min: u32,
+    pub broadcasts_per_min: u32,
+    pub intents_per_min: u32,
+    pub receipts_per_min: u32,
+}
+
+impl Default for InboundRateLimits {
+    fn default() -> Self {
+        Self {
+            announcements_per_min: 6,
+            broadcasts_per_min: 10,
+            intents_per_min: 30,
+            receipts_per_min: 30,
+        }
+    }
+}
+
+impl InboundRateLimits {
+    pub fn per_min(&self, class: InboundClass) -> u32 {
+        match class {
+            InboundClass::Announcement => self.announcements_per_min,
+            InboundClass::Broadcast => self.broadcasts_per_min,
+            InboundClass::Intent => self.intents_per_min,
+            InboundClass::Receipt => self.receipts_per_min,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    milli_tokens: u64,
+    refilled_at_ms: u64,
+}
+
+impl TokenBucket {
+    fn full(per_min: u32, now_ms: u64) -> Self {
+        Self { milli_tokens: per_min as u64 * MILLI, refilled_at_ms: now_ms }
+    }
+
+    fn refill(&mut self, per_min: u32, now_ms: u64) {
+        let capacity = per_min as u64 * MILLI;
+        let elapsed_ms = now_ms.saturating_sub(self.refilled_at_ms);
+        // per_min tokens a minute comes to per_min thousandths of a token every 60ms
+        let added = elapsed_ms.saturating_mul(per_min as u64) / 60;
+        self.milli_tokens = self.milli_tokens.saturating_add(added).min(capacity);
+        self.refilled_at_ms = now_ms;
+    }
+
+    // Whole tokens available now
+    pub fn available(&self) -> u64 {
+        self.milli_tokens / MILLI
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PeerRateLimiter {
+    buckets: BTreeMap<InboundClass, TokenBucket>,
+    // Messages refused per class since we started
+    pub limited: BTreeMap<InboundClass, u64>,
+}
+
+impl PeerRateLimiter {
+    // Takes a token for one message of `class`; false when the bucket is empty
+    pub fn admit(&mut self, class: InboundClass, limits: &InboundRateLimits, now_ms: u64) -> bool {
+        let per_min = limits.per_min(class);
+        if per_min == 0 {
+            return true;
+        }
+        let bucket = self.buckets.entry(class).or_insert_with(|| TokenBucket::full(per_min, now_ms));
+        bucket.refill(per_min, now_ms);
+        if bucket.milli_tokens >= MILLI {
+            bucket.milli_tokens -= MILLI;
+            return true;
+        }
+        *self.limited.entry(class).or_insert(0) += 1;
+        false
+    }
+
+    // When a limited message last arrived; a limiter idle for a minute holds only full buckets
+    pub fn last_used_ms(&self) -> u64 {
+        self.buckets.values().map(|bucket| bucket.refilled_at_ms).max().unwrap_or(0)
+    }
+
+    pub fn summary(&self, limits: &InboundRateLimits, now_ms: u64) -> serde_json::Value {
+        let classes = [InboundClass::Announcement, InboundClass::Broadcast, InboundClass::Intent, InboundClass::Receipt];
+        let by_class: BTreeMap<String, serde_json::Value> = classes.into_iter()
+            .map(|class| {
+                let per_min = limits.per_min(class);
+                let available = self.buckets.get(&class).map_or(per_min as u64, |bucket| {
+                    let mut bucket = bucket.clone();
+                    bucket.refill(per_min, now_ms);
+                    bucket.available()
+                });
+                (format!("{:?}", class), serde_json::json!({
+                    "per_min": per_min,
+                    "available": available,
+                    "limited": self.limited.get(&class).copied().unwrap_or(0),
+                }))
+            })
+            .collect();
+        serde_json::json!(by_class)
+    }
+}
@@ -0,0 +1,157 @@
+// TRAFFIC RECORDING
+// A bounded capture of what crosses the node's boundaries (P2P messages in and out, Artemis
+// frames both ways) interleaved with the decisions taken, for building fixtures and looking into
+// incidents. Recording is only ever started explicitly, stops itself at its time or size cap, and
+// is redacted on export.
+
+use serde::{Deserialize, Serialize};
+
+// Ceilings on what a start request may ask for
+pub const MAX_RECORDING_SECS: u64 = 60 * 60;
+pub const MAX_RECORDING_BYTES: usize = 32 * 1024 * 1024;
+
+pub const RECORDING_FORMAT: &str = "hyper-mev-recording";
+pub const RECORDING_FORMAT_VERSION: u32 = 1;
+
+// Field names (matched case-insensitively, by substring) whose values never leave the node
+const REDACTED_FIELDS: [&str; 8] = [
+    "signature",
+    "token",
+    "secret",
+    "password",
+    "private_key",
+    "api_key",
+    "payout",
+    "wallet",
+];
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    PeerInbound,
+    PeerOutbound,
+    ArtemisInbound,
+    ArtemisOutbound,
+    // Journaled decision or skipped commitment, not a message
+    Decision,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RecordedFrame {
+    pub at: u64,
+    pub boundary: Boundary,
+    // Peer node or Artemis channel on the other side, when there is one
+    pub counterparty: Option<String>,
+    // Remote handler, Artemis message type or decision kind
+    pub label: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Recording {
+    pub started_at: u64,
+    pub until: u64,
+    pub max_bytes: usize,
+    pub bytes: usize,
+    pub frames: Vec<RecordedFrame>,
+    // Set once recording has stopped: "DurationCap", "SizeCap" or "Stopped"
+    pub stopped: Option<String>,
+    pub ended_at: Option<u64>,
+}
+
+impl Recording {
+    pub fn start(now: u64, duration_secs: u64, max_bytes: usize) -> Self {
+        Self {
+            started_at: now,
+            until: now + duration_secs.clamp(1, MAX_RECORDING_SECS),
+            max_bytes: max_bytes.clamp(1, MAX_RECORDING_BYTES),
+            bytes: 0,
+            frames: Vec::new(),
+            stopped: None,
+            ended_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.stopped.is_none()
+    }
+
+    pub fn stop(&mut self, reason: &str, now: u64) {
+        if self.is_active() {
+            self.stopped = Some(reason.to_string());
+            self.ended_at = Some(now);
+        }
+    }
+
+    // Captures a frame while recording. Returns the reason when this call hit a cap and
+    // stopped the recording; the frame that would cross the size cap is not kept.
+    pub fn capture(&mut self, frame: RecordedFrame) -> Option<&'static str> {
+        if !self.is_active() {
+            return None;
+        }
+        if frame.at >= self.until {
+            self.stop("DurationCap", frame.at);
+            return Some("DurationCap");
+        }
+        let size = serde_json::to_string(&frame.payload).map(|json| json.len()).unwrap_or(0) + frame.label.len();
+        if self.bytes + size > self.max_bytes {
+            self.stop("SizeCap", frame.at);
+            return Some("SizeCap");
+        }
+        self.bytes += size;
+        self.frames.push(frame);
+        None
+    }
+
+    pub fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "active": self.is_active(),
+            "started_at": self.started_at,
+            "until": self.until,
+            "frames": self.frames.len(),
+            "bytes": self.bytes,
+            "max_bytes": self.max_bytes,
+            "stopped": self.stopped,
+            "ended_at": self.ended_at,
+        })
+    }
+
+    // Fixture file with every frame redacted
+    pub fn export(&self, node: &str) -> serde_json::Value {
+        let frames: Vec<RecordedFrame> = self.frames.iter()
+            .map(|frame| RecordedFrame { payload: redact(&frame.payload), ..frame.clone() })
+            .collect();
+        serde_json::json!({
+            "format": RECORDING_FORMAT,
+            "version": RECORDING_FORMAT_VERSION,
+            "node": node,
+            "started_at": self.started_at,
+            "ended_at": self.ended_at,
+            "stopped": self.stopped,
+            "frames": frames,
+        })
+    }
+}
+
+// Blanks sensitive fields at any depth, including inside JSON carried as strings (embedded
+// opportunities, intents and receipts)
+pub fn redact(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(map.iter()
+            .map(|(key, value)| {
+                let lowered = key.to_lowercase();
+                if REDACTED_FIELDS.iter().any(|field| lowered.contains(field)) && !value.is_null() {
+                    (key.clone(), serde_json::json!("[redacted]"))
+                } else {
+                    (key.clone(), redact(value))
+                }
+            })
+            .collect()),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(redact).collect()),
+        serde_json::Value::String(text) => match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(embedded @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) =>
+                serde_json::Value::String(redact(&embedded).to_string()),
+            _ => value.clone(),
+        },
+        _ => value.clone(),
+    }
+}
@@ -0,0 +1,288 @@
+// COUNTERPARTY RECORD VIEWS
+// An operator whose numbers for an execution look off can ask the counterparty for its records of
+// that opportunity and get a field-level diff against ours. The counterparty shares each section
+// (opportunity, intents, receipt, settlement lines) as its policy allows, signs what it sends,
+// and never hands over another provider's figures: amounts belonging to anyone but the two
+// parties are bucketed on both sides before comparing, so the diff still lines up.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::allocation::AllocationRecord;
+use crate::bundle::ItemSignature;
+use crate::CapitalUsage;
+
+// Views kept for get_counterparty_views, oldest dropped first
+pub const MAX_COUNTERPARTY_VIEWS: usize = 50;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordSection {
+    Opportunity,
+    // Intents between the two parties, either way
+    Intents,
+    Receipt,
+    // Statement lines with one party as executor and the other as provider
+    Settlement,
+}
+
+pub const ALL_SECTIONS: [RecordSection; 4] =
+    [RecordSection::Opportunity, RecordSection::Intents, RecordSection::Receipt, RecordSection::Settlement];
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum SharingLevel {
+    Anyone,
+    // Only nodes that took part in the opportunity: finder, provider to or from us, or executor
+    Counterparties,
+    Nobody,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RecordSharingPolicy {
+    pub opportunity: SharingLevel,
+    pub intents: SharingLevel,
+    pub receipt: SharingLevel,
+    pub settlement: SharingLevel,
+}
+
+impl Default for RecordSharingPolicy {
+    fn default() -> Self {
+        Self {
+            // Broadcast to the whole pool anyway
+            opportunity: SharingLevel::Anyone,
+            intents: SharingLevel::Counterparties,
+            receipt: SharingLevel::Counterparties,
+            settlement: SharingLevel::Counterparties,
+        }
+    }
+}
+
+impl RecordSharingPolicy {
+    pub fn level(&self, section: RecordSection) -> SharingLevel {
+        match section {
+            RecordSection::Opportunity => self.opportunity,
+            RecordSection::Intents => self.intents,
+            RecordSection::Receipt => self.receipt,
+            RecordSection::Settlement => self.settlement,
+        }
+    }
+
+    // Why `section` isn't shared with this requester, if it isn't
+    pub fn refusal(&self, section: RecordSection, counterparty: bool) -> Option<&'static str> {
+        match self.level(section) {
+            SharingLevel::Anyone => None,
+            SharingLevel::Counterparties if counterparty => None,
+            SharingLevel::Counterparties => Some("NotACounterparty"),
+            SharingLevel::Nobody => Some("NotShared"),
+        }
+    }
+}
+
+// One record as shared: canonical JSON (third-party amounts bucketed where `redacted`) under a
+// key both parties derive the same way
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SharedRecord {
+    pub section: RecordSection,
+    pub key: String,
+    pub canonical: String,
+    #[serde(default)]
+    pub redacted: bool,
+    #[serde(default)]
+    pub signatures: Vec<ItemSignature>,
+}
+
+// Signed by the responder as its canonical JSON
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RecordSet {
+    pub opp_id: String,
+    pub requested_by: String,
+    pub records: Vec<SharedRecord>,
+    pub refused: BTreeMap<RecordSection, String>,
+}
+
+impl RecordSet {
+    pub fn canonical_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum RecordDiffKind {
+    Match,
+    Mismatch,
+    MissingFromOurs,
+    MissingFromTheirs,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FieldMismatch {
+    // Dotted path into the record, with array indexes
+    pub path: String,
+    pub ours: serde_json::Value,
+    pub theirs: serde_json::Value,
+}
+
+// Signatures a record carried: None when it carried none
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SignatureCheck {
+    pub ours: Option<bool>,
+    pub theirs: Option<bool>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RecordDiff {
+    pub section: RecordSection,
+    pub key: String,
+    pub kind: RecordDiffKind,
+    pub fields: Vec<FieldMismatch>,
+    pub redacted: bool,
+    pub signatures: SignatureCheck,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CounterpartyView {
+    pub request_id: String,
+    pub peer: String,
+    pub opp_id: String,
+    pub sections: Vec<RecordSection>,
+    pub requested_at: u64,
+    pub received_at: Option<u64>,
+    // The responder's signature over its record set
+    pub response_signature_valid: Option<bool>,
+    pub refused: BTreeMap<RecordSection, String>,
+    pub diffs: Vec<RecordDiff>,
+}
+
+impl CounterpartyView {
+    pub fn summary(&self) -> serde_json::Value {
+        let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        for diff in &self.diffs {
+            *by_kind.entry(format!("{:?}", diff.kind)).or_insert(0) += 1;
+        }
+        serde_json::json!({
+            "request_id": self.request_id,
+            "peer": self.peer,
+            "opp_id": self.opp_id,
+            "requested_at": self.requested_at,
+            "received_at": self.received_at,
+            "response_signature_valid": self.response_signature_valid,
+            "by_kind": by_kind,
+        })
+    }
+}
+
+// The fields of a receipt that both parties hold alike, whether it's kept in full or archived
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ReceiptView {
+    pub opp_id: String,
+    pub executor: String,
+    pub status: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub total_proceeds: String,
+    pub gas_paid_usdc: String,
+    pub used_amounts: Vec<CapitalUsage>,
+    // Only while the full receipt is kept
+    pub allocation: Option<AllocationRecord>,
+}
+
+impl ReceiptView {
+    // Buckets every figure belonging to neither party; true if there was one
+    pub fn redact(&mut self, parties: &[&str]) -> bool {
+        let third_party = |node: &str| !parties.contains(&node);
+        let mut redacted = false;
+        for used in self.used_amounts.iter_mut().filter(|used| third_party(&used.node_id)) {
+            used.amount_used = bucket_amount(&used.amount_used);
+            used.profit_share = bucket_amount(&used.profit_share);
+            redacted = true;
+        }
+        if let Some(allocation) = self.allocation.as_mut() {
+            for candidate in allocation.candidates.iter_mut().filter(|candidate| third_party(&candidate.provider)) {
+                candidate.max_amount = bucket_amount(&candidate.max_amount);
+                redacted = true;
+            }
+            for line in allocation.allocation.iter_mut().filter(|line| third_party(&line.provider)) {
+                line.amount = bucket_amount(&line.amount);
+                redacted = true;
+            }
+        }
+        redacted
+    }
+}
+
+// Power-of-ten floor of an amount ("1000+" for 1000..9999); what a third party's figure is
+// reduced to. Unparseable amounts become "unknown".
+pub fn bucket_amount(amount: &str) -> String {
+    let digits = amount.trim();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return "unknown".to_string();
+    }
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    format!("1{}+", "0".repeat(digits.len() - 1))
+}
+
+// Collects the leaf fields where two records differ
+pub fn field_mismatches(ours: &serde_json::Value, theirs: &serde_json::Value, path: &str, out: &mut Vec<FieldMismatch>) {
+    use serde_json::Value;
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match (ours, theirs) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                field_mismatches(a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null), &join(key), out);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                field_mismatches(a.get(index).unwrap_or(&Value::Null), b.get(index).unwrap_or(&Value::Null), &join(&index.to_string()), out);
+            }
+        }
+        _ if ours != theirs => out.push(FieldMismatch {
+            path: path.to_string(),
+            ours: ours.clone(),
+            theirs: theirs.clone(),
+        }),
+        _ => {}
+    }
+}
+
+// Pairs records by section and key and diffs each pair field by field. Signature checks are
+// left for the caller to fill in.
+pub fn diff_records(ours: &[SharedRecord], theirs: &[SharedRecord], sections: &[RecordSection]) -> Vec<RecordDiff> {
+    let index = |records: &[SharedRecord]| -> BTreeMap<(RecordSection, String), SharedRecord> {
+        records.iter()
+            .filter(|record| sections.contains(&record.section))
+            .map(|record| ((record.section, record.key.clone()), record.clone()))
+            .collect()
+    };
+    let (ours, theirs) = (index(ours), index(theirs));
+    let keys: BTreeSet<&(RecordSection, String)> = ours.keys().chain(theirs.keys()).collect();
+    keys.into_iter()
+        .map(|slot| {
+            let (section, key) = slot.clone();
+            let (our_record, their_record) = (ours.get(slot), theirs.get(slot));
+            let mut fields = Vec::new();
+            let kind = match (our_record, their_record) {
+                (Some(a), Some(b)) => {
+                    let parse = |record: &SharedRecord| serde_json::from_str::<serde_json::Value>(&record.canonical)
+                        .unwrap_or_else(|_| serde_json::Value::String(record.canonical.clone()));
+                    field_mismatches(&parse(a), &parse(b), "", &mut fields);
+                    if fields.is_empty() { RecordDiffKind::Match } else { RecordDiffKind::Mismatch }
+                }
+                (None, _) => RecordDiffKind::MissingFromOurs,
+                (_, None) => RecordDiffKind::MissingFromTheirs,
+            };
+            RecordDiff {
+                section,
+                key,
+                kind,
+                fields,
+                redacted: our_record.into_iter().chain(their_record).any(|record| record.redacted),
+                signatures: SignatureCheck::default(),
+            }
+        })
+        .collect()
+}
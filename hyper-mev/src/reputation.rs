@@ -0,0 +1,143 @@
+// PEER REPUTATION
+// What we have seen of each peer: how many of its messages were well-formed, how many of the
+// opportunities it found reached a receipt or expired, and how much of the profit it estimated
+// those executions actually realized. Kept in app state, so it survives restarts. The policy in
+// NodeConfig turns it into decisions: ignoring a peer's broadcasts outright, and not committing
+// capital to finds from finders below a bar. Thresholds only apply once there is enough history.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PeerReputation {
+    // Messages that parsed, and those that didn't or were refused as invalid
+    pub valid_messages: u64,
+    pub invalid_messages: u64,
+    // Opportunities this peer found that we accepted, and how they ended
+    pub opportunities_sent: u64,
+    pub opportunities_settled: u64,
+    pub opportunities_expired: u64,
+    // Over settled finds with a known estimate: the profit the finder estimated, and what the
+    // receipts show after gas (zero for failed executions)
+    pub estimated_profit: String,
+    pub realized_profit: String,
+    // Broadcasts dropped because of this record
+    pub broadcasts_ignored: u64,
+    // Messages refused for exceeding the inbound rate limits; they count as invalid
+    pub rate_limited: u64,
+}
+
+impl PeerReputation {
+    pub fn note_malformed(&mut self) {
+        self.invalid_messages += 1;
+    }
+
+    // A message already counted as well-formed that was then refused on its content
+    pub fn note_refused(&mut self) {
+        self.valid_messages = self.valid_messages.saturating_sub(1);
+        self.invalid_messages += 1;
+    }
+
+    pub fn note_settled(&mut self, estimated: Option<U256>, realized: U256) {
+        self.opportunities_settled += 1;
+        let Some(estimated) = estimated else {
+            return;
+        };
+        let total = |sum: &str, amount: U256| (sum.parse::<U256>().unwrap_or(U256::ZERO) + amount).to_string();
+        self.estimated_profit = total(&self.estimated_profit, estimated);
+        self.realized_profit = total(&self.realized_profit, realized);
+    }
+
+    pub fn invalid_bps(&self) -> u64 {
+        let invalid = self.invalid_messages + self.rate_limited;
+        let total = self.valid_messages + invalid;
+        if total == 0 {
+            return 0;
+        }
+        invalid.saturating_mul(10_000) / total
+    }
+
+    // Realized profit as bps of the estimate; None before any estimated profit was settled
+    pub fn realization_bps(&self) -> Option<u64> {
+        let estimated = self.estimated_profit.parse::<U256>().ok().filter(|estimated| !estimated.is_zero())?;
+        let realized = self.realized_profit.parse::<U256>().unwrap_or(U256::ZERO);
+        let bps = realized.saturating_mul(U256::from(10_000u64)) / estimated;
+        Some(u64::try_from(bps).unwrap_or(u64::MAX))
+    }
+
+    // 0 without a record; 1 after 5 settled finds, 2 after 50. Either needs no more finds expired
+    // than settled, and at least half the estimated profit realized.
+    pub fn tier(&self) -> u8 {
+        let productive = self.opportunities_settled >= self.opportunities_expired
+            && self.realization_bps().map_or(true, |bps| bps >= 5_000);
+        match self.opportunities_settled {
+            settled if productive && settled >= 50 => 2,
+            settled if productive && settled >= 5 => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "valid_messages": self.valid_messages,
+            "invalid_messages": self.invalid_messages,
+            "invalid_bps": self.invalid_bps(),
+            "opportunities_sent": self.opportunities_sent,
+            "opportunities_settled": self.opportunities_settled,
+            "opportunities_expired": self.opportunities_expired,
+            "estimated_profit": self.estimated_profit,
+            "realized_profit": self.realized_profit,
+            "realization_bps": self.realization_bps(),
+            "tier": self.tier(),
+            "broadcasts_ignored": self.broadcasts_ignored,
+            "rate_limited": self.rate_limited,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ReputationPolicy {
+    // Messages seen before ignore_above_invalid_bps applies
+    pub min_messages: u64,
+    // Broadcasts from a peer whose invalid share exceeds this are acknowledged and dropped
+    pub ignore_above_invalid_bps: Option<u16>,
+    // Settled finds before min_realization_bps applies
+    pub min_settled: u64,
+    // Finds from finders below these aren't evaluated for our capital
+    pub min_realization_bps: Option<u16>,
+    pub min_tier_to_evaluate: u8,
+}
+
+impl Default for ReputationPolicy {
+    fn default() -> Self {
+        Self {
+            min_messages: 20,
+            ignore_above_invalid_bps: None,
+            min_settled: 5,
+            min_realization_bps: None,
+            min_tier_to_evaluate: 0,
+        }
+    }
+}
+
+impl ReputationPolicy {
+    pub fn ignores(&self, reputation: &PeerReputation) -> bool {
+        let seen = reputation.valid_messages + reputation.invalid_messages + reputation.rate_limited;
+        self.ignore_above_invalid_bps
+            .is_some_and(|limit| seen >= self.min_messages && reputation.invalid_bps() > limit as u64)
+    }
+
+    // Why a finder's finds don't get our capital, if they don't
+    pub fn evaluation_refusal(&self, reputation: &PeerReputation) -> Option<String> {
+        if reputation.tier() < self.min_tier_to_evaluate {
+            return Some(format!("tier {} is below {}", reputation.tier(), self.min_tier_to_evaluate));
+        }
+        let realization = reputation.realization_bps()
+            .filter(|_| reputation.opportunities_settled >= self.min_settled)?;
+        self.min_realization_bps
+            .filter(|min| realization < *min as u64)
+            .map(|min| format!("realized {} bps of estimated profit, below {}", realization, min))
+    }
+}
@@ -0,0 +1,128 @@
+// CAPITAL RESERVATIONS
+// Two-phase commitment ahead of execution. The executor asks each provider in its allocation to
+// reserve the allocated amount; the provider confirms only while its balance still backs the
+// lock, and the reservation lapses at its expiry. Only a fully confirmed allocation goes to the
+// bridge: a rejection or a request left unanswered past its expiry excludes that intent, and the
+// executor re-plans with the rest. Pure bookkeeping: messaging and locks are up to the caller.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::allocation::AllocationLine;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum ReservationStatus {
+    Requested,
+    Confirmed,
+    Rejected(String),
+}
+
+// One allocated intent's reservation, as the executor tracks it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ReservationLine {
+    pub provider: String,
+    pub amount: String,
+    pub expires_at: u64,
+    pub status: ReservationStatus,
+}
+
+// An executor's reservations for one opportunity, by intent hash
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ReservationRound {
+    pub lines: BTreeMap<String, ReservationLine>,
+    // Intents rejected or unanswered; re-planning leaves them out for good
+    pub excluded: BTreeSet<String>,
+}
+
+fn amount(value: &str) -> u128 {
+    value.parse::<u128>().unwrap_or(0)
+}
+
+impl ReservationRound {
+    // Allocation lines without a live reservation for at least their amount: never requested,
+    // confirmed for less than is now allocated, or confirmed but lapsed
+    pub fn to_request(&self, allocation: &[AllocationLine], now: u64) -> Vec<AllocationLine> {
+        allocation.iter()
+            .filter(|line| match self.lines.get(&line.intent_hash) {
+                None => true,
+                Some(reserved) => match reserved.status {
+                    ReservationStatus::Requested => false,
+                    ReservationStatus::Confirmed => reserved.expires_at <= now || amount(&reserved.amount) < amount(&line.amount),
+                    ReservationStatus::Rejected(_) => false,
+                },
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn mark_requested(&mut self, line: &AllocationLine, expires_at: u64) {
+        self.lines.insert(line.intent_hash.clone(), ReservationLine {
+            provider: line.provider.clone(),
+            amount: line.amount.clone(),
+            expires_at,
+            status: ReservationStatus::Requested,
+        });
+    }
+
+    // Every line holds a live reservation for at least its amount
+    pub fn confirmed(&self, allocation: &[AllocationLine], now: u64) -> bool {
+        allocation.iter().all(|line| self.lines.get(&line.intent_hash).is_some_and(|reserved|
+            reserved.status == ReservationStatus::Confirmed
+                && reserved.expires_at > now
+                && amount(&reserved.amount) >= amount(&line.amount)))
+    }
+
+    // Applies a provider's answer; answers from anyone but the provider asked, or to a request
+    // no longer outstanding, are ignored. A provider may grant a shorter expiry than requested.
+    // Returns whether the intent was excluded.
+    pub fn record_response(&mut self, intent_hash: &str, provider: &str, confirmed: bool, expires_at: u64, reason: &str) -> Option<bool> {
+        let line = self.lines.get_mut(intent_hash)
+            .filter(|line| line.provider == provider && line.status == ReservationStatus::Requested)?;
+        if confirmed {
+            line.status = ReservationStatus::Confirmed;
+            line.expires_at = line.expires_at.min(expires_at);
+            return Some(false);
+        }
+        line.status = ReservationStatus::Rejected(reason.to_string());
+        self.excluded.insert(intent_hash.to_string());
+        Some(true)
+    }
+
+    // Requests unanswered by their expiry count as rejected. Returns the intents excluded.
+    pub fn expire_unanswered(&mut self, now: u64) -> Vec<String> {
+        let mut expired = Vec::new();
+        for (intent_hash, line) in self.lines.iter_mut() {
+            if line.status == ReservationStatus::Requested && line.expires_at <= now {
+                line.status = ReservationStatus::Rejected("Unanswered".to_string());
+                expired.push(intent_hash.clone());
+            }
+        }
+        self.excluded.extend(expired.iter().cloned());
+        expired
+    }
+}
+
+// A reservation we granted as a capital provider; the commitment lock stays pinned until expiry
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CapitalReservation {
+    pub executor: String,
+    pub intent_hash: String,
+    pub asset: String,
+    pub amount: String,
+    pub reserved_at: u64,
+    pub expires_at: u64,
+}
+
+// Why a provider refuses to reserve, given its lock on the opportunity and its balance
+pub fn reservation_refusal(requested: &str, locked: Option<&str>, balance: &str, committed_total: &str) -> Option<String> {
+    let Some(locked) = locked else {
+        return Some("NoCommitment".to_string());
+    };
+    if amount(requested) > amount(locked) {
+        return Some(format!("ExceedsCommitment: {} requested, {} locked", requested, locked));
+    }
+    if amount(balance) < amount(committed_total) {
+        return Some(format!("BalanceMoved: {} held against {} committed", balance, committed_total));
+    }
+    None
+}
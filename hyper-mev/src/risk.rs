@@ -0,0 +1,97 @@
+// OPPORTUNITY RISK FLAGS
+// Structural red flags that the threshold checks don't see, computed when an opportunity is
+// ingested and again when bridge lookups come back. Each flag is one entry in RISK_RULES; the
+// node config maps flags to actions, and evaluation only ever asks for the strictest action, so
+// a new flag is a new enum variant and rule here and nothing else.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::AaveLiquidationOpportunity;
+
+// Highest liquidation bonus Aave v3 configures for any mainnet reserve
+pub const PROTOCOL_MAX_BONUS_BPS: u16 = 1_000;
+
+// Finders with fewer earlier broadcasts than this count as brand-new
+pub const NEW_FINDER_MIN_HISTORY: u64 = 3;
+
+// Tokens known to carry a transfer-fee switch or blacklist, flagged without asking the bridge
+const KNOWN_FEE_ON_TRANSFER_TOKENS: [&str; 2] = [
+    "0xdac17f958d2ee523a2206206994597c13d831ec7", // USDT
+    "0x45804880de22913dafe09f4980848ece6ecbaf78", // PAXG
+];
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskFlag {
+    // Victim has contract code: possibly built to make liquidations fail or cost more
+    ContractVictim,
+    // Repay asset or any seized collateral has transfer fees or blacklisting
+    FeeOnTransferAsset,
+    // Some leg's advertised bonus sits exactly at the protocol maximum
+    MaxBonus,
+    // Finder has little or no history with us
+    NewFinder,
+}
+
+// Declared from most to least permissive; the derived Ord makes the strictest the max
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskAction {
+    #[default]
+    Ignore,
+    RequireIndependentValidation,
+    RequireManualApproval,
+    Reject,
+}
+
+// Answer to a ProbeRisk lookup sent to the bridge
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RiskProbe {
+    pub is_contract: bool,
+    pub fee_on_transfer_assets: Vec<String>,
+}
+
+pub struct RiskContext<'a> {
+    pub opportunity: &'a AaveLiquidationOpportunity,
+    // Our own bridge's finds have no finder history to judge
+    pub local_find: bool,
+    pub finder_history: u64,
+    pub probe: Option<&'a RiskProbe>,
+}
+
+type RiskRule = fn(&RiskContext) -> bool;
+
+const RISK_RULES: [(RiskFlag, RiskRule); 4] = [
+    (RiskFlag::ContractVictim, |ctx| ctx.probe.is_some_and(|probe| probe.is_contract)),
+    (RiskFlag::FeeOnTransferAsset, |ctx| {
+        let seized = ctx.opportunity.seize_legs().into_iter().map(|leg| leg.asset);
+        std::iter::once(ctx.opportunity.repay_asset.clone()).chain(seized).any(|asset| {
+            KNOWN_FEE_ON_TRANSFER_TOKENS.iter().any(|token| asset.eq_ignore_ascii_case(token))
+                || ctx.probe.is_some_and(|probe| probe.fee_on_transfer_assets.iter().any(|fee| fee.eq_ignore_ascii_case(&asset)))
+        })
+    }),
+    (RiskFlag::MaxBonus, |ctx| ctx.opportunity.seize_legs().iter().any(|leg| leg.bonus_bps == PROTOCOL_MAX_BONUS_BPS)),
+    (RiskFlag::NewFinder, |ctx| !ctx.local_find && ctx.finder_history < NEW_FINDER_MIN_HISTORY),
+];
+
+// Flags that can only be raised with a bridge lookup
+pub const PROBED_FLAGS: [RiskFlag; 2] = [RiskFlag::ContractVictim, RiskFlag::FeeOnTransferAsset];
+
+pub fn compute_risk_flags(ctx: &RiskContext) -> BTreeSet<RiskFlag> {
+    RISK_RULES.iter()
+        .filter(|(_, rule)| rule(ctx))
+        .map(|(flag, _)| *flag)
+        .collect()
+}
+
+// The strictest configured action among `flags`, with the flag that calls for it. Once the
+// operator has approved, flags asking for manual approval no longer count.
+pub fn strictest_action(flags: &BTreeSet<RiskFlag>, actions: &BTreeMap<RiskFlag, RiskAction>, approved: bool) -> (RiskAction, Option<RiskFlag>) {
+    flags.iter()
+        .map(|flag| match actions.get(flag).copied().unwrap_or_default() {
+            RiskAction::RequireManualApproval if approved => (RiskAction::Ignore, *flag),
+            action => (action, *flag),
+        })
+        .filter(|(action, _)| *action != RiskAction::Ignore)
+        .max_by_key(|(action, _)| *action)
+        .map_or((RiskAction::Ignore, None), |(action, flag)| (action, Some(flag)))
+}
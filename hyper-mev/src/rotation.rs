@@ -0,0 +1,203 @@
+// SIGNING-KEY ROTATION
+// Signed messages also carry a countersignature by a signing key the app holds itself. The
+// networking key belongs to the runtime and the PKI, so it can't be replaced from here; this key
+// can. Rotating it sends peers a KeyRotation signed by both the old and the new key, which a peer
+// takes only if the old-key signature checks against the key it holds for us. Everything else it
+// keeps on us (reputation, settlements, peer state) is keyed by node and stays as it was. Messages
+// countersigned by the old key still pass for key_rotation_grace_secs after the swap, so those
+// already on their way aren't lost, and no second rotation starts inside that window.
+
+use crate::{hex_decode, hex_encode};
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SigningKeyPair {
+    // Hex secp256k1 secret; never leaves state
+    secret: String,
+    // Hex compressed public key, as peers hold it
+    pub public_key: String,
+}
+
+// Kept out of logs and debug output
+impl std::fmt::Debug for SigningKeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKeyPair").field("public_key", &self.public_key).finish()
+    }
+}
+
+impl SigningKeyPair {
+    pub fn generate() -> Self {
+        let key = SigningKey::random(&mut rand_core::OsRng);
+        Self {
+            secret: hex_encode(&key.to_bytes()),
+            public_key: hex_encode(key.verifying_key().to_encoded_point(true).as_bytes()),
+        }
+    }
+
+    // Hex signature over `bytes`; None if the stored secret doesn't load
+    pub fn sign(&self, bytes: &[u8]) -> Option<String> {
+        let key = SigningKey::from_slice(&hex_decode(&self.secret)?).ok()?;
+        let signature: Signature = key.sign(bytes);
+        Some(hex_encode(&signature.to_bytes()))
+    }
+}
+
+// Whether `signature` (hex) by the hex public key `public_key` verifies over `bytes`
+pub fn verify_key_signature(public_key: &str, bytes: &[u8], signature: &str) -> bool {
+    let (Some(key), Some(signature)) = (hex_decode(public_key), hex_decode(signature)) else {
+        return false;
+    };
+    let (Ok(key), Ok(signature)) = (VerifyingKey::from_sec1_bytes(&key), Signature::from_slice(&signature)) else {
+        return false;
+    };
+    key.verify(bytes, &signature).is_ok()
+}
+
+// A key replaced by a rotation, still honoured until valid_until (unix seconds)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RetiredKey {
+    pub public_key: String,
+    pub valid_until: u64,
+}
+
+impl RetiredKey {
+    // A rotation stays pending, and blocks the next, until the old key stops being honoured
+    pub fn pending(&self, now: u64) -> bool {
+        now < self.valid_until
+    }
+}
+
+// Keys a countersignature from a peer may be made with: the one we hold for it, and the one it
+// replaced while that is still inside its grace window
+pub fn accepted_keys<'a>(current: &'a str, retired: Option<&'a RetiredKey>, now: u64) -> Vec<&'a str> {
+    let mut keys = vec![current];
+    keys.extend(retired.filter(|retired| retired.pending(now)).map(|retired| retired.public_key.as_str()));
+    keys
+}
+
+// What both keys sign on a rotation
+pub fn rotation_bytes(node: &str, old_key: &str, new_key: &str, rotated_at: u64) -> Vec<u8> {
+    format!("KeyRotation\n{}\n{}\n{}\n{}", node, old_key, new_key, rotated_at).into_bytes()
+}
+
+// A rotation as received, before anything is taken from it
+pub struct RotationClaim<'a> {
+    pub node: &'a str,
+    pub old_key: &'a str,
+    pub new_key: &'a str,
+    pub rotated_at: u64,
+    pub old_signature: &'a str,
+    pub new_signature: &'a str,
+}
+
+// Checks a rotation from `sender` against the key we hold for it (`held`) and the one its last
+// rotation retired. Err names why it is ignored.
+pub fn judge_rotation(
+    sender: &str,
+    held: Option<&str>,
+    retired: Option<&RetiredKey>,
+    claim: &RotationClaim,
+    now: u64,
+) -> Result<(), &'static str> {
+    if claim.node != sender {
+        return Err("NodeMismatch");
+    }
+    let Some(held) = held else {
+        return Err("NoKeyHeld");
+    };
+    if claim.old_key != held {
+        return Err("OldKeyMismatch");
+    }
+    let bytes = rotation_bytes(claim.node, claim.old_key, claim.new_key, claim.rotated_at);
+    if !verify_key_signature(claim.old_key, &bytes, claim.old_signature) {
+        return Err("BadOldKeySignature");
+    }
+    if claim.new_key == claim.old_key || !verify_key_signature(claim.new_key, &bytes, claim.new_signature) {
+        return Err("BadNewKeySignature");
+    }
+    if retired.is_some_and(|retired| retired.pending(now)) {
+        return Err("RotationPending");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_rotation(old: &SigningKeyPair, new: &SigningKeyPair, rotated_at: u64) -> (String, String) {
+        let bytes = rotation_bytes("alice.os", &old.public_key, &new.public_key, rotated_at);
+        (old.sign(&bytes).unwrap(), new.sign(&bytes).unwrap())
+    }
+
+    fn claim<'a>(old: &'a SigningKeyPair, new: &'a SigningKeyPair, signatures: &'a (String, String)) -> RotationClaim<'a> {
+        RotationClaim {
+            node: "alice.os",
+            old_key: &old.public_key,
+            new_key: &new.public_key,
+            rotated_at: 1_000,
+            old_signature: &signatures.0,
+            new_signature: &signatures.1,
+        }
+    }
+
+    #[test]
+    fn signatures_verify_only_under_their_own_key() {
+        let key = SigningKeyPair::generate();
+        let other = SigningKeyPair::generate();
+        let signature = key.sign(b"message").unwrap();
+        assert!(verify_key_signature(&key.public_key, b"message", &signature));
+        assert!(!verify_key_signature(&key.public_key, b"massage", &signature));
+        assert!(!verify_key_signature(&other.public_key, b"message", &signature));
+        assert!(!verify_key_signature(&key.public_key, b"message", "not hex"));
+    }
+
+    #[test]
+    fn rotation_signed_by_the_held_key_and_the_new_one_is_taken() {
+        let (old, new) = (SigningKeyPair::generate(), SigningKeyPair::generate());
+        let signatures = signed_rotation(&old, &new, 1_000);
+        let claim = claim(&old, &new, &signatures);
+        assert_eq!(judge_rotation("alice.os", Some(&old.public_key), None, &claim, 1_000), Ok(()));
+        assert_eq!(judge_rotation("bob.os", Some(&old.public_key), None, &claim, 1_000), Err("NodeMismatch"));
+        assert_eq!(judge_rotation("alice.os", None, None, &claim, 1_000), Err("NoKeyHeld"));
+        assert_eq!(judge_rotation("alice.os", Some(&new.public_key), None, &claim, 1_000), Err("OldKeyMismatch"));
+    }
+
+    #[test]
+    fn rotation_without_a_valid_old_key_signature_is_refused() {
+        let (old, new) = (SigningKeyPair::generate(), SigningKeyPair::generate());
+        // Signed by the new key in place of the old one, as someone holding only the new key would
+        let by_new = signed_rotation(&old, &new, 1_000).1;
+        let forged = (by_new.clone(), by_new);
+        assert_eq!(
+            judge_rotation("alice.os", Some(&old.public_key), None, &claim(&old, &new, &forged), 1_000),
+            Err("BadOldKeySignature")
+        );
+        // Signed for a different time than it claims
+        let stale = signed_rotation(&old, &new, 999);
+        assert_eq!(
+            judge_rotation("alice.os", Some(&old.public_key), None, &claim(&old, &new, &stale), 1_000),
+            Err("BadOldKeySignature")
+        );
+    }
+
+    #[test]
+    fn one_rotation_is_pending_until_the_grace_window_ends() {
+        let (old, new) = (SigningKeyPair::generate(), SigningKeyPair::generate());
+        let signatures = signed_rotation(&old, &new, 1_000);
+        let claim = claim(&old, &new, &signatures);
+        let retired = RetiredKey { public_key: "earlier".to_string(), valid_until: 1_600 };
+        assert_eq!(judge_rotation("alice.os", Some(&old.public_key), Some(&retired), &claim, 1_599), Err("RotationPending"));
+        assert_eq!(judge_rotation("alice.os", Some(&old.public_key), Some(&retired), &claim, 1_600), Ok(()));
+    }
+
+    #[test]
+    fn retired_key_is_accepted_only_inside_its_grace_window() {
+        let retired = RetiredKey { public_key: "old".to_string(), valid_until: 1_600 };
+        assert_eq!(accepted_keys("new", Some(&retired), 1_599), vec!["new", "old"]);
+        assert_eq!(accepted_keys("new", Some(&retired), 1_600), vec!["new"]);
+        assert_eq!(accepted_keys("new", None, 0), vec!["new"]);
+    }
+}
@@ -0,0 +1,259 @@
+// SELF-TEST PIPELINE
+// Drives one synthetic opportunity through every local stage (ingestion checks, the capital
+// provider decision, allocation, an execution by an in-process stub bridge, receipt verification,
+// the proceeds split and a settlement statement) without touching node state: everything lives in
+// locals and is dropped on return. Synthetic ids carry SELF_TEST_PREFIX, which the send and
+// ingestion paths refuse, so a synthetic artifact can never reach a peer or the stored state.
+
+use hyperware_process_lib::eth::U256;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::allocation::{allocate, split_proceeds, verify_allocation, AllocationCandidate, AllocationRecord, ProceedsSplit};
+use crate::{
+    decide_commitment, AaveLiquidationIntent, AaveLiquidationOpportunity, AaveLiquidationReceipt, CapitalUsage,
+    ExecutionStatus, IntentData, NodeConfig, OriginClass, SeizeLeg, SeizedLeg, StatementLine, StrategyConfig,
+};
+
+pub const SELF_TEST_PREFIX: &str = "selftest-";
+
+const SYNTHETIC_ASSET: &str = "0x0000000000000000000000000000000000005e1f";
+const SYNTHETIC_PEER: &str = "selftest-peer.os";
+const SYNTHETIC_REPAY: &str = "1000000000";
+const SYNTHETIC_PROFIT: &str = "1000000000000";
+
+pub fn is_self_test_id(opp_id: &str) -> bool {
+    opp_id.starts_with(SELF_TEST_PREFIX)
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StageResult {
+    pub stage: String,
+    pub passed: bool,
+    pub elapsed_us: u64,
+    pub detail: serde_json::Value,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SelfTestReport {
+    pub opp_id: String,
+    pub passed: bool,
+    // The first stage that failed; later pipeline stages are not run, teardown always is
+    pub failed_stage: Option<String>,
+    pub stages: Vec<StageResult>,
+}
+
+impl SelfTestReport {
+    // Runs a stage unless an earlier one failed
+    pub fn stage<T>(&mut self, name: &str, run: impl FnOnce() -> Result<(T, serde_json::Value), String>) -> Option<T> {
+        if self.failed_stage.is_some() {
+            return None;
+        }
+        self.run_stage(name, run)
+    }
+
+    // Runs a stage regardless, recording its outcome and duration; the first failure is kept
+    pub fn run_stage<T>(&mut self, name: &str, run: impl FnOnce() -> Result<(T, serde_json::Value), String>) -> Option<T> {
+        let started = Instant::now();
+        let outcome = run();
+        let elapsed_us = started.elapsed().as_micros() as u64;
+        let (value, detail, error) = match outcome {
+            Ok((value, detail)) => (Some(value), detail, None),
+            Err(error) => (None, serde_json::Value::Null, Some(error)),
+        };
+        if error.is_some() && self.failed_stage.is_none() {
+            self.failed_stage = Some(name.to_string());
+        }
+        self.stages.push(StageResult {
+            stage: name.to_string(),
+            passed: error.is_none(),
+            elapsed_us,
+            detail,
+            error,
+        });
+        value
+    }
+
+    pub fn finish(mut self) -> Self {
+        self.passed = self.failed_stage.is_none();
+        self
+    }
+}
+
+// The whole pipeline as `me` under `config` and the aave-liquidation `strategy`, at unix time `now`
+pub fn run_pipeline(config: &NodeConfig, strategy: Option<&StrategyConfig>, me: &str, now: u64) -> SelfTestReport {
+    let opp_id = format!("{}{}", SELF_TEST_PREFIX, now);
+    let mut report = SelfTestReport { opp_id: opp_id.clone(), ..Default::default() };
+
+    let opportunity = report.stage("inject_opportunity", || {
+        let opportunity = AaveLiquidationOpportunity {
+            opp_id: opp_id.clone(),
+            victim_address: SYNTHETIC_ASSET.to_string(),
+            repay_asset: SYNTHETIC_ASSET.to_string(),
+            seize_asset: SYNTHETIC_ASSET.to_string(),
+            max_repay_amount: SYNTHETIC_REPAY.to_string(),
+            min_bonus_bps: 500,
+            health_factor: "950000000000000000".to_string(),
+            deadline_block: u64::MAX,
+            estimated_profit_usd: SYNTHETIC_PROFIT.to_string(),
+            seize_assets: vec![SeizeLeg {
+                asset: SYNTHETIC_ASSET.to_string(),
+                expected_amount: SYNTHETIC_REPAY.to_string(),
+                bonus_bps: 500,
+            }],
+            distribution: config.distribution_scheme.clone(),
+        };
+        opportunity.check_seize_legs()?;
+        opportunity.check_health_factor()?;
+        let json = serde_json::to_string(&opportunity).map_err(|e| e.to_string())?;
+        serde_json::from_str::<AaveLiquidationOpportunity>(&json).map_err(|e| format!("Round trip failed: {}", e))?;
+        Ok((opportunity, serde_json::json!({ "bytes": json.len() })))
+    });
+
+    let intents = report.stage("provider_evaluation", || {
+        let opportunity = opportunity.as_ref().ok_or("No opportunity")?;
+        // A synthetic balance covering the whole repay, so only the configured rules can refuse
+        let balance = SYNTHETIC_REPAY.parse::<U256>().unwrap_or(U256::ZERO);
+        // No other commitments and no gas price: the synthetic run is judged on the config alone
+        let decision = decide_commitment(config, opportunity, OriginClass::Bot, strategy, balance, balance, 0, None, None, None);
+        if !decision.commit {
+            let failed: Vec<_> = decision.checks.iter().filter(|check| !check.passed).map(|check| check.rule.clone()).collect();
+            return Err(format!("Our rules would not commit: {}", failed.join(", ")));
+        }
+        let mut intents = Vec::new();
+        for (provider, amount) in [(me.to_string(), decision.amount.clone()), (SYNTHETIC_PEER.to_string(), SYNTHETIC_REPAY.to_string())] {
+            let intent = AaveLiquidationIntent {
+                opp_id: opp_id.clone(),
+                asset: opportunity.repay_asset.clone(),
+                max_amount: amount.clone(),
+                min_bonus_bps: opportunity.min_bonus_bps,
+                expires_block: opportunity.deadline_block,
+                requires_swap: false,
+                simulated: true,
+                fee_bps: 0,
+            };
+            intents.push(IntentData {
+                intent: serde_json::to_string(&intent).map_err(|e| e.to_string())?,
+                submitter_node: provider,
+                max_amount: amount,
+                expires_block: intent.expires_block,
+                received_at: now,
+                fee_bps: 0,
+            });
+        }
+        Ok((intents, serde_json::json!({ "amount": decision.amount, "checks": decision.checks.len() })))
+    });
+
+    let record = report.stage("executor_allocation", || {
+        let opportunity = opportunity.as_ref().ok_or("No opportunity")?;
+        let intents = intents.as_ref().ok_or("No intents")?;
+        let candidates: Vec<AllocationCandidate> = intents.iter().map(|data| data.allocation_candidate()).collect();
+        let allocation = allocate(config.allocation_policy, &opportunity.max_repay_amount, &candidates)?;
+        if allocation.is_empty() {
+            return Err("Allocation funded nothing".to_string());
+        }
+        let lines = allocation.len();
+        let record = AllocationRecord {
+            policy: config.allocation_policy,
+            target_amount: opportunity.max_repay_amount.clone(),
+            candidates,
+            allocation,
+        };
+        Ok((record, serde_json::json!({ "policy": config.allocation_policy, "lines": lines })))
+    });
+
+    let receipt = report.stage("bridge_round_trip", || {
+        let record = record.clone().ok_or("No allocation")?;
+        let receipt = stub_bridge_execute(&opp_id, &record, config)?;
+        let json = serde_json::to_string(&receipt).map_err(|e| e.to_string())?;
+        let receipt: AaveLiquidationReceipt = serde_json::from_str(&json).map_err(|e| format!("Receipt round trip failed: {}", e))?;
+        Ok((receipt, serde_json::json!({ "bridge": "in-process stub", "receipt_bytes": json.len() })))
+    });
+
+    report.stage("verify_receipt", || {
+        let receipt = receipt.as_ref().ok_or("No receipt")?;
+        let record = receipt.allocation.as_ref().ok_or("Receipt declares no allocation")?;
+        let known: Vec<AllocationCandidate> = intents.as_deref().unwrap_or_default().iter().map(|data| data.allocation_candidate()).collect();
+        verify_allocation(record, &known).map_err(|mismatch| format!("{:?}", mismatch))?;
+        if !matches!(receipt.status, ExecutionStatus::Success) {
+            return Err(format!("Stub execution ended {}", receipt.status.name()));
+        }
+        Ok(((), serde_json::json!({ "replayed": true })))
+    });
+
+    let split = report.stage("distribution", || {
+        let receipt = receipt.as_ref().ok_or("No receipt")?;
+        let record = receipt.allocation.as_ref().ok_or("Receipt declares no allocation")?;
+        let split: ProceedsSplit = split_proceeds(&receipt.total_proceeds, &receipt.gas_paid_usdc, config.finder_fee_bps,
+            config.executor_fee_bps, &record.allocation, &config.distribution_scheme)?;
+        let total = split.shares.iter().map(|line| line.share.parse::<u128>().unwrap_or(0)).sum::<u128>()
+            + split.finder_fee.parse::<u128>().unwrap_or(0)
+            + split.executor_fee.parse::<u128>().unwrap_or(0)
+            + split.dust.parse::<u128>().unwrap_or(0);
+        let after_gas = split.gross.parse::<u128>().unwrap_or(0).saturating_sub(split.gas_cost.parse::<u128>().unwrap_or(0));
+        if total != after_gas {
+            return Err(format!("Split sums to {}, proceeds after gas are {}", total, after_gas));
+        }
+        let detail = serde_json::to_value(&split).unwrap_or_default();
+        Ok((split, detail))
+    });
+
+    report.stage("settlement_entry", || {
+        let receipt = receipt.as_ref().ok_or("No receipt")?;
+        split.as_ref().ok_or("No split")?;
+        let lines: Vec<StatementLine> = receipt.used_amounts.iter()
+            .map(|usage| StatementLine {
+                opp_id: opp_id.clone(),
+                block_number: receipt.block_number,
+                tx_hash: receipt.tx_hash.clone(),
+                status: receipt.status.name().to_string(),
+                executor: me.to_string(),
+                provider: usage.node_id.clone(),
+                asset: usage.asset.clone(),
+                amount_used: usage.amount_used.clone(),
+                share_owed: usage.profit_share.clone(),
+            })
+            .collect();
+        let json = serde_json::to_string(&lines).map_err(|e| e.to_string())?;
+        Ok(((), serde_json::json!({ "lines": lines.len(), "bytes": json.len() })))
+    });
+
+    report.finish()
+}
+
+// Stands in for the bridge: checks the collection the way it would, then reports a successful
+// execution that used the whole allocation and split a fixed profit over it
+fn stub_bridge_execute(opp_id: &str, record: &AllocationRecord, config: &NodeConfig) -> Result<AaveLiquidationReceipt, String> {
+    let replayed = allocate(record.policy, &record.target_amount, &record.candidates)?;
+    if replayed != record.allocation {
+        return Err("Stub bridge refused: declared allocation does not replay".to_string());
+    }
+    let split = split_proceeds(SYNTHETIC_PROFIT, "0", 0, 0, &record.allocation, &config.distribution_scheme)?;
+    let used_amounts = record.allocation.iter().zip(&split.shares)
+        .map(|(line, share)| CapitalUsage {
+            node_id: line.provider.clone(),
+            asset: SYNTHETIC_ASSET.to_string(),
+            amount_used: line.amount.clone(),
+            profit_share: share.share.clone(),
+            fee_bps: line.fee_bps,
+        })
+        .collect();
+    Ok(AaveLiquidationReceipt {
+        opp_id: opp_id.to_string(),
+        status: ExecutionStatus::Success,
+        block_number: 0,
+        tx_hash: format!("0x{:064x}", 0),
+        used_amounts,
+        total_proceeds: SYNTHETIC_PROFIT.to_string(),
+        gas_paid_usdc: "0".to_string(),
+        provider_rule: None,
+        allocation: Some(record.clone()),
+        execution_wallet: None,
+        seized: vec![SeizedLeg {
+            asset: SYNTHETIC_ASSET.to_string(),
+            amount: record.target_amount.clone(),
+        }],
+    })
+}
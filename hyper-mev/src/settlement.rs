@@ -0,0 +1,92 @@
+// COUNTERPARTY ONBOARDING
+// An executor that draws on our capital for the first time becomes a counterparty awaiting the
+// operator's approval (payout address checked out of band, terms agreed). What we are owed keeps
+// accruing whatever the state; payouts and netting only include approved counterparties, and a
+// suspended one is also kept away from our capital. Pure bookkeeping: journaling is up to the caller.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum CounterpartyState {
+    // Never drew on our capital
+    #[default]
+    Unknown,
+    PendingApproval,
+    Approved,
+    Suspended,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct CounterpartyRecord {
+    pub state: CounterpartyState,
+    pub changed_at: String,
+    // Operator's reason for the last approval or suspension
+    #[serde(default)]
+    pub reason: Option<String>,
+    // Execution that first used our capital, and how many have since
+    #[serde(default)]
+    pub first_exposure: Option<String>,
+    #[serde(default)]
+    pub executions: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct CounterpartyRegistry {
+    pub records: BTreeMap<String, CounterpartyRecord>,
+}
+
+impl CounterpartyRegistry {
+    pub fn state(&self, peer: &str) -> CounterpartyState {
+        self.records.get(peer).map(|record| record.state).unwrap_or_default()
+    }
+
+    // Payouts and netting are generated for approved counterparties only
+    pub fn settles_with(&self, peer: &str) -> bool {
+        self.state(peer) == CounterpartyState::Approved
+    }
+
+    pub fn is_suspended(&self, peer: &str) -> bool {
+        self.state(peer) == CounterpartyState::Suspended
+    }
+
+    // Records an execution by `peer` that used our capital. Returns true when this made the peer
+    // a counterparty awaiting approval.
+    pub fn note_exposure(&mut self, peer: &str, opp_id: &str, now: String) -> bool {
+        let record = self.records.entry(peer.to_string()).or_default();
+        record.executions += 1;
+        if record.state != CounterpartyState::Unknown {
+            return false;
+        }
+        record.state = CounterpartyState::PendingApproval;
+        record.changed_at = now;
+        record.first_exposure = Some(opp_id.to_string());
+        true
+    }
+
+    // Approves a counterparty, including reinstating a suspended one or approving ahead of any
+    // exposure. Returns the state it left.
+    pub fn approve(&mut self, peer: &str, reason: String, now: String) -> Result<CounterpartyState, String> {
+        self.transition(peer, CounterpartyState::Approved, reason, now)
+    }
+
+    pub fn suspend(&mut self, peer: &str, reason: String, now: String) -> Result<CounterpartyState, String> {
+        self.transition(peer, CounterpartyState::Suspended, reason, now)
+    }
+
+    fn transition(&mut self, peer: &str, to: CounterpartyState, reason: String, now: String) -> Result<CounterpartyState, String> {
+        let record = self.records.entry(peer.to_string()).or_default();
+        let from = record.state;
+        if from == to {
+            return Err(format!("{} is already {:?}", peer, to));
+        }
+        record.state = to;
+        record.changed_at = now;
+        record.reason = Some(reason);
+        Ok(from)
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = (&String, &CounterpartyRecord)> {
+        self.records.iter().filter(|(_, record)| record.state == CounterpartyState::PendingApproval)
+    }
+}
@@ -0,0 +1,287 @@
+// MESSAGE SIGNING
+// Announcements, intents and receipts carry their sender's signature, made with the node's
+// networking key over the message's canonical form plus a nonce. The transport already tells us
+// which node sent a message; the signature binds the claims inside it (the node_id announced, the
+// max_amount offered, the proceeds reported) to that node, so they can be checked again later.
+// Announcements also carry the signer's networking key, which we compare with the PKI's and
+// keep on the peer record. Each signature is countersigned with the app's own signing key, the
+// one rotate_signing_key replaces (see rotation.rs).
+
+use hyperware_app_common::send_rmp;
+use hyperware_process_lib::{
+    last_blob, our,
+    net::{NetAction, NetResponse},
+    Address, LazyLoadBlob, Request,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::rotation::{accepted_keys, verify_key_signature};
+use crate::{hex_decode, hex_encode, HyperMevApp, MevMessage};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct MessageSignature {
+    // Node that signed; must be the node that sent the message
+    pub signer: String,
+    // Hex networking key of the signer, on announcements only
+    #[serde(default)]
+    pub public_key: Option<String>,
+    pub nonce: String,
+    // Hex signature over signing_bytes
+    pub signature: String,
+    // Hex countersignature over the same bytes by the signer's app signing key
+    #[serde(default)]
+    pub key_signature: Option<String>,
+    // Hex app signing key of the signer, on announcements only
+    #[serde(default)]
+    pub signing_key: Option<String>,
+}
+
+// What gets signed: the message without its signature, keys sorted at every depth, with the nonce
+pub fn signing_bytes(message: &serde_json::Value, nonce: &str) -> Vec<u8> {
+    let mut unsigned = message.clone();
+    if let Some(fields) = unsigned.as_object_mut() {
+        fields.remove("signature");
+    }
+    let mut text = String::new();
+    write_canonical(&serde_json::json!({ "message": unsigned, "nonce": nonce }), &mut text);
+    text.into_bytes()
+}
+
+// The signature a message carries, if any. Err when the field is there but isn't one.
+pub fn carried_signature(message: &serde_json::Value) -> Result<Option<MessageSignature>, String> {
+    match &message["signature"] {
+        serde_json::Value::Null => Ok(None),
+        signature => serde_json::from_value(signature.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid message signature: {}", e)),
+    }
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&fields[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+// Signing and checking through the networking module, which holds the node's key
+impl HyperMevApp {
+    // Signs with this node's networking key; net:distro:sys prefixes the bytes with our address
+    pub(crate) async fn sign_bytes(bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let body = rmp_serde::to_vec(&NetAction::Sign)
+            .map_err(|e| format!("Failed to encode sign request: {}", e))?;
+        let request = Request::to(("our", "net", "distro", "sys"))
+            .expects_response(5)
+            .blob(LazyLoadBlob { mime: None, bytes: bytes.to_vec() })
+            .body(body);
+        let _response: NetResponse = send_rmp(request)
+            .await
+            .map_err(|e| format!("Sign request failed: {:?}", e))?;
+        last_blob()
+            .map(|blob| blob.bytes)
+            .ok_or_else(|| "Sign response carried no signature".to_string())
+    }
+
+    // The networking key the PKI lists for `node`, as hex
+    pub(crate) async fn networking_key(node: &str) -> Option<String> {
+        let body = rmp_serde::to_vec(&NetAction::GetPeer(node.to_string())).ok()?;
+        let request = Request::to(("our", "net", "distro", "sys"))
+            .expects_response(5)
+            .body(body);
+        match send_rmp::<NetResponse>(request).await.ok()? {
+            NetResponse::Peer(Some(identity)) => Some(identity.networking_key),
+            _ => None,
+        }
+    }
+
+    // A hex signature by the full address `signer` over `bytes`, as found in a bundle
+    pub(crate) async fn check_bundle_signature(signer: &str, bytes: &[u8], signature: &str) -> Result<bool, String> {
+        let signer = signer.parse::<Address>().map_err(|_| format!("Invalid signer address {}", signer))?;
+        let signature = hex_decode(signature).ok_or("Signature is not valid hex")?;
+        Self::verify_signature(signer, bytes, signature).await
+    }
+
+    // Checks a signature made by `signer` (node and process) over `bytes`
+    pub(crate) async fn verify_signature(signer: Address, bytes: &[u8], signature: Vec<u8>) -> Result<bool, String> {
+        let body = rmp_serde::to_vec(&NetAction::Verify { from: signer, signature })
+            .map_err(|e| format!("Failed to encode verify request: {}", e))?;
+        let request = Request::to(("our", "net", "distro", "sys"))
+            .expects_response(5)
+            .blob(LazyLoadBlob { mime: None, bytes: bytes.to_vec() })
+            .body(body);
+        let response: NetResponse = send_rmp(request)
+            .await
+            .map_err(|e| format!("Verify request failed: {:?}", e))?;
+        match response {
+            NetResponse::Verified(valid) => Ok(valid),
+            _ => Err("Unexpected response to verify request".to_string()),
+        }
+    }
+
+    // sign_bytes for callers that can't await; blocks until the networking module answers
+    pub(crate) fn sign_bytes_now(bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let body = rmp_serde::to_vec(&NetAction::Sign)
+            .map_err(|e| format!("Failed to encode sign request: {}", e))?;
+        Request::to(("our", "net", "distro", "sys"))
+            .blob(LazyLoadBlob { mime: None, bytes: bytes.to_vec() })
+            .body(body)
+            .send_and_await_response(5)
+            .map_err(|e| format!("Sign request failed: {:?}", e))?
+            .map_err(|e| format!("Sign request failed: {:?}", e))?;
+        last_blob()
+            .map(|blob| blob.bytes)
+            .ok_or_else(|| "Sign response carried no signature".to_string())
+    }
+
+    // A message ready to send: numbered with our next nonce, then signed over its canonical form and
+    // countersigned with our app signing key. Announcements also carry both our keys. If signing
+    // fails it goes out unsigned, as older nodes send it, but still numbered.
+    pub(crate) fn sign_message(&self, message: &MevMessage) -> serde_json::Value {
+        let mut json = Self::message_json(message);
+        // Never below the clock, so the sequence keeps rising even if our state was lost
+        let nonce = (self.outbound_nonce.get() + 1).max(Self::current_timestamp_ms());
+        self.outbound_nonce.set(nonce);
+        json["nonce"] = serde_json::json!(nonce);
+        let nonce = nonce.to_string();
+        let bytes = signing_bytes(&json, &nonce);
+        match Self::sign_bytes_now(&bytes) {
+            Ok(signature) => {
+                let announcement = matches!(message, MevMessage::NodeAnnouncement { .. });
+                json["signature"] = serde_json::to_value(MessageSignature {
+                    signer: our().node,
+                    public_key: self.our_networking_key.clone().filter(|_| announcement),
+                    nonce,
+                    signature: hex_encode(&signature),
+                    key_signature: self.signing_key.as_ref().and_then(|key| key.sign(&bytes)),
+                    signing_key: self.signing_key.as_ref().filter(|_| announcement).map(|key| key.public_key.clone()),
+                }).unwrap_or_default();
+            }
+            Err(e) => println!("   {} goes out unsigned: {}", message.kind(), e),
+        }
+        json
+    }
+
+    // The signature on a message from `sender` must be the sender's own and verify over the
+    // message, and once the sender has an app signing key its countersignature must verify too.
+    // An unsigned message passes only while require_signed_messages is off and the sender has
+    // never announced a key. Returns whether the message was signed.
+    pub(crate) async fn check_message_signature(&self, sender: &Address, message_json: &str) -> Result<bool, String> {
+        let message: serde_json::Value = serde_json::from_str(message_json)
+            .map_err(|e| format!("Invalid message: {}", e))?;
+        let Some(signature) = carried_signature(&message)? else {
+            let keyed = self.known_peers.get(&sender.node).is_some_and(|peer| peer.public_key.is_some());
+            if keyed || self.node_config.require_signed_messages {
+                return Err(serde_json::json!({
+                    "error": "UnsignedMessage",
+                    "reason": if keyed { "Sender has announced a signing key" } else { "Signed messages are required" },
+                }).to_string());
+            }
+            return Ok(false);
+        };
+        if signature.signer != sender.node {
+            return Err(serde_json::json!({
+                "error": "SignerMismatch",
+                "signer": signature.signer,
+                "sender": sender.node,
+            }).to_string());
+        }
+        let raw = hex_decode(&signature.signature).ok_or("Signature is not valid hex")?;
+        let bytes = signing_bytes(&message, &signature.nonce);
+        if !Self::verify_signature(sender.clone(), &bytes, raw).await? {
+            return Err(serde_json::json!({
+                "error": "BadSignature",
+                "signer": signature.signer,
+            }).to_string());
+        }
+        // Countersigned by the app key we hold for the sender, or its retired one within the grace
+        // window; by the key it carries if it is announcing one for the first time. Rotations are
+        // checked against both keys by their handler instead.
+        let now = Self::current_timestamp().parse::<u64>().unwrap_or(0);
+        let keys = match self.known_peers.get(&sender.node).and_then(|peer| peer.signing_key.as_deref().map(|key| (key, peer))) {
+            Some((current, peer)) => accepted_keys(current, peer.retired_signing_key.as_ref(), now),
+            None => signature.signing_key.as_deref().into_iter().collect(),
+        };
+        let countersigned = signature.key_signature.as_deref()
+            .is_some_and(|key_signature| keys.iter().any(|key| verify_key_signature(key, &bytes, key_signature)));
+        if !keys.is_empty() && !countersigned && message["type"] != "KeyRotation" {
+            return Err(serde_json::json!({
+                "error": "BadKeySignature",
+                "signer": signature.signer,
+            }).to_string());
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(value: serde_json::Value) -> String {
+        let mut text = String::new();
+        write_canonical(&value, &mut text);
+        text
+    }
+
+    #[test]
+    fn canonical_form_sorts_keys_at_every_depth() {
+        let value = serde_json::json!({ "b": [{ "z": 1, "a": "x" }, 2], "a": { "d": null, "c": true } });
+        assert_eq!(canonical(value), r#"{"a":{"c":true,"d":null},"b":[{"a":"x","z":1},2]}"#);
+        // Array order is meaning, not formatting
+        assert_ne!(canonical(serde_json::json!([1, 2])), canonical(serde_json::json!([2, 1])));
+        assert_eq!(canonical(serde_json::json!({ "k\"ey": "va\nl" })), r#"{"k\"ey":"va\nl"}"#);
+    }
+
+    #[test]
+    fn signed_bytes_ignore_formatting_and_the_signature_itself() {
+        let sent: serde_json::Value = serde_json::from_str(r#"{"type":"IntentSubmission","max_amount":"100","opp_id":"opp"}"#).unwrap();
+        let received: serde_json::Value = serde_json::from_str(r#"{
+            "opp_id": "opp",
+            "max_amount": "100",
+            "type": "IntentSubmission",
+            "signature": { "signer": "alice.os", "nonce": "7", "signature": "00" }
+        }"#).unwrap();
+        assert_eq!(signing_bytes(&sent, "7"), signing_bytes(&received, "7"));
+    }
+
+    #[test]
+    fn signed_bytes_change_with_the_claims_and_the_nonce() {
+        let message = serde_json::json!({ "type": "IntentSubmission", "max_amount": "100" });
+        let inflated = serde_json::json!({ "type": "IntentSubmission", "max_amount": "1000" });
+        assert_ne!(signing_bytes(&message, "7"), signing_bytes(&inflated, "7"));
+        assert_ne!(signing_bytes(&message, "7"), signing_bytes(&message, "8"));
+    }
+
+    #[test]
+    fn carried_signature_is_optional_but_must_parse_when_present() {
+        assert_eq!(carried_signature(&serde_json::json!({ "type": "NodeAnnouncement" })), Ok(None));
+        let signed = serde_json::json!({ "signature": { "signer": "alice.os", "nonce": "7", "signature": "ab" } });
+        let signature = carried_signature(&signed).unwrap().unwrap();
+        assert_eq!(signature.signer, "alice.os");
+        assert_eq!(signature.public_key, None);
+        assert!(carried_signature(&serde_json::json!({ "signature": "ab" })).is_err());
+    }
+}
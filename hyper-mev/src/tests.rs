@@ -0,0 +1,1020 @@
+// Unit tests for the pure decision helpers of the app, and for state bookkeeping that never
+// reaches the runtime; anything touching the network is exercised by the self-test instead
+
+use super::*;
+use crate::executions::PENDING_EXECUTION_TIMEOUT_SECS;
+use crate::nonce::judge_nonce;
+use crate::outbound::{OutboundPriority, QueuedSend};
+
+// Times we enter an opportunity while its estimate swings between `low` and `high`, `swings`
+// times, around a threshold of 1000. Each entry after the first follows a withdrawal.
+fn participation_cycles(low: u64, high: u64, swings: usize, entry_margin_bps: u16, exit_margin_bps: u16) -> usize {
+    let threshold = U256::from(1000u64);
+    let mut participating = false;
+    let mut cycles = 0;
+    for round in 0..swings * 2 {
+        let profit = U256::from(if round % 2 == 0 { high } else { low });
+        let clears = clears_profit_bar(profit, threshold, entry_margin_bps, exit_margin_bps, participating);
+        if clears && !participating {
+            cycles += 1;
+        }
+        participating = clears;
+    }
+    cycles
+}
+
+#[test]
+fn oscillating_estimate_churns_only_across_the_margins() {
+    // Inside the band the margins open, we enter once however often the estimate swings
+    assert_eq!(participation_cycles(950, 1150, 10, 1000, 1000), 1);
+    assert_eq!(participation_cycles(950, 1150, 100, 1000, 1000), 1);
+    // Without margins, every swing is a submit and a withdrawal
+    assert_eq!(participation_cycles(950, 1150, 10, 0, 0), 10);
+    // Swings past the exit bar still take us out each time
+    assert_eq!(participation_cycles(850, 1150, 10, 1000, 1000), 10);
+    // Swings that never reach the entry bar never get us in
+    assert_eq!(participation_cycles(950, 1050, 10, 1000, 1000), 0);
+}
+
+fn intent_from(submitter: &str, max_amount: u64, received_at: u64) -> IntentData {
+    IntentData {
+        intent: "{}".to_string(),
+        submitter_node: submitter.to_string(),
+        max_amount: max_amount.to_string(),
+        expires_block: 100,
+        received_at,
+        fee_bps: 0,
+    }
+}
+
+#[test]
+fn resubmitted_intent_replaces_the_one_held() {
+    let mut intents = Vec::new();
+    assert_eq!(merge_intent(&mut intents, intent_from("alice.os", 100, 10)), IntentMerge::Added);
+    assert_eq!(
+        merge_intent(&mut intents, intent_from("alice.os", 50, 11)),
+        IntentMerge::Replaced { previous_amount: "100".to_string() },
+    );
+    assert_eq!(intents.len(), 1);
+    assert_eq!(intents[0].max_amount, "50");
+}
+
+#[test]
+fn older_intent_does_not_replace_a_newer_one() {
+    let mut intents = vec![intent_from("alice.os", 50, 20)];
+    assert_eq!(merge_intent(&mut intents, intent_from("alice.os", 100, 10)), IntentMerge::Stale);
+    assert_eq!(intents.len(), 1);
+    assert_eq!(intents[0].max_amount, "50");
+}
+
+#[test]
+fn intents_from_different_providers_are_kept_apart() {
+    let mut intents = Vec::new();
+    merge_intent(&mut intents, intent_from("alice.os", 100, 10));
+    merge_intent(&mut intents, intent_from("bob.os", 70, 11));
+    assert_eq!(intents.len(), 2);
+}
+
+#[test]
+fn nonces_must_increase_per_sender_and_kind() {
+    assert_eq!(judge_nonce(Some(1), None, false), Ok(Some(1)));
+    assert_eq!(judge_nonce(Some(5), Some(4), false), Ok(Some(5)));
+    assert_eq!(judge_nonce(Some(4), Some(4), false), Err("Replay"));
+    assert_eq!(judge_nonce(Some(3), Some(4), false), Err("Replay"));
+    // Dropping the nonce after having sent one is a replay too
+    assert_eq!(judge_nonce(None, Some(4), false), Err("Replay"));
+}
+
+#[test]
+fn nonceless_messages_pass_only_while_signing_is_optional() {
+    assert_eq!(judge_nonce(None, None, false), Ok(None));
+    assert_eq!(judge_nonce(None, None, true), Err("MissingNonce"));
+}
+
+#[test]
+fn replayed_intent_is_filed_once() {
+    let mut app = app_with_opportunity(true, false, false);
+    app.submitted_intents.clear();
+    // Dispatch as executor would reach the runtime; filing the intent doesn't
+    app.node_config.executor_enabled = false;
+    app.known_peers.insert("alice.os".to_string(), announced_peer("alice.os", vec![NodeRole::CapitalProvider]));
+    let submission = MevMessage::IntentSubmission {
+        opp_id: "opp".to_string(),
+        strategy_id: "aave-liquidation".to_string(),
+        intent: intent_in(USDC, 3000, false).intent,
+        max_amount: "3000".to_string(),
+        min_bonus_bps: 500,
+        expires_block: 100,
+        fee_bps: 0,
+        signature: None,
+    };
+    let mut json = serde_json::to_value(&submission).unwrap();
+    json["nonce"] = serde_json::json!(7);
+    let json = json.to_string();
+    
+    app.accept_intent_submission("alice.os".to_string(), &json, true).unwrap();
+    let error = app.accept_intent_submission("alice.os".to_string(), &json, true).unwrap_err();
+    assert!(error.contains("Replay"), "{}", error);
+    assert_eq!(app.submitted_intents["opp"].len(), 1);
+    assert_eq!(app.peer_nonces["alice.os"]["IntentSubmission"], 7);
+}
+
+fn submission_for(opp_id: &str, max_amount: u64) -> String {
+    let submission = MevMessage::IntentSubmission {
+        opp_id: opp_id.to_string(),
+        strategy_id: "aave-liquidation".to_string(),
+        intent: intent_in(USDC, max_amount, false).intent,
+        max_amount: max_amount.to_string(),
+        min_bonus_bps: 500,
+        expires_block: 100,
+        fee_bps: 0,
+        signature: None,
+    };
+    serde_json::to_string(&submission).unwrap()
+}
+
+#[test]
+fn each_opportunity_goes_to_the_bridge_once() {
+    let mut app = HyperMevApp::default();
+    app.node_config.node_id = "executor.os".to_string();
+    app.node_config.min_profit_threshold_usd = "0".to_string();
+    connect_bridge(&mut app);
+    for opp_id in ["opp", "opp-2"] {
+        let opportunity = AaveLiquidationOpportunity { opp_id: opp_id.to_string(), ..usdc_opportunity() };
+        let data = OpportunityData::new(serde_json::to_string(&opportunity).unwrap(), "aave-liquidation".to_string(),
+            "finder.os".to_string(), Vec::new());
+        app.active_opportunities.insert(opp_id.to_string(), data);
+    }
+    for provider in ["alice.os", "bob.os", "carol.os", "dave.os"] {
+        app.known_peers.insert(provider.to_string(), announced_peer(provider, vec![NodeRole::CapitalProvider]));
+    }
+    
+    // What receive_intent_submission hands the bridge, recorded instead of sent
+    let mut sent = Vec::new();
+    for (provider, opp_id) in [("alice.os", "opp"), ("bob.os", "opp"), ("dave.os", "opp-2"), ("carol.os", "opp")] {
+        for dispatch in app.accept_intent_submission(provider.to_string(), &submission_for(opp_id, 3000), true).unwrap() {
+            sent.extend(app.intent_collection_for(&dispatch));
+        }
+    }
+    
+    assert_eq!(sent.len(), 2);
+    let recipients: Vec<(&str, Vec<&str>)> = sent.iter()
+        .map(|collection| {
+            assert_eq!(collection["type"], "IntentCollection");
+            let submitters = collection["intents"].as_array().unwrap().iter()
+                .map(|intent| intent["submitter_node"].as_str().unwrap())
+                .collect();
+            (collection["opp_id"].as_str().unwrap(), submitters)
+        })
+        .collect();
+    // Each went out with the intents it held at its first dispatch; later ones are filed only
+    assert_eq!(recipients, vec![("opp", vec!["alice.os"]), ("opp-2", vec!["dave.os"])]);
+    assert_eq!(app.submitted_intents["opp"].len(), 3);
+    assert!(["opp", "opp-2"].iter().all(|opp_id| app.pending_executions.contains_key(*opp_id)
+        && app.active_opportunities[*opp_id].status == OpportunityStatus::Executing));
+}
+
+#[test]
+fn succession_waits_a_failover_window_per_holder_passed_over() {
+    let window = 60;
+    assert!(!succession_due(59, window, 1, false));
+    assert!(succession_due(60, window, 1, false));
+    // Skipping straight past the primary takes two windows, not one
+    assert!(!succession_due(60, window, 2, false));
+    assert!(succession_due(120, window, 2, false));
+    // A holder away for maintenance is passed over at once
+    assert!(succession_due(0, window, 1, true));
+    assert!(!succession_due(0, window, 2, true));
+}
+
+#[test]
+fn block_windows_go_by_the_head_once_one_is_known() {
+    // Slow blocks: 120s is ten blocks by the clock but only three by the head
+    assert_eq!(blocks_since(Some(103), Some(100), 1120, 1000), 3);
+    assert_eq!(blocks_since(None, Some(100), 1120, 1000), 10);
+    // Arrived before the bridge reported a head
+    assert_eq!(blocks_since(Some(103), None, 1120, 1000), 10);
+    assert_eq!(blocks_since(Some(99), Some(100), 1120, 1000), 0);
+}
+
+#[test]
+fn provider_requirement_is_the_strictest_threshold_reached() {
+    let mut config = NodeConfig::default();
+    config.min_distinct_providers_above_usd = vec![
+        ProviderRequirement { threshold_usd: "1000".to_string(), min_providers: 2 },
+        ProviderRequirement { threshold_usd: "10000".to_string(), min_providers: 3 },
+        ProviderRequirement { threshold_usd: "not a number".to_string(), min_providers: 9 },
+    ];
+    assert!(config.provider_requirement_for(U256::from(999u64)).is_none());
+    assert_eq!(config.provider_requirement_for(U256::from(1000u64)).map(|rule| rule.min_providers), Some(2));
+    assert_eq!(config.provider_requirement_for(U256::from(50_000u64)).map(|rule| rule.min_providers), Some(3));
+    assert!(NodeConfig::default().provider_requirement_for(U256::from(50_000u64)).is_none());
+}
+
+#[test]
+fn deadline_ends_the_provider_wait_before_the_safety_margin() {
+    let deadline = 100;
+    assert!(!provider_wait_over(2, 10, Some(90), deadline));
+    assert!(provider_wait_over(10, 10, Some(90), deadline));
+    // The last block dispatch may still go out at forces the decision, window or not
+    assert!(provider_wait_over(2, 10, Some(deadline - DISPATCH_SAFETY_MARGIN_BLOCKS), deadline));
+    assert!(!provider_wait_over(2, 10, None, deadline));
+}
+
+#[test]
+fn peer_messages_round_trip_through_parse() {
+    let messages = [
+        MevMessage::ExecutorSuccession { opp_id: "opp".to_string(), holder: "carol.os".to_string(), holder_index: 2 },
+        MevMessage::IntentRevocation { opp_id: "opp".to_string() },
+        MevMessage::SyncRequest { max_opportunities: 50 },
+        MevMessage::MirrorControl { action: MirrorAction::ResendRequest, from_seq: Some(3), to_seq: Some(7) },
+        MevMessage::MirrorEntries { entries: Vec::new(), restore: true, last: true },
+        MevMessage::Ping { sent_at: "1700000000".to_string(), reply: true },
+        MevMessage::ProviderShortfall { opp_id: "opp".to_string(), distinct_providers: 1, min_providers: 3 },
+        MevMessage::IntentAmendment { opp_id: "opp".to_string(), new_max_amount: "0".to_string() },
+        MevMessage::BackfillRequest { finder: "alice.os".to_string(), from_seq: 4, to_seq: 9 },
+        MevMessage::RecordRequest { request_id: "view-1".to_string(), opp_id: "opp".to_string(), sections: ALL_SECTIONS.to_vec() },
+    ];
+    for message in messages {
+        let json = serde_json::to_string(&message).unwrap();
+        let parsed = MevMessage::parse(&json, message.kind()).unwrap();
+        assert_eq!(parsed.kind(), message.kind());
+        assert_eq!(parsed.handler(), message.handler());
+    }
+}
+
+#[test]
+fn parse_refuses_a_message_sent_to_the_wrong_handler() {
+    let json = serde_json::json!({ "type": "IntentRevocation", "opp_id": "opp" }).to_string();
+    let error = MevMessage::parse(&json, "SyncRequest").unwrap_err();
+    assert!(error.contains("MalformedMessage"));
+}
+
+#[test]
+fn cancellations_from_older_peers_parse_under_the_old_tag() {
+    let json = serde_json::json!({ "type": "OpportunityCancelled", "cancellation": "{}" }).to_string();
+    let Ok(MevMessage::OpportunityCancellation { cancellation, signature }) = MevMessage::parse(&json, "OpportunityCancellation") else {
+        panic!("old cancellation tag did not parse");
+    };
+    assert_eq!(cancellation, "{}");
+    assert_eq!(signature, None);
+}
+
+#[test]
+fn untyped_era_messages_parse_or_come_back_malformed() {
+    // Older finders nest the opportunity inline; it reaches the handler as its JSON text
+    let update = serde_json::json!({ "type": "OpportunityUpdate", "opp_id": "opp", "opportunity": { "opp_id": "opp" } }).to_string();
+    let Ok(MevMessage::OpportunityUpdate { opportunity, strategy_id, .. }) = MevMessage::parse(&update, "OpportunityUpdate") else {
+        panic!("inline opportunity update did not parse");
+    };
+    assert_eq!(opportunity, r#"{"opp_id":"opp"}"#);
+    assert_eq!(strategy_id, "");
+    let expiry = serde_json::json!({ "type": "OpportunityExpired", "opp_id": "opp" }).to_string();
+    let Ok(MevMessage::OpportunityExpired { reason, best_coverage_reached, .. }) = MevMessage::parse(&expiry, "OpportunityExpired") else {
+        panic!("bare expiry did not parse");
+    };
+    assert_eq!((reason, best_coverage_reached), (None, None));
+    
+    // What the handlers used to read as "" or 0 is now refused
+    for (json, expected) in [
+        (r#"{"type":"IntentWindowExtended","opp_id":"opp"}"#, "IntentWindowExtended"),
+        (r#"{"type":"PositionUpdate","borrower":"0xabc","health_factor":"0.9"}"#, "PositionUpdate"),
+        (r#"{"type":"ExecutionClaim","opp_id":7}"#, "ExecutionClaim"),
+        (r#"{"monitoring":[]}"#, "MonitoringCoverage"),
+        (r#"{"type":"Ping","sent_at":"1","reply":true}"#, "RecordResponse"),
+    ] {
+        assert!(MevMessage::parse(json, expected).unwrap_err().contains("MalformedMessage"), "{}", json);
+    }
+}
+
+#[test]
+fn outbound_priorities_follow_the_typed_handlers() {
+    let opp_id = || "opp".to_string();
+    let critical = [
+        MevMessage::OpportunityExpired { opp_id: opp_id(), reason: None, best_coverage_reached: None },
+        MevMessage::IntentAmendment { opp_id: opp_id(), new_max_amount: "0".to_string() },
+        MevMessage::IntentAmendmentRejection { opp_id: opp_id(), reason: None, committed_amount: None },
+        MevMessage::ExecutionClaim { opp_id: opp_id() },
+        MevMessage::ExecutorSuccession { opp_id: opp_id(), holder: "bob.os".to_string(), holder_index: 1 },
+        MevMessage::OpportunityCancellation { cancellation: "{}".to_string(), signature: None },
+    ];
+    for message in &critical {
+        assert_eq!(OutboundPriority::for_handler(message.handler()), OutboundPriority::Critical, "{}", message.kind());
+    }
+    let low = MevMessage::MonitoringCoverage { monitoring: Vec::new(), subscribed: Vec::new(), block: 0 };
+    assert_eq!(OutboundPriority::for_handler(low.handler()), OutboundPriority::Low);
+    let bulk = MevMessage::SyncResponse { opportunities: Vec::new(), intents: Vec::new(), truncated: false };
+    assert_eq!(OutboundPriority::for_handler(bulk.handler()), OutboundPriority::Bulk);
+    let ping = MevMessage::Ping { sent_at: "0".to_string(), reply: false };
+    assert_eq!(OutboundPriority::for_handler(ping.handler()), OutboundPriority::Normal);
+}
+
+fn queued(handler: &str, opp_id: &str, at: u64) -> QueuedSend {
+    QueuedSend {
+        handler: handler.to_string(),
+        message: serde_json::json!({ "opp_id": opp_id }),
+        priority: OutboundPriority::for_handler(handler),
+        enqueued_at: at,
+    }
+}
+
+#[test]
+fn full_queue_sheds_everything_but_receipts() {
+    let mut queues = OutboundQueues::default();
+    let capacity = outbound::PEER_QUEUE_CAPACITY;
+    // A receipt first, then the queue filled to its cap with broadcasts and announcements
+    queues.enqueue("bob.os", queued("ReceiveExecutionReceipt", "receipt-0", 0));
+    for i in 1..capacity {
+        let handler = if i % 2 == 0 { "ReceiveOpportunityBroadcast" } else { "ReceiveNodeAnnouncement" };
+        queues.enqueue("bob.os", queued(handler, &format!("filler-{}", i), i as u64));
+    }
+    assert!(queues.dropped.is_empty());
+    // Ten more receipts overflow it, as do ten pings
+    for i in 1..=10 {
+        queues.enqueue("bob.os", queued("ReceiveExecutionReceipt", &format!("receipt-{}", i), 100 + i));
+        queues.enqueue("bob.os", queued("ReceivePing", &format!("ping-{}", i), 100 + i));
+    }
+    
+    // Twenty dropped, all of them the oldest announcements
+    assert_eq!(queues.dropped.len(), 20);
+    assert!(queues.dropped.iter().all(|drop| drop.priority == OutboundPriority::Low && drop.reason == "queue_full"));
+    assert_eq!(queues.dropped[0].opp_id.as_deref(), Some("filler-1"));
+    let queue = &queues.peers["bob.os"];
+    assert_eq!(queue.sends.len(), capacity);
+    assert_eq!(queue.dropped, 20);
+    
+    // Drained in the order drain_peer_queue takes them, every receipt goes out first
+    let mut queue = queues.peers.remove("bob.os").unwrap();
+    let mut drained = Vec::new();
+    while let Some(index) = queue.next_index() {
+        drained.push(queue.sends.remove(index).unwrap());
+    }
+    let receipts: Vec<&str> = drained[..11].iter()
+        .map(|send| {
+            assert_eq!(send.handler, "ReceiveExecutionReceipt");
+            send.message["opp_id"].as_str().unwrap()
+        })
+        .collect();
+    let expected: Vec<String> = (0..=10).map(|i| format!("receipt-{}", i)).collect();
+    assert_eq!(receipts, expected);
+    assert!(drained[11..].iter().all(|send| send.handler != "ReceiveExecutionReceipt"));
+    assert_eq!(drained.iter().filter(|send| send.handler == "ReceivePing").count(), 10);
+}
+
+// One maintenance tick the way drain_peer_queue runs it, with the stalled peer silent and never
+// answering; returns what was delivered
+fn drain_tick(queues: &mut OutboundQueues, now: u64, stalled: &str, budget: &mut usize) -> Vec<(String, String)> {
+    let mut delivered = Vec::new();
+    let peers: Vec<String> = queues.peers.keys().cloned().collect();
+    for peer in peers {
+        let silent = peer == stalled;
+        let take = |budget: &mut usize| if *budget > 0 { *budget -= 1; true } else { false };
+        while let Some(send) = queues.next_send(&peer, now, silent, |_| take(budget)) {
+            let answered = !silent;
+            let sent = (peer.clone(), send.message["opp_id"].as_str().unwrap_or_default().to_string());
+            if !queues.settle(&peer, send, answered, now) || silent {
+                break;
+            }
+            delivered.push(sent);
+        }
+    }
+    delivered
+}
+
+#[test]
+fn stalled_peer_does_not_hold_up_the_others() {
+    let mut queues = OutboundQueues::default();
+    for i in 0..5 {
+        queues.enqueue("alice.os", queued("ReceiveExecutionReceipt", &format!("to-alice-{}", i), 1000));
+    }
+    for i in 0..3 {
+        queues.enqueue("bob.os", queued("ReceiveIntentSubmission", &format!("to-bob-{}", i), 1000));
+    }
+    
+    // Alice is drained first and stalls, yet all of Bob's sends go out on the same tick
+    let mut budget = 10;
+    let delivered = drain_tick(&mut queues, 1000, "alice.os", &mut budget);
+    let expected: Vec<(String, String)> = (0..3).map(|i| ("bob.os".to_string(), format!("to-bob-{}", i))).collect();
+    assert_eq!(delivered, expected);
+    // Alice cost one attempt, which went back on her queue, and she is backed off
+    let alice = &queues.peers["alice.os"];
+    assert_eq!(alice.sends.len(), 5);
+    assert_eq!(alice.sends[0].message["opp_id"], "to-alice-0");
+    assert!(!alice.ready(1000));
+    assert_eq!(budget, 6);
+    
+    // Inside her backoff, a new send to Bob still goes out and Alice is not tried
+    queues.enqueue("bob.os", queued("ReceiveIntentSubmission", "to-bob-3", 1001));
+    let delivered = drain_tick(&mut queues, 1001, "alice.os", &mut budget);
+    assert_eq!(delivered, vec![("bob.os".to_string(), "to-bob-3".to_string())]);
+    assert_eq!(queues.peers["alice.os"].sends.len(), 5);
+    assert_eq!(queues.peers["bob.os"].delivered, 4);
+}
+
+#[test]
+fn mirror_control_leaves_the_range_out_unless_it_is_set() {
+    let offer = serde_json::to_value(MevMessage::MirrorControl { action: MirrorAction::Offer, from_seq: None, to_seq: None }).unwrap();
+    assert_eq!(offer, serde_json::json!({ "type": "MirrorControl", "action": "Offer" }));
+}
+
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const DAI: &str = "0x6B175474E89094C44Da98b954EedeAC495271d0F";
+
+fn usdc_opportunity() -> AaveLiquidationOpportunity {
+    serde_json::from_value(serde_json::json!({
+        "opp_id": "opp",
+        "victim_address": "0x0000000000000000000000000000000000000001",
+        "repay_asset": USDC,
+        "seize_asset": DAI,
+        "max_repay_amount": "10000",
+        "min_bonus_bps": 500,
+        "health_factor": "900000000000000000",
+        "deadline_block": 100,
+        "estimated_profit_usd": "10",
+    })).unwrap()
+}
+
+fn intent_in(asset: &str, max_amount: u64, requires_swap: bool) -> IntentData {
+    let intent = serde_json::json!({
+        "opp_id": "opp",
+        "asset": asset,
+        "max_amount": max_amount.to_string(),
+        "min_bonus_bps": 500,
+        "expires_block": 100,
+        "requires_swap": requires_swap,
+    });
+    IntentData { intent: intent.to_string(), ..intent_from("alice.os", max_amount, 10) }
+}
+
+#[test]
+fn coverage_counts_only_intents_in_the_repay_asset() {
+    let opportunity = usdc_opportunity();
+    // Same asset in a different case still counts; DAI against a USDC repay does not
+    let intents = [intent_in(USDC, 3000, false), intent_in(&USDC.to_lowercase(), 1000, false), intent_in(DAI, 5000, false)];
+    assert_eq!(intent_coverage(&opportunity, &intents), U256::from(4000u64));
+    // The DAI intent counts once it opts into being swapped
+    let intents = [intent_in(USDC, 3000, false), intent_in(DAI, 5000, true)];
+    assert_eq!(intent_coverage(&opportunity, &intents), U256::from(8000u64));
+    // A zero-amount intent adds nothing, and an unreadable one is passed over
+    let unreadable = IntentData { intent: "not json".to_string(), ..intent_from("bob.os", 2000, 10) };
+    let intents = [intent_in(USDC, 0, false), unreadable];
+    assert_eq!(intent_coverage(&opportunity, &intents), U256::ZERO);
+}
+
+fn decide(opportunity: &AaveLiquidationOpportunity, free: u64, operator: u64, manual: Option<u64>) -> CommitmentDecision {
+    decide_commitment(
+        &NodeConfig::default(),
+        opportunity,
+        OriginClass::Bot,
+        None,
+        U256::from(free),
+        U256::from(operator),
+        0,
+        Some(50),
+        None,
+        manual.map(U256::from),
+    )
+}
+
+fn failed_rules(decision: &CommitmentDecision) -> Vec<&str> {
+    decision.checks.iter().filter(|check| !check.passed).map(|check| check.rule.as_str()).collect()
+}
+
+fn profitable_opportunity() -> AaveLiquidationOpportunity {
+    // 100 USD against the default 10 USD bar
+    AaveLiquidationOpportunity { estimated_profit_usd: "100000000000000000000".to_string(), ..usdc_opportunity() }
+}
+
+#[test]
+fn commitment_covers_the_repay_and_flags_demo_capital() {
+    let decision = decide(&profitable_opportunity(), 50_000, 50_000, None);
+    assert!(decision.commit, "failed: {:?}", failed_rules(&decision));
+    assert_eq!(decision.amount, "10000");
+    assert!(!decision.simulated);
+    // More than the operator put in, so part of it is demo capital
+    assert!(decide(&profitable_opportunity(), 50_000, 2000, None).simulated);
+    // Not enough free to cover the full repay
+    assert_eq!(failed_rules(&decide(&profitable_opportunity(), 4000, 4000, None)), vec!["capital_available", "commitment_sizing"]);
+}
+
+#[test]
+fn every_rule_is_reported_even_when_an_early_one_fails() {
+    let opportunity = AaveLiquidationOpportunity { health_factor: U256::MAX.to_string(), ..profitable_opportunity() };
+    let decision = decide(&opportunity, 50_000, 50_000, None);
+    assert!(!decision.commit);
+    assert_eq!(decision.amount, "0");
+    assert_eq!(failed_rules(&decision), vec!["health_factor", "commitment_sizing"]);
+    let rules: Vec<&str> = decision.checks.iter().map(|check| check.rule.as_str()).collect();
+    assert!(rules.contains(&"capital_available") && rules.contains(&"profit_entry_bar"));
+}
+
+#[test]
+fn profit_must_clear_the_bar_plus_the_entry_margin() {
+    // 10.1 USD clears the 10 USD bar but not the 2% entry margin above it
+    let opportunity = AaveLiquidationOpportunity { estimated_profit_usd: "10100000000000000000".to_string(), ..usdc_opportunity() };
+    assert_eq!(failed_rules(&decide(&opportunity, 50_000, 50_000, None)), vec!["profit_entry_bar", "commitment_sizing"]);
+    let opportunity = AaveLiquidationOpportunity { estimated_profit_usd: "10200000000000000000".to_string(), ..usdc_opportunity() };
+    assert!(decide(&opportunity, 50_000, 50_000, None).commit);
+}
+
+#[test]
+fn manual_amount_replaces_sizing_but_stays_within_repay_and_balance() {
+    let decision = decide(&profitable_opportunity(), 50_000, 50_000, Some(1500));
+    assert!(decision.commit);
+    assert_eq!(decision.amount, "1500");
+    assert_eq!(failed_rules(&decide(&profitable_opportunity(), 50_000, 50_000, Some(20_000))), vec!["manual_amount", "commitment_sizing"]);
+    assert_eq!(failed_rules(&decide(&profitable_opportunity(), 1000, 1000, Some(1500))), vec!["capital_available", "commitment_sizing"]);
+}
+
+// Announcements between in-memory nodes, handled as receive_node_announcement does: the sender is
+// added, a non-reply is answered, and peers it lists that we didn't know are announced to
+fn run_mesh(connections: &[(&str, &str)]) -> (HashMap<String, HashMap<String, PeerInfo>>, usize) {
+    let config = NodeConfig::default();
+    let mut nodes: HashMap<String, HashMap<String, PeerInfo>> = HashMap::new();
+    let mut queue: VecDeque<(String, String, Vec<String>, bool)> = VecDeque::new();
+    let mut delivered = 0;
+    for (from, to) in connections {
+        nodes.entry(from.to_string()).or_default().insert(to.to_string(), PeerInfo::pending("0".to_string()));
+        let shared = peers_to_share(&nodes[*from], to);
+        queue.push_back((from.to_string(), to.to_string(), shared, false));
+        while let Some((from, to, listed, reply)) = queue.pop_front() {
+            delivered += 1;
+            assert!(delivered < 100, "announcements never settle");
+            let known = nodes.entry(to.clone()).or_default();
+            known.entry(from.clone()).or_insert_with(|| PeerInfo::pending("0".to_string()));
+            if !reply {
+                queue.push_back((to.clone(), from.clone(), peers_to_share(known, &from), true));
+            }
+            for peer in peers_to_introduce(listed, &to, known, &config) {
+                known.insert(peer.clone(), PeerInfo::pending("0".to_string()));
+                queue.push_back((to.clone(), peer.clone(), peers_to_share(known, &peer), false));
+            }
+        }
+    }
+    (nodes, delivered)
+}
+
+#[test]
+fn three_nodes_connected_in_a_line_form_a_mesh() {
+    for connections in [[("a.os", "b.os"), ("b.os", "c.os")], [("b.os", "c.os"), ("a.os", "b.os")]] {
+        let (nodes, _) = run_mesh(&connections);
+        assert!(nodes["a.os"].contains_key("c.os"), "{:?}", connections);
+        assert!(nodes["c.os"].contains_key("a.os"), "{:?}", connections);
+        for (node, known) in &nodes {
+            assert!(!known.contains_key(node));
+            assert_eq!(known.len(), 2);
+        }
+    }
+}
+
+#[test]
+fn introductions_skip_us_known_and_blocked_peers_and_stop_at_max_peers() {
+    let mut config = NodeConfig::default();
+    config.blacklisted_peers.insert("mallory.os".to_string());
+    let known: HashMap<String, PeerInfo> = [("bob.os".to_string(), PeerInfo::pending("0".to_string()))].into();
+    let offered = ["us.os", "bob.os", "mallory.os", "carol.os", "carol.os", "dave.os"].map(str::to_string).to_vec();
+    assert_eq!(peers_to_introduce(offered.clone(), "us.os", &known, &config), vec!["carol.os", "dave.os"]);
+    config.max_peers = 2;
+    assert_eq!(peers_to_introduce(offered, "us.os", &known, &config), vec!["carol.os"]);
+}
+
+// Runs a handler body to completion; every path the tests take finishes without waiting on the runtime
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    match std::future::Future::poll(future.as_mut(), &mut context) {
+        std::task::Poll::Ready(output) => output,
+        std::task::Poll::Pending => panic!("handler waited on the runtime"),
+    }
+}
+
+// A known peer that announced itself with `roles`
+fn announced_peer(node: &str, roles: Vec<NodeRole>) -> PeerInfo {
+    PeerInfo {
+        node_info: Some(NodeInfo {
+            node_id: node.to_string(),
+            app_version: "0".to_string(),
+            roles,
+            capital_assets: Vec::new(),
+        }),
+        ..PeerInfo::pending("0".to_string())
+    }
+}
+
+#[test]
+fn same_broadcast_three_times_is_handled_once() {
+    let mut app = app_with_opportunity(true, false, false);
+    // Evaluating as capital provider would reach the runtime; storing and dedup don't
+    app.node_config.capital_provider_enabled = false;
+    app.known_peers.insert("finder.os".to_string(), announced_peer("finder.os", vec![NodeRole::Finder]));
+    let broadcast = MevMessage::OpportunityBroadcast {
+        opp_id: "opp".to_string(),
+        strategy_id: "aave-liquidation".to_string(),
+        opportunity: serde_json::to_string(&usdc_opportunity()).unwrap(),
+        finder_fee_bps: 100,
+        deadline_block: 100,
+        executor_preference: vec!["executor.os".to_string()],
+        finder_seq: None,
+        backfill: false,
+        origin: OpportunityOrigin::default(),
+        hop_count: None,
+        finder_node: None,
+    };
+    let json = serde_json::to_string(&broadcast).unwrap();
+    for _ in 0..3 {
+        block_on(app.accept_opportunity_broadcast("finder.os".to_string(), &json, false)).unwrap();
+    }
+    assert_eq!(app.active_opportunities.len(), 1);
+    assert_eq!(app.submitted_intents["opp"].len(), 1);
+    assert_eq!(app.seen_broadcasts.duplicates_dropped, 2);
+    // A re-priced payload under the same opp_id is not a repeat
+    assert!(!app.seen_broadcasts.is_duplicate("opp", &intent_hash(r#"{"opp_id":"opp","estimated_profit_usd":"2"}"#)));
+}
+
+#[test]
+fn seen_broadcasts_expire_and_stay_bounded() {
+    let mut seen = SeenBroadcasts::default();
+    seen.record("old", "a".to_string(), 10);
+    seen.record("new", "b".to_string(), 100);
+    seen.prune(110, 50);
+    assert!(!seen.is_duplicate("old", "a"));
+    assert!(seen.is_duplicate("new", "b"));
+    
+    let mut seen = SeenBroadcasts::default();
+    for i in 0..MAX_SEEN_BROADCASTS as u64 {
+        seen.record(&format!("opp-{}", i), "d".to_string(), 1_000 + i);
+    }
+    seen.record("one-more", "d".to_string(), 1_000_000);
+    assert_eq!(seen.entries.len(), MAX_SEEN_BROADCASTS);
+    assert!(!seen.entries.contains_key("opp-0"));
+    assert!(seen.entries.contains_key("one-more"));
+}
+
+#[test]
+fn broadcasts_are_forwarded_until_the_hop_limit() {
+    // The default of 0 never forwards
+    assert_eq!(forward_hop(None, 0), None);
+    assert_eq!(forward_hop(None, 2), Some(1));
+    assert_eq!(forward_hop(Some(1), 2), Some(2));
+    assert_eq!(forward_hop(Some(2), 2), None);
+    assert_eq!(forward_hop(Some(u8::MAX), u8::MAX), None);
+}
+
+// An opportunity at the given stage, with everything that stage hangs on it
+fn app_with_opportunity(stored: bool, committed: bool, linked: bool) -> HyperMevApp {
+    let mut app = HyperMevApp::default();
+    let opportunity = serde_json::to_string(&usdc_opportunity()).unwrap();
+    if stored {
+        let data = OpportunityData::new(opportunity, "aave-liquidation".to_string(), "finder.os".to_string(), Vec::new());
+        app.active_opportunities.insert("opp".to_string(), data);
+        app.submitted_intents.insert("opp".to_string(), vec![intent_in(USDC, 3000, false)]);
+    }
+    if committed {
+        let outstanding = OutstandingIntent {
+            intent: intent_in(USDC, 3000, false).parsed_intent().unwrap(),
+            sent_to: vec!["executor.os".to_string()],
+            submitted_at: "10".to_string(),
+            amendment_rejected: None,
+            config_revision: 0,
+            revocation_rejected: None,
+        };
+        app.our_intents.insert("opp".to_string(), outstanding.clone());
+        app.revoked_intents.insert("opp".to_string(), outstanding);
+        app.capital_commitments.insert("opp".to_string(), CapitalCommitment {
+            asset: USDC.to_string(),
+            amount: "3000".to_string(),
+            committed_at: "10".to_string(),
+        });
+        app.committed_amounts.insert(USDC.to_string(), "3000".to_string());
+    }
+    if linked {
+        app.duplicate_index.insert("aave-liquidation:0xvictim".to_string(), "opp".to_string());
+        app.opp_aliases.insert("opp-copy".to_string(), "opp".to_string());
+    }
+    app
+}
+
+// Every map still referring to `opp_id`
+fn dangling(app: &HyperMevApp, opp_id: &str) -> Vec<&'static str> {
+    let mut found = Vec::new();
+    if app.active_opportunities.contains_key(opp_id) { found.push("active_opportunities"); }
+    if app.submitted_intents.contains_key(opp_id) { found.push("submitted_intents"); }
+    if app.our_intents.contains_key(opp_id) { found.push("our_intents"); }
+    if app.revoked_intents.contains_key(opp_id) { found.push("revoked_intents"); }
+    if app.capital_commitments.contains_key(opp_id) { found.push("capital_commitments"); }
+    if app.reservation_rounds.contains_key(opp_id) { found.push("reservation_rounds"); }
+    if app.capital_reservations.contains_key(opp_id) { found.push("capital_reservations"); }
+    if app.duplicate_index.values().any(|canonical| canonical == opp_id) { found.push("duplicate_index"); }
+    if app.opp_aliases.iter().any(|(alias, canonical)| alias == opp_id || canonical == opp_id) { found.push("opp_aliases"); }
+    if !app.committed_amounts.is_empty() { found.push("committed_amounts"); }
+    found
+}
+
+#[test]
+fn retiring_from_any_stage_leaves_no_dangling_references() {
+    let stages = [(true, false, false), (true, true, false), (true, true, true), (false, true, false)];
+    for (stored, committed, linked) in stages {
+        for outcome in [RetirementOutcome::Executed, RetirementOutcome::expired("Deadline", Some("peer.os".to_string()), false)] {
+            let expired = matches!(outcome, RetirementOutcome::Expired { .. });
+            let mut app = app_with_opportunity(stored, committed, linked);
+            let retired = app.retire_opportunity("opp", outcome);
+            assert_eq!(dangling(&app, "opp"), Vec::<&str>::new(), "stage {:?}", (stored, committed, linked));
+            assert_eq!(retired, stored || (expired && committed));
+            // Expiry is recorded for anything we stored or had capital on; execution leaves it to the receipt
+            assert_eq!(app.expired_opportunities.contains_key("opp"), expired && (stored || committed));
+        }
+    }
+}
+
+#[test]
+fn retiring_twice_changes_nothing_the_second_time() {
+    let mut app = app_with_opportunity(true, true, true);
+    assert!(app.retire_opportunity("opp", RetirementOutcome::expired("Deadline", Some("peer.os".to_string()), false)));
+    let journaled = app.journal.len();
+    assert!(!app.retire_opportunity("opp", RetirementOutcome::expired("Deadline", Some("peer.os".to_string()), false)));
+    assert_eq!(app.journal.len(), journaled);
+}
+
+#[test]
+fn receipts_count_only_from_the_executor() {
+    let app = app_with_opportunity(true, false, false);
+    let mut data = app.active_opportunities["opp"].clone();
+    // No preference list: any executor, as before
+    assert!(data.may_report_receipt("anyone.os"));
+    
+    data.executor_preference = vec!["first.os".to_string(), "second.os".to_string()];
+    assert!(data.may_report_receipt("first.os"));
+    assert!(!data.may_report_receipt("second.os"));
+    // Rights passed on after first.os claimed: the claimant's receipt counts, the new holder's not until it claims
+    data.claimed_by = Some("first.os".to_string());
+    data.execution_holder_index = 1;
+    assert!(data.may_report_receipt("first.os"));
+    assert!(!data.may_report_receipt("second.os"));
+    assert!(!data.may_report_receipt("mallory.os"));
+    data.claimed_by = Some("second.os".to_string());
+    assert!(data.may_report_receipt("second.os"));
+    assert!(!data.may_report_receipt("first.os"));
+}
+
+#[test]
+fn non_json_bodies_are_dead_lettered_and_counted() {
+    let mut app = HyperMevApp::default();
+    let bodies = ["\u{0}\u{1}\u{7f}binary\u{ff}", r#"{"type":"IntentRevocation","opp_id":"op"#, ""];
+    for body in bodies {
+        let error = app.screen_body("mallory.os".to_string(), "ReceiveIntentRevocation", body).unwrap_err();
+        let error: serde_json::Value = serde_json::from_str(&error).unwrap();
+        assert_eq!(error["error"], "MalformedBody");
+        assert_eq!(error["body_len"], body.len());
+    }
+    assert_eq!(app.dead_letters.len(), 3);
+    assert_eq!(app.malformed_message_strikes["mallory.os"], 3);
+    assert_eq!(app.dead_letters[0].sample, "\\x00\\x01\\x7fbinary\\xc3\\xbf");
+    assert_eq!(app.dead_letters[1].handler, "ReceiveIntentRevocation");
+    
+    // Well-formed JSON passes screening, whatever it turns out to hold
+    assert!(app.screen_body("alice.os".to_string(), "ReceiveIntentRevocation", r#"{"type":"Nonsense"}"#).is_ok());
+    assert!(!app.malformed_message_strikes.contains_key("alice.os"));
+}
+
+#[test]
+fn dead_letter_samples_are_short_and_unambiguous() {
+    assert_eq!(escaped_sample(r#"a\b"#), "a\\x5cb");
+    assert_eq!(escaped_sample(&"x".repeat(500)).len(), DEAD_LETTER_SAMPLE_BYTES);
+}
+
+#[test]
+fn each_handler_refuses_another_handlers_message() {
+    let messages = [
+        MevMessage::IntentRevocation { opp_id: "opp".to_string() },
+        MevMessage::IntentRevocationRejection { opp_id: "opp".to_string(), reason: "executing".to_string() },
+        MevMessage::SyncRequest { max_opportunities: 5 },
+        MevMessage::MirrorControl { action: MirrorAction::Offer, from_seq: None, to_seq: None },
+        MevMessage::OpportunityCancellation { cancellation: "{}".to_string(), signature: None },
+    ];
+    for sent in &messages {
+        let json = serde_json::to_string(sent).unwrap();
+        for expected in &messages {
+            let parsed = MevMessage::parse(&json, expected.kind());
+            if sent.kind() == expected.kind() {
+                assert!(parsed.is_ok());
+            } else {
+                assert!(parsed.unwrap_err().contains("MalformedMessage"), "{} to {}", sent.kind(), expected.kind());
+            }
+        }
+    }
+    // A known type missing its fields is malformed too, not silently defaulted
+    let error = MevMessage::parse(r#"{"type":"IntentRevocation"}"#, "IntentRevocation").unwrap_err();
+    assert!(error.contains("opp_id"));
+}
+
+#[test]
+fn finder_sequence_gaps_are_tracked_and_backfilled() {
+    let mut stats = FinderSequenceStats::default();
+    // The first number seen is the baseline, whatever came before it
+    assert_eq!(stats.observe(40), None);
+    assert_eq!(stats.observe(41), None);
+    assert_eq!(stats.observe(45), Some((42, 44)));
+    assert_eq!(stats.gaps_detected, 3);
+    // A repeat changes nothing; a late arrival fills its gap
+    assert_eq!(stats.observe(45), None);
+    assert_eq!(stats.observe(43), None);
+    assert_eq!(stats.missing, BTreeSet::from([42, 44]));
+    assert_eq!((stats.received, stats.backfilled), (4, 1));
+}
+
+#[test]
+fn finder_sequence_tracks_only_the_latest_gaps() {
+    let mut stats = FinderSequenceStats::default();
+    stats.observe(1);
+    assert_eq!(stats.observe(MAX_TRACKED_GAPS * 3), Some((MAX_TRACKED_GAPS * 2, MAX_TRACKED_GAPS * 3 - 1)));
+    assert_eq!(stats.missing.len() as u64, MAX_TRACKED_GAPS);
+    assert_eq!(stats.gaps_detected, MAX_TRACKED_GAPS * 3 - 2);
+}
+
+fn journaled(app: &HyperMevApp, kind: &str) -> Vec<serde_json::Value> {
+    app.journal.iter().filter(|entry| entry.kind == kind).map(|entry| entry.detail.clone()).collect()
+}
+
+#[test]
+fn config_update_with_one_bad_field_changes_nothing() {
+    let mut app = HyperMevApp::default();
+    let update = serde_json::json!({ "finder_fee_bps": 200, "capital_provider_fee_bps": 20_000, "trial_secs": 60 });
+    let error = block_on(app.update_node_config(update.to_string())).unwrap_err();
+    assert!(error.contains("capital_provider_fee_bps"), "{}", error);
+    // The valid field staged alongside it was not applied either, and no trial was opened
+    assert_eq!(app.node_config.finder_fee_bps, 100);
+    assert_eq!(app.node_config.capital_provider_fee_bps, 0);
+    assert!(app.config_trial.is_none());
+    assert_eq!(app.config_revision, 0);
+    assert!(app.config_history.is_empty());
+    assert!(journaled(&app, "ConfigChanged").is_empty());
+}
+
+#[test]
+fn trial_config_reverts_once_its_window_closes() {
+    let mut app = HyperMevApp::default();
+    let update = serde_json::json!({ "finder_fee_bps": 200, "trial_secs": 60 });
+    block_on(app.update_node_config(update.to_string())).unwrap();
+    assert_eq!(app.node_config.finder_fee_bps, 200);
+    let trial = app.config_trial.as_ref().unwrap();
+    assert_eq!(trial.previous.finder_fee_bps, 100);
+    assert_eq!(trial.revert_at, trial.started_at + 60);
+    
+    // Inside the window nothing happens
+    app.revert_expired_config_trial();
+    assert_eq!(app.node_config.finder_fee_bps, 200);
+    assert!(app.config_trial.is_some());
+    
+    // Once it has closed, the pre-trial config is back and recorded as a revision of its own
+    app.config_trial.as_mut().unwrap().revert_at = 0;
+    app.revert_expired_config_trial();
+    assert_eq!(app.node_config.finder_fee_bps, 100);
+    assert!(app.config_trial.is_none());
+    assert_eq!(app.config_revision, 2);
+    let reasons: Vec<&str> = app.config_history.values().map(|revision| revision.reason.as_str()).collect();
+    assert_eq!(reasons, vec!["Trial", "TrialReverted"]);
+    assert_eq!(app.config_history[&2].config.finder_fee_bps, 100);
+    let changes = journaled(&app, "ConfigChanged");
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1]["reason"], "TrialReverted");
+    assert_eq!(changes[1]["changed"], serde_json::json!(["finder_fee_bps"]));
+}
+
+#[test]
+fn committed_trial_config_stays() {
+    let mut app = HyperMevApp::default();
+    let update = serde_json::json!({ "finder_fee_bps": 200, "trial_secs": 60 });
+    block_on(app.update_node_config(update.to_string())).unwrap();
+    block_on(app.commit_config(String::new())).unwrap();
+    assert!(app.config_trial.is_none());
+    let committed = journaled(&app, "ConfigCommitted");
+    assert_eq!(committed.len(), 1);
+    assert_eq!(committed[0]["changed"], serde_json::json!(["finder_fee_bps"]));
+    
+    // Nothing is left to revert, however long it has been
+    app.revert_expired_config_trial();
+    assert_eq!(app.node_config.finder_fee_bps, 200);
+    assert_eq!(app.config_revision, 1);
+    assert_eq!(app.config_history[&1].reason, "Trial");
+    // And a second commit has no trial to keep
+    assert!(block_on(app.commit_config(String::new())).is_err());
+}
+
+fn connect_bridge(app: &mut HyperMevApp) {
+    let connection = ArtemisConnection { connected_at: 0, last_message_at: 0, bridge_id: Some("bridge-1".to_string()), bridge_version: None };
+    app.artemis_channels.open(1, connection);
+}
+
+// An executor holding "opp" and one intent for it, with a bridge connected
+fn executor_app() -> HyperMevApp {
+    let mut app = app_with_opportunity(true, false, false);
+    app.node_config.node_id = "executor.os".to_string();
+    app.node_config.min_profit_threshold_usd = "0".to_string();
+    connect_bridge(&mut app);
+    app
+}
+
+// Saved and loaded back the way the process persists its state across a restart
+fn restarted(app: &HyperMevApp) -> HyperMevApp {
+    serde_json::from_str(&serde_json::to_string(app).unwrap()).unwrap()
+}
+
+fn now() -> u64 {
+    HyperMevApp::current_timestamp().parse().unwrap()
+}
+
+#[test]
+fn restart_before_dispatch_dispatches_once_a_bridge_connects() {
+    let mut app = restarted(&executor_app());
+    assert_eq!(resume_execution(app.pending_executions.get("opp"), false, None, now()), ExecutionResume::Dispatch);
+    // Bridge connections don't survive the restart, so it holds until one is back
+    assert!(app.intent_collection_for("opp").is_none());
+    assert!(app.pending_executions.is_empty());
+    connect_bridge(&mut app);
+    assert!(app.intent_collection_for("opp").is_some());
+    assert!(app.pending_executions.contains_key("opp"));
+}
+
+#[test]
+fn restart_after_dispatch_asks_the_bridge_instead_of_dispatching_again() {
+    let mut app = executor_app();
+    let collection = app.intent_collection_for("opp").unwrap();
+    let mut app = restarted(&app);
+    let pending = &app.pending_executions["opp"];
+    assert_eq!(serde_json::to_value(&pending.allocation).unwrap(), collection["allocation"]);
+    assert_eq!(pending.bridge_id.as_deref(), Some("bridge-1"));
+    assert_eq!(app.active_opportunities["opp"].status, OpportunityStatus::Executing);
+    assert_eq!(resume_execution(Some(pending), false, None, now()), ExecutionResume::Query);
+    connect_bridge(&mut app);
+    assert!(app.intent_collection_for("opp").is_none());
+}
+
+#[test]
+fn restart_while_the_bridge_executes_keeps_waiting() {
+    let mut app = executor_app();
+    app.intent_collection_for("opp").unwrap();
+    let mut app = restarted(&app);
+    connect_bridge(&mut app);
+    let report = serde_json::json!({ "type": "ExecutionStatusReport", "opp_id": "opp", "status": "InProgress" });
+    app.handle_artemis_message_json(1, &report.to_string()).unwrap();
+    // The bridge's word outlives another restart, and the execution stays with it
+    let mut app = restarted(&app);
+    let pending = &app.pending_executions["opp"];
+    assert_eq!(pending.last_status.as_deref(), Some("InProgress"));
+    assert_eq!(resume_execution(Some(pending), false, Some("InProgress"), now()),
+        ExecutionResume::Await("InProgress".to_string()));
+    connect_bridge(&mut app);
+    assert!(app.intent_collection_for("opp").is_none());
+}
+
+#[test]
+fn restart_after_the_bridge_finished_recovers_the_receipt() {
+    let mut app = executor_app();
+    app.intent_collection_for("opp").unwrap();
+    let app = restarted(&app);
+    let pending = app.pending_executions.get("opp");
+    assert_eq!(resume_execution(pending, false, Some("Completed"), now()), ExecutionResume::RecoverReceipt);
+    // Even once we'd have given up on it
+    assert_eq!(resume_execution(pending, false, Some("Completed"), now() + 10_000), ExecutionResume::RecoverReceipt);
+}
+
+#[test]
+fn restart_after_a_lost_dispatch_sends_it_again() {
+    let mut app = executor_app();
+    app.intent_collection_for("opp").unwrap();
+    let mut app = restarted(&app);
+    assert_eq!(resume_execution(app.pending_executions.get("opp"), false, Some("Unknown"), now()), ExecutionResume::Redispatch);
+    // What the sync does with it: the execution is dropped and the opportunity dispatched afresh
+    app.pending_executions.remove("opp");
+    app.set_opportunity_status("opp", OpportunityStatus::CollectingIntents);
+    connect_bridge(&mut app);
+    assert!(app.intent_collection_for("opp").is_some());
+    assert!(app.pending_executions.contains_key("opp"));
+}
+
+#[test]
+fn restart_past_the_timeout_gives_the_execution_up() {
+    let mut app = executor_app();
+    app.intent_collection_for("opp").unwrap();
+    let app = restarted(&app);
+    let pending = &app.pending_executions["opp"];
+    let dispatched_at: u64 = pending.dispatched_at.parse().unwrap();
+    let late = dispatched_at + PENDING_EXECUTION_TIMEOUT_SECS + 1;
+    assert!(!pending.timed_out(late - 1));
+    assert!(pending.timed_out(late));
+    for status in [None, Some("InProgress"), Some("Unknown")] {
+        assert_eq!(resume_execution(Some(pending), false, status, late), ExecutionResume::GiveUp, "{:?}", status);
+    }
+}
+
+#[test]
+fn restart_after_the_receipt_has_nothing_in_flight() {
+    let mut app = executor_app();
+    app.intent_collection_for("opp").unwrap();
+    let app = restarted(&app);
+    let pending = app.pending_executions.get("opp");
+    for status in [None, Some("Completed"), Some("Unknown")] {
+        assert_eq!(resume_execution(pending, true, status, now()), ExecutionResume::Settled, "{:?}", status);
+    }
+    assert_eq!(resume_execution(None, true, None, now()), ExecutionResume::Settled);
+}
@@ -0,0 +1,60 @@
+// UI PUSH CHANNEL
+// Browser tabs open a WebSocket on /updates and get pool events as they happen instead of polling
+// get_opportunities and get_execution_receipts. The process has one WebSocket handler for every
+// path, so a tab names itself with a Subscribe frame first; from then on its channel is kept
+// apart from the bridge's and only ever pushed to. Channels live in memory only and are dropped
+// on Close.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Tabs served at once; a further Subscribe is refused
+pub const MAX_UI_CHANNELS: usize = 16;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum UiEventKind {
+    OpportunityAdded,
+    IntentReceived,
+    ReceiptSettled,
+    PeerConnected,
+    BalanceChanged,
+    ReceiptReorged,
+}
+
+// What each tab receives: `{"type", "payload", "timestamp"}`, timestamp in unix seconds
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct UiEvent {
+    #[serde(rename = "type")]
+    pub kind: UiEventKind,
+    pub payload: serde_json::Value,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct UiChannel {
+    pub opened_at: u64,
+    pub pushed: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UiChannels {
+    pub channels: BTreeMap<u32, UiChannel>,
+}
+
+impl UiChannels {
+    pub fn contains(&self, channel_id: u32) -> bool {
+        self.channels.contains_key(&channel_id)
+    }
+
+    pub fn open(&mut self, channel_id: u32, now: u64) -> Result<(), String> {
+        if !self.contains(channel_id) && self.channels.len() >= MAX_UI_CHANNELS {
+            return Err(format!("At most {} UI channels", MAX_UI_CHANNELS));
+        }
+        self.channels.entry(channel_id).or_insert(UiChannel { opened_at: now, pushed: 0 });
+        Ok(())
+    }
+
+    pub fn close(&mut self, channel_id: u32) -> bool {
+        self.channels.remove(&channel_id).is_some()
+    }
+}
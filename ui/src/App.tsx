@@ -20,6 +20,7 @@ function App() {
     addCapital,
     connectToPeer,
     toggleRole,
+    subscribeToUpdates,
   } = useMevStore();
 
   // Local UI state
@@ -50,6 +51,12 @@ function App() {
     return () => clearInterval(interval);
   }, [isConnected, fetchNodeStatus]);
 
+  // Live feed: refresh what changed as the node reports it
+  useEffect(() => {
+    if (!isConnected) return;
+    return subscribeToUpdates();
+  }, [isConnected, subscribeToUpdates]);
+
   // Update config form when nodeStatus changes
   useEffect(() => {
     if (nodeStatus) {
@@ -314,7 +321,7 @@ function App() {
                       <span className="strategy">{opp.strategy_id}</span>
                       <span className="finder">Found by: {opp.finder_node}</span>
                     </div>
-                    <div className="opp-time">{new Date(opp.received_at).toLocaleString()}</div>
+                    <div className="opp-time">{new Date(opp.received_at * 1000).toLocaleString()}</div>
                   </div>
                 ))
               ) : (
@@ -336,7 +343,7 @@ function App() {
                       <span className="executor">Executed by: {receipt.executor_node}</span>
                       <span className="proceeds">Our proceeds: {formatAmount(receipt.our_proceeds)}</span>
                     </div>
-                    <div className="receipt-time">{new Date(receipt.verified_at).toLocaleString()}</div>
+                    <div className="receipt-time">{new Date(receipt.verified_at * 1000).toLocaleString()}</div>
                   </div>
                 ))
               ) : (
@@ -27,16 +27,26 @@ interface Opportunity {
   opp_id: string;
   strategy_id: string;
   finder_node: string;
-  received_at: string;
+  // Unix seconds
+  received_at: number;
   opportunity: any;
 }
 
+// Pushed on the /updates WebSocket once the tab has sent {"type": "Subscribe"}
+interface UiEvent {
+  type: 'OpportunityAdded' | 'IntentReceived' | 'ReceiptSettled' | 'PeerConnected' | 'BalanceChanged';
+  payload: any;
+  timestamp: number;
+}
+
 interface ExecutionReceipt {
   opp_id: string;
   executor_node: string;
   our_proceeds: string;
-  verified_at: string;
-  receipt: any;
+  // Unix seconds
+  verified_at: number;
+  summary: any;
+  receipt?: any;
 }
 
 // API helper function
@@ -87,6 +97,8 @@ export interface MevState {
   addCapital: (asset: string, amount: string) => Promise<void>;
   connectToPeer: (peerNode: string) => Promise<void>;
   toggleRole: (role: 'finder_enabled' | 'capital_provider_enabled' | 'executor_enabled') => Promise<void>;
+  // Opens the live feed, reconnecting until the returned function is called
+  subscribeToUpdates: () => () => void;
 }
 
 export const useMevStore = create<MevState>((set, get) => ({
@@ -153,9 +165,9 @@ export const useMevStore = create<MevState>((set, get) => ({
 
   fetchOpportunities: async () => {
     try {
-      const response = await callMevApi('GetOpportunities');
-      const opportunities: Opportunity[] = JSON.parse(response);
-      set({ opportunities });
+      const response = await callMevApi('GetOpportunities', { limit: 50 });
+      const page: { items: Opportunity[]; total: number; has_more: boolean } = JSON.parse(response);
+      set({ opportunities: page.items });
     } catch (error) {
       console.error('Failed to fetch opportunities:', error);
     }
@@ -163,9 +175,9 @@ export const useMevStore = create<MevState>((set, get) => ({
 
   fetchExecutionReceipts: async () => {
     try {
-      const response = await callMevApi('GetExecutionReceipts');
-      const receipts: ExecutionReceipt[] = JSON.parse(response);
-      set({ executionReceipts: receipts });
+      const response = await callMevApi('GetExecutionReceipts', { limit: 20, detail: 'summary' });
+      const page: { items: ExecutionReceipt[]; total: number; has_more: boolean; next_cursor: string | null } = JSON.parse(response);
+      set({ executionReceipts: page.items });
     } catch (error) {
       console.error('Failed to fetch execution receipts:', error);
     }
@@ -227,6 +239,51 @@ export const useMevStore = create<MevState>((set, get) => ({
     } catch (error) {
       console.error(`Failed to toggle ${role}:`, error);
     }
+  },
+
+  subscribeToUpdates: () => {
+    let socket: WebSocket | null = null;
+    let retry: ReturnType<typeof setTimeout> | null = null;
+    let closed = false;
+
+    const connect = () => {
+      const scheme = window.location.protocol === 'https:' ? 'wss' : 'ws';
+      socket = new WebSocket(`${scheme}://${window.location.host}${import.meta.env.BASE_URL}updates`);
+      socket.onopen = () => socket?.send(JSON.stringify({ type: 'Subscribe' }));
+      socket.onmessage = (message) => {
+        let event: UiEvent;
+        try {
+          event = JSON.parse(message.data);
+        } catch {
+          return;
+        }
+        switch (event.type) {
+          case 'OpportunityAdded':
+          case 'IntentReceived':
+            get().fetchOpportunities();
+            break;
+          case 'ReceiptSettled':
+            get().fetchExecutionReceipts();
+            break;
+          case 'PeerConnected':
+          case 'BalanceChanged':
+            get().fetchNodeStatus();
+            break;
+        }
+      };
+      socket.onclose = () => {
+        if (!closed) {
+          retry = setTimeout(connect, 5000);
+        }
+      };
+    };
+
+    connect();
+    return () => {
+      closed = true;
+      if (retry) clearTimeout(retry);
+      socket?.close();
+    };
   }
 }));
 